@@ -0,0 +1,154 @@
+use crate::{Direction, Game, Turn};
+use async_graphql::SimpleObject;
+use serde::{Deserialize, Serialize};
+
+/// Number of moves the solver looks ahead from each position.
+pub const ANALYSIS_DEPTH: u32 = 2;
+
+/// Number of blunders kept in an analysis.
+const MAX_BLUNDERS: usize = 3;
+
+/// Weight of an empty cell in the solver's evaluation, in points of board score.
+const EMPTY_CELL_WEIGHT: u64 = 16;
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
+
+/// How a finished game compares to the moves preferred by a bounded-depth solver.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct GameAnalysis {
+    /// Percentage of moves as good as the solver's preferred move.
+    pub accuracy: u8,
+    /// Number of moves analyzed.
+    pub move_count: u32,
+    /// The moves that lost the most against the solver's preferred move, worst first.
+    pub blunders: Vec<Blunder>,
+}
+
+/// A move that evaluated worse than the solver's preferred move.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct Blunder {
+    /// Index of the move in the game, `1` being the first move.
+    pub move_index: u32,
+    /// The board the move was played on.
+    pub board: u64,
+    pub played: Direction,
+    pub best: Direction,
+    /// How much worse the played move evaluated than the best one.
+    pub loss: u64,
+}
+
+impl GameAnalysis {
+    /// Analyzes the `turns` of a game, as recorded in its move log.
+    ///
+    /// The solver runs an expectimax search of the next [`ANALYSIS_DEPTH`] moves, assuming
+    /// every empty cell is as likely to get the next `2`, and evaluates the boards it reaches
+    /// by score and free space.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::{Direction, GameAnalysis, Turn};
+    ///
+    /// let turn = |direction, board| Turn {
+    ///     direction,
+    ///     spawned_tile: 0,
+    ///     spawn_index: None,
+    ///     board,
+    ///     score: 0,
+    ///     is_ended: false,
+    /// };
+    /// // Sliding up instead of merging the bottom row `4 4 2 2` to the left.
+    /// let turns = [
+    ///     turn(None, 0x0000_0000_1000_2211),
+    ///     turn(Some(Direction::Up), 0x1211_2000_0000_0001),
+    /// ];
+    /// let analysis = GameAnalysis::analyze(&turns);
+    ///
+    /// assert_eq!(analysis.accuracy, 0);
+    /// assert_eq!(analysis.blunders[0].played, Direction::Up);
+    /// assert_eq!(analysis.blunders[0].best, Direction::Left);
+    /// ```
+    pub fn analyze(turns: &[Turn]) -> GameAnalysis {
+        let mut matching = 0;
+        let mut blunders = Vec::new();
+        let positions = turns.windows(2).zip(1..);
+        for (pair, move_index) in positions {
+            let (board, Some(played)) = (pair[0].board, &pair[1].direction) else {
+                continue;
+            };
+            let Some((best, best_value)) = Self::best_move(board, ANALYSIS_DEPTH) else {
+                continue;
+            };
+            let played_value =
+                Self::search(Game::move_board(board, played.clone()), ANALYSIS_DEPTH - 1);
+            let loss = best_value.saturating_sub(played_value);
+            if loss == 0 {
+                matching += 1;
+            } else {
+                blunders.push(Blunder {
+                    move_index,
+                    board,
+                    played: played.clone(),
+                    best,
+                    loss,
+                });
+            }
+        }
+
+        let move_count = matching + blunders.len() as u32;
+        let accuracy = match move_count {
+            0 => 100,
+            _ => (matching * 100 / move_count) as u8,
+        };
+        // Stable, so equal losses keep the earliest blunders.
+        blunders.sort_by(|a, b| b.loss.cmp(&a.loss));
+        blunders.truncate(MAX_BLUNDERS);
+        GameAnalysis {
+            accuracy,
+            move_count,
+            blunders,
+        }
+    }
+
+    /// The preferred move on `board` and its value, or `None` if no move changes the board.
+    fn best_move(board: u64, depth: u32) -> Option<(Direction, u64)> {
+        let mut best: Option<(Direction, u64)> = None;
+        for direction in DIRECTIONS {
+            let moved = Game::move_board(board, direction.clone());
+            if moved == board {
+                continue;
+            }
+            let value = Self::search(moved, depth - 1);
+            if best
+                .as_ref()
+                .map_or(true, |(_, best_value)| value > *best_value)
+            {
+                best = Some((direction, value));
+            }
+        }
+        best
+    }
+
+    /// The expected value of `board`, right after a slide, when a `2` spawns in any of its
+    /// empty cells and the best of the next `depth` moves is played.
+    fn search(board: u64, depth: u32) -> u64 {
+        let empty = u64::from(Game::count_empty(board));
+        if depth == 0 || empty == 0 {
+            return Game::score(board) + EMPTY_CELL_WEIGHT * empty;
+        }
+        let mut total = 0;
+        for cell in (0..16).filter(|cell| (board >> (cell * 4)) & 0xF == 0) {
+            let spawned = board | (1 << (cell * 4));
+            total += match Self::best_move(spawned, depth) {
+                Some((_, value)) => value,
+                None => Game::score(spawned),
+            };
+        }
+        total / empty
+    }
+}
@@ -0,0 +1,51 @@
+use async_graphql::{InputObject, SimpleObject};
+use linera_sdk::base::{ApplicationId, BcsSignable, Owner, PublicKey, Signature, Timestamp};
+use serde::{Deserialize, Serialize};
+
+/// Length of an attestation epoch: attestations are only accepted during the epoch they name,
+/// so third-party apps can tell how recent a score is.
+pub const ATTESTATION_EPOCH_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// A statement that `owner` reached `best_score` in the 2048 application `application_id`,
+/// as of epoch `epoch`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject, InputObject)]
+#[graphql(input_name = "ScoreAttestationInput")]
+pub struct ScoreAttestation {
+    pub owner: Owner,
+    pub best_score: u64,
+    pub application_id: ApplicationId,
+    pub epoch: u32,
+}
+
+impl BcsSignable for ScoreAttestation {}
+
+/// A score attestation with the signature of the application's attester.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct SignedAttestation {
+    pub attestation: ScoreAttestation,
+    pub signature: Signature,
+}
+
+/// Returns the attestation epoch `time` falls in.
+///
+/// ```
+/// use game2048::{attestation_epoch, ATTESTATION_EPOCH_SECS};
+/// use linera_sdk::base::Timestamp;
+///
+/// let epoch_micros = ATTESTATION_EPOCH_SECS * 1_000_000;
+/// assert_eq!(attestation_epoch(Timestamp::from(epoch_micros - 1)), 0);
+/// assert_eq!(attestation_epoch(Timestamp::from(epoch_micros)), 1);
+/// ```
+pub fn attestation_epoch(time: Timestamp) -> u32 {
+    (time.micros() / (ATTESTATION_EPOCH_SECS * 1_000_000)) as u32
+}
+
+/// Checks that `attestation` was signed by `attester`, the key the 2048 application's admins
+/// registered. Other applications call this before trusting a score, after checking the
+/// attestation's application id and epoch themselves.
+pub fn verify_attestation(attestation: &SignedAttestation, attester: PublicKey) -> bool {
+    attestation
+        .signature
+        .check(&attestation.attestation, attester)
+        .is_ok()
+}
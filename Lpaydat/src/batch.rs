@@ -0,0 +1,60 @@
+use std::fmt;
+
+use crate::Operation;
+use async_graphql::scalar;
+
+/// The most operations a batch may hold.
+pub const MAX_BATCH_LEN: usize = 16;
+
+// Batches are given to the `batch` mutation as JSON operations.
+scalar!(Operation);
+
+/// Why a batch of operations is rejected before any of them executes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BatchError {
+    Empty,
+    TooLong(usize),
+    Nested,
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::Empty => write!(formatter, "Batches need at least one operation"),
+            BatchError::TooLong(len) => write!(
+                formatter,
+                "Batches hold at most {MAX_BATCH_LEN} operations, not {len}"
+            ),
+            BatchError::Nested => write!(formatter, "Batches cannot contain batches"),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+/// Checks that `operations` can execute as one batch: there is at least one and at most
+/// [`MAX_BATCH_LEN`] of them, and none is itself a batch.
+///
+/// ```
+/// use game2048::{validate_batch, BatchError, Operation};
+///
+/// assert_eq!(validate_batch(&[Operation::JoinMatchQueue]), Ok(()));
+/// assert_eq!(validate_batch(&[]), Err(BatchError::Empty));
+/// let nested = Operation::Batch(vec![Operation::JoinMatchQueue]);
+/// assert_eq!(validate_batch(&[nested]), Err(BatchError::Nested));
+/// ```
+pub fn validate_batch(operations: &[Operation]) -> Result<(), BatchError> {
+    if operations.is_empty() {
+        return Err(BatchError::Empty);
+    }
+    if operations.len() > MAX_BATCH_LEN {
+        return Err(BatchError::TooLong(operations.len()));
+    }
+    if operations
+        .iter()
+        .any(|operation| matches!(operation, Operation::Batch(_)))
+    {
+        return Err(BatchError::Nested);
+    }
+    Ok(())
+}
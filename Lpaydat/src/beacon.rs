@@ -0,0 +1,139 @@
+use crate::CommunityId;
+use async_graphql::scalar;
+use linera_sdk::base::{BcsHashable, CryptoHash, Owner, Timestamp};
+use serde::{Deserialize, Serialize};
+
+/// Largest entropy, in bytes, a participant may reveal.
+pub const MAX_ENTROPY_LEN: usize = 64;
+
+/// How long, in microseconds, a game request may still be started once the reveal deadline
+/// of its beacon round has passed.
+pub const GAME_REQUEST_TIMEOUT_MICROS: u64 = 10 * 60 * 1_000_000;
+
+/// The phase of a beacon round at a given time.
+///
+/// Participants commit to secret entropy until the commit deadline, then reveal it until the
+/// reveal deadline. Once the reveal deadline has passed the round is timed out: reveals are
+/// no longer accepted and the seed is derived from whatever was revealed in time.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum BeaconPhase {
+    Commit,
+    Reveal,
+    TimedOut,
+}
+
+scalar!(BeaconPhase);
+
+/// The deadlines of a beacon round.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BeaconSchedule {
+    pub commit_deadline: Timestamp,
+    pub reveal_deadline: Timestamp,
+}
+
+impl BeaconSchedule {
+    /// Returns the phase of the round at `now`. Deadlines are exclusive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::{BeaconPhase, BeaconSchedule};
+    ///
+    /// let schedule = BeaconSchedule {
+    ///     commit_deadline: 100.into(),
+    ///     reveal_deadline: 200.into(),
+    /// };
+    ///
+    /// assert_eq!(schedule.phase(99.into()), BeaconPhase::Commit);
+    /// assert_eq!(schedule.phase(100.into()), BeaconPhase::Reveal);
+    /// assert_eq!(schedule.phase(200.into()), BeaconPhase::TimedOut);
+    /// ```
+    pub fn phase(&self, now: Timestamp) -> BeaconPhase {
+        if now < self.commit_deadline {
+            BeaconPhase::Commit
+        } else if now < self.reveal_deadline {
+            BeaconPhase::Reveal
+        } else {
+            BeaconPhase::TimedOut
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct Commitment {
+    owner: Owner,
+    entropy: Vec<u8>,
+}
+
+impl BcsHashable for Commitment {}
+
+#[derive(Deserialize, Serialize)]
+struct BeaconInput {
+    round_id: u32,
+    reveals: Vec<(Owner, Vec<u8>)>,
+}
+
+impl BcsHashable for BeaconInput {}
+
+#[derive(Deserialize, Serialize)]
+struct GameRequestInput {
+    output: CryptoHash,
+    request_id: u32,
+}
+
+impl BcsHashable for GameRequestInput {}
+
+/// A game requested before its seed is known, waiting for its beacon round to close.
+///
+/// Requests are only accepted while the round takes commitments, so nobody knows the seed
+/// the game will get when asking for it: players cannot create many games and only keep
+/// the favorable ones.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GameRequest {
+    pub owner: Owner,
+    pub namespace: Option<CommunityId>,
+    pub sandbox: bool,
+    /// The beacon round whose output fixes the seed.
+    pub round_id: u32,
+    /// When the request expires if the game was not started.
+    pub expires_at: Timestamp,
+}
+
+/// Returns the commitment `owner` publishes for its secret `entropy`.
+///
+/// The owner is part of the commitment so nobody can replay another participant's
+/// commitment and copy its reveal.
+pub fn commitment(owner: Owner, entropy: &[u8]) -> CryptoHash {
+    CryptoHash::new(&Commitment {
+        owner,
+        entropy: entropy.to_vec(),
+    })
+}
+
+/// Combines the entropy revealed in a round into its output.
+///
+/// `reveals` must be ordered by owner, so every validator derives the same output. A single
+/// honest participant revealing unpredictable entropy is enough to make the output
+/// unpredictable.
+pub fn beacon_output(round_id: u32, reveals: Vec<(Owner, Vec<u8>)>) -> CryptoHash {
+    CryptoHash::new(&BeaconInput { round_id, reveals })
+}
+
+/// Derives a game seed from the output of a beacon round.
+///
+/// Seeds are never `0`, which asks the contract to draw a seed from the block context.
+pub fn beacon_seed(output: &CryptoHash) -> u16 {
+    let bytes = output.as_bytes();
+    let value = u16::from_le_bytes([bytes[0], bytes[1]]);
+    value % u16::MAX + 1
+}
+
+/// Derives the seed of a requested game from the output of its beacon round, so requests
+/// waiting for the same round get different games.
+pub fn game_request_seed(output: &CryptoHash, request_id: u32) -> u16 {
+    let input = GameRequestInput {
+        output: *output,
+        request_id,
+    };
+    beacon_seed(&CryptoHash::new(&input))
+}
@@ -0,0 +1,95 @@
+use async_graphql::{scalar, SimpleObject};
+use linera_sdk::base::{ChainId, Owner};
+use serde::{Deserialize, Serialize};
+
+/// Number of moves each player gets in a head-to-head challenge. Games end once they reach
+/// it, if they did not end before.
+pub const CHALLENGE_MOVE_LIMIT: u32 = 200;
+
+/// The progress of a head-to-head challenge.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ChallengeStatus {
+    /// Waiting for a player of the opponent chain to accept it.
+    Pending,
+    /// Both games were started, at least one of them is still being played.
+    Active,
+    /// Both games ended and the challenger's chain settled the winner.
+    Finished,
+}
+
+scalar!(ChallengeStatus);
+
+/// A race between the players of two chains to the highest score, in games started from the
+/// same seed. Both chains keep a copy, the challenger's chain settling the result.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct ChallengeRecord {
+    /// Identifies the challenge together with `challenger_chain`.
+    pub challenge_id: u32,
+    pub challenger_chain: ChainId,
+    pub opponent_chain: ChainId,
+    pub challenger: Owner,
+    /// The player who accepted the challenge, `None` while it is pending.
+    pub opponent: Option<Owner>,
+    pub seed: u16,
+    pub move_limit: u32,
+    pub status: ChallengeStatus,
+    /// The game played for the challenge on this chain, `None` until it started.
+    pub game_id: Option<u16>,
+    /// The final scores, each `None` until the game ended.
+    pub challenger_score: Option<u64>,
+    pub opponent_score: Option<u64>,
+    /// The player with the highest score once finished, `None` for a draw.
+    pub winner: Option<Owner>,
+}
+
+impl ChallengeRecord {
+    /// Finishes an active challenge once both final scores are known, returning whether it
+    /// did.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::{ChallengeRecord, ChallengeStatus, CHALLENGE_MOVE_LIMIT};
+    /// use linera_sdk::base::{ChainId, CryptoHash, Owner};
+    ///
+    /// let challenger = Owner(CryptoHash::test_hash("challenger"));
+    /// let opponent = Owner(CryptoHash::test_hash("opponent"));
+    /// let mut challenge = ChallengeRecord {
+    ///     challenge_id: 0,
+    ///     challenger_chain: ChainId::root(0),
+    ///     opponent_chain: ChainId::root(1),
+    ///     challenger,
+    ///     opponent: Some(opponent),
+    ///     seed: 7,
+    ///     move_limit: CHALLENGE_MOVE_LIMIT,
+    ///     status: ChallengeStatus::Active,
+    ///     game_id: Some(7),
+    ///     challenger_score: Some(120),
+    ///     opponent_score: None,
+    ///     winner: None,
+    /// };
+    /// assert!(!challenge.settle());
+    ///
+    /// challenge.opponent_score = Some(348);
+    /// assert!(challenge.settle());
+    /// assert_eq!(challenge.status, ChallengeStatus::Finished);
+    /// assert_eq!(challenge.winner, Some(opponent));
+    /// ```
+    pub fn settle(&mut self) -> bool {
+        let (Some(challenger_score), Some(opponent_score)) =
+            (self.challenger_score, self.opponent_score)
+        else {
+            return false;
+        };
+        if self.status != ChallengeStatus::Active {
+            return false;
+        }
+        self.status = ChallengeStatus::Finished;
+        self.winner = match challenger_score.cmp(&opponent_score) {
+            std::cmp::Ordering::Greater => Some(self.challenger),
+            std::cmp::Ordering::Less => self.opponent,
+            std::cmp::Ordering::Equal => None,
+        };
+        true
+    }
+}
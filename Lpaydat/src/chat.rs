@@ -0,0 +1,52 @@
+use async_graphql::{scalar, SimpleObject};
+use linera_sdk::base::{Owner, Timestamp};
+use serde::{Deserialize, Serialize};
+
+/// The largest chat payload, in bytes.
+pub const MAX_CHAT_PAYLOAD_LEN: usize = 512;
+/// The largest chat key a player may publish, in bytes.
+pub const MAX_CHAT_KEY_LEN: usize = 64;
+/// The most messages kept in the chat of a match. Later messages are dropped.
+pub const MAX_CHAT_MESSAGES: usize = 100;
+
+/// What a player says in the chat of a match.
+///
+/// Encrypted payloads are sealed by the sender's client for the other players of the match,
+/// with the keys they published with `SetChatKey`. The application never sees the plaintext:
+/// it stores and serves the ciphertext as opaque bytes, which only the players can read.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ChatPayload {
+    Text(String),
+    Encrypted(Vec<u8>),
+}
+
+scalar!(ChatPayload);
+
+impl ChatPayload {
+    /// Returns the size of the payload in bytes.
+    ///
+    /// ```
+    /// use game2048::ChatPayload;
+    ///
+    /// assert_eq!(ChatPayload::Text("gg".to_string()).len(), 2);
+    /// assert!(ChatPayload::Encrypted(Vec::new()).is_empty());
+    /// ```
+    pub fn len(&self) -> usize {
+        match self {
+            ChatPayload::Text(text) => text.len(),
+            ChatPayload::Encrypted(ciphertext) => ciphertext.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A message in the chat of a match, kept on the matchmaking chain.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct ChatMessage {
+    pub sender: Owner,
+    pub payload: ChatPayload,
+    pub sent_at: Timestamp,
+}
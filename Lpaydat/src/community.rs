@@ -0,0 +1,15 @@
+use async_graphql::{InputObject, SimpleObject};
+use linera_sdk::base::{Amount, Owner};
+use serde::{Deserialize, Serialize};
+
+/// Fees a community charges for the games played in its namespace.
+#[derive(
+    Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SimpleObject, InputObject,
+)]
+#[graphql(input_name = "FeeSettingsInput")]
+pub struct FeeSettings {
+    /// Fee charged for every new game.
+    pub game_fee: Amount,
+    /// Account receiving the fees, or the community admins when unset.
+    pub recipient: Option<Owner>,
+}
@@ -3,20 +3,44 @@
 // mod game;
 mod state;
 
-use std::str::FromStr;
-
 use linera_sdk::{
-    base::{ChainId, WithContractAbi},
-    views::{RootView, View},
+    base::{
+        Account, Amount, ApplicationId, ChainId, CryptoHash, Owner, Signature, Timestamp,
+        WithContractAbi,
+    },
+    bcs,
+    views::{RootView, View, ViewStorageContext},
     Contract, ContractRuntime,
 };
 
-use self::state::Game2048;
-use game2048::{gen_range, Game, Message, Operation};
+use linera_token_creation::{Operation as TokenOperation, TokenAbi, TokenResponse};
+
+use self::state::{
+    BeaconRoundState, CommunityState, Game2048, GameState, GameStatus, MetricsState, PlayerState,
+    TemplateState, TournamentState,
+};
+use game2048::{
+    attestation_epoch, beacon_output, commitment, daily_seed, date_of, day_of, duration_seconds,
+    fault_for, game_request_seed, join_code, replay_board, replay_outcome, validate_batch,
+    verify_attestation, AccountMerge, Achievement, BeaconPhase, BeaconSchedule, BoardRepr,
+    ChallengeRecord, ChallengeStatus, ChatMessage, ChatPayload, CommunityId, DailyScore, Direction,
+    DisputeChallenge, DisputeChallengeStatus, DisputeSubmission, EfficiencyEntry, FaultKind,
+    FeeSettings, Game, Game2048Parameters, GameAnalysis, GameEvent, GameMode, GameRequest,
+    GameTemplate, GameVerification, HistoryRetention, JoinCode, LargeBoard, LeaderboardEntry,
+    LeaderboardWindow, MatchDispute, MatchNotice, MatchRecord, Message, MoveHistory, Operation,
+    OperationFailure, PassSale, Position, QueueEntry, RemoteScore, Replay, Rng, ScoreAttestation,
+    SeasonPass, SignedAttestation, SpectatedGame, TournamentResult, TournamentStatus, Turn,
+    CHALLENGE_MOVE_LIMIT, DEFAULT_ACTIVE_GAME_LIMIT, DEFAULT_LOBBY, DISPUTE_PERIOD_MICROS,
+    GAME_REQUEST_TIMEOUT_MICROS, MAX_BOARD_SIZE, MAX_CHAT_KEY_LEN, MAX_CHAT_MESSAGES,
+    MAX_CHAT_PAYLOAD_LEN, MAX_ENTROPY_LEN, MOVE_TABLE_SAMPLES, SUPPORTED_BOARD_SIZE, TUTORIALS,
+    UNDO_LIMIT,
+};
 
 pub struct Game2048Contract {
     state: Game2048,
     runtime: ContractRuntime<Self>,
+    /// Whether the operations executing belong to a batch, which must not drop any.
+    in_batch: bool,
 }
 
 linera_sdk::contract!(Game2048Contract);
@@ -27,105 +51,3502 @@ impl WithContractAbi for Game2048Contract {
 
 impl Contract for Game2048Contract {
     type Message = Message;
-    type Parameters = ();
+    type Parameters = Game2048Parameters;
     type InstantiationArgument = u16;
 
-    async fn load(runtime: ContractRuntime<Self>) -> Self {
+    async fn load(mut runtime: ContractRuntime<Self>) -> Self {
+        if cfg!(debug_assertions) {
+            // Sample different rows on every load, so that repeated loads cover the tables.
+            let mut rng = Rng::new(runtime.system_time().micros());
+            Game::check_move_tables(&mut rng, MOVE_TABLE_SAMPLES)
+                .unwrap_or_else(|mismatch| panic!("{mismatch}"));
+        }
         let state = Game2048::load(runtime.root_view_storage_context())
             .await
             .expect("Failed to load state");
-        Game2048Contract { state, runtime }
+        Game2048Contract {
+            state,
+            runtime,
+            in_batch: false,
+        }
     }
 
     async fn instantiate(&mut self, seed: Self::InstantiationArgument) {
-        self.runtime.application_parameters();
+        let parameters = self.runtime.application_parameters();
+        assert!(
+            parameters.bond_token.is_some()
+                || parameters.dispute_bond == Amount::ZERO
+                    && parameters.dispute_challenge_bond == Amount::ZERO,
+            "Dispute bonds need a bond token"
+        );
+        if let Some(creator) = self.runtime.authenticated_signer() {
+            self.state.admins.set(vec![creator]);
+        }
 
         // Initialize a default game entry if it doesn't exist
         let game_id = seed; // Example game ID
-        if self
-            .state
-            .games
-            .load_entry_or_insert(&game_id)
-            .await
-            .is_err()
-        {
-            let game = self.state.games.load_entry_mut(&game_id).await.unwrap();
-            game.game_id.set(game_id);
-            game.board.set(0); // Set a default board value, e.g., an empty board
+        let game = self.state.games.load_entry_mut(&game_id).await.unwrap();
+        game.game_id.set(game_id);
+        game.board.set(0); // Set a default board value, e.g., an empty board
+    }
+
+    async fn execute_operation(&mut self, envelope: Self::Operation) -> Self::Response {
+        let operation = envelope.decode().unwrap_or_else(|error| panic!("{error}"));
+        if let Operation::Batch(operations) = operation {
+            validate_batch(&operations).unwrap_or_else(|error| panic!("{error}"));
+            self.count_operation("Batch").await;
+            // A failure of any operation aborts the block, reverting those executed before.
+            self.in_batch = true;
+            for operation in operations {
+                self.execute(operation).await;
+            }
+            self.in_batch = false;
+        } else {
+            self.execute(operation).await;
+        }
+    }
+
+    async fn execute_message(&mut self, message: Self::Message) {
+        let origin = self
+            .runtime
+            .message_id()
+            .expect("Incoming message ID has to be available")
+            .chain_id;
+        match message {
+            Message::Game {
+                game_id,
+                move_index,
+                board,
+                score,
+                is_ended,
+                ..
+            } => {
+                self.record_turn(origin, game_id, move_index).await;
+                let key = (origin, game_id);
+                let spectated = self.state.spectated.get(&key).await.unwrap();
+                let is_hub =
+                    self.runtime.application_parameters().hub == Some(self.runtime.chain_id());
+                // The hub follows every game from its creation.
+                let mut game = match spectated {
+                    Some(game) => game,
+                    None if is_hub && move_index == 0 => SpectatedGame {
+                        chain_id: origin,
+                        game_id,
+                        move_index,
+                        board,
+                        score,
+                        is_ended,
+                    },
+                    None => return,
+                };
+                if !game.apply(move_index, board, score, is_ended) {
+                    return;
+                }
+                self.state.spectated.insert(&key, game).unwrap();
+                if is_hub {
+                    self.record_remote_score(origin, game_id, score);
+                }
+            }
+            Message::ResendRequest { game_id, from, to } => {
+                self.resend_turns(origin, game_id, from, to).await
+            }
+            Message::JoinMatchQueue { rating } => {
+                let owner = self.message_signer();
+                self.enqueue(owner, origin, DEFAULT_LOBBY.to_string(), rating)
+                    .await
+            }
+            Message::JoinLobbyQueue { lobby, rating } => {
+                let owner = self.message_signer();
+                self.enqueue(owner, origin, lobby, rating).await
+            }
+            Message::LeaveMatchQueue => {
+                let owner = self.message_signer();
+                self.state.match_queue.remove(&owner).unwrap();
+            }
+            Message::MatchFound {
+                match_id,
+                owner,
+                opponent,
+                seed,
+            } => self.start_match_game(match_id, owner, opponent, seed).await,
+            Message::Challenge { challenge_id, seed } => {
+                self.receive_challenge(origin, challenge_id, seed).await
+            }
+            Message::ChallengeAccepted { challenge_id } => {
+                self.challenge_accepted(origin, challenge_id).await
+            }
+            Message::ChallengeResult {
+                challenge_id,
+                score,
+            } => self.challenge_result(origin, challenge_id, score).await,
+            Message::ChallengeSettled {
+                challenge_id,
+                challenger_score,
+                opponent_score,
+                winner,
+            } => {
+                let key = (origin, challenge_id);
+                let Some(mut record) = self.state.challenges.get(&key).await.unwrap() else {
+                    return;
+                };
+                if record.status == ChallengeStatus::Active {
+                    record.challenger_score = Some(challenger_score);
+                    record.opponent_score = Some(opponent_score);
+                    record.winner = winner;
+                    record.status = ChallengeStatus::Finished;
+                    self.state.challenges.insert(&key, record).unwrap();
+                }
+            }
+            Message::Subscribe { game_id } => self.subscribe(origin, game_id).await,
+            Message::Unsubscribe { game_id } => {
+                if let Some(mut spectators) = self.state.spectators.get(&game_id).await.unwrap() {
+                    spectators.retain(|chain_id| *chain_id != origin);
+                    self.state.spectators.insert(&game_id, spectators).unwrap();
+                }
+            }
+            Message::Snapshot {
+                game_id,
+                move_index,
+                board,
+                score,
+                is_ended,
+            } => {
+                // Turns follow the snapshot, so the timeline starts from it.
+                let key = (origin, game_id);
+                self.state.timelines.insert(&key, move_index).unwrap();
+                let game = SpectatedGame {
+                    chain_id: origin,
+                    game_id,
+                    move_index,
+                    board,
+                    score,
+                    is_ended,
+                };
+                self.state.spectated.insert(&key, game).unwrap();
+            }
+            Message::DisputeMatch {
+                match_id,
+                replay,
+                claimed_score,
+                bond_escrow,
+            } => {
+                let player = self.message_signer();
+                self.submit_replay(origin, player, match_id, replay, claimed_score, bond_escrow)
+                    .await
+            }
+            Message::ChallengeDisputeResult {
+                match_id,
+                player,
+                bond_escrow,
+            } => {
+                let challenger = self.message_signer();
+                self.receive_dispute_challenge(origin, challenger, match_id, player, bond_escrow)
+                    .await
+            }
+            Message::CheckMatchGame {
+                match_id,
+                player,
+                move_count,
+                board,
+            } => {
+                self.check_match_game(origin, match_id, player, move_count, board)
+                    .await
+            }
+            Message::MatchGameChecked {
+                match_id,
+                player,
+                matches,
+            } => {
+                self.match_game_checked(origin, match_id, player, matches)
+                    .await
+            }
+            Message::ChatKey { key } => {
+                let owner = self.message_signer();
+                self.state.chat_keys.insert(&owner, key).unwrap();
+            }
+            Message::Chat { match_id, payload } => {
+                let sender = self.message_signer();
+                self.post_chat(sender, match_id, payload).await
+            }
+            Message::MintReward { owner } => self.mint_reward(Account {
+                chain_id: origin,
+                owner: Some(owner),
+            }),
+            Message::SettleBond {
+                escrow_id,
+                recipient,
+            } => {
+                assert_eq!(
+                    origin,
+                    self.runtime.application_id().creation.chain_id,
+                    "Bonds are settled by the matchmaking chain"
+                );
+                let chain_id = self.runtime.chain_id();
+                self.settle_bond(chain_id, escrow_id, recipient);
+            }
         }
     }
 
-    async fn execute_operation(&mut self, operation: Self::Operation) -> Self::Response {
+    async fn store(mut self) {
+        self.state.save().await.expect("Failed to save state");
+    }
+}
+
+impl Game2048Contract {
+    /// Executes an operation that is not a batch.
+    async fn execute(&mut self, operation: Operation) {
+        if let Some(feature) = operation.feature() {
+            let features = self.runtime.application_parameters().features;
+            assert!(
+                features.contains(feature),
+                "{} is disabled in this deployment",
+                operation.name()
+            );
+        }
+        self.count_operation(operation.name()).await;
+        // Windows widen over time, so players who could not be paired earlier may be now.
+        self.pair_queued_players().await;
         match operation {
-            Operation::NewGame { seed } => {
+            Operation::NewGame {
+                seed,
+                namespace,
+                sandbox,
+                max_moves,
+                board_size,
+            } => {
+                assert!(max_moves != Some(0), "Games need at least one move");
                 let seed = self.get_seed(seed);
-                let new_board = Game::new(seed).board;
+                let board_size = board_size.unwrap_or(SUPPORTED_BOARD_SIZE);
+                self.new_game(seed, namespace, sandbox, None, None, board_size)
+                    .await;
                 let game = self.state.games.load_entry_mut(&seed).await.unwrap();
-
-                game.game_id.set(seed);
-                game.board.set(new_board);
-
-                self.send_message(seed, new_board, 0, false);
+                game.max_moves.set(max_moves);
             }
-            Operation::EndGame { game_id } => {
+            Operation::EndGame { game_id, namespace } => {
+                let signer = self.runtime.authenticated_signer();
                 let board = self.state.games.load_entry_mut(&game_id).await.unwrap();
-                board.is_ended.set(true);
+                Self::check_namespace(board, &namespace);
+                Self::check_owner(board, signer);
+                self.finish_game(game_id).await;
             }
-            Operation::MakeMove { game_id, direction } => {
-                let seed = self.get_seed(0);
+            Operation::MakeMove {
+                game_id,
+                direction,
+                namespace,
+            } => {
+                let block_height = self.runtime.block_height().0;
+                let chain_id = self.runtime.chain_id();
+                let now = self.runtime.system_time();
+                let signer = self.runtime.authenticated_signer();
+                let min_interval = self
+                    .runtime
+                    .application_parameters()
+                    .min_move_interval_micros;
                 let board = self.state.games.load_entry_mut(&game_id).await.unwrap();
+                Self::check_namespace(board, &namespace);
+                Self::check_owner(board, signer);
+
+                // Directions that move no tile are dropped before the game is touched: they
+                // do not count as moves, nor end a game whose time limit is over.
+                let is_useful = match board.large_board.get() {
+                    Some(large_board) => large_board.slide(direction.clone()) != *large_board,
+                    None => Game::is_useful_move(*board.board.get(), direction.clone()),
+                };
+                if !is_useful {
+                    self.count_failure(OperationFailure::UselessMove).await;
+                    return;
+                }
+
+                if *board.status.get() == GameStatus::Paused {
+                    self.count_failure(OperationFailure::GamePaused).await;
+                    return;
+                }
+                if !board.is_ended() {
+                    let template = match *board.template_id.get() {
+                        Some(template_id) => {
+                            let template = self.state.templates.try_load_entry(&template_id);
+                            let template = template.await.unwrap().expect("Template exists");
+                            Some(template.settings.get().clone())
+                        }
+                        None => None,
+                    };
+                    let deadline = template
+                        .as_ref()
+                        .and_then(|template| template.deadline(*board.created_at.get()));
+                    if deadline.is_some_and(|deadline| now >= deadline) {
+                        self.finish_game(game_id).await;
+                        self.count_failure(OperationFailure::TimeExpired).await;
+                        return;
+                    }
+                    let ended_tournament = match *board.tournament_id.get() {
+                        Some(tournament_id) => {
+                            let tournament = self.state.tournaments.try_load_entry(&tournament_id);
+                            let tournament = tournament.await.unwrap().expect("Tournament exists");
+                            Some(tournament_id)
+                                .filter(|_| tournament.status(now) == TournamentStatus::Ended)
+                        }
+                        None => None,
+                    };
+                    if let Some(tournament_id) = ended_tournament {
+                        let entrant = board.owner.get().expect("Tournament games have an owner");
+                        self.end_tournament_game(tournament_id, entrant, game_id)
+                            .await;
+                        self.count_failure(OperationFailure::TimeExpired).await;
+                        return;
+                    }
+                    if let Some(last_move_at) = *board.last_move_at.get() {
+                        let elapsed = now.delta_since(last_move_at).as_micros();
+                        assert!(
+                            elapsed >= min_interval,
+                            "Game {game_id} was played {elapsed} microseconds ago, moves must be \
+                             at least {min_interval} microseconds apart"
+                        );
+                    }
+                    board.last_move_at.set(Some(now));
+                    if let Some(large_board) = *board.large_board.get() {
+                        self.make_large_move(game_id, large_board, direction).await;
+                        return;
+                    }
 
-                let is_ended = board.is_ended.get();
-                if !is_ended {
-                    let mut game = Game {
+                    let spawn_index = *board.spawn_index.get();
+                    let position = Position {
                         board: *board.board.get(),
-                        seed,
+                        score: *board.score.get(),
+                        combo: *board.combo.get(),
+                        mode: *board.mode.get(),
+                        template: template.as_ref(),
                     };
+                    let mut rng = Rng::for_spawn(block_height, chain_id, spawn_index);
+                    let elapsed_micros = now.delta_since(*board.created_at.get()).as_micros();
+                    let outcome = position
+                        .play(direction.clone(), &mut rng, elapsed_micros)
+                        .expect("Useless moves were dropped");
+                    let merges = position.board.merges(direction.clone());
+                    Self::record_merges(board, &merges, Game::max_tile(outcome.board));
 
-                    let new_board = Game::execute(&mut game, direction);
-                    let is_ended = Game::is_ended(new_board);
-                    let score = Game::score(new_board);
+                    let move_count = board.move_count.get_mut();
+                    *move_count += 1;
+                    board.history.push(MoveHistory {
+                        move_index: *move_count,
+                        previous_board: position.board,
+                        previous_score: position.score,
+                        previous_combo: position.combo,
+                    });
+                    // Undos are turns too, so turns are not indexed by move count.
+                    let turn_index = *board.move_log.count.get();
+                    board.spawn_index.set(spawn_index + 1);
+                    board.board.set(outcome.board);
+                    board.score.set(outcome.score);
+                    board.combo.set(outcome.combo);
+                    board.updated_at.set(now);
+                    if outcome.is_ended {
+                        board.status.set(GameStatus::Ended);
+                    }
+                    let reached_limit = board
+                        .max_moves
+                        .get()
+                        .is_some_and(|max_moves| *board.move_count.get() >= max_moves);
 
-                    board.board.set(new_board);
-                    board.score.set(score);
-                    if is_ended {
-                        board.is_ended.set(true);
+                    let move_made = GameEvent::MoveMade {
+                        game_id,
+                        move_count: *board.move_count.get(),
+                        direction: direction.clone(),
+                        board: outcome.board,
+                        score: outcome.score,
+                    };
+                    let turn = Turn {
+                        direction: Some(direction),
+                        spawned_tile: outcome.spawned_tile,
+                        spawn_index: Some(spawn_index),
+                        board: outcome.board,
+                        score: outcome.score,
+                        is_ended: outcome.is_ended,
+                    };
+                    Self::log_turn(board, &mut self.state.metrics, turn.clone()).await;
+                    if outcome.is_ended {
+                        Self::analyze_game(board).await;
+                    }
+                    if let Some(owner) = board.owner.get().filter(|_| !*board.sandbox.get()) {
+                        let mode = *board.mode.get();
+                        self.record_move(owner, mode, &turn).await;
+                    }
+                    if outcome.is_ended {
+                        self.rank_game(game_id).await;
+                        self.reward_game(game_id).await;
+                        self.record_daily_score(game_id).await;
                     }
 
-                    self.send_message(game_id, new_board, score, is_ended);
+                    self.publish_turn(game_id, namespace, turn_index, turn)
+                        .await;
+                    self.emit_event(move_made);
+                    if outcome.is_ended {
+                        self.emit_game_ended(game_id).await;
+                        self.end_challenge_game(game_id).await;
+                    } else if reached_limit {
+                        self.finish_game(game_id).await;
+                    }
+                } else {
+                    self.count_failure(OperationFailure::GameEnded).await;
                 }
             }
-        }
-    }
-
-    async fn execute_message(&mut self, _message: Self::Message) {}
+            Operation::CreateCommunity {
+                community_id,
+                name,
+                admins,
+                default_mode,
+                fees,
+            } => {
+                self.create_community(community_id, name, admins, default_mode, fees)
+                    .await
+            }
+            Operation::ConfigureCommunity {
+                community_id,
+                name,
+                admins,
+                default_mode,
+                fees,
+            } => {
+                let signer = self.signer();
+                let community = self.load_community(&community_id).await;
+                Self::check_admin(community, &signer);
 
-    async fn store(mut self) {
-        self.state.save().await.expect("Failed to save state");
+                if let Some(name) = name {
+                    community.name.set(name);
+                }
+                if let Some(admins) = admins {
+                    assert!(!admins.is_empty(), "A community needs at least one admin");
+                    community.admins.set(admins);
+                }
+                if let Some(default_mode) = default_mode {
+                    community.default_mode.set(default_mode);
+                }
+                if let Some(fees) = fees {
+                    community.fees.set(fees);
+                }
+            }
+            Operation::JoinCommunity { community_id } => {
+                let signer = self.signer();
+                let community = self.load_community(&community_id).await;
+                if !community.members.contains(&signer).await.unwrap() {
+                    community.members.insert(&signer).unwrap();
+                    let member_count = community.member_count.get_mut();
+                    *member_count += 1;
+                }
+            }
+            Operation::LeaveCommunity { community_id } => {
+                let signer = self.signer();
+                let community = self.load_community(&community_id).await;
+                if community.members.contains(&signer).await.unwrap() {
+                    community.members.remove(&signer).unwrap();
+                    let member_count = community.member_count.get_mut();
+                    *member_count -= 1;
+                }
+            }
+            Operation::CreateBeaconRound {
+                commit_deadline,
+                reveal_deadline,
+                stake,
+            } => {
+                self.create_beacon_round(commit_deadline, reveal_deadline, stake)
+                    .await
+            }
+            Operation::CommitEntropy {
+                round_id,
+                commitment,
+            } => self.commit_entropy(round_id, commitment).await,
+            Operation::RevealEntropy { round_id, entropy } => {
+                self.reveal_entropy(round_id, entropy).await
+            }
+            Operation::CloseBeaconRound { round_id } => self.close_beacon_round(round_id).await,
+            Operation::RequestAccountMerge { primary } => {
+                let signer = self.signer();
+                assert!(signer != primary, "An account cannot be merged into itself");
+                let player = self.state.players.load_entry_mut(&signer).await.unwrap();
+                player.merge_request.set(Some(primary));
+                player.merge_confirmed.set(false);
+            }
+            Operation::ConfirmAccountMerge { duplicate } => {
+                let signer = self.signer();
+                let player = self.state.players.load_entry_mut(&duplicate).await.unwrap();
+                assert!(
+                    *player.merge_request.get() == Some(signer),
+                    "Account {duplicate} did not ask to be merged into the signer's account"
+                );
+                player.merge_confirmed.set(true);
+            }
+            Operation::ApproveAccountMerge { duplicate } => self.merge_accounts(duplicate).await,
+            Operation::RequestGame {
+                namespace,
+                sandbox,
+                round_id,
+            } => self.request_game(namespace, sandbox, round_id).await,
+            Operation::StartGame { request_id } => self.start_game(request_id).await,
+            Operation::CreateTemplate { template } => self.create_template(template).await,
+            Operation::NewTemplateGame { template_id, seed } => {
+                let seed = self.get_seed(seed);
+                self.new_game(
+                    seed,
+                    None,
+                    false,
+                    None,
+                    Some(template_id),
+                    SUPPORTED_BOARD_SIZE,
+                )
+                .await
+            }
+            Operation::MintJoinCode {
+                template_id,
+                capacity,
+                expires_at,
+            } => self.mint_join_code(template_id, capacity, expires_at).await,
+            Operation::JoinWithCode { code } => self.join_with_code(code).await,
+            Operation::SetActiveGameLimit { limit } => {
+                let signer = self.signer();
+                assert!(
+                    self.state.admins.get().contains(&signer),
+                    "Only admins can set the active game limit"
+                );
+                self.state.active_game_limit.set(Some(limit));
+            }
+            Operation::SetItemPrice { item, price } => {
+                let signer = self.signer();
+                assert!(
+                    self.state.admins.get().contains(&signer),
+                    "Only admins can manage the item catalog"
+                );
+                match price {
+                    Some(price) => self.state.item_prices.insert(&item, price).unwrap(),
+                    None => self.state.item_prices.remove(&item).unwrap(),
+                }
+            }
+            Operation::SetTreasury { chain_id, owner } => {
+                let signer = self.signer();
+                assert!(
+                    self.state.admins.get().contains(&signer),
+                    "Only admins can set the treasury"
+                );
+                self.state.treasury.set(Some(Account { chain_id, owner }));
+            }
+            Operation::BuyItem { item, max_price } => self.buy_item(item, max_price).await,
+            Operation::ConfigureSeasonPass { settings } => {
+                let signer = self.signer();
+                assert!(
+                    self.state.admins.get().contains(&signer),
+                    "Only admins can configure season passes"
+                );
+                self.state.pass_settings.set(Some(settings));
+            }
+            Operation::BuySeasonPass { max_price } => self.buy_season_pass(max_price).await,
+            Operation::SetAttester { attester } => {
+                let signer = self.signer();
+                assert!(
+                    self.state.admins.get().contains(&signer),
+                    "Only admins can set the attester"
+                );
+                self.state.attester.set(Some(attester));
+            }
+            Operation::SubmitAttestation {
+                attestation,
+                signature,
+            } => self.submit_attestation(attestation, signature).await,
+            Operation::SetLeaderboardFilters { filters } => {
+                let signer = self.signer();
+                assert!(
+                    self.state.admins.get().contains(&signer),
+                    "Only admins can set the leaderboard filters"
+                );
+                self.state.leaderboard_filters.set(filters);
+            }
+            Operation::JoinMatchQueue => self.join_match_queue(DEFAULT_LOBBY.to_string()).await,
+            Operation::JoinLobbyQueue { lobby } => self.join_match_queue(lobby).await,
+            Operation::CreateLobby { name, lobby } => {
+                let signer = self.signer();
+                assert!(
+                    self.state.admins.get().contains(&signer),
+                    "Only admins can create lobbies"
+                );
+                let exists = self.state.lobbies.contains_key(&name).await.unwrap();
+                assert!(
+                    name != DEFAULT_LOBBY && !exists,
+                    "Lobby {name} already exists"
+                );
+                self.state.lobbies.insert(&name, lobby).unwrap();
+            }
+            Operation::LeaveMatchQueue => {
+                self.signer();
+                self.send_to_matchmaker(Message::LeaveMatchQueue);
+            }
+            Operation::Undo { game_id, namespace } => self.undo(game_id, namespace).await,
+            Operation::Challenge { opponent_chain } => self.challenge(opponent_chain).await,
+            Operation::AcceptChallenge {
+                challenger_chain,
+                challenge_id,
+            } => self.accept_challenge(challenger_chain, challenge_id).await,
+            Operation::Spectate { chain_id, game_id } => {
+                assert!(
+                    chain_id != self.runtime.chain_id(),
+                    "Games of this chain are not spectated"
+                );
+                self.runtime
+                    .prepare_message(Message::Subscribe { game_id })
+                    .send_to(chain_id);
+                self.count_message();
+            }
+            Operation::StopSpectating { chain_id, game_id } => {
+                self.state.spectated.remove(&(chain_id, game_id)).unwrap();
+                self.runtime
+                    .prepare_message(Message::Unsubscribe { game_id })
+                    .send_to(chain_id);
+                self.count_message();
+            }
+            Operation::SponsorGames {
+                community_id,
+                players,
+                games_per_player,
+                expires_at,
+            } => {
+                self.sponsor_games(community_id, players, games_per_player, expires_at)
+                    .await
+            }
+            Operation::WithdrawSponsorship { sponsorship_id } => {
+                self.withdraw_sponsorship(sponsorship_id).await
+            }
+            Operation::DisputeMatch {
+                match_id,
+                replay,
+                claimed_score,
+            } => self.dispute_match(match_id, replay, claimed_score),
+            Operation::ResolveDispute { match_id } => self.resolve_expired_dispute(match_id).await,
+            Operation::ChallengeDisputeResult { match_id, player } => {
+                self.challenge_dispute_result(match_id, player)
+            }
+            Operation::FinalizeDispute { match_id } => self.finalize_dispute(match_id).await,
+            Operation::SetChatKey { key } => {
+                assert!(
+                    !key.is_empty() && key.len() <= MAX_CHAT_KEY_LEN,
+                    "Chat keys have 1 to {MAX_CHAT_KEY_LEN} bytes"
+                );
+                self.send_to_matchmaker(Message::ChatKey { key });
+            }
+            Operation::SendChat { match_id, payload } => {
+                assert!(
+                    !payload.is_empty() && payload.len() <= MAX_CHAT_PAYLOAD_LEN,
+                    "Chat messages have 1 to {MAX_CHAT_PAYLOAD_LEN} bytes"
+                );
+                self.send_to_matchmaker(Message::Chat { match_id, payload });
+            }
+            Operation::CreateTournament {
+                name,
+                seed,
+                move_limit,
+            } => self.create_tournament(name, seed, move_limit).await,
+            Operation::JoinTournament { tournament_id } => {
+                self.join_tournament(tournament_id).await
+            }
+            Operation::SubmitResult { tournament_id } => self.submit_result(tournament_id).await,
+            Operation::CloseTournament { tournament_id } => {
+                self.close_tournament(tournament_id).await
+            }
+            Operation::VerifyGame { game_id } => self.verify_game(game_id).await,
+            Operation::ConfigureMode { mode, settings } => {
+                let signer = self.signer();
+                assert!(
+                    self.state.admins.get().contains(&signer),
+                    "Only admins can configure game modes"
+                );
+                self.state.modes.insert(&mode, settings).unwrap();
+            }
+            Operation::SetTournamentEnd {
+                tournament_id,
+                ends_at,
+            } => self.set_tournament_end(tournament_id, ends_at).await,
+            Operation::NewDailyGame => self.new_daily_game().await,
+            Operation::PlayTutorial {
+                tutorial,
+                direction,
+            } => self.play_tutorial(tutorial, direction).await,
+            Operation::PauseGame { game_id, namespace } => {
+                self.pause_game(game_id, namespace, true).await
+            }
+            Operation::ResumeGame { game_id, namespace } => {
+                self.pause_game(game_id, namespace, false).await
+            }
+            Operation::Batch(_) => unreachable!("Batches are not nested"),
+            Operation::EndTournamentGames { tournament_id } => {
+                let signer = self.signer();
+                assert!(
+                    self.state.admins.get().contains(&signer),
+                    "Only admins can end tournament games"
+                );
+                let now = self.runtime.system_time();
+                let tournament = self.load_open_tournament(tournament_id).await;
+                assert!(
+                    tournament.status(now) == TournamentStatus::Ended,
+                    "Tournament {tournament_id} has not ended"
+                );
+                self.end_tournament_games(tournament_id).await;
+            }
+        }
     }
-}
 
-impl Game2048Contract {
     fn get_seed(&mut self, init_seed: u16) -> u16 {
         if init_seed != 0 {
             init_seed
         } else {
-            let block_height = self.runtime.block_height().to_string();
-            gen_range(&block_height, 0, u16::MAX)
+            self.draw_seed()
+        }
+    }
+
+    /// Draws a game seed from the chain, height and timestamp of the block, never `0`.
+    fn draw_seed(&mut self) -> u16 {
+        let draw = *self.state.seed_draws.get();
+        self.state.seed_draws.set(draw + 1);
+        let mut rng = Rng::for_block(
+            self.runtime.chain_id(),
+            self.runtime.block_height().0,
+            self.runtime.system_time().micros(),
+            draw,
+        );
+        rng.next_seed()
+    }
+
+    fn signer(&mut self) -> Owner {
+        self.runtime
+            .authenticated_signer()
+            .expect("Operation must be signed")
+    }
+
+    async fn load_community(&mut self, community_id: &CommunityId) -> &mut CommunityState {
+        let community = self.state.communities.load_entry_mut(community_id).await;
+        let community = community.unwrap();
+        assert!(
+            !community.admins.get().is_empty(),
+            "Community {community_id} does not exist"
+        );
+        community
+    }
+
+    async fn create_community(
+        &mut self,
+        community_id: CommunityId,
+        name: String,
+        mut admins: Vec<Owner>,
+        default_mode: GameMode,
+        fees: FeeSettings,
+    ) {
+        let creator = self.signer();
+        let existing = self.state.communities.try_load_entry(&community_id).await;
+        assert!(
+            existing.unwrap().is_none(),
+            "Community {community_id} already exists"
+        );
+
+        if !admins.contains(&creator) {
+            admins.push(creator);
+        }
+        let community = self
+            .state
+            .communities
+            .load_entry_mut(&community_id)
+            .await
+            .unwrap();
+        community.name.set(name);
+        community.admins.set(admins);
+        community.default_mode.set(default_mode);
+        community.fees.set(fees);
+        community.members.insert(&creator).unwrap();
+        community.member_count.set(1);
+    }
+
+    fn check_admin(community: &CommunityState, owner: &Owner) {
+        assert!(
+            community.admins.get().contains(owner),
+            "Only community admins can do this"
+        );
+    }
+
+    /// Checks that the signer may create games in `namespace`, returning the mode they
+    /// are played in.
+    async fn enter_namespace(&mut self, namespace: &Option<CommunityId>) -> GameMode {
+        let Some(community_id) = namespace else {
+            return GameMode::default();
+        };
+        let signer = self.signer();
+        let community = self.load_community(community_id).await;
+        assert!(
+            community.members.contains(&signer).await.unwrap(),
+            "Only members can play in community {community_id}"
+        );
+        *community.default_mode.get()
+    }
+
+    /// Charges the signer the fee of a new game in `community_id`, paying it to the fee
+    /// recipient, or to the first admin of the community if there is none. The fee is taken
+    /// from a sponsorship of the signer in the community that is still valid, and from their
+    /// own balance if none is.
+    async fn charge_game_fee(&mut self, community_id: &CommunityId) {
+        let signer = self.signer();
+        let now = self.runtime.system_time();
+        let community = self.load_community(community_id).await;
+        let fees = community.fees.get().clone();
+        if fees.game_fee == Amount::ZERO {
+            return;
+        }
+        let recipient = fees
+            .recipient
+            .or_else(|| community.admins.get().first().copied())
+            .expect("The community has no admin to pay its fees to");
+        let recipient = Account {
+            chain_id: self.runtime.chain_id(),
+            owner: Some(recipient),
+        };
+
+        let sponsorship_ids = match self.state.players.try_load_entry(&signer).await.unwrap() {
+            Some(player) => player.sponsorships.indices().await.unwrap(),
+            None => Vec::new(),
+        };
+        for sponsorship_id in sponsorship_ids {
+            let sponsorship = self
+                .state
+                .sponsorships
+                .load_entry_mut(&sponsorship_id)
+                .await
+                .unwrap();
+            let credits = sponsorship.credits.get(&signer).await.unwrap().unwrap_or(0);
+            if sponsorship.community_id.get() != community_id
+                || *sponsorship.expires_at.get() <= now
+                || credits == 0
+                || *sponsorship.balance.get() < fees.game_fee
+            {
+                continue;
+            }
+            sponsorship.credits.insert(&signer, credits - 1).unwrap();
+            let balance = *sponsorship.balance.get();
+            sponsorship
+                .balance
+                .set(balance.saturating_sub(fees.game_fee));
+            self.runtime.transfer(None, recipient, fees.game_fee);
+            return;
         }
+        self.runtime
+            .transfer(Some(signer), recipient, fees.game_fee);
     }
 
-    fn send_message(&mut self, game_id: u16, board: u64, score: u64, is_ended: bool) {
-        let chain_id =
-            ChainId::from_str("256e1dbc00482ddd619c293cc0df94d366afe7980022bb22d99e33036fd465dd")
+    /// Prepays the fees of `games_per_player` games in `community_id` for each of `players`,
+    /// at the current fee of the community.
+    async fn sponsor_games(
+        &mut self,
+        community_id: CommunityId,
+        mut players: Vec<Owner>,
+        games_per_player: u32,
+        expires_at: Timestamp,
+    ) {
+        let sponsor = self.signer();
+        players.sort();
+        players.dedup();
+        assert!(
+            !players.is_empty() && games_per_player > 0,
+            "Sponsorships need players and games"
+        );
+        assert!(
+            expires_at > self.runtime.system_time(),
+            "Sponsorships must expire in the future"
+        );
+        let community = self.load_community(&community_id).await;
+        let games = u128::from(games_per_player) * players.len() as u128;
+        let total = community.fees.get().game_fee.saturating_mul(games);
+        if total > Amount::ZERO {
+            let chain_balance = Account {
+                chain_id: self.runtime.chain_id(),
+                owner: None,
+            };
+            self.runtime.transfer(Some(sponsor), chain_balance, total);
+        }
+
+        let sponsorship_id = *self.state.next_sponsorship.get();
+        self.state.next_sponsorship.set(sponsorship_id + 1);
+        let sponsorship = self
+            .state
+            .sponsorships
+            .load_entry_mut(&sponsorship_id)
+            .await
+            .unwrap();
+        sponsorship.sponsor.set(Some(sponsor));
+        sponsorship.community_id.set(community_id);
+        sponsorship.expires_at.set(expires_at);
+        sponsorship.balance.set(total);
+        for player in &players {
+            sponsorship
+                .credits
+                .insert(player, games_per_player)
                 .unwrap();
+        }
+        for player in &players {
+            let player = self.state.players.load_entry_mut(player).await.unwrap();
+            player.sponsorships.insert(&sponsorship_id).unwrap();
+        }
+    }
+
+    /// Refunds the fees left in an expired sponsorship to its sponsor, and stops it from
+    /// covering its players' games.
+    async fn withdraw_sponsorship(&mut self, sponsorship_id: u32) {
+        let signer = self.signer();
+        let now = self.runtime.system_time();
+        let sponsorship = self
+            .state
+            .sponsorships
+            .try_load_entry(&sponsorship_id)
+            .await
+            .unwrap();
+        let sponsorship = sponsorship.expect("Sponsorship does not exist");
+        assert!(
+            *sponsorship.sponsor.get() == Some(signer),
+            "Only the sponsor can withdraw sponsorship {sponsorship_id}"
+        );
+        assert!(
+            *sponsorship.expires_at.get() <= now,
+            "Sponsorship {sponsorship_id} has not expired yet"
+        );
+        let balance = *sponsorship.balance.get();
+        let players = sponsorship.credits.indices().await.unwrap();
+        drop(sponsorship);
+
+        if balance > Amount::ZERO {
+            let sponsor = Account {
+                chain_id: self.runtime.chain_id(),
+                owner: Some(signer),
+            };
+            self.runtime.transfer(None, sponsor, balance);
+        }
+        for player in &players {
+            let known = self
+                .state
+                .players
+                .try_load_entry(player)
+                .await
+                .unwrap()
+                .is_some();
+            if known {
+                let player = self.state.players.load_entry_mut(player).await.unwrap();
+                player.sponsorships.remove(&sponsorship_id).unwrap();
+            }
+        }
+        self.state
+            .sponsorships
+            .remove_entry(&sponsorship_id)
+            .unwrap();
+    }
+
+    /// Rejects operations addressing `game` from outside the namespace it was created in.
+    fn check_namespace(game: &GameState, namespace: &Option<CommunityId>) {
+        assert!(
+            game.namespace.get() == namespace,
+            "Game {} does not belong to this community",
+            game.game_id.get()
+        );
+    }
+
+    /// Rejects operations playing or ending `game` from anyone but the signer who created
+    /// it. Games created without a signer may only be played without one.
+    fn check_owner(game: &GameState, signer: Option<Owner>) {
+        assert!(
+            *game.owner.get() == signer,
+            "Only the owner of game {} can play it",
+            game.game_id.get()
+        );
+    }
+
+    /// Records the merges of a move of `game`, after which its largest tile is `max_tile`.
+    fn record_merges(game: &mut GameState, merges: &[u64], max_tile: u64) {
+        *game.total_merges.get_mut() += merges.len() as u64;
+        if let Some(&largest) = merges.iter().max() {
+            let largest_merge = game.largest_merge.get_mut();
+            *largest_merge = (*largest_merge).max(largest);
+        }
+        let highest_tile = game.highest_tile.get_mut();
+        *highest_tile = (*highest_tile).max(max_tile);
+    }
+
+    /// Compares the moves of a game that just ended to the solver's, if its move log was
+    /// kept in full.
+    async fn analyze_game(game: &mut GameState) {
+        if game.large_board.get().is_some() {
+            return;
+        }
+        let turns = game.move_log.read_all().await.unwrap();
+        game.analysis
+            .set(turns.map(|turns| GameAnalysis::analyze(&turns)));
+    }
+
+    /// Starts a new move log for `game`, keeping the turns `retention` keeps.
+    fn reset_move_log(
+        game: &mut GameState,
+        metrics: &mut MetricsState<ViewStorageContext>,
+        retention: HistoryRetention,
+    ) {
+        let log = &mut game.move_log;
+        *metrics.kept_turns.get_mut() -= u64::from(*log.kept.get());
+        log.turns.clear();
+        log.count.set(0);
+        log.kept.set(0);
+        log.retention.set(retention);
+    }
+
+    /// Appends `turn` to the move log of `game`, dropping the turns its history retention
+    /// no longer keeps.
+    async fn log_turn(
+        game: &mut GameState,
+        metrics: &mut MetricsState<ViewStorageContext>,
+        turn: Turn,
+    ) {
+        let log = &mut game.move_log;
+        let turn_index = *log.count.get();
+        let kept_before = *log.kept.get();
+        log.turns.insert(&turn_index, turn).unwrap();
+        let mut kept = kept_before + 1;
+        for dropped in log.retention.get().dropped_by(turn_index) {
+            if log.turns.contains_key(&dropped).await.unwrap() {
+                log.turns.remove(&dropped).unwrap();
+                kept -= 1;
+            }
+        }
+        log.count.set(turn_index + 1);
+        log.kept.set(kept);
+        let kept_turns = metrics.kept_turns.get_mut();
+        *kept_turns = *kept_turns + u64::from(kept) - u64::from(kept_before);
+    }
+
+    /// Reports turn `turn_index` of game `game_id` to the hub, if any, with the fault the application
+    /// parameters inject in it, if any. A turn held back by a delay is sent after the next
+    /// turn that is.
+    async fn report_turn(
+        &mut self,
+        game_id: u16,
+        namespace: Option<CommunityId>,
+        turn_index: u32,
+        turn: Turn,
+    ) {
+        let parameters = self.runtime.application_parameters();
+        let Some(hub) = parameters.hub else {
+            return;
+        };
+        let faults = parameters.message_faults;
+        let copies = match fault_for(&faults, game_id, turn_index) {
+            None => 1,
+            Some(FaultKind::Drop) => 0,
+            Some(FaultKind::Duplicate) => 2,
+            Some(FaultKind::Delay) => {
+                self.state
+                    .delayed_turns
+                    .insert(&game_id, turn_index)
+                    .unwrap();
+                0
+            }
+        };
+        for _ in 0..copies {
+            let message = Self::turn_message(game_id, namespace.clone(), turn_index, turn.clone());
+            self.runtime.prepare_message(message).send_to(hub);
+            self.count_message();
+        }
+        if copies == 0 {
+            return;
+        }
+        if let Some(delayed) = self.state.delayed_turns.get(&game_id).await.unwrap() {
+            self.state.delayed_turns.remove(&game_id).unwrap();
+            self.resend_turns(hub, game_id, delayed, delayed).await;
+        }
+    }
+
+    async fn count_operation(&mut self, name: &str) {
+        let operations = &mut self.state.metrics.operations;
+        let count = operations.get(name).await.unwrap().unwrap_or(0);
+        operations.insert(name, count + 1).unwrap();
+    }
+
+    async fn count_failure(&mut self, failure: OperationFailure) {
+        assert!(!self.in_batch, "Batched operation dropped: {failure:?}");
+        let failures = &mut self.state.metrics.failures;
+        let count = failures.get(&failure).await.unwrap().unwrap_or(0);
+        failures.insert(&failure, count + 1).unwrap();
+    }
+
+    fn count_message(&mut self) {
+        *self.state.metrics.messages_sent.get_mut() += 1;
+    }
+
+    /// Emits `event` to the game events stream of this chain.
+    fn emit_event(&mut self, event: GameEvent) {
+        let value = bcs::to_bytes(&event).expect("Game events are serializable");
         self.runtime
-            .prepare_message(Message::Game {
+            .emit(GameEvent::stream_name(), &event.key(), &value);
+        self.state.game_events.push(event);
+        *self.state.metrics.events_emitted.get_mut() += 1;
+    }
+
+    /// Emits the end of game `game_id`, which just ended.
+    async fn emit_game_ended(&mut self, game_id: u16) {
+        let game = self.state.games.load_entry_mut(&game_id).await.unwrap();
+        let event = GameEvent::GameEnded {
+            game_id,
+            owner: *game.owner.get(),
+            score: *game.score.get(),
+            move_count: *game.move_count.get(),
+        };
+        self.emit_event(event);
+    }
+
+    fn turn_message(
+        game_id: u16,
+        namespace: Option<CommunityId>,
+        move_index: u32,
+        turn: Turn,
+    ) -> Message {
+        Message::Game {
+            game_id,
+            namespace,
+            move_index,
+            direction: turn.direction,
+            spawned_tile: turn.spawned_tile,
+            spawn_index: turn.spawn_index,
+            board: turn.board,
+            score: turn.score,
+            is_ended: turn.is_ended,
+        }
+    }
+
+    /// Sends turn `turn_index` of game `game_id` to the spectators of the game, who are
+    /// dropped once it is over, and reports it to the hub.
+    async fn publish_turn(
+        &mut self,
+        game_id: u16,
+        namespace: Option<CommunityId>,
+        turn_index: u32,
+        turn: Turn,
+    ) {
+        let is_ended = turn.is_ended;
+        let spectators = self.state.spectators.get(&game_id).await.unwrap();
+        for chain_id in spectators.unwrap_or_default() {
+            let message = Self::turn_message(game_id, namespace.clone(), turn_index, turn.clone());
+            self.runtime.prepare_message(message).send_to(chain_id);
+            self.count_message();
+        }
+        if is_ended {
+            self.state.spectators.remove(&game_id).unwrap();
+        }
+        self.report_turn(game_id, namespace, turn_index, turn).await;
+    }
+
+    /// Sends a snapshot of game `game_id` to `spectator`, who then receives its turns until it
+    /// ends. Snapshots of games that are over are sent without subscribing.
+    async fn subscribe(&mut self, spectator: ChainId, game_id: u16) {
+        let Some(game) = self.state.games.try_load_entry(&game_id).await.unwrap() else {
+            return;
+        };
+        let snapshot = Message::Snapshot {
+            game_id,
+            move_index: game.move_log.count.get().saturating_sub(1),
+            board: *game.board.get(),
+            score: *game.score.get(),
+            is_ended: game.is_ended(),
+        };
+        let is_ended = game.is_ended();
+        drop(game);
+        self.runtime.prepare_message(snapshot).send_to(spectator);
+        self.count_message();
+        if is_ended {
+            return;
+        }
+        let mut spectators = self
+            .state
+            .spectators
+            .get(&game_id)
+            .await
+            .unwrap()
+            .unwrap_or_default();
+        if !spectators.contains(&spectator) {
+            spectators.push(spectator);
+            self.state.spectators.insert(&game_id, spectators).unwrap();
+        }
+    }
+
+    /// Sends the final snapshot of game `game_id`, which ended without a turn, to its
+    /// spectators and drops them.
+    async fn end_spectating(&mut self, game_id: u16) {
+        let Some(spectators) = self.state.spectators.get(&game_id).await.unwrap() else {
+            return;
+        };
+        self.state.spectators.remove(&game_id).unwrap();
+        let game = self.state.games.load_entry_mut(&game_id).await.unwrap();
+        let move_index = game.move_log.count.get().saturating_sub(1);
+        let (board, score) = (*game.board.get(), *game.score.get());
+        for chain_id in spectators {
+            let snapshot = Message::Snapshot {
                 game_id,
+                move_index,
                 board,
                 score,
-                is_ended,
+                is_ended: true,
+            };
+            self.runtime.prepare_message(snapshot).send_to(chain_id);
+            self.count_message();
+        }
+    }
+
+    /// Records the score of game `game_id` of `chain_id` on the hub, which becomes the best
+    /// score if it beats it.
+    fn record_remote_score(&mut self, chain_id: ChainId, game_id: u16, score: u64) {
+        self.state
+            .remote_scores
+            .insert(&(chain_id, game_id), score)
+            .unwrap();
+        let best = self.state.best_remote_score.get_mut();
+        if !best.as_ref().is_some_and(|best| best.score >= score) {
+            *best = Some(RemoteScore {
+                chain_id,
+                game_id,
+                score,
+            });
+        }
+    }
+
+    /// Sends the turns `from..=to` of a game played on this chain to the `requester` again,
+    /// reading them from the game's move log. Turns the log did not keep are not sent.
+    async fn resend_turns(&mut self, requester: ChainId, game_id: u16, from: u32, to: u32) {
+        let Some(game) = self.state.games.try_load_entry(&game_id).await.unwrap() else {
+            return;
+        };
+        let namespace = game.namespace.get().clone();
+        let turns = game.move_log.read(from..=to).await.unwrap();
+        drop(game);
+
+        for (move_index, turn) in turns {
+            let message = Self::turn_message(game_id, namespace.clone(), move_index, turn);
+            self.runtime.prepare_message(message).send_to(requester);
+            self.count_message();
+        }
+    }
+
+    /// Records a turn of a game played on the `origin` chain, asking for the turns missing
+    /// from its timeline when messages arrive out of order or were lost.
+    async fn record_turn(&mut self, origin: ChainId, game_id: u16, move_index: u32) {
+        let key = (origin, game_id);
+        let last_seen = self.state.timelines.get(&key).await.unwrap();
+        let expected = last_seen.map_or(0, |index| index + 1);
+
+        if move_index == 0 || move_index == expected {
+            // A creation restarts the timeline, even when the game id is reused.
+            self.state.timelines.insert(&key, move_index).unwrap();
+        } else if move_index > expected {
+            self.runtime
+                .prepare_message(Message::ResendRequest {
+                    game_id,
+                    from: expected,
+                    to: move_index,
+                })
+                .send_to(origin);
+            self.count_message();
+        }
+    }
+
+    async fn create_beacon_round(
+        &mut self,
+        commit_deadline: Timestamp,
+        reveal_deadline: Timestamp,
+        stake: Amount,
+    ) {
+        let organizer = self.signer();
+        let now = self.runtime.system_time();
+        assert!(
+            now < commit_deadline && commit_deadline < reveal_deadline,
+            "Beacon deadlines must be in the future and commits must close before reveals"
+        );
+
+        let round_id = *self.state.next_beacon_round.get();
+        self.state.next_beacon_round.set(round_id + 1);
+        let round = self
+            .state
+            .beacon_rounds
+            .load_entry_mut(&round_id)
+            .await
+            .unwrap();
+        round.organizer.set(Some(organizer));
+        round.commit_deadline.set(commit_deadline);
+        round.reveal_deadline.set(reveal_deadline);
+        round.stake.set(stake);
+    }
+
+    async fn load_beacon_round(&mut self, round_id: u32) -> (&mut BeaconRoundState, BeaconPhase) {
+        let now = self.runtime.system_time();
+        let round = self
+            .state
+            .beacon_rounds
+            .load_entry_mut(&round_id)
+            .await
+            .unwrap();
+        assert!(
+            round.organizer.get().is_some(),
+            "Beacon round {round_id} does not exist"
+        );
+        assert!(!*round.is_closed.get(), "Beacon round {round_id} is closed");
+        let schedule = BeaconSchedule {
+            commit_deadline: *round.commit_deadline.get(),
+            reveal_deadline: *round.reveal_deadline.get(),
+        };
+        (round, schedule.phase(now))
+    }
+
+    async fn commit_entropy(&mut self, round_id: u32, commitment: CryptoHash) {
+        let signer = self.signer();
+        let (round, phase) = self.load_beacon_round(round_id).await;
+        assert!(
+            phase == BeaconPhase::Commit,
+            "Commitments to beacon round {round_id} are closed"
+        );
+        let committed = round.commitments.contains_key(&signer).await.unwrap();
+        assert!(!committed, "Entropy was already committed");
+
+        round.commitments.insert(&signer, commitment).unwrap();
+        *round.commit_count.get_mut() += 1;
+    }
+
+    async fn reveal_entropy(&mut self, round_id: u32, entropy: Vec<u8>) {
+        let signer = self.signer();
+        assert!(
+            entropy.len() <= MAX_ENTROPY_LEN,
+            "Entropy is limited to {MAX_ENTROPY_LEN} bytes"
+        );
+        let (round, phase) = self.load_beacon_round(round_id).await;
+        assert!(
+            phase == BeaconPhase::Reveal,
+            "Beacon round {round_id} does not accept reveals"
+        );
+        let committed = round.commitments.get(&signer).await.unwrap();
+        assert!(
+            committed == Some(commitment(signer, &entropy)),
+            "Entropy does not match the commitment"
+        );
+        let revealed = round.reveals.contains_key(&signer).await.unwrap();
+        assert!(!revealed, "Entropy was already revealed");
+
+        round.reveals.insert(&signer, entropy).unwrap();
+        *round.reveal_count.get_mut() += 1;
+    }
+
+    /// Closes a beacon round once it timed out, or early when every participant revealed.
+    async fn close_beacon_round(&mut self, round_id: u32) {
+        let (round, phase) = self.load_beacon_round(round_id).await;
+        let all_revealed = round.reveal_count.get() == round.commit_count.get();
+        assert!(
+            phase == BeaconPhase::TimedOut || (phase == BeaconPhase::Reveal && all_revealed),
+            "Beacon round {round_id} is still waiting for reveals"
+        );
+
+        let mut reveals = Vec::new();
+        round
+            .reveals
+            .for_each_index_value(|owner, entropy| {
+                reveals.push((owner, entropy));
+                Ok(())
             })
-            .send_to(chain_id);
+            .await
+            .unwrap();
+        let mut slashed = Vec::new();
+        for owner in round.commitments.indices().await.unwrap() {
+            if !round.reveals.contains_key(&owner).await.unwrap() {
+                slashed.push(owner);
+            }
+        }
+
+        let forfeited = round.stake.get().saturating_mul(slashed.len() as u128);
+        if !reveals.is_empty() {
+            round.output.set(Some(beacon_output(round_id, reveals)));
+        }
+        round.slashed.set(slashed);
+        round.forfeited.set(forfeited);
+        round.is_closed.set(true);
+    }
+
+    /// Requests a game seeded by beacon round `round_id`, which must still take commitments so
+    /// that nobody knows its output yet. Expired requests are dropped on the way.
+    async fn request_game(&mut self, namespace: Option<CommunityId>, sandbox: bool, round_id: u32) {
+        let owner = self.signer();
+        self.enter_namespace(&namespace).await;
+        let (round, phase) = self.load_beacon_round(round_id).await;
+        assert!(
+            phase == BeaconPhase::Commit,
+            "Games can only be requested while beacon round {round_id} takes commitments"
+        );
+        let expires_at = round
+            .reveal_deadline
+            .get()
+            .saturating_add_micros(GAME_REQUEST_TIMEOUT_MICROS);
+
+        let now = self.runtime.system_time();
+        let mut expired = Vec::new();
+        self.state
+            .game_requests
+            .for_each_index_value(|request_id, request| {
+                if request.expires_at <= now {
+                    expired.push(request_id);
+                }
+                Ok(())
+            })
+            .await
+            .unwrap();
+        for request_id in expired {
+            self.state.game_requests.remove(&request_id).unwrap();
+        }
+
+        let request_id = *self.state.next_game_request.get();
+        self.state.next_game_request.set(request_id + 1);
+        let request = GameRequest {
+            owner,
+            namespace,
+            sandbox,
+            round_id,
+            expires_at,
+        };
+        self.state
+            .game_requests
+            .insert(&request_id, request)
+            .unwrap();
+    }
+
+    /// Starts a requested game with the seed derived from the output of its beacon round.
+    async fn start_game(&mut self, request_id: u32) {
+        let owner = self.signer();
+        let now = self.runtime.system_time();
+        let request = self.state.game_requests.get(&request_id).await.unwrap();
+        let request = request.unwrap_or_else(|| panic!("Game request {request_id} does not exist"));
+        assert!(
+            request.owner == owner,
+            "Only the requester can start this game"
+        );
+        assert!(
+            now < request.expires_at,
+            "Game request {request_id} expired"
+        );
+
+        let round_id = request.round_id;
+        let round = self.state.beacon_rounds.try_load_entry(&round_id).await;
+        let output = round
+            .unwrap()
+            .and_then(|round| *round.output.get())
+            .unwrap_or_else(|| panic!("Beacon round {round_id} has no output yet"));
+        self.state.game_requests.remove(&request_id).unwrap();
+
+        let seed = game_request_seed(&output, request_id);
+        self.new_game(
+            seed,
+            request.namespace,
+            request.sandbox,
+            Some(round_id),
+            None,
+            SUPPORTED_BOARD_SIZE,
+        )
+        .await;
+    }
+
+    /// Creates game `seed` for the signer, replacing any previous game of the signer or of no
+    /// one with that id unless it is played for a tournament, a match, a daily challenge or an
+    /// ongoing challenge.
+    /// `seed_round` is the beacon round the seed was derived from, if any. Games started
+    /// from a template take their mode and board size from it instead of their namespace and
+    /// `board_size`.
+    async fn new_game(
+        &mut self,
+        seed: u16,
+        namespace: Option<CommunityId>,
+        sandbox: bool,
+        seed_round: Option<u32>,
+        template_id: Option<u32>,
+        mut board_size: u8,
+    ) {
+        let owner = self.runtime.authenticated_signer();
+        if let Some(game) = self.state.games.try_load_entry(&seed).await.unwrap() {
+            Self::check_namespace(&game, &namespace);
+            // Such as the placeholder game created with the application.
+            let is_ownerless = game.owner.get().is_none();
+            assert!(
+                is_ownerless || *game.owner.get() == owner,
+                "Game {seed} belongs to another player and cannot be replaced"
+            );
+            assert!(
+                game.tournament_id.get().is_none()
+                    && game.match_id.get().is_none()
+                    && game.daily_challenge.get().is_none(),
+                "Game {seed} is played for a tournament, a match or a daily challenge and cannot \
+                 be replaced"
+            );
+            assert!(
+                game.challenge.get().is_none() || game.is_ended(),
+                "Game {seed} is played for a challenge and cannot be replaced before it ends"
+            );
+        }
+        let mut mode = self.enter_namespace(&namespace).await;
+        if let Some(template_id) = template_id {
+            let template = self.load_template(template_id).await;
+            if *template.invite_only.get() {
+                let participant = match owner {
+                    Some(signer) => template.participants.contains(&signer).await.unwrap(),
+                    None => false,
+                };
+                assert!(
+                    participant,
+                    "Only participants who joined template {template_id} can play it"
+                );
+            }
+            template.games.insert(&seed).unwrap();
+            mode = template.settings.get().mode;
+            board_size = template.settings.get().board_size;
+        }
+        self.check_premium_mode(mode).await;
+        if !sandbox {
+            if let Some(community_id) = &namespace {
+                self.charge_game_fee(community_id).await;
+            }
+        }
+        self.create_game(seed, seed, owner, namespace, sandbox, mode, board_size)
+            .await;
+        let game = self.state.games.load_entry_mut(&seed).await.unwrap();
+        game.seed_round.set(seed_round);
+        game.template_id.set(template_id);
+    }
+
+    /// Creates game `game_id` for `owner` from the board of `seed`, replacing any previous game
+    /// with that id, and reports its first turn.
+    ///
+    /// Games on boards larger than 4x4 keep their board in `large_board`: their turns do not
+    /// fit the `u64` board of a [`Turn`], so they are neither logged nor reported, and they
+    /// allow no undos.
+    #[allow(clippy::too_many_arguments)]
+    async fn create_game(
+        &mut self,
+        game_id: u16,
+        seed: u16,
+        owner: Option<Owner>,
+        namespace: Option<CommunityId>,
+        sandbox: bool,
+        mode: GameMode,
+        board_size: u8,
+    ) {
+        let now = self.runtime.system_time();
+        let large_board = LargeBoard::new(board_size, seed);
+        let new_board = match large_board {
+            Some(_) => 0,
+            None => Game::new(seed).board,
+        };
+        let settings = self.state.modes.get(&mode).await.unwrap();
+        let retention = settings.unwrap_or_default().history_retention;
+        let game = self.state.games.load_entry_mut(&game_id).await.unwrap();
+        let previous_owner = *game.owner.get();
+        let was_active = !game.is_ended() && !*game.sandbox.get();
+
+        game.game_id.set(game_id);
+        game.owner.set(owner);
+        game.namespace.set(namespace.clone());
+        game.sandbox.set(sandbox);
+        game.seed_round.set(None);
+        game.template_id.set(None);
+        game.tournament_id.set(None);
+        game.daily_challenge.set(None);
+        game.match_id.set(None);
+        game.mode.set(mode);
+        game.seed.set(seed);
+        game.board.set(new_board);
+        game.large_board.set(large_board);
+        game.score.set(0);
+        game.combo.set(0);
+        game.status.set(GameStatus::Active);
+        game.move_count.set(0);
+        game.spawn_index.set(0);
+        game.created_at.set(now);
+        game.updated_at.set(now);
+        game.last_move_at.set(None);
+        game.highest_tile.set(match &large_board {
+            Some(large_board) => large_board.max_tile(),
+            None => Game::max_tile(new_board),
+        });
+        game.total_merges.set(0);
+        game.largest_merge.set(0);
+
+        let turn = Turn {
+            direction: None,
+            spawned_tile: 0,
+            spawn_index: None,
+            board: new_board,
+            score: 0,
+            is_ended: false,
+        };
+        Self::reset_move_log(game, &mut self.state.metrics, retention);
+        if large_board.is_none() {
+            Self::log_turn(game, &mut self.state.metrics, turn.clone()).await;
+        }
+        game.history.clear();
+        game.undos_left
+            .set(if large_board.is_none() { UNDO_LIMIT } else { 0 });
+        game.analysis.set(None);
+        game.verification.set(None);
+        game.challenge.set(None);
+        game.max_moves.set(None);
+        if let Some(previous_owner) = previous_owner.filter(|_| was_active) {
+            self.release_game_slot(previous_owner).await;
+        }
+        self.assign_game(game_id, previous_owner, owner, sandbox, new_board)
+            .await;
+
+        if large_board.is_none() {
+            self.publish_turn(game_id, namespace, 0, turn).await;
+        }
+        self.emit_event(GameEvent::GameCreated {
+            game_id,
+            owner,
+            seed,
+            mode,
+            board: new_board,
+        });
+    }
+
+    /// Plays `direction`, which moves tiles of `large_board`, in game `game_id`. Games on
+    /// large boards have no combos nor template rules, and are scored like 4x4 games.
+    async fn make_large_move(
+        &mut self,
+        game_id: u16,
+        large_board: LargeBoard,
+        direction: Direction,
+    ) {
+        let block_height = self.runtime.block_height().0;
+        let chain_id = self.runtime.chain_id();
+        let now = self.runtime.system_time();
+        let game = self.state.games.load_entry_mut(&game_id).await.unwrap();
+        let spawn_index = *game.spawn_index.get();
+        let mut rng = Rng::for_spawn(block_height, chain_id, spawn_index);
+        let merges = large_board.merges(direction.clone());
+        let large_board = large_board.slide(direction.clone()).spawn_with(&mut rng);
+        game.large_board.set(Some(large_board));
+        Self::record_merges(game, &merges, large_board.max_tile());
+        game.spawn_index.set(spawn_index + 1);
+        game.score.set(large_board.score());
+        game.updated_at.set(now);
+        let move_count = game.move_count.get_mut();
+        *move_count += 1;
+        let move_count = *move_count;
+        let reached_limit = game
+            .max_moves
+            .get()
+            .is_some_and(|max_moves| move_count >= max_moves);
+        self.emit_event(GameEvent::MoveMade {
+            game_id,
+            move_count,
+            direction,
+            board: 0,
+            score: large_board.score(),
+        });
+        if large_board.is_stuck() || reached_limit {
+            self.finish_game(game_id).await;
+        }
+    }
+
+    /// Takes back the last move of game `game_id`, restoring the position it was played from.
+    /// The spawn index is kept, so playing the same move again spawns from another seed.
+    async fn undo(&mut self, game_id: u16, namespace: Option<CommunityId>) {
+        let signer = self.runtime.authenticated_signer();
+        let now = self.runtime.system_time();
+        let game = self.state.games.load_entry_mut(&game_id).await.unwrap();
+        Self::check_namespace(game, &namespace);
+        Self::check_owner(game, signer);
+        assert!(!game.is_ended(), "Game {game_id} is over");
+        assert!(
+            *game.status.get() != GameStatus::Paused,
+            "Game {game_id} is paused"
+        );
+        let undos_left = *game.undos_left.get();
+        assert!(undos_left > 0, "No undos left in game {game_id}");
+
+        let move_count = *game.move_count.get();
+        let start = game.history.count().saturating_sub(UNDO_LIMIT as usize + 1);
+        let history = game.history.read(start..).await.unwrap();
+        let entry = MoveHistory::latest(&history, move_count)
+            .unwrap_or_else(|| panic!("Game {game_id} has no move to take back"));
+        game.board.set(entry.previous_board);
+        game.score.set(entry.previous_score);
+        game.combo.set(entry.previous_combo);
+        game.move_count.set(move_count - 1);
+        game.undos_left.set(undos_left - 1);
+        game.updated_at.set(now);
+
+        let turn = Turn {
+            direction: None,
+            spawned_tile: 0,
+            spawn_index: None,
+            board: entry.previous_board,
+            score: entry.previous_score,
+            is_ended: false,
+        };
+        let turn_index = *game.move_log.count.get();
+        Self::log_turn(game, &mut self.state.metrics, turn.clone()).await;
+        self.publish_turn(game_id, namespace, turn_index, turn)
+            .await;
+    }
+
+    /// Pauses game `game_id` if `pause`, resumes it otherwise. Only the owner of the game can,
+    /// and games that are over stay over.
+    async fn pause_game(&mut self, game_id: u16, namespace: Option<CommunityId>, pause: bool) {
+        let signer = self.runtime.authenticated_signer();
+        let game = self.state.games.load_entry_mut(&game_id).await.unwrap();
+        Self::check_namespace(game, &namespace);
+        Self::check_owner(game, signer);
+        let status = *game.status.get();
+        if pause {
+            assert!(status == GameStatus::Active, "Game {game_id} is not active");
+            game.status.set(GameStatus::Paused);
+        } else {
+            assert!(status == GameStatus::Paused, "Game {game_id} is not paused");
+            game.status.set(GameStatus::Active);
+        }
+    }
+
+    /// Ends game `game_id` if it is still running: the game is analyzed and, unless it is a
+    /// sandbox game, its owner's heatmap and active game slots are updated.
+    async fn finish_game(&mut self, game_id: u16) {
+        let game = self.state.games.load_entry_mut(&game_id).await.unwrap();
+        if game.is_ended() {
+            return;
+        }
+        game.status.set(GameStatus::Ended);
+        Self::analyze_game(game).await;
+        if let Some(owner) = game.owner.get().filter(|_| !*game.sandbox.get()) {
+            let final_board = *game.board.get();
+            let player = self.state.players.load_entry_mut(&owner).await.unwrap();
+            player.heatmap.get_mut().record_max_tile(final_board);
+            self.release_game_slot(owner).await;
+        }
+        self.rank_game(game_id).await;
+        self.reward_game(game_id).await;
+        self.record_daily_score(game_id).await;
+        self.emit_game_ended(game_id).await;
+        self.end_spectating(game_id).await;
+        self.end_challenge_game(game_id).await;
+    }
+
+    /// Mints the reward tokens of the application parameters to the owner of game `game_id`,
+    /// which just ended, if its score earns them. Games ending on other chains than the token's
+    /// ask it to mint them there.
+    async fn reward_game(&mut self, game_id: u16) {
+        let Some(rewards) = self.runtime.application_parameters().rewards else {
+            return;
+        };
+        let game = self.state.games.load_entry_mut(&game_id).await.unwrap();
+        let Some(owner) = *game.owner.get() else {
+            return;
+        };
+        let is_flagged = *game.verification.get() == Some(GameVerification::Flagged);
+        if *game.sandbox.get() || is_flagged || *game.score.get() < rewards.min_score {
+            return;
+        }
+        let chain_id = self.runtime.chain_id();
+        if rewards.chain_id != chain_id {
+            self.runtime
+                .prepare_message(Message::MintReward { owner })
+                .send_to(rewards.chain_id);
+            self.count_message();
+            return;
+        }
+        self.mint_reward(Account {
+            chain_id,
+            owner: Some(owner),
+        });
+    }
+
+    /// Mints the reward tokens of one game to `recipient`, on the chain the token was created
+    /// on.
+    fn mint_reward(&mut self, recipient: Account) {
+        let rewards = self
+            .runtime
+            .application_parameters()
+            .rewards
+            .expect("Games earn no rewards");
+        let mint = TokenOperation::MintReward {
+            token: rewards.token,
+            recipient,
+            amount: rewards.amount,
+        };
+        let token_application = rewards.token_application.with_abi::<TokenAbi>();
+        // Authenticated, for the token application to know which application mints.
+        self.runtime
+            .call_application(true, token_application, &mint);
+    }
+
+    /// Puts game `game_id`, which just ended, on the leaderboards of its namespace, mode and
+    /// current windows where it is its owner's best, if the leaderboard filters rank it.
+    async fn rank_game(&mut self, game_id: u16) {
+        let now = self.runtime.system_time();
+        let game = self.state.games.load_entry_mut(&game_id).await.unwrap();
+        let Some(owner) = *game.owner.get() else {
+            return;
+        };
+        let duration = duration_seconds(*game.created_at.get(), *game.updated_at.get());
+        let exclusion = self.state.leaderboard_filters.get().exclusion(
+            *game.sandbox.get(),
+            *game.move_count.get(),
+            duration,
+        );
+        let is_flagged = *game.verification.get() == Some(GameVerification::Flagged);
+        if exclusion.is_some() || is_flagged || game.large_board.get().is_some() {
+            return;
+        }
+        let (board, score, move_count) =
+            (*game.board.get(), *game.score.get(), *game.move_count.get());
+        let mode = *game.mode.get();
+        let entry = LeaderboardEntry {
+            owner,
+            namespace: game.namespace.get().clone(),
+            game_id,
+            score,
+            ended_at: now,
+        };
+        let key = (entry.namespace.clone(), owner);
+
+        let weights = self.runtime.application_parameters().efficiency_weights;
+        let efficiency = EfficiencyEntry {
+            owner,
+            namespace: entry.namespace.clone(),
+            game_id,
+            efficiency: weights.efficiency(board, score, move_count, duration),
+            score,
+            move_count,
+            duration_secs: duration,
+            max_tile: Game::max_tile(board),
+            ended_at: now,
+        };
+        let best = self.state.efficiency_leaderboard.get(&key).await.unwrap();
+        if !best.is_some_and(|best| best.efficiency >= efficiency.efficiency) {
+            self.state
+                .efficiency_leaderboard
+                .insert(&key, efficiency)
+                .unwrap();
+        }
+
+        for window in LeaderboardWindow::ALL {
+            for mode in [Some(mode), None] {
+                // The best game of all modes and time is kept in `leaderboard`.
+                if mode.is_none() && window == LeaderboardWindow::AllTime {
+                    continue;
+                }
+                let key = (
+                    mode,
+                    window,
+                    window.period(now),
+                    entry.namespace.clone(),
+                    owner,
+                );
+                let best = self.state.mode_leaderboards.get(&key).await.unwrap();
+                if !best.is_some_and(|best| best.score >= entry.score) {
+                    self.state
+                        .mode_leaderboards
+                        .insert(&key, entry.clone())
+                        .unwrap();
+                }
+            }
+        }
+
+        let best = self.state.leaderboard.get(&key).await.unwrap();
+        if best.is_some_and(|best| best.score >= entry.score) {
+            return;
+        }
+        self.state.leaderboard.insert(&key, entry).unwrap();
+    }
+
+    /// Recomputes game `game_id` from its move log. Games it does not reproduce are flagged
+    /// and taken off the leaderboards, so that corrupted state cannot rank.
+    async fn verify_game(&mut self, game_id: u16) {
+        let game = self.state.games.try_load_entry(&game_id).await.unwrap();
+        let game = game.unwrap_or_else(|| panic!("Game {game_id} does not exist"));
+        assert!(
+            game.large_board.get().is_none(),
+            "Game {game_id} is played on a large board, which logs no turns"
+        );
+        let turns = game.move_log.read_all().await.unwrap();
+        let turns =
+            turns.unwrap_or_else(|| panic!("The move log of game {game_id} was not kept in full"));
+        let replay = Replay::from_turns(
+            game_id,
+            *game.seed.get(),
+            *game.mode.get(),
+            *game.sandbox.get(),
+            &turns,
+        );
+        let is_verified =
+            replay.reproduces(*game.board.get(), *game.move_count.get(), *game.score.get());
+        let owner = *game.owner.get();
+        let namespace = game.namespace.get().clone();
+        drop(game);
+
+        let game = self.state.games.load_entry_mut(&game_id).await.unwrap();
+        if is_verified {
+            game.verification.set(Some(GameVerification::Verified));
+            return;
+        }
+        game.verification.set(Some(GameVerification::Flagged));
+        if let Some(owner) = owner {
+            self.unrank_game(game_id, namespace, owner).await;
+        }
+    }
+
+    /// Removes the entries of game `game_id` of `owner` from the leaderboards of `namespace`.
+    async fn unrank_game(&mut self, game_id: u16, namespace: Option<CommunityId>, owner: Owner) {
+        let key = (namespace.clone(), owner);
+        let entry = self.state.leaderboard.get(&key).await.unwrap();
+        if entry.is_some_and(|entry| entry.game_id == game_id) {
+            self.state.leaderboard.remove(&key).unwrap();
+        }
+        let entry = self.state.efficiency_leaderboard.get(&key).await.unwrap();
+        if entry.is_some_and(|entry| entry.game_id == game_id) {
+            self.state.efficiency_leaderboard.remove(&key).unwrap();
+        }
+        let mut keys = Vec::new();
+        self.state
+            .mode_leaderboards
+            .for_each_index_value(|key, entry| {
+                if key.3 == namespace && key.4 == owner && entry.game_id == game_id {
+                    keys.push(key);
+                }
+                Ok(())
+            })
+            .await
+            .unwrap();
+        for key in keys {
+            self.state.mode_leaderboards.remove(&key).unwrap();
+        }
+    }
+
+    async fn create_template(&mut self, template: GameTemplate) {
+        let organizer = self.signer();
+        assert!(!template.name.is_empty(), "Templates need a name");
+        assert!(
+            (SUPPORTED_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&template.board_size),
+            "Boards are {SUPPORTED_BOARD_SIZE} to {MAX_BOARD_SIZE} cells wide"
+        );
+
+        let template_id = *self.state.next_template.get();
+        self.state.next_template.set(template_id + 1);
+        let state = self
+            .state
+            .templates
+            .load_entry_mut(&template_id)
+            .await
+            .unwrap();
+        state.organizer.set(Some(organizer));
+        state.settings.set(template);
+    }
+
+    async fn load_template(&mut self, template_id: u32) -> &mut TemplateState {
+        let template = self.state.templates.load_entry_mut(&template_id).await;
+        let template = template.unwrap();
+        assert!(
+            template.organizer.get().is_some(),
+            "Template {template_id} does not exist"
+        );
+        template
+    }
+
+    async fn mint_join_code(&mut self, template_id: u32, capacity: u32, expires_at: Timestamp) {
+        let signer = self.signer();
+        let now = self.runtime.system_time();
+        assert!(
+            capacity > 0,
+            "Join codes must admit at least one participant"
+        );
+        assert!(now < expires_at, "Join codes must expire in the future");
+        let template = self.load_template(template_id).await;
+        assert!(
+            *template.organizer.get() == Some(signer),
+            "Only the organizer of template {template_id} can mint join codes"
+        );
+        template.invite_only.set(true);
+
+        let mut nonce = *self.state.next_join_code.get();
+        let code = loop {
+            let code = join_code(template_id, nonce, now);
+            nonce += 1;
+            if !self.state.join_codes.contains_key(&code).await.unwrap() {
+                break code;
+            }
+        };
+        self.state.next_join_code.set(nonce);
+        let join_code = JoinCode {
+            template_id,
+            capacity,
+            uses: 0,
+            expires_at,
+        };
+        self.state.join_codes.insert(&code, join_code).unwrap();
+    }
+
+    /// Registers the signer to the template of `code`. Joining again is a no-op, and does not
+    /// use up the code.
+    async fn join_with_code(&mut self, code: String) {
+        let signer = self.signer();
+        let now = self.runtime.system_time();
+        let code = code.to_uppercase();
+        let join_code = self.state.join_codes.get_mut(&code).await.unwrap();
+        let join_code = join_code.unwrap_or_else(|| panic!("Unknown join code {code}"));
+        assert!(now < join_code.expires_at, "Join code {code} expired");
+        let template_id = join_code.template_id;
+        let template = self.state.templates.load_entry_mut(&template_id).await;
+        let template = template.unwrap();
+        if template.participants.contains(&signer).await.unwrap() {
+            return;
+        }
+        assert!(
+            join_code.uses < join_code.capacity,
+            "Join code {code} is full"
+        );
+        join_code.uses += 1;
+        template.participants.insert(&signer).unwrap();
+    }
+
+    async fn create_tournament(&mut self, name: String, seed: u16, move_limit: u32) {
+        let organizer = self.signer();
+        let now = self.runtime.system_time();
+        assert!(!name.is_empty(), "Tournaments need a name");
+        assert!(move_limit > 0, "Tournament games need at least one move");
+
+        let tournament_id = *self.state.next_tournament.get();
+        self.state.next_tournament.set(tournament_id + 1);
+        let tournament = self
+            .state
+            .tournaments
+            .load_entry_mut(&tournament_id)
+            .await
+            .unwrap();
+        tournament.organizer.set(Some(organizer));
+        tournament.name.set(name);
+        tournament.seed.set(seed);
+        tournament.move_limit.set(move_limit);
+        tournament.created_at.set(now);
+    }
+
+    async fn load_open_tournament(&mut self, tournament_id: u32) -> &mut TournamentState {
+        let tournament = self.state.tournaments.load_entry_mut(&tournament_id).await;
+        let tournament = tournament.unwrap();
+        assert!(
+            tournament.organizer.get().is_some(),
+            "Tournament {tournament_id} does not exist"
+        );
+        assert!(
+            !*tournament.is_closed.get(),
+            "Tournament {tournament_id} is closed"
+        );
+        tournament
+    }
+
+    /// Enters the signer in tournament `tournament_id`, starting their game from its seed
+    /// under the first free game id from the seed on. Tournament games allow no undos.
+    async fn join_tournament(&mut self, tournament_id: u32) {
+        let entrant = self.signer();
+        let now = self.runtime.system_time();
+        let tournament = self.load_open_tournament(tournament_id).await;
+        assert!(
+            tournament.status(now) == TournamentStatus::Open,
+            "Tournament {tournament_id} is over"
+        );
+        assert!(
+            !tournament.entrants.contains_key(&entrant).await.unwrap(),
+            "Player {entrant} already entered tournament {tournament_id}"
+        );
+        let seed = *tournament.seed.get();
+        let move_limit = *tournament.move_limit.get();
+        let game_id = self.free_game_id(seed).await;
+        self.create_game(
+            game_id,
+            seed,
+            Some(entrant),
+            None,
+            false,
+            GameMode::default(),
+            SUPPORTED_BOARD_SIZE,
+        )
+        .await;
+        let game = self.state.games.load_entry_mut(&game_id).await.unwrap();
+        game.tournament_id.set(Some(tournament_id));
+        game.max_moves.set(Some(move_limit));
+        game.undos_left.set(0);
+        let tournament = self.load_open_tournament(tournament_id).await;
+        tournament.entrants.insert(&entrant, game_id).unwrap();
+    }
+
+    /// Records the final score of the signer's game in tournament `tournament_id`, which must
+    /// still be played for the tournament and have ended, unless the tournament did.
+    async fn submit_result(&mut self, tournament_id: u32) {
+        let player = self.signer();
+        let now = self.runtime.system_time();
+        let tournament = self.load_open_tournament(tournament_id).await;
+        let game_id = tournament.entrants.get(&player).await.unwrap();
+        let game_id = game_id
+            .unwrap_or_else(|| panic!("Player {player} did not enter tournament {tournament_id}"));
+        assert!(
+            !tournament.results.contains_key(&player).await.unwrap(),
+            "Player {player} already submitted a result to tournament {tournament_id}"
+        );
+        let has_ended = tournament.status(now) == TournamentStatus::Ended;
+        let score = {
+            let game = self.state.games.try_load_entry(&game_id).await.unwrap();
+            let game = game.filter(|game| {
+                *game.tournament_id.get() == Some(tournament_id)
+                    && *game.owner.get() == Some(player)
+            });
+            let game = game.unwrap_or_else(|| {
+                panic!("Game {game_id} is no longer played for tournament {tournament_id}")
+            });
+            assert!(has_ended || game.is_ended(), "Game {game_id} is not over");
+            *game.score.get()
+        };
+        if has_ended {
+            self.end_tournament_game(tournament_id, player, game_id)
+                .await;
+            return;
+        }
+        let result = TournamentResult {
+            player,
+            game_id,
+            score,
+            submitted_at: now,
+        };
+        let tournament = self.load_open_tournament(tournament_id).await;
+        tournament.results.insert(&player, result).unwrap();
+    }
+
+    /// Closes tournament `tournament_id`, first ending the games still in flight if its end
+    /// time passed.
+    async fn close_tournament(&mut self, tournament_id: u32) {
+        let signer = self.signer();
+        let now = self.runtime.system_time();
+        let tournament = self.load_open_tournament(tournament_id).await;
+        assert!(
+            *tournament.organizer.get() == Some(signer),
+            "Only the organizer of tournament {tournament_id} can close it"
+        );
+        if tournament.status(now) == TournamentStatus::Ended {
+            self.end_tournament_games(tournament_id).await;
+        }
+        let tournament = self.load_open_tournament(tournament_id).await;
+        tournament.is_closed.set(true);
+    }
+
+    async fn set_tournament_end(&mut self, tournament_id: u32, ends_at: Timestamp) {
+        let signer = self.signer();
+        let now = self.runtime.system_time();
+        let tournament = self.load_open_tournament(tournament_id).await;
+        assert!(
+            *tournament.organizer.get() == Some(signer),
+            "Only the organizer of tournament {tournament_id} can set its end"
+        );
+        assert!(
+            tournament.status(now) == TournamentStatus::Open,
+            "Tournament {tournament_id} is over"
+        );
+        assert!(ends_at > now, "Tournaments end in the future");
+        tournament.ends_at.set(Some(ends_at));
+    }
+
+    /// Ends the games still in flight in tournament `tournament_id`, whose end time passed.
+    async fn end_tournament_games(&mut self, tournament_id: u32) {
+        let tournament = self.load_open_tournament(tournament_id).await;
+        let mut entrants = Vec::new();
+        tournament
+            .entrants
+            .for_each_index_value(|entrant, game_id| {
+                entrants.push((entrant, game_id));
+                Ok(())
+            })
+            .await
+            .unwrap();
+        for (entrant, game_id) in entrants {
+            self.end_tournament_game(tournament_id, entrant, game_id)
+                .await;
+        }
+    }
+
+    /// Ends game `game_id` of `entrant` in tournament `tournament_id`, whose end time passed,
+    /// with its current score. The score becomes the result of the entrant, submitted at the
+    /// end time, unless they submitted one. Games replaced since they were entered are left
+    /// alone.
+    async fn end_tournament_game(&mut self, tournament_id: u32, entrant: Owner, game_id: u16) {
+        let game = self.state.games.try_load_entry(&game_id).await.unwrap();
+        let Some(game) = game.filter(|game| {
+            *game.tournament_id.get() == Some(tournament_id) && *game.owner.get() == Some(entrant)
+        }) else {
+            return;
+        };
+        let score = *game.score.get();
+        drop(game);
+        self.finish_game(game_id).await;
+
+        let tournament = self.load_open_tournament(tournament_id).await;
+        if tournament.results.contains_key(&entrant).await.unwrap() {
+            return;
+        }
+        let ends_at = tournament.ends_at.get().expect("The tournament ended");
+        let result = TournamentResult {
+            player: entrant,
+            game_id,
+            score,
+            submitted_at: ends_at,
+        };
+        tournament.results.insert(&entrant, result).unwrap();
+    }
+
+    /// Starts the signer's game in the daily challenge of the current day, from the seed of
+    /// that day under the first free game id from the seed on. Daily games allow no undos.
+    async fn new_daily_game(&mut self) {
+        let player = self.signer();
+        let day = day_of(self.runtime.system_time());
+        let key = (day, player);
+        assert!(
+            !self.state.daily_scores.contains_key(&key).await.unwrap(),
+            "Player {player} already played the daily challenge of {}",
+            date_of(day)
+        );
+        let seed = daily_seed(day);
+        let game_id = self.free_game_id(seed).await;
+        self.create_game(
+            game_id,
+            seed,
+            Some(player),
+            None,
+            false,
+            GameMode::default(),
+            SUPPORTED_BOARD_SIZE,
+        )
+        .await;
+        let game = self.state.games.load_entry_mut(&game_id).await.unwrap();
+        game.daily_challenge.set(Some(day));
+        game.undos_left.set(0);
+        let entry = DailyScore {
+            player,
+            game_id,
+            score: 0,
+            ended_at: None,
+        };
+        self.state.daily_scores.insert(&key, entry).unwrap();
+    }
+
+    /// Records the final score of game `game_id`, which just ended, in the daily challenge it
+    /// is played for, if any.
+    async fn record_daily_score(&mut self, game_id: u16) {
+        let now = self.runtime.system_time();
+        let game = self.state.games.load_entry_mut(&game_id).await.unwrap();
+        let (Some(day), Some(player)) = (*game.daily_challenge.get(), *game.owner.get()) else {
+            return;
+        };
+        let score = *game.score.get();
+        let entry = self
+            .state
+            .daily_scores
+            .get_mut(&(day, player))
+            .await
+            .unwrap();
+        if let Some(entry) = entry.filter(|entry| entry.game_id == game_id) {
+            entry.score = score;
+            entry.ended_at = Some(now);
+        }
+    }
+
+    /// Plays `direction` at the signer's current step of tutorial `tutorial_index`, completing
+    /// the step if it is the expected move. Completing every tutorial unlocks
+    /// [`Achievement::TutorialComplete`].
+    async fn play_tutorial(&mut self, tutorial_index: u8, direction: Direction) {
+        let signer = self.signer();
+        let tutorial = TUTORIALS
+            .get(usize::from(tutorial_index))
+            .unwrap_or_else(|| panic!("Tutorial {tutorial_index} does not exist"));
+        let player = self.state.players.load_entry_mut(&signer).await.unwrap();
+        let completed = player
+            .tutorial_steps
+            .get(&tutorial_index)
+            .await
+            .unwrap()
+            .unwrap_or_default();
+        let Some(step) = tutorial.steps.get(usize::from(completed)) else {
+            panic!("Player {signer} already completed tutorial {tutorial_index}");
+        };
+        if step.expected != direction {
+            self.count_failure(OperationFailure::WrongTutorialMove)
+                .await;
+            return;
+        }
+        player
+            .tutorial_steps
+            .insert(&tutorial_index, completed + 1)
+            .unwrap();
+        Self::check_tutorials_complete(player).await;
+    }
+
+    /// Unlocks [`Achievement::TutorialComplete`] for `player` if they completed every
+    /// tutorial.
+    async fn check_tutorials_complete(player: &mut PlayerState) {
+        let mut all_complete = true;
+        for (index, tutorial) in (0..).zip(&TUTORIALS) {
+            let completed = player.tutorial_steps.get(&index).await.unwrap();
+            all_complete &= tutorial.is_complete(completed.unwrap_or_default());
+        }
+        if all_complete {
+            player
+                .achievements
+                .insert(&Achievement::TutorialComplete)
+                .unwrap();
+        }
+    }
+
+    /// Sells one `item` to the signer. The payment and the inventory update are part of the
+    /// same operation, so a failed payment leaves the inventory untouched and vice versa.
+    async fn buy_item(&mut self, item: String, max_price: Amount) {
+        let signer = self.signer();
+        let price = self.state.item_prices.get(&item).await.unwrap();
+        let price = price.unwrap_or_else(|| panic!("Item {item} is not for sale"));
+        assert!(
+            price <= max_price,
+            "Item {item} costs {price}, more than the maximum price of {max_price}"
+        );
+        self.pay_treasury(signer, price);
+        let player = self.state.players.load_entry_mut(&signer).await.unwrap();
+        let count = player.inventory.get_mut_or_default(&item).await.unwrap();
+        *count += 1;
+    }
+
+    /// Sells a season pass to the signer, extending the one they hold if it is still valid.
+    async fn buy_season_pass(&mut self, max_price: Amount) {
+        let signer = self.signer();
+        let now = self.runtime.system_time();
+        let settings = self.state.pass_settings.get().clone();
+        let settings = settings.expect("Season passes are not on sale");
+        let price = settings.price;
+        assert!(
+            price <= max_price,
+            "A season pass costs {price}, more than the maximum price of {max_price}"
+        );
+        self.pay_treasury(signer, price);
+
+        let player = self.state.players.load_entry_mut(&signer).await.unwrap();
+        let pass = SeasonPass::extend(*player.season_pass.get(), now, settings.duration_secs);
+        player.season_pass.set(Some(pass));
+        self.state.pass_sales.push(PassSale {
+            buyer: signer,
+            price,
+            sold_at: now,
+            expires_at: pass.expires_at,
+        });
+        let revenue = self.state.pass_revenue.get_mut();
+        *revenue = revenue.saturating_add(price);
+    }
+
+    fn message_signer(&mut self) -> Owner {
+        self.runtime
+            .authenticated_signer()
+            .expect("Matchmaking messages must be authenticated")
+    }
+
+    async fn has_free_game_slot(&mut self, owner: Owner) -> bool {
+        let limit = self
+            .state
+            .active_game_limit
+            .get()
+            .unwrap_or(DEFAULT_ACTIVE_GAME_LIMIT);
+        match self.state.players.try_load_entry(&owner).await.unwrap() {
+            Some(player) => *player.active_games.get() < limit,
+            None => true,
+        }
+    }
+
+    /// Sends a message to the matchmaking chain, on behalf of the signer.
+    fn send_to_matchmaker(&mut self, message: Message) {
+        let chain_id = self.runtime.application_id().creation.chain_id;
+        self.runtime
+            .prepare_message(message)
+            .with_authentication()
+            .send_to(chain_id);
+        self.count_message();
+    }
+
+    /// Queues the signer for a match in `lobby`, rated by their best score on this chain.
+    async fn join_match_queue(&mut self, lobby: String) {
+        let signer = self.signer();
+        assert!(
+            self.has_free_game_slot(signer).await,
+            "Player {signer} has no free game slot; end a game before joining the queue"
+        );
+        let rating = match self.state.players.try_load_entry(&signer).await.unwrap() {
+            Some(player) => player.stats.get().best_score,
+            None => 0,
+        };
+        let message = if lobby == DEFAULT_LOBBY {
+            Message::JoinMatchQueue { rating }
+        } else {
+            Message::JoinLobbyQueue { lobby, rating }
+        };
+        self.send_to_matchmaker(message);
+    }
+
+    /// Queues `owner` in `lobby`. Requests for unknown lobbies or outside the lobby's rating
+    /// bracket are dropped rather than failing the block receiving them.
+    async fn enqueue(&mut self, owner: Owner, chain_id: ChainId, lobby: String, rating: u64) {
+        if lobby != DEFAULT_LOBBY {
+            let settings = self.state.lobbies.get(&lobby).await.unwrap();
+            if !settings.is_some_and(|settings| settings.admits(rating)) {
+                return;
+            }
+        }
+        let now = self.runtime.system_time();
+        let queued = self.state.match_queue.get(&owner).await.unwrap();
+        // Joining the same lobby again updates the rating without losing the time waited.
+        let enqueued_at = queued
+            .filter(|entry| entry.lobby == lobby)
+            .map_or(now, |entry| entry.enqueued_at);
+        let entry = QueueEntry {
+            owner,
+            lobby,
+            chain_id,
+            rating,
+            enqueued_at,
+        };
+        self.state.match_queue.insert(&owner, entry).unwrap();
+        self.pair_queued_players().await;
+    }
+
+    /// Pairs the queued players of a lobby who accept each other's rating. The longest waiting
+    /// players are paired first, each with the compatible player of closest rating.
+    async fn pair_queued_players(&mut self) {
+        let now = self.runtime.system_time();
+        let mut waiting = Vec::new();
+        self.state
+            .match_queue
+            .for_each_index_value(|_, entry| {
+                waiting.push(entry);
+                Ok(())
+            })
+            .await
+            .unwrap();
+        waiting.sort_by_key(|entry| entry.enqueued_at);
+        while !waiting.is_empty() {
+            let entry = waiting.remove(0);
+            let opponent = waiting
+                .iter()
+                .enumerate()
+                .filter(|(_, other)| other.lobby == entry.lobby && entry.is_compatible(other, now))
+                .min_by_key(|(_, other)| entry.rating.abs_diff(other.rating))
+                .map(|(index, _)| index);
+            if let Some(index) = opponent {
+                let opponent = waiting.remove(index);
+                self.start_match(entry, opponent, now).await;
+            }
+        }
+    }
+
+    /// Records a match between two queued players and notifies the chains they joined from.
+    async fn start_match(&mut self, first: QueueEntry, second: QueueEntry, now: Timestamp) {
+        let match_id = *self.state.next_match_id.get();
+        self.state.next_match_id.set(match_id + 1);
+        let seed = self.draw_seed();
+        for (player, opponent) in [(&first, &second), (&second, &first)] {
+            self.state.match_queue.remove(&player.owner).unwrap();
+            let message = Message::MatchFound {
+                match_id,
+                owner: player.owner,
+                opponent: opponent.owner,
+                seed,
+            };
+            self.runtime
+                .prepare_message(message)
+                .send_to(player.chain_id);
+            self.count_message();
+        }
+        let lobby = first.lobby.clone();
+        let stats = self.state.lobby_stats.get(&lobby).await.unwrap();
+        let mut stats = stats.unwrap_or_default();
+        stats.matches += 1;
+        stats.total_wait_secs += first.waited_secs(now) + second.waited_secs(now);
+        self.state.lobby_stats.insert(&lobby, stats).unwrap();
+        let record = MatchRecord {
+            match_id,
+            lobby,
+            players: vec![first.owner, second.owner],
+            seed,
+            created_at: now,
+        };
+        self.state.matches.insert(&match_id, record).unwrap();
+    }
+
+    /// Starts the game of `owner` in match `match_id` from the board of `seed`, under the first
+    /// free game id from `seed` on. Players without a free game slot are only notified.
+    async fn start_match_game(&mut self, match_id: u32, owner: Owner, opponent: Owner, seed: u16) {
+        let mut game_id = None;
+        if self.has_free_game_slot(owner).await {
+            let id = self.free_game_id(seed).await;
+            self.create_game(
+                id,
+                seed,
+                Some(owner),
+                None,
+                false,
+                GameMode::default(),
+                SUPPORTED_BOARD_SIZE,
+            )
+            .await;
+            let game = self.state.games.load_entry_mut(&id).await.unwrap();
+            game.match_id.set(Some(match_id));
+            game_id = Some(id);
+        }
+        let notice = MatchNotice {
+            match_id,
+            opponent,
+            game_id,
+        };
+        self.state.match_notices.insert(&owner, notice).unwrap();
+    }
+
+    /// Sends the replay of the signer's game in match `match_id` to the matchmaking chain,
+    /// along with the dispute bond.
+    fn dispute_match(&mut self, match_id: u32, replay: Vec<u8>, claimed_score: u64) {
+        let bond = self.runtime.application_parameters().dispute_bond;
+        let bond_escrow = self.post_bond(bond);
+        self.send_to_matchmaker(Message::DisputeMatch {
+            match_id,
+            replay,
+            claimed_score,
+            bond_escrow,
+        });
+    }
+
+    /// Sends the challenge of the replay `player` submitted to the dispute of match
+    /// `match_id` to the matchmaking chain, along with the challenge bond.
+    fn challenge_dispute_result(&mut self, match_id: u32, player: Owner) {
+        let bond = self.runtime.application_parameters().dispute_challenge_bond;
+        let bond_escrow = self.post_bond(bond);
+        self.send_to_matchmaker(Message::ChallengeDisputeResult {
+            match_id,
+            player,
+            bond_escrow,
+        });
+    }
+
+    /// Appends a message of `sender` to the chat of match `match_id`. Messages from outside
+    /// the match or past [`MAX_CHAT_MESSAGES`] are dropped rather than failing the block
+    /// receiving them.
+    async fn post_chat(&mut self, sender: Owner, match_id: u32, payload: ChatPayload) {
+        let record = self.state.matches.get(&match_id).await.unwrap();
+        if !record.is_some_and(|record| record.players.contains(&sender)) {
+            return;
+        }
+        let mut chat = self
+            .state
+            .chats
+            .get(&match_id)
+            .await
+            .unwrap()
+            .unwrap_or_default();
+        if chat.len() >= MAX_CHAT_MESSAGES {
+            return;
+        }
+        chat.push(ChatMessage {
+            sender,
+            payload,
+            sent_at: self.runtime.system_time(),
+        });
+        self.state.chats.insert(&match_id, chat).unwrap();
+    }
+
+    /// Moves `bond` from the signer's balance of the bond token into an escrow of the token
+    /// application on this chain, which only this application settles, and returns its id.
+    /// Returns `None` for no bond.
+    fn post_bond(&mut self, bond: Amount) -> Option<u64> {
+        // Only signers dispute, even without a bond.
+        self.signer();
+        if bond == Amount::ZERO {
+            return None;
+        }
+        let (token_application, token) = self.bond_token();
+        let open = TokenOperation::OpenEscrow {
+            token,
+            amount: u128::from(bond),
+        };
+        // Authenticated, for the escrow to hold the signer's tokens for this application.
+        let response = self
+            .runtime
+            .call_application(true, token_application, &open);
+        match response {
+            TokenResponse::Escrow(escrow_id) => Some(escrow_id),
+            TokenResponse::Ok => panic!("The token application opened no escrow"),
+        }
+    }
+
+    /// Settles the bond escrow `escrow_id` of `chain_id` for `recipient`, burning the bond if
+    /// `None`.
+    fn settle_bond(&mut self, chain_id: ChainId, escrow_id: u64, recipient: Option<Account>) {
+        if chain_id != self.runtime.chain_id() {
+            let message = Message::SettleBond {
+                escrow_id,
+                recipient,
+            };
+            self.runtime.prepare_message(message).send_to(chain_id);
+            self.count_message();
+            return;
+        }
+        let (token_application, _) = self.bond_token();
+        let settle = TokenOperation::SettleEscrow {
+            escrow_id,
+            recipient,
+        };
+        self.runtime
+            .call_application(true, token_application, &settle);
+    }
+
+    /// Returns the token application holding the bonds, and the name of the bond token.
+    fn bond_token(&mut self) -> (ApplicationId<TokenAbi>, String) {
+        let bond_token = self
+            .runtime
+            .application_parameters()
+            .bond_token
+            .expect("Dispute bonds need a bond token");
+        let token_application = bond_token.token_application.with_abi::<TokenAbi>();
+        (token_application, bond_token.token)
+    }
+
+    /// Refunds the bond escrow `escrow_id`, if any, to `owner` on `chain_id`, the chain
+    /// holding it.
+    fn refund_bond(&mut self, chain_id: ChainId, owner: Owner, escrow_id: Option<u64>) {
+        if let Some(escrow_id) = escrow_id {
+            let recipient = Account {
+                chain_id,
+                owner: Some(owner),
+            };
+            self.settle_bond(chain_id, escrow_id, Some(recipient));
+        }
+    }
+
+    /// Records the replay `player` submitted from `origin` to the dispute of match `match_id`,
+    /// with the score they claim and the escrow holding their bond, opening the dispute if
+    /// needed and resolving it once every player submitted a replay. Submissions from outside
+    /// the match, repeated or past the deadline are dropped with their bond refunded, rather
+    /// than failing the block receiving them.
+    async fn submit_replay(
+        &mut self,
+        origin: ChainId,
+        player: Owner,
+        match_id: u32,
+        replay: Vec<u8>,
+        claimed_score: u64,
+        bond_escrow: Option<u64>,
+    ) {
+        let now = self.runtime.system_time();
+        let bond = self.runtime.application_parameters().dispute_bond;
+        let record = self.state.matches.get(&match_id).await.unwrap();
+        let Some(record) = record.filter(|record| record.players.contains(&player)) else {
+            self.refund_bond(origin, player, bond_escrow);
+            return;
+        };
+        let dispute = self.state.disputes.get(&match_id).await.unwrap();
+        let mut dispute = dispute.unwrap_or_else(|| MatchDispute {
+            match_id,
+            bond,
+            deadline: now.saturating_add_micros(DISPUTE_PERIOD_MICROS),
+            submissions: Vec::new(),
+            resolution: None,
+            challenges: Vec::new(),
+        });
+        if dispute.resolution.is_some() || dispute.has_submitted(&player) || now >= dispute.deadline
+        {
+            self.refund_bond(origin, player, bond_escrow);
+            return;
+        }
+        let outcome = replay_outcome(&replay, record.seed);
+        dispute.submissions.push(DisputeSubmission {
+            player,
+            chain_id: origin,
+            claimed_score,
+            verified_score: outcome.map(|outcome| outcome.score),
+            move_count: outcome.map_or(0, |outcome| outcome.move_index),
+            final_board: outcome.map_or(0, |outcome| outcome.board),
+            forged: false,
+            bond_escrow,
+        });
+        if dispute.submissions.len() == record.players.len() {
+            self.resolve_dispute(&mut dispute);
+        }
+        self.state.disputes.insert(&match_id, dispute).unwrap();
+    }
+
+    /// Resolves the dispute of match `match_id` once its deadline passed, without the replays
+    /// still missing.
+    async fn resolve_expired_dispute(&mut self, match_id: u32) {
+        let dispute = self.state.disputes.get(&match_id).await.unwrap();
+        let mut dispute = dispute.expect("Match is not disputed");
+        assert!(
+            dispute.resolution.is_none(),
+            "The dispute of match {match_id} is already resolved"
+        );
+        assert!(
+            self.runtime.system_time() >= dispute.deadline,
+            "The dispute of match {match_id} takes replays until its deadline"
+        );
+        self.resolve_dispute(&mut dispute);
+        self.state.disputes.insert(&match_id, dispute).unwrap();
+    }
+
+    /// Resolves `dispute`, paying out its bonds right away unless its resolution can be
+    /// challenged.
+    fn resolve_dispute(&mut self, dispute: &mut MatchDispute) {
+        let window = self
+            .runtime
+            .application_parameters()
+            .dispute_challenge_window_micros;
+        dispute.resolve(self.runtime.system_time(), window);
+        if window == 0 {
+            self.pay_out_dispute(dispute);
+        }
+    }
+
+    /// Records the challenge `challenger` sent from `origin` of the replay of `player` in the
+    /// dispute of match `match_id`, with the escrow holding its bond, and asks the chain the
+    /// game was played on to compare it to the replay. Challenges of replays the resolution
+    /// does not rely on, repeated or past the challenge window are dropped with their bond
+    /// refunded.
+    async fn receive_dispute_challenge(
+        &mut self,
+        origin: ChainId,
+        challenger: Owner,
+        match_id: u32,
+        player: Owner,
+        bond_escrow: Option<u64>,
+    ) {
+        let now = self.runtime.system_time();
+        let bond = self.runtime.application_parameters().dispute_challenge_bond;
+        let dispute = self.state.disputes.get(&match_id).await.unwrap();
+        let Some(mut dispute) = dispute else {
+            self.refund_bond(origin, challenger, bond_escrow);
+            return;
+        };
+        let is_open = dispute
+            .resolution
+            .as_ref()
+            .is_some_and(|resolution| !resolution.is_paid && now < resolution.payout_at);
+        let is_challenged = dispute
+            .challenges
+            .iter()
+            .any(|challenge| challenge.player == player);
+        let submission = dispute
+            .submissions
+            .iter()
+            .find(|submission| submission.player == player && submission.is_honest())
+            .filter(|_| is_open && !is_challenged)
+            .cloned();
+        let Some(submission) = submission else {
+            self.refund_bond(origin, challenger, bond_escrow);
+            return;
+        };
+        dispute.challenges.push(DisputeChallenge {
+            challenger,
+            chain_id: origin,
+            player,
+            bond,
+            bond_escrow,
+            status: DisputeChallengeStatus::Pending,
+        });
+        self.state.disputes.insert(&match_id, dispute).unwrap();
+        let message = Message::CheckMatchGame {
+            match_id,
+            player,
+            move_count: submission.move_count,
+            board: submission.final_board,
+        };
+        self.runtime
+            .prepare_message(message)
+            .send_to(submission.chain_id);
+        self.count_message();
+    }
+
+    /// Compares the game `player` played in match `match_id` on this chain to a replay
+    /// reaching `board` after `move_count` moves, answering the matchmaking chain.
+    async fn check_match_game(
+        &mut self,
+        origin: ChainId,
+        match_id: u32,
+        player: Owner,
+        move_count: u32,
+        board: u64,
+    ) {
+        let notice = self.state.match_notices.get(&player).await.unwrap();
+        let game_id = notice
+            .filter(|notice| notice.match_id == match_id)
+            .and_then(|notice| notice.game_id);
+        let mut matches = None;
+        if let Some(game_id) = game_id {
+            if let Some(game) = self.state.games.try_load_entry(&game_id).await.unwrap() {
+                // Games whose move log was not kept in full cannot be checked.
+                if let Some(turns) = game.move_log.read_all().await.unwrap() {
+                    let replay = Replay::from_turns(
+                        game_id,
+                        *game.seed.get(),
+                        *game.mode.get(),
+                        *game.sandbox.get(),
+                        &turns,
+                    );
+                    matches = Some(replay_board(&replay, move_count as usize) == Some(board));
+                }
+            }
+        }
+        let message = Message::MatchGameChecked {
+            match_id,
+            player,
+            matches,
+        };
+        self.runtime.prepare_message(message).send_to(origin);
+        self.count_message();
+    }
+
+    /// Settles the challenge of the replay of `player` in the dispute of match `match_id`
+    /// with the answer of `origin`, which must be the chain the replay was submitted from.
+    async fn match_game_checked(
+        &mut self,
+        origin: ChainId,
+        match_id: u32,
+        player: Owner,
+        matches: Option<bool>,
+    ) {
+        let Some(mut dispute) = self.state.disputes.get(&match_id).await.unwrap() else {
+            return;
+        };
+        let is_game_chain = dispute
+            .submissions
+            .iter()
+            .any(|submission| submission.player == player && submission.chain_id == origin);
+        let is_paid = dispute
+            .resolution
+            .as_ref()
+            .is_some_and(|resolution| resolution.is_paid);
+        if is_game_chain && !is_paid {
+            dispute.settle_challenge(player, matches);
+            self.state.disputes.insert(&match_id, dispute).unwrap();
+        }
+    }
+
+    /// Pays out the bonds of the dispute of match `match_id` once its challenge window
+    /// closed.
+    async fn finalize_dispute(&mut self, match_id: u32) {
+        let dispute = self.state.disputes.get(&match_id).await.unwrap();
+        let mut dispute = dispute.expect("Match is not disputed");
+        let resolution = dispute
+            .resolution
+            .as_ref()
+            .expect("The dispute is not resolved yet");
+        assert!(
+            !resolution.is_paid,
+            "The bonds of the dispute of match {match_id} are already paid out"
+        );
+        assert!(
+            self.runtime.system_time() >= resolution.payout_at,
+            "The resolution of the dispute of match {match_id} can still be challenged"
+        );
+        self.pay_out_dispute(&mut dispute);
+        self.state.disputes.insert(&match_id, dispute).unwrap();
+    }
+
+    /// Pays out the bonds of the resolved `dispute`, the challenges still pending being
+    /// inconclusive.
+    fn pay_out_dispute(&mut self, dispute: &mut MatchDispute) {
+        let pending = dispute
+            .challenges
+            .iter()
+            .filter(|challenge| challenge.status == DisputeChallengeStatus::Pending)
+            .map(|challenge| challenge.player)
+            .collect::<Vec<_>>();
+        for player in pending {
+            dispute.settle_challenge(player, None);
+        }
+        for (chain_id, escrow_id, recipient) in dispute.settlements() {
+            self.settle_bond(chain_id, escrow_id, recipient);
+        }
+        if let Some(resolution) = &mut dispute.resolution {
+            resolution.is_paid = true;
+        }
+    }
+
+    /// Returns the first game id from `seed` on that no game uses.
+    async fn free_game_id(&mut self, seed: u16) -> u16 {
+        let mut id = seed;
+        while self
+            .state
+            .games
+            .try_load_entry(&id)
+            .await
+            .unwrap()
+            .is_some()
+        {
+            id = id.wrapping_add(1);
+        }
+        id
+    }
+
+    /// Challenges the players of `opponent_chain`, starting the signer's game from a seed
+    /// drawn from the block context.
+    async fn challenge(&mut self, opponent_chain: ChainId) {
+        let challenger = self.signer();
+        let challenger_chain = self.runtime.chain_id();
+        assert!(
+            opponent_chain != challenger_chain,
+            "A chain cannot challenge itself"
+        );
+        let challenge_id = *self.state.next_challenge_id.get();
+        self.state.next_challenge_id.set(challenge_id + 1);
+        let seed = self.draw_seed();
+        let key = (challenger_chain, challenge_id);
+        let game_id = self.start_challenge_game(challenger, key, seed).await;
+        let record = ChallengeRecord {
+            challenge_id,
+            challenger_chain,
+            opponent_chain,
+            challenger,
+            opponent: None,
+            seed,
+            move_limit: CHALLENGE_MOVE_LIMIT,
+            status: ChallengeStatus::Pending,
+            game_id: Some(game_id),
+            challenger_score: None,
+            opponent_score: None,
+            winner: None,
+        };
+        self.state.challenges.insert(&key, record).unwrap();
+        self.runtime
+            .prepare_message(Message::Challenge { challenge_id, seed })
+            .with_authentication()
+            .send_to(opponent_chain);
+        self.count_message();
+    }
+
+    /// Records a challenge sent by the signer of the message from the `origin` chain, until
+    /// a player of this chain accepts it.
+    async fn receive_challenge(&mut self, origin: ChainId, challenge_id: u32, seed: u16) {
+        let challenger = self
+            .runtime
+            .authenticated_signer()
+            .expect("Challenges must be authenticated");
+        let record = ChallengeRecord {
+            challenge_id,
+            challenger_chain: origin,
+            opponent_chain: self.runtime.chain_id(),
+            challenger,
+            opponent: None,
+            seed,
+            move_limit: CHALLENGE_MOVE_LIMIT,
+            status: ChallengeStatus::Pending,
+            game_id: None,
+            challenger_score: None,
+            opponent_score: None,
+            winner: None,
+        };
+        let key = (origin, challenge_id);
+        self.state.challenges.insert(&key, record).unwrap();
+    }
+
+    /// Accepts a pending challenge received from `challenger_chain`, starting the signer's
+    /// game from the challenge seed.
+    async fn accept_challenge(&mut self, challenger_chain: ChainId, challenge_id: u32) {
+        let opponent = self.signer();
+        let key = (challenger_chain, challenge_id);
+        let mut record = self
+            .state
+            .challenges
+            .get(&key)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| panic!("Challenge {challenge_id} was not received"));
+        assert!(
+            record.challenger_chain != self.runtime.chain_id()
+                && record.status == ChallengeStatus::Pending,
+            "Challenge {challenge_id} cannot be accepted"
+        );
+        let game_id = self.start_challenge_game(opponent, key, record.seed).await;
+        record.opponent = Some(opponent);
+        record.status = ChallengeStatus::Active;
+        record.game_id = Some(game_id);
+        self.state.challenges.insert(&key, record).unwrap();
+        self.runtime
+            .prepare_message(Message::ChallengeAccepted { challenge_id })
+            .with_authentication()
+            .send_to(challenger_chain);
+        self.count_message();
+    }
+
+    /// Starts the game of `owner` for challenge `key` from the board of `seed`, under the first
+    /// free game id from `seed` on. Challenge games allow no undos.
+    async fn start_challenge_game(&mut self, owner: Owner, key: (ChainId, u32), seed: u16) -> u16 {
+        let game_id = self.free_game_id(seed).await;
+        self.create_game(
+            game_id,
+            seed,
+            Some(owner),
+            None,
+            false,
+            GameMode::default(),
+            SUPPORTED_BOARD_SIZE,
+        )
+        .await;
+        let game = self.state.games.load_entry_mut(&game_id).await.unwrap();
+        game.challenge.set(Some(key));
+        game.max_moves.set(Some(CHALLENGE_MOVE_LIMIT));
+        game.undos_left.set(0);
+        game_id
+    }
+
+    /// Activates a challenge sent from this chain, once the signer of the message accepted
+    /// it on the opponent chain.
+    async fn challenge_accepted(&mut self, origin: ChainId, challenge_id: u32) {
+        let opponent = self
+            .runtime
+            .authenticated_signer()
+            .expect("Challenges must be authenticated");
+        let key = (self.runtime.chain_id(), challenge_id);
+        let Some(mut record) = self.state.challenges.get(&key).await.unwrap() else {
+            return;
+        };
+        if record.opponent_chain != origin || record.status != ChallengeStatus::Pending {
+            return;
+        }
+        record.opponent = Some(opponent);
+        record.status = ChallengeStatus::Active;
+        self.settle_challenge(key, record);
+    }
+
+    /// Records the final score of the opponent's game in a challenge sent from this chain.
+    async fn challenge_result(&mut self, origin: ChainId, challenge_id: u32, score: u64) {
+        let key = (self.runtime.chain_id(), challenge_id);
+        let Some(mut record) = self.state.challenges.get(&key).await.unwrap() else {
+            return;
+        };
+        if record.opponent_chain != origin || record.status != ChallengeStatus::Active {
+            return;
+        }
+        record.opponent_score = Some(score);
+        self.settle_challenge(key, record);
+    }
+
+    /// Records the final score of game `game_id`, which just ended, if it was played for a
+    /// challenge. Opponents report it to the challenger's chain, which settles the challenge.
+    async fn end_challenge_game(&mut self, game_id: u16) {
+        let game = self.state.games.load_entry_mut(&game_id).await.unwrap();
+        let Some(key) = *game.challenge.get() else {
+            return;
+        };
+        let score = *game.score.get();
+        let Some(mut record) = self.state.challenges.get(&key).await.unwrap() else {
+            return;
+        };
+        let (challenger_chain, challenge_id) = key;
+        if challenger_chain == self.runtime.chain_id() {
+            record.challenger_score = Some(score);
+            self.settle_challenge(key, record);
+        } else {
+            record.opponent_score = Some(score);
+            self.state.challenges.insert(&key, record).unwrap();
+            self.runtime
+                .prepare_message(Message::ChallengeResult {
+                    challenge_id,
+                    score,
+                })
+                .send_to(challenger_chain);
+            self.count_message();
+        }
+    }
+
+    /// Stores a challenge sent from this chain, declaring its winner to the opponent chain if
+    /// both final scores are known.
+    fn settle_challenge(&mut self, key: (ChainId, u32), mut record: ChallengeRecord) {
+        if record.settle() {
+            let message = Message::ChallengeSettled {
+                challenge_id: key.1,
+                challenger_score: record.challenger_score.unwrap_or_default(),
+                opponent_score: record.opponent_score.unwrap_or_default(),
+                winner: record.winner,
+            };
+            self.runtime
+                .prepare_message(message)
+                .send_to(record.opponent_chain);
+            self.count_message();
+        }
+        self.state.challenges.insert(&key, record).unwrap();
+    }
+
+    /// Records a signed attestation after checking it against the state: attestations of
+    /// outdated scores, of past epochs or of other applications are rejected.
+    async fn submit_attestation(&mut self, attestation: ScoreAttestation, signature: Signature) {
+        let attester = self.state.attester.get().expect("No attester is set");
+        let owner = attestation.owner;
+        let attestation = SignedAttestation {
+            attestation,
+            signature,
+        };
+        assert!(
+            verify_attestation(&attestation, attester),
+            "The attestation is not signed by the attester"
+        );
+        let application_id = self.runtime.application_id().forget_abi();
+        assert!(
+            attestation.attestation.application_id == application_id,
+            "The attestation is for another application"
+        );
+        let epoch = attestation_epoch(self.runtime.system_time());
+        assert!(
+            attestation.attestation.epoch == epoch,
+            "Only attestations for the current epoch {epoch} are accepted"
+        );
+        let best_score = match self.state.players.try_load_entry(&owner).await.unwrap() {
+            Some(player) => player.stats.get().best_score,
+            None => 0,
+        };
+        assert!(
+            attestation.attestation.best_score == best_score,
+            "The best score of {owner} is {best_score}"
+        );
+        self.state.attestations.insert(&owner, attestation).unwrap();
+    }
+
+    /// Transfers `price` native tokens from `payer` to the treasury.
+    fn pay_treasury(&mut self, payer: Owner, price: Amount) {
+        if price > Amount::ZERO {
+            let treasury = self.state.treasury.get().expect("No treasury is set");
+            self.runtime.transfer(Some(payer), treasury, price);
+        }
+    }
+
+    /// Rejects games in a premium mode unless the signer holds a valid season pass.
+    async fn check_premium_mode(&mut self, mode: GameMode) {
+        let Some(settings) = self.state.pass_settings.get() else {
+            return;
+        };
+        if !settings.premium_modes.contains(&mode) {
+            return;
+        }
+        let now = self.runtime.system_time();
+        let pass = match self.runtime.authenticated_signer() {
+            Some(signer) => match self.state.players.try_load_entry(&signer).await.unwrap() {
+                Some(player) => *player.season_pass.get(),
+                None => None,
+            },
+            None => None,
+        };
+        assert!(
+            pass.is_some_and(|pass| pass.is_valid(now)),
+            "Games in {mode:?} mode require a season pass"
+        );
+    }
+
+    /// Moves `game_id` from the profile of its previous owner to the one of its new owner,
+    /// counting the tiles of its initial `board` as spawns. Sandbox games are listed in the
+    /// profile but do not count in its statistics, nor against its active game limit.
+    async fn assign_game(
+        &mut self,
+        game_id: u16,
+        previous_owner: Option<Owner>,
+        owner: Option<Owner>,
+        sandbox: bool,
+        board: u64,
+    ) {
+        if let Some(previous_owner) = previous_owner.filter(|previous| Some(*previous) != owner) {
+            let player = self.state.players.load_entry_mut(&previous_owner).await;
+            player.unwrap().games.remove(&game_id).unwrap();
+        }
+        if let Some(owner) = owner {
+            let limit = self
+                .state
+                .active_game_limit
+                .get()
+                .unwrap_or(DEFAULT_ACTIVE_GAME_LIMIT);
+            let player = self.state.players.load_entry_mut(&owner).await.unwrap();
+            player.games.insert(&game_id).unwrap();
+            if !sandbox {
+                let active_games = player.active_games.get_mut();
+                assert!(
+                    *active_games < limit,
+                    "Player {owner} already has {limit} active games; end one to start another"
+                );
+                *active_games += 1;
+                player.stats.get_mut().games_played += 1;
+                player.heatmap.get_mut().record_spawns(board);
+            }
+        }
+    }
+
+    async fn record_move(&mut self, owner: Owner, mode: GameMode, turn: &Turn) {
+        let player = self.state.players.load_entry_mut(&owner).await.unwrap();
+        let stats = player.stats.get_mut();
+        stats.moves_played += 1;
+        stats.best_score = stats.best_score.max(turn.score);
+        let mode_best = player.mode_bests.get_mut_or_default(&mode).await.unwrap();
+        *mode_best = (*mode_best).max(turn.score);
+        let heatmap = player.heatmap.get_mut();
+        heatmap.record_spawns(turn.spawned_tile);
+        if turn.is_ended {
+            heatmap.record_max_tile(turn.board);
+            self.release_game_slot(owner).await;
+        }
+    }
+
+    /// Frees the active game slot of `owner` taken by a game that ended or was replaced.
+    async fn release_game_slot(&mut self, owner: Owner) {
+        let player = self.state.players.load_entry_mut(&owner).await.unwrap();
+        let active_games = player.active_games.get_mut();
+        *active_games = active_games.saturating_sub(1);
+    }
+
+    /// Merges the `duplicate` account into the primary account it asked to be merged into,
+    /// once that account confirmed it: its games, sponsored games and rankings are
+    /// transferred, counters, heatmaps and inventories are summed, achievements are pooled and
+    /// the best records, the furthest tutorial steps and the longest season pass are kept. The merged account may not end up with more active
+    /// games than the limit.
+    async fn merge_accounts(&mut self, duplicate: Owner) {
+        let admin = self.signer();
+        assert!(
+            self.state.admins.get().contains(&admin),
+            "Only admins can approve account merges"
+        );
+        let now = self.runtime.system_time();
+        let limit = self
+            .state
+            .active_game_limit
+            .get()
+            .unwrap_or(DEFAULT_ACTIVE_GAME_LIMIT);
+
+        let player = self.state.players.load_entry_mut(&duplicate).await.unwrap();
+        let primary = player
+            .merge_request
+            .get()
+            .expect("This account did not ask to be merged");
+        assert!(
+            *player.merge_confirmed.get(),
+            "Account {primary} did not confirm the merge"
+        );
+        let duplicate_stats = player.stats.get().clone();
+        let duplicate_heatmap = player.heatmap.get().clone();
+        let duplicate_active_games = *player.active_games.get();
+        let duplicate_pass = *player.season_pass.get();
+        let mut duplicate_inventory = Vec::new();
+        player
+            .inventory
+            .for_each_index_value(|item, count| {
+                duplicate_inventory.push((item, count));
+                Ok(())
+            })
+            .await
+            .unwrap();
+        let mut duplicate_mode_bests = Vec::new();
+        player
+            .mode_bests
+            .for_each_index_value(|mode, score| {
+                duplicate_mode_bests.push((mode, score));
+                Ok(())
+            })
+            .await
+            .unwrap();
+        let mut duplicate_tutorial_steps = Vec::new();
+        player
+            .tutorial_steps
+            .for_each_index_value(|tutorial, completed| {
+                duplicate_tutorial_steps.push((tutorial, completed));
+                Ok(())
+            })
+            .await
+            .unwrap();
+        let duplicate_achievements = player.achievements.indices().await.unwrap();
+        let games = player.games.indices().await.unwrap();
+        let sponsorships = player.sponsorships.indices().await.unwrap();
+
+        for game_id in &games {
+            let game = self.state.games.load_entry_mut(game_id).await.unwrap();
+            game.owner.set(Some(primary));
+        }
+        for sponsorship_id in &sponsorships {
+            let sponsorship = self
+                .state
+                .sponsorships
+                .load_entry_mut(sponsorship_id)
+                .await
+                .unwrap();
+            let credits = sponsorship.credits.get(&duplicate).await.unwrap();
+            sponsorship.credits.remove(&duplicate).unwrap();
+            let primary_credits = sponsorship.credits.get_mut_or_default(&primary).await;
+            *primary_credits.unwrap() += credits.unwrap_or(0);
+        }
+        self.merge_rankings(duplicate, primary).await;
+
+        let player = self.state.players.load_entry_mut(&primary).await.unwrap();
+        let active_games = *player.active_games.get() + duplicate_active_games;
+        assert!(
+            active_games <= limit,
+            "Account {primary} would have {active_games} active games, more than the limit of \
+             {limit}"
+        );
+        player.active_games.set(active_games);
+        for game_id in &games {
+            player.games.insert(game_id).unwrap();
+        }
+        for sponsorship_id in &sponsorships {
+            player.sponsorships.insert(sponsorship_id).unwrap();
+        }
+        let primary_stats = player.stats.get().clone();
+        player.stats.set(primary_stats.merge(&duplicate_stats));
+        let heatmap = player.heatmap.get().merge(&duplicate_heatmap);
+        player.heatmap.set(heatmap);
+        for (item, count) in duplicate_inventory {
+            *player.inventory.get_mut_or_default(&item).await.unwrap() += count;
+        }
+        for (mode, score) in duplicate_mode_bests {
+            let mode_best = player.mode_bests.get_mut_or_default(&mode).await.unwrap();
+            *mode_best = (*mode_best).max(score);
+        }
+        for (tutorial, completed) in duplicate_tutorial_steps {
+            let steps = player
+                .tutorial_steps
+                .get_mut_or_default(&tutorial)
+                .await
+                .unwrap();
+            *steps = (*steps).max(completed);
+        }
+        for achievement in &duplicate_achievements {
+            player.achievements.insert(achievement).unwrap();
+        }
+        // Tutorials each account completed in part may add up to all of them.
+        Self::check_tutorials_complete(player).await;
+        let pass = (*player.season_pass.get()).max(duplicate_pass);
+        player.season_pass.set(pass);
+        self.state.players.remove_entry(&duplicate).unwrap();
+
+        self.state.account_merges.push(AccountMerge {
+            duplicate,
+            primary,
+            approved_by: admin,
+            merged_at: now,
+            games,
+            primary_stats,
+            duplicate_stats,
+        });
+    }
+
+    /// Moves the leaderboard, daily challenge and tournament entries of `duplicate` to
+    /// `primary`. Where both have an entry, the better one is kept.
+    async fn merge_rankings(&mut self, duplicate: Owner, primary: Owner) {
+        let mut entries = Vec::new();
+        self.state
+            .leaderboard
+            .for_each_index_value(|key, entry| {
+                if key.1 == duplicate {
+                    entries.push((key.0, entry));
+                }
+                Ok(())
+            })
+            .await
+            .unwrap();
+        for (namespace, mut entry) in entries {
+            let leaderboard = &mut self.state.leaderboard;
+            leaderboard.remove(&(namespace.clone(), duplicate)).unwrap();
+            let key = (namespace, primary);
+            let best = leaderboard.get(&key).await.unwrap();
+            if !best.is_some_and(|best| best.score >= entry.score) {
+                entry.owner = primary;
+                leaderboard.insert(&key, entry).unwrap();
+            }
+        }
+
+        let mut entries = Vec::new();
+        self.state
+            .efficiency_leaderboard
+            .for_each_index_value(|key, entry| {
+                if key.1 == duplicate {
+                    entries.push((key.0, entry));
+                }
+                Ok(())
+            })
+            .await
+            .unwrap();
+        for (namespace, mut entry) in entries {
+            let leaderboard = &mut self.state.efficiency_leaderboard;
+            leaderboard.remove(&(namespace.clone(), duplicate)).unwrap();
+            let key = (namespace, primary);
+            let best = leaderboard.get(&key).await.unwrap();
+            if !best.is_some_and(|best| best.efficiency >= entry.efficiency) {
+                entry.owner = primary;
+                leaderboard.insert(&key, entry).unwrap();
+            }
+        }
+
+        let mut entries = Vec::new();
+        self.state
+            .mode_leaderboards
+            .for_each_index_value(|key, entry| {
+                if key.4 == duplicate {
+                    entries.push((key, entry));
+                }
+                Ok(())
+            })
+            .await
+            .unwrap();
+        for (key, mut entry) in entries {
+            let leaderboard = &mut self.state.mode_leaderboards;
+            leaderboard.remove(&key).unwrap();
+            let (mode, window, period, namespace, _) = key;
+            let key = (mode, window, period, namespace, primary);
+            let best = leaderboard.get(&key).await.unwrap();
+            if !best.is_some_and(|best| best.score >= entry.score) {
+                entry.owner = primary;
+                leaderboard.insert(&key, entry).unwrap();
+            }
+        }
+
+        let mut entries = Vec::new();
+        self.state
+            .daily_scores
+            .for_each_index_value(|(day, player), entry| {
+                if player == duplicate {
+                    entries.push((day, entry));
+                }
+                Ok(())
+            })
+            .await
+            .unwrap();
+        for (day, mut entry) in entries {
+            self.state.daily_scores.remove(&(day, duplicate)).unwrap();
+            let key = (day, primary);
+            let best = self.state.daily_scores.get(&key).await.unwrap();
+            if !best.is_some_and(|best| best.score >= entry.score) {
+                entry.player = primary;
+                self.state.daily_scores.insert(&key, entry).unwrap();
+            }
+        }
+
+        for tournament_id in self.state.tournaments.indices().await.unwrap() {
+            let tournament = self
+                .state
+                .tournaments
+                .load_entry_mut(&tournament_id)
+                .await
+                .unwrap();
+            let Some(game_id) = tournament.entrants.get(&duplicate).await.unwrap() else {
+                continue;
+            };
+            let result = tournament.results.get(&duplicate).await.unwrap();
+            tournament.entrants.remove(&duplicate).unwrap();
+            tournament.results.remove(&duplicate).unwrap();
+            // An entry with a result beats one without.
+            if tournament.entrants.contains_key(&primary).await.unwrap() {
+                let best = tournament.results.get(&primary).await.unwrap();
+                if best.map(|best| best.score) >= result.as_ref().map(|result| result.score) {
+                    continue;
+                }
+            }
+            tournament.entrants.insert(&primary, game_id).unwrap();
+            if let Some(mut result) = result {
+                result.player = primary;
+                tournament.results.insert(&primary, result).unwrap();
+            }
+        }
     }
 }
@@ -0,0 +1,46 @@
+use crate::beacon_seed;
+use async_graphql::SimpleObject;
+use linera_sdk::base::{BcsHashable, CryptoHash, Owner, Timestamp};
+use serde::{Deserialize, Serialize};
+
+/// Length of a day of daily challenges, in microseconds. Days start at midnight UTC.
+pub const DAY_MICROS: u64 = 24 * 60 * 60 * 1_000_000;
+
+/// The game of a player in the daily challenge of a day.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct DailyScore {
+    pub player: Owner,
+    pub game_id: u16,
+    /// The score of the game, final once it ended.
+    pub score: u64,
+    /// When the game ended, `None` while it is played.
+    pub ended_at: Option<Timestamp>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct DailyInput {
+    day: u32,
+}
+
+impl BcsHashable for DailyInput {}
+
+/// Returns the day of `timestamp`, counted from the Unix epoch.
+///
+/// ```
+/// use game2048::{day_of, DAY_MICROS};
+/// use linera_sdk::base::Timestamp;
+///
+/// assert_eq!(day_of(Timestamp::from(DAY_MICROS - 1)), 0);
+/// assert_eq!(day_of(Timestamp::from(3 * DAY_MICROS)), 3);
+/// ```
+pub fn day_of(timestamp: Timestamp) -> u32 {
+    (timestamp.micros() / DAY_MICROS) as u32
+}
+
+/// Derives the seed every player of the daily challenge of `day` starts from.
+///
+/// Seeds are public from the start: anyone can compute the board of any day ahead of it,
+/// which is fine as long as everyone plays the same one.
+pub fn daily_seed(day: u32) -> u16 {
+    beacon_seed(&CryptoHash::new(&DailyInput { day }))
+}
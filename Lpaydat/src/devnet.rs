@@ -0,0 +1,257 @@
+//! Boots a local Linera network with both applications and some demo data, for frontend
+//! developers to work against with a single `cargo run --bin devnet`.
+//!
+//! The network is driven through the `linera` CLI, which must be on the `PATH` together with
+//! the `linera-server` and `linera-proxy` binaries, at the version of `linera-sdk` used here.
+//! The contract and service are built for `wasm32-unknown-unknown`, so that target must be
+//! installed. Everything started is stopped on Ctrl-C.
+
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+#[cfg(not(target_arch = "wasm32"))]
+use {
+    anyhow::{bail, Context},
+    clap::Parser,
+    serde_json::{json, Value},
+    std::{net::SocketAddr, path::Path, process::Stdio, time::Duration},
+    tokio::{
+        io::{AsyncBufReadExt, BufReader},
+        process::{Child, Command},
+    },
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Parser)]
+struct Options {
+    /// Number of demo player chains to open, each with a game in progress.
+    #[arg(long, default_value_t = 2)]
+    players: u16,
+    /// Port of the node service exposing the GraphQL endpoints.
+    #[arg(long, default_value_t = 8080)]
+    service_port: u16,
+    /// Address the token application's HTTP API listens on. Its default port is the node
+    /// service's, so it is moved elsewhere.
+    #[arg(long, default_value = "127.0.0.1:8090")]
+    token_app: SocketAddr,
+}
+
+/// The wallet and storage of the local network, passed to every `linera` command.
+#[cfg(not(target_arch = "wasm32"))]
+struct Linera {
+    wallet: String,
+    storage: String,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Linera {
+    /// Starts the network, returning it once its wallet is ready.
+    async fn net_up() -> anyhow::Result<(Linera, Child)> {
+        let mut network = Command::new("linera")
+            .args(["net", "up"])
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .context("Failed to run `linera`, is it on the PATH?")?;
+        let stdout = network.stdout.take().expect("Output is piped");
+        let mut lines = BufReader::new(stdout).lines();
+        let (mut wallet, mut storage) = (None, None);
+        while wallet.is_none() || storage.is_none() {
+            let Some(line) = lines.next_line().await? else {
+                bail!("`linera net up` exited before printing its wallet");
+            };
+            let exported = |name: &str| {
+                let value = line.strip_prefix(&format!("export {name}="))?;
+                Some(value.trim_matches('"').to_string())
+            };
+            wallet = wallet.or_else(|| exported("LINERA_WALLET"));
+            storage = storage.or_else(|| exported("LINERA_STORAGE"));
+        }
+        let linera = Linera {
+            wallet: wallet.expect("Wallet was printed"),
+            storage: storage.expect("Storage was printed"),
+        };
+        Ok((linera, network))
+    }
+
+    fn command(&self) -> Command {
+        let mut command = Command::new("linera");
+        command
+            .env("LINERA_WALLET", &self.wallet)
+            .env("LINERA_STORAGE", &self.storage)
+            .kill_on_drop(true);
+        command
+    }
+
+    /// Runs a `linera` command to completion, returning the last line it printed.
+    async fn run(&self, args: &[&str]) -> anyhow::Result<String> {
+        let output = self
+            .command()
+            .args(args)
+            .stderr(Stdio::inherit())
+            .output()
+            .await?;
+        if !output.status.success() {
+            bail!("`linera {}` failed", args.join(" "));
+        }
+        let stdout = String::from_utf8(output.stdout)?;
+        Ok(stdout.lines().last().unwrap_or_default().trim().to_string())
+    }
+}
+
+/// Sends a GraphQL request, failing on errors, and returns its data.
+#[cfg(not(target_arch = "wasm32"))]
+async fn graphql(client: &reqwest::Client, url: &str, query: &str) -> anyhow::Result<Value> {
+    let response: Value = client
+        .post(url)
+        .json(&json!({ "query": query }))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    if let Some(errors) = response.get("errors") {
+        bail!("GraphQL request to {url} failed: {errors}");
+    }
+    Ok(response["data"].clone())
+}
+
+/// Polls `url` until it answers, as services take a moment to start listening.
+#[cfg(not(target_arch = "wasm32"))]
+async fn wait_for(client: &reqwest::Client, url: &str) -> anyhow::Result<()> {
+    for _ in 0..60 {
+        if client.get(url).send().await.is_ok() {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    bail!("{url} did not come up")
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let options = Options::parse();
+    let client = reqwest::Client::new();
+
+    println!("Building the game application...");
+    let status = Command::new("cargo")
+        .args(["build", "--release", "--target", "wasm32-unknown-unknown"])
+        .args(["--bin", "game2048_contract", "--bin", "game2048_service"])
+        .status()
+        .await?;
+    if !status.success() {
+        bail!("Building the game application failed");
+    }
+    let wasm = Path::new("target/wasm32-unknown-unknown/release");
+    let contract = wasm.join("game2048_contract.wasm");
+    let service = wasm.join("game2048_service.wasm");
+
+    println!("Starting the network...");
+    let (linera, _network) = Linera::net_up().await?;
+    // Turns of every game are reported to the hub chain.
+    let hub = linera.run(&["open-chain"]).await?;
+    let parameters = json!({ "hub": hub }).to_string();
+    let application_id = linera
+        .run(&[
+            "publish-and-create",
+            contract.to_str().expect("Path is UTF-8"),
+            service.to_str().expect("Path is UTF-8"),
+            "--json-parameters",
+            &parameters,
+            "--json-argument",
+            "0",
+        ])
+        .await?;
+
+    let mut players = Vec::new();
+    for _ in 0..options.players {
+        let chain_id = linera.run(&["open-chain"]).await?;
+        linera
+            .run(&[
+                "request-application",
+                &application_id,
+                "--requester-chain-id",
+                &chain_id,
+            ])
+            .await?;
+        players.push(chain_id);
+    }
+    // The creator chain answers the requests, then the players register the application.
+    linera.run(&["process-inbox"]).await?;
+    for chain_id in &players {
+        linera.run(&["process-inbox", chain_id]).await?;
+    }
+
+    let port = options.service_port.to_string();
+    let _node_service = linera
+        .command()
+        .args(["service", "--port", &port])
+        .spawn()?;
+    let node_service = format!("http://localhost:{port}");
+    wait_for(&client, &node_service).await?;
+    let chains = graphql(&client, &node_service, "query { chains { default } }").await?;
+    let default_chain = chains["chains"]["default"]
+        .as_str()
+        .context("The wallet has no default chain")?
+        .to_string();
+
+    let game_app =
+        |chain_id: &str| format!("{node_service}/chains/{chain_id}/applications/{application_id}");
+    for (seed, chain_id) in (1..).zip(&players) {
+        let url = game_app(chain_id);
+        graphql(
+            &client,
+            &url,
+            &format!("mutation {{ newGame(seed: {seed}) }}"),
+        )
+        .await?;
+        for direction in ["Left", "Up", "Right"] {
+            let mutation =
+                format!("mutation {{ makeMove(gameId: {seed}, direction: \"{direction}\") }}");
+            graphql(&client, &url, &mutation).await?;
+        }
+    }
+
+    let manifest = Path::new("../winrhcp/linera_token_creation/Cargo.toml");
+    let _token_app = Command::new("cargo")
+        .args(["run", "--manifest-path"])
+        .arg(manifest)
+        .env("TOKEN_APP_ADDR", options.token_app.to_string())
+        .kill_on_drop(true)
+        .spawn()?;
+    let token_app = format!("http://{}", options.token_app);
+    wait_for(&client, &format!("{token_app}/tokens")).await?;
+    let player = players.first().unwrap_or(&default_chain);
+    for (name, symbol, creator) in [("Voyager", "VOY", &default_chain), ("Tile", "TILE", player)] {
+        client
+            .post(format!("{token_app}/create_token"))
+            .json(&json!({
+                "name": name,
+                "symbol": symbol,
+                "total_supply": 1_000_000,
+                "creator": creator,
+            }))
+            .send()
+            .await?
+            .error_for_status()?;
+    }
+
+    println!();
+    println!("Node service:        {node_service} (subscriptions on ws://localhost:{port}/ws)");
+    println!("Game application:    {application_id}");
+    println!("Creator chain:       {default_chain}");
+    println!("  GraphQL endpoint:  {}", game_app(&default_chain));
+    println!("Hub chain:           {hub}");
+    println!("  GraphQL endpoint:  {}", game_app(&hub));
+    for (seed, chain_id) in (1..).zip(&players) {
+        println!("Player chain:        {chain_id} (game {seed})");
+        println!("  GraphQL endpoint:  {}", game_app(chain_id));
+    }
+    println!("Token HTTP API:      {token_app} (tokens VOY and TILE)");
+    println!("Wallet:              LINERA_WALLET={}", linera.wallet);
+    println!("Storage:             LINERA_STORAGE={}", linera.storage);
+    println!();
+    println!("Press Ctrl-C to stop the network.");
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use async_graphql::scalar;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum Direction {
     Up,
     Down,
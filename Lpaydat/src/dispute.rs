@@ -0,0 +1,373 @@
+use async_graphql::{scalar, SimpleObject};
+use linera_sdk::base::{Account, Amount, ApplicationId, ChainId, Owner, Timestamp};
+use serde::{Deserialize, Serialize};
+
+use crate::{Checkpoint, Game, Replay};
+
+/// Time the players of a match have to submit their replays once its result is disputed:
+/// one day. Disputes are resolved without the missing replays afterwards.
+pub const DISPUTE_PERIOD_MICROS: u64 = 24 * 60 * 60 * 1_000_000;
+
+/// The token dispute bonds are posted in, held in escrows of the token application on the
+/// chains the bonds are posted from until the dispute is paid out.
+///
+/// The token application must be listed in the required applications of this one, and
+/// players must hold the token on the chains they dispute from.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BondToken {
+    pub token_application: ApplicationId,
+    /// The name of the token.
+    pub token: String,
+}
+
+/// A contested match result, settled by replaying the games of its players on the
+/// matchmaking chain instead of trusting the scores they claim.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct MatchDispute {
+    pub match_id: u32,
+    /// The bond each submitting player posted, refunded unless their replay does not back
+    /// their claim.
+    pub bond: Amount,
+    /// When the dispute is resolved without the replays not submitted by then.
+    pub deadline: Timestamp,
+    /// The replays submitted so far, in the order they were received.
+    pub submissions: Vec<DisputeSubmission>,
+    /// `None` until the dispute is resolved.
+    pub resolution: Option<DisputeResolution>,
+    /// The challenges of the replays the resolution relies on, in the order they were
+    /// received.
+    pub challenges: Vec<DisputeChallenge>,
+}
+
+/// The replay a player submitted to a dispute, as verified by the matchmaking chain.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct DisputeSubmission {
+    pub player: Owner,
+    /// The chain the replay was submitted from, where refunds and winnings are paid and the
+    /// game of the match was played.
+    pub chain_id: ChainId,
+    pub claimed_score: u64,
+    /// The score of the final board of the replay, `None` if the replay does not verify or
+    /// was not played from the seed of the match.
+    pub verified_score: Option<u64>,
+    /// The number of moves of the replay and its final board, `0` if it does not verify.
+    pub move_count: u32,
+    pub final_board: u64,
+    /// Whether a challenge proved that the replay is not the game played in the match.
+    pub forged: bool,
+    /// The escrow holding the bond on the chain the replay was submitted from, `None` for no
+    /// bond.
+    pub bond_escrow: Option<u64>,
+}
+
+/// How a dispute was resolved.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct DisputeResolution {
+    /// The honest player with the highest verified score, `None` for a draw or if no player
+    /// was honest.
+    pub winner: Option<Owner>,
+    /// The players whose replay did not back their claim, losing their bond.
+    pub slashed: Vec<Owner>,
+    /// Total bond of the slashed players, paid to the winner, or burned without one. The
+    /// bonds of forged replays go to their challengers instead.
+    pub forfeited: Amount,
+    pub resolved_at: Timestamp,
+    /// When the bonds are paid out, the resolution being open to challenges until then.
+    pub payout_at: Timestamp,
+    pub is_paid: bool,
+}
+
+/// The progress of the challenge of a replay.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum DisputeChallengeStatus {
+    /// Waiting for the chain the game was played on to compare it to the replay.
+    Pending,
+    /// The replay is not the game played in the match: the challenger gets their bond back
+    /// along with the bond of the challenged player.
+    Upheld,
+    /// The replay is the game played in the match: the challenged player gets the bond of
+    /// the challenger.
+    Rejected,
+    /// The game could not be compared to the replay, or not before the payout: the
+    /// challenger gets their bond back.
+    Inconclusive,
+}
+
+scalar!(DisputeChallengeStatus);
+
+/// A claim that the replay of `player` is not the game they played in the match, checked
+/// against the move log of the chain the game was played on.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct DisputeChallenge {
+    pub challenger: Owner,
+    /// The chain the challenge was sent from, where the challenger is paid.
+    pub chain_id: ChainId,
+    pub player: Owner,
+    pub bond: Amount,
+    /// The escrow holding the bond on the chain the challenge was sent from, `None` for no
+    /// bond.
+    pub bond_escrow: Option<u64>,
+    pub status: DisputeChallengeStatus,
+}
+
+impl DisputeSubmission {
+    /// Returns whether the replay verifies, backs the claimed score and was not proven
+    /// forged.
+    pub fn is_honest(&self) -> bool {
+        !self.forged && self.verified_score == Some(self.claimed_score)
+    }
+}
+
+impl MatchDispute {
+    /// Returns whether `player` submitted a replay.
+    pub fn has_submitted(&self, player: &Owner) -> bool {
+        self.submissions
+            .iter()
+            .any(|submission| submission.player == *player)
+    }
+
+    /// Resolves the dispute from the replays submitted so far, paying out once
+    /// `challenge_window_micros` passed. Players who did not submit a replay cannot win, but
+    /// lose no bond either.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::{DisputeSubmission, MatchDispute};
+    /// use linera_sdk::base::{Amount, ChainId, CryptoHash, Owner, Timestamp};
+    ///
+    /// let submission = |name, claimed_score, verified_score| DisputeSubmission {
+    ///     player: Owner(CryptoHash::test_hash(name)),
+    ///     chain_id: ChainId::root(0),
+    ///     claimed_score,
+    ///     verified_score,
+    ///     move_count: 0,
+    ///     final_board: 0,
+    ///     forged: false,
+    ///     bond_escrow: None,
+    /// };
+    /// let mut dispute = MatchDispute {
+    ///     match_id: 0,
+    ///     bond: Amount::from_tokens(2),
+    ///     deadline: Timestamp::from(0),
+    ///     // The higher claim is not backed by its replay.
+    ///     submissions: vec![
+    ///         submission("honest", 120, Some(120)),
+    ///         submission("liar", 900, Some(88)),
+    ///     ],
+    ///     resolution: None,
+    ///     challenges: vec![],
+    /// };
+    /// let resolution = dispute.resolve(Timestamp::from(1), 10);
+    /// assert_eq!(resolution.winner, Some(Owner(CryptoHash::test_hash("honest"))));
+    /// assert_eq!(resolution.slashed, [Owner(CryptoHash::test_hash("liar"))]);
+    /// assert_eq!(resolution.forfeited, Amount::from_tokens(2));
+    /// assert_eq!(resolution.payout_at, Timestamp::from(11));
+    /// ```
+    pub fn resolve(&mut self, now: Timestamp, challenge_window_micros: u64) -> DisputeResolution {
+        let honest = self
+            .submissions
+            .iter()
+            .filter(|submission| submission.is_honest())
+            .collect::<Vec<_>>();
+        let best = honest
+            .iter()
+            .map(|submission| submission.claimed_score)
+            .max();
+        let mut leaders = honest
+            .iter()
+            .filter(|submission| Some(submission.claimed_score) == best);
+        let winner = match (leaders.next(), leaders.next()) {
+            (Some(leader), None) => Some(leader.player),
+            _ => None,
+        };
+        let slashed = self
+            .submissions
+            .iter()
+            .filter(|submission| !submission.is_honest())
+            .map(|submission| submission.player)
+            .collect::<Vec<_>>();
+        let resolution = DisputeResolution {
+            winner,
+            forfeited: self.bond.saturating_mul(slashed.len() as u128),
+            slashed,
+            resolved_at: now,
+            payout_at: now.saturating_add_micros(challenge_window_micros),
+            is_paid: false,
+        };
+        self.resolution = Some(resolution.clone());
+        resolution
+    }
+
+    /// Records that the chain the game of `player` was played on compared it to their replay,
+    /// `matches` being `None` if it could not. A forged replay resolves the dispute again,
+    /// keeping the time of the payout.
+    pub fn settle_challenge(&mut self, player: Owner, matches: Option<bool>) {
+        let Some(challenge) = self
+            .challenges
+            .iter_mut()
+            .find(|challenge| challenge.player == player)
+        else {
+            return;
+        };
+        if challenge.status != DisputeChallengeStatus::Pending {
+            return;
+        }
+        challenge.status = match matches {
+            Some(true) => DisputeChallengeStatus::Rejected,
+            Some(false) => DisputeChallengeStatus::Upheld,
+            None => DisputeChallengeStatus::Inconclusive,
+        };
+        if matches != Some(false) {
+            return;
+        }
+        for submission in &mut self.submissions {
+            if submission.player == player {
+                submission.forged = true;
+            }
+        }
+        if let Some(resolution) = self.resolution.clone() {
+            let payout_at = resolution.payout_at;
+            let window = payout_at.delta_since(resolution.resolved_at).as_micros();
+            self.resolve(resolution.resolved_at, window);
+        }
+    }
+
+    /// Returns how to settle each bond escrow once the challenge window closed: the chain
+    /// holding the escrow, its id and the account paid, `None` to burn the bond. Challenges
+    /// still pending are inconclusive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::{
+    ///     DisputeChallenge, DisputeChallengeStatus, DisputeSubmission, MatchDispute,
+    /// };
+    /// use linera_sdk::base::{Account, Amount, ChainId, CryptoHash, Owner, Timestamp};
+    ///
+    /// let owner = |name| Owner(CryptoHash::test_hash(name));
+    /// let submission = |name, score, escrow| DisputeSubmission {
+    ///     player: owner(name),
+    ///     chain_id: ChainId::root(0),
+    ///     claimed_score: score,
+    ///     verified_score: Some(score),
+    ///     move_count: 10,
+    ///     final_board: 0x1234,
+    ///     forged: false,
+    ///     bond_escrow: Some(escrow),
+    /// };
+    /// let mut dispute = MatchDispute {
+    ///     match_id: 0,
+    ///     bond: Amount::from_tokens(2),
+    ///     deadline: Timestamp::from(0),
+    ///     submissions: vec![submission("loser", 120, 0), submission("forger", 900, 1)],
+    ///     resolution: None,
+    ///     challenges: vec![DisputeChallenge {
+    ///         challenger: owner("loser"),
+    ///         chain_id: ChainId::root(0),
+    ///         player: owner("forger"),
+    ///         bond: Amount::from_tokens(1),
+    ///         bond_escrow: Some(2),
+    ///         status: DisputeChallengeStatus::Pending,
+    ///     }],
+    /// };
+    /// assert_eq!(dispute.resolve(Timestamp::from(0), 10).winner, Some(owner("forger")));
+    ///
+    /// // The game of the forger differs from their replay.
+    /// dispute.settle_challenge(owner("forger"), Some(false));
+    /// assert_eq!(dispute.resolution.as_ref().unwrap().winner, Some(owner("loser")));
+    /// let loser = Some(Account {
+    ///     chain_id: ChainId::root(0),
+    ///     owner: Some(owner("loser")),
+    /// });
+    /// // The bonds of the winner, of the forger and of the challenger.
+    /// assert_eq!(
+    ///     dispute.settlements(),
+    ///     [
+    ///         (ChainId::root(0), 0, loser),
+    ///         (ChainId::root(0), 1, loser),
+    ///         (ChainId::root(0), 2, loser),
+    ///     ]
+    /// );
+    /// ```
+    pub fn settlements(&self) -> Vec<(ChainId, u64, Option<Account>)> {
+        let Some(resolution) = &self.resolution else {
+            return Vec::new();
+        };
+        let account = |chain_id, owner| {
+            Some(Account {
+                chain_id,
+                owner: Some(owner),
+            })
+        };
+        let submission_of = |player: Owner| {
+            self.submissions
+                .iter()
+                .find(|submission| submission.player == player)
+        };
+        let winner = resolution
+            .winner
+            .and_then(submission_of)
+            .and_then(|winner| account(winner.chain_id, winner.player));
+
+        let mut settlements = Vec::new();
+        for submission in &self.submissions {
+            let Some(escrow) = submission.bond_escrow else {
+                continue;
+            };
+            let upheld = self.challenges.iter().find(|challenge| {
+                challenge.player == submission.player
+                    && challenge.status == DisputeChallengeStatus::Upheld
+            });
+            let recipient = match upheld {
+                _ if submission.is_honest() => account(submission.chain_id, submission.player),
+                Some(challenge) => account(challenge.chain_id, challenge.challenger),
+                None => winner,
+            };
+            settlements.push((submission.chain_id, escrow, recipient));
+        }
+        for challenge in &self.challenges {
+            let Some(escrow) = challenge.bond_escrow else {
+                continue;
+            };
+            let refund = account(challenge.chain_id, challenge.challenger);
+            let recipient = match challenge.status {
+                DisputeChallengeStatus::Rejected => submission_of(challenge.player)
+                    .map_or(refund, |player| account(player.chain_id, player.player)),
+                DisputeChallengeStatus::Pending
+                | DisputeChallengeStatus::Upheld
+                | DisputeChallengeStatus::Inconclusive => refund,
+            };
+            settlements.push((challenge.chain_id, escrow, recipient));
+        }
+        settlements
+    }
+}
+
+/// Replays the encoded replay `bytes`, returning the number of its moves, its final board and
+/// the score of that board, or `None` if it does not verify or its game was not started from
+/// `seed`.
+///
+/// Replays are checked move by move, but the tiles they spawn are not drawn again: spawns
+/// depend on the blocks the moves were made in, which replays do not record. Challenges
+/// compare replays to the move logs of the chains the games were played on instead.
+pub fn replay_outcome(bytes: &[u8], seed: u16) -> Option<Checkpoint> {
+    let replay = Replay::decode(bytes).ok()?;
+    if replay.seed != seed || replay.verify().is_err() {
+        return None;
+    }
+    let board = replay_board(&replay, replay.moves.len())?;
+    Some(Checkpoint {
+        move_index: replay.moves.len() as u32,
+        board,
+        score: Game::score(board),
+    })
+}
+
+/// Returns the board of `replay` after its first `move_count` moves, `None` if it has fewer.
+pub fn replay_board(replay: &Replay, move_count: usize) -> Option<u64> {
+    let moves = replay.moves.get(..move_count)?;
+    Some(moves.iter().fold(replay.initial_board, |board, step| {
+        Game::move_board(board, step.direction.clone()) | step.spawned_tile
+    }))
+}
@@ -0,0 +1,237 @@
+use std::fmt;
+
+use crate::{Direction, Operation};
+use linera_sdk::bcs;
+use serde::{Deserialize, Serialize};
+
+/// The operation encoding version produced by current clients.
+pub const CURRENT_OPERATION_VERSION: u8 = 5;
+
+/// An operation tagged with the version of the encoding of its `payload`.
+///
+/// Every supported version has its own decoder, so the contract keeps accepting operations
+/// from clients built against older versions of the `Operation` enum.
+///
+/// | Version | Payload                                                      |
+/// |---------|--------------------------------------------------------------|
+/// | 1       | `v1::Operation`, the original game operations                 |
+/// | 2       | `v2::Operation`, adding community namespaces and management   |
+/// | 3       | `v3::Operation`, adding sandbox games                         |
+/// | 4       | `v4::Operation`, adding move limits                           |
+/// | 5       | `Operation`, adding board sizes                               |
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct OperationEnvelope {
+    pub version: u8,
+    pub payload: Vec<u8>,
+}
+
+impl OperationEnvelope {
+    /// Wraps `operation` using the current encoding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::{Operation, OperationEnvelope};
+    ///
+    /// let operation = Operation::NewGame {
+    ///     seed: 7,
+    ///     namespace: None,
+    ///     sandbox: false,
+    ///     max_moves: Some(100),
+    ///     board_size: None,
+    /// };
+    /// let envelope = OperationEnvelope::new(&operation);
+    /// assert_eq!(envelope.decode().unwrap(), operation);
+    /// ```
+    pub fn new(operation: &Operation) -> Self {
+        OperationEnvelope {
+            version: CURRENT_OPERATION_VERSION,
+            payload: bcs::to_bytes(operation).expect("Operations are serializable"),
+        }
+    }
+
+    /// Decodes the payload into the current `Operation`, upgrading older versions.
+    pub fn decode(&self) -> Result<Operation, OperationDecodeError> {
+        match self.version {
+            1 => Ok(bcs::from_bytes::<v1::Operation>(&self.payload)?.into()),
+            // Only `NewGame` changed since version 2, every other variant decodes as is.
+            2 if self.payload.first() == Some(&0) => {
+                Ok(bcs::from_bytes::<v2::Operation>(&self.payload)?.into())
+            }
+            3 if self.payload.first() == Some(&0) => {
+                Ok(bcs::from_bytes::<v3::Operation>(&self.payload)?.into())
+            }
+            4 if self.payload.first() == Some(&0) => {
+                Ok(bcs::from_bytes::<v4::Operation>(&self.payload)?.into())
+            }
+            2..=5 => Ok(bcs::from_bytes::<Operation>(&self.payload)?),
+            version => Err(OperationDecodeError::UnsupportedVersion(version)),
+        }
+    }
+}
+
+/// Why an `OperationEnvelope` could not be decoded.
+#[derive(Debug)]
+pub enum OperationDecodeError {
+    UnsupportedVersion(u8),
+    MalformedPayload(bcs::Error),
+}
+
+impl fmt::Display for OperationDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OperationDecodeError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported operation version {version}")
+            }
+            OperationDecodeError::MalformedPayload(error) => {
+                write!(f, "Malformed operation payload: {error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for OperationDecodeError {}
+
+impl From<bcs::Error> for OperationDecodeError {
+    fn from(error: bcs::Error) -> Self {
+        OperationDecodeError::MalformedPayload(error)
+    }
+}
+
+/// Operations as encoded by version 1 clients.
+pub mod v1 {
+    use super::*;
+
+    /// The original game operations, all played in the global namespace.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub enum Operation {
+        NewGame { seed: u16 },
+        EndGame { game_id: u16 },
+        MakeMove { game_id: u16, direction: Direction },
+    }
+
+    impl From<Operation> for crate::Operation {
+        fn from(operation: Operation) -> Self {
+            match operation {
+                Operation::NewGame { seed } => crate::Operation::NewGame {
+                    seed,
+                    namespace: None,
+                    sandbox: false,
+                    max_moves: None,
+                    board_size: None,
+                },
+                Operation::EndGame { game_id } => crate::Operation::EndGame {
+                    game_id,
+                    namespace: None,
+                },
+                Operation::MakeMove { game_id, direction } => crate::Operation::MakeMove {
+                    game_id,
+                    direction,
+                    namespace: None,
+                },
+            }
+        }
+    }
+}
+
+/// Operations as encoded by version 2 clients.
+pub mod v2 {
+    use super::*;
+    use crate::CommunityId;
+
+    /// The version 2 encoding of `NewGame`, before sandbox games. Its other operations are
+    /// encoded like the current ones.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub enum Operation {
+        NewGame {
+            seed: u16,
+            namespace: Option<CommunityId>,
+        },
+    }
+
+    impl From<Operation> for crate::Operation {
+        fn from(operation: Operation) -> Self {
+            match operation {
+                Operation::NewGame { seed, namespace } => crate::Operation::NewGame {
+                    seed,
+                    namespace,
+                    sandbox: false,
+                    max_moves: None,
+                    board_size: None,
+                },
+            }
+        }
+    }
+}
+
+/// Operations as encoded by version 3 clients.
+pub mod v3 {
+    use super::*;
+    use crate::CommunityId;
+
+    /// The version 3 encoding of `NewGame`, before move limits. Its other operations are
+    /// encoded like the current ones.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub enum Operation {
+        NewGame {
+            seed: u16,
+            namespace: Option<CommunityId>,
+            sandbox: bool,
+        },
+    }
+
+    impl From<Operation> for crate::Operation {
+        fn from(operation: Operation) -> Self {
+            match operation {
+                Operation::NewGame {
+                    seed,
+                    namespace,
+                    sandbox,
+                } => crate::Operation::NewGame {
+                    seed,
+                    namespace,
+                    sandbox,
+                    max_moves: None,
+                    board_size: None,
+                },
+            }
+        }
+    }
+}
+
+/// Operations as encoded by version 4 clients.
+pub mod v4 {
+    use super::*;
+    use crate::CommunityId;
+
+    /// The version 4 encoding of `NewGame`, before board sizes. Its other operations are
+    /// encoded like the current ones.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub enum Operation {
+        NewGame {
+            seed: u16,
+            namespace: Option<CommunityId>,
+            sandbox: bool,
+            max_moves: Option<u32>,
+        },
+    }
+
+    impl From<Operation> for crate::Operation {
+        fn from(operation: Operation) -> Self {
+            match operation {
+                Operation::NewGame {
+                    seed,
+                    namespace,
+                    sandbox,
+                    max_moves,
+                } => crate::Operation::NewGame {
+                    seed,
+                    namespace,
+                    sandbox,
+                    max_moves,
+                    board_size: None,
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{Direction, GameMode};
+use async_graphql::scalar;
+use linera_sdk::base::{Owner, StreamName};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Name of the event stream the activity of games is emitted to, see [`GameEvent`].
+pub const GAME_EVENTS_STREAM: &[u8] = b"games";
+
+/// Activity of the games of a chain, emitted to its [`GAME_EVENTS_STREAM`] event stream.
+///
+/// Unlike the turns sent to spectators, events have no destination: indexers and other
+/// applications read them, BCS-encoded, from the blocks of the chain. Clients of the service
+/// list them from an [`EventCursor`] instead.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum GameEvent {
+    /// Game `game_id` was created from `seed`, replacing any previous game with that id.
+    GameCreated {
+        game_id: u16,
+        owner: Option<Owner>,
+        seed: u16,
+        mode: GameMode,
+        /// The initial board, `0` for boards larger than 4x4.
+        board: u64,
+    },
+    /// A move was played in game `game_id`, its `move_count`th.
+    MoveMade {
+        game_id: u16,
+        move_count: u32,
+        direction: Direction,
+        /// The board after the move, `0` for boards larger than 4x4.
+        board: u64,
+        score: u64,
+    },
+    /// Game `game_id` ended after `move_count` moves.
+    GameEnded {
+        game_id: u16,
+        owner: Option<Owner>,
+        score: u64,
+        move_count: u32,
+    },
+}
+
+impl GameEvent {
+    /// Returns the name of the stream game events are emitted to.
+    pub fn stream_name() -> StreamName {
+        StreamName(GAME_EVENTS_STREAM.to_vec())
+    }
+
+    /// Returns the key of the event in its stream: the big-endian id of its game, so that the
+    /// events of a game share their key.
+    ///
+    /// ```
+    /// use game2048::GameEvent;
+    ///
+    /// let event = GameEvent::GameEnded {
+    ///     game_id: 0x0102,
+    ///     owner: None,
+    ///     score: 16,
+    ///     move_count: 3,
+    /// };
+    /// assert_eq!(event.key(), [1, 2]);
+    /// ```
+    pub fn key(&self) -> [u8; 2] {
+        let (GameEvent::GameCreated { game_id, .. }
+        | GameEvent::MoveMade { game_id, .. }
+        | GameEvent::GameEnded { game_id, .. }) = self;
+        game_id.to_be_bytes()
+    }
+}
+
+scalar!(GameEvent);
+
+/// A position in the game events of a chain, between two events. Clients get one with every
+/// page of events, and resume from it to fetch exactly the events emitted since.
+///
+/// Cursors are handed out as opaque strings:
+///
+/// ```
+/// use game2048::EventCursor;
+///
+/// let cursor = EventCursor::after(41);
+/// assert_eq!(cursor.first_index(), 42);
+/// assert_eq!(EventCursor::parse(&cursor.to_string()), Some(cursor));
+/// assert_eq!(EventCursor::parse("42"), None);
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub struct EventCursor(u64);
+
+impl EventCursor {
+    /// The cursor before the first event of the chain.
+    pub const START: EventCursor = EventCursor(0);
+
+    /// Returns the cursor right after the event at `index`.
+    pub fn after(index: u64) -> Self {
+        EventCursor(index + 1)
+    }
+
+    /// Returns the index of the first event after the cursor.
+    pub fn first_index(self) -> u64 {
+        self.0
+    }
+
+    /// Parses a cursor formatted by [`EventCursor`]'s `Display` implementation.
+    pub fn parse(cursor: &str) -> Option<Self> {
+        if cursor.len() != 16 {
+            return None;
+        }
+        u64::from_str_radix(cursor, 16).ok().map(EventCursor)
+    }
+}
+
+impl fmt::Display for EventCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{CommunityId, GameMode};
+use async_graphql::{scalar, SimpleObject};
+use linera_sdk::{
+    base::{BcsHashable, CryptoHash},
+    serde_json,
+};
+use serde::{Deserialize, Serialize};
+
+/// Formats the service can export rankings in.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+scalar!(ExportFormat);
+
+/// A ranked game result, as exported for campaign reporting.
+#[derive(Clone, Debug, Deserialize, Serialize, SimpleObject)]
+pub struct ExportRow {
+    pub rank: u32,
+    pub game_id: u16,
+    pub namespace: Option<CommunityId>,
+    pub mode: GameMode,
+    pub score: u64,
+    pub move_count: u32,
+    pub is_ended: bool,
+}
+
+impl BcsHashable for ExportRow {}
+
+/// A page of exported rows, hashed as a whole so tampering with any row, its order or the
+/// page boundaries changes the bundle hash.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ExportBundle {
+    pub offset: u32,
+    pub rows: Vec<ExportRow>,
+}
+
+impl BcsHashable for ExportBundle {}
+
+impl ExportBundle {
+    /// Returns the hash of every row, in order.
+    pub fn row_hashes(&self) -> Vec<CryptoHash> {
+        self.rows.iter().map(CryptoHash::new).collect()
+    }
+
+    /// Returns the hash committing to the whole page.
+    pub fn hash(&self) -> CryptoHash {
+        CryptoHash::new(self)
+    }
+
+    /// Renders the rows in the requested `format`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::{ExportBundle, ExportFormat, ExportRow, GameMode};
+    ///
+    /// let bundle = ExportBundle {
+    ///     offset: 0,
+    ///     rows: vec![ExportRow {
+    ///         rank: 1,
+    ///         game_id: 7,
+    ///         namespace: Some("voyager, x".to_string()),
+    ///         mode: GameMode::Classic,
+    ///         score: 2048,
+    ///         move_count: 120,
+    ///         is_ended: true,
+    ///     }],
+    /// };
+    ///
+    /// assert_eq!(
+    ///     bundle.render(ExportFormat::Csv),
+    ///     "rank,game_id,namespace,mode,score,move_count,is_ended\n\
+    ///      1,7,\"voyager, x\",Classic,2048,120,true\n"
+    /// );
+    /// ```
+    pub fn render(&self, format: ExportFormat) -> String {
+        match format {
+            ExportFormat::Csv => {
+                let mut csv =
+                    String::from("rank,game_id,namespace,mode,score,move_count,is_ended\n");
+                for row in &self.rows {
+                    csv.push_str(&format!(
+                        "{},{},{},{:?},{},{},{}\n",
+                        row.rank,
+                        row.game_id,
+                        csv_field(row.namespace.as_deref().unwrap_or_default()),
+                        row.mode,
+                        row.score,
+                        row.move_count,
+                        row.is_ended,
+                    ));
+                }
+                csv
+            }
+            ExportFormat::Json => {
+                serde_json::to_string(&self.rows).expect("Rows are serializable to JSON")
+            }
+        }
+    }
+}
+
+/// Quotes a CSV field when it contains separators, quotes or line breaks.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+/// What happens to a turn reported to the hub, see [`MessageFault`].
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum FaultKind {
+    /// The turn is not sent.
+    Drop,
+    /// The turn is sent twice.
+    Duplicate,
+    /// The turn is held back and sent after the next turn of the game.
+    Delay,
+}
+
+/// A fault injected in the delivery of a turn to the hub, so that integration tests can check
+/// the hub reconciles its copy of the games with the chains they are played on.
+///
+/// Only meant for tests: deployed applications are created without faults. The hub recovers
+/// a turn that was dropped or delayed once a later turn of the game reaches it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MessageFault {
+    pub game_id: u16,
+    /// Index of the turn within the game, `0` being the creation of the game.
+    pub move_index: u32,
+    pub kind: FaultKind,
+}
+
+/// Returns the fault injected in turn `move_index` of game `game_id`, if any.
+///
+/// ```
+/// use game2048::{fault_for, FaultKind, MessageFault};
+///
+/// let faults = [MessageFault { game_id: 5, move_index: 2, kind: FaultKind::Drop }];
+/// assert_eq!(fault_for(&faults, 5, 2), Some(FaultKind::Drop));
+/// assert_eq!(fault_for(&faults, 5, 3), None);
+/// assert_eq!(fault_for(&faults, 6, 2), None);
+/// ```
+pub fn fault_for(faults: &[MessageFault], game_id: u16, move_index: u32) -> Option<FaultKind> {
+    faults
+        .iter()
+        .find(|fault| fault.game_id == game_id && fault.move_index == move_index)
+        .map(|fault| fault.kind)
+}
@@ -0,0 +1,172 @@
+use crate::Operation;
+use serde::{Deserialize, Serialize};
+
+/// The optional subsystems a deployment enables, as a set of bits set in the application
+/// parameters. Operations of a disabled subsystem are rejected; games are always playable.
+///
+/// Serialized as the integer of its bits, every subsystem being enabled by default.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FeatureFlags(pub u32);
+
+impl FeatureFlags {
+    /// Communities, their namespaces and the sponsorship of their game fees.
+    pub const COMMUNITIES: Self = FeatureFlags(1 << 0);
+    /// Randomness beacon rounds and the games seeded from them.
+    pub const BEACON: Self = FeatureFlags(1 << 1);
+    /// Tournament templates and their join codes.
+    pub const TEMPLATES: Self = FeatureFlags(1 << 2);
+    /// The item shop, paid in native tokens.
+    pub const SHOP: Self = FeatureFlags(1 << 3);
+    /// Season passes and the rewards they multiply.
+    pub const SEASON_PASS: Self = FeatureFlags(1 << 4);
+    /// Score attestations.
+    pub const ATTESTATIONS: Self = FeatureFlags(1 << 5);
+    /// The matchmaking queue, its lobbies, the disputes of match results and match chats.
+    pub const MATCHMAKING: Self = FeatureFlags(1 << 6);
+    /// Head-to-head challenges between chains.
+    pub const CHALLENGES: Self = FeatureFlags(1 << 7);
+    /// Spectating the games of other chains.
+    pub const SPECTATING: Self = FeatureFlags(1 << 8);
+    /// Tournaments ranking the games their entrants play from the same seed.
+    pub const TOURNAMENTS: Self = FeatureFlags(1 << 9);
+    /// Daily challenges, where every player gets the same board each day.
+    pub const DAILY_CHALLENGE: Self = FeatureFlags(1 << 10);
+
+    /// No optional subsystem: only games.
+    pub const NONE: Self = FeatureFlags(0);
+    /// Every subsystem.
+    pub const ALL: Self = FeatureFlags((1 << 11) - 1);
+
+    /// Every subsystem with its name.
+    const NAMED: [(Self, &'static str); 11] = [
+        (Self::COMMUNITIES, "Communities"),
+        (Self::BEACON, "Beacon"),
+        (Self::TEMPLATES, "Templates"),
+        (Self::SHOP, "Shop"),
+        (Self::SEASON_PASS, "SeasonPass"),
+        (Self::ATTESTATIONS, "Attestations"),
+        (Self::MATCHMAKING, "Matchmaking"),
+        (Self::CHALLENGES, "Challenges"),
+        (Self::SPECTATING, "Spectating"),
+        (Self::TOURNAMENTS, "Tournaments"),
+        (Self::DAILY_CHALLENGE, "DailyChallenge"),
+    ];
+
+    /// Returns whether every subsystem of `other` is enabled.
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns these flags with the subsystems of `other` enabled too.
+    pub fn with(self, other: Self) -> Self {
+        FeatureFlags(self.0 | other.0)
+    }
+
+    /// Returns these flags with the subsystems of `other` disabled.
+    pub fn without(self, other: Self) -> Self {
+        FeatureFlags(self.0 & !other.0)
+    }
+
+    /// Returns the names of the enabled subsystems.
+    ///
+    /// ```
+    /// use game2048::FeatureFlags;
+    ///
+    /// let flags = FeatureFlags::NONE.with(FeatureFlags::SHOP).with(FeatureFlags::CHALLENGES);
+    /// assert_eq!(flags.names(), ["Shop", "Challenges"]);
+    /// assert!(flags.contains(FeatureFlags::SHOP));
+    /// assert!(!flags.without(FeatureFlags::SHOP).contains(FeatureFlags::SHOP));
+    /// assert_eq!(FeatureFlags::ALL.names().len(), 11);
+    /// ```
+    pub fn names(self) -> Vec<&'static str> {
+        Self::NAMED
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| *name)
+            .collect()
+    }
+}
+
+impl Default for FeatureFlags {
+    fn default() -> Self {
+        FeatureFlags::ALL
+    }
+}
+
+impl Operation {
+    /// Returns the subsystem the operation belongs to, `None` for the operations every
+    /// deployment supports.
+    ///
+    /// ```
+    /// use game2048::{FeatureFlags, Operation};
+    ///
+    /// assert_eq!(Operation::JoinMatchQueue.feature(), Some(FeatureFlags::MATCHMAKING));
+    /// assert_eq!(Operation::EndGame { game_id: 1, namespace: None }.feature(), None);
+    /// ```
+    pub fn feature(&self) -> Option<FeatureFlags> {
+        FeatureFlags::of_operation(self.name())
+    }
+}
+
+impl FeatureFlags {
+    /// Returns the subsystem of the operation named `name`, as named by [`Operation::name`],
+    /// `None` for the operations every deployment supports and for unknown names.
+    ///
+    /// ```
+    /// use game2048::FeatureFlags;
+    ///
+    /// assert_eq!(FeatureFlags::of_operation("BuyItem"), Some(FeatureFlags::SHOP));
+    /// assert_eq!(FeatureFlags::of_operation("MakeMove"), None);
+    /// ```
+    pub fn of_operation(name: &str) -> Option<Self> {
+        match name {
+            "CreateCommunity"
+            | "ConfigureCommunity"
+            | "JoinCommunity"
+            | "LeaveCommunity"
+            | "SponsorGames"
+            | "WithdrawSponsorship" => Some(FeatureFlags::COMMUNITIES),
+            "CreateBeaconRound" | "CommitEntropy" | "RevealEntropy" | "CloseBeaconRound"
+            | "RequestGame" | "StartGame" => Some(FeatureFlags::BEACON),
+            "CreateTemplate" | "NewTemplateGame" | "MintJoinCode" | "JoinWithCode" => {
+                Some(FeatureFlags::TEMPLATES)
+            }
+            "SetItemPrice" | "BuyItem" => Some(FeatureFlags::SHOP),
+            "ConfigureSeasonPass" | "BuySeasonPass" => Some(FeatureFlags::SEASON_PASS),
+            "SetAttester" | "SubmitAttestation" => Some(FeatureFlags::ATTESTATIONS),
+            "JoinMatchQueue"
+            | "LeaveMatchQueue"
+            | "CreateLobby"
+            | "JoinLobbyQueue"
+            | "DisputeMatch"
+            | "ResolveDispute"
+            | "ChallengeDisputeResult"
+            | "FinalizeDispute"
+            | "SetChatKey"
+            | "SendChat" => Some(FeatureFlags::MATCHMAKING),
+            "Challenge" | "AcceptChallenge" => Some(FeatureFlags::CHALLENGES),
+            "Spectate" | "StopSpectating" => Some(FeatureFlags::SPECTATING),
+            "CreateTournament" | "JoinTournament" | "SubmitResult" | "CloseTournament"
+            | "SetTournamentEnd" | "EndTournamentGames" => Some(FeatureFlags::TOURNAMENTS),
+            "NewDailyGame" => Some(FeatureFlags::DAILY_CHALLENGE),
+            // Games, account merges and the settings of the chain, including the treasury
+            // paid by the shop and season passes alike.
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the subsystem, `None` unless exactly one subsystem is set.
+    ///
+    /// ```
+    /// use game2048::FeatureFlags;
+    ///
+    /// assert_eq!(FeatureFlags::SEASON_PASS.name(), Some("SeasonPass"));
+    /// assert_eq!(FeatureFlags::ALL.name(), None);
+    /// ```
+    pub fn name(self) -> Option<&'static str> {
+        Self::NAMED
+            .iter()
+            .find(|(flag, _)| *flag == self)
+            .map(|(_, name)| *name)
+    }
+}
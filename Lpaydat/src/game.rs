@@ -1,4 +1,5 @@
-use crate::{gen_range, Direction, ROW_MASK};
+use crate::moves::{MoveTableMismatch, MoveTables};
+use crate::{gen_range, Direction, Rng, ROW_MASK};
 use lazy_static::lazy_static;
 use std::ops::Add;
 include!("../moves_data.rs");
@@ -18,7 +19,7 @@ pub struct Moves {
 }
 
 lazy_static! {
-    /// Constructs a new `tfe::Moves`.
+    /// Constructs a new `Moves`.
     ///
     /// `Moves` stores `right`, `left`, `up`, and `down` moves per row.
     ///  e.g. left: `0x0011 -> 0x2000` and right: `0x0011 -> 0x0002`.
@@ -37,9 +38,12 @@ lazy_static! {
     };
 }
 
+/// How many rows of the move tables the contract checks when it loads, in debug builds.
+pub const MOVE_TABLE_SAMPLES: u32 = 64;
+
 /// Struct used to play a single game of 2048.
 ///
-/// `tfe::Game` uses a single `u64` as board value.
+/// `Game` uses a single `u64` as board value.
 /// The board itself is divided into rows (x4 16 bit "row" per "board") which are
 /// divided into tiles (4x 4 bit "nybbles" per "row").
 ///
@@ -51,7 +55,7 @@ pub struct Game {
     pub seed: u16,
 }
 impl Game {
-    /// Constructs a new `tfe::Game`.
+    /// Constructs a new `Game`.
     ///
     /// `Game` stores a board internally as a `u64`.
     ///
@@ -60,18 +64,18 @@ impl Game {
     /// Simple example:
     ///
     /// ```
-    /// use tfe::Game;
+    /// use game2048::Game;
     ///
-    /// let mut game = Game::new();
+    /// let mut game = Game::new(1);
     /// # println!("{:016x}", game.board);
     /// ```
     ///
     /// Accessing board value:
     ///
     /// ```
-    /// use tfe::Game;
+    /// use game2048::Game;
     ///
-    /// let mut game = Game::new();
+    /// let mut game = Game::new(1);
     /// println!("{:016x}", game.board);
     /// ```
     pub fn new(seed: u16) -> Self {
@@ -81,11 +85,34 @@ impl Game {
         };
 
         game.board |= Self::spawn_tile(game.board, game.seed);
-        game.board |= Self::spawn_tile(game.board, game.seed + 1);
+        game.board |= Self::spawn_tile(game.board, game.seed.wrapping_add(1));
 
         game
     }
 
+    /// Checks `samples` rows drawn from `rng` of the precomputed move tables against the
+    /// row-merge algorithm they were generated from, so that stale or corrupted tables fail
+    /// fast instead of silently playing wrong moves.
+    ///
+    /// ```
+    /// use game2048::{Game, Rng};
+    ///
+    /// assert!(Game::check_move_tables(&mut Rng::new(7), 256).is_ok());
+    /// ```
+    pub fn check_move_tables(rng: &mut Rng, samples: u32) -> Result<(), MoveTableMismatch> {
+        let tables = MoveTables {
+            left: MOVES.left,
+            right: MOVES.right,
+            down: MOVES.down,
+            up: MOVES.up,
+            scores: MOVES.scores,
+        };
+        for _ in 0..samples {
+            tables.check_row(rng.gen_range(0, ROW_MASK + 1) as u16)?;
+        }
+        Ok(())
+    }
+
     /// Returns `board` moved in given `direction`.
     ///
     /// - When `Direction::Left`, return board moved left
@@ -98,27 +125,24 @@ impl Game {
     /// Simple example:
     ///
     /// ```
-    /// use tfe::{Game, Direction};
+    /// use game2048::{Game, Direction};
     ///
     /// let board = 0x0000_0000_0022_1100;
-    /// let moved = Game::execute(board, &[Direction::Left]);
+    /// let mut game = Game { board, seed: 1 };
+    /// let moved = game.execute(Direction::Left);
     ///
     /// // | 0 | 0 | 0 | 0 |      | 0 | 0 | 0 | 0 |
     /// // | 0 | 0 | 0 | 0 |  =>  | 0 | 0 | 0 | 0 |
     /// // | 0 | 0 | 4 | 4 |      | 8 | 0 | 0 | 0 |
     /// // | 2 | 2 | 0 | 0 |      | 4 | 0 | 0 | 0 |
     ///
-    /// assert_eq!(board, 0x0000_0000_0022_1100);
-    /// assert_eq!(moved, 0x0000_0000_3000_2000);
+    /// // The moved tiles are in place and a single new tile was spawned.
+    /// assert_eq!(game.board, 0x0000_0000_0022_1100);
+    /// assert_eq!(moved & 0xF000_F000, 0x0000_0000_3000_2000);
+    /// assert_eq!(Game::count_empty(moved), 13);
     /// ```
     pub fn execute(&mut self, direction: Direction) -> u64 {
-        let mut current_board = self.board;
-        current_board = match direction {
-            Direction::Left => Self::move_left(current_board),
-            Direction::Right => Self::move_right(current_board),
-            Direction::Down => Self::move_down(current_board),
-            Direction::Up => Self::move_up(current_board),
-        };
+        let mut current_board = Self::move_board(self.board, direction);
 
         if current_board != self.board {
             current_board = current_board | Self::spawn_tile(current_board, self.seed)
@@ -127,6 +151,96 @@ impl Game {
         current_board
     }
 
+    /// Returns `board` moved in given `direction`, with a tile spawned from `rng` if the move
+    /// changed the board. This is how moves are played on chain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::{Direction, Game, Rng};
+    ///
+    /// let board = 0x0000_0000_0022_1100;
+    /// let moved = Game::execute_with(board, Direction::Left, &mut Rng::new(1));
+    ///
+    /// assert_eq!(moved & 0xF000_F000, 0x0000_0000_3000_2000);
+    /// assert_eq!(Game::count_empty(moved), 13);
+    /// ```
+    pub fn execute_with(board: u64, direction: Direction, rng: &mut Rng) -> u64 {
+        let moved = Self::move_board(board, direction);
+        if moved == board {
+            return board;
+        }
+        moved | Self::spawn_tile_with(moved, rng)
+    }
+
+    /// Replays `moves` from the initial board of `seed`, spawning tiles from a generator
+    /// seeded with `seed`, and returns the final board and its classic score. Moves that do
+    /// not change the board are skipped.
+    ///
+    /// This is the game clients play offline from a seed alone. Games played on chain spawn
+    /// tiles from their blocks instead, see `Rng::for_spawn`, so they are recomputed from the
+    /// tiles recorded in their move log.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::{Direction, Game, Rng};
+    ///
+    /// let moves = [Direction::Left, Direction::Up, Direction::Right];
+    /// let mut board = Game::new(5).board;
+    /// let mut rng = Rng::new(5);
+    /// for direction in moves.clone() {
+    ///     board = Game::execute_with(board, direction, &mut rng);
+    /// }
+    ///
+    /// assert_eq!(Game::replay(5, &moves), (board, Game::score(board)));
+    /// assert_eq!(Game::replay(5, &[]), (Game::new(5).board, 0));
+    /// ```
+    pub fn replay(seed: u16, moves: &[Direction]) -> (u64, u64) {
+        let mut rng = Rng::new(u64::from(seed));
+        let board = moves
+            .iter()
+            .fold(Self::new(seed).board, |board, direction| {
+                Self::execute_with(board, direction.clone(), &mut rng)
+            });
+        (board, Self::score(board))
+    }
+
+    /// Returns `board` moved in given `direction`, without spawning a new tile.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::{Direction, Game};
+    ///
+    /// let board = 0x0000_0000_0022_1100;
+    /// assert_eq!(Game::move_board(board, Direction::Left), Game::move_left(board));
+    /// ```
+    pub fn move_board(board: u64, direction: Direction) -> u64 {
+        match direction {
+            Direction::Left => Self::move_left(board),
+            Direction::Right => Self::move_right(board),
+            Direction::Down => Self::move_down(board),
+            Direction::Up => Self::move_up(board),
+        }
+    }
+
+    /// Returns whether moving `board` in `direction` changes it. Moves that do not are
+    /// rejected before anything about the game is updated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::{Direction, Game};
+    ///
+    /// let board = 0x1000_0000_1000_0000; // Two tiles against the left edge.
+    /// assert!(!Game::is_useful_move(board, Direction::Left));
+    /// assert!(Game::is_useful_move(board, Direction::Right));
+    /// ```
+    pub fn is_useful_move(board: u64, direction: Direction) -> bool {
+        Self::move_board(board, direction) != board
+    }
+
     /// Converts a 64-bit board representation to a 4x4 matrix of u16 values.
     ///
     /// This function takes a u64 board representation where each 4 bits represent
@@ -144,6 +258,8 @@ impl Game {
     /// # Example
     ///
     /// ```
+    /// use game2048::Game;
+    ///
     /// let board = 0x0000_0000_0022_1100; // represents a board with 1, 1, 2, 2 in the bottom two rows
     /// let matrix = Game::convert_to_matrix(board);
     /// assert_eq!(matrix, [
@@ -163,6 +279,31 @@ impl Game {
         matrix
     }
 
+    /// Converts a 64-bit board representation to a 4x4 matrix of tile values, in the
+    /// orientation of [`Game::convert_to_matrix`], with `0` for empty cells.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use game2048::Game;
+    ///
+    /// let board = 0x0000_0000_0022_1B00;
+    /// assert_eq!(Game::to_values_matrix(board), [
+    ///     [0, 0, 0, 0],
+    ///     [0, 0, 0, 0],
+    ///     [0, 0, 4, 4],
+    ///     [2, 2048, 0, 0]
+    /// ]);
+    /// ```
+    pub fn to_values_matrix(board: u64) -> [[u32; 4]; 4] {
+        Self::convert_to_matrix(board).map(|row| {
+            row.map(|exponent| match exponent {
+                0 => 0,
+                exponent => 1 << exponent,
+            })
+        })
+    }
+
     /// Determines if the game has ended.
     ///
     /// The game is considered ended if:
@@ -181,12 +322,12 @@ impl Game {
     /// # Examples
     ///
     /// ```
-    /// use tfe::Game;
+    /// use game2048::Game;
     ///
     /// let board = 0x0000_0000_0000_0B00; // A board with a tile value of 2048
     /// assert!(Game::is_ended(board)); // Game should be ended
     ///
-    /// let board = 0x0000_0000_0000_0000; // An empty board
+    /// let board = 0x0000_0000_0000_0011; // A board with two mergeable tiles
     /// assert!(!Game::is_ended(board)); // Game should not be ended
     /// ```
     pub fn is_ended(board: u64) -> bool {
@@ -199,23 +340,32 @@ impl Game {
             }
         }
 
-        // Check if any move changes the board
+        Self::is_stuck(board)
+    }
+
+    /// Returns `true` if no move in any direction changes `board`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::Game;
+    ///
+    /// assert!(Game::is_stuck(0x1212_2121_1212_2121));
+    /// assert!(!Game::is_stuck(0x0000_0000_0000_0B00));
+    /// ```
+    pub fn is_stuck(board: u64) -> bool {
         let left = Self::move_left(board);
         let right = Self::move_right(board);
         let up = Self::move_up(board);
         let down = Self::move_down(board);
 
-        if board == left && board == right && board == up && board == down {
-            return true;
-        }
-
-        false
+        board == left && board == right && board == up && board == down
     }
 
     /// Returns a transposed board where rows are transformed into columns and vice versa.
     ///
     /// ```
-    /// use tfe::Game;
+    /// use game2048::Game;
     ///
     /// // | F | E | D | C |       | F | B | 7 | 3 |
     /// // | B | A | 9 | 8 |   =>  | E | A | 6 | 2 |
@@ -244,7 +394,7 @@ impl Game {
     /// # Examples
     ///
     /// ```
-    /// use tfe::Game;
+    /// use game2048::Game;
     ///
     /// let board  = 0x0000_0000_0000_0011_u64;
     /// let result = Game::move_up(board);
@@ -274,7 +424,7 @@ impl Game {
     /// # Examples
     ///
     /// ```
-    /// use tfe::Game;
+    /// use game2048::Game;
     ///
     /// let board  = 0x0011_0000_0000_0011_u64;
     /// let result = Game::move_down(board);
@@ -304,7 +454,7 @@ impl Game {
     /// # Examples
     ///
     /// ```
-    /// use tfe::Game;
+    /// use game2048::Game;
     ///
     /// let board  = 0x0000_0000_0000_2211_u64;
     /// let result = Game::move_right(board);
@@ -333,7 +483,7 @@ impl Game {
     /// # Examples
     ///
     /// ```
-    /// use tfe::Game;
+    /// use game2048::Game;
     ///
     /// let board  = 0x0000_0000_0000_2211_u64;
     /// let result = Game::move_left(board);
@@ -361,7 +511,7 @@ impl Game {
     /// # Examples
     ///
     /// ```
-    /// use tfe::Game;
+    /// use game2048::Game;
     ///
     /// let board  = 0x0000_0000_0000_2211_u64;
     /// let result = Game::count_empty(board);
@@ -380,6 +530,25 @@ impl Game {
         empty
     }
 
+    /// Returns the value of the largest tile on `board`, or `0` for an empty board.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::Game;
+    ///
+    /// assert_eq!(Game::max_tile(0x0000_0000_0000_0B21), 2048);
+    /// assert_eq!(Game::max_tile(0), 0);
+    /// ```
+    pub fn max_tile(board: u64) -> u64 {
+        let exponent = (0..16).map(|i| (board >> (i * 4)) & 0xF).max().unwrap_or(0);
+        if exponent == 0 {
+            0
+        } else {
+            1 << exponent
+        }
+    }
+
     /// Returns the sum of 4 lookups in `table` for each "row" in `board`.
     pub fn table_helper<T: Clone + Add<Output = T>>(board: u64, table: &[T]) -> T {
         table[((board) & ROW_MASK) as usize].clone()
@@ -403,6 +572,27 @@ impl Game {
         }
     }
 
+    /// Returns a `2` spawned on an empty cell of `board` picked from `rng`, as a mask of the
+    /// board bits it occupies, or `0` if the board is full.
+    ///
+    /// Only `2`s spawn, so that scores derived from the board alone count merges only.
+    pub fn spawn_tile_with(board: u64, rng: &mut Rng) -> u64 {
+        let empty = Self::count_empty(board);
+        if empty == 0 {
+            return 0;
+        }
+        let mut index = rng.gen_range(0, u64::from(empty));
+        for cell in 0..16 {
+            if (board >> (4 * cell)) & 0xF == 0 {
+                if index == 0 {
+                    return 1 << (4 * cell);
+                }
+                index -= 1;
+            }
+        }
+        unreachable!("The board has {empty} empty cells")
+    }
+
     /// Returns a `1` shifted to the position of any `0` bit in `board` randomly.
     pub fn spawn_tile(board: u64, seed: u16) -> u64 {
         let mut tmp = board;
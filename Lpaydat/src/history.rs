@@ -0,0 +1,49 @@
+use async_graphql::SimpleObject;
+use serde::{Deserialize, Serialize};
+
+/// Number of moves a player may take back in each game.
+pub const UNDO_LIMIT: u32 = 3;
+
+/// The position a move of a game was played from, restored if the move is taken back.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct MoveHistory {
+    /// The number of moves of the game once the move was played.
+    pub move_index: u32,
+    pub previous_board: u64,
+    pub previous_score: u64,
+    pub previous_combo: u32,
+}
+
+impl MoveHistory {
+    /// Returns the entry of the move that led to the current position of a game with
+    /// `move_count` moves, given the latest entries of its history in order.
+    ///
+    /// Moves taken back stay in the history, followed by the moves played in their place, so
+    /// the latest entry of a move index is the one in effect. At most [`UNDO_LIMIT`] moves
+    /// were taken back after it, so it is among the last `UNDO_LIMIT + 1` entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::MoveHistory;
+    ///
+    /// let entry = |move_index, previous_board| MoveHistory {
+    ///     move_index,
+    ///     previous_board,
+    ///     previous_score: 0,
+    ///     previous_combo: 0,
+    /// };
+    /// // Two moves, the second one taken back and played again differently.
+    /// let history = [entry(1, 0x11), entry(2, 0x21), entry(2, 0x21)];
+    ///
+    /// assert_eq!(MoveHistory::latest(&history, 2), Some(&history[2]));
+    /// assert_eq!(MoveHistory::latest(&history, 1), Some(&history[0]));
+    /// assert_eq!(MoveHistory::latest(&history, 0), None);
+    /// ```
+    pub fn latest(history: &[MoveHistory], move_count: u32) -> Option<&MoveHistory> {
+        history
+            .iter()
+            .rev()
+            .find(|entry| entry.move_index == move_count)
+    }
+}
@@ -0,0 +1,61 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+
+use crate::{
+    db::{Index, IndexedMove, IndexedToken, Stats},
+    sources::GameRecord,
+};
+
+const DEFAULT_GAME_LIMIT: u32 = 100;
+
+type ApiResult<T> = Result<Json<T>, (StatusCode, String)>;
+
+#[derive(Deserialize)]
+struct GamesQuery {
+    owner: Option<String>,
+    limit: Option<u32>,
+}
+
+pub fn router(index: Index) -> Router {
+    Router::new()
+        .route("/games", get(games))
+        .route("/games/:game_id/moves", get(moves))
+        .route("/tokens", get(tokens))
+        .route("/stats", get(stats))
+        .with_state(index)
+}
+
+fn internal_error(error: rusqlite::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+}
+
+async fn games(
+    State(index): State<Index>,
+    Query(query): Query<GamesQuery>,
+) -> ApiResult<Vec<GameRecord>> {
+    let limit = query.limit.unwrap_or(DEFAULT_GAME_LIMIT);
+    let games = index.games(query.owner.as_deref(), limit);
+    games.map(Json).map_err(internal_error)
+}
+
+async fn moves(
+    State(index): State<Index>,
+    Path(game_id): Path<u16>,
+) -> ApiResult<Vec<IndexedMove>> {
+    index.moves(game_id).map(Json).map_err(internal_error)
+}
+
+async fn tokens(State(index): State<Index>) -> ApiResult<Vec<IndexedToken>> {
+    index.tokens().map(Json).map_err(internal_error)
+}
+
+async fn stats(State(index): State<Index>) -> ApiResult<Stats> {
+    index.stats().map(Json).map_err(internal_error)
+}
@@ -0,0 +1,277 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+use crate::sources::{GameRecord, MoveRecord, TokenRecord};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS games (
+    game_id INTEGER PRIMARY KEY,
+    owner TEXT,
+    namespace TEXT,
+    sandbox INTEGER NOT NULL,
+    mode TEXT NOT NULL,
+    score INTEGER NOT NULL,
+    move_count INTEGER NOT NULL,
+    is_ended INTEGER NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS moves (
+    game_id INTEGER NOT NULL,
+    move_index INTEGER NOT NULL,
+    direction TEXT,
+    spawned_tile TEXT NOT NULL,
+    board TEXT NOT NULL,
+    score INTEGER NOT NULL,
+    PRIMARY KEY (game_id, move_index)
+);
+CREATE TABLE IF NOT EXISTS tokens (
+    name TEXT PRIMARY KEY,
+    symbol TEXT NOT NULL,
+    total_supply TEXT NOT NULL,
+    creator TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS balances (
+    token TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    PRIMARY KEY (token, owner)
+);
+";
+
+/// The SQLite index, shared between the poller and the API.
+#[derive(Clone)]
+pub struct Index {
+    connection: Arc<Mutex<Connection>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stats {
+    pub games: u64,
+    pub finished_games: u64,
+    pub players: u64,
+    pub moves: u64,
+    pub top_score: u64,
+    pub tokens: u64,
+}
+
+impl Index {
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        let connection = Connection::open(path)?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(Index {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    /// Records the latest state of a game and returns how many of its turns are indexed.
+    ///
+    /// Game ids are seeds, so a game may be replaced by a new one with the same id: the turns
+    /// of the previous game are dropped when the creation time changes.
+    pub fn upsert_game(&self, game: &GameRecord) -> rusqlite::Result<u32> {
+        let connection = self.connection.lock().unwrap();
+        let created_at = connection
+            .query_row(
+                "SELECT created_at FROM games WHERE game_id = ?1",
+                params![game.game_id],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+        if created_at.is_some_and(|created_at| created_at != game.created_at) {
+            connection.execute(
+                "DELETE FROM moves WHERE game_id = ?1",
+                params![game.game_id],
+            )?;
+        }
+        connection.execute(
+            "INSERT OR REPLACE INTO games (game_id, owner, namespace, sandbox, mode, score, \
+             move_count, is_ended, created_at, updated_at) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                game.game_id,
+                game.owner,
+                game.namespace,
+                game.sandbox,
+                game.mode,
+                game.score,
+                game.move_count,
+                game.is_ended,
+                game.created_at,
+                game.updated_at,
+            ],
+        )?;
+        connection.query_row(
+            "SELECT COUNT(*) FROM moves WHERE game_id = ?1",
+            params![game.game_id],
+            |row| row.get(0),
+        )
+    }
+
+    /// Records turns of `game_id`, the first one being turn `offset`.
+    pub fn insert_moves(
+        &self,
+        game_id: u16,
+        offset: u32,
+        moves: &[MoveRecord],
+    ) -> rusqlite::Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        for (move_index, turn) in (offset..).zip(moves) {
+            transaction.execute(
+                "INSERT OR REPLACE INTO moves (game_id, move_index, direction, spawned_tile, \
+                 board, score) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    game_id,
+                    move_index,
+                    turn.direction,
+                    format!("{:016x}", turn.spawned_tile),
+                    format!("{:016x}", turn.board),
+                    turn.score,
+                ],
+            )?;
+        }
+        transaction.commit()
+    }
+
+    /// Replaces a token and its positive balances. Amounts are `u128`, past what SQLite
+    /// integers hold, so they are stored as decimal text.
+    pub fn upsert_token(&self, token: &TokenRecord) -> rusqlite::Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        transaction.execute(
+            "INSERT OR REPLACE INTO tokens (name, symbol, total_supply, creator) \
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                token.name,
+                token.symbol,
+                token.total_supply.to_string(),
+                token.creator
+            ],
+        )?;
+        transaction.execute("DELETE FROM balances WHERE token = ?1", params![token.name])?;
+        for (owner, amount) in token.balances.iter().filter(|(_, amount)| **amount > 0) {
+            transaction.execute(
+                "INSERT INTO balances (token, owner, amount) VALUES (?1, ?2, ?3)",
+                params![token.name, owner, amount.to_string()],
+            )?;
+        }
+        transaction.commit()
+    }
+
+    /// Lists indexed games, best scores first.
+    pub fn games(&self, owner: Option<&str>, limit: u32) -> rusqlite::Result<Vec<GameRecord>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(
+            "SELECT game_id, owner, namespace, sandbox, mode, score, move_count, is_ended, \
+             created_at, updated_at FROM games WHERE ?1 IS NULL OR owner = ?1 \
+             ORDER BY score DESC, game_id LIMIT ?2",
+        )?;
+        let games = statement.query_map(params![owner, limit], |row| {
+            Ok(GameRecord {
+                game_id: row.get(0)?,
+                owner: row.get(1)?,
+                namespace: row.get(2)?,
+                sandbox: row.get(3)?,
+                mode: row.get(4)?,
+                score: row.get(5)?,
+                move_count: row.get(6)?,
+                is_ended: row.get(7)?,
+                created_at: row.get(8)?,
+                updated_at: row.get(9)?,
+            })
+        })?;
+        games.collect()
+    }
+
+    /// Lists the indexed turns of a game, with boards and spawned tiles in hexadecimal.
+    pub fn moves(&self, game_id: u16) -> rusqlite::Result<Vec<IndexedMove>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(
+            "SELECT move_index, direction, spawned_tile, board, score FROM moves \
+             WHERE game_id = ?1 ORDER BY move_index",
+        )?;
+        let moves = statement.query_map(params![game_id], |row| {
+            Ok(IndexedMove {
+                move_index: row.get(0)?,
+                direction: row.get(1)?,
+                spawned_tile: row.get(2)?,
+                board: row.get(3)?,
+                score: row.get(4)?,
+            })
+        })?;
+        moves.collect()
+    }
+
+    pub fn tokens(&self) -> rusqlite::Result<Vec<IndexedToken>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(
+            "SELECT name, symbol, CAST(total_supply AS TEXT), creator, \
+             (SELECT COUNT(*) FROM balances WHERE token = name) \
+             FROM tokens ORDER BY name",
+        )?;
+        let tokens = statement.query_map([], |row| {
+            Ok(IndexedToken {
+                name: row.get(0)?,
+                symbol: row.get(1)?,
+                total_supply: row.get(2)?,
+                creator: row.get(3)?,
+                holders: row.get(4)?,
+            })
+        })?;
+        tokens.collect()
+    }
+
+    pub fn stats(&self) -> rusqlite::Result<Stats> {
+        let connection = self.connection.lock().unwrap();
+        connection.query_row(
+            "SELECT \
+             (SELECT COUNT(*) FROM games), \
+             (SELECT COUNT(*) FROM games WHERE is_ended), \
+             (SELECT COUNT(DISTINCT owner) FROM games), \
+             (SELECT COUNT(*) FROM moves WHERE direction IS NOT NULL), \
+             (SELECT COALESCE(MAX(score), 0) FROM games), \
+             (SELECT COUNT(*) FROM tokens)",
+            [],
+            |row| {
+                Ok(Stats {
+                    games: row.get(0)?,
+                    finished_games: row.get(1)?,
+                    players: row.get(2)?,
+                    moves: row.get(3)?,
+                    top_score: row.get(4)?,
+                    tokens: row.get(5)?,
+                })
+            },
+        )
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedMove {
+    pub move_index: u32,
+    pub direction: Option<String>,
+    pub spawned_tile: String,
+    pub board: String,
+    pub score: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedToken {
+    pub name: String,
+    pub symbol: String,
+    /// The supply in base units, as a decimal string since it may not fit a JSON number.
+    pub total_supply: String,
+    pub creator: String,
+    /// Number of accounts holding a positive balance.
+    pub holders: u32,
+}
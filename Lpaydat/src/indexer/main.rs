@@ -0,0 +1,71 @@
+//! Indexes the games of the 2048 application and the tokens of the token application into a
+//! SQLite database, and serves the index over a small REST API for dashboards.
+//!
+//! Applications are polled: the game application through the GraphQL endpoint the node
+//! service exposes for it, and the token application through its HTTP API.
+
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+mod api;
+mod db;
+mod sources;
+
+#[cfg(not(target_arch = "wasm32"))]
+use {
+    self::{
+        db::Index,
+        sources::{GameApp, TokenApp},
+    },
+    clap::Parser,
+    std::{net::SocketAddr, path::PathBuf, time::Duration},
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Parser)]
+struct Options {
+    /// GraphQL endpoint of the game application, as exposed by the node service, e.g.
+    /// `http://localhost:8080/chains/<chain>/applications/<application>`.
+    #[arg(long)]
+    game_app: String,
+    /// Base URL of the token application's HTTP API, e.g. `http://127.0.0.1:8080`.
+    #[arg(long)]
+    token_app: Option<String>,
+    /// Path of the SQLite database, created if missing.
+    #[arg(long, default_value = "index.sqlite")]
+    db: PathBuf,
+    /// Address the REST API listens on.
+    #[arg(long, default_value = "127.0.0.1:3000")]
+    listen: SocketAddr,
+    /// Seconds between two polls of the applications.
+    #[arg(long, default_value_t = 5)]
+    interval: u64,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let options = Options::parse();
+    let index = Index::open(&options.db)?;
+    let game_app = GameApp::new(options.game_app);
+    let token_app = options.token_app.map(TokenApp::new);
+
+    let poller = {
+        let index = index.clone();
+        let interval = Duration::from_secs(options.interval);
+        async move {
+            loop {
+                if let Err(error) = sources::poll(&index, &game_app, token_app.as_ref()).await {
+                    eprintln!("Indexing failed, retrying: {error:#}");
+                }
+                tokio::time::sleep(interval).await;
+            }
+        }
+    };
+    tokio::spawn(poller);
+
+    println!("Serving the index on http://{}", options.listen);
+    axum::Server::bind(&options.listen)
+        .serve(api::router(index).into_make_service())
+        .await?;
+    Ok(())
+}
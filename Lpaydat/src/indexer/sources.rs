@@ -0,0 +1,189 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Context};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::db::Index;
+
+/// A game as listed by the game application.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameRecord {
+    pub game_id: u16,
+    pub owner: Option<String>,
+    pub namespace: Option<String>,
+    pub sandbox: bool,
+    pub mode: String,
+    pub score: u64,
+    pub move_count: u32,
+    pub is_ended: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// A turn of a game, turn `0` being its creation.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveRecord {
+    pub direction: Option<String>,
+    pub spawned_tile: u64,
+    pub board: u64,
+    pub score: u64,
+}
+
+/// A token as listed by the token application.
+#[derive(Debug, Deserialize)]
+pub struct TokenRecord {
+    pub name: String,
+    pub symbol: String,
+    pub total_supply: u128,
+    pub creator: String,
+    pub balances: HashMap<String, u128>,
+}
+
+const GAME_FIELDS: &str =
+    "gameId owner namespace sandbox mode score moveCount isEnded createdAt updatedAt";
+
+pub struct GameApp {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl GameApp {
+    pub fn new(url: String) -> Self {
+        GameApp {
+            client: reqwest::Client::new(),
+            url,
+        }
+    }
+
+    async fn query<T: DeserializeOwned>(&self, query: &str, variables: Value) -> anyhow::Result<T> {
+        let request = json!({ "query": query, "variables": variables });
+        let response: Value = self
+            .client
+            .post(&self.url)
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if let Some(errors) = response.get("errors") {
+            bail!("GraphQL errors: {errors}");
+        }
+        let data = response
+            .get("data")
+            .context("GraphQL response without data")?;
+        Ok(serde_json::from_value(data.clone())?)
+    }
+
+    /// Lists the games of every namespace, the global one included.
+    pub async fn games(&self) -> anyhow::Result<Vec<GameRecord>> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Community {
+            community_id: String,
+        }
+        #[derive(Deserialize)]
+        struct Communities {
+            communities: Vec<Community>,
+        }
+        #[derive(Deserialize)]
+        struct Games {
+            games: Vec<GameRecord>,
+        }
+
+        let query = "query { communities { communityId } }";
+        let communities: Communities = self.query(query, json!({})).await?;
+        let namespaces = communities
+            .communities
+            .into_iter()
+            .map(|community| Some(community.community_id));
+        let query = format!(
+            "query($namespace: String) {{ games(namespace: $namespace) {{ {GAME_FIELDS} }} }}"
+        );
+        let mut games = Vec::new();
+        for namespace in std::iter::once(None).chain(namespaces) {
+            let page: Games = self
+                .query(&query, json!({ "namespace": namespace }))
+                .await?;
+            games.extend(page.games);
+        }
+        Ok(games)
+    }
+
+    /// Lists the turns of `game_id` from `offset` on.
+    pub async fn moves(&self, game_id: u16, offset: u32) -> anyhow::Result<Vec<MoveRecord>> {
+        #[derive(Deserialize)]
+        struct Moves {
+            moves: Option<Vec<MoveRecord>>,
+        }
+
+        let query = "query($gameId: Int!, $offset: Int) { \
+                     moves(gameId: $gameId, offset: $offset) { direction spawnedTile board score } }";
+        let variables = json!({ "gameId": game_id, "offset": offset });
+        let moves: Moves = self.query(query, variables).await?;
+        Ok(moves.moves.unwrap_or_default())
+    }
+}
+
+pub struct TokenApp {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl TokenApp {
+    pub fn new(url: String) -> Self {
+        TokenApp {
+            client: reqwest::Client::new(),
+            url,
+        }
+    }
+
+    /// Lists every token, a page at a time.
+    pub async fn tokens(&self) -> anyhow::Result<Vec<TokenRecord>> {
+        const PAGE: usize = 100;
+        let url = format!("{}/tokens", self.url.trim_end_matches('/'));
+        let mut tokens = Vec::new();
+        loop {
+            let response = self
+                .client
+                .get(&url)
+                .query(&[("offset", tokens.len()), ("limit", PAGE)])
+                .send()
+                .await?
+                .error_for_status()?;
+            let page: Vec<TokenRecord> = response.json().await?;
+            let is_last = page.len() < PAGE;
+            tokens.extend(page);
+            if is_last {
+                return Ok(tokens);
+            }
+        }
+    }
+}
+
+/// Brings the index up to date with both applications.
+pub async fn poll(
+    index: &Index,
+    game_app: &GameApp,
+    token_app: Option<&TokenApp>,
+) -> anyhow::Result<()> {
+    for game in game_app.games().await? {
+        let indexed = index.upsert_game(&game)?;
+        // The log holds a turn per move, plus the creation of the game.
+        if indexed <= game.move_count {
+            let moves = game_app.moves(game.game_id, indexed).await?;
+            index.insert_moves(game.game_id, indexed, &moves)?;
+        }
+    }
+    if let Some(token_app) = token_app {
+        for token in token_app.tokens().await? {
+            index.upsert_token(&token)?;
+        }
+    }
+    Ok(())
+}
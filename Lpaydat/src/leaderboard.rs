@@ -0,0 +1,203 @@
+use crate::{CommunityId, Game};
+use async_graphql::{scalar, InputObject, SimpleObject};
+use linera_sdk::base::{ChainId, Owner, Timestamp};
+use serde::{Deserialize, Serialize};
+
+/// Thresholds a game must reach to be ranked, filtering out trivially seeded or instantly
+/// ended games. The defaults rank every game that is not a sandbox game.
+#[derive(
+    Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SimpleObject, InputObject,
+)]
+#[graphql(input_name = "LeaderboardFiltersInput")]
+pub struct LeaderboardFilters {
+    /// Moves a game needs to be ranked.
+    pub min_moves: u32,
+    /// Seconds between the start of a game and its last move needed to be ranked.
+    pub min_duration_secs: u64,
+}
+
+/// Why a game is left out of leaderboards.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum LeaderboardExclusion {
+    /// Practice games are never ranked.
+    Sandbox,
+    /// The game has fewer moves than `min_moves`.
+    TooFewMoves,
+    /// The game was played for less than `min_duration_secs`.
+    TooShort,
+    /// The move log of the game does not reproduce it, see `VerifyGame`.
+    FailedVerification,
+    /// The board of the game is larger than 4x4: only games on the same board compare.
+    LargeBoard,
+}
+
+scalar!(LeaderboardExclusion);
+
+impl LeaderboardFilters {
+    /// Returns why a game with `move_count` moves played over `duration_secs` seconds is not
+    /// ranked, or `None` if it is.
+    ///
+    /// ```
+    /// use game2048::{LeaderboardExclusion, LeaderboardFilters};
+    ///
+    /// let filters = LeaderboardFilters { min_moves: 10, min_duration_secs: 30 };
+    /// assert_eq!(filters.exclusion(false, 10, 30), None);
+    /// assert_eq!(filters.exclusion(false, 9, 30), Some(LeaderboardExclusion::TooFewMoves));
+    /// assert_eq!(filters.exclusion(false, 10, 29), Some(LeaderboardExclusion::TooShort));
+    /// assert_eq!(filters.exclusion(true, 10, 30), Some(LeaderboardExclusion::Sandbox));
+    /// ```
+    pub fn exclusion(
+        &self,
+        sandbox: bool,
+        move_count: u32,
+        duration_secs: u64,
+    ) -> Option<LeaderboardExclusion> {
+        if sandbox {
+            Some(LeaderboardExclusion::Sandbox)
+        } else if move_count < self.min_moves {
+            Some(LeaderboardExclusion::TooFewMoves)
+        } else if duration_secs < self.min_duration_secs {
+            Some(LeaderboardExclusion::TooShort)
+        } else {
+            None
+        }
+    }
+}
+
+/// The period a leaderboard ranks games over.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum LeaderboardWindow {
+    /// Every game since the application was created.
+    #[default]
+    AllTime,
+    /// Games ended since midnight UTC.
+    Daily,
+    /// Games ended in the current week, weeks starting on the Unix epoch.
+    Weekly,
+}
+
+scalar!(LeaderboardWindow);
+
+impl LeaderboardWindow {
+    /// Every window, each game being ranked in all of them.
+    pub const ALL: [LeaderboardWindow; 3] = [
+        LeaderboardWindow::AllTime,
+        LeaderboardWindow::Daily,
+        LeaderboardWindow::Weekly,
+    ];
+
+    /// Returns the period of the window `time` falls in: the days or weeks since the Unix
+    /// epoch, and `0` for the all-time window.
+    ///
+    /// ```
+    /// use game2048::LeaderboardWindow;
+    /// use linera_sdk::base::Timestamp;
+    ///
+    /// let day_micros = 86_400 * 1_000_000;
+    /// let time = Timestamp::from(8 * day_micros + 1);
+    /// assert_eq!(LeaderboardWindow::AllTime.period(time), 0);
+    /// assert_eq!(LeaderboardWindow::Daily.period(time), 8);
+    /// assert_eq!(LeaderboardWindow::Weekly.period(time), 1);
+    /// assert_eq!(LeaderboardWindow::Daily.period(Timestamp::from(day_micros - 1)), 0);
+    /// ```
+    pub fn period(self, time: Timestamp) -> u64 {
+        let days = time.micros() / (86_400 * 1_000_000);
+        match self {
+            LeaderboardWindow::AllTime => 0,
+            LeaderboardWindow::Daily => days,
+            LeaderboardWindow::Weekly => days / 7,
+        }
+    }
+}
+
+/// A player's best ranked game in a namespace.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct LeaderboardEntry {
+    pub owner: Owner,
+    pub namespace: Option<CommunityId>,
+    pub game_id: u16,
+    pub score: u64,
+    /// When the game ended.
+    pub ended_at: Timestamp,
+}
+
+/// Weights of the efficiency ranking, rewarding games won with few moves, quickly and with
+/// large tiles over games ground out for a long time. Set in the application parameters, so
+/// every chain ranks games the same way.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject, InputObject)]
+#[graphql(input_name = "EfficiencyWeightsInput")]
+#[serde(default)]
+pub struct EfficiencyWeights {
+    /// Points per point of score per move.
+    pub score_per_move: u64,
+    /// Points per second a game ended under `par_time_secs`.
+    pub time_bonus: u64,
+    /// Seconds after which games get no time bonus.
+    pub par_time_secs: u64,
+    /// Points per doubling of the largest tile, a 2048 tile being 11 doublings.
+    pub tile_bonus: u64,
+}
+
+impl Default for EfficiencyWeights {
+    fn default() -> Self {
+        EfficiencyWeights {
+            score_per_move: 100,
+            time_bonus: 1,
+            par_time_secs: 600,
+            tile_bonus: 50,
+        }
+    }
+}
+
+impl EfficiencyWeights {
+    /// Returns the efficiency of a game that reached `board` and `score` in `move_count`
+    /// moves played over `duration_secs` seconds. Games without moves score nothing.
+    ///
+    /// ```
+    /// use game2048::EfficiencyWeights;
+    ///
+    /// let weights = EfficiencyWeights::default();
+    /// // Score 1000 in 100 moves, over 5 minutes, with a 128 tile.
+    /// let board = 0x0000_0000_0000_0007;
+    /// assert_eq!(weights.efficiency(board, 1_000, 100, 300), 100 * 10 + 300 + 50 * 7);
+    /// // Grinding the same score out over more moves and time ranks lower.
+    /// assert!(weights.efficiency(board, 1_000, 400, 900) < weights.efficiency(board, 1_000, 100, 300));
+    /// assert_eq!(weights.efficiency(0, 0, 0, 0), 0);
+    /// ```
+    pub fn efficiency(&self, board: u64, score: u64, move_count: u32, duration_secs: u64) -> u64 {
+        if move_count == 0 {
+            return 0;
+        }
+        let score_per_move = score / u64::from(move_count);
+        let seconds_saved = self.par_time_secs.saturating_sub(duration_secs);
+        let doublings = u64::from(Game::max_tile(board).checked_ilog2().unwrap_or(0));
+        self.score_per_move
+            .saturating_mul(score_per_move)
+            .saturating_add(self.time_bonus.saturating_mul(seconds_saved))
+            .saturating_add(self.tile_bonus.saturating_mul(doublings))
+    }
+}
+
+/// A player's most efficient ranked game in a namespace.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct EfficiencyEntry {
+    pub owner: Owner,
+    pub namespace: Option<CommunityId>,
+    pub game_id: u16,
+    pub efficiency: u64,
+    pub score: u64,
+    pub move_count: u32,
+    pub duration_secs: u64,
+    pub max_tile: u64,
+    /// When the game ended.
+    pub ended_at: Timestamp,
+}
+
+/// The score of a game played on another chain, as last reported to the hub.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct RemoteScore {
+    /// The chain the game is played on.
+    pub chain_id: ChainId,
+    pub game_id: u16,
+    pub score: u64,
+}
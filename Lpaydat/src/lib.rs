@@ -1,15 +1,118 @@
+mod analysis;
+mod attestation;
+mod batch;
+mod beacon;
+mod challenge;
+mod chat;
+mod community;
+mod daily;
 mod direction;
+mod dispute;
+mod envelope;
+mod events;
+mod export;
+mod faults;
+mod features;
 mod game;
+mod history;
+mod leaderboard;
+mod matchmaking;
+mod metrics;
+mod mode;
 mod moves;
+mod pass;
+mod player;
+mod position;
 mod random;
+mod replay;
+mod repr;
+mod response;
+mod reward;
+mod scoring;
+mod share;
+mod solver;
+mod spectator;
+mod svg;
+mod template;
+mod time;
+mod tournament;
+mod tutorial;
+mod wide;
 
+pub use crate::analysis::{Blunder, GameAnalysis, ANALYSIS_DEPTH};
+pub use crate::attestation::{
+    attestation_epoch, verify_attestation, ScoreAttestation, SignedAttestation,
+    ATTESTATION_EPOCH_SECS,
+};
+pub use crate::batch::{validate_batch, BatchError, MAX_BATCH_LEN};
+pub use crate::beacon::{
+    beacon_output, beacon_seed, commitment, game_request_seed, BeaconPhase, BeaconSchedule,
+    GameRequest, GAME_REQUEST_TIMEOUT_MICROS, MAX_ENTROPY_LEN,
+};
+pub use crate::challenge::{ChallengeRecord, ChallengeStatus, CHALLENGE_MOVE_LIMIT};
+pub use crate::chat::{
+    ChatMessage, ChatPayload, MAX_CHAT_KEY_LEN, MAX_CHAT_MESSAGES, MAX_CHAT_PAYLOAD_LEN,
+};
+pub use crate::community::FeeSettings;
+pub use crate::daily::{daily_seed, day_of, DailyScore, DAY_MICROS};
 pub use crate::direction::Direction;
-pub use crate::game::Game;
-pub use crate::moves::{Moves, COL_MASK, ROW_MASK};
-pub use crate::random::gen_range;
-use async_graphql::{Request, Response};
+pub use crate::dispute::{
+    replay_board, replay_outcome, BondToken, DisputeChallenge, DisputeChallengeStatus,
+    DisputeResolution, DisputeSubmission, MatchDispute, DISPUTE_PERIOD_MICROS,
+};
+pub use crate::envelope::{
+    v1, v2, v3, v4, OperationDecodeError, OperationEnvelope, CURRENT_OPERATION_VERSION,
+};
+pub use crate::events::{EventCursor, GameEvent, GAME_EVENTS_STREAM};
+pub use crate::export::{ExportBundle, ExportFormat, ExportRow};
+pub use crate::faults::{fault_for, FaultKind, MessageFault};
+pub use crate::features::FeatureFlags;
+pub use crate::game::{Game, MOVE_TABLE_SAMPLES};
+pub use crate::history::{MoveHistory, UNDO_LIMIT};
+pub use crate::leaderboard::{
+    EfficiencyEntry, EfficiencyWeights, LeaderboardEntry, LeaderboardExclusion, LeaderboardFilters,
+    LeaderboardWindow, RemoteScore,
+};
+pub use crate::matchmaking::{
+    Lobby, LobbyStats, MatchNotice, MatchRecord, QueueEntry, DEFAULT_LOBBY, MATCH_WINDOW_BASE,
+    MATCH_WINDOW_GROWTH_PER_SEC,
+};
+pub use crate::metrics::OperationFailure;
+pub use crate::mode::{GameMode, HistoryRetention, ModeSettings};
+pub use crate::moves::{MoveTableMismatch, Moves, COL_MASK, ROW_MASK};
+pub use crate::pass::{PassSale, PassSettings, SeasonPass, PASS_REWARD_MULTIPLIER};
+pub use crate::player::{AccountMerge, PlayerStats, TileHeatmap, DEFAULT_ACTIVE_GAME_LIMIT};
+pub use crate::position::{MoveOutcome, Position};
+pub use crate::random::{gen_range, Rng};
+pub use crate::replay::{
+    Checkpoint, GameVerification, Replay, ReplayError, ReplayMove, CHECKPOINT_INTERVAL,
+    REPLAY_MAGIC, REPLAY_VERSION,
+};
+pub use crate::repr::{BoardRepr, LargeBoard, MAX_BOARD_SIZE};
+pub use crate::response::{verify_response, ClaimedValue, ResponseClaim, SignedResponse};
+pub use crate::reward::GameRewards;
+pub use crate::scoring::{ComboMultiplier, MergeSum, ScorePolicy, ScoredMove, TimeBonus};
+pub use crate::share::{GameResult, ShareCard};
+pub use crate::solver::{Solver, HINT_DEPTH, MAX_HINT_DEPTH};
+pub use crate::spectator::SpectatedGame;
+pub use crate::svg::{board_svg, BoardTheme};
+pub use crate::template::{
+    join_code, Difficulty, GameTemplate, JoinCode, JOIN_CODE_LEN, SUPPORTED_BOARD_SIZE,
+};
+pub use crate::time::{date_of, day_from_date, duration_seconds, moves_per_minute, rfc3339};
+pub use crate::tournament::{
+    tournament_standings, TournamentResult, TournamentStanding, TournamentStatus,
+};
+pub use crate::tutorial::{Achievement, Tutorial, TutorialStep, TUTORIALS};
+pub use crate::wide::{
+    BoardEncoding, WideBoard, MAX_WIDE_BOARD_SIZE, MAX_WIDE_EXPONENT, WIDE_CELL_BITS,
+};
+use async_graphql::{Request, Response, SimpleObject};
 use linera_sdk::{
-    base::{ContractAbi, ServiceAbi},
+    base::{
+        Account, Amount, ChainId, ContractAbi, CryptoHash, Owner, PublicKey, ServiceAbi, Signature,
+        Timestamp,
+    },
     graphql::GraphQLMutationRoot,
 };
 use serde::{Deserialize, Serialize};
@@ -17,7 +120,7 @@ use serde::{Deserialize, Serialize};
 pub struct Game2048Abi;
 
 impl ContractAbi for Game2048Abi {
-    type Operation = Operation;
+    type Operation = OperationEnvelope;
     type Response = ();
 }
 
@@ -26,19 +129,486 @@ impl ServiceAbi for Game2048Abi {
     type QueryResponse = Response;
 }
 
-#[derive(Debug, Deserialize, Serialize, GraphQLMutationRoot)]
+/// Settings fixed when the application is created. Missing fields take their default.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Game2048Parameters {
+    pub efficiency_weights: EfficiencyWeights,
+    /// The chain the turns of every game are reported to, for leaderboards and settlement.
+    /// Turns are not reported when `None`.
+    pub hub: Option<ChainId>,
+    /// The campaign the application is deployed for, so that hubs tell deployments apart.
+    pub campaign_id: Option<String>,
+    /// The optional subsystems enabled in this deployment, all of them by default.
+    pub features: FeatureFlags,
+    /// Faults injected in the delivery of turns to the hub, for tests only.
+    pub message_faults: Vec<MessageFault>,
+    /// The bond players post to dispute the result of a match, slashed if their replay does
+    /// not back the score they claim. Bonds count base units of the bond token.
+    pub dispute_bond: Amount,
+    /// How long the resolution of a dispute can be challenged before its bonds are paid out,
+    /// `0` to pay them out as soon as it is resolved.
+    pub dispute_challenge_window_micros: u64,
+    /// The bond posted to challenge a replay, slashed if the replay is the game played, in
+    /// base units of the bond token.
+    pub dispute_challenge_bond: Amount,
+    /// The minimum time between two moves of a game, so that bots cannot outpace human
+    /// players. Faster moves are rejected. `0` for no limit.
+    pub min_move_interval_micros: u64,
+    /// The keys node operators sign critical service responses with, see `ResponseClaim`.
+    /// Empty if responses are not signed.
+    pub response_signers: Vec<PublicKey>,
+    /// The reward tokens minted for games ending with a high enough score, if any.
+    pub rewards: Option<GameRewards>,
+    /// The token dispute bonds are posted in, required unless both bonds are zero.
+    pub bond_token: Option<BondToken>,
+}
+
+/// Identifier of a community hosted by this application.
+///
+/// Games created with a community id live in that community's namespace, isolated from
+/// the games (and rankings) of every other community. Games created without one belong to
+/// the global namespace.
+pub type CommunityId = String;
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, GraphQLMutationRoot)]
 pub enum Operation {
-    NewGame { seed: u16 },
-    EndGame { game_id: u16 },
-    MakeMove { game_id: u16, direction: Direction },
+    NewGame {
+        seed: u16,
+        namespace: Option<CommunityId>,
+        /// Creates a practice game, excluded from leaderboards, player statistics and limits.
+        sandbox: bool,
+        /// Moves after which the game ends, `None` for no limit.
+        max_moves: Option<u32>,
+        /// Width and height of the board, from 4 to `MAX_BOARD_SIZE`. `None` for the 4x4
+        /// board of `Game`.
+        board_size: Option<u8>,
+    },
+    EndGame {
+        game_id: u16,
+        namespace: Option<CommunityId>,
+    },
+    MakeMove {
+        game_id: u16,
+        direction: Direction,
+        namespace: Option<CommunityId>,
+    },
+    /// Creates a community with its own namespace, administered by `admins` and the creator.
+    CreateCommunity {
+        community_id: CommunityId,
+        name: String,
+        admins: Vec<Owner>,
+        default_mode: GameMode,
+        fees: FeeSettings,
+    },
+    /// Updates the configuration of a community. Only its admins may do so.
+    ConfigureCommunity {
+        community_id: CommunityId,
+        name: Option<String>,
+        admins: Option<Vec<Owner>>,
+        default_mode: Option<GameMode>,
+        fees: Option<FeeSettings>,
+    },
+    JoinCommunity {
+        community_id: CommunityId,
+    },
+    LeaveCommunity {
+        community_id: CommunityId,
+    },
+    /// Opens a randomness beacon round. Participants staking `stake` commit to entropy
+    /// before `commit_deadline` and reveal it before `reveal_deadline`.
+    CreateBeaconRound {
+        commit_deadline: Timestamp,
+        reveal_deadline: Timestamp,
+        stake: Amount,
+    },
+    /// Commits to secret entropy for a beacon round, see [`commitment`].
+    CommitEntropy {
+        round_id: u32,
+        commitment: CryptoHash,
+    },
+    /// Reveals the entropy committed to in a beacon round.
+    RevealEntropy {
+        round_id: u32,
+        entropy: Vec<u8>,
+    },
+    /// Derives the output of a beacon round, slashing the stake of participants who did not
+    /// reveal. Anyone may close a round once it timed out or everyone revealed.
+    CloseBeaconRound {
+        round_id: u32,
+    },
+    /// Asks to merge the signer's account into `primary`, once `primary` confirmed it and an
+    /// admin approved it.
+    RequestAccountMerge {
+        primary: Owner,
+    },
+    /// Merges a duplicate account into the primary account it asked for, once that account
+    /// confirmed it. Admins only.
+    ApproveAccountMerge {
+        duplicate: Owner,
+    },
+    /// Sets how many active games each player may have at once. Admins only.
+    SetActiveGameLimit {
+        limit: u32,
+    },
+    /// Requests a game whose seed is derived from the output of beacon round `round_id`,
+    /// which must still be taking commitments. See [`GameRequest`].
+    RequestGame {
+        namespace: Option<CommunityId>,
+        sandbox: bool,
+        round_id: u32,
+    },
+    /// Starts a requested game once its beacon round closed with an output.
+    StartGame {
+        request_id: u32,
+    },
+    /// Creates a template games can be started from, organized by the signer.
+    CreateTemplate {
+        template: GameTemplate,
+    },
+    /// Starts a game with the settings of template `template_id`. A `seed` of `0` picks
+    /// one from the block context.
+    NewTemplateGame {
+        template_id: u32,
+        seed: u16,
+    },
+    /// Mints a join code for template `template_id`, usable by `capacity` participants until
+    /// `expires_at`. Only the organizer of the template may do so, and the template is then
+    /// reserved to the participants who joined.
+    MintJoinCode {
+        template_id: u32,
+        capacity: u32,
+        expires_at: Timestamp,
+    },
+    /// Registers the signer as a participant of the template a join code was minted for.
+    JoinWithCode {
+        code: String,
+    },
+    /// Lists `item` in the catalog at `price`, or removes it from the catalog when `price` is
+    /// `None`. Admins only.
+    SetItemPrice {
+        item: String,
+        price: Option<Amount>,
+    },
+    /// Sets the account receiving the payments for items, `owner` being `None` for the chain
+    /// balance of `chain_id`. Admins only.
+    SetTreasury {
+        chain_id: ChainId,
+        owner: Option<Owner>,
+    },
+    /// Buys one `item` from the catalog, paying its price in native tokens from the signer's
+    /// balance to the treasury. Fails if the price is above `max_price`.
+    BuyItem {
+        item: String,
+        max_price: Amount,
+    },
+    /// Sets the price, duration and premium modes of season passes. Admins only.
+    ConfigureSeasonPass {
+        settings: PassSettings,
+    },
+    /// Buys a season pass, paying its price in native tokens from the signer's balance to the
+    /// treasury. Fails if the price is above `max_price`.
+    BuySeasonPass {
+        max_price: Amount,
+    },
+    /// Sets the key signing score attestations. Admins only.
+    SetAttester {
+        attester: PublicKey,
+    },
+    /// Records an attestation signed by the attester, so that other applications can read it
+    /// from this one. It must match the owner's current best score and the current epoch.
+    SubmitAttestation {
+        attestation: ScoreAttestation,
+        signature: Signature,
+    },
+    /// Sets the minimum moves and duration games need to be ranked. Admins only.
+    SetLeaderboardFilters {
+        filters: LeaderboardFilters,
+    },
+    /// Asks the matchmaking chain, the chain that created the application, to pair the
+    /// signer with a player of similar rating. Both players then get a game started from the
+    /// same seed.
+    JoinMatchQueue,
+    /// Withdraws the signer from the matchmaking queue.
+    LeaveMatchQueue,
+    /// Creates a lobby on the matchmaking chain. Admins only.
+    CreateLobby {
+        name: String,
+        lobby: Lobby,
+    },
+    /// Like `JoinMatchQueue`, but only pairs the signer with players of `lobby`. Requests for
+    /// unknown lobbies or outside the lobby's rating bracket are dropped.
+    JoinLobbyQueue {
+        lobby: String,
+    },
+    /// Takes back the last move of a game, restoring the position it was played from. Each
+    /// game allows [`UNDO_LIMIT`] undos, and none once it is over.
+    Undo {
+        game_id: u16,
+        namespace: Option<CommunityId>,
+    },
+    /// Challenges the players of `opponent_chain` to a race to the highest score in
+    /// [`CHALLENGE_MOVE_LIMIT`] moves. The signer's game starts right away, the opponent's
+    /// once a player of their chain accepts.
+    Challenge {
+        opponent_chain: ChainId,
+    },
+    /// Accepts challenge `challenge_id` of `challenger_chain`, starting the signer's game.
+    AcceptChallenge {
+        challenger_chain: ChainId,
+        challenge_id: u32,
+    },
+    /// Follows game `game_id` of `chain_id`: the chain first sends a snapshot of the game,
+    /// then each of its turns until it ends.
+    Spectate {
+        chain_id: ChainId,
+        game_id: u16,
+    },
+    /// Stops following game `game_id` of `chain_id`.
+    StopSpectating {
+        chain_id: ChainId,
+        game_id: u16,
+    },
+    /// Prepays the fees of `games_per_player` games in `community_id` for each of `players`,
+    /// from the signer's balance to the chain's. The players' new games in the community are
+    /// paid from the sponsorship until it runs out or `expires_at`.
+    SponsorGames {
+        community_id: CommunityId,
+        players: Vec<Owner>,
+        games_per_player: u32,
+        expires_at: Timestamp,
+    },
+    /// Refunds the fees left in sponsorship `sponsorship_id` to its sponsor once it expired.
+    WithdrawSponsorship {
+        sponsorship_id: u32,
+    },
+    /// Contests the result of match `match_id` with the encoded replay of the signer's game
+    /// and the score it reaches, posting the dispute bond. Sent to the matchmaking chain,
+    /// which resolves the dispute once every player submitted a replay.
+    DisputeMatch {
+        match_id: u32,
+        replay: Vec<u8>,
+        claimed_score: u64,
+    },
+    /// Resolves the dispute of match `match_id` without the replays missing at its deadline,
+    /// on the matchmaking chain.
+    ResolveDispute {
+        match_id: u32,
+    },
+    /// Challenges the replay `player` submitted to the dispute of match `match_id` as not
+    /// being the game they played, posting the challenge bond. Sent to the matchmaking chain
+    /// while the resolution of the dispute is open to challenges.
+    ChallengeDisputeResult {
+        match_id: u32,
+        player: Owner,
+    },
+    /// Pays out the bonds of the dispute of match `match_id` once its challenge window
+    /// closed, on the matchmaking chain.
+    FinalizeDispute {
+        match_id: u32,
+    },
+    /// Opens a tournament named `name`, whose entrants all play one game from `seed`, ending
+    /// after `move_limit` moves. The signer organizes it.
+    CreateTournament {
+        name: String,
+        seed: u16,
+        move_limit: u32,
+    },
+    /// Enters the signer in tournament `tournament_id`, starting their game. Creating and
+    /// registering the game happen atomically.
+    JoinTournament {
+        tournament_id: u32,
+    },
+    /// Submits the final score of the signer's ended game in tournament `tournament_id`.
+    SubmitResult {
+        tournament_id: u32,
+    },
+    /// Closes tournament `tournament_id` to entrants and results, settling its standings.
+    /// Only its organizer can close it.
+    CloseTournament {
+        tournament_id: u32,
+    },
+    /// Recomputes game `game_id` from its move log, flagging it and removing it from the
+    /// leaderboards if the moves do not reproduce its board. Anyone can verify any game.
+    VerifyGame {
+        game_id: u16,
+    },
+    /// Sets the settings of the games created in `mode` from now on. Admins only.
+    ConfigureMode {
+        mode: GameMode,
+        settings: ModeSettings,
+    },
+    /// Ends tournament `tournament_id` at `ends_at`: games still in flight then end with their
+    /// current score. Only its organizer can set it, before the tournament ends.
+    SetTournamentEnd {
+        tournament_id: u32,
+        ends_at: Timestamp,
+    },
+    /// Ends the games still in flight in tournament `tournament_id`, whose end time passed,
+    /// recording their current score for the entrants who submitted no result. Admins only.
+    EndTournamentGames {
+        tournament_id: u32,
+    },
+    /// Starts the signer's game in the daily challenge of the current day, from the seed
+    /// every player gets that day. Players enter each daily challenge once.
+    NewDailyGame,
+    /// Plays `direction` at the signer's current step of tutorial `tutorial`, an index into
+    /// [`TUTORIALS`]. The step is completed if `direction` is the move it teaches.
+    PlayTutorial {
+        tutorial: u8,
+        direction: Direction,
+    },
+    /// Pauses game `game_id`: its moves are dropped until it is resumed. The clocks of timed
+    /// games keep running. Only the owner of the game can pause it.
+    PauseGame {
+        game_id: u16,
+        namespace: Option<CommunityId>,
+    },
+    /// Resumes game `game_id`, paused by its owner.
+    ResumeGame {
+        game_id: u16,
+        namespace: Option<CommunityId>,
+    },
+    /// Publishes the public key other players encrypt chat messages to the signer with, on
+    /// the matchmaking chain. At most [`MAX_CHAT_KEY_LEN`] bytes.
+    SetChatKey {
+        key: Vec<u8>,
+    },
+    /// Posts `payload` to the chat of match `match_id`, on the matchmaking chain. Only the
+    /// players of the match can post, at most [`MAX_CHAT_PAYLOAD_LEN`] bytes at a time.
+    SendChat {
+        match_id: u32,
+        payload: ChatPayload,
+    },
+    /// Executes up to [`MAX_BATCH_LEN`] operations in order, all or none of them: if one is
+    /// rejected, or dropped without effect, the whole batch is. Batches cannot be nested.
+    Batch(Vec<Operation>),
+    /// Confirms the request of account `duplicate` to be merged into the signer's account,
+    /// letting an admin approve it.
+    ConfirmAccountMerge {
+        duplicate: Owner,
+    },
+}
+
+/// A turn of a game, as recorded in the move log of the chain it is played on.
+#[derive(Clone, Debug, Deserialize, Serialize, SimpleObject)]
+pub struct Turn {
+    /// The direction played, or `None` for the creation of the game and for undos.
+    pub direction: Option<Direction>,
+    /// The tile spawned after the move, as a mask of the board bits it occupies.
+    pub spawned_tile: u64,
+    /// Index of the spawn within the game the tile was seeded with, `None` for the creation
+    /// of the game and for undos.
+    pub spawn_index: Option<u32>,
+    pub board: u64,
+    pub score: u64,
+    pub is_ended: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub enum Message {
+    /// A turn of a game played on the sending chain.
     Game {
         game_id: u16,
+        namespace: Option<CommunityId>,
+        /// Index of the turn within the game, `0` being the creation of the game.
+        move_index: u32,
+        /// The direction played, or `None` when the game was just created or a move was
+        /// taken back.
+        direction: Option<Direction>,
+        /// The tile spawned after the move, as a mask of the board bits it occupies.
+        spawned_tile: u64,
+        /// Index of the spawn within the game the tile was seeded with, `None` when the game
+        /// was just created or a move was taken back.
+        spawn_index: Option<u32>,
         board: u64,
         score: u64,
         is_ended: bool,
     },
+    /// Asks the chain a game is played on to send its turns `from..=to` again.
+    ResendRequest { game_id: u16, from: u32, to: u32 },
+    /// Queues the authenticated signer for a match, sent to the matchmaking chain.
+    JoinMatchQueue { rating: u64 },
+    /// Removes the authenticated signer from the matchmaking queue.
+    LeaveMatchQueue,
+    /// Queues the authenticated signer for a match within `lobby`.
+    JoinLobbyQueue { lobby: String, rating: u64 },
+    /// Tells the chain `owner` joined the queue from that they were paired with `opponent`.
+    MatchFound {
+        match_id: u32,
+        owner: Owner,
+        opponent: Owner,
+        seed: u16,
+    },
+    /// Challenges the players of the receiving chain, on behalf of the authenticated signer.
+    Challenge { challenge_id: u32, seed: u16 },
+    /// Tells the challenger's chain the authenticated signer accepted the challenge.
+    ChallengeAccepted { challenge_id: u32 },
+    /// Tells the challenger's chain the final score of the opponent's game.
+    ChallengeResult { challenge_id: u32, score: u64 },
+    /// Tells the opponent's chain the outcome of a challenge, `winner` being `None` for a draw.
+    ChallengeSettled {
+        challenge_id: u32,
+        challenger_score: u64,
+        opponent_score: u64,
+        winner: Option<Owner>,
+    },
+    /// Asks the chain a game is played on for a snapshot of it and its turns from then on.
+    Subscribe { game_id: u16 },
+    /// Asks the chain a game is played on to stop sending its turns.
+    Unsubscribe { game_id: u16 },
+    /// The state of a game played on the sending chain as of turn `move_index`, sent to
+    /// spectators when they subscribe and when the game ends without a turn.
+    Snapshot {
+        game_id: u16,
+        move_index: u32,
+        board: u64,
+        score: u64,
+        is_ended: bool,
+    },
+    /// Submits the replay of the authenticated signer to the dispute of match `match_id`,
+    /// sent to the matchmaking chain with the escrow holding the dispute bond on the sending
+    /// chain.
+    DisputeMatch {
+        match_id: u32,
+        replay: Vec<u8>,
+        claimed_score: u64,
+        bond_escrow: Option<u64>,
+    },
+    /// Challenges the replay of `player` in the dispute of match `match_id` on behalf of the
+    /// authenticated signer, sent to the matchmaking chain with the escrow holding the
+    /// challenge bond on the sending chain.
+    ChallengeDisputeResult {
+        match_id: u32,
+        player: Owner,
+        bond_escrow: Option<u64>,
+    },
+    /// Asks the chain the game of `player` in match `match_id` was played on whether its
+    /// board after `move_count` moves is `board`.
+    CheckMatchGame {
+        match_id: u32,
+        player: Owner,
+        move_count: u32,
+        board: u64,
+    },
+    /// Answers `CheckMatchGame`, `matches` being `None` if the game is not known anymore.
+    MatchGameChecked {
+        match_id: u32,
+        player: Owner,
+        matches: Option<bool>,
+    },
+    /// Publishes the chat key of the authenticated signer, sent to the matchmaking chain.
+    ChatKey { key: Vec<u8> },
+    /// Posts to the chat of match `match_id` on behalf of the authenticated signer, sent to
+    /// the matchmaking chain.
+    Chat { match_id: u32, payload: ChatPayload },
+    /// Mints the reward of a game of `owner` that ended on the sending chain, sent to the
+    /// chain the reward token was created on.
+    MintReward { owner: Owner },
+    /// Settles the bond escrow `escrow_id` of this chain for `recipient`, burning the bond if
+    /// `None`, sent by the matchmaking chain as it pays out a dispute.
+    SettleBond {
+        escrow_id: u64,
+        recipient: Option<Account>,
+    },
 }
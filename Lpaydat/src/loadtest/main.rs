@@ -0,0 +1,323 @@
+//! Drives load-test scenarios against a deployment of the 2048 application, so operators can
+//! size chains before launching a campaign.
+//!
+//! Simulated players go through the node service, the client of the Linera SDK that frontends
+//! use, which must run with a wallet owning the chains given. Each player starts games on one
+//! of them and plays at a steady rate, picking its moves with `predictMove` before submitting
+//! them with `makeMove`. With tournaments, a template is created for each and the players are
+//! split between them. Free games are sandboxed, so they never reach the leaderboards.
+//!
+//! A scenario is a sequence of stages, run one after the other. The latency of every request
+//! and the errors returned are reported by kind of request at the end of each stage.
+
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+mod report;
+
+#[cfg(not(target_arch = "wasm32"))]
+use {
+    self::report::Metrics,
+    anyhow::{bail, Context},
+    clap::{Args, Parser},
+    serde::Deserialize,
+    serde_json::{json, Value},
+    std::{
+        path::PathBuf,
+        sync::{
+            atomic::{AtomicU16, Ordering},
+            Arc,
+        },
+        time::Duration,
+    },
+    tokio::time::{Instant, MissedTickBehavior},
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Parser)]
+struct Options {
+    /// URL of the node service.
+    #[arg(long, default_value = "http://localhost:8080")]
+    node_service: String,
+    /// ID of the game application.
+    #[arg(long)]
+    application_id: String,
+    /// Chain the players are spread over, repeated for several.
+    #[arg(long = "chain", required = true)]
+    chains: Vec<String>,
+    /// JSON file with the list of stages to run, instead of the single stage of the options
+    /// below, e.g. `[{"players": 10, "movesPerMinute": 30, "tournaments": 0,
+    /// "durationSecs": 60}]`.
+    #[arg(long)]
+    scenario: Option<PathBuf>,
+    #[command(flatten)]
+    stage: Stage,
+}
+
+/// A period of steady load.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Args, Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Stage {
+    /// Number of simulated players.
+    #[arg(long, default_value_t = 10)]
+    players: u32,
+    /// Moves each player submits per minute.
+    #[arg(long, default_value_t = 30)]
+    moves_per_minute: u32,
+    /// Number of tournaments played at the same time, `0` for free games only.
+    #[arg(long, default_value_t = 0)]
+    tournaments: u32,
+    /// How long the stage lasts, in seconds.
+    #[arg(long, default_value_t = 60)]
+    duration_secs: u64,
+}
+
+/// The deployment under test.
+#[cfg(not(target_arch = "wasm32"))]
+struct Target {
+    client: reqwest::Client,
+    node_service: String,
+    application_id: String,
+    chains: Vec<String>,
+    /// Seed of the next game, which is also its ID.
+    next_seed: AtomicU16,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Target {
+    /// Sends a GraphQL request to the application on `chain`, recording its latency and
+    /// error in `metrics` as a request of `kind`, and returns its data.
+    async fn request(
+        &self,
+        metrics: &Metrics,
+        kind: &'static str,
+        chain: &str,
+        query: &str,
+    ) -> anyhow::Result<Value> {
+        let started = Instant::now();
+        let result = self.graphql(chain, query).await;
+        metrics.record(kind, started.elapsed(), result.as_ref().err());
+        result
+    }
+
+    async fn graphql(&self, chain: &str, query: &str) -> anyhow::Result<Value> {
+        let url = format!(
+            "{}/chains/{chain}/applications/{}",
+            self.node_service, self.application_id
+        );
+        let response: Value = self
+            .client
+            .post(&url)
+            .json(&json!({ "query": query }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if let Some(errors) = response.get("errors") {
+            bail!("GraphQL request failed: {errors}");
+        }
+        Ok(response["data"].clone())
+    }
+
+    /// Returns the seed of a new game. Seed `0` is skipped, as it asks for a random seed.
+    fn next_seed(&self) -> u16 {
+        loop {
+            let seed = self.next_seed.fetch_add(1, Ordering::Relaxed);
+            if seed != 0 {
+                return seed;
+            }
+        }
+    }
+
+    /// Creates the template of a tournament on `chain`, returning its ID.
+    ///
+    /// Template IDs are not returned by the contract, so the first unused one is looked up
+    /// beforehand: tournaments must not be created on `chain` by others during the run.
+    async fn create_tournament(
+        &self,
+        metrics: &Metrics,
+        chain: &str,
+        index: u32,
+    ) -> anyhow::Result<u32> {
+        let mut template_id = 0;
+        loop {
+            let query =
+                format!("query {{ templateResults(templateId: {template_id}) {{ templateId }} }}");
+            let response = self
+                .request(metrics, "templateResults", chain, &query)
+                .await?;
+            if response["templateResults"].is_null() {
+                break;
+            }
+            template_id += 1;
+        }
+        let mutation = format!(
+            "mutation {{ createTemplate(template: {{ name: \"Load test {index}\", \
+             mode: \"Classic\", boardSize: 4, difficulty: \"Normal\" }}) }}"
+        );
+        self.request(metrics, "createTemplate", chain, &mutation)
+            .await
+            .with_context(|| format!("Failed to create tournament {index} on chain {chain}"))?;
+        Ok(template_id)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+const DIRECTIONS: [&str; 4] = ["Left", "Up", "Right", "Down"];
+
+/// A simulated player.
+#[cfg(not(target_arch = "wasm32"))]
+struct Player {
+    target: Arc<Target>,
+    metrics: Arc<Metrics>,
+    chain: String,
+    /// The template of the tournament the player takes part in, if any.
+    template_id: Option<u32>,
+    /// Index in [`DIRECTIONS`] of the last direction played.
+    direction: usize,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Player {
+    /// Plays games until `deadline`, sending a request every `period`.
+    async fn play(mut self, deadline: Instant, period: Duration) {
+        let mut ticks = tokio::time::interval(period);
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut game_id = None;
+        loop {
+            ticks.tick().await;
+            if Instant::now() >= deadline {
+                return;
+            }
+            match game_id {
+                None => game_id = self.start_game().await,
+                Some(id) => {
+                    if !self.make_move(id).await {
+                        game_id = None;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Starts a game, returning its ID.
+    async fn start_game(&self) -> Option<u16> {
+        let seed = self.target.next_seed();
+        let (kind, mutation) = match self.template_id {
+            Some(template_id) => (
+                "newTemplateGame",
+                format!("mutation {{ newTemplateGame(templateId: {template_id}, seed: {seed}) }}"),
+            ),
+            None => (
+                "newGame",
+                format!("mutation {{ newGame(seed: {seed}, sandbox: true) }}"),
+            ),
+        };
+        self.request(kind, &mutation).await.map(|_| seed)
+    }
+
+    /// Plays the first direction changing the board, starting from the one after the last
+    /// played. Returns whether the game goes on.
+    async fn make_move(&mut self, game_id: u16) -> bool {
+        for _ in 0..DIRECTIONS.len() {
+            self.direction = (self.direction + 1) % DIRECTIONS.len();
+            let direction = DIRECTIONS[self.direction];
+            let query = format!(
+                "query {{ predictMove(gameId: {game_id}, direction: \"{direction}\") \
+                 {{ willEnd }} }}"
+            );
+            let Some(prediction) = self.request("predictMove", &query).await else {
+                return true;
+            };
+            let prediction = &prediction["predictMove"];
+            if prediction.is_null() {
+                continue;
+            }
+            let mutation =
+                format!("mutation {{ makeMove(gameId: {game_id}, direction: \"{direction}\") }}");
+            let moved = self.request("makeMove", &mutation).await.is_some();
+            return !moved || prediction["willEnd"] != json!(true);
+        }
+        false
+    }
+
+    async fn request(&self, kind: &'static str, query: &str) -> Option<Value> {
+        self.target
+            .request(&self.metrics, kind, &self.chain, query)
+            .await
+            .ok()
+    }
+}
+
+/// Runs `stage` against `target`, returning the metrics of its requests.
+#[cfg(not(target_arch = "wasm32"))]
+async fn run(target: &Arc<Target>, stage: &Stage) -> anyhow::Result<Metrics> {
+    let metrics = Arc::new(Metrics::default());
+    let mut tournaments = Vec::new();
+    for index in 0..stage.tournaments {
+        let chain = &target.chains[index as usize % target.chains.len()];
+        let template_id = target.create_tournament(&metrics, chain, index).await?;
+        tournaments.push((chain.clone(), template_id));
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(stage.duration_secs);
+    let period = Duration::from_secs(60) / stage.moves_per_minute.max(1);
+    let mut players = Vec::new();
+    for index in 0..stage.players as usize {
+        let (chain, template_id) = match tournaments.get(index % tournaments.len().max(1)) {
+            Some((chain, template_id)) => (chain.clone(), Some(*template_id)),
+            None => (target.chains[index % target.chains.len()].clone(), None),
+        };
+        let player = Player {
+            target: target.clone(),
+            metrics: metrics.clone(),
+            chain,
+            template_id,
+            direction: index % DIRECTIONS.len(),
+        };
+        players.push(tokio::spawn(player.play(deadline, period)));
+    }
+    for player in players {
+        player.await?;
+    }
+    Ok(Arc::into_inner(metrics).expect("Players are done"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let options = Options::parse();
+    let stages: Vec<Stage> = match &options.scenario {
+        Some(path) => {
+            let scenario = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            serde_json::from_str(&scenario)
+                .with_context(|| format!("Invalid scenario in {}", path.display()))?
+        }
+        None => vec![options.stage],
+    };
+    let target = Arc::new(Target {
+        client: reqwest::Client::builder()
+            .timeout(Duration::from_secs(60))
+            .build()?,
+        node_service: options.node_service,
+        application_id: options.application_id,
+        chains: options.chains,
+        next_seed: AtomicU16::new(1),
+    });
+
+    for (index, stage) in stages.iter().enumerate() {
+        println!(
+            "Stage {}: {} players, {} moves per minute, {} tournaments, for {} s",
+            index + 1,
+            stage.players,
+            stage.moves_per_minute,
+            stage.tournaments,
+            stage.duration_secs,
+        );
+        let metrics = run(&target, stage).await?;
+        println!("{metrics}");
+    }
+    Ok(())
+}
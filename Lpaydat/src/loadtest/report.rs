@@ -0,0 +1,88 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::{collections::BTreeMap, fmt, sync::Mutex, time::Duration};
+
+/// Upper bounds of the latency buckets, in milliseconds. Slower requests land in a last,
+/// unbounded bucket.
+const BUCKETS_MS: [u64; 12] = [
+    5, 10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 30_000,
+];
+
+/// Width of the longest bar of a histogram, in characters.
+const BAR_WIDTH: u64 = 40;
+
+/// Latencies and errors of the requests of one kind.
+#[derive(Default)]
+struct Stats {
+    /// Latencies of all requests, including failed ones.
+    latencies: Vec<Duration>,
+    /// Number of failed requests, by error message.
+    errors: BTreeMap<String, u64>,
+}
+
+/// Latencies and errors of the requests sent during a stage, by kind of request.
+#[derive(Default)]
+pub struct Metrics {
+    stats: Mutex<BTreeMap<&'static str, Stats>>,
+}
+
+impl Metrics {
+    /// Records a request of `kind` answered after `latency`, with the error it failed with.
+    pub fn record(&self, kind: &'static str, latency: Duration, error: Option<&anyhow::Error>) {
+        let mut stats = self.stats.lock().unwrap();
+        let stats = stats.entry(kind).or_default();
+        stats.latencies.push(latency);
+        if let Some(error) = error {
+            let mut message = format!("{error:#}");
+            if let Some((end, _)) = message.char_indices().nth(160) {
+                message.truncate(end);
+                message.push('…');
+            }
+            *stats.errors.entry(message).or_default() += 1;
+        }
+    }
+}
+
+impl fmt::Display for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let stats = self.stats.lock().unwrap();
+        if stats.is_empty() {
+            return writeln!(f, "No requests were sent.");
+        }
+        for (kind, stats) in stats.iter() {
+            let mut latencies = stats.latencies.clone();
+            latencies.sort();
+            let percentile = |percent: usize| latencies[(latencies.len() - 1) * percent / 100];
+            let failed: u64 = stats.errors.values().sum();
+            writeln!(
+                f,
+                "{kind}: {} requests, {failed} failed, p50 {:?}, p90 {:?}, p99 {:?}, max {:?}",
+                latencies.len(),
+                percentile(50),
+                percentile(90),
+                percentile(99),
+                percentile(100),
+            )?;
+
+            let mut counts = [0; BUCKETS_MS.len() + 1];
+            for latency in &latencies {
+                let millis = latency.as_millis() as u64;
+                let bucket = BUCKETS_MS.iter().position(|&bound| millis <= bound);
+                counts[bucket.unwrap_or(BUCKETS_MS.len())] += 1;
+            }
+            let highest = counts.iter().copied().max().unwrap_or(0).max(1);
+            for (index, count) in counts.iter().enumerate() {
+                let label = match BUCKETS_MS.get(index) {
+                    Some(bound) => format!("<= {bound} ms"),
+                    None => format!("> {} ms", BUCKETS_MS[BUCKETS_MS.len() - 1]),
+                };
+                let bar = "#".repeat((count * BAR_WIDTH).div_ceil(highest) as usize);
+                writeln!(f, "  {label:>11} | {bar} {count}")?;
+            }
+            for (message, count) in &stats.errors {
+                writeln!(f, "  {count} x {message}")?;
+            }
+        }
+        Ok(())
+    }
+}
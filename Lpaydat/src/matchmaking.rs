@@ -0,0 +1,148 @@
+use async_graphql::{InputObject, SimpleObject};
+use linera_sdk::base::{ChainId, Owner, Timestamp};
+use serde::{Deserialize, Serialize};
+
+/// Rating difference players accept as soon as they join the queue.
+pub const MATCH_WINDOW_BASE: u64 = 500;
+/// How much the accepted rating difference grows for every second spent in the queue.
+pub const MATCH_WINDOW_GROWTH_PER_SEC: u64 = 20;
+
+/// The lobby players join with `JoinMatchQueue`, open to every rating.
+pub const DEFAULT_LOBBY: &str = "global";
+
+/// A named lobby, such as a region or a skill bracket. Players are only paired with players
+/// of the same lobby.
+#[derive(
+    Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SimpleObject, InputObject,
+)]
+#[graphql(input_name = "LobbyInput")]
+pub struct Lobby {
+    pub description: String,
+    /// Lowest rating admitted.
+    pub min_rating: u64,
+    /// Highest rating admitted, if any.
+    pub max_rating: Option<u64>,
+}
+
+impl Lobby {
+    /// Returns whether players rated `rating` may join the lobby.
+    ///
+    /// ```
+    /// use game2048::Lobby;
+    ///
+    /// let bracket = Lobby { description: "Rookies".to_string(), min_rating: 0, max_rating: Some(2_000) };
+    /// assert!(bracket.admits(2_000));
+    /// assert!(!bracket.admits(2_001));
+    /// ```
+    pub fn admits(&self, rating: u64) -> bool {
+        rating >= self.min_rating && self.max_rating.map_or(true, |max| rating <= max)
+    }
+}
+
+/// The matches made in a lobby, for capacity planning.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct LobbyStats {
+    pub matches: u32,
+    /// Seconds the matched players spent in the queue, summed over all of them.
+    pub total_wait_secs: u64,
+}
+
+/// A player waiting for an opponent on the matchmaking chain.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct QueueEntry {
+    pub owner: Owner,
+    pub lobby: String,
+    /// The chain the player joined from, notified once a match is found.
+    pub chain_id: ChainId,
+    /// The player's best score when they joined.
+    pub rating: u64,
+    pub enqueued_at: Timestamp,
+}
+
+impl QueueEntry {
+    /// Returns the rating difference the player accepts at `now`, widening the longer they
+    /// wait.
+    ///
+    /// ```
+    /// use game2048::{QueueEntry, MATCH_WINDOW_BASE, MATCH_WINDOW_GROWTH_PER_SEC};
+    /// use linera_sdk::base::{ChainId, CryptoHash, Owner, Timestamp};
+    ///
+    /// let entry = QueueEntry {
+    ///     owner: Owner(CryptoHash::test_hash("player")),
+    ///     lobby: "global".to_string(),
+    ///     chain_id: ChainId::root(0),
+    ///     rating: 1_000,
+    ///     enqueued_at: Timestamp::from(0),
+    /// };
+    /// assert_eq!(entry.window(Timestamp::from(0)), MATCH_WINDOW_BASE);
+    /// assert_eq!(
+    ///     entry.window(Timestamp::from(10_000_000)),
+    ///     MATCH_WINDOW_BASE + 10 * MATCH_WINDOW_GROWTH_PER_SEC
+    /// );
+    /// ```
+    pub fn window(&self, now: Timestamp) -> u64 {
+        let waited_secs = self.waited_secs(now);
+        MATCH_WINDOW_BASE.saturating_add(waited_secs.saturating_mul(MATCH_WINDOW_GROWTH_PER_SEC))
+    }
+
+    /// Returns how long the player has waited at `now`, in whole seconds.
+    pub fn waited_secs(&self, now: Timestamp) -> u64 {
+        now.delta_since(self.enqueued_at).as_micros() / 1_000_000
+    }
+
+    /// Returns whether both players accept each other's rating at `now`.
+    pub fn is_compatible(&self, other: &QueueEntry, now: Timestamp) -> bool {
+        let difference = self.rating.abs_diff(other.rating);
+        difference <= self.window(now) && difference <= other.window(now)
+    }
+
+    /// Returns how many seconds after `now` both players accept each other's rating.
+    ///
+    /// ```
+    /// use game2048::{QueueEntry, MATCH_WINDOW_BASE, MATCH_WINDOW_GROWTH_PER_SEC};
+    /// use linera_sdk::base::{ChainId, CryptoHash, Owner, Timestamp};
+    ///
+    /// let entry = |name, rating, enqueued_secs: u64| QueueEntry {
+    ///     owner: Owner(CryptoHash::test_hash(name)),
+    ///     lobby: "global".to_string(),
+    ///     chain_id: ChainId::root(0),
+    ///     rating,
+    ///     enqueued_at: Timestamp::from(enqueued_secs * 1_000_000),
+    /// };
+    /// let now = Timestamp::from(30_000_000);
+    /// let veteran = entry("veteran", 0, 0);
+    /// let newcomer = entry("newcomer", MATCH_WINDOW_BASE + 40 * MATCH_WINDOW_GROWTH_PER_SEC, 30);
+    /// assert!(!veteran.is_compatible(&newcomer, now));
+    /// // The window of the player who joined last decides.
+    /// assert_eq!(veteran.seconds_until_compatible(&newcomer, now), 40);
+    /// assert!(veteran.is_compatible(&newcomer, Timestamp::from(70_000_000)));
+    /// ```
+    pub fn seconds_until_compatible(&self, other: &QueueEntry, now: Timestamp) -> u64 {
+        let difference = self.rating.abs_diff(other.rating);
+        let needed_secs = difference
+            .saturating_sub(MATCH_WINDOW_BASE)
+            .div_ceil(MATCH_WINDOW_GROWTH_PER_SEC);
+        let latest = self.enqueued_at.max(other.enqueued_at);
+        let waited_secs = now.delta_since(latest).as_micros() / 1_000_000;
+        needed_secs.saturating_sub(waited_secs)
+    }
+}
+
+/// Two players paired by the matchmaking chain, who play games started from the same seed.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct MatchRecord {
+    pub match_id: u32,
+    pub lobby: String,
+    pub players: Vec<Owner>,
+    pub seed: u16,
+    pub created_at: Timestamp,
+}
+
+/// A match as seen from the chain of one of its players.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct MatchNotice {
+    pub match_id: u32,
+    pub opponent: Owner,
+    /// The game started for the match, `None` if the player had no free game slot.
+    pub game_id: Option<u16>,
+}
@@ -0,0 +1,96 @@
+use crate::Operation;
+use async_graphql::scalar;
+use serde::{Deserialize, Serialize};
+
+/// Why an operation was dropped without effect.
+///
+/// Operations rejected with an error are not counted: they abort their whole block, which
+/// leaves no trace in the state.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum OperationFailure {
+    /// The move does not change the board.
+    UselessMove,
+    /// The game was already over.
+    GameEnded,
+    /// The game was paused by its owner.
+    GamePaused,
+    /// The time limit of the game was over: the game ended instead.
+    TimeExpired,
+    /// The move is not the one the current tutorial step teaches.
+    WrongTutorialMove,
+}
+
+scalar!(OperationFailure);
+
+impl Operation {
+    /// Returns the name of the variant, which operations are counted by.
+    ///
+    /// ```
+    /// use game2048::Operation;
+    ///
+    /// assert_eq!(Operation::JoinMatchQueue.name(), "JoinMatchQueue");
+    /// ```
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operation::NewGame { .. } => "NewGame",
+            Operation::EndGame { .. } => "EndGame",
+            Operation::MakeMove { .. } => "MakeMove",
+            Operation::CreateCommunity { .. } => "CreateCommunity",
+            Operation::ConfigureCommunity { .. } => "ConfigureCommunity",
+            Operation::JoinCommunity { .. } => "JoinCommunity",
+            Operation::LeaveCommunity { .. } => "LeaveCommunity",
+            Operation::CreateBeaconRound { .. } => "CreateBeaconRound",
+            Operation::CommitEntropy { .. } => "CommitEntropy",
+            Operation::RevealEntropy { .. } => "RevealEntropy",
+            Operation::CloseBeaconRound { .. } => "CloseBeaconRound",
+            Operation::RequestAccountMerge { .. } => "RequestAccountMerge",
+            Operation::ApproveAccountMerge { .. } => "ApproveAccountMerge",
+            Operation::SetActiveGameLimit { .. } => "SetActiveGameLimit",
+            Operation::RequestGame { .. } => "RequestGame",
+            Operation::StartGame { .. } => "StartGame",
+            Operation::CreateTemplate { .. } => "CreateTemplate",
+            Operation::NewTemplateGame { .. } => "NewTemplateGame",
+            Operation::MintJoinCode { .. } => "MintJoinCode",
+            Operation::JoinWithCode { .. } => "JoinWithCode",
+            Operation::SetItemPrice { .. } => "SetItemPrice",
+            Operation::SetTreasury { .. } => "SetTreasury",
+            Operation::BuyItem { .. } => "BuyItem",
+            Operation::ConfigureSeasonPass { .. } => "ConfigureSeasonPass",
+            Operation::BuySeasonPass { .. } => "BuySeasonPass",
+            Operation::SetAttester { .. } => "SetAttester",
+            Operation::SubmitAttestation { .. } => "SubmitAttestation",
+            Operation::SetLeaderboardFilters { .. } => "SetLeaderboardFilters",
+            Operation::JoinMatchQueue => "JoinMatchQueue",
+            Operation::LeaveMatchQueue => "LeaveMatchQueue",
+            Operation::CreateLobby { .. } => "CreateLobby",
+            Operation::JoinLobbyQueue { .. } => "JoinLobbyQueue",
+            Operation::Undo { .. } => "Undo",
+            Operation::Challenge { .. } => "Challenge",
+            Operation::AcceptChallenge { .. } => "AcceptChallenge",
+            Operation::Spectate { .. } => "Spectate",
+            Operation::StopSpectating { .. } => "StopSpectating",
+            Operation::SponsorGames { .. } => "SponsorGames",
+            Operation::WithdrawSponsorship { .. } => "WithdrawSponsorship",
+            Operation::DisputeMatch { .. } => "DisputeMatch",
+            Operation::ResolveDispute { .. } => "ResolveDispute",
+            Operation::ChallengeDisputeResult { .. } => "ChallengeDisputeResult",
+            Operation::FinalizeDispute { .. } => "FinalizeDispute",
+            Operation::CreateTournament { .. } => "CreateTournament",
+            Operation::JoinTournament { .. } => "JoinTournament",
+            Operation::SubmitResult { .. } => "SubmitResult",
+            Operation::CloseTournament { .. } => "CloseTournament",
+            Operation::VerifyGame { .. } => "VerifyGame",
+            Operation::ConfigureMode { .. } => "ConfigureMode",
+            Operation::SetTournamentEnd { .. } => "SetTournamentEnd",
+            Operation::EndTournamentGames { .. } => "EndTournamentGames",
+            Operation::NewDailyGame => "NewDailyGame",
+            Operation::PlayTutorial { .. } => "PlayTutorial",
+            Operation::PauseGame { .. } => "PauseGame",
+            Operation::ResumeGame { .. } => "ResumeGame",
+            Operation::SetChatKey { .. } => "SetChatKey",
+            Operation::SendChat { .. } => "SendChat",
+            Operation::Batch(_) => "Batch",
+            Operation::ConfirmAccountMerge { .. } => "ConfirmAccountMerge",
+        }
+    }
+}
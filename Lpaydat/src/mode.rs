@@ -0,0 +1,105 @@
+use crate::{ComboMultiplier, Game, MergeSum, ScorePolicy, TimeBonus};
+use async_graphql::{scalar, InputObject, SimpleObject};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// The rule set a game is played under.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum GameMode {
+    /// The original game: merge tiles until reaching 2048 or running out of moves.
+    #[default]
+    Classic,
+    /// Merges made early in the game earn a time bonus.
+    Blitz,
+    /// Play continues past 2048, rewarding streaks of merging moves.
+    Endless,
+}
+
+impl GameMode {
+    /// Returns the policy used to score games played in this mode.
+    pub fn score_policy(&self) -> &'static dyn ScorePolicy {
+        match self {
+            GameMode::Classic => &MergeSum,
+            GameMode::Blitz => &TimeBonus {
+                window_micros: 5 * 60 * 1_000_000,
+            },
+            GameMode::Endless => &ComboMultiplier { max_multiplier: 4 },
+        }
+    }
+
+    /// Returns whether a game played in this mode is over.
+    pub fn is_ended(&self, board: u64) -> bool {
+        match self {
+            GameMode::Classic | GameMode::Blitz => Game::is_ended(board),
+            GameMode::Endless => Game::is_stuck(board),
+        }
+    }
+}
+
+scalar!(GameMode);
+
+/// How much of the move log of a game is kept on chain. Turns are published to the hub and
+/// spectators whether they are kept or not.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum HistoryRetention {
+    /// No turn is kept.
+    None,
+    /// Only the last turns are kept, at most this many.
+    LastTurns(u32),
+    /// Every turn whose index is a multiple of the interval is kept as a checkpoint, along
+    /// with the turns played since the last checkpoint. Intervals of `0` and `1` keep every
+    /// turn.
+    Checkpointed(u32),
+}
+
+impl Default for HistoryRetention {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+scalar!(HistoryRetention);
+
+impl HistoryRetention {
+    /// Keeps every turn, so that games can be replayed and verified.
+    pub const FULL: Self = HistoryRetention::Checkpointed(1);
+
+    /// Returns the turns no longer kept once turn `turn_index` is logged, the turn itself
+    /// included if it is not kept at all.
+    ///
+    /// ```
+    /// use game2048::HistoryRetention;
+    ///
+    /// assert_eq!(HistoryRetention::None.dropped_by(4), 4..5);
+    /// assert_eq!(HistoryRetention::LastTurns(3).dropped_by(2), 0..0);
+    /// assert_eq!(HistoryRetention::LastTurns(3).dropped_by(4), 1..2);
+    /// assert_eq!(HistoryRetention::Checkpointed(5).dropped_by(9), 0..0);
+    /// assert_eq!(HistoryRetention::Checkpointed(5).dropped_by(10), 6..10);
+    /// assert!(HistoryRetention::FULL.dropped_by(10).is_empty());
+    /// ```
+    pub fn dropped_by(&self, turn_index: u32) -> Range<u32> {
+        match *self {
+            HistoryRetention::None | HistoryRetention::LastTurns(0) => turn_index..turn_index + 1,
+            HistoryRetention::LastTurns(count) => match turn_index.checked_sub(count) {
+                Some(dropped) => dropped..dropped + 1,
+                None => 0..0,
+            },
+            HistoryRetention::Checkpointed(interval)
+                if interval > 1 && turn_index >= interval && turn_index % interval == 0 =>
+            {
+                turn_index + 1 - interval..turn_index
+            }
+            HistoryRetention::Checkpointed(_) => 0..0,
+        }
+    }
+}
+
+/// The settings of a game mode, applied to the games created in it.
+#[derive(
+    Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SimpleObject, InputObject,
+)]
+#[graphql(input_name = "ModeSettingsInput")]
+pub struct ModeSettings {
+    /// How much of the move log of the games is kept.
+    pub history_retention: HistoryRetention,
+}
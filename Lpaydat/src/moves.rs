@@ -6,6 +6,8 @@ pub static ROW_MASK: u64 = 0xFFFF;
 /// Used to extract a "vertical slice" out of a 64 bit integer.
 pub static COL_MASK: u64 = 0x000F_000F_000F_000F_u64;
 
+use std::fmt;
+
 /// Struct that contains all available moves per row for up, down, right and left.
 /// Also stores the score for a given row.
 ///
@@ -30,6 +32,9 @@ impl Moves {
     ///
     /// `Moves` stores `right`, `left`, `up`, and `down` moves per row.
     /// Also stores the `scores` per row.
+    ///
+    /// This is the only implementation of the tables: the ones `Game` plays with are
+    /// generated from it by `scripts/precompute_moves.rs`.
     pub fn new() -> Moves {
         // initialization of move tables
         let mut left_moves = vec![0; 65536];
@@ -58,36 +63,8 @@ impl Moves {
 
             scores[row as usize] = s;
 
-            let mut i = 0;
-
             // perform a move to the left using current {row} as board
-            while i < 3 {
-                let mut j = i + 1;
-
-                while j < 4 {
-                    if line[j] != 0 {
-                        break;
-                    };
-                    j += 1;
-                }
-
-                if j == 4 {
-                    break;
-                };
-
-                if line[i] == 0 {
-                    line[i] = line[j];
-                    line[j] = 0;
-                    continue;
-                } else if line[i] == line[j] {
-                    if line[i] != 0xF {
-                        line[i] += 1
-                    };
-                    line[j] = 0;
-                }
-
-                i += 1;
-            }
+            Moves::slide_line(&mut line, 0xF);
 
             let result = (line[0]) | (line[1] << 4) | (line[2] << 8) | (line[3] << 12);
 
@@ -117,8 +94,149 @@ impl Moves {
             scores,
         }
     }
+
+    /// Checks the entries of `row` in these tables against `Moves::slide_line`.
+    ///
+    /// ```
+    /// use game2048::Moves;
+    ///
+    /// let mut moves = Moves::new();
+    /// assert!(moves.check_row(0x2211).is_ok());
+    /// moves.left[0x2211] ^= 1;
+    /// assert_eq!(moves.check_row(0x2211).unwrap_err().table, "left");
+    /// ```
+    pub fn check_row(&self, row: u16) -> Result<(), MoveTableMismatch> {
+        MoveTables {
+            left: &self.left,
+            right: &self.right,
+            down: &self.down,
+            up: &self.up,
+            scores: &self.scores,
+        }
+        .check_row(row)
+    }
+
+    /// Slides the tiles of `line` towards its first cell, equal neighbors merging once.
+    /// Cells hold tile exponents, `0` for empty cells; two tiles of `max_exponent` merge into
+    /// a single one of the same exponent.
+    ///
+    /// Rows of any width move this way: the tables slide 4-cell rows with it, and
+    /// `BoardRepr::slide` the rows and columns of larger boards.
+    ///
+    /// ```
+    /// use game2048::Moves;
+    ///
+    /// let mut line = [0, 2, 2, 3, 3];
+    /// Moves::slide_line(&mut line, 0x1F);
+    /// assert_eq!(line, [3, 4, 0, 0, 0]);
+    /// ```
+    pub fn slide_line(line: &mut [u64], max_exponent: u64) {
+        let mut i = 0;
+
+        while i + 1 < line.len() {
+            let mut j = i + 1;
+
+            while j < line.len() {
+                if line[j] != 0 {
+                    break;
+                };
+                j += 1;
+            }
+
+            if j == line.len() {
+                break;
+            };
+
+            if line[i] == 0 {
+                line[i] = line[j];
+                line[j] = 0;
+                continue;
+            } else if line[i] == line[j] {
+                if line[i] != max_exponent {
+                    line[i] += 1
+                };
+                line[j] = 0;
+            }
+
+            i += 1;
+        }
+    }
 }
 
+/// Borrowed move tables, in the layout of `Moves`, to check against the row-merge algorithm.
+pub(crate) struct MoveTables<'a> {
+    pub left: &'a [u64],
+    pub right: &'a [u64],
+    pub down: &'a [u64],
+    pub up: &'a [u64],
+    pub scores: &'a [u64],
+}
+
+impl MoveTables<'_> {
+    /// Checks the entries of `row` in every table against the ones `Moves::slide_line`
+    /// yields for it, returning the first that differs.
+    pub(crate) fn check_row(&self, row: u16) -> Result<(), MoveTableMismatch> {
+        let row = u64::from(row);
+        let cells = [0, 4, 8, 12].map(|shift| (row >> shift) & 0xF);
+
+        let mut line = cells;
+        Moves::slide_line(&mut line, 0xF);
+        let right = line[0] | (line[1] << 4) | (line[2] << 8) | (line[3] << 12);
+        let mut line = [cells[3], cells[2], cells[1], cells[0]];
+        Moves::slide_line(&mut line, 0xF);
+        let left = line[3] | (line[2] << 4) | (line[1] << 8) | (line[0] << 12);
+        let score = cells
+            .iter()
+            .filter(|&&tile| tile > 1)
+            .map(|&tile| (tile - 1) * (2 << tile))
+            .sum();
+
+        let column = Moves::column_from(row);
+        let expected = [
+            ("left", self.left, row ^ left),
+            ("right", self.right, row ^ right),
+            ("down", self.down, column ^ Moves::column_from(right)),
+            ("up", self.up, column ^ Moves::column_from(left)),
+            ("scores", self.scores, score),
+        ];
+        for (table, entries, expected) in expected {
+            let found = entries[row as usize];
+            if found != expected {
+                return Err(MoveTableMismatch {
+                    table,
+                    row: row as u16,
+                    expected,
+                    found,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An entry of a move table that disagrees with the row-merge algorithm, typically because
+/// `moves_data.rs` is stale or corrupted.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MoveTableMismatch {
+    pub table: &'static str,
+    pub row: u16,
+    pub expected: u64,
+    pub found: u64,
+}
+
+impl fmt::Display for MoveTableMismatch {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "Move table `{}` holds {:#x} for row {:#06x} instead of {:#x}: regenerate \
+             moves_data.rs with scripts/precompute_moves.rs",
+            self.table, self.found, self.row, self.expected
+        )
+    }
+}
+
+impl std::error::Error for MoveTableMismatch {}
+
 impl Default for Moves {
     fn default() -> Self {
         Moves::new()
@@ -0,0 +1,79 @@
+use crate::GameMode;
+use async_graphql::{InputObject, SimpleObject};
+use linera_sdk::base::{Amount, Owner, TimeDelta, Timestamp};
+use serde::{Deserialize, Serialize};
+
+/// Rewards of achievements are multiplied by this while a season pass is valid.
+pub const PASS_REWARD_MULTIPLIER: u32 = 2;
+
+/// What a season pass costs and unlocks, as configured by admins.
+#[derive(
+    Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SimpleObject, InputObject,
+)]
+#[graphql(input_name = "PassSettingsInput")]
+pub struct PassSettings {
+    /// Price of a pass, in native tokens paid to the treasury.
+    pub price: Amount,
+    /// How long a pass lasts, in seconds.
+    pub duration_secs: u64,
+    /// Modes only pass holders can start games in.
+    pub premium_modes: Vec<GameMode>,
+}
+
+/// A player's season pass. Buying one while a pass is still valid extends it.
+#[derive(
+    Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize, SimpleObject,
+)]
+pub struct SeasonPass {
+    pub expires_at: Timestamp,
+}
+
+impl SeasonPass {
+    /// Returns the pass a player holds after buying one at `now`, `current` being the pass
+    /// they held before, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::SeasonPass;
+    ///
+    /// let pass = SeasonPass::extend(None, 1_000.into(), 500);
+    /// assert_eq!(pass.expires_at.micros(), 1_000 + 500_000_000);
+    /// // Remaining time is kept, expired passes start over.
+    /// let extended = SeasonPass::extend(Some(pass), 2_000.into(), 500);
+    /// assert_eq!(extended.expires_at.micros(), 1_000 + 1_000_000_000);
+    /// let renewed = SeasonPass::extend(Some(pass), 600_000_000.into(), 500);
+    /// assert_eq!(renewed.expires_at.micros(), 1_100_000_000);
+    /// ```
+    pub fn extend(current: Option<SeasonPass>, now: Timestamp, duration_secs: u64) -> SeasonPass {
+        let start = current
+            .map(|pass| pass.expires_at)
+            .filter(|expires_at| *expires_at > now)
+            .unwrap_or(now);
+        SeasonPass {
+            expires_at: start.saturating_add(TimeDelta::from_secs(duration_secs)),
+        }
+    }
+
+    pub fn is_valid(&self, now: Timestamp) -> bool {
+        now < self.expires_at
+    }
+
+    /// Returns how much achievement rewards are multiplied by for the holder at `now`.
+    pub fn reward_multiplier(pass: Option<SeasonPass>, now: Timestamp) -> u32 {
+        match pass {
+            Some(pass) if pass.is_valid(now) => PASS_REWARD_MULTIPLIER,
+            _ => 1,
+        }
+    }
+}
+
+/// A pass sale, recorded for revenue reporting.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct PassSale {
+    pub buyer: Owner,
+    pub price: Amount,
+    pub sold_at: Timestamp,
+    /// When the buyer's pass expires after the sale.
+    pub expires_at: Timestamp,
+}
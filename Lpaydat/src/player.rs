@@ -0,0 +1,136 @@
+use async_graphql::SimpleObject;
+use linera_sdk::base::{Owner, Timestamp};
+use serde::{Deserialize, Serialize};
+
+/// Number of active games a player may have at once, unless admins configured another limit.
+pub const DEFAULT_ACTIVE_GAME_LIMIT: u32 = 10;
+
+/// Lifetime statistics of a player, across all of their games.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct PlayerStats {
+    pub games_played: u32,
+    pub moves_played: u32,
+    pub best_score: u64,
+}
+
+impl PlayerStats {
+    /// Combines the statistics of two accounts of the same player: counters are summed
+    /// and the best of each record is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::PlayerStats;
+    ///
+    /// let primary = PlayerStats { games_played: 3, moves_played: 400, best_score: 2_000 };
+    /// let duplicate = PlayerStats { games_played: 1, moves_played: 150, best_score: 5_000 };
+    ///
+    /// assert_eq!(
+    ///     primary.merge(&duplicate),
+    ///     PlayerStats { games_played: 4, moves_played: 550, best_score: 5_000 }
+    /// );
+    /// ```
+    pub fn merge(&self, other: &PlayerStats) -> PlayerStats {
+        PlayerStats {
+            games_played: self.games_played.saturating_add(other.games_played),
+            moves_played: self.moves_played.saturating_add(other.moves_played),
+            best_score: self.best_score.max(other.best_score),
+        }
+    }
+}
+
+/// Where a player's tiles spawn and where their largest tile ends up, across all of their
+/// games.
+///
+/// Both histograms count cells in the orientation of
+/// [`Game::convert_to_matrix`](crate::Game::convert_to_matrix): rows from top to bottom,
+/// columns from left to right.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct TileHeatmap {
+    /// Number of tiles spawned in each cell.
+    pub spawns: [[u32; 4]; 4],
+    /// Number of finished games whose largest tile ended in each cell. Games finishing with
+    /// several largest tiles count once in each of their cells.
+    pub max_tiles: [[u32; 4]; 4],
+}
+
+impl TileHeatmap {
+    /// Counts a spawn in every cell that is non-empty in `tiles`, a board holding only the
+    /// spawned tiles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::TileHeatmap;
+    ///
+    /// let mut heatmap = TileHeatmap::default();
+    /// heatmap.record_spawns(0x1000_0000_0000_0002);
+    ///
+    /// assert_eq!(heatmap.spawns[0], [1, 0, 0, 0]);
+    /// assert_eq!(heatmap.spawns[3], [0, 0, 0, 1]);
+    /// ```
+    pub fn record_spawns(&mut self, tiles: u64) {
+        for (row, col) in Self::cells(tiles, |exponent| exponent != 0) {
+            self.spawns[row][col] = self.spawns[row][col].saturating_add(1);
+        }
+    }
+
+    /// Counts the cells holding the largest tile of the final `board` of a game.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::TileHeatmap;
+    ///
+    /// let mut heatmap = TileHeatmap::default();
+    /// heatmap.record_max_tile(0x0000_0000_0B00_0021);
+    ///
+    /// assert_eq!(heatmap.max_tiles[2], [0, 1, 0, 0]);
+    /// assert_eq!(heatmap.max_tiles.iter().flatten().sum::<u32>(), 1);
+    /// ```
+    pub fn record_max_tile(&mut self, board: u64) {
+        let max = (0..16).map(|i| (board >> (i * 4)) & 0xF).max().unwrap_or(0);
+        if max == 0 {
+            return;
+        }
+        for (row, col) in Self::cells(board, |exponent| exponent == max) {
+            self.max_tiles[row][col] = self.max_tiles[row][col].saturating_add(1);
+        }
+    }
+
+    /// Sums the histograms of two accounts of the same player.
+    pub fn merge(&self, other: &TileHeatmap) -> TileHeatmap {
+        let mut merged = self.clone();
+        for row in 0..4 {
+            for col in 0..4 {
+                merged.spawns[row][col] =
+                    merged.spawns[row][col].saturating_add(other.spawns[row][col]);
+                merged.max_tiles[row][col] =
+                    merged.max_tiles[row][col].saturating_add(other.max_tiles[row][col]);
+            }
+        }
+        merged
+    }
+
+    /// The matrix coordinates of the cells of `board` whose exponent matches `filter`.
+    fn cells(board: u64, filter: impl Fn(u64) -> bool) -> impl Iterator<Item = (usize, usize)> {
+        (0..16)
+            .filter(move |i| filter((board >> (i * 4)) & 0xF))
+            .map(|i| (3 - i / 4, 3 - i % 4))
+    }
+}
+
+/// Audit record of a duplicate account merged into a primary one.
+#[derive(Clone, Debug, Deserialize, Serialize, SimpleObject)]
+pub struct AccountMerge {
+    pub duplicate: Owner,
+    pub primary: Owner,
+    /// The admin who approved the merge.
+    pub approved_by: Owner,
+    pub merged_at: Timestamp,
+    /// The games transferred to the primary account.
+    pub games: Vec<u16>,
+    /// The statistics of both accounts before the merge.
+    pub primary_stats: PlayerStats,
+    pub duplicate_stats: PlayerStats,
+}
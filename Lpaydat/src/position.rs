@@ -0,0 +1,82 @@
+use crate::{Direction, Game, GameMode, GameTemplate, Rng, ScoredMove};
+
+/// A game between two moves: everything the effects of its next move depend on.
+pub struct Position<'a> {
+    pub board: u64,
+    pub score: u64,
+    /// Number of consecutive moves that merged tiles.
+    pub combo: u32,
+    pub mode: GameMode,
+    /// The template the game was started from, if any, which decides when it ends.
+    pub template: Option<&'a GameTemplate>,
+}
+
+/// The effects of a move, as recorded by the contract.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MoveOutcome {
+    pub board: u64,
+    /// The tile spawned after the move, as a mask of the board bits it occupies.
+    pub spawned_tile: u64,
+    pub score: u64,
+    pub combo: u32,
+    pub is_ended: bool,
+}
+
+impl Position<'_> {
+    /// Plays `direction`, spawning the new tile from `rng`, `elapsed_micros` after the game
+    /// was created. Returns `None` if the move does not change the board.
+    ///
+    /// The contract applies moves with this function, so clients calling it with the generator
+    /// of the next spawn, see `Rng::for_spawn`, predict exactly what the contract will record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::{Direction, GameMode, Position, Rng};
+    ///
+    /// let position = Position {
+    ///     board: 0x0000_0000_0000_1100,
+    ///     score: 0,
+    ///     combo: 0,
+    ///     mode: GameMode::Classic,
+    ///     template: None,
+    /// };
+    ///
+    /// let outcome = position.play(Direction::Left, &mut Rng::new(1), 0).unwrap();
+    /// assert_eq!(outcome.board & 0xF000, 0x2000);
+    /// assert_eq!((outcome.score, outcome.combo), (8, 1));
+    /// assert!(position.play(Direction::Down, &mut Rng::new(1), 0).is_none());
+    /// ```
+    pub fn play(
+        &self,
+        direction: Direction,
+        rng: &mut Rng,
+        elapsed_micros: u64,
+    ) -> Option<MoveOutcome> {
+        if !Game::is_useful_move(self.board, direction.clone()) {
+            return None;
+        }
+        let moved_board = Game::move_board(self.board, direction.clone());
+        let board = Game::execute_with(self.board, direction, rng);
+        let merged = Game::score(board) > Game::score(self.board);
+        let combo = if merged { self.combo + 1 } else { 0 };
+        let score = self.mode.score_policy().score(&ScoredMove {
+            previous_board: self.board,
+            board,
+            previous_score: self.score,
+            combo,
+            elapsed_micros,
+        });
+        let is_ended = match self.template {
+            Some(template) => template.is_ended(board),
+            None => self.mode.is_ended(board),
+        };
+        Some(MoveOutcome {
+            board,
+            spawned_tile: board ^ moved_board,
+            score,
+            combo,
+            is_ended,
+        })
+    }
+}
@@ -1,13 +1,25 @@
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
+use linera_sdk::base::ChainId;
+use serde::{Deserialize, Serialize};
+
+// Keys of the SipHash instance used by `gen_range`.
+//
+// These match the zero keys `std::collections::hash_map::DefaultHasher::new()` used before,
+// so seeds derived by earlier versions of the contract keep producing the exact same initial
+// boards.
+const KEY_0: u64 = 0;
+const KEY_1: u64 = 0;
 
 // Function to generate a random number based on a string input
 // and within a specified range defined by min and max.
+//
+// Initial boards are derived with it; game seeds and tiles spawned by moves come from `Rng`.
 pub fn gen_range(input: &str, min: u16, max: u16) -> u16 {
-    // Hash the input string to create a seed
-    let mut hasher = DefaultHasher::new();
-    input.hash(&mut hasher);
-    let seed = hasher.finish();
+    // Hash the input string to create a seed. The `0xFF` terminator mirrors how `str` values
+    // were fed to the standard library hasher, keeping the outputs unchanged.
+    let mut message = Vec::with_capacity(input.len() + 1);
+    message.extend_from_slice(input.as_bytes());
+    message.push(0xFF);
+    let seed = sip_hash_1_3(KEY_0, KEY_1, &message);
 
     // Calculate the range
     let range = max - min;
@@ -15,3 +27,164 @@ pub fn gen_range(input: &str, min: u16, max: u16) -> u16 {
     // Use the seed to get a number within the range using modulus
     (seed % range as u64) as u16 + min
 }
+
+/// A deterministic pseudo-random generator (SplitMix64), for the tiles spawned by moves.
+///
+/// The state advances with every value drawn, and the same seed yields the same sequence on
+/// every validator, so spawns can be reproduced for verification.
+///
+/// # Examples
+///
+/// ```
+/// use game2048::Rng;
+///
+/// let mut rng = Rng::new(7);
+/// let first = rng.next_u64();
+/// assert_ne!(rng.next_u64(), first);
+/// assert_eq!(Rng::new(7).next_u64(), first);
+///
+/// let cell = rng.gen_range(0, 16);
+/// assert!(cell < 16);
+/// ```
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    /// Seeds the generator of the tile spawned by a move executed at `block_height` on
+    /// `chain_id`, as the `spawn_index`-th spawn of its game.
+    ///
+    /// Mixing the index in gives moves made in the same block distinct generators.
+    ///
+    /// ```
+    /// use game2048::Rng;
+    /// use linera_sdk::base::ChainId;
+    ///
+    /// let chain_id = ChainId::root(0);
+    /// assert_ne!(
+    ///     Rng::for_spawn(7, chain_id, 0).next_u64(),
+    ///     Rng::for_spawn(7, chain_id, 1).next_u64()
+    /// );
+    /// assert_ne!(
+    ///     Rng::for_spawn(7, chain_id, 0).next_u64(),
+    ///     Rng::for_spawn(7, ChainId::root(1), 0).next_u64()
+    /// );
+    /// ```
+    pub fn for_spawn(block_height: u64, chain_id: ChainId, spawn_index: u32) -> Self {
+        let mut rng = Rng::new(block_height);
+        for word in <[u64; 4]>::from(chain_id.0) {
+            rng.state = rng.next_u64() ^ word;
+        }
+        rng.state = rng.next_u64() ^ u64::from(spawn_index);
+        rng
+    }
+
+    /// Seeds the generator of the `draw`-th game seed drawn on `chain_id`, in the block at
+    /// `block_height` made at `timestamp_micros`.
+    ///
+    /// ```
+    /// use game2048::Rng;
+    /// use linera_sdk::base::ChainId;
+    ///
+    /// let chain_id = ChainId::root(0);
+    /// assert_ne!(
+    ///     Rng::for_block(chain_id, 7, 1_000, 0).next_seed(),
+    ///     Rng::for_block(chain_id, 7, 1_000, 1).next_seed()
+    /// );
+    /// assert_ne!(
+    ///     Rng::for_block(chain_id, 7, 1_000, 0).next_seed(),
+    ///     Rng::for_block(chain_id, 7, 2_000, 0).next_seed()
+    /// );
+    /// ```
+    pub fn for_block(
+        chain_id: ChainId,
+        block_height: u64,
+        timestamp_micros: u64,
+        draw: u64,
+    ) -> Self {
+        let mut rng = Rng::new(block_height);
+        for word in <[u64; 4]>::from(chain_id.0) {
+            rng.state = rng.next_u64() ^ word;
+        }
+        rng.state = rng.next_u64() ^ timestamp_micros;
+        rng.state = rng.next_u64() ^ draw;
+        rng
+    }
+
+    /// Returns the next value of the sequence.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a game seed, never `0`, which asks the contract to draw one.
+    pub fn next_seed(&mut self) -> u16 {
+        self.gen_range(1, u64::from(u16::MAX) + 1) as u16
+    }
+
+    /// Returns a value from `min` included to `max` excluded, `min` if the range is empty.
+    pub fn gen_range(&mut self, min: u64, max: u64) -> u64 {
+        let range = max.saturating_sub(min);
+        // The high half of the product maps the value to the range without a division.
+        min + ((u128::from(self.next_u64()) * u128::from(range)) >> 64) as u64
+    }
+}
+
+// SipHash-1-3 (one compression round, three finalization rounds) over `data`.
+//
+// The algorithm is implemented here rather than taken from `std`, whose `DefaultHasher` makes
+// no stability guarantee across Rust versions: seeds feed consensus-critical tile spawns, so
+// every validator must derive them identically regardless of the toolchain it was built with.
+fn sip_hash_1_3(key_0: u64, key_1: u64, data: &[u8]) -> u64 {
+    let mut state = [
+        key_0 ^ 0x736f_6d65_7073_6575,
+        key_1 ^ 0x646f_7261_6e64_6f6d,
+        key_0 ^ 0x6c79_6765_6e65_7261,
+        key_1 ^ 0x7465_6462_7974_6573,
+    ];
+
+    let mut blocks = data.chunks_exact(8);
+    for block in &mut blocks {
+        let word = u64::from_le_bytes(block.try_into().expect("Block is eight bytes long"));
+        state[3] ^= word;
+        sip_round(&mut state);
+        state[0] ^= word;
+    }
+
+    // The last block holds the remaining bytes and the message length in its top byte.
+    let mut last = (data.len() as u64 & 0xFF) << 56;
+    for (i, byte) in blocks.remainder().iter().enumerate() {
+        last |= u64::from(*byte) << (8 * i);
+    }
+    state[3] ^= last;
+    sip_round(&mut state);
+    state[0] ^= last;
+
+    state[2] ^= 0xFF;
+    for _ in 0..3 {
+        sip_round(&mut state);
+    }
+
+    state[0] ^ state[1] ^ state[2] ^ state[3]
+}
+
+fn sip_round(state: &mut [u64; 4]) {
+    state[0] = state[0].wrapping_add(state[1]);
+    state[1] = state[1].rotate_left(13) ^ state[0];
+    state[0] = state[0].rotate_left(32);
+    state[2] = state[2].wrapping_add(state[3]);
+    state[3] = state[3].rotate_left(16) ^ state[2];
+    state[0] = state[0].wrapping_add(state[3]);
+    state[3] = state[3].rotate_left(21) ^ state[0];
+    state[2] = state[2].wrapping_add(state[1]);
+    state[1] = state[1].rotate_left(17) ^ state[2];
+    state[2] = state[2].rotate_left(32);
+}
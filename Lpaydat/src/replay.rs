@@ -0,0 +1,441 @@
+use std::fmt;
+
+use crate::{replay_board, Direction, Game, GameMode, Turn};
+use async_graphql::scalar;
+use linera_sdk::base::{BcsHashable, CryptoHash};
+use serde::{Deserialize, Serialize};
+
+/// Bytes every `.r2048` replay starts with.
+pub const REPLAY_MAGIC: [u8; 5] = *b"R2048";
+
+/// The version of the replay format written by [`Replay::encode`].
+pub const REPLAY_VERSION: u8 = 1;
+
+/// Number of moves between two checkpoints of a replay.
+pub const CHECKPOINT_INTERVAL: u32 = 64;
+
+/// Length of the header, from the magic bytes to the move count included.
+const HEADER_LEN: usize = 24;
+
+/// Length of an encoded checkpoint.
+const CHECKPOINT_LEN: usize = 20;
+
+/// Length of the final hash.
+const HASH_LEN: usize = 32;
+
+/// A game in the portable `.r2048` replay format, for archiving games and verifying them
+/// without access to the chain.
+///
+/// Integers are encoded in little-endian order:
+///
+/// | Field         | Bytes        | Content                                                   |
+/// |---------------|--------------|-----------------------------------------------------------|
+/// | magic         | 5            | [`REPLAY_MAGIC`]                                          |
+/// | version       | 1            | [`REPLAY_VERSION`]                                        |
+/// | game ID       | 2            |                                                           |
+/// | seed          | 2            | The seed the initial board was spawned from               |
+/// | mode          | 1            | `0` for classic, `1` for blitz, `2` for endless           |
+/// | flags         | 1            | Bit `0` if the game ended, bit `1` for sandbox games      |
+/// | initial board | 8            |                                                           |
+/// | move count    | 4            |                                                           |
+/// | moves         | 1 per move   | See [`ReplayMove`]                                        |
+/// | checkpoints   | 4 + 20 each  | Their count, then the move index, board and score of each |
+/// | final hash    | 32           | See [`Replay::hash`]                                      |
+///
+/// A checkpoint follows every [`CHECKPOINT_INTERVAL`] moves, and the last one records the
+/// final board and score.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Replay {
+    pub game_id: u16,
+    pub seed: u16,
+    pub mode: GameMode,
+    pub is_ended: bool,
+    pub sandbox: bool,
+    pub initial_board: u64,
+    pub moves: Vec<ReplayMove>,
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+/// A move of a replay, encoded in one byte: the direction in bits `0..2` (up, down, left,
+/// right), the cell of the spawned tile in bits `2..6`, and its exponent in bits `6..8`, `0`
+/// if no tile spawned.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReplayMove {
+    pub direction: Direction,
+    /// The tile spawned after the move, as a mask of the board bits it occupies.
+    pub spawned_tile: u64,
+}
+
+/// The state of a game after `move_index` moves.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Checkpoint {
+    pub move_index: u32,
+    pub board: u64,
+    pub score: u64,
+}
+
+/// Why a replay could not be read or does not verify.
+#[derive(Debug, Eq, PartialEq)]
+pub enum ReplayError {
+    BadMagic,
+    UnsupportedVersion(u8),
+    UnknownMode(u8),
+    /// The replay ends before one of its fields.
+    Truncated,
+    /// Bytes follow the final hash.
+    TrailingBytes,
+    HashMismatch,
+    /// The initial board is not the one spawned from the seed.
+    InitialBoardMismatch,
+    /// The move does not change the board, or its tile spawned on an occupied cell.
+    IllegalMove(u32),
+    /// The replayed board differs from the checkpoint after this move.
+    CheckpointMismatch(u32),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::BadMagic => write!(f, "Not a 2048 replay"),
+            ReplayError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported replay version {version}")
+            }
+            ReplayError::UnknownMode(mode) => write!(f, "Unknown game mode {mode}"),
+            ReplayError::Truncated => write!(f, "Truncated replay"),
+            ReplayError::TrailingBytes => write!(f, "Unexpected bytes after the replay"),
+            ReplayError::HashMismatch => write!(f, "The replay does not match its hash"),
+            ReplayError::InitialBoardMismatch => {
+                write!(f, "The initial board was not spawned from the seed")
+            }
+            ReplayError::IllegalMove(move_index) => write!(f, "Move {move_index} is illegal"),
+            ReplayError::CheckpointMismatch(move_index) => {
+                write!(
+                    f,
+                    "The board after move {move_index} differs from its checkpoint"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Whether the move log of a game reproduces its state, as checked by `VerifyGame`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum GameVerification {
+    /// The moves of the log reproduce the board, move count and score of the game.
+    Verified,
+    /// The moves of the log do not reproduce the game, which is no longer ranked.
+    Flagged,
+}
+
+scalar!(GameVerification);
+
+/// The encoded replay, up to its final hash.
+#[derive(Deserialize, Serialize)]
+struct ReplayBody(Vec<u8>);
+
+impl BcsHashable for ReplayBody {}
+
+impl Replay {
+    /// Records a game from its move log, whose first turn is the creation of the game. Moves
+    /// that were taken back are left out, along with their undos.
+    pub fn from_turns(
+        game_id: u16,
+        seed: u16,
+        mode: GameMode,
+        sandbox: bool,
+        turns: &[Turn],
+    ) -> Self {
+        let (creation, log) = turns
+            .split_first()
+            .expect("The move log starts at creation");
+        let mut turns = Vec::new();
+        for turn in log {
+            match turn.direction {
+                Some(_) => turns.push(turn),
+                None => {
+                    turns.pop();
+                }
+            }
+        }
+        let moves = turns
+            .iter()
+            .map(|turn| ReplayMove {
+                direction: turn.direction.clone().expect("Moves have a direction"),
+                spawned_tile: turn.spawned_tile,
+            })
+            .collect();
+        let mut checkpoints = Vec::new();
+        for (move_index, turn) in (1..).zip(&turns) {
+            if move_index % CHECKPOINT_INTERVAL == 0 || move_index == turns.len() as u32 {
+                checkpoints.push(Checkpoint {
+                    move_index,
+                    board: turn.board,
+                    score: turn.score,
+                });
+            }
+        }
+        if turns.is_empty() {
+            checkpoints.push(Checkpoint {
+                move_index: 0,
+                board: creation.board,
+                score: creation.score,
+            });
+        }
+        let last_turn = turns.last().copied().unwrap_or(creation);
+        Replay {
+            game_id,
+            seed,
+            mode,
+            is_ended: last_turn.is_ended,
+            sandbox,
+            initial_board: creation.board,
+            moves,
+            checkpoints,
+        }
+    }
+
+    /// Returns whether the moves of the replay are legal and lead to `board` in `move_count`
+    /// moves, scoring `score` in classic games, whose score derives from the board alone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::{Game, GameMode, Replay, Turn};
+    ///
+    /// let board = Game::new(3).board;
+    /// let creation = Turn {
+    ///     direction: None,
+    ///     spawned_tile: 0,
+    ///     spawn_index: None,
+    ///     board,
+    ///     score: 0,
+    ///     is_ended: false,
+    /// };
+    /// let replay = Replay::from_turns(3, 3, GameMode::Classic, false, &[creation]);
+    ///
+    /// assert!(replay.reproduces(board, 0, 0));
+    /// assert!(!replay.reproduces(board, 1, 0));
+    /// assert!(!replay.reproduces(board | 0xF, 0, 0));
+    /// ```
+    pub fn reproduces(&self, board: u64, move_count: u32, score: u64) -> bool {
+        self.verify().is_ok()
+            && self.moves.len() == move_count as usize
+            && replay_board(self, self.moves.len()) == Some(board)
+            && (self.mode != GameMode::Classic || Game::score(board) == score)
+    }
+
+    /// Returns the hash ending the encoded replay: the [`CryptoHash`] of all the bytes
+    /// before it.
+    pub fn hash(body: &[u8]) -> CryptoHash {
+        CryptoHash::new(&ReplayBody(body.to_vec()))
+    }
+
+    /// Encodes the replay in the `.r2048` format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::{Direction, Game, GameMode, Replay, Turn};
+    ///
+    /// let mut board = Game::new(5).board;
+    /// let mut turns = vec![Turn {
+    ///     direction: None,
+    ///     spawned_tile: 0,
+    ///     spawn_index: None,
+    ///     board,
+    ///     score: 0,
+    ///     is_ended: false,
+    /// }];
+    /// for (spawn_index, direction) in [Direction::Left, Direction::Up].into_iter().enumerate() {
+    ///     let moved = Game::move_board(board, direction.clone());
+    ///     board = Game { board, seed: spawn_index as u16 }.execute(direction.clone());
+    ///     turns.push(Turn {
+    ///         direction: Some(direction),
+    ///         spawned_tile: board ^ moved,
+    ///         spawn_index: Some(spawn_index as u32),
+    ///         board,
+    ///         score: Game::score(board),
+    ///         is_ended: false,
+    ///     });
+    /// }
+    ///
+    /// let replay = Replay::from_turns(5, 5, GameMode::Classic, false, &turns);
+    /// let bytes = replay.encode();
+    /// assert_eq!(&bytes[..5], b"R2048");
+    /// assert_eq!(Replay::decode(&bytes), Ok(replay));
+    /// ```
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            HEADER_LEN + self.moves.len() + 4 + CHECKPOINT_LEN * self.checkpoints.len() + HASH_LEN,
+        );
+        bytes.extend_from_slice(&REPLAY_MAGIC);
+        bytes.push(REPLAY_VERSION);
+        bytes.extend_from_slice(&self.game_id.to_le_bytes());
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.push(match self.mode {
+            GameMode::Classic => 0,
+            GameMode::Blitz => 1,
+            GameMode::Endless => 2,
+        });
+        bytes.push(u8::from(self.is_ended) | u8::from(self.sandbox) << 1);
+        bytes.extend_from_slice(&self.initial_board.to_le_bytes());
+        bytes.extend_from_slice(&(self.moves.len() as u32).to_le_bytes());
+        bytes.extend(self.moves.iter().map(ReplayMove::encode));
+        bytes.extend_from_slice(&(self.checkpoints.len() as u32).to_le_bytes());
+        for checkpoint in &self.checkpoints {
+            bytes.extend_from_slice(&checkpoint.move_index.to_le_bytes());
+            bytes.extend_from_slice(&checkpoint.board.to_le_bytes());
+            bytes.extend_from_slice(&checkpoint.score.to_le_bytes());
+        }
+        let hash = Self::hash(&bytes);
+        bytes.extend_from_slice(hash.as_bytes());
+        bytes
+    }
+
+    /// Decodes a replay in the `.r2048` format, checking its final hash.
+    pub fn decode(bytes: &[u8]) -> Result<Self, ReplayError> {
+        let mut reader = Reader { bytes };
+        if reader.take(REPLAY_MAGIC.len())? != REPLAY_MAGIC {
+            return Err(ReplayError::BadMagic);
+        }
+        let version = reader.u8()?;
+        if version != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+        let game_id = reader.u16()?;
+        let seed = reader.u16()?;
+        let mode = match reader.u8()? {
+            0 => GameMode::Classic,
+            1 => GameMode::Blitz,
+            2 => GameMode::Endless,
+            mode => return Err(ReplayError::UnknownMode(mode)),
+        };
+        let flags = reader.u8()?;
+        let initial_board = reader.u64()?;
+        let move_count = reader.u32()? as usize;
+        let moves = reader.take(move_count)?;
+        let moves = moves.iter().map(|&byte| ReplayMove::decode(byte)).collect();
+        let checkpoint_count = reader.u32()? as usize;
+        let mut checkpoints = Vec::new();
+        for _ in 0..checkpoint_count {
+            checkpoints.push(Checkpoint {
+                move_index: reader.u32()?,
+                board: reader.u64()?,
+                score: reader.u64()?,
+            });
+        }
+        let body = &bytes[..bytes.len() - reader.bytes.len()];
+        if reader.take(HASH_LEN)? != &Self::hash(body).as_bytes()[..] {
+            return Err(ReplayError::HashMismatch);
+        }
+        if !reader.bytes.is_empty() {
+            return Err(ReplayError::TrailingBytes);
+        }
+        Ok(Replay {
+            game_id,
+            seed,
+            mode,
+            is_ended: flags & 1 != 0,
+            sandbox: flags & 2 != 0,
+            initial_board,
+            moves,
+            checkpoints,
+        })
+    }
+
+    /// Replays the moves from the initial board, checking that each is legal and that the
+    /// boards match the checkpoints. Scores are not checked, as some modes score moves by
+    /// when they were played.
+    pub fn verify(&self) -> Result<(), ReplayError> {
+        if Game::new(self.seed).board != self.initial_board {
+            return Err(ReplayError::InitialBoardMismatch);
+        }
+        let mut checkpoints = self.checkpoints.iter().peekable();
+        let mut board = self.initial_board;
+        for move_index in 0..=self.moves.len() as u32 {
+            if move_index > 0 {
+                let replay_move = &self.moves[move_index as usize - 1];
+                let moved = Game::move_board(board, replay_move.direction.clone());
+                if moved == board || moved & replay_move.spawned_tile != 0 {
+                    return Err(ReplayError::IllegalMove(move_index));
+                }
+                board = moved | replay_move.spawned_tile;
+            }
+            while let Some(checkpoint) = checkpoints.next_if(|c| c.move_index == move_index) {
+                if checkpoint.board != board {
+                    return Err(ReplayError::CheckpointMismatch(move_index));
+                }
+            }
+        }
+        match checkpoints.next() {
+            Some(checkpoint) => Err(ReplayError::CheckpointMismatch(checkpoint.move_index)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl ReplayMove {
+    fn encode(&self) -> u8 {
+        let direction = match self.direction {
+            Direction::Up => 0,
+            Direction::Down => 1,
+            Direction::Left => 2,
+            Direction::Right => 3,
+        };
+        if self.spawned_tile == 0 {
+            return direction;
+        }
+        let cell = self.spawned_tile.trailing_zeros() / 4;
+        let exponent = (self.spawned_tile >> (4 * cell)) & 0x3;
+        direction | (cell as u8) << 2 | (exponent as u8) << 6
+    }
+
+    fn decode(byte: u8) -> Self {
+        let direction = match byte & 0x3 {
+            0 => Direction::Up,
+            1 => Direction::Down,
+            2 => Direction::Left,
+            _ => Direction::Right,
+        };
+        let cell = (byte >> 2) & 0xF;
+        let exponent = u64::from(byte >> 6);
+        ReplayMove {
+            direction,
+            spawned_tile: exponent << (4 * cell),
+        }
+    }
+}
+
+/// Reads the fields of a replay in order.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ReplayError> {
+        if self.bytes.len() < len {
+            return Err(ReplayError::Truncated);
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, ReplayError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, ReplayError> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, ReplayError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, ReplayError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
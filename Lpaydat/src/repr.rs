@@ -0,0 +1,417 @@
+use crate::{Direction, Game, Moves, Rng, MAX_WIDE_EXPONENT, SUPPORTED_BOARD_SIZE, WIDE_CELL_BITS};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Write as _};
+
+/// Width and height of the largest boards games are played on.
+pub const MAX_BOARD_SIZE: u8 = 6;
+
+/// A square board packed into integers, each cell holding the exponent of its tile, `0` for
+/// empty cells.
+///
+/// Cells are numbered like the nibbles of a `u64` board: cell `0` is the bottom right one,
+/// and cells follow right to left, then bottom to top. Boards are implemented by `u64` for
+/// 4x4 boards, as played by [`Game`], `u128` for 5x5 boards, laid out like a [`WideBoard`]
+/// of that size, and `[u64; N]` for larger ones, one row per integer.
+///
+/// [`WideBoard`]: crate::WideBoard
+pub trait BoardRepr: Copy + Eq + fmt::Debug {
+    /// Width and height of the board.
+    const SIZE: u8;
+    /// Largest tile exponent a cell holds.
+    const MAX_EXPONENT: u8;
+
+    /// Returns the board without tiles.
+    fn empty() -> Self;
+
+    /// Returns the exponent of the tile at `row` and `col`, counted from the top left.
+    fn exponent(&self, row: u8, col: u8) -> u8;
+
+    /// Sets the exponent of the tile at `row` and `col`, counted from the top left.
+    fn set_exponent(&mut self, row: u8, col: u8, exponent: u8);
+
+    /// Returns the row and column of cell `index`.
+    fn position(index: u32) -> (u8, u8) {
+        let size = u32::from(Self::SIZE);
+        let last = Self::SIZE - 1;
+        (last - (index / size) as u8, last - (index % size) as u8)
+    }
+
+    /// Returns the board mirrored along its main diagonal.
+    ///
+    /// ```
+    /// use game2048::{BoardRepr, Game};
+    ///
+    /// let board = 0xFEDC_BA98_7654_3210_u64;
+    /// let mut wide = <[u64; 4]>::empty();
+    /// for row in 0..4 {
+    ///     for col in 0..4 {
+    ///         wide.set_exponent(row, col, board.exponent(row, col));
+    ///     }
+    /// }
+    /// let transposed = wide.transpose();
+    /// assert_eq!(transposed.convert_to_matrix(), Game::transpose(board).convert_to_matrix());
+    /// ```
+    fn transpose(&self) -> Self {
+        let mut transposed = Self::empty();
+        for row in 0..Self::SIZE {
+            for col in 0..Self::SIZE {
+                transposed.set_exponent(col, row, self.exponent(row, col));
+            }
+        }
+        transposed
+    }
+
+    /// Returns the exponent of each tile, row by row from the top left, `0` for empty cells.
+    fn convert_to_matrix(&self) -> Vec<Vec<u16>> {
+        (0..Self::SIZE)
+            .map(|row| {
+                (0..Self::SIZE)
+                    .map(|col| u16::from(self.exponent(row, col)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the board after sliding its tiles towards `direction`, equal neighbors merging
+    /// once as in [`Moves::slide_line`]. No tile spawns.
+    ///
+    /// ```
+    /// use game2048::{BoardRepr, Direction};
+    ///
+    /// let mut board = <[u64; 6]>::empty();
+    /// for col in 0..6 {
+    ///     board.set_exponent(5, col, 1);
+    /// }
+    /// let slid = board.slide(Direction::Right);
+    /// assert_eq!(slid.convert_to_matrix()[5], [0, 0, 0, 2, 2, 2]);
+    /// assert_eq!(board.slide(Direction::Down), board);
+    /// ```
+    fn slide(&self, direction: Direction) -> Self {
+        let mut result = Self::empty();
+        for line in 0..Self::SIZE {
+            let cells = line_cells(Self::SIZE, line, &direction);
+            let mut exponents = cells
+                .iter()
+                .map(|&(row, col)| u64::from(self.exponent(row, col)))
+                .collect::<Vec<_>>();
+            Moves::slide_line(&mut exponents, u64::from(Self::MAX_EXPONENT));
+            for (&(row, col), exponent) in cells.iter().zip(exponents) {
+                result.set_exponent(row, col, exponent as u8);
+            }
+        }
+        result
+    }
+
+    /// Returns the values of the tiles merges create when sliding towards `direction`, line
+    /// by line, in the order [`BoardRepr::slide`] merges them.
+    ///
+    /// ```
+    /// use game2048::{BoardRepr, Direction};
+    ///
+    /// let board = 0x0000_0000_0000_1111_u64;
+    /// assert_eq!(board.merges(Direction::Left), [4, 4]);
+    /// assert!(board.merges(Direction::Up).is_empty());
+    /// ```
+    fn merges(&self, direction: Direction) -> Vec<u64> {
+        let mut merges = Vec::new();
+        for line in 0..Self::SIZE {
+            let mut pending = None;
+            for (row, col) in line_cells(Self::SIZE, line, &direction) {
+                let exponent = self.exponent(row, col);
+                if exponent == 0 {
+                    continue;
+                }
+                if pending == Some(exponent) {
+                    merges.push(1 << (exponent + 1).min(Self::MAX_EXPONENT));
+                    pending = None;
+                } else {
+                    pending = Some(exponent);
+                }
+            }
+        }
+        merges
+    }
+
+    /// Returns the number of cells without a tile.
+    fn count_empty(&self) -> u32 {
+        let cell_count = u32::from(Self::SIZE) * u32::from(Self::SIZE);
+        (0..cell_count)
+            .filter(|&index| {
+                let (row, col) = Self::position(index);
+                self.exponent(row, col) == 0
+            })
+            .count() as u32
+    }
+
+    /// Returns whether no move changes the board.
+    fn is_stuck(&self) -> bool {
+        [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ]
+        .into_iter()
+        .all(|direction| self.slide(direction) == *self)
+    }
+
+    /// Returns the value of the largest tile, or `0` for an empty board.
+    fn max_tile(&self) -> u64 {
+        let exponent = self
+            .convert_to_matrix()
+            .into_iter()
+            .flatten()
+            .max()
+            .unwrap_or(0);
+        if exponent == 0 {
+            0
+        } else {
+            1 << exponent
+        }
+    }
+
+    /// Returns the classic score of the board, computed like [`Game::score`] from its tiles
+    /// alone, since only `2`s spawn.
+    fn score(&self) -> u64 {
+        self.convert_to_matrix()
+            .into_iter()
+            .flatten()
+            .filter(|&exponent| exponent > 1)
+            .map(|exponent| u64::from(exponent - 1) << (exponent + 1))
+            .sum()
+    }
+
+    /// Returns the board with a `2` spawned on an empty cell picked from `rng`, counting
+    /// empty cells in cell order like [`Game::spawn_tile_with`]. Full boards are returned as
+    /// they are.
+    fn spawn_with(&self, rng: &mut Rng) -> Self {
+        let empty = self.count_empty();
+        if empty == 0 {
+            return *self;
+        }
+        let mut index = rng.gen_range(0, u64::from(empty));
+        let mut board = *self;
+        for cell in 0.. {
+            let (row, col) = Self::position(cell);
+            if self.exponent(row, col) == 0 {
+                if index == 0 {
+                    board.set_exponent(row, col, 1);
+                    return board;
+                }
+                index -= 1;
+            }
+        }
+        unreachable!("The board has {empty} empty cells")
+    }
+}
+
+/// Returns the cells of line `line` of a board `size` cells wide, from the one tiles slide
+/// towards when moving in `direction`.
+fn line_cells(size: u8, line: u8, direction: &Direction) -> Vec<(u8, u8)> {
+    (0..size)
+        .map(|step| match direction {
+            Direction::Left => (line, step),
+            Direction::Right => (line, size - 1 - step),
+            Direction::Up => (step, line),
+            Direction::Down => (size - 1 - step, line),
+        })
+        .collect()
+}
+
+/// The 4x4 board of [`Game`], moved with its precomputed tables.
+impl BoardRepr for u64 {
+    const SIZE: u8 = SUPPORTED_BOARD_SIZE;
+    const MAX_EXPONENT: u8 = 0xF;
+
+    fn empty() -> Self {
+        0
+    }
+
+    fn exponent(&self, row: u8, col: u8) -> u8 {
+        ((self >> (4 * (15 - 4 * row - col))) & 0xF) as u8
+    }
+
+    fn set_exponent(&mut self, row: u8, col: u8, exponent: u8) {
+        assert!(exponent <= Self::MAX_EXPONENT, "Tiles are at most 2^15");
+        let shift = 4 * (15 - 4 * row - col);
+        *self = (*self & !(0xF << shift)) | (u64::from(exponent) << shift);
+    }
+
+    fn transpose(&self) -> Self {
+        Game::transpose(*self)
+    }
+
+    fn slide(&self, direction: Direction) -> Self {
+        Game::move_board(*self, direction)
+    }
+
+    fn score(&self) -> u64 {
+        Game::score(*self)
+    }
+
+    fn spawn_with(&self, rng: &mut Rng) -> Self {
+        self | Game::spawn_tile_with(*self, rng)
+    }
+}
+
+/// A 5x5 board of 5-bit cells, with the cells of a [`WideBoard`](crate::WideBoard) of that
+/// size.
+impl BoardRepr for u128 {
+    const SIZE: u8 = 5;
+    const MAX_EXPONENT: u8 = MAX_WIDE_EXPONENT;
+
+    fn empty() -> Self {
+        0
+    }
+
+    fn exponent(&self, row: u8, col: u8) -> u8 {
+        let shift = WIDE_CELL_BITS * u32::from(24 - 5 * row - col);
+        ((self >> shift) & u128::from(MAX_WIDE_EXPONENT)) as u8
+    }
+
+    fn set_exponent(&mut self, row: u8, col: u8, exponent: u8) {
+        assert!(exponent <= Self::MAX_EXPONENT, "Tiles are at most 2^31");
+        let shift = WIDE_CELL_BITS * u32::from(24 - 5 * row - col);
+        *self &= !(u128::from(MAX_WIDE_EXPONENT) << shift);
+        *self |= u128::from(exponent) << shift;
+    }
+}
+
+/// An NxN board of 5-bit cells, row `i` from the top in integer `i`, its leftmost cell in the
+/// highest bits. Rows of up to 12 cells fit.
+impl<const N: usize> BoardRepr for [u64; N] {
+    const SIZE: u8 = N as u8;
+    const MAX_EXPONENT: u8 = MAX_WIDE_EXPONENT;
+
+    fn empty() -> Self {
+        assert!(
+            (1..=12).contains(&N),
+            "Rows of 5-bit cells are at most 12 cells wide"
+        );
+        [0; N]
+    }
+
+    fn exponent(&self, row: u8, col: u8) -> u8 {
+        let shift = WIDE_CELL_BITS * u32::from(Self::SIZE - 1 - col);
+        ((self[usize::from(row)] >> shift) & u64::from(MAX_WIDE_EXPONENT)) as u8
+    }
+
+    fn set_exponent(&mut self, row: u8, col: u8, exponent: u8) {
+        assert!(exponent <= Self::MAX_EXPONENT, "Tiles are at most 2^31");
+        let shift = WIDE_CELL_BITS * u32::from(Self::SIZE - 1 - col);
+        let cells = &mut self[usize::from(row)];
+        *cells &= !(u64::from(MAX_WIDE_EXPONENT) << shift);
+        *cells |= u64::from(exponent) << shift;
+    }
+}
+
+/// The board of a game larger than the 4x4 boards [`Game`] plays.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum LargeBoard {
+    Five(u128),
+    Six([u64; 6]),
+}
+
+impl LargeBoard {
+    /// Returns the initial board of a game `size` cells wide started from `seed`, with two
+    /// `2`s spawned from a generator seeded with `seed`, or `None` for the 4x4 boards of
+    /// [`Game`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::LargeBoard;
+    ///
+    /// assert_eq!(LargeBoard::new(4, 7), None);
+    /// let board = LargeBoard::new(6, 7).unwrap();
+    /// assert_eq!(board.size(), 6);
+    /// assert_eq!(board.score(), 0);
+    /// assert_eq!(board.max_tile(), 2);
+    /// assert_eq!(board.convert_to_matrix().concat().iter().sum::<u16>(), 2);
+    /// ```
+    pub fn new(size: u8, seed: u16) -> Option<Self> {
+        let mut rng = Rng::new(u64::from(seed));
+        let board = match size {
+            SUPPORTED_BOARD_SIZE => return None,
+            5 => LargeBoard::Five(u128::empty()),
+            6 => LargeBoard::Six(<[u64; 6]>::empty()),
+            _ => panic!("Boards are {SUPPORTED_BOARD_SIZE} to {MAX_BOARD_SIZE} cells wide"),
+        };
+        Some(board.spawn_with(&mut rng).spawn_with(&mut rng))
+    }
+
+    /// Returns the width and height of the board.
+    pub fn size(&self) -> u8 {
+        match self {
+            LargeBoard::Five(_) => u128::SIZE,
+            LargeBoard::Six(_) => <[u64; 6]>::SIZE,
+        }
+    }
+
+    /// Returns the board after sliding its tiles towards `direction`. No tile spawns.
+    pub fn slide(&self, direction: Direction) -> Self {
+        match self {
+            LargeBoard::Five(board) => LargeBoard::Five(board.slide(direction)),
+            LargeBoard::Six(board) => LargeBoard::Six(board.slide(direction)),
+        }
+    }
+
+    /// Returns the board with a `2` spawned on an empty cell picked from `rng`.
+    pub fn spawn_with(&self, rng: &mut Rng) -> Self {
+        match self {
+            LargeBoard::Five(board) => LargeBoard::Five(board.spawn_with(rng)),
+            LargeBoard::Six(board) => LargeBoard::Six(board.spawn_with(rng)),
+        }
+    }
+
+    /// Returns whether no move changes the board.
+    pub fn is_stuck(&self) -> bool {
+        match self {
+            LargeBoard::Five(board) => board.is_stuck(),
+            LargeBoard::Six(board) => board.is_stuck(),
+        }
+    }
+
+    /// Returns the classic score of the board, see [`BoardRepr::score`].
+    pub fn score(&self) -> u64 {
+        match self {
+            LargeBoard::Five(board) => board.score(),
+            LargeBoard::Six(board) => board.score(),
+        }
+    }
+
+    /// Returns the values of the tiles merges create when sliding towards `direction`.
+    pub fn merges(&self, direction: Direction) -> Vec<u64> {
+        match self {
+            LargeBoard::Five(board) => board.merges(direction),
+            LargeBoard::Six(board) => board.merges(direction),
+        }
+    }
+
+    /// Returns the value of the largest tile.
+    pub fn max_tile(&self) -> u64 {
+        match self {
+            LargeBoard::Five(board) => board.max_tile(),
+            LargeBoard::Six(board) => board.max_tile(),
+        }
+    }
+
+    /// Returns the exponent of each tile, row by row from the top left.
+    pub fn convert_to_matrix(&self) -> Vec<Vec<u16>> {
+        match self {
+            LargeBoard::Five(board) => board.convert_to_matrix(),
+            LargeBoard::Six(board) => board.convert_to_matrix(),
+        }
+    }
+
+    /// Returns the packed board as hexadecimal digits.
+    pub fn to_hex(&self) -> String {
+        match self {
+            LargeBoard::Five(board) => format!("{board:032x}"),
+            LargeBoard::Six(rows) => rows.iter().fold(String::new(), |mut hex, row| {
+                write!(hex, "{row:08x}").unwrap();
+                hex
+            }),
+        }
+    }
+}
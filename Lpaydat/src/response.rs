@@ -0,0 +1,72 @@
+use async_graphql::{scalar, InputObject, SimpleObject};
+use linera_sdk::base::{
+    Amount, ApplicationId, BcsSignable, BlockHeight, ChainId, KeyPair, Owner, PublicKey, Signature,
+};
+use serde::{Deserialize, Serialize};
+
+/// A critical value served by the 2048 application on chain `chain_id`, as of the blocks below
+/// `next_block_height`.
+///
+/// Services hold no keys, so they cannot sign their responses: the operator of the node
+/// serving the value signs the claim instead, with one of the application's response signers.
+/// Frontends relaying values to many users pass the signed claim along, and users check it
+/// with [`verify_response`] rather than trusting the frontend. The chain and height let them
+/// compare the value with the one another node serves at that height.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject, InputObject)]
+#[graphql(input_name = "ResponseClaimInput")]
+pub struct ResponseClaim {
+    pub application_id: ApplicationId,
+    pub chain_id: ChainId,
+    pub next_block_height: BlockHeight,
+    pub value: ClaimedValue,
+}
+
+impl BcsSignable for ResponseClaim {}
+
+/// A value a [`ResponseClaim`] vouches for.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ClaimedValue {
+    /// The final score of game `game_id`, which is over.
+    FinalScore {
+        game_id: u16,
+        owner: Option<Owner>,
+        score: u64,
+    },
+    /// The prepaid fees sponsorship `sponsorship_id` has not spent yet.
+    SponsorshipBalance {
+        sponsorship_id: u32,
+        balance: Amount,
+    },
+}
+
+scalar!(ClaimedValue);
+
+/// A response claim with the signature of a response signer.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct SignedResponse {
+    pub claim: ResponseClaim,
+    pub signer: PublicKey,
+    pub signature: Signature,
+}
+
+impl SignedResponse {
+    /// Signs `claim` with the key of a response signer, as node operators do before handing
+    /// the claim to frontends.
+    pub fn new(claim: ResponseClaim, key_pair: &KeyPair) -> Self {
+        SignedResponse {
+            signature: Signature::new(&claim, key_pair),
+            signer: key_pair.public(),
+            claim,
+        }
+    }
+}
+
+/// Checks that `response` was signed by one of `signers`, the response signers of the
+/// application. Callers check the application id, chain and height of the claim themselves.
+pub fn verify_response(response: &SignedResponse, signers: &[PublicKey]) -> bool {
+    signers.contains(&response.signer)
+        && response
+            .signature
+            .check(&response.claim, response.signer)
+            .is_ok()
+}
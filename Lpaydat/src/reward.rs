@@ -0,0 +1,26 @@
+use linera_sdk::base::{ApplicationId, ChainId};
+use serde::{Deserialize, Serialize};
+
+/// Reward tokens minted to the owners of games ending with a high enough score, by calling
+/// the token application on the chain the token was created on. Games ending on another
+/// chain ask that one to mint their reward, which the token application then sends on to
+/// the owner's account on the chain the game ended on.
+///
+/// The creator of the token must authorize this application to mint it, with the token
+/// application's `SetMinter` operation: the call fails otherwise, and with it the block
+/// ending the game or receiving its reward. Sandbox games and games flagged by verification
+/// earn no rewards.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GameRewards {
+    /// The token application, to be listed in the required applications of this one so
+    /// that every chain playing games can call it.
+    pub token_application: ApplicationId,
+    /// The chain the token was created on, the only one minting it.
+    pub chain_id: ChainId,
+    /// The name of the token minted.
+    pub token: String,
+    /// The lowest score earning a reward.
+    pub min_score: u64,
+    /// The tokens minted for each rewarded game, in base units.
+    pub amount: u128,
+}
@@ -0,0 +1,106 @@
+use crate::Game;
+
+/// Everything a `ScorePolicy` may take into account when a move is applied.
+pub struct ScoredMove {
+    /// The board before the move.
+    pub previous_board: u64,
+    /// The board after the move, including the spawned tile.
+    pub board: u64,
+    /// The policy score before the move.
+    pub previous_score: u64,
+    /// The number of consecutive merging moves, including this one if it merged tiles.
+    pub combo: u32,
+    /// Microseconds elapsed since the game started.
+    pub elapsed_micros: u64,
+}
+
+impl ScoredMove {
+    /// Returns the merge-sum points gained by the move.
+    pub fn merge_gain(&self) -> u64 {
+        Game::score(self.board).saturating_sub(Game::score(self.previous_board))
+    }
+}
+
+/// A formula turning moves into a game score.
+///
+/// Each game mode selects its policy (see `GameMode::score_policy`), so games and the rankings
+/// built from them are scored consistently with the rules they were played under.
+pub trait ScorePolicy {
+    /// Returns the score of the game after `scored` was applied.
+    fn score(&self, scored: &ScoredMove) -> u64;
+}
+
+/// The classic 2048 score: the sum of all merged tiles, derived from the board alone.
+pub struct MergeSum;
+
+impl ScorePolicy for MergeSum {
+    fn score(&self, scored: &ScoredMove) -> u64 {
+        Game::score(scored.board)
+    }
+}
+
+/// Multiplies the points of merging moves by the length of the current merge streak,
+/// up to `max_multiplier`.
+///
+/// # Examples
+///
+/// ```
+/// use game2048::{ComboMultiplier, ScorePolicy, ScoredMove};
+///
+/// let policy = ComboMultiplier { max_multiplier: 4 };
+/// let scored = ScoredMove {
+///     previous_board: 0x0000_0000_0000_0022,
+///     board: 0x0000_0000_0000_0031,
+///     previous_score: 100,
+///     combo: 3,
+///     elapsed_micros: 0,
+/// };
+///
+/// // Merging the two 4s gains 16 points, tripled by the streak.
+/// assert_eq!(scored.merge_gain(), 16);
+/// assert_eq!(policy.score(&scored), 148);
+/// ```
+pub struct ComboMultiplier {
+    pub max_multiplier: u32,
+}
+
+impl ScorePolicy for ComboMultiplier {
+    fn score(&self, scored: &ScoredMove) -> u64 {
+        let multiplier = scored.combo.clamp(1, self.max_multiplier);
+        scored.previous_score + scored.merge_gain() * u64::from(multiplier)
+    }
+}
+
+/// Adds a bonus to the points of every merge, decreasing linearly from 100% at the start of
+/// the game to nothing once `window_micros` have elapsed.
+///
+/// # Examples
+///
+/// ```
+/// use game2048::{ScorePolicy, ScoredMove, TimeBonus};
+///
+/// let policy = TimeBonus { window_micros: 60_000_000 };
+/// let scored = ScoredMove {
+///     previous_board: 0x0000_0000_0000_0022,
+///     board: 0x0000_0000_0000_0031,
+///     previous_score: 100,
+///     combo: 1,
+///     elapsed_micros: 15_000_000,
+/// };
+///
+/// // A quarter of the window has elapsed, so the 16 points are worth 75% more.
+/// assert_eq!(policy.score(&scored), 128);
+/// ```
+pub struct TimeBonus {
+    pub window_micros: u64,
+}
+
+impl ScorePolicy for TimeBonus {
+    fn score(&self, scored: &ScoredMove) -> u64 {
+        let gain = scored.merge_gain();
+        let remaining = self.window_micros.saturating_sub(scored.elapsed_micros);
+        let bonus =
+            (u128::from(gain) * u128::from(remaining) / u128::from(self.window_micros)) as u64;
+        scored.previous_score + gain + bonus
+    }
+}
@@ -2,15 +2,51 @@
 
 mod state;
 
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
 
-use self::state::Game2048;
-use async_graphql::{EmptySubscription, Object, Schema, SimpleObject};
-use game2048::{Direction, Game, Operation};
-use linera_sdk::{base::WithServiceAbi, bcs, views::View, Service, ServiceRuntime};
+use self::state::{Game2048, GameStatus};
+use async_graphql::{
+    registry::{MetaType, Registry},
+    Context, EmptySubscription, Object, OutputType, Schema, SimpleObject,
+};
+use game2048::{
+    attestation_epoch, beacon_seed, board_svg, commitment, date_of, day_from_date, day_of,
+    duration_seconds, game_request_seed, moves_per_minute, rfc3339, tournament_standings,
+    AccountMerge, Achievement, BoardRepr, BoardTheme, ChallengeRecord, ChallengeStatus,
+    ChatMessage, ChatPayload, ClaimedValue, CommunityId, DailyScore, Direction, EfficiencyEntry,
+    EfficiencyWeights, EventCursor, ExportBundle, ExportFormat, ExportRow, FeatureFlags,
+    FeeSettings, Game, Game2048Parameters, GameAnalysis, GameEvent, GameMode, GameResult,
+    GameTemplate, GameVerification, HistoryRetention, LeaderboardEntry, LeaderboardExclusion,
+    LeaderboardFilters, LeaderboardWindow, Lobby, LobbyStats, MatchDispute, MatchNotice,
+    MatchRecord, ModeSettings, MoveHistory, Operation, OperationEnvelope, OperationFailure,
+    PassSale, PassSettings, PlayerStats, Position, QueueEntry, RemoteScore, Replay, ResponseClaim,
+    Rng, ScoreAttestation, ShareCard, SignedAttestation, Solver, SpectatedGame, TileHeatmap,
+    TournamentStanding, TournamentStatus, Turn, Tutorial, CURRENT_OPERATION_VERSION,
+    DEFAULT_ACTIVE_GAME_LIMIT, DEFAULT_LOBBY, HINT_DEPTH, MAX_HINT_DEPTH, SUPPORTED_BOARD_SIZE,
+    TUTORIALS,
+};
+use linera_sdk::{
+    base::{
+        Amount, ApplicationId, BlockHeight, ChainId, CryptoHash, Owner, PublicKey, Signature,
+        Timestamp, WithServiceAbi,
+    },
+    bcs,
+    util::yield_once,
+    views::{CollectionView, ReadGuardedView, View, ViewError, ViewStorageContext},
+    Service, ServiceRuntime,
+};
+use serde::Serialize;
 
 pub struct Game2048Service {
     state: Arc<Game2048>,
+    application_id: ApplicationId,
+    chain_id: ChainId,
+    parameters: Game2048Parameters,
+    now: Timestamp,
+    next_block_height: u64,
     // runtime: Arc<Mutex<ServiceRuntime<Self>>>,
 }
 
@@ -21,7 +57,7 @@ impl WithServiceAbi for Game2048Service {
 }
 
 impl Service for Game2048Service {
-    type Parameters = ();
+    type Parameters = Game2048Parameters;
 
     async fn new(runtime: ServiceRuntime<Self>) -> Self {
         let state = Game2048::load(runtime.root_view_storage_context())
@@ -29,6 +65,11 @@ impl Service for Game2048Service {
             .expect("Failed to load state");
         Game2048Service {
             state: Arc::new(state),
+            application_id: runtime.application_id().forget_abi(),
+            chain_id: runtime.chain_id(),
+            parameters: runtime.application_parameters(),
+            now: runtime.system_time(),
+            next_block_height: runtime.next_block_height().0,
             // runtime: Arc::new(Mutex::new(runtime)),
         }
     }
@@ -37,6 +78,11 @@ impl Service for Game2048Service {
         let schema = Schema::build(
             QueryRoot {
                 state: self.state.clone(),
+                application_id: self.application_id,
+                chain_id: self.chain_id,
+                parameters: self.parameters.clone(),
+                now: self.now,
+                next_block_height: self.next_block_height,
                 // runtime: self.runtime.clone(),
             },
             MutationRoot,
@@ -49,45 +95,2152 @@ impl Service for Game2048Service {
 
 struct QueryRoot {
     state: Arc<Game2048>,
+    application_id: ApplicationId,
+    chain_id: ChainId,
+    parameters: Game2048Parameters,
+    now: Timestamp,
+    next_block_height: u64,
     // runtime: Arc<Mutex<ServiceRuntime<Game2048Service>>>,
 }
 
 #[derive(SimpleObject)]
 struct GameState {
     game_id: u16,
-    board: [[u16; 4]; 4],
+    owner: Option<Owner>,
+    namespace: Option<CommunityId>,
+    sandbox: bool,
+    /// The beacon round the seed was derived from, `None` if the creator chose the seed.
+    seed_round: Option<u32>,
+    /// The template the game was started from, if any.
+    template_id: Option<u32>,
+    /// The date of the daily challenge the game is played for, if any.
+    daily_challenge: Option<String>,
+    mode: GameMode,
+    /// The seed the initial board was spawned from. Usually the game id, but matches and daily
+    /// challenges may start games from the seed of another id.
+    seed: u16,
+    /// Width and height of the board.
+    board_size: u8,
+    /// The exponent of each tile, `0` for empty cells.
+    board: Vec<Vec<u16>>,
+    /// The packed board in hexadecimal. 4x4 boards take 16 digits, one per tile exponent.
+    board_hex: String,
+    /// The value of each tile, `0` for empty cells.
+    tiles: Vec<Vec<u32>>,
+    status: GameStatus,
     is_ended: bool,
     score: u64,
+    /// The value of the largest tile the game reached.
+    highest_tile: u64,
+    /// Number of merges played, each pair of tiles merged counting once.
+    total_merges: u64,
+    /// The value of the largest tile a merge created, `0` before the first merge.
+    largest_merge: u64,
+    move_count: u32,
+    /// Moves after which the game ends, `None` for no limit.
+    max_moves: Option<u32>,
+    /// Number of tiles spawned by moves so far, mixed into the seed of the next spawn.
+    spawn_index: u32,
+    /// Number of moves the player may still take back.
+    undos_left: u32,
+    /// Which turns of the game are kept in its move log.
+    history_retention: HistoryRetention,
+    /// When the game was started, in RFC 3339 format.
+    created_at: String,
+    /// When the game was last played, in RFC 3339 format.
+    updated_at: String,
+    /// When the last move was played, in RFC 3339 format. `None` before the first move.
+    last_move_at: Option<String>,
+    /// Seconds elapsed between the start of the game and its last move.
+    duration_seconds: u64,
+    moves_per_minute: f64,
+    /// Whether the move log reproduced the game when it was last verified, `None` if it never
+    /// was.
+    verification: Option<GameVerification>,
+    /// Why the game is not ranked on leaderboards, `None` if it is.
+    leaderboard_exclusion: Option<LeaderboardExclusion>,
+}
+
+/// A move suggested by the solver.
+#[derive(SimpleObject)]
+struct Hint {
+    direction: Direction,
+    /// The expected value of the move, see `Solver::search`. Higher is better, comparable
+    /// between hints searched to the same depth only.
+    evaluation: f64,
+}
+
+/// The effects a move is predicted to have, with the operation submitting it.
+#[derive(SimpleObject)]
+struct MovePrediction {
+    /// The operation bytes, as returned by the `makeMove` mutation.
+    operation: Vec<u8>,
+    board: [[u16; 4]; 4],
+    score_delta: u64,
+    will_end: bool,
+    /// The spawn the new tile is seeded with, see `GameState::spawn_index`.
+    spawn_index: u32,
+}
+
+/// A player waiting for a match.
+#[derive(SimpleObject)]
+struct QueuedPlayer {
+    owner: Owner,
+    lobby: String,
+    rating: u64,
+    /// When the player joined the queue, in RFC 3339 format.
+    enqueued_at: String,
+    /// The rating difference the player currently accepts.
+    window: u64,
+    /// Seconds until a queued opponent becomes acceptable, `0` if one already is, or `None`
+    /// while nobody else is queued in the lobby. Players are paired by the next block of the
+    /// chain.
+    estimated_wait_secs: Option<u64>,
+}
+
+/// How many operations of a kind were executed.
+#[derive(SimpleObject)]
+struct OperationCount {
+    operation: String,
+    count: u64,
+}
+
+/// How many operations were dropped for a reason.
+#[derive(SimpleObject)]
+struct FailureCount {
+    failure: OperationFailure,
+    count: u64,
+}
+
+/// Counters kept by the contract of this chain.
+#[derive(SimpleObject)]
+struct Metrics {
+    /// Operations executed, by name, most frequent first.
+    operations: Vec<OperationCount>,
+    /// Operations dropped without effect, by reason. Rejected operations abort their block
+    /// and are not counted.
+    failures: Vec<FailureCount>,
+    messages_sent: u64,
+    /// Events emitted to the game events stream, see `GameEvent`.
+    events_emitted: u64,
+    /// Turns kept in the move logs of the games, see `HistoryRetention`.
+    kept_turns: u64,
+}
+
+/// A matchmaking lobby with its queue statistics.
+#[derive(SimpleObject)]
+struct LobbyInfo {
+    name: String,
+    settings: Lobby,
+    queued: u32,
+    /// Seconds the longest waiting player of the lobby has been queued.
+    longest_wait_secs: u64,
+    stats: LobbyStats,
+    /// Average seconds matched players spent in the queue, `None` before the first match.
+    average_wait_secs: Option<u64>,
+}
+
+/// A page of an export, with the hashes making it tamper-evident.
+#[derive(SimpleObject)]
+struct Export {
+    format: ExportFormat,
+    offset: u32,
+    /// Total number of rows available across all pages.
+    total: u32,
+    content: String,
+    /// Hash of every exported row, in order.
+    row_hashes: Vec<String>,
+    /// Hash committing to the offset and all rows of this page.
+    bundle_hash: String,
+}
+
+#[derive(SimpleObject)]
+struct Community {
+    community_id: CommunityId,
+    name: String,
+    admins: Vec<Owner>,
+    default_mode: GameMode,
+    fees: FeeSettings,
+    member_count: u32,
+}
+
+#[derive(SimpleObject)]
+struct Player {
+    owner: Owner,
+    stats: PlayerStats,
+    /// Where tiles spawn and where the largest tile ends up in the player's games.
+    heatmap: TileHeatmap,
+    /// The player's best score in each mode they played, sandbox games aside.
+    mode_bests: Vec<ModeBest>,
+    games: Vec<u16>,
+    /// The account this one asked to be merged into, awaiting its confirmation and admin
+    /// approval.
+    merge_request: Option<Owner>,
+    /// Whether the account of `merge_request` confirmed the merge.
+    merge_confirmed: bool,
+    /// Number of games that are neither ended nor sandbox games.
+    active_games: u32,
+    inventory: Vec<InventoryItem>,
+    /// When the player's season pass expires, in RFC 3339 format. `None` if they never
+    /// bought one.
+    season_pass_expires_at: Option<String>,
+    /// The sponsorships paying the fees of the player's games, see `sponsorship`.
+    sponsorships: Vec<u32>,
+    /// How many steps of each tutorial the player completed, in the order of `tutorials`.
+    tutorial_steps: Vec<u8>,
+    achievements: Vec<Achievement>,
+}
+
+/// What rankings show of a player next to their rows.
+#[derive(Clone, SimpleObject)]
+struct PlayerProfile {
+    owner: Owner,
+    stats: PlayerStats,
+    /// Number of games that are neither ended nor sandbox games.
+    active_games: u32,
+    /// When the player's season pass expires, in RFC 3339 format. `None` if they never
+    /// bought one.
+    season_pass_expires_at: Option<String>,
+}
+
+/// A row of the leaderboard.
+#[derive(SimpleObject)]
+struct LeaderboardRow {
+    #[graphql(flatten)]
+    entry: LeaderboardEntry,
+    /// The profile of the player, `None` if they have no state on this chain.
+    profile: Option<PlayerProfile>,
+}
+
+/// A row of the standings of a tournament.
+#[derive(SimpleObject)]
+struct StandingRow {
+    #[graphql(flatten)]
+    standing: TournamentStanding,
+    /// The profile of the entrant, `None` if they have no state on this chain.
+    profile: Option<PlayerProfile>,
+}
+
+/// A player's best score in a mode.
+#[derive(SimpleObject)]
+struct ModeBest {
+    mode: GameMode,
+    score: u64,
+}
+
+/// Season pass sales, for revenue reporting.
+#[derive(SimpleObject)]
+struct PassRevenue {
+    sales_count: u32,
+    revenue: Amount,
+    sales: Vec<PassSale>,
+}
+
+/// A page of game events, see `events`.
+#[derive(SimpleObject)]
+struct EventPage {
+    events: Vec<ListedEvent>,
+    /// The cursor to resume from to fetch the events emitted after this page.
+    cursor: String,
+    /// Whether events were already emitted after this page.
+    has_more: bool,
+}
+
+#[derive(SimpleObject)]
+struct ListedEvent {
+    /// The cursor right after the event.
+    cursor: String,
+    event: GameEvent,
+}
+
+/// A scripted tutorial, see `playTutorial`.
+#[derive(SimpleObject)]
+struct TutorialInfo {
+    /// The index of the tutorial, which operations refer to it by.
+    tutorial: u8,
+    name: String,
+    steps: Vec<TutorialStepInfo>,
+}
+
+#[derive(SimpleObject)]
+struct TutorialStepInfo {
+    /// The exponent of each tile, `0` for empty cells.
+    board: Vec<Vec<u16>>,
+    /// The only move the step accepts.
+    expected: Direction,
+    hint: String,
+}
+
+#[derive(SimpleObject)]
+struct InventoryItem {
+    item: String,
+    count: u32,
+}
+
+#[derive(SimpleObject)]
+struct CatalogItem {
+    item: String,
+    price: Amount,
+}
+
+/// The account receiving item payments.
+#[derive(SimpleObject)]
+struct Treasury {
+    chain_id: ChainId,
+    /// `None` for the chain balance.
+    owner: Option<Owner>,
+}
+
+/// The results of every game started from a template, best scores first.
+#[derive(SimpleObject)]
+struct TemplateResults {
+    template_id: u32,
+    organizer: Owner,
+    template: GameTemplate,
+    /// Whether only participants who joined with a code may start games.
+    invite_only: bool,
+    participants: Vec<Owner>,
+    /// Number of games that ended, by reaching the target tile, running out of moves or
+    /// running out of time.
+    finished_count: u32,
+    games: Vec<TemplateGame>,
+}
+
+#[derive(SimpleObject)]
+struct TemplateGame {
+    game_id: u16,
+    owner: Option<Owner>,
+    score: u64,
+    max_tile: u64,
+    move_count: u32,
+    is_ended: bool,
+}
+
+#[derive(SimpleObject)]
+struct TemplateJoinCode {
+    code: String,
+    capacity: u32,
+    uses: u32,
+    /// When the code stops admitting participants, in RFC 3339 format.
+    expires_at: String,
+}
+
+#[derive(SimpleObject)]
+struct BeaconRound {
+    round_id: u32,
+    organizer: Owner,
+    /// Deadline for commitments, in RFC 3339 format.
+    commit_deadline: String,
+    /// Deadline for reveals, in RFC 3339 format.
+    reveal_deadline: String,
+    stake: Amount,
+    commit_count: u32,
+    reveal_count: u32,
+    is_closed: bool,
+    output: Option<CryptoHash>,
+    /// The game seed derived from the output.
+    seed: Option<u16>,
+    slashed: Vec<Owner>,
+    forfeited: Amount,
+}
+
+#[derive(SimpleObject)]
+struct Sponsorship {
+    sponsorship_id: u32,
+    sponsor: Owner,
+    community_id: CommunityId,
+    /// When the sponsorship stops paying for games, in RFC 3339 format.
+    expires_at: String,
+    /// Prepaid fees not spent yet.
+    balance: Amount,
+    /// The games each sponsored player may still start on the sponsorship.
+    credits: Vec<SponsorCredit>,
+}
+
+#[derive(SimpleObject)]
+struct SponsorCredit {
+    player: Owner,
+    games: u32,
+}
+
+#[derive(SimpleObject)]
+struct Tournament {
+    tournament_id: u32,
+    organizer: Owner,
+    name: String,
+    seed: u16,
+    move_limit: u32,
+    /// When the tournament was created, in RFC 3339 format.
+    created_at: String,
+    /// When the games of the entrants end, in RFC 3339 format, `None` if they only end at
+    /// their move limit.
+    ends_at: Option<String>,
+    status: TournamentStatus,
+    is_closed: bool,
+    entrant_count: u32,
+    /// The entrants who submitted a result, best first.
+    standings: Vec<StandingRow>,
+}
+
+#[derive(SimpleObject)]
+struct PendingGame {
+    request_id: u32,
+    namespace: Option<CommunityId>,
+    sandbox: bool,
+    round_id: u32,
+    /// When the request expires if the game was not started, in RFC 3339 format.
+    expires_at: String,
+    /// The id the game will get, once its beacon round closed with an output.
+    game_id: Option<u16>,
+}
+
+/// What a deployment of the application accepts, for generic wallets to render operation forms.
+#[derive(SimpleObject)]
+struct AbiInfo {
+    /// The version operations are wrapped in by the mutations returning their bytes.
+    operation_version: u8,
+    /// The names of the optional subsystems enabled in this deployment.
+    features: Vec<&'static str>,
+    operations: Vec<OperationInfo>,
+}
+
+/// An operation, with the mutation returning its bytes.
+#[derive(SimpleObject)]
+struct OperationInfo {
+    name: String,
+    mutation: String,
+    /// The subsystem the operation belongs to, `None` if every deployment supports it.
+    feature: Option<&'static str>,
+    /// Whether this deployment accepts the operation.
+    enabled: bool,
+    arguments: Vec<ArgumentInfo>,
+}
+
+/// An argument of a mutation.
+#[derive(SimpleObject)]
+struct ArgumentInfo {
+    name: String,
+    /// The GraphQL type of the argument, such as `[Owner!]!`, optional unless it ends
+    /// with `!`.
+    type_name: String,
 }
 
 #[Object]
 impl QueryRoot {
     async fn game(&self, game_id: u16) -> Option<GameState> {
-        if let Ok(Some(game)) = self.state.games.try_load_entry(&game_id).await {
-            let game_state = GameState {
-                game_id: *game.game_id.get(),
-                board: Game::convert_to_matrix(*game.board.get()),
-                is_ended: *game.is_ended.get(),
-                score: *game.score.get(),
-            };
-            Some(game_state)
+        if let Ok(Some(game)) = load_entry(&self.state.games, &game_id).await {
+            Some(self.game_state(&game))
         } else {
             None
         }
     }
+
+    /// Renders the current board of a game as a small SVG image.
+    async fn board_svg(&self, game_id: u16, theme: Option<BoardTheme>) -> Option<String> {
+        let game = load_entry(&self.state.games, &game_id).await.ok()??;
+        Some(board_svg(*game.board.get(), theme.unwrap_or_default()))
+    }
+
+    /// Compares the moves of a finished game to the ones preferred by a bounded-depth solver.
+    async fn game_analysis(&self, game_id: u16) -> Option<GameAnalysis> {
+        let game = load_entry(&self.state.games, &game_id).await.ok()??;
+        game.analysis.get().clone()
+    }
+
+    /// Suggests the next move of a game, searched by the solver `depth` moves ahead, at most
+    /// `MAX_HINT_DEPTH`. Returns `None` if the game is over or does not exist.
+    async fn hint(&self, game_id: u16, depth: Option<u32>) -> Option<Hint> {
+        let game = load_entry(&self.state.games, &game_id).await.ok()??;
+        if game.is_ended() {
+            return None;
+        }
+        let depth = depth.unwrap_or(HINT_DEPTH).min(MAX_HINT_DEPTH);
+        let (direction, evaluation) = Solver::search(*game.board.get(), depth)?;
+        Some(Hint {
+            direction,
+            evaluation,
+        })
+    }
+
+    /// Returns the turns of a game from `offset` on, turn `0` being the creation of the game.
+    /// Turns the history retention of the game did not keep are skipped.
+    async fn moves(&self, game_id: u16, offset: Option<u32>) -> Option<Vec<Turn>> {
+        let game = load_entry(&self.state.games, &game_id).await.ok()??;
+        let turns = game.move_log.read(offset.unwrap_or(0)..).await.ok()?;
+        Some(turns.into_iter().map(|(_, turn)| turn).collect())
+    }
+
+    /// Predicts the effects of moving game `game_id` in `direction`, for frontends to show the
+    /// move before it is confirmed. The prediction is what the contract computes if the move
+    /// is the first one made on the game in the next block of this chain.
+    ///
+    /// This is a query rather than a `makeMove` result because the node service expects
+    /// mutations to return the operation bytes only. Returns `None` if the contract would
+    /// reject or drop the move: the game is over, belongs to another namespace, or does not
+    /// change in `direction`.
+    async fn predict_move(
+        &self,
+        game_id: u16,
+        direction: Direction,
+        namespace: Option<CommunityId>,
+    ) -> Option<MovePrediction> {
+        let game = load_entry(&self.state.games, &game_id).await.ok()??;
+        let board = *game.board.get();
+        if game.is_ended()
+            || *game.namespace.get() != namespace
+            || !Game::is_useful_move(board, direction.clone())
+        {
+            return None;
+        }
+        let template = match *game.template_id.get() {
+            Some(template_id) => {
+                let template = load_entry(&self.state.templates, &template_id).await;
+                Some(template.ok()??.settings.get().clone())
+            }
+            None => None,
+        };
+        let spawn_index = *game.spawn_index.get();
+        let operation = Operation::MakeMove {
+            game_id,
+            direction: direction.clone(),
+            namespace,
+        };
+        let operation = bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap();
+
+        let created_at = *game.created_at.get();
+        let deadline = template
+            .as_ref()
+            .and_then(|template| template.deadline(created_at));
+        if deadline.is_some_and(|deadline| self.now >= deadline) {
+            // The move ends the game instead.
+            return Some(MovePrediction {
+                operation,
+                board: Game::convert_to_matrix(board),
+                score_delta: 0,
+                will_end: true,
+                spawn_index,
+            });
+        }
+        let position = Position {
+            board,
+            score: *game.score.get(),
+            combo: *game.combo.get(),
+            mode: *game.mode.get(),
+            template: template.as_ref(),
+        };
+        let mut rng = Rng::for_spawn(self.next_block_height, self.chain_id, spawn_index);
+        let elapsed_micros = self.now.delta_since(created_at).as_micros();
+        let outcome = position.play(direction, &mut rng, elapsed_micros)?;
+        Some(MovePrediction {
+            operation,
+            board: Game::convert_to_matrix(outcome.board),
+            score_delta: outcome.score.saturating_sub(position.score),
+            will_end: outcome.is_ended,
+            spawn_index,
+        })
+    }
+
+    /// Returns metadata for sharing the result of a finished game, with a hash verifying it.
+    async fn share_card(&self, game_id: u16, theme: Option<BoardTheme>) -> Option<ShareCard> {
+        let game = load_entry(&self.state.games, &game_id).await.ok()??;
+        if !game.is_ended() {
+            return None;
+        }
+        let turns = game.move_log.read_all().await.ok()??;
+        let result = GameResult {
+            game_id,
+            namespace: game.namespace.get().clone(),
+            sandbox: *game.sandbox.get(),
+            mode: *game.mode.get(),
+            board: *game.board.get(),
+            score: *game.score.get(),
+            move_count: *game.move_count.get(),
+            turns,
+        };
+        Some(result.share_card(theme.unwrap_or_default()))
+    }
+
+    /// Lists the positions the moves of a game were played from, in the order they were
+    /// played, including the moves taken back.
+    async fn history(&self, game_id: u16) -> Option<Vec<MoveHistory>> {
+        let game = load_entry(&self.state.games, &game_id).await.ok()??;
+        game.history.read(..).await.ok()
+    }
+
+    /// Exports a game in the portable `.r2048` replay format, see `Replay`. Games are exported
+    /// as they are, whether they ended or not, unless their move log was not kept in full.
+    async fn replay(&self, game_id: u16) -> Option<Vec<u8>> {
+        let game = load_entry(&self.state.games, &game_id).await.ok()??;
+        let turns = game.move_log.read_all().await.ok()??;
+        if turns.is_empty() {
+            return None;
+        }
+        let replay = Replay::from_turns(
+            game_id,
+            *game.seed.get(),
+            *game.mode.get(),
+            *game.sandbox.get(),
+            &turns,
+        );
+        Some(replay.encode())
+    }
+
+    /// Lists the games of a community, or of the global namespace when `namespace` is omitted,
+    /// by id.
+    ///
+    /// Sandbox games are included unless `sandbox` is given, in which case only the games
+    /// whose sandbox flag matches it are listed.
+    async fn games(&self, namespace: Option<CommunityId>, sandbox: Option<bool>) -> Vec<GameState> {
+        let mut games = self.namespace_games(namespace).await;
+        if let Some(sandbox) = sandbox {
+            games.retain(|game| game.sandbox == sandbox);
+        }
+        games
+    }
+
+    /// Returns the `limit` best players of a namespace, or of the global namespace when
+    /// `namespace` is omitted, with their best ranked game in `mode`, or in any mode when
+    /// `mode` is omitted. Daily and weekly windows only rank the games that ended in the
+    /// current day or week. Ties go to the earliest game, then to the lowest owner and game id.
+    ///
+    /// `topN` is the former name of `limit`, kept for existing clients.
+    async fn leaderboard(
+        &self,
+        ctx: &Context<'_>,
+        mode: Option<GameMode>,
+        window: Option<LeaderboardWindow>,
+        limit: Option<u32>,
+        top_n: Option<u32>,
+        namespace: Option<CommunityId>,
+    ) -> Vec<LeaderboardRow> {
+        let window = window.unwrap_or_default();
+        let mut entries = Vec::new();
+        if mode.is_none() && window == LeaderboardWindow::AllTime {
+            self.state
+                .leaderboard
+                .for_each_index_value(|_, entry| {
+                    if entry.namespace == namespace {
+                        entries.push(entry);
+                    }
+                    Ok(())
+                })
+                .await
+                .unwrap();
+        } else {
+            let period = window.period(self.now);
+            self.state
+                .mode_leaderboards
+                .for_each_index_value(|key, entry| {
+                    if key.0 == mode && key.1 == window && key.2 == period && key.3 == namespace {
+                        entries.push(entry);
+                    }
+                    Ok(())
+                })
+                .await
+                .unwrap();
+        }
+        entries.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(a.ended_at.cmp(&b.ended_at))
+                .then(a.owner.cmp(&b.owner))
+                .then(a.game_id.cmp(&b.game_id))
+        });
+        entries.truncate(limit.or(top_n).unwrap_or(10) as usize);
+        let profiles = if ctx.look_ahead().field("profile").exists() {
+            self.load_profiles(entries.iter().map(|entry| entry.owner))
+                .await
+        } else {
+            BTreeMap::new()
+        };
+        entries
+            .into_iter()
+            .map(|entry| LeaderboardRow {
+                profile: profiles.get(&entry.owner).cloned(),
+                entry,
+            })
+            .collect()
+    }
+
+    /// Returns the `top_n` most efficient players of a namespace, or of the global namespace
+    /// when `namespace` is omitted, with their most efficient ranked game. Efficiency weighs
+    /// score per move, time under par and the largest tile, see `efficiencyWeights`. Ties go
+    /// to the earliest game, then to the lowest owner and game id.
+    async fn efficiency_leaderboard(
+        &self,
+        top_n: Option<u32>,
+        namespace: Option<CommunityId>,
+    ) -> Vec<EfficiencyEntry> {
+        let mut entries = Vec::new();
+        self.state
+            .efficiency_leaderboard
+            .for_each_index_value(|_, entry| {
+                if entry.namespace == namespace {
+                    entries.push(entry);
+                }
+                Ok(())
+            })
+            .await
+            .unwrap();
+        entries.sort_by(|a, b| {
+            b.efficiency
+                .cmp(&a.efficiency)
+                .then(a.ended_at.cmp(&b.ended_at))
+                .then(a.owner.cmp(&b.owner))
+                .then(a.game_id.cmp(&b.game_id))
+        });
+        entries.truncate(top_n.unwrap_or(10) as usize);
+        entries
+    }
+
+    /// The weights of the efficiency leaderboard, set in the application parameters.
+    async fn efficiency_weights(&self) -> EfficiencyWeights {
+        self.parameters.efficiency_weights.clone()
+    }
+
+    /// The chain the turns of every game are reported to, `None` if they are not reported.
+    async fn hub(&self) -> Option<ChainId> {
+        self.parameters.hub
+    }
+
+    /// The campaign the application was deployed for, if any.
+    async fn campaign_id(&self) -> Option<String> {
+        self.parameters.campaign_id.clone()
+    }
+
+    /// The names of the optional subsystems enabled in this deployment.
+    async fn features(&self) -> Vec<&'static str> {
+        self.parameters.features.names()
+    }
+
+    /// Describes the operations the mutations of the service build, with their arguments and
+    /// whether the subsystem they belong to is enabled in this deployment.
+    async fn abi_info(&self) -> AbiInfo {
+        // The arguments are read from the schema of the mutations, so that they cannot drift
+        // from them. The registry is internal to `async-graphql`, whose version is pinned.
+        let mut registry = Registry::default();
+        <MutationRoot as OutputType>::create_type_info(&mut registry);
+        let mutation_type = <MutationRoot as OutputType>::type_name();
+        let Some(MetaType::Object { fields, .. }) = registry.types.get(&*mutation_type) else {
+            unreachable!("mutations are resolved by an object");
+        };
+        let operations = fields
+            .values()
+            .map(|field| {
+                let mut name = field.name.clone();
+                name[..1].make_ascii_uppercase();
+                let feature = FeatureFlags::of_operation(&name);
+                OperationInfo {
+                    feature: feature.and_then(FeatureFlags::name),
+                    enabled: feature
+                        .map_or(true, |feature| self.parameters.features.contains(feature)),
+                    name,
+                    mutation: field.name.clone(),
+                    arguments: field
+                        .args
+                        .values()
+                        .map(|argument| ArgumentInfo {
+                            name: argument.name.clone(),
+                            type_name: argument.ty.clone(),
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+        AbiInfo {
+            operation_version: CURRENT_OPERATION_VERSION,
+            features: self.parameters.features.names(),
+            operations,
+        }
+    }
+
+    /// Exports a page of the games of a namespace ranked by score, ties broken by game id.
+    /// Sandbox games and games below the leaderboard filters are not ranked.
+    async fn leaderboard_export(
+        &self,
+        format: Option<ExportFormat>,
+        namespace: Option<CommunityId>,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> Export {
+        let format = format.unwrap_or_default();
+        let offset = offset.unwrap_or(0);
+        let limit = limit.unwrap_or(100).min(1_000);
+
+        let mut games = self.namespace_games(namespace).await;
+        games.retain(|game| game.leaderboard_exclusion.is_none());
+        games.sort_by(|a, b| b.score.cmp(&a.score).then(a.game_id.cmp(&b.game_id)));
+        let total = games.len() as u32;
+        let rows = games
+            .into_iter()
+            .zip(1..)
+            .skip(offset as usize)
+            .take(limit as usize)
+            .map(|(game, rank)| ExportRow {
+                rank,
+                game_id: game.game_id,
+                namespace: game.namespace,
+                mode: game.mode,
+                score: game.score,
+                move_count: game.move_count,
+                is_ended: game.is_ended,
+            })
+            .collect();
+
+        let bundle = ExportBundle { offset, rows };
+        Export {
+            format,
+            offset,
+            total,
+            content: bundle.render(format),
+            row_hashes: bundle
+                .row_hashes()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            bundle_hash: bundle.hash().to_string(),
+        }
+    }
+
+    async fn community(&self, community_id: CommunityId) -> Option<Community> {
+        if let Ok(Some(community)) = load_entry(&self.state.communities, &community_id).await {
+            Some(Self::community_info(community_id, &community))
+        } else {
+            None
+        }
+    }
+
+    /// Lists all communities hosted by this application with their member counts, by id.
+    async fn communities(&self) -> Vec<Community> {
+        let mut community_ids = self.state.communities.indices().await.unwrap();
+        community_ids.sort();
+        let mut communities = Vec::new();
+        for community_id in community_ids {
+            if let Ok(Some(community)) = load_entry(&self.state.communities, &community_id).await {
+                communities.push(Self::community_info(community_id, &community));
+            }
+        }
+        communities
+    }
+
+    /// The profile of `owner`, with modes, items, games, sponsorships and achievements listed
+    /// in ascending order.
+    async fn player(&self, owner: Owner) -> Option<Player> {
+        let player = load_entry(&self.state.players, &owner).await.ok()??;
+        let mut mode_bests = Vec::new();
+        player
+            .mode_bests
+            .for_each_index_value(|mode, score| {
+                mode_bests.push(ModeBest { mode, score });
+                Ok(())
+            })
+            .await
+            .ok()?;
+        mode_bests.sort_by_key(|best| best.mode);
+        let mut inventory = Vec::new();
+        player
+            .inventory
+            .for_each_index_value(|item, count| {
+                inventory.push(InventoryItem { item, count });
+                Ok(())
+            })
+            .await
+            .ok()?;
+        inventory.sort_by(|a, b| a.item.cmp(&b.item));
+        let mut games = player.games.indices().await.ok()?;
+        games.sort();
+        let mut sponsorships = player.sponsorships.indices().await.ok()?;
+        sponsorships.sort();
+        let mut achievements = player.achievements.indices().await.ok()?;
+        achievements.sort();
+        let mut tutorial_steps = Vec::new();
+        for tutorial in 0..TUTORIALS.len() as u8 {
+            let completed = player.tutorial_steps.get(&tutorial).await.ok()?;
+            tutorial_steps.push(completed.unwrap_or_default());
+        }
+        Some(Player {
+            owner,
+            stats: player.stats.get().clone(),
+            heatmap: player.heatmap.get().clone(),
+            mode_bests,
+            games,
+            merge_request: *player.merge_request.get(),
+            merge_confirmed: *player.merge_confirmed.get(),
+            active_games: *player.active_games.get(),
+            inventory,
+            season_pass_expires_at: player
+                .season_pass
+                .get()
+                .map(|pass| rfc3339(pass.expires_at)),
+            sponsorships,
+            tutorial_steps,
+            achievements,
+        })
+    }
+
+    /// The tutorials shipped with the game, in the order players are offered them.
+    async fn tutorials(&self) -> Vec<TutorialInfo> {
+        (0..)
+            .zip(&TUTORIALS)
+            .map(|(tutorial, Tutorial { name, steps })| TutorialInfo {
+                tutorial,
+                name: name.to_string(),
+                steps: steps
+                    .iter()
+                    .map(|step| TutorialStepInfo {
+                        board: Game::convert_to_matrix(step.board)
+                            .iter()
+                            .map(|row| row.to_vec())
+                            .collect(),
+                        expected: step.expected.clone(),
+                        hint: step.hint.to_string(),
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// The attestation of `owner`'s best score for the current epoch, for the attester to sign
+    /// and submit.
+    async fn attestation_payload(&self, owner: Owner) -> ScoreAttestation {
+        let best_score = match load_entry(&self.state.players, &owner).await.unwrap() {
+            Some(player) => player.stats.get().best_score,
+            None => 0,
+        };
+        ScoreAttestation {
+            owner,
+            best_score,
+            application_id: self.application_id,
+            epoch: attestation_epoch(self.now),
+        }
+    }
+
+    /// The latest attestation submitted for `owner`, to be checked with `verify_attestation`
+    /// against the `attester` key.
+    async fn score_attestation(&self, owner: Owner) -> Option<SignedAttestation> {
+        self.state.attestations.get(&owner).await.unwrap()
+    }
+
+    async fn attester(&self) -> Option<PublicKey> {
+        *self.state.attester.get()
+    }
+
+    /// Lists the players waiting for a match on this chain, longest waiting first, optionally
+    /// only those of `lobby`.
+    async fn match_queue(&self, lobby: Option<String>) -> Vec<QueuedPlayer> {
+        let mut queue = self.queue_entries().await;
+        if let Some(lobby) = lobby {
+            queue.retain(|entry| entry.lobby == lobby);
+        }
+        queue
+            .iter()
+            .map(|entry| QueuedPlayer {
+                owner: entry.owner,
+                lobby: entry.lobby.clone(),
+                rating: entry.rating,
+                enqueued_at: rfc3339(entry.enqueued_at),
+                window: entry.window(self.now),
+                estimated_wait_secs: queue
+                    .iter()
+                    .filter(|other| other.owner != entry.owner && other.lobby == entry.lobby)
+                    .map(|other| entry.seconds_until_compatible(other, self.now))
+                    .min(),
+            })
+            .collect()
+    }
+
+    /// Lists the matchmaking lobbies of this chain, the default one first and the others by
+    /// name, with statistics on their queues and the matches made in them.
+    async fn lobbies(&self) -> Vec<LobbyInfo> {
+        let mut lobbies = Vec::new();
+        self.state
+            .lobbies
+            .for_each_index_value(|name, settings| {
+                lobbies.push((name, settings));
+                Ok(())
+            })
+            .await
+            .unwrap();
+        lobbies.sort_by(|a, b| a.0.cmp(&b.0));
+        lobbies.insert(0, (DEFAULT_LOBBY.to_string(), Lobby::default()));
+        let queue = self.queue_entries().await;
+        let mut infos = Vec::new();
+        for (name, settings) in lobbies {
+            let queued = queue.iter().filter(|entry| entry.lobby == name);
+            let stats = self.state.lobby_stats.get(&name).await.unwrap();
+            let stats = stats.unwrap_or_default();
+            let matched_players = 2 * u64::from(stats.matches);
+            infos.push(LobbyInfo {
+                queued: queued.clone().count() as u32,
+                longest_wait_secs: queued
+                    .map(|entry| entry.waited_secs(self.now))
+                    .max()
+                    .unwrap_or(0),
+                average_wait_secs: (matched_players > 0)
+                    .then(|| stats.total_wait_secs / matched_players),
+                name,
+                settings,
+                stats,
+            });
+        }
+        infos
+    }
+
+    /// Lists the matches made on this chain, by id.
+    async fn matches(&self) -> Vec<MatchRecord> {
+        let mut matches = Vec::new();
+        self.state
+            .matches
+            .for_each_index_value(|_, record| {
+                matches.push(record);
+                Ok(())
+            })
+            .await
+            .unwrap();
+        matches.sort_by_key(|record| record.match_id);
+        matches
+    }
+
+    /// The latest match of `owner`, if they joined the queue from this chain.
+    async fn match_notice(&self, owner: Owner) -> Option<MatchNotice> {
+        self.state.match_notices.get(&owner).await.unwrap()
+    }
+
+    /// The dispute of the result of match `match_id`, on the matchmaking chain.
+    async fn dispute(&self, match_id: u32) -> Option<MatchDispute> {
+        self.state.disputes.get(&match_id).await.unwrap()
+    }
+
+    /// The chat of match `match_id`, on the matchmaking chain. Queries are not
+    /// authenticated: encrypted payloads are served as is, for the players to decrypt.
+    async fn chat(&self, match_id: u32) -> Vec<ChatMessage> {
+        let chat = self.state.chats.get(&match_id).await.unwrap();
+        chat.unwrap_or_default()
+    }
+
+    /// The key `owner` published to receive encrypted chat messages, on the matchmaking
+    /// chain.
+    async fn chat_key(&self, owner: Owner) -> Option<Vec<u8>> {
+        self.state.chat_keys.get(&owner).await.unwrap()
+    }
+
+    /// The bond players post to dispute the result of a match.
+    async fn dispute_bond(&self) -> Amount {
+        self.parameters.dispute_bond
+    }
+
+    /// The bond players post to challenge the replay a dispute resolution relies on.
+    async fn dispute_challenge_bond(&self) -> Amount {
+        self.parameters.dispute_challenge_bond
+    }
+
+    /// How long, in microseconds, the resolution of a dispute can be challenged before its
+    /// bonds are paid out.
+    async fn dispute_challenge_window_micros(&self) -> u64 {
+        self.parameters.dispute_challenge_window_micros
+    }
+
+    /// The minimum time, in microseconds, between two moves of a game. `0` for no limit.
+    async fn min_move_interval_micros(&self) -> u64 {
+        self.parameters.min_move_interval_micros
+    }
+
+    /// Lists the challenges this chain sent or received, optionally only those in `status`,
+    /// by challenger chain and challenge id.
+    async fn challenges(&self, status: Option<ChallengeStatus>) -> Vec<ChallengeRecord> {
+        let mut challenges = Vec::new();
+        self.state
+            .challenges
+            .for_each_index_value(|_, record| {
+                if status.map_or(true, |status| record.status == status) {
+                    challenges.push(record);
+                }
+                Ok(())
+            })
+            .await
+            .unwrap();
+        challenges.sort_by_key(|record| (record.challenger_chain, record.challenge_id));
+        challenges
+    }
+
+    /// Challenge `challenge_id` of `challenger_chain`, if this chain took part in it.
+    async fn challenge(
+        &self,
+        challenger_chain: ChainId,
+        challenge_id: u32,
+    ) -> Option<ChallengeRecord> {
+        let key = (challenger_chain, challenge_id);
+        self.state.challenges.get(&key).await.unwrap()
+    }
+
+    /// The games of other chains this chain spectates, up to date with the turns received,
+    /// by chain and game id. The hub lists every game reported to it.
+    async fn spectated_games(&self) -> Vec<SpectatedGame> {
+        let mut games = Vec::new();
+        self.state
+            .spectated
+            .for_each_index_value(|_, game| {
+                games.push(game);
+                Ok(())
+            })
+            .await
+            .unwrap();
+        games.sort_by_key(|game| (game.chain_id, game.game_id));
+        games
+    }
+
+    /// Returns the `top_n` best games reported to the hub, across all chains. Ties go to the
+    /// earliest chain and game id.
+    async fn remote_leaderboard(&self, top_n: Option<u32>) -> Vec<RemoteScore> {
+        let mut scores = Vec::new();
+        self.state
+            .remote_scores
+            .for_each_index_value(|(chain_id, game_id), score| {
+                scores.push(RemoteScore {
+                    chain_id,
+                    game_id,
+                    score,
+                });
+                Ok(())
+            })
+            .await
+            .unwrap();
+        scores.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(a.chain_id.cmp(&b.chain_id))
+                .then(a.game_id.cmp(&b.game_id))
+        });
+        scores.truncate(top_n.unwrap_or(10) as usize);
+        scores
+    }
+
+    /// The best score of all the games reported to the hub.
+    async fn best_remote_score(&self) -> Option<RemoteScore> {
+        self.state.best_remote_score.get().clone()
+    }
+
+    /// The chains spectating game `game_id` of this chain.
+    async fn spectators(&self, game_id: u16) -> Vec<ChainId> {
+        let spectators = self.state.spectators.get(&game_id).await.unwrap();
+        spectators.unwrap_or_default()
+    }
+
+    /// Counters of the operations executed on this chain and of the messages it sent. The
+    /// operations are listed most executed first, then by name.
+    async fn metrics(&self) -> Metrics {
+        let metrics = &self.state.metrics;
+        let mut operations = Vec::new();
+        metrics
+            .operations
+            .for_each_index_value(|operation, count| {
+                operations.push(OperationCount { operation, count });
+                Ok(())
+            })
+            .await
+            .unwrap();
+        operations.sort_by(|a, b| b.count.cmp(&a.count).then(a.operation.cmp(&b.operation)));
+        let mut failures = Vec::new();
+        metrics
+            .failures
+            .for_each_index_value(|failure, count| {
+                failures.push(FailureCount { failure, count });
+                Ok(())
+            })
+            .await
+            .unwrap();
+        failures.sort_by_key(|failure| failure.failure);
+        Metrics {
+            operations,
+            failures,
+            messages_sent: *metrics.messages_sent.get(),
+            events_emitted: *metrics.events_emitted.get(),
+            kept_turns: *metrics.kept_turns.get(),
+        }
+    }
+
+    async fn leaderboard_filters(&self) -> LeaderboardFilters {
+        self.state.leaderboard_filters.get().clone()
+    }
+
+    /// The settings applied to the games created in `mode`.
+    async fn mode_settings(&self, mode: GameMode) -> ModeSettings {
+        let settings = self.state.modes.get(&mode).await.unwrap();
+        settings.unwrap_or_default()
+    }
+
+    async fn pass_settings(&self) -> Option<PassSettings> {
+        self.state.pass_settings.get().clone()
+    }
+
+    /// The game events emitted after the `after` cursor, oldest first, at most `limit` of them
+    /// (100 by default). Reconnecting clients pass the cursor of the last page they got to
+    /// fetch exactly the events they missed. Lists events from the first one without a cursor;
+    /// `None` for malformed cursors.
+    async fn events(&self, after: Option<String>, limit: Option<u32>) -> Option<EventPage> {
+        let after = match after {
+            Some(after) => EventCursor::parse(&after)?,
+            None => EventCursor::START,
+        };
+        let count = self.state.game_events.count() as u64;
+        let start = after.first_index().min(count);
+        let end = count.min(start + u64::from(limit.unwrap_or(100)));
+        let events = self
+            .state
+            .game_events
+            .read(start as usize..end as usize)
+            .await
+            .ok()?;
+        let cursor = if end > start {
+            EventCursor::after(end - 1)
+        } else {
+            after
+        };
+        Some(EventPage {
+            events: (start..)
+                .zip(events)
+                .map(|(index, event)| ListedEvent {
+                    cursor: EventCursor::after(index).to_string(),
+                    event,
+                })
+                .collect(),
+            cursor: cursor.to_string(),
+            has_more: end < count,
+        })
+    }
+
+    async fn pass_revenue(&self) -> PassRevenue {
+        let sales = self.state.pass_sales.read(..).await.unwrap();
+        PassRevenue {
+            sales_count: sales.len() as u32,
+            revenue: *self.state.pass_revenue.get(),
+            sales,
+        }
+    }
+
+    /// Lists the power-up items for sale with their prices, in native tokens, by item name.
+    async fn item_catalog(&self) -> Vec<CatalogItem> {
+        let mut catalog = Vec::new();
+        self.state
+            .item_prices
+            .for_each_index_value(|item, price| {
+                catalog.push(CatalogItem { item, price });
+                Ok(())
+            })
+            .await
+            .unwrap();
+        catalog.sort_by(|a, b| a.item.cmp(&b.item));
+        catalog
+    }
+
+    async fn treasury(&self) -> Option<Treasury> {
+        let treasury = (*self.state.treasury.get())?;
+        Some(Treasury {
+            chain_id: treasury.chain_id,
+            owner: treasury.owner,
+        })
+    }
+
+    /// The maximum number of active games per player.
+    async fn active_game_limit(&self) -> u32 {
+        self.game_limit()
+    }
+
+    /// How many more active games `owner` may start before reaching the limit. Sandbox
+    /// games are not limited.
+    async fn remaining_game_slots(&self, owner: Owner) -> Option<u32> {
+        let player = load_entry(&self.state.players, &owner).await.ok()?;
+        let active_games = player.map_or(0, |player| *player.active_games.get());
+        Some(self.game_limit().saturating_sub(active_games))
+    }
+
+    /// Lists every account merge, oldest first.
+    async fn account_merges(&self) -> Vec<AccountMerge> {
+        self.state.account_merges.read(..).await.unwrap()
+    }
+
+    async fn beacon_round(&self, round_id: u32) -> Option<BeaconRound> {
+        let round = load_entry(&self.state.beacon_rounds, &round_id).await;
+        let round = round.ok()??;
+        let output = *round.output.get();
+        Some(BeaconRound {
+            round_id,
+            organizer: (*round.organizer.get())?,
+            commit_deadline: rfc3339(*round.commit_deadline.get()),
+            reveal_deadline: rfc3339(*round.reveal_deadline.get()),
+            stake: *round.stake.get(),
+            commit_count: *round.commit_count.get(),
+            reveal_count: *round.reveal_count.get(),
+            is_closed: *round.is_closed.get(),
+            output,
+            seed: output.as_ref().map(beacon_seed),
+            slashed: round.slashed.get().clone(),
+            forfeited: *round.forfeited.get(),
+        })
+    }
+
+    /// The keys node operators sign critical responses with, such as the claims of
+    /// `finalScoreClaim` and `sponsorshipBalanceClaim`.
+    async fn response_signers(&self) -> Vec<PublicKey> {
+        self.parameters.response_signers.clone()
+    }
+
+    /// The final score of game `game_id`, for a response signer to sign. `None` unless the
+    /// game is over.
+    async fn final_score_claim(&self, game_id: u16) -> Option<ResponseClaim> {
+        let game = load_entry(&self.state.games, &game_id).await.ok()??;
+        if !game.is_ended() {
+            return None;
+        }
+        Some(self.claim(ClaimedValue::FinalScore {
+            game_id,
+            owner: *game.owner.get(),
+            score: *game.score.get(),
+        }))
+    }
+
+    /// The unspent balance of sponsorship `sponsorship_id`, for a response signer to sign.
+    /// `None` if the sponsorship does not exist.
+    async fn sponsorship_balance_claim(&self, sponsorship_id: u32) -> Option<ResponseClaim> {
+        let sponsorship = load_entry(&self.state.sponsorships, &sponsorship_id).await;
+        let sponsorship = sponsorship.ok()??;
+        if sponsorship.sponsor.get().is_none() {
+            return None;
+        }
+        Some(self.claim(ClaimedValue::SponsorshipBalance {
+            sponsorship_id,
+            balance: *sponsorship.balance.get(),
+        }))
+    }
+
+    async fn sponsorship(&self, sponsorship_id: u32) -> Option<Sponsorship> {
+        let sponsorship = load_entry(&self.state.sponsorships, &sponsorship_id).await;
+        let sponsorship = sponsorship.ok()??;
+        let mut credits = Vec::new();
+        sponsorship
+            .credits
+            .for_each_index_value(|player, games| {
+                credits.push(SponsorCredit { player, games });
+                Ok(())
+            })
+            .await
+            .ok()?;
+        credits.sort_by_key(|credit| credit.player);
+        Some(Sponsorship {
+            sponsorship_id,
+            sponsor: (*sponsorship.sponsor.get())?,
+            community_id: sponsorship.community_id.get().clone(),
+            expires_at: rfc3339(*sponsorship.expires_at.get()),
+            balance: *sponsorship.balance.get(),
+            credits,
+        })
+    }
+
+    /// Computes the commitment `owner` submits for `entropy`. Query it from your own node:
+    /// the entropy must stay secret until the reveal phase.
+    async fn beacon_commitment(&self, owner: Owner, entropy: Vec<u8>) -> CryptoHash {
+        commitment(owner, &entropy)
+    }
+
+    /// The finished games of the daily challenge of `date`, as `YYYY-MM-DD`, best first, the
+    /// first to end first among equal scores. Defaults to today's challenge; `None` for
+    /// malformed dates.
+    async fn daily_leaderboard(
+        &self,
+        date: Option<String>,
+        limit: Option<u32>,
+    ) -> Option<Vec<DailyScore>> {
+        let day = match date {
+            Some(date) => day_from_date(&date)?,
+            None => day_of(self.now),
+        };
+        let mut scores = Vec::new();
+        self.state
+            .daily_scores
+            .for_each_index_value(|key, entry| {
+                if key.0 == day && entry.ended_at.is_some() {
+                    scores.push(entry);
+                }
+                Ok(())
+            })
+            .await
+            .ok()?;
+        scores.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(a.ended_at.cmp(&b.ended_at))
+                .then(a.player.cmp(&b.player))
+        });
+        scores.truncate(limit.unwrap_or(10) as usize);
+        Some(scores)
+    }
+
+    async fn tournament(&self, ctx: &Context<'_>, tournament_id: u32) -> Option<Tournament> {
+        let tournament = load_entry(&self.state.tournaments, &tournament_id).await;
+        let tournament = tournament.ok()??;
+        let mut results = Vec::new();
+        tournament
+            .results
+            .for_each_index_value(|_, result| {
+                results.push(result);
+                Ok(())
+            })
+            .await
+            .ok()?;
+        let standings = tournament_standings(results);
+        let selects_profiles = ctx
+            .look_ahead()
+            .field("standings")
+            .field("profile")
+            .exists();
+        let profiles = if selects_profiles {
+            self.load_profiles(standings.iter().map(|standing| standing.player))
+                .await
+        } else {
+            BTreeMap::new()
+        };
+        let standings = standings
+            .into_iter()
+            .map(|standing| StandingRow {
+                profile: profiles.get(&standing.player).cloned(),
+                standing,
+            })
+            .collect();
+        Some(Tournament {
+            tournament_id,
+            organizer: (*tournament.organizer.get())?,
+            name: tournament.name.get().clone(),
+            seed: *tournament.seed.get(),
+            move_limit: *tournament.move_limit.get(),
+            created_at: rfc3339(*tournament.created_at.get()),
+            ends_at: tournament.ends_at.get().map(rfc3339),
+            status: tournament.status(self.now),
+            is_closed: *tournament.is_closed.get(),
+            entrant_count: tournament.entrants.indices().await.ok()?.len() as u32,
+            standings,
+        })
+    }
+
+    async fn template_results(&self, template_id: u32) -> Option<TemplateResults> {
+        let template = load_entry(&self.state.templates, &template_id).await;
+        let template = template.ok()??;
+        let mut game_ids = template.games.indices().await.ok()?;
+        game_ids.sort();
+        let mut games = Vec::new();
+        for game_id in game_ids {
+            let Ok(Some(game)) = load_entry(&self.state.games, &game_id).await else {
+                continue;
+            };
+            if *game.template_id.get() != Some(template_id) {
+                continue;
+            }
+            games.push(TemplateGame {
+                game_id,
+                owner: *game.owner.get(),
+                score: *game.score.get(),
+                max_tile: Game::max_tile(*game.board.get()),
+                move_count: *game.move_count.get(),
+                is_ended: game.is_ended(),
+            });
+        }
+        games.sort_by(|a, b| b.score.cmp(&a.score).then(a.game_id.cmp(&b.game_id)));
+        let mut participants = template.participants.indices().await.ok()?;
+        participants.sort();
+
+        Some(TemplateResults {
+            template_id,
+            organizer: (*template.organizer.get())?,
+            template: template.settings.get().clone(),
+            invite_only: *template.invite_only.get(),
+            participants,
+            finished_count: games.iter().filter(|game| game.is_ended).count() as u32,
+            games,
+        })
+    }
+
+    /// Lists the join codes minted for `template_id`, including expired and full ones, by
+    /// code.
+    async fn join_codes(&self, template_id: u32) -> Vec<TemplateJoinCode> {
+        let mut join_codes = Vec::new();
+        self.state
+            .join_codes
+            .for_each_index_value(|code, join_code| {
+                if join_code.template_id == template_id {
+                    join_codes.push(TemplateJoinCode {
+                        code,
+                        capacity: join_code.capacity,
+                        uses: join_code.uses,
+                        expires_at: rfc3339(join_code.expires_at),
+                    });
+                }
+                Ok(())
+            })
+            .await
+            .unwrap();
+        join_codes.sort_by(|a, b| a.code.cmp(&b.code));
+        join_codes
+    }
+
+    /// Lists the games `owner` requested and has not started yet, including expired ones
+    /// until they are dropped, by request id.
+    async fn pending_games(&self, owner: Owner) -> Vec<PendingGame> {
+        let mut requests = Vec::new();
+        self.state
+            .game_requests
+            .for_each_index_value(|request_id, request| {
+                if request.owner == owner {
+                    requests.push((request_id, request));
+                }
+                Ok(())
+            })
+            .await
+            .unwrap();
+        requests.sort_by_key(|(request_id, _)| *request_id);
+
+        let mut pending_games = Vec::new();
+        for (request_id, request) in requests {
+            let round = load_entry(&self.state.beacon_rounds, &request.round_id).await;
+            let output = round.ok().flatten().and_then(|round| *round.output.get());
+            pending_games.push(PendingGame {
+                request_id,
+                namespace: request.namespace,
+                sandbox: request.sandbox,
+                round_id: request.round_id,
+                expires_at: rfc3339(request.expires_at),
+                game_id: output.map(|output| game_request_seed(&output, request_id)),
+            });
+        }
+        pending_games
+    }
 }
 
+impl QueryRoot {
+    /// Returns a claim that this chain serves `value` as of its next block.
+    fn claim(&self, value: ClaimedValue) -> ResponseClaim {
+        ResponseClaim {
+            application_id: self.application_id,
+            chain_id: self.chain_id,
+            next_block_height: BlockHeight(self.next_block_height),
+            value,
+        }
+    }
+
+    /// Loads the profiles of the players of the rows of a ranking in one pass, reading the
+    /// state of each player once however many rows they have, instead of once per row.
+    async fn load_profiles(
+        &self,
+        owners: impl IntoIterator<Item = Owner>,
+    ) -> BTreeMap<Owner, PlayerProfile> {
+        let mut profiles = BTreeMap::new();
+        for owner in owners.into_iter().collect::<BTreeSet<_>>() {
+            let Ok(Some(player)) = load_entry(&self.state.players, &owner).await else {
+                continue;
+            };
+            let profile = PlayerProfile {
+                owner,
+                stats: player.stats.get().clone(),
+                active_games: *player.active_games.get(),
+                season_pass_expires_at: player
+                    .season_pass
+                    .get()
+                    .map(|pass| rfc3339(pass.expires_at)),
+            };
+            profiles.insert(owner, profile);
+        }
+        profiles
+    }
+
+    /// Returns the matchmaking queue, longest waiting players first, then by owner.
+    async fn queue_entries(&self) -> Vec<QueueEntry> {
+        let mut queue = Vec::new();
+        self.state
+            .match_queue
+            .for_each_index_value(|_, entry| {
+                queue.push(entry);
+                Ok(())
+            })
+            .await
+            .unwrap();
+        queue.sort_by_key(|entry| (entry.enqueued_at, entry.owner));
+        queue
+    }
+
+    fn game_limit(&self) -> u32 {
+        let limit = self.state.active_game_limit.get();
+        limit.unwrap_or(DEFAULT_ACTIVE_GAME_LIMIT)
+    }
+
+    async fn namespace_games(&self, namespace: Option<CommunityId>) -> Vec<GameState> {
+        let mut game_ids = self.state.games.indices().await.unwrap();
+        game_ids.sort();
+        let mut games = Vec::new();
+        for game_id in game_ids {
+            if let Ok(Some(game)) = load_entry(&self.state.games, &game_id).await {
+                if *game.namespace.get() == namespace {
+                    games.push(self.game_state(&game));
+                }
+            }
+        }
+        games
+    }
+
+    fn game_state(&self, game: &state::GameState) -> GameState {
+        let move_count = *game.move_count.get();
+        let created_at = *game.created_at.get();
+        let updated_at = *game.updated_at.get();
+        let sandbox = *game.sandbox.get();
+        let board = *game.board.get();
+        let large_board = *game.large_board.get();
+        let (matrix, board_hex) = match &large_board {
+            Some(large_board) => (large_board.convert_to_matrix(), large_board.to_hex()),
+            None => (board.convert_to_matrix(), format!("{board:016x}")),
+        };
+        let duration_seconds = duration_seconds(created_at, updated_at);
+        let verification = *game.verification.get();
+        let leaderboard_exclusion = match verification {
+            Some(GameVerification::Flagged) => Some(LeaderboardExclusion::FailedVerification),
+            _ if large_board.is_some() => Some(LeaderboardExclusion::LargeBoard),
+            _ => self.state.leaderboard_filters.get().exclusion(
+                sandbox,
+                move_count,
+                duration_seconds,
+            ),
+        };
+        GameState {
+            game_id: *game.game_id.get(),
+            owner: *game.owner.get(),
+            namespace: game.namespace.get().clone(),
+            sandbox,
+            seed_round: *game.seed_round.get(),
+            template_id: *game.template_id.get(),
+            daily_challenge: game.daily_challenge.get().map(date_of),
+            mode: *game.mode.get(),
+            seed: *game.seed.get(),
+            board_size: large_board.map_or(SUPPORTED_BOARD_SIZE, |board| board.size()),
+            tiles: matrix
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|&exponent| if exponent == 0 { 0 } else { 1 << exponent })
+                        .collect()
+                })
+                .collect(),
+            board: matrix,
+            board_hex,
+            status: *game.status.get(),
+            is_ended: game.is_ended(),
+            score: *game.score.get(),
+            highest_tile: *game.highest_tile.get(),
+            total_merges: *game.total_merges.get(),
+            largest_merge: *game.largest_merge.get(),
+            move_count,
+            max_moves: *game.max_moves.get(),
+            spawn_index: *game.spawn_index.get(),
+            undos_left: *game.undos_left.get(),
+            history_retention: *game.move_log.retention.get(),
+            created_at: rfc3339(created_at),
+            updated_at: rfc3339(updated_at),
+            last_move_at: game.last_move_at.get().map(rfc3339),
+            duration_seconds,
+            moves_per_minute: moves_per_minute(move_count, created_at, updated_at),
+            verification,
+            leaderboard_exclusion,
+        }
+    }
+
+    fn community_info(community_id: CommunityId, community: &state::CommunityState) -> Community {
+        Community {
+            community_id,
+            name: community.name.get().clone(),
+            admins: community.admins.get().clone(),
+            default_mode: *community.default_mode.get(),
+            fees: community.fees.get().clone(),
+            member_count: *community.member_count.get(),
+        }
+    }
+}
+
+/// Loads entry `index` of `collection`, `None` if it is missing.
+///
+/// The fields of a query are resolved concurrently, and a collection refuses to lend an entry
+/// while another one is loading or borrowed, so a lookup waits for the other fields to release
+/// the collection instead of failing.
+async fn load_entry<'a, I: Serialize, W: View<ViewStorageContext>>(
+    collection: &'a CollectionView<I, W>,
+    index: &I,
+) -> Result<Option<ReadGuardedView<'a, W>>, ViewError> {
+    loop {
+        match collection.try_load_entry(index).await {
+            Err(ViewError::CannotAcquireCollectionEntry) => yield_once().await,
+            result => return result,
+        }
+    }
+}
+
+/// Builds the operations of the contract, one mutation per operation, wrapped in the current
+/// `OperationEnvelope`. The mutations `Operation` derives build bare operations, which the
+/// contract does not accept; the tests check that both cover the same operations.
 struct MutationRoot;
 
 #[Object]
 impl MutationRoot {
-    async fn new_game(&self, seed: Option<u16>) -> Vec<u8> {
-        let seed = seed.unwrap_or(0);
-        bcs::to_bytes(&Operation::NewGame { seed }).unwrap()
+    async fn new_game(
+        &self,
+        seed: Option<u16>,
+        namespace: Option<CommunityId>,
+        sandbox: Option<bool>,
+        max_moves: Option<u32>,
+        board_size: Option<u8>,
+    ) -> Vec<u8> {
+        let operation = Operation::NewGame {
+            seed: seed.unwrap_or(0),
+            namespace,
+            sandbox: sandbox.unwrap_or(false),
+            max_moves,
+            board_size,
+        };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn end_game(&self, game_id: u16, namespace: Option<CommunityId>) -> Vec<u8> {
+        let operation = Operation::EndGame { game_id, namespace };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn make_move(
+        &self,
+        game_id: u16,
+        direction: Direction,
+        namespace: Option<CommunityId>,
+    ) -> Vec<u8> {
+        let operation = Operation::MakeMove {
+            game_id,
+            direction,
+            namespace,
+        };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn undo(&self, game_id: u16, namespace: Option<CommunityId>) -> Vec<u8> {
+        let operation = Operation::Undo { game_id, namespace };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn pause_game(&self, game_id: u16, namespace: Option<CommunityId>) -> Vec<u8> {
+        let operation = Operation::PauseGame { game_id, namespace };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn resume_game(&self, game_id: u16, namespace: Option<CommunityId>) -> Vec<u8> {
+        let operation = Operation::ResumeGame { game_id, namespace };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn batch(&self, operations: Vec<Operation>) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::Batch(operations))).unwrap()
+    }
+
+    async fn create_community(
+        &self,
+        community_id: CommunityId,
+        name: String,
+        admins: Vec<Owner>,
+        default_mode: Option<GameMode>,
+        fees: Option<FeeSettings>,
+    ) -> Vec<u8> {
+        let operation = Operation::CreateCommunity {
+            community_id,
+            name,
+            admins,
+            default_mode: default_mode.unwrap_or_default(),
+            fees: fees.unwrap_or_default(),
+        };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn configure_community(
+        &self,
+        community_id: CommunityId,
+        name: Option<String>,
+        admins: Option<Vec<Owner>>,
+        default_mode: Option<GameMode>,
+        fees: Option<FeeSettings>,
+    ) -> Vec<u8> {
+        let operation = Operation::ConfigureCommunity {
+            community_id,
+            name,
+            admins,
+            default_mode,
+            fees,
+        };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn join_community(&self, community_id: CommunityId) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::JoinCommunity {
+            community_id,
+        }))
+        .unwrap()
+    }
+
+    async fn leave_community(&self, community_id: CommunityId) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::LeaveCommunity {
+            community_id,
+        }))
+        .unwrap()
+    }
+
+    async fn create_beacon_round(
+        &self,
+        commit_deadline: Timestamp,
+        reveal_deadline: Timestamp,
+        stake: Option<Amount>,
+    ) -> Vec<u8> {
+        let operation = Operation::CreateBeaconRound {
+            commit_deadline,
+            reveal_deadline,
+            stake: stake.unwrap_or_default(),
+        };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn commit_entropy(&self, round_id: u32, commitment: CryptoHash) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::CommitEntropy {
+            round_id,
+            commitment,
+        }))
+        .unwrap()
+    }
+
+    async fn reveal_entropy(&self, round_id: u32, entropy: Vec<u8>) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::RevealEntropy {
+            round_id,
+            entropy,
+        }))
+        .unwrap()
+    }
+
+    async fn close_beacon_round(&self, round_id: u32) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::CloseBeaconRound {
+            round_id,
+        }))
+        .unwrap()
+    }
+
+    async fn request_account_merge(&self, primary: Owner) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::RequestAccountMerge {
+            primary,
+        }))
+        .unwrap()
+    }
+
+    async fn confirm_account_merge(&self, duplicate: Owner) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::ConfirmAccountMerge {
+            duplicate,
+        }))
+        .unwrap()
+    }
+
+    async fn approve_account_merge(&self, duplicate: Owner) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::ApproveAccountMerge {
+            duplicate,
+        }))
+        .unwrap()
+    }
+
+    async fn request_game(
+        &self,
+        namespace: Option<CommunityId>,
+        sandbox: bool,
+        round_id: u32,
+    ) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::RequestGame {
+            namespace,
+            sandbox,
+            round_id,
+        }))
+        .unwrap()
+    }
+
+    async fn create_template(&self, template: GameTemplate) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::CreateTemplate {
+            template,
+        }))
+        .unwrap()
+    }
+
+    async fn new_template_game(&self, template_id: u32, seed: u16) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::NewTemplateGame {
+            template_id,
+            seed,
+        }))
+        .unwrap()
+    }
+
+    async fn mint_join_code(
+        &self,
+        template_id: u32,
+        capacity: u32,
+        expires_at: Timestamp,
+    ) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::MintJoinCode {
+            template_id,
+            capacity,
+            expires_at,
+        }))
+        .unwrap()
+    }
+
+    async fn join_with_code(&self, code: String) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::JoinWithCode { code })).unwrap()
+    }
+
+    async fn create_tournament(&self, name: String, seed: u16, move_limit: u32) -> Vec<u8> {
+        let operation = Operation::CreateTournament {
+            name,
+            seed,
+            move_limit,
+        };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn new_daily_game(&self) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::NewDailyGame)).unwrap()
+    }
+
+    async fn play_tutorial(&self, tutorial: u8, direction: Direction) -> Vec<u8> {
+        let operation = Operation::PlayTutorial {
+            tutorial,
+            direction,
+        };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    /// Creates the signer's game for tournament `tournament_id` and enters it, in a single
+    /// operation: the tournament cannot close between the two.
+    async fn join_tournament(&self, tournament_id: u32) -> Vec<u8> {
+        let operation = Operation::JoinTournament { tournament_id };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn submit_result(&self, tournament_id: u32) -> Vec<u8> {
+        let operation = Operation::SubmitResult { tournament_id };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn close_tournament(&self, tournament_id: u32) -> Vec<u8> {
+        let operation = Operation::CloseTournament { tournament_id };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn set_tournament_end(&self, tournament_id: u32, ends_at: Timestamp) -> Vec<u8> {
+        let operation = Operation::SetTournamentEnd {
+            tournament_id,
+            ends_at,
+        };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn end_tournament_games(&self, tournament_id: u32) -> Vec<u8> {
+        let operation = Operation::EndTournamentGames { tournament_id };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn configure_mode(&self, mode: GameMode, settings: ModeSettings) -> Vec<u8> {
+        let operation = Operation::ConfigureMode { mode, settings };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn verify_game(&self, game_id: u16) -> Vec<u8> {
+        let operation = Operation::VerifyGame { game_id };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn start_game(&self, request_id: u32) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::StartGame {
+            request_id,
+        }))
+        .unwrap()
+    }
+
+    async fn set_item_price(&self, item: String, price: Option<Amount>) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::SetItemPrice {
+            item,
+            price,
+        }))
+        .unwrap()
+    }
+
+    async fn set_treasury(&self, chain_id: ChainId, owner: Option<Owner>) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::SetTreasury {
+            chain_id,
+            owner,
+        }))
+        .unwrap()
+    }
+
+    async fn buy_item(&self, item: String, max_price: Amount) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::BuyItem {
+            item,
+            max_price,
+        }))
+        .unwrap()
+    }
+
+    async fn configure_season_pass(&self, settings: PassSettings) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::ConfigureSeasonPass {
+            settings,
+        }))
+        .unwrap()
+    }
+
+    async fn buy_season_pass(&self, max_price: Amount) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::BuySeasonPass {
+            max_price,
+        }))
+        .unwrap()
+    }
+
+    async fn set_leaderboard_filters(&self, filters: LeaderboardFilters) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::SetLeaderboardFilters {
+            filters,
+        }))
+        .unwrap()
+    }
+
+    async fn join_match_queue(&self) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::JoinMatchQueue)).unwrap()
+    }
+
+    async fn leave_match_queue(&self) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::LeaveMatchQueue)).unwrap()
+    }
+
+    async fn create_lobby(&self, name: String, lobby: Lobby) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::CreateLobby {
+            name,
+            lobby,
+        }))
+        .unwrap()
+    }
+
+    async fn join_lobby_queue(&self, lobby: String) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::JoinLobbyQueue {
+            lobby,
+        }))
+        .unwrap()
+    }
+
+    async fn challenge(&self, opponent_chain: ChainId) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::Challenge {
+            opponent_chain,
+        }))
+        .unwrap()
+    }
+
+    async fn accept_challenge(&self, challenger_chain: ChainId, challenge_id: u32) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::AcceptChallenge {
+            challenger_chain,
+            challenge_id,
+        }))
+        .unwrap()
+    }
+
+    async fn spectate(&self, chain_id: ChainId, game_id: u16) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::Spectate {
+            chain_id,
+            game_id,
+        }))
+        .unwrap()
+    }
+
+    async fn stop_spectating(&self, chain_id: ChainId, game_id: u16) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::StopSpectating {
+            chain_id,
+            game_id,
+        }))
+        .unwrap()
+    }
+
+    async fn sponsor_games(
+        &self,
+        community_id: CommunityId,
+        players: Vec<Owner>,
+        games_per_player: u32,
+        expires_at: Timestamp,
+    ) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::SponsorGames {
+            community_id,
+            players,
+            games_per_player,
+            expires_at,
+        }))
+        .unwrap()
+    }
+
+    async fn withdraw_sponsorship(&self, sponsorship_id: u32) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::WithdrawSponsorship {
+            sponsorship_id,
+        }))
+        .unwrap()
+    }
+
+    async fn dispute_match(&self, match_id: u32, replay: Vec<u8>, claimed_score: u64) -> Vec<u8> {
+        let operation = Operation::DisputeMatch {
+            match_id,
+            replay,
+            claimed_score,
+        };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn resolve_dispute(&self, match_id: u32) -> Vec<u8> {
+        let operation = Operation::ResolveDispute { match_id };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn challenge_dispute_result(&self, match_id: u32, player: Owner) -> Vec<u8> {
+        let operation = Operation::ChallengeDisputeResult { match_id, player };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn finalize_dispute(&self, match_id: u32) -> Vec<u8> {
+        let operation = Operation::FinalizeDispute { match_id };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn set_chat_key(&self, key: Vec<u8>) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::SetChatKey { key })).unwrap()
+    }
+
+    async fn send_chat(&self, match_id: u32, payload: ChatPayload) -> Vec<u8> {
+        let operation = Operation::SendChat { match_id, payload };
+        bcs::to_bytes(&OperationEnvelope::new(&operation)).unwrap()
+    }
+
+    async fn set_attester(&self, attester: PublicKey) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::SetAttester {
+            attester,
+        }))
+        .unwrap()
+    }
+
+    async fn submit_attestation(
+        &self,
+        attestation: ScoreAttestation,
+        signature: Signature,
+    ) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::SubmitAttestation {
+            attestation,
+            signature,
+        }))
+        .unwrap()
     }
 
-    async fn make_move(&self, game_id: u16, direction: Direction) -> Vec<u8> {
-        let operation = Operation::MakeMove { game_id, direction };
-        bcs::to_bytes(&operation).unwrap()
+    async fn set_active_game_limit(&self, limit: u32) -> Vec<u8> {
+        bcs::to_bytes(&OperationEnvelope::new(&Operation::SetActiveGameLimit {
+            limit,
+        }))
+        .unwrap()
     }
 }
@@ -0,0 +1,145 @@
+use crate::{board_svg, BoardTheme, CommunityId, Game, GameMode, Turn};
+use async_graphql::{ComplexObject, SimpleObject};
+use linera_sdk::base::{BcsHashable, CryptoHash};
+use serde::{Deserialize, Serialize};
+
+/// The outcome of a finished game together with every turn that led to it.
+///
+/// Its hash verifies a shared result: anyone can fetch the move log, replay it and compare.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GameResult {
+    pub game_id: u16,
+    pub namespace: Option<CommunityId>,
+    pub sandbox: bool,
+    pub mode: GameMode,
+    pub board: u64,
+    pub score: u64,
+    pub move_count: u32,
+    pub turns: Vec<Turn>,
+}
+
+impl BcsHashable for GameResult {}
+
+/// Open Graph style metadata for sharing a game result.
+#[derive(Clone, Debug, SimpleObject)]
+#[graphql(complex)]
+pub struct ShareCard {
+    pub title: String,
+    pub description: String,
+    pub score: u64,
+    pub max_tile: u64,
+    /// The final board, as an SVG `data:` URI.
+    pub image: String,
+    /// Hash of the [`GameResult`], committing to the final state and every turn.
+    pub verification_hash: CryptoHash,
+}
+
+impl GameResult {
+    pub fn hash(&self) -> CryptoHash {
+        CryptoHash::new(self)
+    }
+
+    /// Builds the share card of this result, picturing the board with `theme`.
+    pub fn share_card(&self, theme: BoardTheme) -> ShareCard {
+        let max_tile = Game::max_tile(self.board);
+        let mut title = match &self.namespace {
+            Some(community_id) => format!("2048 in {community_id}: {} points", self.score),
+            None => format!("2048: {} points", self.score),
+        };
+        if self.sandbox {
+            title.push_str(" (practice)");
+        }
+        let description = format!(
+            "Reached the {max_tile} tile in {} moves of {:?} mode. Every move is recorded \
+             on-chain and can be replayed to verify this result.",
+            self.move_count, self.mode
+        );
+        ShareCard {
+            title,
+            description,
+            score: self.score,
+            max_tile,
+            image: svg_data_uri(&board_svg(self.board, theme)),
+            verification_hash: self.hash(),
+        }
+    }
+}
+
+impl ShareCard {
+    /// Renders the card as `<meta>` tags for the `<head>` of a page.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::{BoardTheme, GameMode, GameResult};
+    ///
+    /// let result = GameResult {
+    ///     game_id: 7,
+    ///     namespace: Some("<voyagers>".to_string()),
+    ///     sandbox: false,
+    ///     mode: GameMode::Classic,
+    ///     board: 0x0000_0000_0000_0B21,
+    ///     score: 20_484,
+    ///     move_count: 950,
+    ///     turns: Vec::new(),
+    /// };
+    /// let tags = result.share_card(BoardTheme::Classic).to_meta_tags();
+    ///
+    /// assert!(tags.starts_with(
+    ///     "<meta property=\"og:title\" content=\"2048 in &lt;voyagers&gt;: 20484 points\">\n"
+    /// ));
+    /// assert!(tags.contains("<meta property=\"og:image\" content=\"data:image/svg+xml,"));
+    /// ```
+    pub fn to_meta_tags(&self) -> String {
+        let score = self.score.to_string();
+        let max_tile = self.max_tile.to_string();
+        let verification_hash = self.verification_hash.to_string();
+        let properties = [
+            ("og:title", self.title.as_str()),
+            ("og:description", &self.description),
+            ("og:image", &self.image),
+            ("og:image:type", "image/svg+xml"),
+            ("game:score", &score),
+            ("game:max_tile", &max_tile),
+            ("game:verification_hash", &verification_hash),
+        ];
+
+        let mut tags = String::new();
+        for (property, content) in properties {
+            let content = html_escape(content);
+            tags.push_str(&format!(
+                "<meta property=\"{property}\" content=\"{content}\">\n"
+            ));
+        }
+        tags
+    }
+}
+
+#[ComplexObject]
+impl ShareCard {
+    /// The card as `<meta>` tags for the `<head>` of a page.
+    async fn meta_tags(&self) -> String {
+        self.to_meta_tags()
+    }
+}
+
+/// Percent-encodes an SVG document into a `data:` URI.
+fn svg_data_uri(svg: &str) -> String {
+    let mut uri = String::from("data:image/svg+xml,");
+    for byte in svg.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~=:/;,'()".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    uri
+}
+
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,154 @@
+use crate::{Direction, Game};
+
+/// Depth the `hint` query searches to when none is given.
+pub const HINT_DEPTH: u32 = 2;
+
+/// Deepest search the `hint` query runs, bounding the work of a single query.
+pub const MAX_HINT_DEPTH: u32 = 3;
+
+/// Weight of an empty cell in the evaluation of a board.
+const EMPTY_WEIGHT: f64 = 270.0;
+
+/// Weight of the tile exponents lost to non-monotonic rows and columns.
+const MONOTONICITY_WEIGHT: f64 = 47.0;
+
+/// Weight of the exponent differences between neighbouring tiles.
+const SMOOTHNESS_WEIGHT: f64 = 11.0;
+
+/// Weight of the board score, so that merges are worth something on their own.
+const SCORE_WEIGHT: f64 = 1.0;
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
+
+/// A 2048 player searching moves with expectimax over the packed board, for bots, tests and
+/// hints.
+///
+/// Moves are maximized over, and spawns are averaged over every empty cell, only `2`s
+/// spawning after moves. The boards reached at the end of the search are evaluated by their
+/// number of empty cells, how monotonic their rows and columns are, how smooth the
+/// differences between neighbouring tiles are, and their score.
+pub struct Solver;
+
+impl Solver {
+    /// Returns the move the solver prefers on `board`, looking `depth` moves ahead, or `None`
+    /// if no move changes the board. A depth of `0` is searched as `1`. Ties go to the first
+    /// of up, down, left and right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::{Direction, Solver};
+    ///
+    /// // |  0 | 0 | 0 | 0 |
+    /// // |  0 | 0 | 0 | 0 |
+    /// // | 16 | 0 | 0 | 0 |
+    /// // |  8 | 4 | 2 | 2 |
+    /// // Merging the bottom row to the left keeps the largest tiles in the corner.
+    /// assert_eq!(Solver::best_move(0x0000_0000_4000_3211, 2), Some(Direction::Left));
+    /// // | 2 | 4 | 2 | 4 |
+    /// // | 4 | 2 | 4 | 2 |
+    /// // | 2 | 4 | 2 | 4 |
+    /// // | 4 | 2 | 4 | 2 |
+    /// assert_eq!(Solver::best_move(0x1212_2121_1212_2121, 2), None);
+    /// ```
+    pub fn best_move(board: u64, depth: u32) -> Option<Direction> {
+        Self::search(board, depth).map(|(direction, _)| direction)
+    }
+
+    /// Returns the move [`Solver::best_move`] prefers on `board` along with its expected
+    /// value: the evaluation of the boards reached `depth` moves ahead, averaged over the tiles
+    /// spawning in between, lost games counting as boards missing every empty cell.
+    ///
+    /// ```
+    /// use game2048::{Direction, Solver};
+    ///
+    /// let board = 0x0000_0000_4000_3211;
+    /// let (direction, value) = Solver::search(board, 1).unwrap();
+    /// assert_eq!(direction, Direction::Left);
+    /// // Merging the twos frees a cell.
+    /// assert!(value > Solver::evaluate(board));
+    /// ```
+    pub fn search(board: u64, depth: u32) -> Option<(Direction, f64)> {
+        Self::best(board, depth.max(1))
+    }
+
+    /// Returns the evaluation of `board` the search falls back to when it stops looking
+    /// ahead. Higher is better.
+    pub fn evaluate(board: u64) -> f64 {
+        let rows = Self::lines(board);
+        let columns = Self::lines(Game::transpose(board));
+        let mut monotonicity_loss = 0;
+        let mut roughness = 0;
+        for line in rows.iter().chain(&columns) {
+            let (mut increases, mut decreases) = (0, 0);
+            for pair in line.windows(2) {
+                let (a, b) = (pair[0], pair[1]);
+                if a < b {
+                    increases += b - a;
+                } else {
+                    decreases += a - b;
+                }
+                if a != 0 && b != 0 {
+                    roughness += a.abs_diff(b);
+                }
+            }
+            monotonicity_loss += increases.min(decreases);
+        }
+        EMPTY_WEIGHT * f64::from(Game::count_empty(board))
+            - MONOTONICITY_WEIGHT * f64::from(monotonicity_loss)
+            - SMOOTHNESS_WEIGHT * f64::from(roughness)
+            + SCORE_WEIGHT * Game::score(board) as f64
+    }
+
+    /// The preferred move on `board` and its expected value, or `None` if no move changes
+    /// the board.
+    fn best(board: u64, depth: u32) -> Option<(Direction, f64)> {
+        let mut best: Option<(Direction, f64)> = None;
+        for direction in DIRECTIONS {
+            let moved = Game::move_board(board, direction.clone());
+            if moved == board {
+                continue;
+            }
+            let value = Self::expect(moved, depth - 1);
+            if !best.as_ref().is_some_and(|(_, best)| *best >= value) {
+                best = Some((direction, value));
+            }
+        }
+        best
+    }
+
+    /// The expected value of `board`, right after a move, when a `2` spawns in any of its
+    /// empty cells and the best of the next `depth` moves is played.
+    fn expect(board: u64, depth: u32) -> f64 {
+        let empty = Game::count_empty(board);
+        if depth == 0 || empty == 0 {
+            return Self::evaluate(board);
+        }
+        let mut total = 0.0;
+        for cell in (0..16).filter(|cell| (board >> (cell * 4)) & 0xF == 0) {
+            let spawned = board | (1 << (cell * 4));
+            total += match Self::best(spawned, depth) {
+                Some((_, value)) => value,
+                // Nothing can be played anymore: the game is lost.
+                None => Self::evaluate(spawned) - EMPTY_WEIGHT * 16.0,
+            };
+        }
+        total / f64::from(empty)
+    }
+
+    /// The tile exponents of the four rows of `board`.
+    fn lines(board: u64) -> [[u32; 4]; 4] {
+        let mut lines = [[0; 4]; 4];
+        for (row, line) in lines.iter_mut().enumerate() {
+            for (column, exponent) in line.iter_mut().enumerate() {
+                *exponent = ((board >> (row * 16 + column * 4)) & 0xF) as u32;
+            }
+        }
+        lines
+    }
+}
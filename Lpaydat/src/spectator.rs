@@ -0,0 +1,51 @@
+use async_graphql::SimpleObject;
+use linera_sdk::base::ChainId;
+use serde::{Deserialize, Serialize};
+
+/// A game played on another chain, as seen by a chain spectating it: the snapshot received
+/// when subscribing, brought up to date by every turn since.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct SpectatedGame {
+    /// The chain the game is played on.
+    pub chain_id: ChainId,
+    pub game_id: u16,
+    /// Index of the last turn applied, the snapshot's until a turn follows it.
+    pub move_index: u32,
+    pub board: u64,
+    pub score: u64,
+    pub is_ended: bool,
+}
+
+impl SpectatedGame {
+    /// Applies turn `move_index` if it is the next one, or the creation of a game reusing
+    /// the id, returning whether it was applied. Turns already applied and turns past a gap
+    /// are left out: the missing ones are resent first.
+    ///
+    /// ```
+    /// use game2048::SpectatedGame;
+    /// use linera_sdk::base::ChainId;
+    ///
+    /// let mut game = SpectatedGame {
+    ///     chain_id: ChainId::root(0),
+    ///     game_id: 1,
+    ///     move_index: 4,
+    ///     board: 0x0000_0000_0000_0011,
+    ///     score: 8,
+    ///     is_ended: false,
+    /// };
+    /// assert!(!game.apply(4, 0x0000_0000_0000_0102, 12, false));
+    /// assert!(!game.apply(6, 0x0000_0000_0000_1102, 12, false));
+    /// assert!(game.apply(5, 0x0000_0000_0000_0102, 12, false));
+    /// assert_eq!((game.move_index, game.board, game.score), (5, 0x0102, 12));
+    /// ```
+    pub fn apply(&mut self, move_index: u32, board: u64, score: u64, is_ended: bool) -> bool {
+        if move_index != 0 && move_index != self.move_index + 1 {
+            return false;
+        }
+        self.move_index = move_index;
+        self.board = board;
+        self.score = score;
+        self.is_ended = is_ended;
+        true
+    }
+}
@@ -1,14 +1,35 @@
 use async_graphql::{scalar, SimpleObject};
-use linera_sdk::views::{
-    linera_views, CollectionView, RegisterView, RootView, View, ViewStorageContext,
+use game2048::{
+    AccountMerge, Achievement, ChallengeRecord, ChatMessage, CommunityId, DailyScore,
+    EfficiencyEntry, FeeSettings, GameAnalysis, GameEvent, GameMode, GameRequest, GameTemplate,
+    GameVerification, HistoryRetention, JoinCode, LargeBoard, LeaderboardEntry, LeaderboardFilters,
+    LeaderboardWindow, Lobby, LobbyStats, MatchDispute, MatchNotice, MatchRecord, ModeSettings,
+    MoveHistory, OperationFailure, PassSale, PassSettings, PlayerStats, QueueEntry, RemoteScore,
+    SeasonPass, SignedAttestation, SpectatedGame, TileHeatmap, TournamentResult, TournamentStatus,
+    Turn,
+};
+use linera_sdk::{
+    base::{Account, Amount, ChainId, CryptoHash, Owner, PublicKey, Timestamp},
+    views::{
+        linera_views::{
+            self, context::Context, map_view::MapView as GenericMapView,
+            register_view::RegisterView as GenericRegisterView,
+        },
+        CollectionView, LogView, MapView, RegisterView, RootView, SetView, View, ViewError,
+        ViewStorageContext,
+    },
 };
 use serde::{Deserialize, Serialize};
+use std::ops::RangeBounds;
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+/// Whether a game can be played.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub enum GameStatus {
     #[default]
     Active,
     Ended,
+    /// Paused by its owner: moves are dropped until the game is resumed.
+    Paused,
 }
 scalar!(GameStatus);
 
@@ -16,14 +37,369 @@ scalar!(GameStatus);
 #[view(context = "ViewStorageContext")]
 pub struct GameState {
     pub game_id: RegisterView<u16>,
+    /// The signer who created the game, if any.
+    pub owner: RegisterView<Option<Owner>>,
+    pub namespace: RegisterView<Option<CommunityId>>,
+    /// Whether this is a practice game, excluded from leaderboards, statistics and limits.
+    pub sandbox: RegisterView<bool>,
+    /// The beacon round the seed was derived from, `None` if the creator chose the seed.
+    pub seed_round: RegisterView<Option<u32>>,
+    /// The template the game was started from, if any.
+    pub template_id: RegisterView<Option<u32>>,
+    /// The tournament the game is played for, if any.
+    pub tournament_id: RegisterView<Option<u32>>,
+    /// The day of the daily challenge the game is played for, if any.
+    pub daily_challenge: RegisterView<Option<u32>>,
+    /// The match the game is played for, if any.
+    pub match_id: RegisterView<Option<u32>>,
+    pub mode: RegisterView<GameMode>,
+    /// The seed the initial board was spawned from. Usually the game id, but matches may
+    /// start games from the seed of a taken id.
+    pub seed: RegisterView<u16>,
     pub board: RegisterView<u64>,
     pub score: RegisterView<u64>,
-    pub is_ended: RegisterView<bool>,
+    pub status: RegisterView<GameStatus>,
+    /// The value of the largest tile the game reached. Undos do not take it back.
+    pub highest_tile: RegisterView<u64>,
+    /// Number of merges played in the game, each pair of tiles merged counting once.
+    pub total_merges: RegisterView<u64>,
+    /// The value of the largest tile a merge created, `0` before the first merge.
+    pub largest_merge: RegisterView<u64>,
+    pub move_count: RegisterView<u32>,
+    /// Moves after which the game ends, `None` for no limit.
+    pub max_moves: RegisterView<Option<u32>>,
+    /// Number of tiles spawned by moves so far, mixed into the seed of the next spawn.
+    pub spawn_index: RegisterView<u32>,
+    /// Number of consecutive moves that merged tiles, used by combo scoring.
+    pub combo: RegisterView<u32>,
+    pub created_at: RegisterView<Timestamp>,
+    pub updated_at: RegisterView<Timestamp>,
+    /// When the last move of the game was played, `None` before its first move.
+    pub last_move_at: RegisterView<Option<Timestamp>>,
+    /// Every turn of the game, undos included, used to serve resend requests. Only the turns
+    /// the history retention of the mode of the game keeps are stored.
+    pub move_log: TurnLog<ViewStorageContext>,
+    /// The position each move was played from, to take moves back.
+    pub history: LogView<MoveHistory>,
+    pub undos_left: RegisterView<u32>,
+    /// How the moves compare to the solver's, computed once the game ends.
+    pub analysis: RegisterView<Option<GameAnalysis>>,
+    /// Whether the move log reproduced the game when it was last verified, `None` if it never
+    /// was.
+    pub verification: RegisterView<Option<GameVerification>>,
+    /// The board of games larger than 4x4, whose `board` stays empty.
+    #[graphql(skip)]
+    pub large_board: RegisterView<Option<LargeBoard>>,
+    /// The challenge the game is played for, by challenger chain and challenge id.
+    #[graphql(skip)]
+    pub challenge: RegisterView<Option<(ChainId, u32)>>,
+}
+
+#[derive(View, SimpleObject)]
+#[view(context = "ViewStorageContext")]
+pub struct CommunityState {
+    pub name: RegisterView<String>,
+    pub admins: RegisterView<Vec<Owner>>,
+    pub default_mode: RegisterView<GameMode>,
+    pub fees: RegisterView<FeeSettings>,
+    pub members: SetView<Owner>,
+    pub member_count: RegisterView<u32>,
+}
+
+#[derive(View, SimpleObject)]
+#[view(context = "ViewStorageContext")]
+pub struct PlayerState {
+    pub stats: RegisterView<PlayerStats>,
+    /// Where tiles spawn and end up in the player's games, excluding sandbox games.
+    pub heatmap: RegisterView<TileHeatmap>,
+    /// The player's best score in each mode, excluding sandbox games.
+    pub mode_bests: MapView<GameMode, u64>,
+    /// The games currently owned by the player.
+    pub games: SetView<u16>,
+    /// The account this one asked to be merged into, awaiting its confirmation and admin
+    /// approval.
+    pub merge_request: RegisterView<Option<Owner>>,
+    /// Whether the account of `merge_request` confirmed the merge.
+    pub merge_confirmed: RegisterView<bool>,
+    /// Number of the player's games that are neither ended nor sandbox games.
+    pub active_games: RegisterView<u32>,
+    /// How many of each power-up item the player owns.
+    pub inventory: MapView<String, u32>,
+    /// The player's latest season pass, possibly expired.
+    pub season_pass: RegisterView<Option<SeasonPass>>,
+    /// The sponsorships paying the fees of the player's games.
+    pub sponsorships: SetView<u32>,
+    /// How many steps of each tutorial the player completed, by index into `TUTORIALS`.
+    pub tutorial_steps: MapView<u8, u8>,
+    pub achievements: SetView<Achievement>,
+}
+
+#[derive(View, SimpleObject)]
+#[view(context = "ViewStorageContext")]
+pub struct TemplateState {
+    /// The owner who created the template, `None` if the template does not exist.
+    pub organizer: RegisterView<Option<Owner>>,
+    pub settings: RegisterView<GameTemplate>,
+    /// The games started from the template. Games whose id was reused since may belong to
+    /// another template.
+    pub games: SetView<u16>,
+    /// Whether only participants who joined with a code may start games, which is the case
+    /// once a join code was minted.
+    pub invite_only: RegisterView<bool>,
+    pub participants: SetView<Owner>,
+}
+
+#[derive(View, SimpleObject)]
+#[view(context = "ViewStorageContext")]
+pub struct TournamentState {
+    /// The owner who created the tournament, `None` if the tournament does not exist.
+    pub organizer: RegisterView<Option<Owner>>,
+    pub name: RegisterView<String>,
+    /// The seed the games of all entrants start from.
+    pub seed: RegisterView<u16>,
+    /// Moves after which the games of the entrants end.
+    pub move_limit: RegisterView<u32>,
+    pub created_at: RegisterView<Timestamp>,
+    /// Whether the tournament is closed to entrants and results.
+    pub is_closed: RegisterView<bool>,
+    /// When the games of the entrants end, `None` if they only end at their move limit.
+    pub ends_at: RegisterView<Option<Timestamp>>,
+    /// The game of each entrant.
+    pub entrants: MapView<Owner, u16>,
+    /// The results submitted by entrants, by player.
+    pub results: MapView<Owner, TournamentResult>,
+}
+
+impl GameState {
+    pub fn is_ended(&self) -> bool {
+        *self.status.get() == GameStatus::Ended
+    }
+}
+
+impl TournamentState {
+    /// Returns where the tournament is in its lifecycle at `now`.
+    pub fn status(&self, now: Timestamp) -> TournamentStatus {
+        TournamentStatus::at(*self.is_closed.get(), *self.ends_at.get(), now)
+    }
+}
+
+#[derive(View, SimpleObject)]
+#[view(context = "ViewStorageContext")]
+pub struct BeaconRoundState {
+    /// The owner who opened the round, `None` if the round does not exist.
+    pub organizer: RegisterView<Option<Owner>>,
+    pub commit_deadline: RegisterView<Timestamp>,
+    pub reveal_deadline: RegisterView<Timestamp>,
+    /// Entry stake forfeited by participants who commit but never reveal.
+    pub stake: RegisterView<Amount>,
+    pub commitments: MapView<Owner, CryptoHash>,
+    pub reveals: MapView<Owner, Vec<u8>>,
+    pub commit_count: RegisterView<u32>,
+    pub reveal_count: RegisterView<u32>,
+    pub is_closed: RegisterView<bool>,
+    /// The beacon output, `None` until the round is closed or if nobody revealed.
+    pub output: RegisterView<Option<CryptoHash>>,
+    /// Participants who committed without revealing in time.
+    pub slashed: RegisterView<Vec<Owner>>,
+    /// Total stake forfeited by the slashed participants.
+    pub forfeited: RegisterView<Amount>,
+}
+
+/// Game fees a sponsor prepaid for a set of players of a community.
+#[derive(View, SimpleObject)]
+#[view(context = "ViewStorageContext")]
+pub struct SponsorshipState {
+    /// The owner who prepaid the fees, `None` if the sponsorship does not exist.
+    pub sponsor: RegisterView<Option<Owner>>,
+    pub community_id: RegisterView<CommunityId>,
+    pub expires_at: RegisterView<Timestamp>,
+    /// Prepaid fees not spent yet, held in the chain balance.
+    pub balance: RegisterView<Amount>,
+    /// Number of games each sponsored player may still start on the sponsorship.
+    pub credits: MapView<Owner, u32>,
+}
+
+/// The key of a ranked game in `mode_leaderboards`: mode, window, period of the window,
+/// namespace and owner.
+pub type ModeLeaderboardKey = (
+    Option<GameMode>,
+    LeaderboardWindow,
+    u64,
+    Option<CommunityId>,
+    Owner,
+);
+
+/// The turns of a game, by turn index, `0` being the creation of the game. Generic over its
+/// context like `MetricsState`, being held directly in a field of `GameState`.
+#[derive(View, SimpleObject)]
+#[graphql(concrete(name = "TurnLog", params(ViewStorageContext)))]
+pub struct TurnLog<C: Context + Send + Sync> {
+    /// Number of turns logged, whether they are kept or not.
+    pub count: GenericRegisterView<C, u32>,
+    /// Which turns are kept, set when the game is created.
+    pub retention: GenericRegisterView<C, HistoryRetention>,
+    /// The turns kept, by turn index.
+    pub turns: GenericMapView<C, u32, Turn>,
+    /// Number of turns kept.
+    pub kept: GenericRegisterView<C, u32>,
+}
+
+impl TurnLog<ViewStorageContext> {
+    /// Returns the turns kept within `range`, in order, with their index.
+    pub async fn read(
+        &self,
+        range: impl RangeBounds<u32> + Send + Sync,
+    ) -> Result<Vec<(u32, Turn)>, ViewError> {
+        let mut turns = Vec::new();
+        self.turns
+            .for_each_index_value(|turn_index, turn| {
+                if range.contains(&turn_index) {
+                    turns.push((turn_index, turn));
+                }
+                Ok(())
+            })
+            .await?;
+        turns.sort_by_key(|(turn_index, _)| *turn_index);
+        Ok(turns)
+    }
+
+    /// Returns every turn, `None` unless they were all kept.
+    pub async fn read_all(&self) -> Result<Option<Vec<Turn>>, ViewError> {
+        if self.kept.get() != self.count.get() {
+            return Ok(None);
+        }
+        let turns = self.read(..).await?;
+        Ok(Some(turns.into_iter().map(|(_, turn)| turn).collect()))
+    }
+}
+
+/// Counters kept by the contract, for observability without an external indexer.
+///
+/// Views held directly in a field of another view are generic over their context: the `View`
+/// derive reaches the nested view through the generic arguments of its type.
+#[derive(View, SimpleObject)]
+#[graphql(concrete(name = "MetricsState", params(ViewStorageContext)))]
+pub struct MetricsState<C: Context + Send + Sync> {
+    /// Number of operations executed, by operation name.
+    pub operations: GenericMapView<C, String, u64>,
+    /// Number of operations dropped without effect, by reason.
+    pub failures: GenericMapView<C, OperationFailure, u64>,
+    /// Number of messages sent to other chains.
+    pub messages_sent: GenericRegisterView<C, u64>,
+    /// Number of events emitted to the game events stream.
+    pub events_emitted: GenericRegisterView<C, u64>,
+    /// Number of turns kept in the move logs of the games of this chain.
+    pub kept_turns: GenericRegisterView<C, u64>,
 }
 
 #[derive(RootView, SimpleObject)]
 #[view(context = "ViewStorageContext")]
 pub struct Game2048 {
     pub games: CollectionView<u16, GameState>,
-    // leaderboard
+    /// Owners allowed to administer the application, starting with its creator.
+    pub admins: RegisterView<Vec<Owner>>,
+    pub players: CollectionView<Owner, PlayerState>,
+    /// Maximum number of active games per player, `None` for the default limit.
+    pub active_game_limit: RegisterView<Option<u32>>,
+    /// Audit log of every account merge.
+    pub account_merges: LogView<AccountMerge>,
+    pub communities: CollectionView<CommunityId, CommunityState>,
+    /// Index of the last contiguous turn received for each game played on another chain.
+    #[graphql(skip)]
+    pub timelines: MapView<(ChainId, u16), u32>,
+    pub beacon_rounds: CollectionView<u32, BeaconRoundState>,
+    pub next_beacon_round: RegisterView<u32>,
+    /// Games waiting for their beacon round to close, by request id.
+    #[graphql(skip)]
+    pub game_requests: MapView<u32, GameRequest>,
+    pub next_game_request: RegisterView<u32>,
+    pub templates: CollectionView<u32, TemplateState>,
+    pub next_template: RegisterView<u32>,
+    /// Join codes, by code.
+    #[graphql(skip)]
+    pub join_codes: MapView<String, JoinCode>,
+    /// Nonce of the next join code, distinguishing codes minted in the same block.
+    pub next_join_code: RegisterView<u32>,
+    pub tournaments: CollectionView<u32, TournamentState>,
+    pub next_tournament: RegisterView<u32>,
+    /// The game of each player in the daily challenge of each day, by day and player.
+    #[graphql(skip)]
+    pub daily_scores: MapView<(u32, Owner), DailyScore>,
+    /// Every event emitted to the game events stream, for clients resuming from a cursor.
+    #[graphql(skip)]
+    pub game_events: LogView<GameEvent>,
+    /// Price of each power-up item for sale.
+    pub item_prices: MapView<String, Amount>,
+    /// The account item payments go to, `None` until an admin sets it.
+    #[graphql(skip)]
+    pub treasury: RegisterView<Option<Account>>,
+    /// Season pass settings, `None` until passes are put on sale.
+    pub pass_settings: RegisterView<Option<PassSettings>>,
+    pub pass_sales: LogView<PassSale>,
+    /// Total price of all pass sales.
+    pub pass_revenue: RegisterView<Amount>,
+    /// The key signing score attestations, `None` until admins set one.
+    pub attester: RegisterView<Option<PublicKey>>,
+    /// The latest attestation of each player.
+    pub attestations: MapView<Owner, SignedAttestation>,
+    /// The thresholds games must reach to be ranked.
+    pub leaderboard_filters: RegisterView<LeaderboardFilters>,
+    /// The settings of each game mode, applied to the games created in it. Modes missing
+    /// from the registry use the default settings.
+    pub modes: MapView<GameMode, ModeSettings>,
+    /// Players waiting for a match, on the matchmaking chain.
+    pub match_queue: MapView<Owner, QueueEntry>,
+    /// Matches made by the matchmaking chain, by id.
+    pub matches: MapView<u32, MatchRecord>,
+    pub next_match_id: RegisterView<u32>,
+    /// The latest match of each player who joined the queue from this chain.
+    pub match_notices: MapView<Owner, MatchNotice>,
+    /// The disputes of the results of matches made by this chain, by match id.
+    pub disputes: MapView<u32, MatchDispute>,
+    /// The chat of each match made by this chain, by match id.
+    pub chats: MapView<u32, Vec<ChatMessage>>,
+    /// The keys players encrypt chat messages to each other with, on the matchmaking chain.
+    pub chat_keys: MapView<Owner, Vec<u8>>,
+    /// The lobbies created by admins, besides the default one.
+    pub lobbies: MapView<String, Lobby>,
+    pub lobby_stats: MapView<String, LobbyStats>,
+    /// The best ranked game of each player, by namespace and owner. Games are ranked when
+    /// they end, if the leaderboard filters let them.
+    #[graphql(skip)]
+    pub leaderboard: MapView<(Option<CommunityId>, Owner), LeaderboardEntry>,
+    /// The best ranked game of each player in each mode and window, by mode, window, period
+    /// of the window, namespace and owner. `None` stands for every mode; the best game of all
+    /// modes and time is kept in `leaderboard`.
+    #[graphql(skip)]
+    pub mode_leaderboards: MapView<ModeLeaderboardKey, LeaderboardEntry>,
+    /// The most efficient ranked game of each player, by namespace and owner, scored with
+    /// the efficiency weights of the application parameters.
+    #[graphql(skip)]
+    pub efficiency_leaderboard: MapView<(Option<CommunityId>, Owner), EfficiencyEntry>,
+    /// The head-to-head challenges this chain took part in, by challenger chain and
+    /// challenge id.
+    #[graphql(skip)]
+    pub challenges: MapView<(ChainId, u32), ChallengeRecord>,
+    /// Id of the next challenge sent from this chain.
+    pub next_challenge_id: RegisterView<u32>,
+    pub sponsorships: CollectionView<u32, SponsorshipState>,
+    pub next_sponsorship: RegisterView<u32>,
+    /// The chains spectating each running game of this chain.
+    pub spectators: MapView<u16, Vec<ChainId>>,
+    /// The games of other chains this chain spectates, by chain and game id. The hub
+    /// follows every game reported to it.
+    #[graphql(skip)]
+    pub spectated: MapView<(ChainId, u16), SpectatedGame>,
+    /// The score of every game reported to the hub, by chain and game id, as of the last
+    /// turn applied in order.
+    #[graphql(skip)]
+    pub remote_scores: MapView<(ChainId, u16), u64>,
+    /// The best score of all the games reported to the hub.
+    pub best_remote_score: RegisterView<Option<RemoteScore>>,
+    /// The turn of each game held back by a delay fault, sent after the next turn.
+    pub delayed_turns: MapView<u16, u32>,
+    /// Number of game seeds drawn from the block context, mixed into the next draw so that
+    /// the seeds drawn in one block differ.
+    pub seed_draws: RegisterView<u64>,
+    pub metrics: MetricsState<ViewStorageContext>,
 }
@@ -0,0 +1,118 @@
+use crate::Game;
+use async_graphql::scalar;
+use serde::{Deserialize, Serialize};
+
+const CELL: u32 = 56;
+const GAP: u32 = 8;
+const SIZE: u32 = 4 * CELL + 5 * GAP;
+
+/// Color themes boards can be rendered with.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum BoardTheme {
+    /// The colors of the web frontend.
+    #[default]
+    Classic,
+    Dark,
+}
+
+scalar!(BoardTheme);
+
+struct Palette {
+    background: &'static str,
+    empty: &'static str,
+    /// Tile colors, indexed by exponent minus one.
+    tiles: [&'static str; 15],
+    dark_text: &'static str,
+    light_text: &'static str,
+}
+
+const CLASSIC: Palette = Palette {
+    background: "#bbada0",
+    empty: "#cdc1b4",
+    tiles: [
+        "#eee4da", "#ede0c8", "#f2b179", "#f59563", "#f67c5f", "#f65e3b", "#edcf72", "#edcc61",
+        "#edc850", "#edc53f", "#f46573", "#f14b61", "#e9443d", "#72b3db", "#5da0e4",
+    ],
+    dark_text: "#776e65",
+    light_text: "#f9f6f2",
+};
+
+const DARK: Palette = Palette {
+    background: "#1f2126",
+    empty: "#2c2f36",
+    tiles: [
+        "#3a3f4b", "#454b5a", "#8a5a2b", "#a0522d", "#b5452e", "#c2362a", "#9c8a2e", "#a89530",
+        "#b39f31", "#c0aa33", "#a8324a", "#b52a45", "#c0223d", "#2f6f9a", "#2a5fa8",
+    ],
+    dark_text: "#d8dee9",
+    light_text: "#f9f6f2",
+};
+
+impl BoardTheme {
+    fn palette(self) -> &'static Palette {
+        match self {
+            BoardTheme::Classic => &CLASSIC,
+            BoardTheme::Dark => &DARK,
+        }
+    }
+}
+
+/// Renders `board` as a small, self-contained SVG image.
+///
+/// # Examples
+///
+/// ```
+/// use game2048::{board_svg, BoardTheme};
+///
+/// let svg = board_svg(0x0000_0000_0000_00B1, BoardTheme::Classic);
+///
+/// assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+/// assert!(svg.contains(">2048</text>"));
+/// assert!(svg.contains(">2</text>"));
+/// ```
+pub fn board_svg(board: u64, theme: BoardTheme) -> String {
+    let palette = theme.palette();
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{SIZE}\" height=\"{SIZE}\" \
+         viewBox=\"0 0 {SIZE} {SIZE}\"><rect width=\"{SIZE}\" height=\"{SIZE}\" rx=\"6\" \
+         fill=\"{}\"/>",
+        palette.background
+    );
+
+    for (row, tiles) in Game::convert_to_matrix(board).iter().enumerate() {
+        for (col, &exponent) in tiles.iter().enumerate() {
+            let x = GAP + col as u32 * (CELL + GAP);
+            let y = GAP + row as u32 * (CELL + GAP);
+            let fill = match exponent {
+                0 => palette.empty,
+                exponent => palette.tiles[exponent as usize - 1],
+            };
+            svg.push_str(&format!(
+                "<rect x=\"{x}\" y=\"{y}\" width=\"{CELL}\" height=\"{CELL}\" rx=\"3\" \
+                 fill=\"{fill}\"/>"
+            ));
+            if exponent == 0 {
+                continue;
+            }
+
+            let value = 1u32 << exponent;
+            let digits = value.to_string().len() as u32;
+            let font_size = if digits <= 2 { 28 } else { 64 / digits + 4 };
+            let color = if exponent <= 2 {
+                palette.dark_text
+            } else {
+                palette.light_text
+            };
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{font_size}\" \
+                 font-weight=\"bold\" text-anchor=\"middle\" dominant-baseline=\"central\" \
+                 fill=\"{color}\">{value}</text>",
+                x + CELL / 2,
+                y + CELL / 2,
+            ));
+        }
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
@@ -0,0 +1,141 @@
+use crate::{BoardEncoding, Game, GameMode};
+use async_graphql::{scalar, InputObject, SimpleObject};
+use linera_sdk::base::{BcsHashable, CryptoHash, TimeDelta, Timestamp};
+use serde::{Deserialize, Serialize};
+
+/// The only board size games can be played on for now.
+pub const SUPPORTED_BOARD_SIZE: u8 = 4;
+
+/// Number of characters in a join code.
+pub const JOIN_CODE_LEN: usize = 8;
+
+/// Characters join codes are made of: upper-case letters and digits, without the ones easily
+/// mistaken for each other (`0`/`O`, `1`/`I`).
+const JOIN_CODE_ALPHABET: &[u8; 32] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+/// How long games created from a template last: they end as soon as the target tile is on
+/// the board.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+scalar!(Difficulty);
+
+impl Difficulty {
+    /// Returns the tile ending games played at this difficulty.
+    pub fn target_tile(self) -> u64 {
+        match self {
+            Difficulty::Easy => 512,
+            Difficulty::Normal => 1024,
+            Difficulty::Hard => 2048,
+        }
+    }
+}
+
+/// Settings an organizer defines once for all the games of a workshop or class.
+#[derive(
+    Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SimpleObject, InputObject,
+)]
+#[graphql(input_name = "GameTemplateInput")]
+pub struct GameTemplate {
+    pub name: String,
+    pub mode: GameMode,
+    /// Width and height of the board, from [`SUPPORTED_BOARD_SIZE`] to
+    /// [`MAX_BOARD_SIZE`](crate::MAX_BOARD_SIZE).
+    pub board_size: u8,
+    pub difficulty: Difficulty,
+    /// How long games may be played for, in seconds from their creation.
+    pub time_limit_secs: Option<u64>,
+}
+
+impl GameTemplate {
+    /// Returns whether a game created from this template is over.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::{Difficulty, GameMode, GameTemplate};
+    ///
+    /// let template = GameTemplate {
+    ///     name: "Workshop".to_string(),
+    ///     mode: GameMode::Classic,
+    ///     board_size: 4,
+    ///     difficulty: Difficulty::Easy,
+    ///     time_limit_secs: None,
+    /// };
+    ///
+    /// assert!(template.is_ended(0x0000_0000_0000_0921)); // A 512 tile.
+    /// assert!(!template.is_ended(0x0000_0000_0000_0821));
+    /// ```
+    pub fn is_ended(&self, board: u64) -> bool {
+        self.mode.is_ended(board) || Game::max_tile(board) >= self.difficulty.target_tile()
+    }
+
+    /// Returns how the boards of games created from this template are encoded, `None` if no
+    /// encoding fits their size.
+    pub fn board_encoding(&self) -> Option<BoardEncoding> {
+        BoardEncoding::for_board_size(self.board_size)
+    }
+
+    /// Returns when a game created at `created_at` runs out of time, if it is limited.
+    pub fn deadline(&self, created_at: Timestamp) -> Option<Timestamp> {
+        let time_limit = TimeDelta::from_secs(self.time_limit_secs?);
+        Some(created_at.saturating_add(time_limit))
+    }
+}
+
+/// A code participants use to register to a template, without the organizer collecting their
+/// accounts up front.
+///
+/// Codes are a convenience, not a secret: like everything else, they can be read from the
+/// application state.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct JoinCode {
+    pub template_id: u32,
+    /// How many participants may join with the code.
+    pub capacity: u32,
+    /// How many participants joined with the code so far.
+    pub uses: u32,
+    pub expires_at: Timestamp,
+}
+
+#[derive(Deserialize, Serialize)]
+struct JoinCodeInput {
+    template_id: u32,
+    nonce: u32,
+    minted_at: Timestamp,
+}
+
+impl BcsHashable for JoinCodeInput {}
+
+/// Derives a join code for `template_id`. Codes minted in the same block differ by their
+/// `nonce`.
+///
+/// # Examples
+///
+/// ```
+/// use game2048::{join_code, JOIN_CODE_LEN};
+///
+/// let code = join_code(0, 0, 1_000.into());
+///
+/// assert_eq!(code.len(), JOIN_CODE_LEN);
+/// assert!(code.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()));
+/// assert_ne!(code, join_code(0, 1, 1_000.into()));
+/// ```
+pub fn join_code(template_id: u32, nonce: u32, minted_at: Timestamp) -> String {
+    let input = JoinCodeInput {
+        template_id,
+        nonce,
+        minted_at,
+    };
+    let hash = CryptoHash::new(&input);
+    let bytes = hash.as_bytes();
+    bytes[..JOIN_CODE_LEN]
+        .iter()
+        .map(|byte| JOIN_CODE_ALPHABET[usize::from(byte % 32)] as char)
+        .collect()
+}
@@ -0,0 +1,128 @@
+use linera_sdk::base::Timestamp;
+
+/// Formats a `Timestamp` as an RFC 3339 string in UTC with microsecond precision.
+///
+/// # Examples
+///
+/// ```
+/// use game2048::rfc3339;
+/// use linera_sdk::base::Timestamp;
+///
+/// assert_eq!(rfc3339(Timestamp::from(0)), "1970-01-01T00:00:00.000000Z");
+/// assert_eq!(
+///     rfc3339(Timestamp::from(1_730_160_000_123_456)),
+///     "2024-10-29T00:00:00.123456Z"
+/// );
+/// ```
+pub fn rfc3339(timestamp: Timestamp) -> String {
+    let micros = timestamp.micros();
+    let seconds = micros / 1_000_000;
+    let (year, month, day) = civil_from_days(seconds / 86_400);
+    let seconds_of_day = seconds % 86_400;
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:06}Z",
+        seconds_of_day / 3_600,
+        seconds_of_day % 3_600 / 60,
+        seconds_of_day % 60,
+        micros % 1_000_000,
+    )
+}
+
+/// Formats day `day`, counted from the Unix epoch, as an ISO 8601 date.
+///
+/// ```
+/// use game2048::date_of;
+///
+/// assert_eq!(date_of(0), "1970-01-01");
+/// assert_eq!(date_of(20_025), "2024-10-29");
+/// ```
+pub fn date_of(day: u32) -> String {
+    let (year, month, day) = civil_from_days(u64::from(day));
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Parses an ISO 8601 date, `YYYY-MM-DD`, into its day counted from the Unix epoch. Returns
+/// `None` for malformed dates and dates before the epoch.
+///
+/// ```
+/// use game2048::day_from_date;
+///
+/// assert_eq!(day_from_date("1970-01-01"), Some(0));
+/// assert_eq!(day_from_date("2024-10-29"), Some(20_025));
+/// assert_eq!(day_from_date("2024-02-29"), Some(19_782));
+/// assert_eq!(day_from_date("2023-02-29"), None);
+/// assert_eq!(day_from_date("1969-12-31"), None);
+/// assert_eq!(day_from_date("2024-1-29"), None);
+/// ```
+pub fn day_from_date(date: &str) -> Option<u32> {
+    let fields = date.split('-').collect::<Vec<_>>();
+    let [year, month, day] = fields[..] else {
+        return None;
+    };
+    let number = |field: &str, len| {
+        let is_number = field.len() == len && field.bytes().all(|byte| byte.is_ascii_digit());
+        is_number.then(|| field.parse::<u64>().ok()).flatten()
+    };
+    let (year, month, day) = (number(year, 4)?, number(month, 2)?, number(day, 2)?);
+    if year < 1970 || !(1..=12).contains(&month) || day == 0 {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+    if civil_from_days(days) != (year, month, day) {
+        return None;
+    }
+    u32::try_from(days).ok()
+}
+
+/// Returns the number of whole seconds elapsed between `start` and `end`.
+pub fn duration_seconds(start: Timestamp, end: Timestamp) -> u64 {
+    end.duration_since(start).as_secs()
+}
+
+/// Returns the average number of moves played per minute between `start` and `end`.
+///
+/// Games shorter than a second have no meaningful rate and return `0.0`.
+pub fn moves_per_minute(moves: u32, start: Timestamp, end: Timestamp) -> f64 {
+    let micros = end.micros().saturating_sub(start.micros());
+    if micros < 1_000_000 {
+        return 0.0;
+    }
+    f64::from(moves) * 60_000_000.0 / micros as f64
+}
+
+/// Converts a `(year, month, day)` civil date after the epoch into days since the Unix epoch.
+///
+/// This is Howard Hinnant's `days_from_civil` algorithm, the inverse of `civil_from_days`.
+/// Days past the end of their month roll over into the next one.
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = year - u64::from(month <= 2);
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let month_index = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Converts days since the Unix epoch into a `(year, month, day)` civil date.
+///
+/// This is Howard Hinnant's `civil_from_days` algorithm restricted to dates after the epoch.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    (year, month, day)
+}
@@ -0,0 +1,119 @@
+use async_graphql::{scalar, SimpleObject};
+use linera_sdk::base::{Owner, Timestamp};
+use serde::{Deserialize, Serialize};
+
+/// Where a tournament is in its lifecycle. Tournaments only move forward, from `Open` to
+/// `Ended` once their end time passes, and to `Closed` once their organizer closes them.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum TournamentStatus {
+    /// Entrants join and play their games.
+    Open,
+    /// The end time passed: games still in flight end with their current score on their
+    /// next operation, and no entrant joins anymore.
+    Ended,
+    /// The standings are settled.
+    Closed,
+}
+
+scalar!(TournamentStatus);
+
+impl TournamentStatus {
+    /// Returns the status at `now` of a tournament ending at `ends_at`, if ever.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::TournamentStatus;
+    /// use linera_sdk::base::Timestamp;
+    ///
+    /// let ends_at = Some(Timestamp::from(100));
+    /// let status = |is_closed, now| TournamentStatus::at(is_closed, ends_at, Timestamp::from(now));
+    /// assert_eq!(status(false, 99), TournamentStatus::Open);
+    /// assert_eq!(status(false, 100), TournamentStatus::Ended);
+    /// assert_eq!(status(true, 99), TournamentStatus::Closed);
+    /// assert_eq!(
+    ///     TournamentStatus::at(false, None, Timestamp::from(u64::MAX)),
+    ///     TournamentStatus::Open
+    /// );
+    /// ```
+    pub fn at(is_closed: bool, ends_at: Option<Timestamp>, now: Timestamp) -> Self {
+        if is_closed {
+            TournamentStatus::Closed
+        } else if ends_at.is_some_and(|ends_at| now >= ends_at) {
+            TournamentStatus::Ended
+        } else {
+            TournamentStatus::Open
+        }
+    }
+}
+
+/// The final score an entrant submitted to a tournament.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct TournamentResult {
+    pub player: Owner,
+    pub game_id: u16,
+    pub score: u64,
+    pub submitted_at: Timestamp,
+}
+
+/// The place of an entrant in the standings of a tournament.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, SimpleObject)]
+pub struct TournamentStanding {
+    /// The rank of the entrant, from 1. Entrants with the same score share their rank.
+    pub rank: u32,
+    pub player: Owner,
+    pub game_id: u16,
+    pub score: u64,
+    pub submitted_at: Timestamp,
+}
+
+/// Ranks the submitted `results` of a tournament by score, the first submitted first among
+/// equal scores, then by player.
+///
+/// # Examples
+///
+/// ```
+/// use game2048::{tournament_standings, TournamentResult};
+/// use linera_sdk::base::{CryptoHash, Owner, Timestamp};
+///
+/// let result = |name, score, submitted_at| TournamentResult {
+///     player: Owner(CryptoHash::test_hash(name)),
+///     game_id: 7,
+///     score,
+///     submitted_at: Timestamp::from(submitted_at),
+/// };
+/// let standings = tournament_standings(vec![
+///     result("ada", 120, 3),
+///     result("bob", 300, 2),
+///     result("cyd", 120, 1),
+/// ]);
+/// let ranks = standings
+///     .iter()
+///     .map(|standing| (standing.rank, standing.score))
+///     .collect::<Vec<_>>();
+/// assert_eq!(ranks, [(1, 300), (2, 120), (2, 120)]);
+/// assert_eq!(standings[1].player, Owner(CryptoHash::test_hash("cyd")));
+/// ```
+pub fn tournament_standings(mut results: Vec<TournamentResult>) -> Vec<TournamentStanding> {
+    results.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(a.submitted_at.cmp(&b.submitted_at))
+            .then(a.player.cmp(&b.player))
+    });
+    let mut standings = Vec::<TournamentStanding>::with_capacity(results.len());
+    for (index, result) in results.into_iter().enumerate() {
+        let rank = match standings.last() {
+            Some(previous) if previous.score == result.score => previous.rank,
+            _ => index as u32 + 1,
+        };
+        standings.push(TournamentStanding {
+            rank,
+            player: result.player,
+            game_id: result.game_id,
+            score: result.score,
+            submitted_at: result.submitted_at,
+        });
+    }
+    standings
+}
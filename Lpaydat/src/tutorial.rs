@@ -0,0 +1,112 @@
+use crate::{Direction, Game};
+use async_graphql::scalar;
+use serde::{Deserialize, Serialize};
+
+/// A step of a tutorial: a fixed board and the move it teaches.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TutorialStep {
+    pub board: u64,
+    /// The only move the step accepts.
+    pub expected: Direction,
+    /// What the step explains to the player.
+    pub hint: &'static str,
+}
+
+/// A scripted tutorial. Every player plays it the same way: its boards are fixed rather than
+/// spawned from a seed, and each step only accepts the move it teaches.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tutorial {
+    pub name: &'static str,
+    pub steps: &'static [TutorialStep],
+}
+
+/// The tutorials shipped with the game, in the order players are offered them.
+///
+/// Every step expects a move that changes its board:
+///
+/// ```
+/// use game2048::TUTORIALS;
+///
+/// assert!(TUTORIALS.iter().all(|tutorial| tutorial.is_playable()));
+/// ```
+pub const TUTORIALS: [Tutorial; 3] = [
+    Tutorial {
+        name: "Sliding",
+        steps: &[
+            TutorialStep {
+                board: 0x0000_0100_0000_0000,
+                expected: Direction::Left,
+                hint: "Every tile slides as far as it can in the direction you swipe.",
+            },
+            TutorialStep {
+                board: 0x0000_1000_0000_0001,
+                expected: Direction::Up,
+                hint: "A new tile appears after every move. All tiles slide together.",
+            },
+        ],
+    },
+    Tutorial {
+        name: "Merging",
+        steps: &[
+            TutorialStep {
+                board: 0x0000_0000_0000_0011,
+                expected: Direction::Left,
+                hint: "Two equal tiles merge into their sum when they collide.",
+            },
+            TutorialStep {
+                board: 0x0000_0000_2000_2000,
+                expected: Direction::Down,
+                hint: "Merged tiles keep merging: two 4s make an 8.",
+            },
+            TutorialStep {
+                board: 0x0000_0000_0000_1111,
+                expected: Direction::Left,
+                hint: "A tile merges at most once per move: four 2s make two 4s.",
+            },
+        ],
+    },
+    Tutorial {
+        name: "Corners",
+        steps: &[
+            TutorialStep {
+                board: 0x0000_0000_0100_3100,
+                expected: Direction::Down,
+                hint: "Keep your largest tile in a corner and build next to it.",
+            },
+            TutorialStep {
+                board: 0x0000_0000_0000_3202,
+                expected: Direction::Left,
+                hint: "Merge towards the corner: the 4s join next to the 8.",
+            },
+            TutorialStep {
+                board: 0x0000_0000_0000_3300,
+                expected: Direction::Left,
+                hint: "Then the corner tile doubles: two 8s make a 16.",
+            },
+        ],
+    },
+];
+
+impl Tutorial {
+    /// Returns whether the move expected by every step changes its board.
+    pub fn is_playable(&self) -> bool {
+        self.steps
+            .iter()
+            .all(|step| Game::is_useful_move(step.board, step.expected.clone()))
+    }
+
+    /// Returns whether a player who completed `completed_steps` steps finished the tutorial.
+    pub fn is_complete(&self, completed_steps: u8) -> bool {
+        usize::from(completed_steps) >= self.steps.len()
+    }
+}
+
+/// A milestone a player unlocks once and keeps, for other features to build on, e.g. quests
+/// only offered to players who completed the tutorials.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum Achievement {
+    /// Completed every tutorial of [`TUTORIALS`].
+    TutorialComplete,
+}
+
+scalar!(Achievement);
@@ -0,0 +1,244 @@
+//! Verifies a game exported in the `.r2048` replay format without access to the chain, for
+//! tournament organizers checking the results submitted to them.
+//!
+//! The replay is read from a `.r2048` file, or from a JSON file holding the response of the
+//! service's `replay` query. Its moves are played again from the board spawned by its seed,
+//! and every checkpoint is compared to the board and score obtained. Unlike `Replay::verify`,
+//! replaying goes on after the first divergence, so that the report lists all of them. The
+//! process fails if any was found.
+
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+#[cfg(not(target_arch = "wasm32"))]
+use {
+    anyhow::{bail, Context},
+    clap::Parser,
+    game2048::{Game, GameMode, Replay, ScoredMove, REPLAY_MAGIC},
+    serde_json::Value,
+    std::{fmt, path::PathBuf},
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Parser)]
+struct Options {
+    /// The replay: a `.r2048` file, or a JSON file with the response of the `replay` query.
+    replay: PathBuf,
+}
+
+/// Where a replay departs from the game its seed and moves describe.
+#[cfg(not(target_arch = "wasm32"))]
+enum Divergence {
+    /// The recorded initial board is not the one spawned from the seed.
+    InitialBoard { recorded: u64, spawned: u64 },
+    /// The move does not change the board.
+    UselessMove { move_index: u32 },
+    /// The tile recorded after the move spawned on an occupied cell.
+    OccupiedSpawn { move_index: u32 },
+    Board {
+        move_index: u32,
+        recorded: u64,
+        replayed: u64,
+    },
+    Score {
+        move_index: u32,
+        recorded: u64,
+        replayed: u64,
+    },
+    /// The checkpoint is past the last move.
+    ExtraCheckpoint { move_index: u32 },
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Divergence::InitialBoard { recorded, spawned } => {
+                writeln!(f, "The initial board was not spawned from the seed")?;
+                write!(f, "{}", side_by_side(*recorded, *spawned))
+            }
+            Divergence::UselessMove { move_index } => {
+                write!(f, "Move {move_index} does not change the board")
+            }
+            Divergence::OccupiedSpawn { move_index } => {
+                write!(
+                    f,
+                    "The tile after move {move_index} spawned on an occupied cell"
+                )
+            }
+            Divergence::Board {
+                move_index,
+                recorded,
+                replayed,
+            } => {
+                writeln!(f, "The board after move {move_index} differs")?;
+                write!(f, "{}", side_by_side(*recorded, *replayed))
+            }
+            Divergence::Score {
+                move_index,
+                recorded,
+                replayed,
+            } => write!(
+                f,
+                "The score after move {move_index} is {recorded}, replaying gives {replayed}"
+            ),
+            Divergence::ExtraCheckpoint { move_index } => {
+                write!(
+                    f,
+                    "A checkpoint is recorded after move {move_index}, past the last move"
+                )
+            }
+        }
+    }
+}
+
+/// Reads the bytes of a replay from `path`.
+#[cfg(not(target_arch = "wasm32"))]
+fn read_replay(path: &PathBuf) -> anyhow::Result<Vec<u8>> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    if bytes.starts_with(&REPLAY_MAGIC) {
+        return Ok(bytes);
+    }
+    let response: Value =
+        serde_json::from_slice(&bytes).context("Neither a `.r2048` replay nor JSON")?;
+    let replay = response
+        .pointer("/data/replay")
+        .or_else(|| response.get("replay"))
+        .unwrap_or(&response);
+    if replay.is_null() {
+        bail!("The response holds no replay, the game does not exist");
+    }
+    serde_json::from_value(replay.clone()).context("The replay is not a list of bytes")
+}
+
+/// Plays the moves of `replay` from the board spawned by its seed, returning the divergences
+/// found along with the final board and score. Scores are only compared in the modes that
+/// do not score moves by when they were played.
+#[cfg(not(target_arch = "wasm32"))]
+fn replay_game(replay: &Replay) -> (Vec<Divergence>, u64, u64) {
+    let mut divergences = Vec::new();
+    let spawned = Game::new(replay.seed).board;
+    if spawned != replay.initial_board {
+        divergences.push(Divergence::InitialBoard {
+            recorded: replay.initial_board,
+            spawned,
+        });
+    }
+    let policy = replay.mode.score_policy();
+    let (mut board, mut score, mut combo) = (spawned, 0, 0);
+    let mut checkpoints = replay.checkpoints.iter().peekable();
+    for move_index in 0..=replay.moves.len() as u32 {
+        if move_index > 0 {
+            let replay_move = &replay.moves[move_index as usize - 1];
+            let moved = Game::move_board(board, replay_move.direction.clone());
+            if moved == board {
+                divergences.push(Divergence::UselessMove { move_index });
+            }
+            let cell = replay_move.spawned_tile.trailing_zeros() / 4;
+            if replay_move.spawned_tile != 0 && (moved >> (4 * cell)) & 0xF != 0 {
+                divergences.push(Divergence::OccupiedSpawn { move_index });
+            }
+            let next = moved | replay_move.spawned_tile;
+            combo = if Game::score(next) > Game::score(board) {
+                combo + 1
+            } else {
+                0
+            };
+            score = policy.score(&ScoredMove {
+                previous_board: board,
+                board: next,
+                previous_score: score,
+                combo,
+                elapsed_micros: 0,
+            });
+            board = next;
+        }
+        while let Some(checkpoint) = checkpoints.next_if(|c| c.move_index == move_index) {
+            if checkpoint.board != board {
+                divergences.push(Divergence::Board {
+                    move_index,
+                    recorded: checkpoint.board,
+                    replayed: board,
+                });
+            }
+            if replay.mode != GameMode::Blitz && checkpoint.score != score {
+                divergences.push(Divergence::Score {
+                    move_index,
+                    recorded: checkpoint.score,
+                    replayed: score,
+                });
+            }
+        }
+    }
+    for checkpoint in checkpoints {
+        divergences.push(Divergence::ExtraCheckpoint {
+            move_index: checkpoint.move_index,
+        });
+    }
+    (divergences, board, score)
+}
+
+/// Renders the rows of `board` with the value of each tile, `.` for empty cells.
+#[cfg(not(target_arch = "wasm32"))]
+fn rows(board: u64) -> Vec<String> {
+    Game::convert_to_matrix(board)
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&exponent| match exponent {
+                    0 => format!("{:>6}", "."),
+                    exponent => format!("{:>6}", 1u64 << exponent),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Renders a recorded board next to the replayed one.
+#[cfg(not(target_arch = "wasm32"))]
+fn side_by_side(recorded: u64, replayed: u64) -> String {
+    let mut lines = format!("  {:<24}    {}\n", "recorded", "replayed");
+    for (recorded, replayed) in rows(recorded).iter().zip(rows(replayed)) {
+        lines.push_str(&format!("  {recorded}    {replayed}\n"));
+    }
+    lines
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> anyhow::Result<()> {
+    let options = Options::parse();
+    let bytes = read_replay(&options.replay)?;
+    let replay = Replay::decode(&bytes).context("Invalid replay")?;
+    let status = if replay.is_ended {
+        "ended"
+    } else {
+        "unfinished"
+    };
+    println!(
+        "Game {} ({:?} mode{}), seed {}: {} moves, {} checkpoints, {status}",
+        replay.game_id,
+        replay.mode,
+        if replay.sandbox { ", sandbox" } else { "" },
+        replay.seed,
+        replay.moves.len(),
+        replay.checkpoints.len(),
+    );
+    if replay.mode == GameMode::Blitz {
+        println!("Scores are not checked: blitz games score moves by when they were played.");
+    }
+
+    let (divergences, board, score) = replay_game(&replay);
+    println!("Replayed final board, score {score}:");
+    for row in rows(board) {
+        println!("  {row}");
+    }
+    println!();
+    if divergences.is_empty() {
+        println!("No divergence: the replay matches its seed.");
+        return Ok(());
+    }
+    for divergence in &divergences {
+        println!("{divergence}");
+    }
+    bail!("{} divergences found", divergences.len())
+}
@@ -0,0 +1,221 @@
+use crate::{Direction, SUPPORTED_BOARD_SIZE};
+use async_graphql::scalar;
+use serde::{Deserialize, Serialize};
+
+/// Number of bits of each cell of a [`WideBoard`].
+pub const WIDE_CELL_BITS: u32 = 5;
+
+/// Largest width and height of a [`WideBoard`]: 25 cells of 5 bits fit in a `u128`.
+pub const MAX_WIDE_BOARD_SIZE: u8 = 5;
+
+/// Largest tile exponent of a [`WideBoard`]: two `2^31` tiles do not merge.
+pub const MAX_WIDE_EXPONENT: u8 = (1 << WIDE_CELL_BITS) - 1;
+
+/// How the cells of a board are packed into an integer. Cells hold the exponent of their
+/// tile, `0` for empty cells.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum BoardEncoding {
+    /// 4x4 boards in a `u64` of 4-bit cells, as played by [`Game`](crate::Game): tiles up
+    /// to 32768.
+    #[default]
+    Nibble,
+    /// Boards up to 5x5 in a `u128` of 5-bit cells, see [`WideBoard`]: tiles up to `2^31`.
+    Wide,
+}
+
+scalar!(BoardEncoding);
+
+impl BoardEncoding {
+    /// Returns the encoding of boards `board_size` cells wide, `None` if none fits them.
+    ///
+    /// ```
+    /// use game2048::BoardEncoding;
+    ///
+    /// assert_eq!(BoardEncoding::for_board_size(4), Some(BoardEncoding::Nibble));
+    /// assert_eq!(BoardEncoding::for_board_size(5), Some(BoardEncoding::Wide));
+    /// assert_eq!(BoardEncoding::for_board_size(6), None);
+    /// ```
+    pub fn for_board_size(board_size: u8) -> Option<Self> {
+        match board_size {
+            SUPPORTED_BOARD_SIZE => Some(BoardEncoding::Nibble),
+            2..=MAX_WIDE_BOARD_SIZE => Some(BoardEncoding::Wide),
+            _ => None,
+        }
+    }
+
+    /// Returns the largest tile exponent cells can hold.
+    pub fn max_exponent(self) -> u8 {
+        match self {
+            BoardEncoding::Nibble => 0xF,
+            BoardEncoding::Wide => MAX_WIDE_EXPONENT,
+        }
+    }
+
+    /// Returns the value of the largest tile boards can hold.
+    pub fn max_tile(self) -> u64 {
+        1 << self.max_exponent()
+    }
+}
+
+/// A board of `size` by `size` cells in the wide encoding.
+///
+/// Cells are ordered like the nibbles of a `u64` board: cell `0`, in the lowest bits, is the
+/// bottom right one, and cells follow right to left, then bottom to top. A 4x4 board converts
+/// to and from the nibble encoding cell by cell.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct WideBoard {
+    pub size: u8,
+    pub cells: u128,
+}
+
+impl WideBoard {
+    /// Returns an empty board of `size` by `size` cells.
+    pub fn empty(size: u8) -> Self {
+        assert!(
+            (1..=MAX_WIDE_BOARD_SIZE).contains(&size),
+            "Wide boards are at most {MAX_WIDE_BOARD_SIZE} cells wide"
+        );
+        WideBoard { size, cells: 0 }
+    }
+
+    /// Converts a 4x4 board from the nibble encoding.
+    pub fn from_nibbles(board: u64) -> Self {
+        let mut wide = WideBoard::empty(SUPPORTED_BOARD_SIZE);
+        for cell in 0..16 {
+            let exponent = (board >> (4 * cell)) & 0xF;
+            wide.cells |= u128::from(exponent) << (WIDE_CELL_BITS * cell);
+        }
+        wide
+    }
+
+    /// Converts a 4x4 board to the nibble encoding, `None` if it has another size or a tile
+    /// above 32768.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::{BoardEncoding, Direction, Game, WideBoard};
+    ///
+    /// let board = 0x0000_0000_0000_2211_u64;
+    /// let wide = WideBoard::from_nibbles(board);
+    /// assert_eq!(wide.to_nibbles(), Some(board));
+    /// assert_eq!(
+    ///     wide.slide(Direction::Left).to_nibbles(),
+    ///     Some(Game::move_left(board))
+    /// );
+    ///
+    /// // Two 32768 tiles merge into a tile only the wide encoding holds.
+    /// let wide = WideBoard::from_nibbles(0x0000_0000_0000_00FF).slide(Direction::Right);
+    /// assert_eq!(wide.max_tile(), 65_536);
+    /// assert!(wide.max_tile() > BoardEncoding::Nibble.max_tile());
+    /// assert_eq!(wide.to_nibbles(), None);
+    /// ```
+    pub fn to_nibbles(&self) -> Option<u64> {
+        if self.size != SUPPORTED_BOARD_SIZE {
+            return None;
+        }
+        let mut board = 0;
+        for cell in 0..16 {
+            let exponent = self.cell(cell);
+            if exponent > BoardEncoding::Nibble.max_exponent() {
+                return None;
+            }
+            board |= u64::from(exponent) << (4 * cell);
+        }
+        Some(board)
+    }
+
+    /// Returns the exponent of the tile at `row` and `col`, counted from the top left.
+    pub fn exponent(&self, row: u8, col: u8) -> u8 {
+        self.cell(self.index(row, col))
+    }
+
+    /// Sets the exponent of the tile at `row` and `col`, counted from the top left.
+    pub fn set_exponent(&mut self, row: u8, col: u8, exponent: u8) {
+        assert!(
+            exponent <= MAX_WIDE_EXPONENT,
+            "Tiles are at most 2^{MAX_WIDE_EXPONENT}"
+        );
+        let shift = WIDE_CELL_BITS * self.index(row, col);
+        self.cells &= !(u128::from(MAX_WIDE_EXPONENT) << shift);
+        self.cells |= u128::from(exponent) << shift;
+    }
+
+    /// Returns the value of the largest tile, or `0` for an empty board.
+    pub fn max_tile(&self) -> u64 {
+        let cell_count = u32::from(self.size) * u32::from(self.size);
+        match (0..cell_count).map(|cell| self.cell(cell)).max() {
+            None | Some(0) => 0,
+            Some(exponent) => 1 << exponent,
+        }
+    }
+
+    /// Returns the board after sliding its tiles towards `direction`, equal neighbors merging
+    /// once. No tile spawns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use game2048::{Direction, WideBoard};
+    ///
+    /// let mut board = WideBoard::empty(5);
+    /// for col in 0..5 {
+    ///     board.set_exponent(4, col, 20);
+    /// }
+    ///
+    /// let slid = board.slide(Direction::Left);
+    /// assert_eq!(
+    ///     (0..5).map(|col| slid.exponent(4, col)).collect::<Vec<_>>(),
+    ///     [21, 21, 20, 0, 0]
+    /// );
+    /// assert_eq!(board.slide(Direction::Down), board);
+    /// ```
+    pub fn slide(&self, direction: Direction) -> WideBoard {
+        let size = self.size;
+        let mut result = WideBoard::empty(size);
+        for line in 0..size {
+            let cells = (0..size)
+                .map(|step| match direction {
+                    Direction::Left => (line, step),
+                    Direction::Right => (line, size - 1 - step),
+                    Direction::Up => (step, line),
+                    Direction::Down => (size - 1 - step, line),
+                })
+                .collect::<Vec<_>>();
+            let mut exponents = Vec::with_capacity(usize::from(size));
+            let mut pending = None;
+            for &(row, col) in &cells {
+                let exponent = self.exponent(row, col);
+                if exponent == 0 {
+                    continue;
+                }
+                match pending {
+                    Some(previous) if previous == exponent && exponent < MAX_WIDE_EXPONENT => {
+                        exponents.push(exponent + 1);
+                        pending = None;
+                    }
+                    Some(previous) => {
+                        exponents.push(previous);
+                        pending = Some(exponent);
+                    }
+                    None => pending = Some(exponent),
+                }
+            }
+            exponents.extend(pending);
+            for (&(row, col), exponent) in cells.iter().zip(exponents) {
+                result.set_exponent(row, col, exponent);
+            }
+        }
+        result
+    }
+
+    fn index(&self, row: u8, col: u8) -> u32 {
+        assert!(row < self.size && col < self.size, "Cell out of the board");
+        let last = u32::from(self.size) - 1;
+        (last - u32::from(row)) * u32::from(self.size) + (last - u32::from(col))
+    }
+
+    fn cell(&self, index: u32) -> u8 {
+        ((self.cells >> (WIDE_CELL_BITS * index)) & u128::from(MAX_WIDE_EXPONENT)) as u8
+    }
+}
@@ -0,0 +1,517 @@
+//! Integration tests for player profiles and the account merge flow, confirmed by the primary
+//! account and approved by an admin.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute, fund, query};
+use game2048::{
+    daily_seed, Direction, FeeSettings, Game, Game2048Abi, Game2048Parameters, GameMode, Operation,
+    TUTORIALS,
+};
+use linera_sdk::{
+    base::{Amount, ApplicationId, KeyPair, Owner, Timestamp},
+    serde_json::{json, Value},
+    test::ActiveChain,
+};
+
+/// Merges the account of `duplicate` into the one of `primary`: the duplicate asks for it,
+/// the primary confirms it and `admin` approves it.
+async fn merge(
+    chain: &mut ActiveChain,
+    application_id: ApplicationId<Game2048Abi>,
+    duplicate: &KeyPair,
+    primary: &KeyPair,
+    admin: &KeyPair,
+) {
+    let (duplicate_owner, primary_owner) = (
+        Owner::from(duplicate.public()),
+        Owner::from(primary.public()),
+    );
+    let steps = [
+        (
+            duplicate,
+            Operation::RequestAccountMerge {
+                primary: primary_owner,
+            },
+        ),
+        (
+            primary,
+            Operation::ConfirmAccountMerge {
+                duplicate: duplicate_owner,
+            },
+        ),
+        (
+            admin,
+            Operation::ApproveAccountMerge {
+                duplicate: duplicate_owner,
+            },
+        ),
+    ];
+    for (key_pair, operation) in steps {
+        chain.set_key_pair(key_pair.copy());
+        assert!(execute(chain, application_id, operation).await);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn approved_merge_transfers_games_and_combines_stats() {
+    let (_validator, mut chain, application_id) = deploy(Game2048Parameters::default()).await;
+    // The creator of the application is its admin, and plays with a duplicate account.
+    let duplicate_key_pair = chain.key_pair().copy();
+    let duplicate = Owner::from(chain.public_key());
+    let primary_key_pair = KeyPair::generate();
+    let primary = Owner::from(primary_key_pair.public());
+
+    for operation in [
+        Operation::NewGame {
+            seed: 5,
+            namespace: None,
+            sandbox: false,
+            max_moves: None,
+            board_size: None,
+        },
+        Operation::MakeMove {
+            game_id: 5,
+            direction: Direction::Left,
+            namespace: None,
+        },
+        Operation::MakeMove {
+            game_id: 5,
+            direction: Direction::Right,
+            namespace: None,
+        },
+    ] {
+        assert!(execute(&chain, application_id, operation).await);
+    }
+    let game = query(
+        &chain,
+        application_id,
+        "query { game(gameId: 5) { owner score moveCount } }",
+    )
+    .await;
+    let score = game["game"]["score"].clone();
+    let move_count = game["game"]["moveCount"].clone();
+    assert_eq!(game["game"]["owner"], json!(duplicate));
+
+    assert!(
+        !execute(
+            &chain,
+            application_id,
+            Operation::ApproveAccountMerge { duplicate },
+        )
+        .await,
+        "accounts are only merged on their own request"
+    );
+    assert!(
+        !execute(
+            &chain,
+            application_id,
+            Operation::RequestAccountMerge { primary: duplicate },
+        )
+        .await,
+        "an account cannot be merged into itself"
+    );
+    assert!(
+        execute(
+            &chain,
+            application_id,
+            Operation::RequestAccountMerge { primary }
+        )
+        .await
+    );
+    let confirm = Operation::ConfirmAccountMerge { duplicate };
+    let approve = Operation::ApproveAccountMerge { duplicate };
+    assert!(
+        !execute(&chain, application_id, approve.clone()).await,
+        "the primary account did not confirm the merge"
+    );
+    assert!(
+        !execute(&chain, application_id, confirm.clone()).await,
+        "only the primary account confirms the merge"
+    );
+    chain.set_key_pair(primary_key_pair);
+    assert!(execute(&chain, application_id, confirm).await);
+    chain.set_key_pair(duplicate_key_pair);
+    assert!(execute(&chain, application_id, approve).await);
+
+    let players = query(
+        &chain,
+        application_id,
+        &format!(
+            "query {{ \
+             primary: player(owner: \"{primary}\") {{ \
+             games stats {{ gamesPlayed movesPlayed bestScore }} mergeRequest mergeConfirmed }} \
+             duplicate: player(owner: \"{duplicate}\") {{ games }} \
+             game(gameId: 5) {{ owner }} \
+             accountMerges {{ duplicate primary approvedBy games }} }}"
+        ),
+    )
+    .await;
+    assert_eq!(
+        players,
+        json!({
+            "primary": {
+                "games": [5],
+                "stats": {
+                    "gamesPlayed": 1,
+                    "movesPlayed": move_count,
+                    "bestScore": score,
+                },
+                "mergeRequest": null,
+                "mergeConfirmed": false,
+            },
+            "duplicate": null,
+            "game": { "owner": primary },
+            "accountMerges": [{
+                "duplicate": duplicate,
+                "primary": primary,
+                "approvedBy": duplicate,
+                "games": [5],
+            }],
+        })
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn merges_move_rankings_and_sponsored_games_within_the_active_game_limit() {
+    let (validator, mut chain, application_id) = deploy(Game2048Parameters::default()).await;
+    // The creator of the application is its admin, and plays with a duplicate account.
+    let duplicate_key_pair = chain.key_pair().copy();
+    let duplicate = Owner::from(chain.public_key());
+    let primary_key_pair = KeyPair::generate();
+    let primary = Owner::from(primary_key_pair.public());
+    let new_game = |seed| Operation::NewGame {
+        seed,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    let end_game = |game_id| Operation::EndGame {
+        game_id,
+        namespace: None,
+    };
+
+    // The daily game of the first day takes the id of its seed, which is still free.
+    let daily_game = daily_seed(0);
+    fund(&validator, &chain, duplicate, 2).await;
+    let operations = [
+        Operation::SetActiveGameLimit { limit: 2 },
+        Operation::NewDailyGame,
+        end_game(daily_game),
+        Operation::CreateCommunity {
+            community_id: "club".to_string(),
+            name: "Club".to_string(),
+            admins: vec![],
+            default_mode: GameMode::Classic,
+            fees: FeeSettings {
+                game_fee: Amount::from_tokens(1),
+                recipient: Some(duplicate),
+            },
+        },
+        Operation::SponsorGames {
+            community_id: "club".to_string(),
+            players: vec![duplicate],
+            games_per_player: 2,
+            expires_at: Timestamp::from(100 * 1_000_000),
+        },
+        // Game 5 ends after its first move, with the tournament result of the duplicate.
+        Operation::CreateTournament {
+            name: "Spring cup".to_string(),
+            seed: 5,
+            move_limit: 1,
+        },
+        Operation::JoinTournament { tournament_id: 0 },
+        Operation::MakeMove {
+            game_id: 5,
+            direction: Direction::Left,
+            namespace: None,
+        },
+        Operation::SubmitResult { tournament_id: 0 },
+        new_game(6),
+        Operation::RequestAccountMerge { primary },
+    ];
+    for operation in operations {
+        assert!(execute(&chain, application_id, operation).await);
+    }
+
+    chain.set_key_pair(primary_key_pair.copy());
+    for operation in [
+        new_game(7),
+        new_game(8),
+        Operation::ConfirmAccountMerge { duplicate },
+    ] {
+        assert!(execute(&chain, application_id, operation).await);
+    }
+    chain.set_key_pair(duplicate_key_pair.copy());
+    let approve = Operation::ApproveAccountMerge { duplicate };
+    assert!(
+        !execute(&chain, application_id, approve.clone()).await,
+        "the merged account would exceed the active game limit"
+    );
+    chain.set_key_pair(primary_key_pair);
+    assert!(execute(&chain, application_id, end_game(8)).await);
+    chain.set_key_pair(duplicate_key_pair);
+    assert!(execute(&chain, application_id, approve).await);
+
+    let response = query(
+        &chain,
+        application_id,
+        &format!(
+            "query {{ \
+             player(owner: \"{primary}\") {{ activeGames sponsorships }} \
+             sponsorship(sponsorshipId: 0) {{ credits {{ player games }} }} \
+             leaderboard {{ owner }} \
+             dailyLeaderboard(date: \"1970-01-01\") {{ player gameId }} \
+             tournament(tournamentId: 0) {{ standings {{ player gameId }} }} }}"
+        ),
+    )
+    .await;
+    assert_eq!(
+        response,
+        json!({
+            "player": { "activeGames": 2, "sponsorships": [0] },
+            "sponsorship": { "credits": [{ "player": primary, "games": 2 }] },
+            "leaderboard": [{ "owner": primary }],
+            "dailyLeaderboard": [{ "player": primary, "gameId": daily_game }],
+            "tournament": { "standings": [{ "player": primary, "gameId": 5 }] },
+        })
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn merges_pool_achievements_and_tutorial_progress() {
+    let (_validator, mut chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let admin = chain.key_pair().copy();
+
+    // Each account played the first steps of each tutorial, as many as listed.
+    let mut key_pairs = Vec::new();
+    for steps in [[2, 3, 3], [1, 0, 0], [2, 3, 0], [1, 0, 3]] {
+        chain.set_key_pair(KeyPair::generate());
+        for ((tutorial, steps), script) in (0..).zip(steps).zip(&TUTORIALS) {
+            for step in &script.steps[..steps] {
+                let play = Operation::PlayTutorial {
+                    tutorial,
+                    direction: step.expected.clone(),
+                };
+                assert!(execute(&chain, application_id, play).await);
+            }
+        }
+        key_pairs.push(chain.key_pair().copy());
+    }
+    // The first account completed every tutorial, the last two only did together.
+    for (duplicate, primary) in [(0, 1), (2, 3)] {
+        let (duplicate, primary) = (&key_pairs[duplicate], &key_pairs[primary]);
+        merge(&mut chain, application_id, duplicate, primary, &admin).await;
+    }
+
+    for primary in [&key_pairs[1], &key_pairs[3]] {
+        let owner = Owner::from(primary.public());
+        let player =
+            format!("query {{ player(owner: \"{owner}\") {{ tutorialSteps achievements }} }}");
+        assert_eq!(
+            query(&chain, application_id, &player).await,
+            json!({ "player": { "tutorialSteps": [2, 3, 3], "achievements": ["TutorialComplete"] } })
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sandbox_games_are_excluded_from_stats_and_rankings() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let owner = Owner::from(chain.public_key());
+
+    for (seed, sandbox) in [(5, false), (6, true)] {
+        let operations = [
+            Operation::NewGame {
+                seed,
+                namespace: None,
+                sandbox,
+                max_moves: None,
+                board_size: None,
+            },
+            Operation::MakeMove {
+                game_id: seed,
+                direction: Direction::Left,
+                namespace: None,
+            },
+            Operation::MakeMove {
+                game_id: seed,
+                direction: Direction::Right,
+                namespace: None,
+            },
+        ];
+        for operation in operations {
+            assert!(execute(&chain, application_id, operation).await);
+        }
+    }
+
+    let response = query(
+        &chain,
+        application_id,
+        &format!(
+            "query {{ \
+             ranked: game(gameId: 5) {{ score moveCount }} \
+             player(owner: \"{owner}\") {{ \
+             games stats {{ gamesPlayed movesPlayed bestScore }} }} \
+             sandbox: games(sandbox: true) {{ gameId }} \
+             regular: games(sandbox: false) {{ gameId }} \
+             leaderboardExport {{ total }} }}"
+        ),
+    )
+    .await;
+    assert_eq!(
+        response,
+        json!({
+            "ranked": response["ranked"],
+            "player": {
+                "games": [5, 6],
+                "stats": {
+                    "gamesPlayed": 1,
+                    "movesPlayed": response["ranked"]["moveCount"],
+                    "bestScore": response["ranked"]["score"],
+                },
+            },
+            "sandbox": [{ "gameId": 6 }],
+            // The game created at instantiation is not a sandbox game.
+            "regular": [{ "gameId": 1 }, { "gameId": 5 }],
+            "leaderboardExport": { "total": 2 },
+        })
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn heatmap_counts_spawns_and_where_the_largest_tile_ends() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let owner = Owner::from(chain.public_key());
+
+    for (seed, sandbox) in [(6, true), (5, false)] {
+        let operations = [
+            Operation::NewGame {
+                seed,
+                namespace: None,
+                sandbox,
+                max_moves: None,
+                board_size: None,
+            },
+            Operation::MakeMove {
+                game_id: seed,
+                direction: Direction::Left,
+                namespace: None,
+            },
+            Operation::EndGame {
+                game_id: seed,
+                namespace: None,
+            },
+        ];
+        for operation in operations {
+            assert!(execute(&chain, application_id, operation).await);
+        }
+    }
+    // Ending a game twice does not count its largest tile twice.
+    let end_game = Operation::EndGame {
+        game_id: 5,
+        namespace: None,
+    };
+    assert!(execute(&chain, application_id, end_game).await);
+
+    let response = query(
+        &chain,
+        application_id,
+        &format!(
+            "query {{ \
+             game(gameId: 5) {{ board moveCount }} \
+             player(owner: \"{owner}\") {{ heatmap {{ spawns maxTiles }} }} }}"
+        ),
+    )
+    .await;
+    let game = &response["game"];
+    let heatmap = &response["player"]["heatmap"];
+    let cells = |matrix: &Value| -> Vec<u64> {
+        let rows = matrix.as_array().unwrap().iter();
+        rows.flat_map(|row| {
+            row.as_array()
+                .unwrap()
+                .iter()
+                .map(|cell| cell.as_u64().unwrap())
+        })
+        .collect()
+    };
+
+    // Only the regular game counts: its initial tiles, then one spawn per move.
+    let initial_tiles = 16 - u64::from(Game::count_empty(Game::new(5).board));
+    let spawns = cells(&heatmap["spawns"]).into_iter().sum::<u64>();
+    assert_eq!(spawns, initial_tiles + game["moveCount"].as_u64().unwrap());
+    let board = cells(&game["board"]);
+    let max = board.iter().max().copied().unwrap();
+    let expected = board.iter().map(|&tile| u64::from(tile == max));
+    assert_eq!(cells(&heatmap["maxTiles"]), expected.collect::<Vec<_>>());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn active_games_are_limited_per_player() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let owner = Owner::from(chain.public_key());
+    let new_game = |seed, sandbox| Operation::NewGame {
+        seed,
+        namespace: None,
+        sandbox,
+        max_moves: None,
+        board_size: None,
+    };
+    let remaining_slots = format!("query {{ remainingGameSlots(owner: \"{owner}\") }}");
+
+    assert!(
+        execute(
+            &chain,
+            application_id,
+            Operation::SetActiveGameLimit { limit: 2 }
+        )
+        .await
+    );
+    assert!(execute(&chain, application_id, new_game(5, false)).await);
+    assert!(execute(&chain, application_id, new_game(6, false)).await);
+    assert_eq!(
+        query(&chain, application_id, &remaining_slots).await,
+        json!({ "remainingGameSlots": 0 })
+    );
+    assert!(
+        !execute(&chain, application_id, new_game(7, false)).await,
+        "players cannot exceed the active game limit"
+    );
+    assert!(
+        execute(&chain, application_id, new_game(6, false)).await,
+        "restarting an active game reuses its slot"
+    );
+    assert!(
+        execute(&chain, application_id, new_game(8, true)).await,
+        "sandbox games are not limited"
+    );
+
+    let end_game = Operation::EndGame {
+        game_id: 5,
+        namespace: None,
+    };
+    assert!(execute(&chain, application_id, end_game).await);
+    assert_eq!(
+        query(&chain, application_id, &remaining_slots).await,
+        json!({ "remainingGameSlots": 1 })
+    );
+    assert!(execute(&chain, application_id, new_game(7, false)).await);
+
+    let response = query(
+        &chain,
+        application_id,
+        &format!("query {{ activeGameLimit player(owner: \"{owner}\") {{ activeGames }} }}"),
+    )
+    .await;
+    assert_eq!(
+        response,
+        json!({
+            "activeGameLimit": 2,
+            "player": { "activeGames": 2 },
+        })
+    );
+}
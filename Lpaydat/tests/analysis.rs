@@ -0,0 +1,75 @@
+//! Integration tests for the coaching analysis of finished games.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute, query};
+use game2048::{Direction, Game2048Parameters, Operation};
+use linera_sdk::serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn finished_games_are_analyzed() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+
+    let new_game = Operation::NewGame {
+        seed: 5,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    assert!(execute(&chain, application_id, new_game).await);
+    for direction in [
+        Direction::Left,
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+        Direction::Up,
+    ] {
+        let operation = Operation::MakeMove {
+            game_id: 5,
+            direction,
+            namespace: None,
+        };
+        assert!(execute(&chain, application_id, operation).await);
+    }
+    let analysis_query =
+        "query { gameAnalysis(gameId: 5) { accuracy moveCount blunders { moveIndex loss } } }";
+    assert_eq!(
+        query(&chain, application_id, analysis_query).await["gameAnalysis"],
+        json!(null),
+        "games are analyzed once they end"
+    );
+
+    let end_game = Operation::EndGame {
+        game_id: 5,
+        namespace: None,
+    };
+    assert!(execute(&chain, application_id, end_game).await);
+    let game = query(
+        &chain,
+        application_id,
+        "query { game(gameId: 5) { moveCount } }",
+    )
+    .await;
+    let move_count = game["game"]["moveCount"].as_u64().unwrap();
+    let response = query(&chain, application_id, analysis_query).await;
+    let analysis = &response["gameAnalysis"];
+    assert!(move_count > 0);
+    assert_eq!(analysis["moveCount"].as_u64(), Some(move_count));
+    assert!(analysis["accuracy"].as_u64().unwrap() <= 100);
+
+    let blunders = analysis["blunders"].as_array().unwrap();
+    assert!(blunders.len() <= 3);
+    let losses = blunders
+        .iter()
+        .map(|blunder| blunder["loss"].as_u64().unwrap());
+    let losses = losses.collect::<Vec<_>>();
+    assert!(losses.windows(2).all(|pair| pair[0] >= pair[1]));
+    for blunder in blunders {
+        let move_index = blunder["moveIndex"].as_u64().unwrap();
+        assert!((1..=move_count).contains(&move_index));
+    }
+}
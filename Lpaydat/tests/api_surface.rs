@@ -0,0 +1,245 @@
+//! Snapshot of the public API of the library, so that breaking changes are caught before a
+//! release rather than by the clients they break.
+//!
+//! Signatures are pinned by coercing items to the exact types clients rely on: changing one
+//! fails to compile. The wire types are pinned by the names of their variants and fields, in
+//! declaration order, which is what their encodings depend on. New variants and fields may be
+//! appended, after which the snapshots below must be extended; anything else is a breaking
+//! change.
+
+use std::fmt;
+
+use game2048::{
+    gen_range, Direction, Game, Game2048Parameters, Message, MoveTableMismatch, Moves, Operation,
+    OperationDecodeError, OperationEnvelope, Rng, COL_MASK, CURRENT_OPERATION_VERSION, ROW_MASK,
+};
+use linera_sdk::base::ChainId;
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+
+#[test]
+fn signatures_are_stable() {
+    let _: fn(u16) -> Game = Game::new;
+    let _: fn(&mut Game, Direction) -> u64 = Game::execute;
+    let _: fn(u64, Direction, &mut Rng) -> u64 = Game::execute_with;
+    let _: fn(u16, &[Direction]) -> (u64, u64) = Game::replay;
+    let _: fn(u64, Direction) -> u64 = Game::move_board;
+    let _: fn(u64, Direction) -> bool = Game::is_useful_move;
+    let _: fn(u64) -> [[u16; 4]; 4] = Game::convert_to_matrix;
+    let _: fn(u64) -> bool = Game::is_ended;
+    let _: fn(u64) -> u64 = Game::score;
+    let _: fn(u64) -> u64 = Game::max_tile;
+    let _: fn(u64, u16) -> u64 = Game::spawn_tile;
+    let _: fn(&mut Rng, u32) -> Result<(), MoveTableMismatch> = Game::check_move_tables;
+    let Game { board: _, seed: _ } = Game::new(1);
+
+    let _: fn() -> Moves = Moves::new;
+    let _: fn(u64) -> u64 = Moves::column_from;
+    let _: fn(&mut [u64], u64) = Moves::slide_line;
+    let _: fn(&Moves, u16) -> Result<(), MoveTableMismatch> = Moves::check_row;
+    let Moves {
+        left: _,
+        right: _,
+        down: _,
+        up: _,
+        scores: _,
+    } = Moves::new();
+    let _: u64 = ROW_MASK | COL_MASK;
+
+    let _: fn(u64) -> Rng = Rng::new;
+    let _: fn(u64, ChainId, u32) -> Rng = Rng::for_spawn;
+    let _: fn(ChainId, u64, u64, u64) -> Rng = Rng::for_block;
+    let _: fn(&mut Rng) -> u16 = Rng::next_seed;
+    let _: fn(&str, u16, u16) -> u16 = gen_range;
+
+    let _: fn(&Operation) -> OperationEnvelope = OperationEnvelope::new;
+    let _: fn(&OperationEnvelope) -> Result<Operation, OperationDecodeError> =
+        OperationEnvelope::decode;
+    let _: fn(&Operation) -> &'static str = Operation::name;
+    let _: u8 = CURRENT_OPERATION_VERSION;
+}
+
+#[test]
+fn wire_types_only_grow() {
+    assert_extends::<Direction>(&["Up", "Down", "Left", "Right"]);
+    assert_extends::<OperationEnvelope>(&["version", "payload"]);
+    assert_extends::<Operation>(&[
+        "NewGame",
+        "EndGame",
+        "MakeMove",
+        "CreateCommunity",
+        "ConfigureCommunity",
+        "JoinCommunity",
+        "LeaveCommunity",
+        "CreateBeaconRound",
+        "CommitEntropy",
+        "RevealEntropy",
+        "CloseBeaconRound",
+        "RequestAccountMerge",
+        "ApproveAccountMerge",
+        "SetActiveGameLimit",
+        "RequestGame",
+        "StartGame",
+        "CreateTemplate",
+        "NewTemplateGame",
+        "MintJoinCode",
+        "JoinWithCode",
+        "SetItemPrice",
+        "SetTreasury",
+        "BuyItem",
+        "ConfigureSeasonPass",
+        "BuySeasonPass",
+        "SetAttester",
+        "SubmitAttestation",
+        "SetLeaderboardFilters",
+        "JoinMatchQueue",
+        "LeaveMatchQueue",
+        "CreateLobby",
+        "JoinLobbyQueue",
+        "Undo",
+        "Challenge",
+        "AcceptChallenge",
+        "Spectate",
+        "StopSpectating",
+        "SponsorGames",
+        "WithdrawSponsorship",
+        "DisputeMatch",
+        "ResolveDispute",
+        "ChallengeDisputeResult",
+        "FinalizeDispute",
+        "CreateTournament",
+        "JoinTournament",
+        "SubmitResult",
+        "CloseTournament",
+        "VerifyGame",
+        "ConfigureMode",
+        "SetTournamentEnd",
+        "EndTournamentGames",
+        "NewDailyGame",
+        "PlayTutorial",
+        "PauseGame",
+        "ResumeGame",
+        "SetChatKey",
+        "SendChat",
+        "Batch",
+        "ConfirmAccountMerge",
+    ]);
+    assert_extends::<Message>(&[
+        "Game",
+        "ResendRequest",
+        "JoinMatchQueue",
+        "LeaveMatchQueue",
+        "JoinLobbyQueue",
+        "MatchFound",
+        "Challenge",
+        "ChallengeAccepted",
+        "ChallengeResult",
+        "ChallengeSettled",
+        "Subscribe",
+        "Unsubscribe",
+        "Snapshot",
+        "DisputeMatch",
+        "ChallengeDisputeResult",
+        "CheckMatchGame",
+        "MatchGameChecked",
+        "ChatKey",
+        "Chat",
+        "MintReward",
+        "SettleBond",
+    ]);
+    assert_extends::<Game2048Parameters>(&[
+        "efficiency_weights",
+        "hub",
+        "campaign_id",
+        "features",
+        "message_faults",
+        "dispute_bond",
+        "dispute_challenge_window_micros",
+        "dispute_challenge_bond",
+        "min_move_interval_micros",
+        "response_signers",
+        "rewards",
+        "bond_token",
+    ]);
+}
+
+/// Checks that the variants or fields of `T` start with `snapshot`, in order.
+fn assert_extends<'de, T: Deserialize<'de>>(snapshot: &[&str]) {
+    let names = names_of::<T>();
+    let name = std::any::type_name::<T>();
+    assert!(
+        names.starts_with(snapshot),
+        "{name} changed in a breaking way: expected {snapshot:?} first, found {names:?}"
+    );
+    assert_eq!(
+        names.len(),
+        snapshot.len(),
+        "{name} grew to {names:?}: extend its snapshot"
+    );
+}
+
+/// Returns the names of the variants or fields of `T`, as its `Deserialize` implementation
+/// declares them.
+fn names_of<'de, T: Deserialize<'de>>() -> Vec<&'static str> {
+    match T::deserialize(NameRecorder) {
+        Err(Recorded::Names(names)) => names.to_vec(),
+        Err(Recorded::Unsupported(message)) => panic!("{message}"),
+        Ok(_) => unreachable!("The recorder produces no values"),
+    }
+}
+
+/// A deserializer that stops at the first enum or struct, reporting its names as an error.
+struct NameRecorder;
+
+#[derive(Debug)]
+enum Recorded {
+    Names(&'static [&'static str]),
+    Unsupported(String),
+}
+
+impl fmt::Display for Recorded {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{self:?}")
+    }
+}
+
+impl std::error::Error for Recorded {}
+
+impl de::Error for Recorded {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        Recorded::Unsupported(message.to_string())
+    }
+}
+
+impl<'de> Deserializer<'de> for NameRecorder {
+    type Error = Recorded;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Recorded> {
+        Err(Recorded::Unsupported(
+            "Only enums and structs are snapshotted".to_string(),
+        ))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Recorded> {
+        Err(Recorded::Names(variants))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Recorded> {
+        Err(Recorded::Names(fields))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map identifier
+        ignored_any
+    }
+}
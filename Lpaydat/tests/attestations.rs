@@ -0,0 +1,149 @@
+//! Integration tests for signed score attestations.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute_at};
+use game2048::{
+    verify_attestation, Direction, Game2048Parameters, Operation, ScoreAttestation,
+    SignedAttestation, ATTESTATION_EPOCH_SECS,
+};
+use linera_sdk::{
+    base::{KeyPair, Owner, Signature},
+    serde_json::{json, Value},
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn attestations_must_match_the_best_score_and_epoch() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let owner = Owner::from(chain.public_key());
+
+    for operation in [
+        Operation::NewGame {
+            seed: 5,
+            namespace: None,
+            sandbox: false,
+            max_moves: None,
+            board_size: None,
+        },
+        Operation::MakeMove {
+            game_id: 5,
+            direction: Direction::Left,
+            namespace: None,
+        },
+        Operation::MakeMove {
+            game_id: 5,
+            direction: Direction::Right,
+            namespace: None,
+        },
+    ] {
+        assert!(execute_at(&chain, application_id, 0, operation).await);
+    }
+
+    // Both fields load the player, so they are queried separately.
+    let query = format!("query {{ player(owner: \"{owner}\") {{ stats {{ bestScore }} }} }}");
+    let player: Value = chain.graphql_query(application_id, query).await;
+    let query = format!(
+        "query {{ attestationPayload(owner: \"{owner}\") {{ owner bestScore applicationId epoch }} }}"
+    );
+    let response: Value = chain.graphql_query(application_id, query).await;
+    let payload = &response["attestationPayload"];
+    assert_eq!(payload["bestScore"], player["player"]["stats"]["bestScore"]);
+    let attestation = ScoreAttestation {
+        owner,
+        best_score: payload["bestScore"].as_u64().unwrap(),
+        application_id: application_id.forget_abi(),
+        epoch: payload["epoch"].as_u64().unwrap() as u32,
+    };
+    assert_eq!(payload["applicationId"], json!(attestation.application_id));
+    let epoch_start = u64::from(attestation.epoch) * ATTESTATION_EPOCH_SECS;
+
+    let attester = chain.key_pair().copy();
+    let submit =
+        |attestation: &ScoreAttestation, key_pair: &KeyPair| Operation::SubmitAttestation {
+            attestation: attestation.clone(),
+            signature: Signature::new(attestation, key_pair),
+        };
+
+    assert!(
+        !execute_at(
+            &chain,
+            application_id,
+            epoch_start,
+            submit(&attestation, &attester)
+        )
+        .await,
+        "attestations need an attester"
+    );
+    let set_attester = Operation::SetAttester {
+        attester: attester.public(),
+    };
+    assert!(execute_at(&chain, application_id, epoch_start, set_attester).await);
+
+    let forged = KeyPair::generate();
+    assert!(
+        !execute_at(
+            &chain,
+            application_id,
+            epoch_start,
+            submit(&attestation, &forged)
+        )
+        .await,
+        "only the attester signs attestations"
+    );
+    let inflated = ScoreAttestation {
+        best_score: attestation.best_score + 4,
+        ..attestation.clone()
+    };
+    assert!(
+        !execute_at(
+            &chain,
+            application_id,
+            epoch_start,
+            submit(&inflated, &attester)
+        )
+        .await,
+        "attestations must match the best score"
+    );
+    let next_epoch = epoch_start + ATTESTATION_EPOCH_SECS;
+    assert!(
+        !execute_at(
+            &chain,
+            application_id,
+            next_epoch,
+            submit(&attestation, &attester)
+        )
+        .await,
+        "attestations expire with their epoch"
+    );
+    assert!(
+        execute_at(
+            &chain,
+            application_id,
+            epoch_start + 1,
+            submit(&attestation, &attester)
+        )
+        .await
+    );
+
+    let query = format!(
+        "query {{ attester scoreAttestation(owner: \"{owner}\") {{ \
+         attestation {{ bestScore epoch }} signature }} }}"
+    );
+    let response: Value = chain.graphql_query(application_id, query).await;
+    assert_eq!(response["attester"], json!(attester.public()));
+    let stored = &response["scoreAttestation"];
+    assert_eq!(
+        stored["attestation"]["bestScore"],
+        json!(attestation.best_score)
+    );
+    let signature: Signature = linera_sdk::serde_json::from_value(stored["signature"].clone())
+        .expect("signatures are serialized as strings");
+    let signed = SignedAttestation {
+        attestation,
+        signature,
+    };
+    assert!(verify_attestation(&signed, attester.public()));
+    assert!(!verify_attestation(&signed, forged.public()));
+}
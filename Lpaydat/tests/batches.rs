@@ -0,0 +1,73 @@
+//! Integration tests for batches of operations.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute};
+use game2048::{Direction, Game2048Parameters, Operation, MAX_BATCH_LEN};
+use linera_sdk::serde_json::{json, Value};
+
+fn new_game(seed: u16) -> Operation {
+    Operation::NewGame {
+        seed,
+        namespace: None,
+        // Sandbox games do not count against the limit of active games.
+        sandbox: true,
+        max_moves: None,
+        board_size: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn batches_execute_all_or_none_of_their_operations() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let query = "query { game(gameId: 5) { moveCount } \
+                 metrics { operations { operation count } } }";
+
+    // The bottom row of seed 5 is `2 2 0 0`: it cannot move down, so the batch is dropped
+    // along with the game it created.
+    let move_down = Operation::MakeMove {
+        game_id: 5,
+        direction: Direction::Down,
+        namespace: None,
+    };
+    let batch = Operation::Batch(vec![new_game(5), move_down]);
+    assert!(!execute(&chain, application_id, batch).await);
+    let response: Value = chain.graphql_query(application_id, query).await;
+    assert_eq!(response["game"], json!(null));
+
+    let move_left = Operation::MakeMove {
+        game_id: 5,
+        direction: Direction::Left,
+        namespace: None,
+    };
+    let batch = Operation::Batch(vec![new_game(5), move_left]);
+    assert!(execute(&chain, application_id, batch).await);
+    let response: Value = chain.graphql_query(application_id, query).await;
+    assert_eq!(response["game"]["moveCount"], json!(1));
+    let operations = response["metrics"]["operations"].as_array().unwrap();
+    for name in ["Batch", "NewGame", "MakeMove"] {
+        assert!(
+            operations.contains(&json!({ "operation": name, "count": 1 })),
+            "{name} is counted once"
+        );
+    }
+
+    assert!(
+        !execute(&chain, application_id, Operation::Batch(vec![])).await,
+        "empty batches are rejected"
+    );
+    let nested = Operation::Batch(vec![Operation::Batch(vec![new_game(7)])]);
+    assert!(
+        !execute(&chain, application_id, nested).await,
+        "batches cannot be nested"
+    );
+    let too_long = Operation::Batch((1..=MAX_BATCH_LEN as u16 + 1).map(new_game).collect());
+    assert!(
+        !execute(&chain, application_id, too_long).await,
+        "batches are bounded"
+    );
+    let longest = Operation::Batch((10..10 + MAX_BATCH_LEN as u16).map(new_game).collect());
+    assert!(execute(&chain, application_id, longest).await);
+}
@@ -0,0 +1,236 @@
+//! Integration tests for the commit-reveal randomness beacon, including its timeouts.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute_at_micros};
+use game2048::{
+    beacon_output, beacon_seed, commitment, game_request_seed, Game2048Parameters, Operation,
+    GAME_REQUEST_TIMEOUT_MICROS,
+};
+use linera_sdk::{
+    base::{Amount, ApplicationId, Owner, Timestamp},
+    serde_json::{json, Value},
+    test::ActiveChain,
+};
+
+const ENTROPY: &[u8] = b"the participant's secret";
+
+async fn setup() -> (ActiveChain, ApplicationId<game2048::Game2048Abi>, Owner) {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let owner = Owner::from(chain.public_key());
+    (chain, application_id, owner)
+}
+
+async fn round(
+    chain: &ActiveChain,
+    application_id: ApplicationId<game2048::Game2048Abi>,
+    round_id: u32,
+) -> Value {
+    let query = format!(
+        "query {{ beaconRound(roundId: {round_id}) {{ \
+         commitCount revealCount isClosed output seed slashed forfeited }} }}"
+    );
+    let response: Value = chain.graphql_query(application_id, query).await;
+    response["beaconRound"].clone()
+}
+
+fn create_round(stake: Amount) -> Operation {
+    Operation::CreateBeaconRound {
+        commit_deadline: Timestamp::from(1_000),
+        reveal_deadline: Timestamp::from(2_000),
+        stake,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn revealed_round_closes_early_with_a_seed() {
+    let (chain, application_id, owner) = setup().await;
+    let commit = Operation::CommitEntropy {
+        round_id: 0,
+        commitment: commitment(owner, ENTROPY),
+    };
+    let reveal = |entropy: &[u8]| Operation::RevealEntropy {
+        round_id: 0,
+        entropy: entropy.to_vec(),
+    };
+    let close = Operation::CloseBeaconRound { round_id: 0 };
+
+    assert!(execute_at_micros(&chain, application_id, 0, create_round(Amount::ONE)).await);
+    assert!(
+        !execute_at_micros(&chain, application_id, 100, reveal(ENTROPY)).await,
+        "reveals are only accepted after the commit deadline"
+    );
+    assert!(execute_at_micros(&chain, application_id, 100, commit.clone()).await);
+    assert!(
+        !execute_at_micros(&chain, application_id, 200, commit).await,
+        "entropy can only be committed once"
+    );
+    assert!(
+        !execute_at_micros(&chain, application_id, 300, close.clone()).await,
+        "rounds cannot close during the commit phase"
+    );
+    assert!(
+        !execute_at_micros(&chain, application_id, 1_000, reveal(b"something else")).await,
+        "reveals must match the commitment"
+    );
+    assert!(execute_at_micros(&chain, application_id, 1_000, reveal(ENTROPY)).await);
+    assert!(execute_at_micros(&chain, application_id, 1_100, close.clone()).await);
+    assert!(
+        !execute_at_micros(&chain, application_id, 1_200, close).await,
+        "rounds close only once"
+    );
+
+    let output = beacon_output(0, vec![(owner, ENTROPY.to_vec())]);
+    assert_eq!(
+        round(&chain, application_id, 0).await,
+        json!({
+            "commitCount": 1,
+            "revealCount": 1,
+            "isClosed": true,
+            "output": output.to_string(),
+            "seed": beacon_seed(&output),
+            "slashed": [],
+            "forfeited": Amount::ZERO.to_string(),
+        })
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn missing_reveals_are_slashed_after_the_timeout() {
+    let (chain, application_id, owner) = setup().await;
+    let stake = Amount::from_tokens(5);
+    let close = Operation::CloseBeaconRound { round_id: 0 };
+
+    assert!(execute_at_micros(&chain, application_id, 0, create_round(stake)).await);
+    assert!(
+        execute_at_micros(
+            &chain,
+            application_id,
+            100,
+            Operation::CommitEntropy {
+                round_id: 0,
+                commitment: commitment(owner, ENTROPY),
+            },
+        )
+        .await
+    );
+    assert!(
+        !execute_at_micros(&chain, application_id, 1_500, close.clone()).await,
+        "rounds wait for reveals until the reveal deadline"
+    );
+    assert!(
+        !execute_at_micros(
+            &chain,
+            application_id,
+            2_000,
+            Operation::RevealEntropy {
+                round_id: 0,
+                entropy: ENTROPY.to_vec(),
+            },
+        )
+        .await,
+        "reveals are rejected once the round timed out"
+    );
+    assert!(execute_at_micros(&chain, application_id, 2_000, close).await);
+
+    assert_eq!(
+        round(&chain, application_id, 0).await,
+        json!({
+            "commitCount": 1,
+            "revealCount": 0,
+            "isClosed": true,
+            "output": null,
+            "seed": null,
+            "slashed": [owner.to_string()],
+            "forfeited": stake.to_string(),
+        })
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn requested_games_are_seeded_once_the_round_closes() {
+    let (chain, application_id, owner) = setup().await;
+    let request = Operation::RequestGame {
+        namespace: None,
+        sandbox: false,
+        round_id: 0,
+    };
+    let start = |request_id| Operation::StartGame { request_id };
+
+    assert!(execute_at_micros(&chain, application_id, 0, create_round(Amount::ZERO)).await);
+    assert!(execute_at_micros(&chain, application_id, 100, request.clone()).await);
+    assert!(execute_at_micros(&chain, application_id, 100, request.clone()).await);
+    assert!(
+        execute_at_micros(
+            &chain,
+            application_id,
+            200,
+            Operation::CommitEntropy {
+                round_id: 0,
+                commitment: commitment(owner, ENTROPY),
+            },
+        )
+        .await
+    );
+    assert!(
+        !execute_at_micros(&chain, application_id, 300, start(0)).await,
+        "games start once the seed is known"
+    );
+    assert!(
+        !execute_at_micros(&chain, application_id, 1_000, request).await,
+        "games cannot be requested once the round stopped taking commitments"
+    );
+    assert!(
+        execute_at_micros(
+            &chain,
+            application_id,
+            1_000,
+            Operation::RevealEntropy {
+                round_id: 0,
+                entropy: ENTROPY.to_vec(),
+            },
+        )
+        .await
+    );
+    assert!(
+        execute_at_micros(
+            &chain,
+            application_id,
+            1_100,
+            Operation::CloseBeaconRound { round_id: 0 }
+        )
+        .await
+    );
+
+    let output = beacon_output(0, vec![(owner, ENTROPY.to_vec())]);
+    let game_id = game_request_seed(&output, 0);
+    let query = format!("query {{ pendingGames(owner: \"{owner}\") {{ requestId gameId }} }}");
+    let pending: Value = chain.graphql_query(application_id, query).await;
+    assert_eq!(
+        pending["pendingGames"],
+        json!([
+            { "requestId": 0, "gameId": game_id },
+            { "requestId": 1, "gameId": game_request_seed(&output, 1) },
+        ])
+    );
+
+    assert!(execute_at_micros(&chain, application_id, 1_200, start(0)).await);
+    assert!(
+        !execute_at_micros(&chain, application_id, 1_300, start(0)).await,
+        "requested games start once"
+    );
+    let expired = 2_000 + GAME_REQUEST_TIMEOUT_MICROS;
+    assert!(
+        !execute_at_micros(&chain, application_id, expired, start(1)).await,
+        "abandoned requests expire"
+    );
+
+    let query = format!("query {{ game(gameId: {game_id}) {{ owner seedRound }} }}");
+    let game: Value = chain.graphql_query(application_id, query).await;
+    assert_eq!(
+        game["game"],
+        json!({ "owner": owner.to_string(), "seedRound": 0 })
+    );
+}
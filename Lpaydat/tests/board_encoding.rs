@@ -0,0 +1,42 @@
+//! Integration tests for the encodings of the board the service exposes besides tile
+//! exponents.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::deploy;
+use game2048::{Game, Game2048Parameters, Operation, OperationEnvelope};
+use linera_sdk::serde_json::{json, Value};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn boards_are_exposed_packed_and_as_tile_values() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let new_game = Operation::NewGame {
+        seed: 5,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    chain
+        .add_block(|block| {
+            block.with_operation(application_id, OperationEnvelope::new(&new_game));
+        })
+        .await;
+
+    let query = "query { game(gameId: 5) { board boardHex tiles } }";
+    let response: Value = chain.graphql_query(application_id, query).await;
+    let board = Game::new(5).board;
+    assert_eq!(
+        response["game"],
+        json!({
+            "board": Game::convert_to_matrix(board),
+            "boardHex": format!("{board:016x}"),
+            "tiles": Game::to_values_matrix(board),
+        })
+    );
+    let hex = response["game"]["boardHex"].as_str().unwrap();
+    assert_eq!(hex.len(), 16);
+    assert_eq!(u64::from_str_radix(hex, 16).unwrap(), board);
+}
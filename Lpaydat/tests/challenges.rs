@@ -0,0 +1,144 @@
+//! Integration tests for head-to-head challenges between the players of two chains.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute, query};
+use game2048::{Direction, Game2048Parameters, Operation, CHALLENGE_MOVE_LIMIT};
+use linera_sdk::{base::Owner, serde_json::json};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn challenges_are_settled_by_the_challenger_chain() {
+    let (validator, challenger, application_id) = deploy(Game2048Parameters::default()).await;
+    let opponent = validator.new_chain().await;
+    opponent.register_application(application_id).await;
+    let challenger_owner = Owner::from(challenger.public_key());
+    let opponent_owner = Owner::from(opponent.public_key());
+
+    assert!(
+        !execute(
+            &challenger,
+            application_id,
+            Operation::Challenge {
+                opponent_chain: challenger.id(),
+            }
+        )
+        .await,
+        "chains cannot challenge themselves"
+    );
+    let challenge = Operation::Challenge {
+        opponent_chain: opponent.id(),
+    };
+    assert!(execute(&challenger, application_id, challenge).await);
+    opponent.handle_received_messages().await;
+
+    let status_query = format!(
+        "query {{ challenge(challengerChain: \"{}\", challengeId: 0) {{ \
+         challenger opponent status moveLimit gameId seed challengerScore opponentScore winner }} }}",
+        challenger.id()
+    );
+    let received = query(&opponent, application_id, &status_query).await;
+    assert_eq!(received["challenge"]["status"], json!("Pending"));
+    assert_eq!(received["challenge"]["challenger"], json!(challenger_owner));
+    assert_eq!(received["challenge"]["gameId"], json!(null));
+    assert_eq!(
+        received["challenge"]["moveLimit"],
+        json!(CHALLENGE_MOVE_LIMIT)
+    );
+
+    let accept = Operation::AcceptChallenge {
+        challenger_chain: challenger.id(),
+        challenge_id: 0,
+    };
+    assert!(execute(&opponent, application_id, accept.clone()).await);
+    assert!(
+        !execute(&opponent, application_id, accept).await,
+        "challenges are only accepted once"
+    );
+    challenger.handle_received_messages().await;
+
+    // Both games start from the same board, without undos.
+    let mut game_ids = Vec::new();
+    let mut boards = Vec::new();
+    for chain in [&challenger, &opponent] {
+        let response = query(chain, application_id, &status_query).await;
+        let challenge = &response["challenge"];
+        assert_eq!(challenge["status"], json!("Active"));
+        assert_eq!(challenge["opponent"], json!(opponent_owner));
+        let game_id = challenge["gameId"].as_u64().unwrap() as u16;
+        let game = query(
+            chain,
+            application_id,
+            &format!("query {{ game(gameId: {game_id}) {{ board undosLeft }} }}"),
+        )
+        .await;
+        assert_eq!(game["game"]["undosLeft"], json!(0));
+        game_ids.push(game_id);
+        boards.push(game["game"]["board"].clone());
+    }
+    assert_eq!(boards[0], boards[1]);
+
+    // The challenger scores, the opponent gives up right away.
+    let mut score = 0;
+    for direction in [
+        Direction::Left,
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+    ]
+    .into_iter()
+    .cycle()
+    .take(20)
+    {
+        let operation = Operation::MakeMove {
+            game_id: game_ids[0],
+            direction,
+            namespace: None,
+        };
+        assert!(execute(&challenger, application_id, operation).await);
+        let game = query(
+            &challenger,
+            application_id,
+            &format!("query {{ game(gameId: {}) {{ score }} }}", game_ids[0]),
+        )
+        .await;
+        score = game["game"]["score"].as_u64().unwrap();
+        if score > 0 {
+            break;
+        }
+    }
+    assert!(score > 0);
+    for (chain, game_id) in [(&challenger, game_ids[0]), (&opponent, game_ids[1])] {
+        let operation = Operation::EndGame {
+            game_id,
+            namespace: None,
+        };
+        assert!(execute(chain, application_id, operation).await);
+    }
+    let waiting = query(&challenger, application_id, &status_query).await;
+    assert_eq!(waiting["challenge"]["challengerScore"], json!(score));
+    assert_eq!(waiting["challenge"]["status"], json!("Active"));
+
+    challenger.handle_received_messages().await;
+    opponent.handle_received_messages().await;
+    for chain in [&challenger, &opponent] {
+        let response = query(chain, application_id, &status_query).await;
+        let challenge = &response["challenge"];
+        assert_eq!(challenge["status"], json!("Finished"));
+        assert_eq!(challenge["challengerScore"], json!(score));
+        assert_eq!(challenge["opponentScore"], json!(0));
+        assert_eq!(challenge["winner"], json!(challenger_owner));
+    }
+    let finished = query(
+        &opponent,
+        application_id,
+        "query { active: challenges(status: \"Active\") { challengeId } \
+         finished: challenges(status: \"Finished\") { challengeId } }",
+    )
+    .await;
+    assert_eq!(
+        finished,
+        json!({ "active": [], "finished": [{ "challengeId": 0 }] })
+    );
+}
@@ -0,0 +1,79 @@
+//! Integration tests for the chat of matches, kept on the matchmaking chain.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute};
+use game2048::{ChatPayload, Game2048Parameters, Operation, MAX_CHAT_PAYLOAD_LEN};
+use linera_sdk::{
+    base::Owner,
+    serde_json::{json, Value},
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_players_of_a_match_can_chat() {
+    let (validator, matchmaker, application_id) = deploy(Game2048Parameters::default()).await;
+    let mut chains = Vec::new();
+    for _ in 0..3 {
+        let chain = validator.new_chain().await;
+        chain.register_application(application_id).await;
+        chains.push(chain);
+    }
+    let owners = chains
+        .iter()
+        .map(|chain| Owner::from(chain.public_key()))
+        .collect::<Vec<_>>();
+    for chain in &chains[..2] {
+        assert!(execute(chain, application_id, Operation::JoinMatchQueue).await);
+    }
+    matchmaker.handle_received_messages().await;
+
+    // The first player publishes a key, the second encrypts to it; the application only
+    // stores the ciphertext.
+    let key = vec![7; 32];
+    let set_key = Operation::SetChatKey { key: key.clone() };
+    assert!(execute(&chains[0], application_id, set_key).await);
+    let ciphertext = vec![1, 2, 3, 4];
+    let encrypted = Operation::SendChat {
+        match_id: 0,
+        payload: ChatPayload::Encrypted(ciphertext.clone()),
+    };
+    assert!(execute(&chains[1], application_id, encrypted).await);
+    matchmaker.handle_received_messages().await;
+    let reply = Operation::SendChat {
+        match_id: 0,
+        payload: ChatPayload::Text("gg".to_string()),
+    };
+    assert!(execute(&chains[0], application_id, reply).await);
+
+    // Messages from outside the match are dropped by the matchmaking chain, and oversized
+    // ones rejected by the sender's.
+    let intrusion = Operation::SendChat {
+        match_id: 0,
+        payload: ChatPayload::Text("hi".to_string()),
+    };
+    assert!(execute(&chains[2], application_id, intrusion).await);
+    let oversized = Operation::SendChat {
+        match_id: 0,
+        payload: ChatPayload::Encrypted(vec![0; MAX_CHAT_PAYLOAD_LEN + 1]),
+    };
+    assert!(!execute(&chains[1], application_id, oversized).await);
+    let empty_key = Operation::SetChatKey { key: Vec::new() };
+    assert!(!execute(&chains[1], application_id, empty_key).await);
+    matchmaker.handle_received_messages().await;
+
+    let query = format!(
+        "query {{ chat(matchId: 0) {{ sender payload }} chatKey(owner: \"{}\") }}",
+        owners[0]
+    );
+    let response: Value = matchmaker.graphql_query(application_id, &query).await;
+    assert_eq!(response["chatKey"], json!(key));
+    assert_eq!(
+        response["chat"],
+        json!([
+            { "sender": owners[1], "payload": { "Encrypted": ciphertext } },
+            { "sender": owners[0], "payload": { "Text": "gg" } },
+        ])
+    );
+}
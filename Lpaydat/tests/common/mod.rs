@@ -0,0 +1,176 @@
+//! Helpers shared by the integration tests: deploying the application and executing its
+//! operations in blocks.
+
+// Each test file is its own crate and uses only some of the helpers.
+#![allow(dead_code)]
+
+use game2048::{Game2048Abi, Game2048Parameters, Operation, OperationEnvelope};
+use linera_execution::system::Recipient;
+use linera_sdk::{
+    base::{Account, Amount, ApplicationId, ChainId, Owner, Timestamp},
+    serde_json::Value,
+    test::{ActiveChain, TestValidator},
+};
+use linera_token_creation::{Operation as TokenOperation, TokenAbi};
+
+/// Creates the application with `parameters` on a new chain of a new validator, from the
+/// bytecode of the current crate.
+pub async fn deploy(
+    parameters: Game2048Parameters,
+) -> (TestValidator, ActiveChain, ApplicationId<Game2048Abi>) {
+    let (validator, bytecode_id) =
+        TestValidator::with_current_bytecode::<Game2048Abi, Game2048Parameters, u16>().await;
+    let mut chain = validator.new_chain().await;
+    let application_id = chain
+        .create_application(bytecode_id, parameters, 1, vec![])
+        .await;
+    (validator, chain, application_id)
+}
+
+/// Creates the token application on a new chain of a new validator, with a token `Coin` of
+/// `supply` base units held by the owner of that chain. Then creates this application there,
+/// requiring the token application, with the parameters `parameters` returns for it.
+pub async fn deploy_with_token(
+    supply: u128,
+    parameters: impl FnOnce(ApplicationId<TokenAbi>, ChainId) -> Game2048Parameters,
+) -> (
+    TestValidator,
+    ActiveChain,
+    ApplicationId<Game2048Abi>,
+    ApplicationId<TokenAbi>,
+) {
+    let (validator, bytecode_id) =
+        TestValidator::with_current_bytecode::<Game2048Abi, Game2048Parameters, u16>().await;
+    let mut chain = validator.new_chain().await;
+    let token_bytecode_id = chain
+        .publish_bytecodes_in::<TokenAbi, (), ()>("../winrhcp/linera_token_creation")
+        .await;
+    let token_id = chain
+        .create_application(token_bytecode_id, (), (), vec![])
+        .await;
+    let create_token = TokenOperation::CreateToken {
+        name: "Coin".to_string(),
+        symbol: "COIN".to_string(),
+        total_supply: supply,
+        decimals: 0,
+        metadata_uri: None,
+    };
+    chain
+        .add_block(|block| {
+            block.with_operation(token_id, create_token);
+        })
+        .await;
+    let parameters = parameters(token_id, chain.id());
+    let application_id = chain
+        .create_application(bytecode_id, parameters, 1, vec![token_id.forget_abi()])
+        .await;
+    (validator, chain, application_id, token_id)
+}
+
+/// Returns the balance in `Coin` of the owner of `chain`, on that chain.
+pub async fn token_balance(chain: &ActiveChain, token_id: ApplicationId<TokenAbi>) -> Value {
+    let owner = Owner::from(chain.public_key());
+    let query = format!("query {{ balance(token: \"Coin\", owner: \"{owner}\") }}");
+    let response = chain.graphql_query(token_id, query).await;
+    response["balance"].clone()
+}
+
+/// Sends `amount` base units of `Coin` from the owner of `token_chain` to the owner of
+/// `chain`, which receives them.
+pub async fn send_tokens(
+    token_chain: &ActiveChain,
+    token_id: ApplicationId<TokenAbi>,
+    chain: &ActiveChain,
+    amount: u128,
+) {
+    let transfer = TokenOperation::CrossChainTransfer {
+        symbol: "COIN".to_string(),
+        amount,
+        target_account: Account {
+            chain_id: chain.id(),
+            owner: Some(Owner::from(chain.public_key())),
+        },
+    };
+    token_chain
+        .add_block(|block| {
+            block.with_operation(token_id, transfer);
+        })
+        .await;
+    chain.handle_received_messages().await;
+}
+
+/// Executes `operation` in a new block of `chain`, returning whether the block was accepted.
+pub async fn execute(
+    chain: &ActiveChain,
+    application_id: ApplicationId<Game2048Abi>,
+    operation: Operation,
+) -> bool {
+    execute_all_at_micros(chain, application_id, 0, &[operation]).await
+}
+
+/// Executes `operation` in a new block of `chain` made `seconds` after the epoch.
+pub async fn execute_at(
+    chain: &ActiveChain,
+    application_id: ApplicationId<Game2048Abi>,
+    seconds: u64,
+    operation: Operation,
+) -> bool {
+    execute_all_at_micros(chain, application_id, seconds * 1_000_000, &[operation]).await
+}
+
+/// Executes `operation` in a new block of `chain` made `micros` microseconds after the epoch.
+pub async fn execute_at_micros(
+    chain: &ActiveChain,
+    application_id: ApplicationId<Game2048Abi>,
+    micros: u64,
+    operation: Operation,
+) -> bool {
+    execute_all_at_micros(chain, application_id, micros, &[operation]).await
+}
+
+/// Executes `operations` in one new block of `chain` made `micros` microseconds after the
+/// epoch, returning whether the block was accepted.
+pub async fn execute_all_at_micros(
+    chain: &ActiveChain,
+    application_id: ApplicationId<Game2048Abi>,
+    micros: u64,
+    operations: &[Operation],
+) -> bool {
+    chain
+        .try_add_block(|block| {
+            block.with_timestamp(Timestamp::from(micros));
+            for operation in operations {
+                block.with_operation(application_id, OperationEnvelope::new(operation));
+            }
+        })
+        .await
+        .is_ok()
+}
+
+/// Runs the GraphQL `query` against the service of the application on `chain`.
+pub async fn query(
+    chain: &ActiveChain,
+    application_id: ApplicationId<Game2048Abi>,
+    query: &str,
+) -> Value {
+    chain.graphql_query(application_id, query).await
+}
+
+/// Transfers `tokens` native tokens from the admin chain to `owner` on `chain`.
+pub async fn fund(validator: &TestValidator, chain: &ActiveChain, owner: Owner, tokens: u128) {
+    let admin_chain = validator.get_chain(&ChainId::root(0));
+    let account = Account {
+        chain_id: chain.id(),
+        owner: Some(owner),
+    };
+    admin_chain
+        .add_block(|block| {
+            block.with_native_token_transfer(
+                None,
+                Recipient::Account(account),
+                Amount::from_tokens(tokens),
+            );
+        })
+        .await;
+    chain.handle_received_messages().await;
+}
@@ -0,0 +1,104 @@
+//! Integration tests for the daily challenge, where every player gets the same board each day.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute_at_micros};
+use game2048::{daily_seed, Direction, Game2048Parameters, Operation, DAY_MICROS};
+use linera_sdk::{
+    base::{KeyPair, Owner},
+    serde_json::{json, Value},
+};
+
+/// The day of 2024-10-29.
+const DAY: u32 = 20_025;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn players_compete_on_the_same_board_each_day() {
+    let (_validator, mut chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let morning = u64::from(DAY) * DAY_MICROS + 8 * 3_600_000_000;
+    let evening = morning + 12 * 3_600_000_000;
+    let seed = daily_seed(DAY);
+    let end_game = |game_id| Operation::EndGame {
+        game_id,
+        namespace: None,
+    };
+
+    // The first player gets the game id of the seed, the second one the next free id.
+    let first = Owner::from(chain.public_key());
+    assert!(execute_at_micros(&chain, application_id, morning, Operation::NewDailyGame).await);
+    assert!(
+        !execute_at_micros(&chain, application_id, evening, Operation::NewDailyGame).await,
+        "players enter each daily challenge once"
+    );
+    let first_key_pair = chain.key_pair().copy();
+    chain.set_key_pair(KeyPair::generate());
+    let second = Owner::from(chain.public_key());
+    assert!(execute_at_micros(&chain, application_id, morning, Operation::NewDailyGame).await);
+    let second_game = seed.wrapping_add(1);
+
+    let query = format!(
+        "query {{ first: game(gameId: {seed}) {{ seed boardHex undosLeft dailyChallenge }} \
+         second: game(gameId: {second_game}) {{ seed boardHex undosLeft dailyChallenge }} \
+         today: dailyLeaderboard(date: \"2024-10-29\") {{ player }} }}"
+    );
+    let response: Value = chain.graphql_query(application_id, &query).await;
+    assert_eq!(response["first"], response["second"]);
+    assert_eq!(response["first"]["seed"], json!(seed));
+    assert_eq!(response["first"]["undosLeft"], json!(0));
+    assert_eq!(response["first"]["dailyChallenge"], json!("2024-10-29"));
+    assert_eq!(
+        response["today"],
+        json!([]),
+        "only finished games are ranked"
+    );
+
+    let make_move = Operation::MakeMove {
+        game_id: second_game,
+        direction: Direction::Left,
+        namespace: None,
+    };
+    assert!(execute_at_micros(&chain, application_id, evening, make_move).await);
+    assert!(execute_at_micros(&chain, application_id, evening, end_game(second_game)).await);
+    chain.set_key_pair(first_key_pair);
+    assert!(execute_at_micros(&chain, application_id, evening + 1, end_game(seed)).await);
+
+    let query = format!(
+        "query {{ today: dailyLeaderboard(date: \"2024-10-29\") {{ player gameId score }} \
+         tomorrow: dailyLeaderboard(date: \"2024-10-30\") {{ player }} \
+         malformed: dailyLeaderboard(date: \"2024-13-01\") {{ player }} \
+         game(gameId: {second_game}) {{ score }} }}"
+    );
+    let response: Value = chain.graphql_query(application_id, &query).await;
+    let second_score = response["game"]["score"].as_u64().unwrap();
+    // Equal scores would be ordered by when their game ended, the second player's first.
+    assert_eq!(
+        response["today"],
+        json!([
+            { "player": second, "gameId": second_game, "score": second_score },
+            { "player": first, "gameId": seed, "score": 0 },
+        ])
+    );
+    assert_eq!(response["tomorrow"], json!([]));
+    assert_eq!(response["malformed"], json!(null));
+
+    // The next day brings a new board.
+    let next_morning = morning + DAY_MICROS;
+    assert!(
+        execute_at_micros(
+            &chain,
+            application_id,
+            next_morning,
+            Operation::NewDailyGame
+        )
+        .await
+    );
+    let next_seed = daily_seed(DAY + 1);
+    let query = format!("query {{ game(gameId: {next_seed}) {{ owner dailyChallenge }} }}");
+    let response: Value = chain.graphql_query(application_id, &query).await;
+    assert_eq!(
+        response,
+        json!({ "game": { "owner": first, "dailyChallenge": "2024-10-30" } })
+    );
+}
@@ -0,0 +1,373 @@
+//! Integration tests for the disputes of match results, resolved by replaying the games of
+//! both players on the matchmaking chain, with bonds held in escrows of a token application.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy_with_token, execute, execute_at_micros, send_tokens, token_balance};
+use game2048::{
+    replay_board, BondToken, Direction, Game, Game2048Parameters, Operation, OperationEnvelope,
+    Replay, ReplayMove,
+};
+use linera_chain::data_types::Certificate;
+use linera_sdk::{
+    base::{Amount, ApplicationId, Owner, Timestamp},
+    serde_json::{json, Value},
+    test::ActiveChain,
+};
+use linera_token_creation::TokenAbi;
+
+/// Base units of the bond token in `Amount::ONE`, the bond of the tests.
+const BOND: u128 = 1_000_000_000_000_000_000;
+
+/// A match between two players who both played four moves of its game, and hold two bonds of
+/// the bond token, created on the matchmaking chain.
+struct PlayedMatch {
+    matchmaker: ActiveChain,
+    application_id: ApplicationId<game2048::Game2048Abi>,
+    token_id: ApplicationId<TokenAbi>,
+    players: Vec<ActiveChain>,
+    owners: Vec<Owner>,
+    /// The replay of the game of each player, and its score.
+    replays: Vec<(Vec<u8>, u64)>,
+}
+
+async fn play_match(parameters: Game2048Parameters) -> PlayedMatch {
+    let (validator, matchmaker, application_id, token_id) =
+        deploy_with_token(1_000 * BOND, |token_id, _| Game2048Parameters {
+            bond_token: Some(BondToken {
+                token_application: token_id.forget_abi(),
+                token: "Coin".to_string(),
+            }),
+            ..parameters
+        })
+        .await;
+    let mut players = Vec::new();
+    for _ in 0..2 {
+        let chain = validator.new_chain().await;
+        chain.register_application(application_id).await;
+        players.push(chain);
+    }
+    let owners = players
+        .iter()
+        .map(|chain| Owner::from(chain.public_key()))
+        .collect::<Vec<_>>();
+
+    for chain in &players {
+        assert!(execute(chain, application_id, Operation::JoinMatchQueue).await);
+    }
+    matchmaker.handle_received_messages().await;
+
+    // Both players play their match game, then fetch its replay.
+    let mut replays = Vec::new();
+    for (chain, owner) in players.iter().zip(&owners) {
+        chain.handle_received_messages().await;
+        let notice: Value = chain
+            .graphql_query(
+                application_id,
+                &format!("query {{ matchNotice(owner: \"{owner}\") {{ gameId }} }}"),
+            )
+            .await;
+        let game_id = notice["matchNotice"]["gameId"].as_u64().unwrap() as u16;
+        for direction in [
+            Direction::Left,
+            Direction::Up,
+            Direction::Right,
+            Direction::Down,
+        ] {
+            let make_move = Operation::MakeMove {
+                game_id,
+                direction,
+                namespace: None,
+            };
+            assert!(execute(chain, application_id, make_move).await);
+        }
+        let query =
+            format!("query {{ replay(gameId: {game_id}) game(gameId: {game_id}) {{ score }} }}");
+        let response: Value = chain.graphql_query(application_id, &query).await;
+        let replay = response["replay"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|byte| byte.as_u64().unwrap() as u8)
+            .collect::<Vec<_>>();
+        replays.push((replay, response["game"]["score"].as_u64().unwrap()));
+    }
+    PlayedMatch {
+        matchmaker,
+        application_id,
+        token_id,
+        players,
+        owners,
+        replays,
+    }
+}
+
+/// Extends the encoded `replay` with moves that spawn tiles of the forger's choosing until
+/// its score beats `target`, returning the forged replay and its score.
+fn forge(replay: &[u8], target: u64) -> (Vec<u8>, u64) {
+    let mut replay = Replay::decode(replay).unwrap();
+    let mut board = replay_board(&replay, replay.moves.len()).unwrap();
+    while Game::score(board) <= target {
+        let direction = [
+            Direction::Left,
+            Direction::Up,
+            Direction::Right,
+            Direction::Down,
+        ]
+        .into_iter()
+        .find(|direction| Game::is_useful_move(board, direction.clone()))
+        .expect("the forged game is lost");
+        let moved = Game::move_board(board, direction.clone());
+        let cell = (0..16)
+            .find(|cell| (moved >> (4 * cell)) & 0xf == 0)
+            .unwrap();
+        let spawned_tile = 1 << (4 * cell);
+        replay.moves.push(ReplayMove {
+            direction,
+            spawned_tile,
+        });
+        board = moved | spawned_tile;
+    }
+    (replay.encode(), Game::score(board))
+}
+
+/// Returns the balance of `count` bonds, as the token service answers it.
+fn coins(count: u128) -> Value {
+    json!((count * BOND).to_string())
+}
+
+/// Has the chains of the players settle the bond escrows the matchmaking chain paid out in
+/// `payout`, then receive the bonds paid across chains, in blocks made `micros` microseconds
+/// after the epoch.
+async fn settle_bonds(players: &[ActiveChain], payout: &Certificate, micros: u64) {
+    let mut settlements = Vec::new();
+    for chain in players {
+        let settlement = chain
+            .add_block(|block| {
+                block
+                    .with_timestamp(Timestamp::from(micros))
+                    .with_messages_from(payout);
+            })
+            .await;
+        settlements.push(settlement);
+    }
+    for chain in players {
+        chain
+            .add_block(|block| {
+                block.with_timestamp(Timestamp::from(micros));
+                for settlement in &settlements {
+                    block.with_messages_from(settlement);
+                }
+            })
+            .await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn disputes_are_resolved_by_replaying_both_games() {
+    let parameters = Game2048Parameters {
+        dispute_bond: Amount::from_tokens(1),
+        ..Game2048Parameters::default()
+    };
+    let PlayedMatch {
+        matchmaker,
+        application_id,
+        token_id,
+        players,
+        owners,
+        replays,
+    } = play_match(parameters).await;
+
+    let (honest_replay, honest_score) = replays[0].clone();
+    let (liar_replay, liar_score) = replays[1].clone();
+    let honest_dispute = Operation::DisputeMatch {
+        match_id: 0,
+        replay: honest_replay,
+        claimed_score: honest_score,
+    };
+    // The second player claims more than their game scored.
+    let inflated = honest_score + liar_score + 4;
+    let liar_dispute = Operation::DisputeMatch {
+        match_id: 0,
+        replay: liar_replay,
+        claimed_score: inflated,
+    };
+    assert!(
+        !execute(&players[0], application_id, honest_dispute.clone()).await,
+        "the player cannot post the bond"
+    );
+    for chain in &players {
+        send_tokens(&matchmaker, token_id, chain, 2 * BOND).await;
+    }
+    assert!(execute(&players[0], application_id, honest_dispute).await);
+    matchmaker.handle_received_messages().await;
+    let liar_block = players[1]
+        .add_block(|block| {
+            block.with_operation(application_id, OperationEnvelope::new(&liar_dispute));
+        })
+        .await;
+    assert_eq!(token_balance(&players[1], token_id).await, coins(1));
+    let payout = matchmaker
+        .add_block(|block| {
+            block.with_messages_from(&liar_block);
+        })
+        .await;
+
+    let query = "query { dispute(matchId: 0) { \
+                 bond \
+                 submissions { player claimedScore verifiedScore } \
+                 resolution { winner slashed forfeited isPaid } } }";
+    let response: Value = matchmaker.graphql_query(application_id, query).await;
+    assert_eq!(
+        response,
+        json!({
+            "dispute": {
+                "bond": "1.",
+                "submissions": [
+                    {
+                        "player": owners[0],
+                        "claimedScore": honest_score,
+                        "verifiedScore": honest_score,
+                    },
+                    {
+                        "player": owners[1],
+                        "claimedScore": inflated,
+                        "verifiedScore": liar_score,
+                    },
+                ],
+                "resolution": {
+                    "winner": owners[0],
+                    "slashed": [owners[1]],
+                    "forfeited": "1.",
+                    "isPaid": true,
+                },
+            },
+        })
+    );
+
+    let resolve = |match_id| Operation::ResolveDispute { match_id };
+    assert!(
+        !execute(&matchmaker, application_id, resolve(0)).await,
+        "the dispute is already resolved"
+    );
+    assert!(
+        !execute(&matchmaker, application_id, resolve(1)).await,
+        "the match is not disputed"
+    );
+
+    // The chains holding the bonds refund the honest player and pay them the liar's bond.
+    settle_bonds(&players, &payout, 0).await;
+    assert_eq!(token_balance(&players[0], token_id).await, coins(3));
+    assert_eq!(token_balance(&players[1], token_id).await, coins(1));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn forged_replays_are_caught_within_the_challenge_window() {
+    let window = 3_600_000_000;
+    let parameters = Game2048Parameters {
+        dispute_bond: Amount::from_tokens(1),
+        dispute_challenge_bond: Amount::from_tokens(1),
+        dispute_challenge_window_micros: window,
+        ..Game2048Parameters::default()
+    };
+    let PlayedMatch {
+        matchmaker,
+        application_id,
+        token_id,
+        players,
+        owners,
+        replays,
+    } = play_match(parameters).await;
+    for chain in &players {
+        send_tokens(&matchmaker, token_id, chain, 2 * BOND).await;
+    }
+
+    // The second player submits a replay that verifies, but is not the game they played.
+    let (honest_replay, honest_score) = replays[0].clone();
+    let (forged_replay, forged_score) = forge(&replays[1].0, honest_score);
+    let honest_dispute = Operation::DisputeMatch {
+        match_id: 0,
+        replay: honest_replay,
+        claimed_score: honest_score,
+    };
+    let forged_dispute = Operation::DisputeMatch {
+        match_id: 0,
+        replay: forged_replay,
+        claimed_score: forged_score,
+    };
+    assert!(execute(&players[0], application_id, honest_dispute).await);
+    assert!(execute(&players[1], application_id, forged_dispute).await);
+    matchmaker.handle_received_messages().await;
+
+    let query = "query { dispute(matchId: 0) { \
+                 challenges { challenger player bond status } \
+                 resolution { winner slashed forfeited isPaid } } }";
+    let response: Value = matchmaker.graphql_query(application_id, query).await;
+    assert_eq!(
+        response["dispute"]["resolution"],
+        json!({
+            "winner": owners[1],
+            "slashed": [],
+            "forfeited": "0.",
+            "isPaid": false,
+        })
+    );
+
+    // The first player challenges the replay, and the chain of the second player finds it
+    // does not match their game.
+    let challenge = Operation::ChallengeDisputeResult {
+        match_id: 0,
+        player: owners[1],
+    };
+    assert!(execute(&players[0], application_id, challenge).await);
+    matchmaker.handle_received_messages().await;
+    players[1].handle_received_messages().await;
+    matchmaker.handle_received_messages().await;
+
+    let response: Value = matchmaker.graphql_query(application_id, query).await;
+    assert_eq!(
+        response,
+        json!({
+            "dispute": {
+                "challenges": [{
+                    "challenger": owners[0],
+                    "player": owners[1],
+                    "bond": "1.",
+                    "status": "Upheld",
+                }],
+                "resolution": {
+                    "winner": owners[0],
+                    "slashed": [owners[1]],
+                    "forfeited": "1.",
+                    "isPaid": false,
+                },
+            },
+        })
+    );
+
+    let finalize = Operation::FinalizeDispute { match_id: 0 };
+    assert!(
+        !execute(&matchmaker, application_id, finalize.clone()).await,
+        "the resolution can still be challenged"
+    );
+    let payout = matchmaker
+        .add_block(|block| {
+            block
+                .with_timestamp(Timestamp::from(window))
+                .with_operation(application_id, OperationEnvelope::new(&finalize));
+        })
+        .await;
+    let response: Value = matchmaker.graphql_query(application_id, query).await;
+    assert_eq!(response["dispute"]["resolution"]["isPaid"], true);
+    assert!(
+        !execute_at_micros(&matchmaker, application_id, window, finalize).await,
+        "the bonds are already paid out"
+    );
+
+    // The challenger gets back both their bonds, and the bond of the forger.
+    settle_bonds(&players, &payout, window).await;
+    assert_eq!(token_balance(&players[0], token_id).await, coins(3));
+    assert_eq!(token_balance(&players[1], token_id).await, coins(1));
+}
@@ -0,0 +1,105 @@
+//! Equivalence of the precomputed move tables `Game` plays with and the reference tables
+//! built by `Moves::new`, which `scripts/precompute_moves.rs` generates them from, and of
+//! both with the row-merge algorithm they cache.
+
+use game2048::{gen_range, Direction, Game, MoveTableMismatch, Moves, Rng, ROW_MASK};
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Left,
+    Direction::Down,
+    Direction::Right,
+];
+
+/// Moves `board` with the reference tables, the way `Game` applies its own.
+fn reference_move(moves: &Moves, board: u64, direction: &Direction) -> u64 {
+    let transposed = Game::transpose(board);
+    let (table, rows, shift) = match direction {
+        Direction::Left => (&moves.left, board, 16),
+        Direction::Right => (&moves.right, board, 16),
+        Direction::Up => (&moves.up, transposed, 4),
+        Direction::Down => (&moves.down, transposed, 4),
+    };
+    (0..4).fold(board, |result, row| {
+        result ^ (table[((rows >> (16 * row)) & ROW_MASK) as usize] << (shift * row))
+    })
+}
+
+fn reference_score(moves: &Moves, board: u64) -> u64 {
+    (0..4)
+        .map(|row| moves.scores[((board >> (16 * row)) & ROW_MASK) as usize])
+        .sum()
+}
+
+#[test]
+fn row_tables_match_the_reference() {
+    let moves = Moves::new();
+    for row in 0..=ROW_MASK {
+        assert_eq!(Game::move_left(row), row ^ moves.left[row as usize]);
+        assert_eq!(Game::move_right(row), row ^ moves.right[row as usize]);
+        assert_eq!(Game::score(row), moves.scores[row as usize]);
+    }
+}
+
+#[test]
+fn full_game_replays_match_the_reference() {
+    let moves = Moves::new();
+    for seed in [0, 1, 7, 2048, u16::MAX] {
+        let mut board = Game::new(seed).board;
+        let mut reference = board;
+        for height in 0..5_000_u32 {
+            if Game::is_ended(board) {
+                break;
+            }
+            let move_seed = gen_range(&height.to_string(), 0, u16::MAX);
+            // Vary the directions so every table is exercised, stuck rows included.
+            let direction = &DIRECTIONS[usize::from(move_seed % 4)];
+
+            let mut game = Game {
+                board,
+                seed: move_seed,
+            };
+            board = game.execute(direction.clone());
+            let moved = reference_move(&moves, reference, direction);
+            if moved != reference {
+                reference = moved | Game::spawn_tile(moved, move_seed);
+            }
+            assert_eq!(board, reference, "seed {seed}, height {height}");
+            assert_eq!(Game::score(board), reference_score(&moves, reference));
+        }
+    }
+}
+
+#[test]
+fn reference_tables_match_the_row_merge_algorithm() {
+    let moves = Moves::new();
+    for row in 0..=u16::MAX {
+        assert_eq!(moves.check_row(row), Ok(()));
+    }
+}
+
+#[test]
+fn sampled_self_check_passes_on_the_precomputed_tables() {
+    for seed in [0, 1, 7, 2048] {
+        assert_eq!(Game::check_move_tables(&mut Rng::new(seed), 4096), Ok(()));
+    }
+}
+
+#[test]
+fn self_check_reports_corrupted_rows() {
+    let mut moves = Moves::new();
+    let row = 0x2211;
+    let expected = moves.up[row];
+    moves.up[row] ^= 0x10;
+    let mismatch = moves.check_row(row as u16).unwrap_err();
+    assert_eq!(
+        mismatch,
+        MoveTableMismatch {
+            table: "up",
+            row: 0x2211,
+            expected,
+            found: expected ^ 0x10,
+        }
+    );
+    assert!(mismatch.to_string().contains("moves_data.rs"));
+}
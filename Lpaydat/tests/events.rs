@@ -0,0 +1,190 @@
+//! Integration tests for the game events emitted to the event stream of a chain.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::deploy;
+use game2048::{Direction, Game2048Parameters, GameEvent, GameMode, Operation, OperationEnvelope};
+use linera_sdk::{
+    base::{ApplicationId, GenericApplicationId, Owner},
+    bcs,
+    serde_json::{self, json, Value},
+    test::ActiveChain,
+};
+
+/// Executes `operation` and returns the game events its block emitted.
+async fn execute(
+    chain: &ActiveChain,
+    application_id: ApplicationId<game2048::Game2048Abi>,
+    operation: Operation,
+) -> Vec<GameEvent> {
+    let certificate = chain
+        .add_block(|block| {
+            block.with_operation(application_id, OperationEnvelope::new(&operation));
+        })
+        .await;
+    let executed_block = certificate.value().executed_block().unwrap();
+    executed_block
+        .outcome
+        .events
+        .iter()
+        .flatten()
+        .map(|event| {
+            assert_eq!(
+                event.stream_id.application_id,
+                GenericApplicationId::User(application_id.forget_abi())
+            );
+            assert_eq!(event.stream_id.stream_name, GameEvent::stream_name());
+            let game_event = bcs::from_bytes::<GameEvent>(&event.value).unwrap();
+            assert_eq!(event.key, game_event.key());
+            game_event
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn games_emit_their_activity() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let owner = Owner::from(chain.public_key());
+    let new_game = Operation::NewGame {
+        seed: 7,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+
+    let events = execute(&chain, application_id, new_game).await;
+    let [GameEvent::GameCreated {
+        game_id: 7,
+        owner: created_by,
+        seed: 7,
+        mode: GameMode::Classic,
+        board,
+    }] = events[..]
+    else {
+        panic!("unexpected events {events:?}");
+    };
+    assert_eq!(created_by, Some(owner));
+    assert_ne!(board, 0);
+
+    let mut move_count = 0;
+    for direction in [Direction::Left, Direction::Right] {
+        let make_move = Operation::MakeMove {
+            game_id: 7,
+            direction: direction.clone(),
+            namespace: None,
+        };
+        let events = execute(&chain, application_id, make_move).await;
+        // Useless moves are dropped without events.
+        if let [GameEvent::MoveMade {
+            game_id: 7,
+            move_count: count,
+            direction: ref played,
+            ..
+        }] = events[..]
+        {
+            move_count += 1;
+            assert_eq!(count, move_count);
+            assert_eq!(*played, direction);
+        } else {
+            assert_eq!(events, []);
+        }
+    }
+
+    let end_game = Operation::EndGame {
+        game_id: 7,
+        namespace: None,
+    };
+    let events = execute(&chain, application_id, end_game).await;
+    let response = chain
+        .graphql_query(
+            application_id,
+            "query { game(gameId: 7) { score } metrics { eventsEmitted } }",
+        )
+        .await;
+    let score = response["game"]["score"].as_u64().unwrap();
+    assert_eq!(
+        events,
+        [GameEvent::GameEnded {
+            game_id: 7,
+            owner: Some(owner),
+            score,
+            move_count,
+        }]
+    );
+    assert_eq!(
+        response["metrics"]["eventsEmitted"],
+        json!(u64::from(move_count) + 2)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn clients_resume_from_their_cursor() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let new_game = |seed| Operation::NewGame {
+        seed,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    let page = |after: Option<&str>, limit| {
+        let after = after.map_or("null".to_string(), |after| format!("\"{after}\""));
+        format!(
+            "query {{ events(after: {after}, limit: {limit}) {{ \
+             events {{ cursor event }} cursor hasMore }} }}"
+        )
+    };
+
+    let mut emitted = execute(&chain, application_id, new_game(3)).await;
+    emitted.extend(execute(&chain, application_id, new_game(5)).await);
+    assert_eq!(emitted.len(), 2);
+
+    let response: Value = chain.graphql_query(application_id, &page(None, 1)).await;
+    let first = &response["events"];
+    assert_eq!(first["hasMore"], json!(true));
+    assert_eq!(
+        first["events"][0]["event"],
+        serde_json::to_value(&emitted[0]).unwrap()
+    );
+    assert_eq!(first["events"][0]["cursor"], first["cursor"]);
+
+    // Resuming from a page lists the events after it, then nothing until new events come.
+    let cursor = first["cursor"].as_str().unwrap();
+    let response: Value = chain
+        .graphql_query(application_id, &page(Some(cursor), 10))
+        .await;
+    let second = &response["events"];
+    assert_eq!(second["hasMore"], json!(false));
+    let event = serde_json::to_value(&emitted[1]).unwrap();
+    assert_eq!(
+        second["events"],
+        json!([{ "cursor": second["cursor"], "event": event }])
+    );
+    let cursor = second["cursor"].as_str().unwrap();
+    let response: Value = chain
+        .graphql_query(application_id, &page(Some(cursor), 10))
+        .await;
+    assert_eq!(
+        response["events"],
+        json!({ "events": [], "cursor": cursor, "hasMore": false })
+    );
+
+    let missed = execute(&chain, application_id, new_game(7)).await;
+    let response: Value = chain
+        .graphql_query(application_id, &page(Some(cursor), 10))
+        .await;
+    let events = response["events"]["events"].as_array().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        events[0]["event"],
+        serde_json::to_value(&missed[0]).unwrap()
+    );
+
+    let response: Value = chain
+        .graphql_query(application_id, &page(Some("not a cursor"), 10))
+        .await;
+    assert_eq!(response["events"], json!(null));
+}
@@ -0,0 +1,147 @@
+//! Integration tests for the subsystems a deployment enables in its application parameters,
+//! and for the mutations describing them.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute};
+use std::collections::BTreeSet;
+
+use async_graphql::{
+    registry::{MetaType, Registry},
+    OutputType,
+};
+use game2048::{
+    FeatureFlags, FeeSettings, Game2048Parameters, GameMode, Operation, CURRENT_OPERATION_VERSION,
+};
+use linera_sdk::{
+    base::ChainId,
+    graphql::GraphQLMutationRoot,
+    serde_json::{json, Value},
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn disabled_subsystems_reject_their_operations() {
+    let parameters = Game2048Parameters {
+        features: FeatureFlags::NONE.with(FeatureFlags::MATCHMAKING),
+        ..Game2048Parameters::default()
+    };
+    let (_validator, chain, application_id) = deploy(parameters).await;
+
+    let response = chain
+        .graphql_query(application_id, "query { features }")
+        .await;
+    assert_eq!(response, json!({ "features": ["Matchmaking"] }));
+
+    // Games are always playable.
+    let new_game = Operation::NewGame {
+        seed: 5,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    assert!(execute(&chain, application_id, new_game).await);
+    assert!(execute(&chain, application_id, Operation::JoinMatchQueue).await);
+
+    let create_community = Operation::CreateCommunity {
+        community_id: "club".to_string(),
+        name: "Club".to_string(),
+        admins: vec![],
+        default_mode: GameMode::Classic,
+        fees: FeeSettings::default(),
+    };
+    assert!(!execute(&chain, application_id, create_community).await);
+    let challenge = Operation::Challenge {
+        opponent_chain: ChainId::root(0),
+    };
+    assert!(!execute(&chain, application_id, challenge).await);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn abi_info_describes_the_operations_of_the_deployment() {
+    let parameters = Game2048Parameters {
+        features: FeatureFlags::NONE.with(FeatureFlags::MATCHMAKING),
+        ..Game2048Parameters::default()
+    };
+    let (_validator, chain, application_id) = deploy(parameters).await;
+
+    let query = "query { abiInfo { \
+                 operationVersion features \
+                 operations { name mutation feature enabled arguments { name typeName } } } }";
+    let response: Value = chain.graphql_query(application_id, query).await;
+    let abi_info = &response["abiInfo"];
+    assert_eq!(
+        abi_info["operationVersion"],
+        json!(CURRENT_OPERATION_VERSION)
+    );
+    assert_eq!(abi_info["features"], json!(["Matchmaking"]));
+    let operation = |name: &str| {
+        abi_info["operations"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|operation| operation["name"] == name)
+            .cloned()
+    };
+
+    assert_eq!(
+        operation("MakeMove"),
+        Some(json!({
+            "name": "MakeMove",
+            "mutation": "makeMove",
+            "feature": null,
+            "enabled": true,
+            "arguments": [
+                { "name": "gameId", "typeName": "Int!" },
+                { "name": "direction", "typeName": "Direction!" },
+                { "name": "namespace", "typeName": "String" },
+            ],
+        }))
+    );
+    assert_eq!(
+        operation("JoinMatchQueue"),
+        Some(json!({
+            "name": "JoinMatchQueue",
+            "mutation": "joinMatchQueue",
+            "feature": "Matchmaking",
+            "enabled": true,
+            "arguments": [],
+        }))
+    );
+    let sponsor_games = operation("SponsorGames").unwrap();
+    assert_eq!(sponsor_games["feature"], "Communities");
+    assert_eq!(sponsor_games["enabled"], false);
+    assert!(sponsor_games["arguments"]
+        .as_array()
+        .unwrap()
+        .contains(&json!({ "name": "players", "typeName": "[Owner!]!" })));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn every_operation_has_a_mutation() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+
+    let query = "query { abiInfo { operations { mutation } } }";
+    let response: Value = chain.graphql_query(application_id, query).await;
+    let mutations = response["abiInfo"]["operations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|operation| operation["mutation"].as_str().unwrap().to_string())
+        .collect::<BTreeSet<_>>();
+    assert_eq!(mutations, derived_mutations());
+}
+
+/// Returns the names of the mutations `Operation` derives, one per variant.
+fn derived_mutations() -> BTreeSet<String> {
+    type DerivedRoot = <Operation as GraphQLMutationRoot>::MutationRoot;
+    let mut registry = Registry::default();
+    <DerivedRoot as OutputType>::create_type_info(&mut registry);
+    let root_type = <DerivedRoot as OutputType>::type_name();
+    let Some(MetaType::Object { fields, .. }) = registry.types.get(&*root_type) else {
+        unreachable!("mutations are resolved by an object");
+    };
+    fields.keys().cloned().collect()
+}
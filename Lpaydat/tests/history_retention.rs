@@ -0,0 +1,97 @@
+//! Integration tests for the history retention of game modes, which bounds the move logs of
+//! their games.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute};
+use game2048::{
+    Direction, Game2048Parameters, GameMode, HistoryRetention, ModeSettings, Operation,
+};
+use linera_sdk::{
+    base::KeyPair,
+    serde_json::{json, Value},
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn modes_keep_the_turns_their_retention_keeps() {
+    let (_validator, mut chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let configure = Operation::ConfigureMode {
+        mode: GameMode::Classic,
+        settings: ModeSettings {
+            history_retention: HistoryRetention::LastTurns(2),
+        },
+    };
+    let new_game = |seed| Operation::NewGame {
+        seed,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    let play = |game_id, direction| Operation::MakeMove {
+        game_id,
+        direction,
+        namespace: None,
+    };
+
+    // Games keep their full history by default, including the ones created before the
+    // retention of their mode changes.
+    assert!(execute(&chain, application_id, new_game(3)).await);
+    assert!(execute(&chain, application_id, configure.clone()).await);
+    assert!(execute(&chain, application_id, new_game(5)).await);
+    for direction in [Direction::Left, Direction::Up, Direction::Right] {
+        assert!(execute(&chain, application_id, play(3, direction.clone())).await);
+        assert!(execute(&chain, application_id, play(5, direction)).await);
+    }
+
+    let query = "query { modeSettings(mode: \"Classic\") { historyRetention } \
+                 full: game(gameId: 3) { moveCount historyRetention } \
+                 bounded: game(gameId: 5) { moveCount historyRetention } \
+                 fullMoves: moves(gameId: 3) { board } \
+                 boundedMoves: moves(gameId: 5) { board } \
+                 fullReplay: replay(gameId: 3) \
+                 boundedReplay: replay(gameId: 5) \
+                 metrics { keptTurns } }";
+    let response: Value = chain.graphql_query(application_id, query).await;
+    assert_eq!(
+        response["modeSettings"],
+        json!({ "historyRetention": { "LastTurns": 2 } })
+    );
+    assert_eq!(
+        response["full"]["historyRetention"],
+        json!({ "Checkpointed": 1 })
+    );
+    assert_eq!(
+        response["bounded"]["historyRetention"],
+        json!({ "LastTurns": 2 })
+    );
+    let full_turns = response["full"]["moveCount"].as_u64().unwrap() + 1;
+    assert!(response["bounded"]["moveCount"].as_u64().unwrap() >= 1);
+    let full_moves = response["fullMoves"].as_array().unwrap();
+    let bounded_moves = response["boundedMoves"].as_array().unwrap();
+    assert_eq!(full_moves.len() as u64, full_turns);
+    assert_eq!(bounded_moves.len(), 2);
+    assert!(response["fullReplay"].is_array());
+    assert_eq!(response["boundedReplay"], json!(null));
+    assert_eq!(response["metrics"]["keptTurns"], json!(full_turns + 2));
+
+    // Only complete move logs can be verified.
+    let verify = |game_id| Operation::VerifyGame { game_id };
+    assert!(execute(&chain, application_id, verify(3)).await);
+    assert!(!execute(&chain, application_id, verify(5)).await);
+
+    // Recreating a game drops its turns from the count.
+    assert!(execute(&chain, application_id, new_game(3)).await);
+    let response: Value = chain
+        .graphql_query(application_id, "query { metrics { keptTurns } }")
+        .await;
+    assert_eq!(response["metrics"]["keptTurns"], json!(1 + 2));
+
+    chain.set_key_pair(KeyPair::generate());
+    assert!(
+        !execute(&chain, application_id, configure).await,
+        "only admins can configure game modes"
+    );
+}
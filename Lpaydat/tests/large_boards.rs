@@ -0,0 +1,96 @@
+//! Integration tests for games played on boards larger than 4x4.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute};
+use game2048::{Direction, Game2048Parameters, LargeBoard, Operation};
+use linera_sdk::serde_json::{json, Value};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn games_are_played_on_the_board_size_they_start_with() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let new_game = |board_size| Operation::NewGame {
+        seed: 9,
+        namespace: None,
+        sandbox: false,
+        max_moves: Some(4),
+        board_size,
+    };
+
+    assert!(
+        !execute(&chain, application_id, new_game(Some(7))).await,
+        "boards are at most 6x6"
+    );
+    assert!(execute(&chain, application_id, new_game(Some(6))).await);
+
+    let query = "query { game(gameId: 9) { boardSize board boardHex score moveCount isEnded \
+                 undosLeft leaderboardExclusion } \
+                 moves(gameId: 9) { board } }";
+    let response: Value = chain.graphql_query(application_id, query).await;
+    let initial = LargeBoard::new(6, 9).unwrap();
+    assert_eq!(response["game"]["boardSize"], json!(6));
+    assert_eq!(
+        response["game"]["board"],
+        json!(initial.convert_to_matrix())
+    );
+    assert_eq!(response["game"]["boardHex"], json!(initial.to_hex()));
+    assert_eq!(response["game"]["undosLeft"], json!(0));
+    assert_eq!(
+        response["game"]["leaderboardExclusion"],
+        json!("LargeBoard")
+    );
+    // Turns of large boards do not fit the move log.
+    assert_eq!(response["moves"], json!([]));
+
+    for direction in [
+        Direction::Left,
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+    ]
+    .into_iter()
+    .cycle()
+    .take(12)
+    {
+        let operation = Operation::MakeMove {
+            game_id: 9,
+            direction,
+            namespace: None,
+        };
+        assert!(execute(&chain, application_id, operation).await);
+    }
+
+    let response: Value = chain.graphql_query(application_id, query).await;
+    let game = &response["game"];
+    assert_eq!(game["moveCount"], json!(4));
+    assert_eq!(game["isEnded"], json!(true));
+    let board = game["board"].as_array().unwrap();
+    assert_eq!(board.len(), 6);
+    assert!(board.iter().all(|row| row.as_array().unwrap().len() == 6));
+    let tiles = board
+        .iter()
+        .flat_map(|row| row.as_array().unwrap())
+        .filter(|exponent| **exponent != json!(0))
+        .count();
+    assert!(tiles > 2, "every move spawns a tile");
+
+    let undo = Operation::Undo {
+        game_id: 9,
+        namespace: None,
+    };
+    assert!(!execute(&chain, application_id, undo).await);
+    let verify = Operation::VerifyGame { game_id: 9 };
+    assert!(
+        !execute(&chain, application_id, verify).await,
+        "games without a move log cannot be verified"
+    );
+
+    // Classic games keep their 4x4 board.
+    assert!(execute(&chain, application_id, new_game(None)).await);
+    let response: Value = chain.graphql_query(application_id, query).await;
+    assert_eq!(response["game"]["boardSize"], json!(4));
+    assert_eq!(response["game"]["boardHex"].as_str().unwrap().len(), 16);
+    assert_eq!(response["moves"].as_array().unwrap().len(), 1);
+}
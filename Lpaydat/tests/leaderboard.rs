@@ -0,0 +1,421 @@
+//! Integration tests for the leaderboard of players' best games, by mode and window, and its
+//! filters on minimum moves and duration, and for the efficiency leaderboard weighted by the
+//! application parameters.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute_at};
+use game2048::{
+    Direction, EfficiencyWeights, FeeSettings, Game2048Parameters, GameMode, LeaderboardFilters,
+    Operation,
+};
+use linera_sdk::{
+    base::{ApplicationId, Owner, Timestamp},
+    serde_json::{json, Value},
+    test::{ActiveChain, TestValidator},
+};
+
+async fn rankings(
+    chain: &ActiveChain,
+    application_id: ApplicationId<game2048::Game2048Abi>,
+) -> Value {
+    let query = "query { \
+                 games { gameId moveCount durationSeconds leaderboardExclusion } \
+                 leaderboardExport { total } }";
+    chain.graphql_query(application_id, query).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn games_below_the_filters_are_not_ranked() {
+    let (validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let other_chain = validator.new_chain().await;
+    other_chain.register_application(application_id).await;
+
+    let new_game = |seed| Operation::NewGame {
+        seed,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    let make_move = |game_id, direction| Operation::MakeMove {
+        game_id,
+        direction,
+        namespace: None,
+    };
+    for (seconds, operation) in [
+        (0, new_game(5)),
+        (10, make_move(5, Direction::Left)),
+        (30, make_move(5, Direction::Right)),
+        (40, new_game(6)),
+    ] {
+        assert!(execute_at(&chain, application_id, seconds, operation).await);
+    }
+
+    // Game 1 is the empty game created on instantiation.
+    let response = rankings(&chain, application_id).await;
+    assert_eq!(response["leaderboardExport"]["total"], json!(3));
+    let game = &response["games"][1];
+    assert_eq!(game["gameId"], json!(5));
+    assert_eq!(game["leaderboardExclusion"], Value::Null);
+    let move_count = game["moveCount"].as_u64().unwrap() as u32;
+    let duration = game["durationSeconds"].as_u64().unwrap();
+    assert!(move_count > 0 && duration > 0);
+
+    let set_filters = |min_moves, min_duration_secs| Operation::SetLeaderboardFilters {
+        filters: LeaderboardFilters {
+            min_moves,
+            min_duration_secs,
+        },
+    };
+    assert!(
+        !execute_at(&other_chain, application_id, 50, set_filters(1, 1)).await,
+        "only admins set the filters"
+    );
+
+    // Reaching the thresholds exactly is enough to be ranked.
+    assert!(
+        execute_at(
+            &chain,
+            application_id,
+            50,
+            set_filters(move_count, duration)
+        )
+        .await
+    );
+    let response = rankings(&chain, application_id).await;
+    assert_eq!(
+        response,
+        json!({
+            "games": [
+                {
+                    "gameId": 1,
+                    "moveCount": 0,
+                    "durationSeconds": 0,
+                    "leaderboardExclusion": "TooFewMoves",
+                },
+                {
+                    "gameId": 5,
+                    "moveCount": move_count,
+                    "durationSeconds": duration,
+                    "leaderboardExclusion": null,
+                },
+                {
+                    "gameId": 6,
+                    "moveCount": 0,
+                    "durationSeconds": 0,
+                    "leaderboardExclusion": "TooFewMoves",
+                },
+            ],
+            "leaderboardExport": { "total": 1 },
+        })
+    );
+
+    assert!(execute_at(&chain, application_id, 60, set_filters(move_count + 1, 0)).await);
+    let response = rankings(&chain, application_id).await;
+    assert_eq!(
+        response["games"][1]["leaderboardExclusion"],
+        json!("TooFewMoves")
+    );
+    assert_eq!(response["leaderboardExport"]["total"], json!(0));
+
+    assert!(execute_at(&chain, application_id, 70, set_filters(0, duration + 1)).await);
+    let response = rankings(&chain, application_id).await;
+    assert_eq!(
+        response["games"][1]["leaderboardExclusion"],
+        json!("TooShort")
+    );
+    assert_eq!(
+        response["games"][2]["leaderboardExclusion"],
+        json!("TooShort")
+    );
+    assert_eq!(response["leaderboardExport"]["total"], json!(0));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn players_are_ranked_by_their_best_finished_game() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let owner = Owner::from(chain.public_key());
+    let club = Some("club".to_string());
+
+    let new_game = |seed, namespace: Option<String>, sandbox| Operation::NewGame {
+        seed,
+        namespace,
+        sandbox,
+        max_moves: None,
+        board_size: None,
+    };
+    let end_game = |game_id, namespace: Option<String>| Operation::EndGame { game_id, namespace };
+    let make_move = |direction| Operation::MakeMove {
+        game_id: 5,
+        direction,
+        namespace: None,
+    };
+    let create_club = Operation::CreateCommunity {
+        community_id: "club".to_string(),
+        name: "Club".to_string(),
+        admins: vec![],
+        default_mode: GameMode::Classic,
+        fees: FeeSettings::default(),
+    };
+    let join_club = Operation::JoinCommunity {
+        community_id: "club".to_string(),
+    };
+    for (seconds, operation) in [
+        (0, new_game(5, None, false)),
+        (10, make_move(Direction::Left)),
+        (20, make_move(Direction::Right)),
+        (30, end_game(5, None)),
+        // A worse game does not replace the best one.
+        (40, new_game(6, None, false)),
+        (50, end_game(6, None)),
+        (60, new_game(7, None, true)),
+        (70, end_game(7, None)),
+        (80, create_club),
+        (80, join_club),
+        (90, new_game(8, club.clone(), false)),
+        (100, end_game(8, club.clone())),
+    ] {
+        assert!(execute_at(&chain, application_id, seconds, operation).await);
+    }
+
+    let query = "query { \
+                 game(gameId: 5) { score } \
+                 leaderboard { owner namespace gameId endedAt } \
+                 clubLeaderboard: leaderboard(namespace: \"club\") { gameId score } \
+                 empty: leaderboard(topN: 0) { gameId } }";
+    let response: Value = chain.graphql_query(application_id, query).await;
+    let score = response["game"]["score"].as_u64().unwrap();
+    assert!(score > 0);
+    assert_eq!(
+        response,
+        json!({
+            "game": { "score": score },
+            "leaderboard": [{
+                "owner": owner,
+                "namespace": null,
+                "gameId": 5,
+                "endedAt": 30_000_000,
+            }],
+            "clubLeaderboard": [{ "gameId": 8, "score": 0 }],
+            "empty": [],
+        })
+    );
+
+    // Rows resolve the profile of their player.
+    let query = format!(
+        "query {{ \
+         player(owner: \"{owner}\") {{ owner stats {{ gamesPlayed bestScore }} activeGames }} \
+         leaderboard {{ \
+         gameId profile {{ owner stats {{ gamesPlayed bestScore }} activeGames }} }} }}"
+    );
+    let response: Value = chain.graphql_query(application_id, &query).await;
+    assert_eq!(response["player"]["stats"]["bestScore"], json!(score));
+    assert_eq!(
+        response["leaderboard"],
+        json!([{ "gameId": 5, "profile": response["player"] }])
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn players_are_ranked_by_their_most_efficient_game() {
+    let (validator, bytecode_id) = TestValidator::with_current_bytecode::<
+        game2048::Game2048Abi,
+        game2048::Game2048Parameters,
+        u16,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+    let weights = EfficiencyWeights {
+        score_per_move: 3,
+        time_bonus: 10,
+        par_time_secs: 60,
+        tile_bonus: 7,
+    };
+    let parameters = Game2048Parameters {
+        efficiency_weights: weights.clone(),
+        ..Game2048Parameters::default()
+    };
+    let application_id = chain
+        .create_application(bytecode_id, parameters, 1, vec![])
+        .await;
+    let owner = Owner::from(chain.public_key());
+
+    let make_move = |direction| Operation::MakeMove {
+        game_id: 5,
+        direction,
+        namespace: None,
+    };
+    for (seconds, operation) in [
+        (
+            0,
+            Operation::NewGame {
+                seed: 5,
+                namespace: None,
+                sandbox: false,
+                max_moves: None,
+                board_size: None,
+            },
+        ),
+        (2, make_move(Direction::Left)),
+        (4, make_move(Direction::Right)),
+        (
+            10,
+            Operation::EndGame {
+                game_id: 5,
+                namespace: None,
+            },
+        ),
+        // A game without moves is not efficient and does not replace the first one.
+        (
+            20,
+            Operation::NewGame {
+                seed: 6,
+                namespace: None,
+                sandbox: false,
+                max_moves: None,
+                board_size: None,
+            },
+        ),
+        (
+            30,
+            Operation::EndGame {
+                game_id: 6,
+                namespace: None,
+            },
+        ),
+    ] {
+        assert!(execute_at(&chain, application_id, seconds, operation).await);
+    }
+
+    let query = "query { \
+                 efficiencyWeights { scorePerMove timeBonus parTimeSecs tileBonus } \
+                 efficiencyLeaderboard { owner gameId efficiency score moveCount durationSecs maxTile } \
+                 empty: efficiencyLeaderboard(namespace: \"club\") { gameId } }";
+    let response: Value = chain.graphql_query(application_id, query).await;
+    assert_eq!(
+        response["efficiencyWeights"],
+        json!({ "scorePerMove": 3, "timeBonus": 10, "parTimeSecs": 60, "tileBonus": 7 })
+    );
+    assert_eq!(response["empty"], json!([]));
+
+    let entries = response["efficiencyLeaderboard"].as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    let entry = &entries[0];
+    assert_eq!(entry["owner"], json!(owner));
+    assert_eq!(entry["gameId"], json!(5));
+    assert_eq!(entry["moveCount"], json!(2));
+    assert_eq!(entry["durationSecs"], json!(4));
+    let score = entry["score"].as_u64().unwrap();
+    let max_tile = entry["maxTile"].as_u64().unwrap();
+    let expected = 3 * (score / 2) + 10 * (60 - 4) + 7 * u64::from(max_tile.ilog2());
+    assert_eq!(entry["efficiency"], json!(expected));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn players_are_ranked_by_mode_and_window() {
+    let (validator, bytecode_id) = TestValidator::with_current_bytecode::<
+        game2048::Game2048Abi,
+        game2048::Game2048Parameters,
+        u16,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+    let application_id = chain
+        .create_application(
+            bytecode_id,
+            game2048::Game2048Parameters::default(),
+            1,
+            vec![],
+        )
+        .await;
+    let owner = Owner::from(chain.public_key());
+    let blitz = Some("blitz".to_string());
+
+    let new_game = |seed, namespace: Option<String>| Operation::NewGame {
+        seed,
+        namespace,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    let make_move = |game_id, direction| Operation::MakeMove {
+        game_id,
+        direction,
+        namespace: None,
+    };
+    let end_game = |game_id, namespace: Option<String>| Operation::EndGame { game_id, namespace };
+    let create_blitz = Operation::CreateCommunity {
+        community_id: "blitz".to_string(),
+        name: "Blitz".to_string(),
+        admins: vec![],
+        default_mode: GameMode::Blitz,
+        fees: FeeSettings::default(),
+    };
+    let join_blitz = Operation::JoinCommunity {
+        community_id: "blitz".to_string(),
+    };
+    // Game 6 ends two days before the others, out of today's window.
+    let now = Timestamp::now().micros() / 1_000_000;
+    let two_days_ago = now - 2 * 86_400;
+    for (seconds, operation) in [
+        (two_days_ago, new_game(6, None)),
+        (two_days_ago, end_game(6, None)),
+        (now, new_game(5, None)),
+        (now, create_blitz),
+        (now, join_blitz),
+        (now, new_game(8, blitz.clone())),
+        (now, end_game(8, blitz.clone())),
+    ] {
+        assert!(execute_at(&chain, application_id, seconds, operation).await);
+    }
+    // Game 5 needs a score to beat game 6, which it would tie with otherwise.
+    let mut score = 0;
+    for direction in [
+        Direction::Left,
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+    ]
+    .into_iter()
+    .cycle()
+    .take(16)
+    {
+        assert!(execute_at(&chain, application_id, now, make_move(5, direction)).await);
+        let response = chain
+            .graphql_query(application_id, "query { game(gameId: 5) { score } }")
+            .await;
+        score = response["game"]["score"].as_u64().unwrap();
+        if score > 0 {
+            break;
+        }
+    }
+    assert!(score > 0);
+    assert!(execute_at(&chain, application_id, now, end_game(5, None)).await);
+
+    let query = format!(
+        "query {{ \
+         classic: leaderboard(mode: \"Classic\") {{ gameId }} \
+         today: leaderboard(window: \"Daily\") {{ gameId }} \
+         globalBlitz: leaderboard(mode: \"Blitz\") {{ gameId }} \
+         blitz: leaderboard(mode: \"Blitz\", window: \"Weekly\", namespace: \"blitz\") {{ gameId }} \
+         none: leaderboard(mode: \"Endless\", limit: 5) {{ gameId }} \
+         player(owner: \"{owner}\") {{ modeBests {{ mode score }} }} }}"
+    );
+    let response: Value = chain.graphql_query(application_id, &query).await;
+    assert_eq!(
+        response,
+        json!({
+            "classic": [{ "gameId": 5 }],
+            "today": [{ "gameId": 5 }],
+            "globalBlitz": [],
+            "blitz": [{ "gameId": 8 }],
+            "none": [],
+            "player": {
+                "modeBests": [{ "mode": "Classic", "score": score }],
+            },
+        })
+    );
+}
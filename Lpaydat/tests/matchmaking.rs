@@ -0,0 +1,228 @@
+//! Integration tests for the matchmaking queue and lobbies run by the chain that created the
+//! application.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute, query};
+use game2048::{Game2048Parameters, Lobby, Operation, DEFAULT_LOBBY};
+use linera_sdk::{base::Owner, serde_json::json};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn queued_players_are_paired_and_notified() {
+    let (validator, matchmaker, application_id) = deploy(Game2048Parameters::default()).await;
+    let mut players = Vec::new();
+    for _ in 0..3 {
+        let chain = validator.new_chain().await;
+        chain.register_application(application_id).await;
+        players.push(chain);
+    }
+    let owners = players
+        .iter()
+        .map(|chain| Owner::from(chain.public_key()))
+        .collect::<Vec<_>>();
+
+    // A single player waits with no estimate, until they leave.
+    assert!(execute(&players[2], application_id, Operation::JoinMatchQueue).await);
+    matchmaker.handle_received_messages().await;
+    let queue = query(
+        &matchmaker,
+        application_id,
+        "query { matchQueue { owner rating window estimatedWaitSecs } }",
+    )
+    .await;
+    assert_eq!(
+        queue,
+        json!({
+            "matchQueue": [{
+                "owner": owners[2],
+                "rating": 0,
+                "window": 500,
+                "estimatedWaitSecs": null,
+            }],
+        })
+    );
+    assert!(execute(&players[2], application_id, Operation::LeaveMatchQueue).await);
+    matchmaker.handle_received_messages().await;
+
+    // Players of equal rating are paired as soon as the second one joins.
+    for chain in &players[..2] {
+        assert!(execute(chain, application_id, Operation::JoinMatchQueue).await);
+    }
+    matchmaker.handle_received_messages().await;
+    let response = query(
+        &matchmaker,
+        application_id,
+        "query { matchQueue { owner } matches { matchId players seed } }",
+    )
+    .await;
+    assert_eq!(response["matchQueue"], json!([]));
+    let matches = response["matches"].as_array().unwrap();
+    assert_eq!(matches.len(), 1);
+    let mut paired = matches[0]["players"].as_array().unwrap().clone();
+    paired.sort_by_key(|owner| owner.to_string());
+    let mut expected = vec![json!(owners[0]), json!(owners[1])];
+    expected.sort_by_key(|owner| owner.to_string());
+    assert_eq!(paired, expected);
+
+    // Both players get a game started from the same board.
+    let mut boards = Vec::new();
+    for (index, chain) in players[..2].iter().enumerate() {
+        chain.handle_received_messages().await;
+        let owner = owners[index];
+        let opponent = owners[1 - index];
+        let notice = query(
+            chain,
+            application_id,
+            &format!("query {{ matchNotice(owner: \"{owner}\") {{ matchId opponent gameId }} }}"),
+        )
+        .await;
+        let game_id = notice["matchNotice"]["gameId"].clone();
+        assert_eq!(
+            notice,
+            json!({
+                "matchNotice": { "matchId": 0, "opponent": opponent, "gameId": game_id },
+            })
+        );
+        let game = query(
+            chain,
+            application_id,
+            &format!("query {{ game(gameId: {game_id}) {{ owner board moveCount }} }}"),
+        )
+        .await;
+        assert_eq!(game["game"]["owner"], json!(owner));
+        assert_eq!(game["game"]["moveCount"], json!(0));
+        boards.push(game["game"]["board"].clone());
+    }
+    assert_eq!(boards[0], boards[1]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn players_are_only_paired_within_their_lobby() {
+    let (validator, matchmaker, application_id) = deploy(Game2048Parameters::default()).await;
+    let mut players = Vec::new();
+    for _ in 0..3 {
+        let chain = validator.new_chain().await;
+        chain.register_application(application_id).await;
+        players.push(chain);
+    }
+    let owners = players
+        .iter()
+        .map(|chain| Owner::from(chain.public_key()))
+        .collect::<Vec<_>>();
+
+    let create_lobby = |name: &str, min_rating, max_rating| Operation::CreateLobby {
+        name: name.to_string(),
+        lobby: Lobby {
+            description: format!("The {name} lobby"),
+            min_rating,
+            max_rating,
+        },
+    };
+    assert!(
+        !execute(
+            &players[0],
+            application_id,
+            create_lobby("eu", 0, Some(100))
+        )
+        .await,
+        "only admins create lobbies"
+    );
+    assert!(
+        execute(
+            &matchmaker,
+            application_id,
+            create_lobby("eu", 0, Some(100))
+        )
+        .await
+    );
+    assert!(
+        execute(
+            &matchmaker,
+            application_id,
+            create_lobby("pro", 1_000, None)
+        )
+        .await
+    );
+    assert!(
+        !execute(
+            &matchmaker,
+            application_id,
+            create_lobby(DEFAULT_LOBBY, 0, None)
+        )
+        .await,
+        "the default lobby always exists"
+    );
+
+    let join = |lobby: &str| Operation::JoinLobbyQueue {
+        lobby: lobby.to_string(),
+    };
+    // Unknown lobbies and ratings outside the bracket are ignored.
+    assert!(execute(&players[0], application_id, join("moon")).await);
+    assert!(execute(&players[1], application_id, join("pro")).await);
+    matchmaker.handle_received_messages().await;
+    let queue = query(
+        &matchmaker,
+        application_id,
+        "query { matchQueue { owner } }",
+    )
+    .await;
+    assert_eq!(queue, json!({ "matchQueue": [] }));
+
+    // Equal ratings in different lobbies are not paired.
+    assert!(execute(&players[0], application_id, join("eu")).await);
+    assert!(execute(&players[1], application_id, Operation::JoinMatchQueue).await);
+    matchmaker.handle_received_messages().await;
+    let queue = query(
+        &matchmaker,
+        application_id,
+        "query { matchQueue(lobby: \"eu\") { owner lobby estimatedWaitSecs } }",
+    )
+    .await;
+    assert_eq!(
+        queue,
+        json!({
+            "matchQueue": [{ "owner": owners[0], "lobby": "eu", "estimatedWaitSecs": null }],
+        })
+    );
+
+    assert!(execute(&players[2], application_id, join("eu")).await);
+    matchmaker.handle_received_messages().await;
+    let lobbies = query(
+        &matchmaker,
+        application_id,
+        "query { lobbies { name settings { minRating maxRating } queued \
+         stats { matches } averageWaitSecs } matches { lobby } }",
+    )
+    .await;
+    assert_eq!(
+        lobbies,
+        json!({
+            "lobbies": [
+                {
+                    "name": DEFAULT_LOBBY,
+                    "settings": { "minRating": 0, "maxRating": null },
+                    "queued": 1,
+                    "stats": { "matches": 0 },
+                    "averageWaitSecs": null,
+                },
+                {
+                    "name": "eu",
+                    "settings": { "minRating": 0, "maxRating": 100 },
+                    "queued": 0,
+                    "stats": { "matches": 1 },
+                    "averageWaitSecs": 0,
+                },
+                {
+                    "name": "pro",
+                    "settings": { "minRating": 1_000, "maxRating": null },
+                    "queued": 0,
+                    "stats": { "matches": 0 },
+                    "averageWaitSecs": null,
+                },
+            ],
+            "matches": [{ "lobby": "eu" }],
+        })
+    );
+}
@@ -0,0 +1,136 @@
+//! Integration tests for the reconciliation of the hub's copy of the games and of their
+//! scores with the chains they are played on, when turns reported to the hub are dropped,
+//! duplicated or reordered.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::execute;
+use game2048::{Direction, FaultKind, Game2048Parameters, MessageFault, Operation};
+use linera_sdk::{
+    base::ApplicationId,
+    serde_json::{json, Value},
+    test::{ActiveChain, TestValidator},
+};
+
+/// Plays `moves` moves of game `game_id`, trying the directions in turn.
+async fn play(
+    chain: &ActiveChain,
+    application_id: ApplicationId<game2048::Game2048Abi>,
+    game_id: u16,
+    moves: usize,
+) {
+    let directions = [
+        Direction::Left,
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+    ];
+    let mut played = 0;
+    for direction in directions.iter().cycle().take(moves * 8) {
+        let operation = Operation::MakeMove {
+            game_id,
+            direction: direction.clone(),
+            namespace: None,
+        };
+        let query = format!("query {{ game(gameId: {game_id}) {{ moveCount }} }}");
+        let before = chain.graphql_query(application_id, &query).await;
+        assert!(execute(chain, application_id, operation).await);
+        let after = chain.graphql_query(application_id, &query).await;
+        if after != before {
+            played += 1;
+            if played == moves {
+                return;
+            }
+        }
+    }
+    panic!("Game {game_id} is stuck after {played} moves");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn the_hub_converges_despite_message_faults() {
+    let (validator, bytecode_id) =
+        TestValidator::with_current_bytecode::<game2048::Game2048Abi, Game2048Parameters, u16>()
+            .await;
+    let hub = validator.new_chain().await;
+    let mut player = validator.new_chain().await;
+    let fault = |game_id, move_index, kind| MessageFault {
+        game_id,
+        move_index,
+        kind,
+    };
+    let parameters = Game2048Parameters {
+        hub: Some(hub.id()),
+        message_faults: vec![
+            fault(5, 2, FaultKind::Drop),
+            fault(5, 3, FaultKind::Duplicate),
+            fault(5, 4, FaultKind::Delay),
+            fault(5, 6, FaultKind::Duplicate),
+            fault(6, 0, FaultKind::Drop),
+            fault(6, 1, FaultKind::Delay),
+        ],
+        ..Game2048Parameters::default()
+    };
+    let application_id = player
+        .create_application(bytecode_id, parameters, 1, vec![])
+        .await;
+    hub.register_application(application_id).await;
+
+    for (game_id, moves) in [(5, 7), (6, 3)] {
+        let new_game = Operation::NewGame {
+            seed: game_id,
+            namespace: None,
+            sandbox: false,
+            max_moves: None,
+            board_size: None,
+        };
+        assert!(execute(&player, application_id, new_game).await);
+        play(&player, application_id, game_id, moves).await;
+    }
+
+    // The hub asks for the turns it missed, and applies them once they are resent.
+    hub.handle_received_messages().await;
+    player.handle_received_messages().await;
+    hub.handle_received_messages().await;
+
+    let mut expected = Vec::new();
+    for game_id in [5, 6] {
+        let query = format!("query {{ game(gameId: {game_id}) {{ score moveCount isEnded }} }}");
+        let response: Value = player.graphql_query(application_id, &query).await;
+        let game = &response["game"];
+        expected.push(json!({
+            "chainId": player.id(),
+            "gameId": game_id,
+            "moveIndex": game["moveCount"],
+            "score": game["score"],
+            "isEnded": game["isEnded"],
+        }));
+    }
+    let query = "query { spectatedGames { chainId gameId moveIndex score isEnded } }";
+    let mut response: Value = hub.graphql_query(application_id, query).await;
+    let games = response["spectatedGames"].as_array_mut().unwrap();
+    games.sort_by_key(|game| game["gameId"].as_u64());
+    assert_eq!(*games, expected);
+
+    // The scores the hub aggregates are the reconciled ones.
+    let mut leaderboard: Vec<Value> = expected
+        .iter()
+        .map(|game| json!({ "gameId": game["gameId"], "score": game["score"] }))
+        .collect();
+    leaderboard.sort_by_key(|game| {
+        (
+            std::cmp::Reverse(game["score"].as_u64()),
+            game["gameId"].as_u64(),
+        )
+    });
+    let query = "query { remoteLeaderboard { gameId score } bestRemoteScore { score } }";
+    let response: Value = hub.graphql_query(application_id, query).await;
+    assert_eq!(
+        response,
+        json!({
+            "remoteLeaderboard": leaderboard,
+            "bestRemoteScore": { "score": leaderboard[0]["score"] },
+        })
+    );
+}
@@ -0,0 +1,76 @@
+//! Integration tests for the counters the contract keeps for observability.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use game2048::{Direction, Operation, OperationEnvelope};
+use linera_sdk::{
+    serde_json::{json, Value},
+    test::TestValidator,
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn operations_failures_and_messages_are_counted() {
+    let (validator, bytecode_id) = TestValidator::with_current_bytecode::<
+        game2048::Game2048Abi,
+        game2048::Game2048Parameters,
+        u16,
+    >()
+    .await;
+    let hub = validator.new_chain().await;
+    let mut chain = validator.new_chain().await;
+    let parameters = game2048::Game2048Parameters {
+        hub: Some(hub.id()),
+        ..game2048::Game2048Parameters::default()
+    };
+    let application_id = chain
+        .create_application(bytecode_id, parameters, 1, vec![])
+        .await;
+
+    // The bottom row of seed 5 is `2 2 0 0`: it cannot move down.
+    let operations = [
+        Operation::NewGame {
+            seed: 5,
+            namespace: None,
+            sandbox: false,
+            max_moves: None,
+            board_size: None,
+        },
+        Operation::MakeMove {
+            game_id: 5,
+            direction: Direction::Down,
+            namespace: None,
+        },
+        Operation::MakeMove {
+            game_id: 5,
+            direction: Direction::Left,
+            namespace: None,
+        },
+    ];
+    chain
+        .add_block(|block| {
+            for operation in &operations {
+                block.with_operation(application_id, OperationEnvelope::new(operation));
+            }
+        })
+        .await;
+
+    let response: Value = chain
+        .graphql_query(
+            application_id,
+            "query { metrics { operations { operation count } \
+             failures { failure count } messagesSent } }",
+        )
+        .await;
+    assert_eq!(
+        response["metrics"],
+        json!({
+            "operations": [
+                { "operation": "MakeMove", "count": 2 },
+                { "operation": "NewGame", "count": 1 },
+            ],
+            "failures": [{ "failure": "UselessMove", "count": 1 }],
+            // The creation of the game and the move that changed the board, reported to the hub.
+            "messagesSent": 2,
+        })
+    );
+}
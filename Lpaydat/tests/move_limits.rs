@@ -0,0 +1,66 @@
+//! Integration tests for games ending after the number of moves set when they are created.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute};
+use game2048::{Direction, Game2048Parameters, Operation};
+use linera_sdk::serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn games_end_after_their_move_limit() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let new_game = |max_moves| Operation::NewGame {
+        seed: 5,
+        namespace: None,
+        sandbox: false,
+        max_moves,
+        board_size: None,
+    };
+
+    assert!(
+        !execute(&chain, application_id, new_game(Some(0))).await,
+        "games need at least one move"
+    );
+    assert!(execute(&chain, application_id, new_game(Some(2))).await);
+
+    let query = "query { game(gameId: 5) { isEnded moveCount maxMoves } }";
+    let response = chain.graphql_query(application_id, query).await;
+    assert_eq!(
+        response,
+        json!({ "game": { "isEnded": false, "moveCount": 0, "maxMoves": 2 } })
+    );
+
+    let mut moves = 0;
+    for direction in [
+        Direction::Left,
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+    ]
+    .into_iter()
+    .cycle()
+    .take(12)
+    {
+        let operation = Operation::MakeMove {
+            game_id: 5,
+            direction,
+            namespace: None,
+        };
+        assert!(execute(&chain, application_id, operation).await);
+        let response = chain.graphql_query(application_id, query).await;
+        let move_count = response["game"]["moveCount"].as_u64().unwrap();
+        if move_count > moves {
+            moves = move_count;
+            assert_eq!(response["game"]["isEnded"], json!(moves == 2));
+        }
+    }
+
+    // Moves past the limit are dropped.
+    let response = chain.graphql_query(application_id, query).await;
+    assert_eq!(
+        response,
+        json!({ "game": { "isEnded": true, "moveCount": 2, "maxMoves": 2 } })
+    );
+}
@@ -0,0 +1,65 @@
+//! Integration tests for the rejection of moves that do not change the board.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute_all_at_micros};
+use game2048::{Difficulty, Direction, Game2048Parameters, GameMode, GameTemplate, Operation};
+use linera_sdk::{
+    base::Owner,
+    serde_json::{json, Value},
+};
+
+const SECOND: u64 = 1_000_000;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn useless_directions_do_not_count_against_the_game() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let owner = Owner::from(chain.public_key());
+    let make_move = |direction| Operation::MakeMove {
+        game_id: 7,
+        direction,
+        namespace: None,
+    };
+    let template = GameTemplate {
+        name: "Timed".to_string(),
+        mode: GameMode::Classic,
+        board_size: 4,
+        difficulty: Difficulty::Easy,
+        time_limit_secs: Some(60),
+    };
+    let setup = vec![
+        Operation::CreateTemplate { template },
+        // Both tiles of seed 7 are against the left edge.
+        Operation::NewTemplateGame {
+            template_id: 0,
+            seed: 7,
+        },
+    ];
+    assert!(execute_all_at_micros(&chain, application_id, 0, &setup).await);
+
+    let state = format!(
+        "query {{ game(gameId: 7) {{ board score isEnded moveCount updatedAt }} \
+         player(owner: \"{owner}\") {{ stats {{ movesPlayed }} }} }}"
+    );
+    let initial: Value = chain.graphql_query(application_id, &state).await;
+    assert_eq!(initial["game"]["isEnded"], json!(false));
+
+    let spam = vec![make_move(Direction::Left); 10];
+    assert!(execute_all_at_micros(&chain, application_id, 30 * SECOND, &spam).await);
+    // Past the time limit, spamming does not end the game on the player's behalf either.
+    assert!(execute_all_at_micros(&chain, application_id, 61 * SECOND, &spam).await);
+    let spammed: Value = chain.graphql_query(application_id, &state).await;
+    assert_eq!(spammed, initial);
+
+    let useful = vec![make_move(Direction::Right)];
+    assert!(execute_all_at_micros(&chain, application_id, 61 * SECOND, &useful).await);
+    let game: Value = chain
+        .graphql_query(
+            application_id,
+            "query { game(gameId: 7) { isEnded moveCount } }",
+        )
+        .await;
+    assert_eq!(game["game"], json!({ "isEnded": true, "moveCount": 0 }));
+}
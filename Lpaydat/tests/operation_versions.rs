@@ -0,0 +1,319 @@
+//! Compatibility matrix for the versioned operation encodings.
+//!
+//! The payloads below are pinned byte-for-byte: they are what clients of each version put on
+//! the wire, so they must keep decoding to the same operations for as long as the version is
+//! supported, whatever happens to the current `Operation` enum.
+
+use game2048::{
+    v1, v2, v3, v4, Direction, Operation, OperationDecodeError, OperationEnvelope,
+    CURRENT_OPERATION_VERSION,
+};
+use linera_sdk::bcs;
+
+fn matrix() -> Vec<(u8, Vec<u8>, Operation)> {
+    vec![
+        (
+            1,
+            vec![0, 7, 0],
+            Operation::NewGame {
+                seed: 7,
+                namespace: None,
+                sandbox: false,
+                max_moves: None,
+                board_size: None,
+            },
+        ),
+        (
+            1,
+            vec![1, 3, 0],
+            Operation::EndGame {
+                game_id: 3,
+                namespace: None,
+            },
+        ),
+        (
+            1,
+            vec![2, 3, 0, 2],
+            Operation::MakeMove {
+                game_id: 3,
+                direction: Direction::Left,
+                namespace: None,
+            },
+        ),
+        (
+            2,
+            vec![0, 7, 0, 0],
+            Operation::NewGame {
+                seed: 7,
+                namespace: None,
+                sandbox: false,
+                max_moves: None,
+                board_size: None,
+            },
+        ),
+        (
+            2,
+            vec![0, 7, 0, 1, 1, b'a'],
+            Operation::NewGame {
+                seed: 7,
+                namespace: Some("a".to_string()),
+                sandbox: false,
+                max_moves: None,
+                board_size: None,
+            },
+        ),
+        (
+            2,
+            vec![2, 3, 0, 3, 1, 1, b'a'],
+            Operation::MakeMove {
+                game_id: 3,
+                direction: Direction::Right,
+                namespace: Some("a".to_string()),
+            },
+        ),
+        (
+            2,
+            vec![5, 1, b'a'],
+            Operation::JoinCommunity {
+                community_id: "a".to_string(),
+            },
+        ),
+        (
+            3,
+            vec![0, 7, 0, 0, 1],
+            Operation::NewGame {
+                seed: 7,
+                namespace: None,
+                sandbox: true,
+                max_moves: None,
+                board_size: None,
+            },
+        ),
+        (
+            3,
+            vec![0, 7, 0, 1, 1, b'a', 0],
+            Operation::NewGame {
+                seed: 7,
+                namespace: Some("a".to_string()),
+                sandbox: false,
+                max_moves: None,
+                board_size: None,
+            },
+        ),
+        (
+            3,
+            vec![2, 3, 0, 3, 1, 1, b'a'],
+            Operation::MakeMove {
+                game_id: 3,
+                direction: Direction::Right,
+                namespace: Some("a".to_string()),
+            },
+        ),
+        (
+            4,
+            vec![0, 7, 0, 0, 0, 1, 200, 0, 0, 0],
+            Operation::NewGame {
+                seed: 7,
+                namespace: None,
+                sandbox: false,
+                max_moves: Some(200),
+                board_size: None,
+            },
+        ),
+        (
+            4,
+            vec![0, 7, 0, 1, 1, b'a', 1, 0],
+            Operation::NewGame {
+                seed: 7,
+                namespace: Some("a".to_string()),
+                sandbox: true,
+                max_moves: None,
+                board_size: None,
+            },
+        ),
+        (
+            5,
+            vec![0, 7, 0, 0, 0, 0, 1, 6],
+            Operation::NewGame {
+                seed: 7,
+                namespace: None,
+                sandbox: false,
+                max_moves: None,
+                board_size: Some(6),
+            },
+        ),
+    ]
+}
+
+#[test]
+fn every_supported_version_decodes() {
+    for (version, payload, expected) in matrix() {
+        let envelope = OperationEnvelope { version, payload };
+        assert_eq!(envelope.decode().unwrap(), expected, "version {version}");
+    }
+}
+
+#[test]
+fn envelopes_are_pinned_on_the_wire() {
+    let envelope = OperationEnvelope {
+        version: 1,
+        payload: vec![0, 7, 0],
+    };
+    assert_eq!(bcs::to_bytes(&envelope).unwrap(), [1, 3, 0, 7, 0]);
+    assert_eq!(
+        bcs::from_bytes::<OperationEnvelope>(&[1, 3, 0, 7, 0]).unwrap(),
+        envelope
+    );
+}
+
+#[test]
+fn v1_operations_encode_to_their_fixtures() {
+    let operations = [
+        v1::Operation::NewGame { seed: 7 },
+        v1::Operation::EndGame { game_id: 3 },
+        v1::Operation::MakeMove {
+            game_id: 3,
+            direction: Direction::Left,
+        },
+    ];
+    let fixtures = matrix().into_iter().filter(|(version, ..)| *version == 1);
+    for (operation, (_, payload, _)) in operations.iter().zip(fixtures) {
+        assert_eq!(bcs::to_bytes(operation).unwrap(), payload);
+    }
+}
+
+#[test]
+fn v2_operations_encode_to_their_fixtures() {
+    let operations = [
+        v2::Operation::NewGame {
+            seed: 7,
+            namespace: None,
+        },
+        v2::Operation::NewGame {
+            seed: 7,
+            namespace: Some("a".to_string()),
+        },
+    ];
+    let fixtures = matrix().into_iter().filter(|(version, ..)| *version == 2);
+    for (operation, (_, payload, _)) in operations.iter().zip(fixtures) {
+        assert_eq!(bcs::to_bytes(operation).unwrap(), payload);
+    }
+}
+
+#[test]
+fn v3_operations_encode_to_their_fixtures() {
+    let operations = [
+        v3::Operation::NewGame {
+            seed: 7,
+            namespace: None,
+            sandbox: true,
+        },
+        v3::Operation::NewGame {
+            seed: 7,
+            namespace: Some("a".to_string()),
+            sandbox: false,
+        },
+    ];
+    let fixtures = matrix().into_iter().filter(|(version, ..)| *version == 3);
+    for (operation, (_, payload, _)) in operations.iter().zip(fixtures) {
+        assert_eq!(bcs::to_bytes(operation).unwrap(), payload);
+    }
+}
+
+#[test]
+fn v4_operations_encode_to_their_fixtures() {
+    let operations = [
+        v4::Operation::NewGame {
+            seed: 7,
+            namespace: None,
+            sandbox: false,
+            max_moves: Some(200),
+        },
+        v4::Operation::NewGame {
+            seed: 7,
+            namespace: Some("a".to_string()),
+            sandbox: true,
+            max_moves: None,
+        },
+    ];
+    let fixtures = matrix().into_iter().filter(|(version, ..)| *version == 4);
+    for (operation, (_, payload, _)) in operations.iter().zip(fixtures) {
+        assert_eq!(bcs::to_bytes(operation).unwrap(), payload);
+    }
+}
+
+#[test]
+fn current_version_round_trips() {
+    for (_, _, operation) in matrix() {
+        let envelope = OperationEnvelope::new(&operation);
+        assert_eq!(envelope.version, CURRENT_OPERATION_VERSION);
+        assert_eq!(envelope.decode().unwrap(), operation);
+    }
+}
+
+#[test]
+fn payloads_are_decoded_by_their_own_version_only() {
+    // A version 1 payload lacks the namespace version 2 expects.
+    let envelope = OperationEnvelope {
+        version: 2,
+        payload: vec![0, 7, 0],
+    };
+    assert!(matches!(
+        envelope.decode(),
+        Err(OperationDecodeError::MalformedPayload(_))
+    ));
+
+    // A version 2 `NewGame` lacks the sandbox flag version 3 expects.
+    let envelope = OperationEnvelope {
+        version: 3,
+        payload: vec![0, 7, 0, 0],
+    };
+    assert!(matches!(
+        envelope.decode(),
+        Err(OperationDecodeError::MalformedPayload(_))
+    ));
+
+    // A version 3 `NewGame` lacks the move limit version 4 expects.
+    let envelope = OperationEnvelope {
+        version: 4,
+        payload: vec![0, 7, 0, 0, 1],
+    };
+    assert!(matches!(
+        envelope.decode(),
+        Err(OperationDecodeError::MalformedPayload(_))
+    ));
+
+    // A version 4 `NewGame` lacks the board size version 5 expects.
+    let envelope = OperationEnvelope {
+        version: 5,
+        payload: vec![0, 7, 0, 0, 0, 1, 200, 0, 0, 0],
+    };
+    assert!(matches!(
+        envelope.decode(),
+        Err(OperationDecodeError::MalformedPayload(_))
+    ));
+
+    // Trailing bytes are rejected rather than ignored.
+    let envelope = OperationEnvelope {
+        version: 1,
+        payload: vec![0, 7, 0, 0],
+    };
+    assert!(matches!(
+        envelope.decode(),
+        Err(OperationDecodeError::MalformedPayload(_))
+    ));
+}
+
+#[test]
+fn unknown_versions_are_rejected() {
+    for version in [0, CURRENT_OPERATION_VERSION + 1, u8::MAX] {
+        let envelope = OperationEnvelope {
+            version,
+            payload: vec![0, 7, 0, 0],
+        };
+        assert!(matches!(
+            envelope.decode(),
+            Err(OperationDecodeError::UnsupportedVersion(v)) if v == version
+        ));
+    }
+}
@@ -0,0 +1,68 @@
+//! Integration tests for the order of listing queries, which must not depend on how the keys
+//! of the views they read are serialized, so that every validator answers alike.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::deploy;
+use game2048::{FeeSettings, Game2048Parameters, GameMode, Operation, OperationEnvelope};
+use linera_sdk::{
+    base::Owner,
+    serde_json::{json, Value},
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn listings_are_sorted_by_key() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let owner = Owner::from(chain.public_key());
+
+    // Keys are stored serialized: 256 comes before 1 in little-endian, and "b" before "aa"
+    // once prefixed with its length.
+    let new_game = |seed| Operation::NewGame {
+        seed,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    let create_community = |community_id: &str| Operation::CreateCommunity {
+        community_id: community_id.to_string(),
+        name: community_id.to_uppercase(),
+        admins: vec![],
+        default_mode: GameMode::Classic,
+        fees: FeeSettings::default(),
+    };
+    let operations = [
+        new_game(256),
+        new_game(1),
+        new_game(2),
+        create_community("b"),
+        create_community("aa"),
+    ];
+    chain
+        .add_block(|block| {
+            for operation in &operations {
+                block.with_operation(application_id, OperationEnvelope::new(operation));
+            }
+        })
+        .await;
+
+    let query = format!(
+        "query {{ games {{ gameId }} communities {{ communityId }} \
+         player(owner: \"{owner}\") {{ games }} }}"
+    );
+    let listings: Value = chain.graphql_query(application_id, query.as_str()).await;
+    assert_eq!(
+        listings,
+        json!({
+            "games": [{ "gameId": 1 }, { "gameId": 2 }, { "gameId": 256 }],
+            "communities": [{ "communityId": "aa" }, { "communityId": "b" }],
+            "player": { "games": [1, 2, 256] },
+        })
+    );
+
+    // Loading the state again gives the same answer.
+    let again: Value = chain.graphql_query(application_id, query.as_str()).await;
+    assert_eq!(again, listings);
+}
@@ -0,0 +1,115 @@
+//! Integration tests for games only being played by the player who created them.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute, execute_at_micros};
+use game2048::{daily_seed, Direction, Game2048Parameters, Operation, DAY_MICROS};
+use linera_sdk::{
+    base::KeyPair,
+    serde_json::{json, Value},
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_the_owner_plays_and_ends_a_game() {
+    let (_validator, mut chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let new_game = Operation::NewGame {
+        seed: 5,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    let make_move = Operation::MakeMove {
+        game_id: 5,
+        direction: Direction::Left,
+        namespace: None,
+    };
+    let end_game = Operation::EndGame {
+        game_id: 5,
+        namespace: None,
+    };
+    assert!(execute(&chain, application_id, new_game).await);
+
+    // Another signer proposes blocks on the same chain.
+    let owner_key_pair = chain.key_pair().copy();
+    chain.set_key_pair(KeyPair::generate());
+    assert!(
+        !execute(&chain, application_id, make_move.clone()).await,
+        "only the owner can move"
+    );
+    assert!(
+        !execute(&chain, application_id, end_game.clone()).await,
+        "only the owner can end the game"
+    );
+    let game: Value = chain
+        .graphql_query(
+            application_id,
+            "query { game(gameId: 5) { moveCount isEnded } }",
+        )
+        .await;
+    assert_eq!(
+        game,
+        json!({ "game": { "moveCount": 0, "isEnded": false } })
+    );
+
+    chain.set_key_pair(owner_key_pair);
+    assert!(execute(&chain, application_id, make_move).await);
+    assert!(execute(&chain, application_id, end_game).await);
+    let game: Value = chain
+        .graphql_query(
+            application_id,
+            "query { game(gameId: 5) { moveCount isEnded } }",
+        )
+        .await;
+    assert_eq!(game, json!({ "game": { "moveCount": 1, "isEnded": true } }));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn new_games_replace_only_the_signers_own_free_games() {
+    let (_validator, mut chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let new_game = |seed| Operation::NewGame {
+        seed,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    let make_move = Operation::MakeMove {
+        game_id: 5,
+        direction: Direction::Left,
+        namespace: None,
+    };
+    // The game created with the application belongs to no one.
+    assert!(execute(&chain, application_id, new_game(1)).await);
+    assert!(execute(&chain, application_id, new_game(5)).await);
+    assert!(execute(&chain, application_id, make_move).await);
+
+    let owner_key_pair = chain.key_pair().copy();
+    chain.set_key_pair(KeyPair::generate());
+    assert!(
+        !execute(&chain, application_id, new_game(5)).await,
+        "only the owner can replace a game"
+    );
+    let game: Value = chain
+        .graphql_query(application_id, "query { game(gameId: 5) { moveCount } }")
+        .await;
+    assert_eq!(game, json!({ "game": { "moveCount": 1 } }));
+
+    // Daily games take their id from the seed of the day, which even their owner cannot reuse.
+    chain.set_key_pair(owner_key_pair);
+    assert!(execute(&chain, application_id, new_game(5)).await);
+    let day = 20_025;
+    let now = u64::from(day) * DAY_MICROS;
+    assert!(execute_at_micros(&chain, application_id, now, Operation::NewDailyGame).await);
+    let end_game = Operation::EndGame {
+        game_id: daily_seed(day),
+        namespace: None,
+    };
+    assert!(execute_at_micros(&chain, application_id, now, end_game).await);
+    assert!(
+        !execute_at_micros(&chain, application_id, now, new_game(daily_seed(day))).await,
+        "daily games cannot be replaced"
+    );
+}
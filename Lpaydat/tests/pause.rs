@@ -0,0 +1,110 @@
+//! Integration tests for pausing and resuming games.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute};
+use game2048::{Direction, Game, Game2048Parameters, Operation};
+use linera_sdk::{
+    base::KeyPair,
+    serde_json::{json, Value},
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn paused_games_cannot_be_played_until_resumed() {
+    let (_validator, mut chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let new_game = Operation::NewGame {
+        seed: 3,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    let pause = Operation::PauseGame {
+        game_id: 3,
+        namespace: None,
+    };
+    let resume = Operation::ResumeGame {
+        game_id: 3,
+        namespace: None,
+    };
+    let undo = Operation::Undo {
+        game_id: 3,
+        namespace: None,
+    };
+    assert!(execute(&chain, application_id, new_game).await);
+
+    let query = "query { game(gameId: 3) { status isEnded moveCount boardHex } \
+                 metrics { failures { failure count } } }";
+    let response: Value = chain.graphql_query(application_id, query).await;
+    assert_eq!(response["game"]["status"], json!("Active"));
+    let board = response["game"]["boardHex"].as_str().unwrap();
+    let board = u64::from_str_radix(board, 16).unwrap();
+    let direction = [Direction::Left, Direction::Right]
+        .into_iter()
+        .find(|direction| Game::is_useful_move(board, direction.clone()))
+        .unwrap();
+    let play = Operation::MakeMove {
+        game_id: 3,
+        direction,
+        namespace: None,
+    };
+
+    // Moves on paused games are dropped, and their undos rejected.
+    assert!(execute(&chain, application_id, pause.clone()).await);
+    assert!(
+        !execute(&chain, application_id, pause.clone()).await,
+        "the game is already paused"
+    );
+    assert!(execute(&chain, application_id, play.clone()).await);
+    assert!(!execute(&chain, application_id, undo).await);
+    let response: Value = chain.graphql_query(application_id, query).await;
+    assert_eq!(response["game"]["status"], json!("Paused"));
+    assert_eq!(response["game"]["isEnded"], json!(false));
+    assert_eq!(response["game"]["moveCount"], json!(0));
+    assert_eq!(
+        response["metrics"]["failures"],
+        json!([{ "failure": "GamePaused", "count": 1 }])
+    );
+
+    assert!(execute(&chain, application_id, resume.clone()).await);
+    assert!(
+        !execute(&chain, application_id, resume.clone()).await,
+        "the game is not paused"
+    );
+    assert!(execute(&chain, application_id, play).await);
+    let response: Value = chain.graphql_query(application_id, query).await;
+    assert_eq!(response["game"]["status"], json!("Active"));
+    assert_eq!(response["game"]["moveCount"], json!(1));
+
+    // Paused games can still be ended, and ended games stay over.
+    assert!(execute(&chain, application_id, pause).await);
+    let end_game = Operation::EndGame {
+        game_id: 3,
+        namespace: None,
+    };
+    assert!(execute(&chain, application_id, end_game).await);
+    assert!(!execute(&chain, application_id, resume).await);
+    let response: Value = chain.graphql_query(application_id, query).await;
+    assert_eq!(response["game"]["status"], json!("Ended"));
+    assert_eq!(response["game"]["isEnded"], json!(true));
+
+    let new_game = Operation::NewGame {
+        seed: 5,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    assert!(execute(&chain, application_id, new_game).await);
+    chain.set_key_pair(KeyPair::generate());
+    let pause = Operation::PauseGame {
+        game_id: 5,
+        namespace: None,
+    };
+    assert!(
+        !execute(&chain, application_id, pause).await,
+        "only the owner of a game can pause it"
+    );
+}
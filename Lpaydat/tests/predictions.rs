@@ -0,0 +1,54 @@
+//! Integration tests for the move predictions the service offers frontends.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute};
+use game2048::{Direction, Game2048Parameters, Operation, OperationEnvelope};
+use linera_sdk::{
+    bcs,
+    serde_json::{json, Value},
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn predicted_moves_match_the_contract() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let new_game = Operation::NewGame {
+        seed: 5,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    assert!(execute(&chain, application_id, new_game).await);
+
+    // The bottom row of seed 5 is `2 2 0 0`: it cannot move down.
+    let predict = |direction: &str| {
+        format!(
+            "query {{ predictMove(gameId: 5, direction: \"{direction}\") \
+             {{ operation board scoreDelta willEnd spawnIndex }} }}"
+        )
+    };
+    let prediction: Value = chain.graphql_query(application_id, &predict("Down")).await;
+    assert_eq!(prediction, json!({ "predictMove": null }));
+
+    let prediction: Value = chain.graphql_query(application_id, &predict("Left")).await;
+    let prediction = &prediction["predictMove"];
+    let make_move = Operation::MakeMove {
+        game_id: 5,
+        direction: Direction::Left,
+        namespace: None,
+    };
+    let operation = bcs::to_bytes(&OperationEnvelope::new(&make_move)).unwrap();
+    assert_eq!(prediction["operation"], json!(operation));
+    assert_eq!(prediction["spawnIndex"], json!(0));
+    assert_eq!(prediction["willEnd"], json!(false));
+
+    assert!(execute(&chain, application_id, make_move).await);
+    let game: Value = chain
+        .graphql_query(application_id, "query { game(gameId: 5) { board score } }")
+        .await;
+    assert_eq!(prediction["board"], game["game"]["board"]);
+    assert_eq!(prediction["scoreDelta"], game["game"]["score"]);
+}
@@ -0,0 +1,94 @@
+//! Integration tests for the minimum interval between the moves of a game.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute_all_at_micros};
+use game2048::{Direction, Game, Game2048Parameters, Operation};
+use linera_sdk::{
+    base::ApplicationId,
+    serde_json::{json, Value},
+    test::ActiveChain,
+};
+
+const SECOND: u64 = 1_000_000;
+
+/// Returns a move that changes the board of game `game_id`, so that it is not dropped.
+async fn useful_move(
+    chain: &ActiveChain,
+    application_id: ApplicationId<game2048::Game2048Abi>,
+    game_id: u16,
+) -> Operation {
+    let query = format!("query {{ game(gameId: {game_id}) {{ boardHex }} }}");
+    let response: Value = chain.graphql_query(application_id, &query).await;
+    let board = response["game"]["boardHex"].as_str().unwrap();
+    let board = u64::from_str_radix(board, 16).unwrap();
+    let direction = [
+        Direction::Left,
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+    ]
+    .into_iter()
+    .find(|direction| Game::is_useful_move(board, direction.clone()))
+    .expect("The game is not over");
+    Operation::MakeMove {
+        game_id,
+        direction,
+        namespace: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn moves_faster_than_the_minimum_interval_are_rejected() {
+    let parameters = Game2048Parameters {
+        min_move_interval_micros: SECOND,
+        ..Game2048Parameters::default()
+    };
+    let (_validator, chain, application_id) = deploy(parameters).await;
+    let new_game = Operation::NewGame {
+        seed: 3,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    assert!(execute_all_at_micros(&chain, application_id, SECOND, &[new_game]).await);
+
+    // The first move is not limited, the next ones must wait for the interval.
+    let play = useful_move(&chain, application_id, 3).await;
+    assert!(execute_all_at_micros(&chain, application_id, SECOND, &[play]).await);
+    let play = useful_move(&chain, application_id, 3).await;
+    assert!(
+        !execute_all_at_micros(&chain, application_id, SECOND * 3 / 2, &[play.clone()]).await,
+        "the move comes half a second after the previous one"
+    );
+    assert!(execute_all_at_micros(&chain, application_id, 2 * SECOND, &[play]).await);
+
+    let query = "query { minMoveIntervalMicros game(gameId: 3) { moveCount lastMoveAt } }";
+    let response: Value = chain.graphql_query(application_id, query).await;
+    assert_eq!(response["minMoveIntervalMicros"], json!(SECOND));
+    assert_eq!(response["game"]["moveCount"], json!(2));
+    assert_eq!(
+        response["game"]["lastMoveAt"],
+        json!("1970-01-01T00:00:02.000000Z")
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn moves_are_not_limited_by_default() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let new_game = Operation::NewGame {
+        seed: 3,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    assert!(execute_all_at_micros(&chain, application_id, SECOND, &[new_game]).await);
+    for _ in 0..3 {
+        let play = useful_move(&chain, application_id, 3).await;
+        assert!(execute_all_at_micros(&chain, application_id, SECOND, &[play]).await);
+    }
+}
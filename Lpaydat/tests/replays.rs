@@ -0,0 +1,97 @@
+//! Integration tests for exporting games in the `.r2048` replay format and verifying games
+//! from their move log.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::deploy;
+use game2048::{Direction, Game2048Parameters, Operation, OperationEnvelope, Replay, ReplayError};
+use linera_sdk::serde_json::{self, json, Value};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn exported_replays_verify_without_the_chain() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let new_game = Operation::NewGame {
+        seed: 5,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    chain
+        .add_block(|block| {
+            block.with_operation(application_id, OperationEnvelope::new(&new_game));
+        })
+        .await;
+    for direction in [
+        Direction::Left,
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ] {
+        let operation = Operation::MakeMove {
+            game_id: 5,
+            direction,
+            namespace: None,
+        };
+        chain
+            .add_block(|block| {
+                block.with_operation(application_id, OperationEnvelope::new(&operation));
+            })
+            .await;
+    }
+
+    let response: Value = chain
+        .graphql_query(
+            application_id,
+            "query { replay(gameId: 5) moves(gameId: 5) { board score } unknown: replay(gameId: 9) }",
+        )
+        .await;
+    assert_eq!(response["unknown"], json!(null));
+    let bytes: Vec<u8> = serde_json::from_value(response["replay"].clone()).unwrap();
+    let replay = Replay::decode(&bytes).unwrap();
+    assert_eq!(replay.verify(), Ok(()));
+
+    let turns = response["moves"].as_array().unwrap();
+    assert_eq!((replay.game_id, replay.seed), (5, 5));
+    assert_eq!(replay.moves.len(), turns.len() - 1);
+    let last = replay.checkpoints.last().unwrap();
+    assert_eq!(last.move_index as usize, turns.len() - 1);
+    assert_eq!(json!(last.board), turns[turns.len() - 1]["board"]);
+    assert_eq!(json!(last.score), turns[turns.len() - 1]["score"]);
+
+    // Tampering with a move breaks the final hash.
+    let mut tampered = bytes.clone();
+    tampered[24] ^= 0x3;
+    assert_eq!(Replay::decode(&tampered), Err(ReplayError::HashMismatch));
+    assert_eq!(
+        Replay::decode(&bytes[..bytes.len() - 1]),
+        Err(ReplayError::Truncated)
+    );
+
+    // The game is recomputed from its move log on the chain as well.
+    let verify = |game_id| Operation::VerifyGame { game_id };
+    chain
+        .add_block(|block| {
+            block.with_operation(application_id, OperationEnvelope::new(&verify(5)));
+        })
+        .await;
+    let unknown = chain
+        .try_add_block(|block| {
+            block.with_operation(application_id, OperationEnvelope::new(&verify(9)));
+        })
+        .await;
+    assert!(unknown.is_err(), "unknown games cannot be verified");
+    let response: Value = chain
+        .graphql_query(
+            application_id,
+            "query { game(gameId: 5) { verification leaderboardExclusion } }",
+        )
+        .await;
+    assert_eq!(
+        response,
+        json!({ "game": { "verification": "Verified", "leaderboardExclusion": null } })
+    );
+}
@@ -0,0 +1,70 @@
+//! Integration tests for the reward tokens minted by the token application to the owners of
+//! games, on the chain the token was created on and on others.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy_with_token, execute, token_balance};
+use game2048::{Game2048Abi, Game2048Parameters, GameRewards, Operation};
+use linera_sdk::{base::ApplicationId, serde_json::json, test::ActiveChain};
+use linera_token_creation::Operation as TokenOperation;
+
+/// Starts a game owned by the signer of `chain` and ends it, earning a reward.
+async fn play_game(chain: &ActiveChain, application_id: ApplicationId<Game2048Abi>, seed: u16) {
+    let new_game = Operation::NewGame {
+        seed,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    assert!(execute(chain, application_id, new_game).await);
+    let end_game = Operation::EndGame {
+        game_id: seed,
+        namespace: None,
+    };
+    assert!(execute(chain, application_id, end_game).await);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn games_ending_on_any_chain_earn_reward_tokens() {
+    let (validator, token_chain, application_id, token_id) =
+        deploy_with_token(1_000, |token_id, chain_id| Game2048Parameters {
+            rewards: Some(GameRewards {
+                token_application: token_id.forget_abi(),
+                chain_id,
+                token: "Coin".to_string(),
+                min_score: 0,
+                amount: 10,
+            }),
+            ..Game2048Parameters::default()
+        })
+        .await;
+    let set_minter = TokenOperation::SetMinter {
+        token: "Coin".to_string(),
+        application_id: application_id.forget_abi(),
+        authorized: true,
+    };
+    token_chain
+        .add_block(|block| {
+            block.with_operation(token_id, set_minter);
+        })
+        .await;
+
+    // Games ending on the token's chain are rewarded right away.
+    play_game(&token_chain, application_id, 5).await;
+    assert_eq!(token_balance(&token_chain, token_id).await, json!("1010"));
+
+    // Games ending elsewhere are rewarded by the token's chain, which sends the tokens over.
+    let player_chain = validator.new_chain().await;
+    player_chain.register_application(application_id).await;
+    play_game(&player_chain, application_id, 7).await;
+    token_chain.handle_received_messages().await;
+    player_chain.handle_received_messages().await;
+    assert_eq!(token_balance(&player_chain, token_id).await, json!("10"));
+
+    let query = "query { escrowed(token: \"Coin\") }";
+    let response = token_chain.graphql_query(token_id, query).await;
+    assert_eq!(response, json!({ "escrowed": "10" }));
+}
@@ -0,0 +1,180 @@
+//! Integration tests for buying power-up items and season passes with native tokens.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute, execute_at, fund};
+use game2048::{Game2048Parameters, GameMode, GameTemplate, Operation, PassSettings};
+use linera_sdk::{
+    base::{Amount, Owner},
+    serde_json::{json, Value},
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn items_are_paid_to_the_treasury() {
+    let (validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let owner = Owner::from(chain.public_key());
+    let treasury_chain = validator.new_chain().await;
+    let buy = |item: &str, max_price| Operation::BuyItem {
+        item: item.to_string(),
+        max_price: Amount::from_tokens(max_price),
+    };
+    let set_price = |item: &str, price: Option<u128>| Operation::SetItemPrice {
+        item: item.to_string(),
+        price: price.map(Amount::from_tokens),
+    };
+
+    assert!(
+        !execute(&chain, application_id, buy("undo", 5)).await,
+        "items must be in the catalog"
+    );
+    assert!(execute(&chain, application_id, set_price("undo", Some(2))).await);
+    assert!(execute(&chain, application_id, set_price("hint", Some(0))).await);
+    assert!(
+        !execute(&chain, application_id, buy("undo", 5)).await,
+        "paid items need a treasury"
+    );
+    let set_treasury = Operation::SetTreasury {
+        chain_id: treasury_chain.id(),
+        owner: None,
+    };
+    assert!(execute(&chain, application_id, set_treasury).await);
+    assert!(
+        !execute(&chain, application_id, buy("undo", 1)).await,
+        "the price is above the maximum"
+    );
+    assert!(
+        !execute(&chain, application_id, buy("undo", 2)).await,
+        "the buyer has no tokens yet"
+    );
+
+    fund(&validator, &chain, owner, 3).await;
+
+    assert!(execute(&chain, application_id, buy("undo", 2)).await);
+    assert!(
+        !execute(&chain, application_id, buy("undo", 2)).await,
+        "a single token is left"
+    );
+    assert!(execute(&chain, application_id, buy("hint", 0)).await);
+    assert!(execute(&chain, application_id, buy("hint", 0)).await);
+    assert!(execute(&chain, application_id, set_price("hint", None)).await);
+    assert!(!execute(&chain, application_id, buy("hint", 0)).await);
+
+    let player: Value = chain
+        .graphql_query(
+            application_id,
+            format!("query {{ player(owner: \"{owner}\") {{ inventory {{ item count }} }} }}"),
+        )
+        .await;
+    let mut inventory = player["player"]["inventory"].as_array().unwrap().clone();
+    inventory.sort_by_key(|entry| entry["item"].as_str().unwrap().to_string());
+    assert_eq!(
+        inventory,
+        [
+            json!({ "item": "hint", "count": 2 }),
+            json!({ "item": "undo", "count": 1 }),
+        ]
+    );
+
+    let catalog: Value = chain
+        .graphql_query(
+            application_id,
+            "query { itemCatalog { item price } treasury { chainId owner } }",
+        )
+        .await;
+    assert_eq!(
+        catalog["itemCatalog"],
+        json!([{ "item": "undo", "price": "2." }])
+    );
+    assert_eq!(
+        catalog["treasury"],
+        json!({ "chainId": treasury_chain.id(), "owner": null })
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn season_passes_unlock_premium_modes() {
+    let (validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let owner = Owner::from(chain.public_key());
+    let buy_pass = Operation::BuySeasonPass {
+        max_price: Amount::from_tokens(1),
+    };
+    let new_game = |seed| Operation::NewTemplateGame {
+        template_id: 0,
+        seed,
+    };
+    let classic_game = Operation::NewGame {
+        seed: 9,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+
+    let template = GameTemplate {
+        name: "Marathon".to_string(),
+        mode: GameMode::Endless,
+        board_size: 4,
+        ..GameTemplate::default()
+    };
+    let create_template = Operation::CreateTemplate { template };
+    assert!(execute_at(&chain, application_id, 0, create_template).await);
+    assert!(
+        !execute_at(&chain, application_id, 0, buy_pass.clone()).await,
+        "passes are not on sale yet"
+    );
+    let configure = Operation::ConfigureSeasonPass {
+        settings: PassSettings {
+            price: Amount::from_tokens(1),
+            duration_secs: 60,
+            premium_modes: vec![GameMode::Endless],
+        },
+    };
+    assert!(execute_at(&chain, application_id, 0, configure).await);
+    let set_treasury = Operation::SetTreasury {
+        chain_id: validator.new_chain().await.id(),
+        owner: None,
+    };
+    assert!(execute_at(&chain, application_id, 0, set_treasury).await);
+    assert!(
+        !execute_at(&chain, application_id, 0, new_game(5)).await,
+        "Endless games need a pass"
+    );
+    assert!(execute_at(&chain, application_id, 0, classic_game).await);
+
+    fund(&validator, &chain, owner, 2).await;
+    assert!(execute_at(&chain, application_id, 1, buy_pass.clone()).await);
+    assert!(execute_at(&chain, application_id, 60, new_game(5)).await);
+    assert!(
+        !execute_at(&chain, application_id, 61, new_game(6)).await,
+        "the pass expired"
+    );
+    assert!(execute_at(&chain, application_id, 61, buy_pass.clone()).await);
+    assert!(execute_at(&chain, application_id, 61, new_game(6)).await);
+    assert!(
+        !execute_at(&chain, application_id, 62, buy_pass).await,
+        "the buyer ran out of tokens"
+    );
+
+    let revenue: Value = chain
+        .graphql_query(
+            application_id,
+            "query { passRevenue { salesCount revenue sales { price } } }",
+        )
+        .await;
+    assert_eq!(
+        revenue["passRevenue"],
+        json!({ "salesCount": 2, "revenue": "2.", "sales": [{ "price": "1." }, { "price": "1." }] })
+    );
+    let player: Value = chain
+        .graphql_query(
+            application_id,
+            format!("query {{ player(owner: \"{owner}\") {{ seasonPassExpiresAt }} }}"),
+        )
+        .await;
+    assert_eq!(
+        player["player"]["seasonPassExpiresAt"],
+        json!("1970-01-01T00:02:01.000000Z")
+    );
+}
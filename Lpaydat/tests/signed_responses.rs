@@ -0,0 +1,85 @@
+//! Integration tests for the response claims node operators sign for frontends.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute};
+use game2048::{
+    verify_response, ClaimedValue, Game2048Parameters, Operation, ResponseClaim, SignedResponse,
+};
+use linera_sdk::{
+    base::{KeyPair, Owner},
+    serde_json::{self, json, Value},
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn signed_final_scores_cannot_be_altered() {
+    let operator = KeyPair::generate();
+    let parameters = Game2048Parameters {
+        response_signers: vec![operator.public()],
+        ..Game2048Parameters::default()
+    };
+    let (_validator, chain, application_id) = deploy(parameters).await;
+    let owner = Owner::from(chain.public_key());
+    let new_game = Operation::NewGame {
+        seed: 3,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    assert!(execute(&chain, application_id, new_game).await);
+
+    // The fields are aliased to the names of the claim, to deserialize it from the response.
+    let query = "query { responseSigners finalScoreClaim(gameId: 3) { \
+                 application_id: applicationId chain_id: chainId \
+                 next_block_height: nextBlockHeight value } game(gameId: 3) { score } }";
+    let response: Value = chain.graphql_query(application_id, query).await;
+    assert_eq!(response["responseSigners"], json!([operator.public()]));
+    assert_eq!(
+        response["finalScoreClaim"],
+        json!(null),
+        "the game is not over"
+    );
+
+    let end_game = Operation::EndGame {
+        game_id: 3,
+        namespace: None,
+    };
+    assert!(execute(&chain, application_id, end_game).await);
+    let response: Value = chain.graphql_query(application_id, query).await;
+    let claim: ResponseClaim = serde_json::from_value(response["finalScoreClaim"].clone()).unwrap();
+    assert_eq!(claim.application_id, application_id.forget_abi());
+    assert_eq!(claim.chain_id, chain.id());
+    assert_eq!(
+        claim.value,
+        ClaimedValue::FinalScore {
+            game_id: 3,
+            owner: Some(owner),
+            score: response["game"]["score"].as_u64().unwrap(),
+        }
+    );
+
+    // Only claims signed by a response signer, and left as signed, verify.
+    let signers = [operator.public()];
+    let signed = SignedResponse::new(claim.clone(), &operator);
+    assert!(verify_response(&signed, &signers));
+    let impostor = SignedResponse::new(claim.clone(), &KeyPair::generate());
+    assert!(!verify_response(&impostor, &signers));
+    let mut tampered = signed;
+    tampered.claim.value = ClaimedValue::FinalScore {
+        game_id: 3,
+        owner: Some(owner),
+        score: u64::MAX,
+    };
+    assert!(!verify_response(&tampered, &signers));
+
+    let response: Value = chain
+        .graphql_query(
+            application_id,
+            "query { sponsorshipBalanceClaim(sponsorshipId: 0) { value } }",
+        )
+        .await;
+    assert_eq!(response["sponsorshipBalanceClaim"], json!(null));
+}
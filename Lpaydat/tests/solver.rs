@@ -0,0 +1,87 @@
+//! Integration tests for the expectimax solver and the hints it gives.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::deploy;
+use game2048::{Direction, Game, Game2048Parameters, Operation, OperationEnvelope, Rng, Solver};
+use linera_sdk::serde_json::{json, Value};
+
+/// Plays a game from `seed` until it ends or `max_moves` are made, returning its final board.
+fn play(seed: u16, max_moves: u32, mut next_move: impl FnMut(u64) -> Option<Direction>) -> u64 {
+    let mut board = Game::new(seed).board;
+    let mut rng = Rng::new(u64::from(seed));
+    for _ in 0..max_moves {
+        let Some(direction) = next_move(board) else {
+            break;
+        };
+        board = Game::execute_with(board, direction, &mut rng);
+    }
+    board
+}
+
+#[test]
+fn the_solver_outplays_cycling_through_directions() {
+    let directions = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+    for seed in [1, 2, 3] {
+        let mut turn = 0;
+        let cycled = play(seed, 400, |board| {
+            let useful = (0..4)
+                .map(|offset| directions[(turn + offset) % 4].clone())
+                .find(|direction| Game::is_useful_move(board, direction.clone()));
+            turn += 1;
+            useful
+        });
+        let solved = play(seed, 400, |board| Solver::best_move(board, 2));
+
+        assert!(
+            Game::score(solved) > Game::score(cycled),
+            "seed {seed}: {} <= {}",
+            Game::score(solved),
+            Game::score(cycled)
+        );
+        assert!(Game::max_tile(solved) >= 256, "seed {seed}");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn hints_suggest_the_move_of_the_solver() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let new_game = Operation::NewGame {
+        seed: 5,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    chain
+        .add_block(|block| {
+            block.with_operation(application_id, OperationEnvelope::new(&new_game));
+        })
+        .await;
+
+    let board = Game::new(5).board;
+    let query = "query { \
+                 hint(gameId: 5) { direction evaluation } \
+                 deep: hint(gameId: 5, depth: 10) { direction evaluation } \
+                 missing: hint(gameId: 6) { direction } }";
+    let response: Value = chain.graphql_query(application_id, query).await;
+    let hint = |depth| {
+        let (direction, evaluation) = Solver::search(board, depth).unwrap();
+        json!({ "direction": direction, "evaluation": evaluation })
+    };
+    assert_eq!(
+        response,
+        json!({
+            "hint": hint(2),
+            "deep": hint(3),
+            "missing": null,
+        })
+    );
+}
@@ -0,0 +1,82 @@
+//! Integration tests for the per-game spawn index seeding the generators of the tiles spawned
+//! by moves.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::deploy;
+use game2048::{Direction, Game, Game2048Parameters, Operation, OperationEnvelope, Rng};
+use linera_sdk::serde_json::{json, Value};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn moves_in_the_same_block_spawn_from_distinct_seeds() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let new_game = Operation::NewGame {
+        seed: 5,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    chain
+        .add_block(|block| {
+            block.with_operation(application_id, OperationEnvelope::new(&new_game));
+        })
+        .await;
+
+    let directions = [
+        Direction::Left,
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+    ];
+    chain
+        .add_block(|block| {
+            for direction in &directions {
+                let operation = Operation::MakeMove {
+                    game_id: 5,
+                    direction: direction.clone(),
+                    namespace: None,
+                };
+                block.with_operation(application_id, OperationEnvelope::new(&operation));
+            }
+        })
+        .await;
+    let height = chain.get_tip_height().await.0;
+
+    let response: Value = chain
+        .graphql_query(
+            application_id,
+            "query { game(gameId: 5) { moveCount spawnIndex } \
+             moves(gameId: 5) { spawnIndex spawnedTile board } }",
+        )
+        .await;
+    let moves = response["moves"].as_array().unwrap();
+    let move_count = moves.len() - 1;
+    assert_eq!(
+        response["game"],
+        json!({ "moveCount": move_count, "spawnIndex": move_count })
+    );
+
+    // Replaying the block with the generators of consecutive spawns reproduces every turn.
+    let mut board = Game::new(5).board;
+    let mut seeds = Vec::new();
+    for direction in &directions {
+        if !Game::is_useful_move(board, direction.clone()) {
+            continue;
+        }
+        let spawn_index = seeds.len() as u32;
+        let mut rng = Rng::for_spawn(height, chain.id(), spawn_index);
+        let seed = rng.clone().next_u64();
+        board = Game::execute_with(board, direction.clone(), &mut rng);
+        let turn = &moves[seeds.len() + 1];
+        assert_eq!(turn["spawnIndex"], json!(spawn_index));
+        assert_eq!(turn["board"], json!(board));
+        seeds.push(seed);
+    }
+    assert_eq!(seeds.len(), move_count);
+    seeds.sort();
+    seeds.dedup();
+    assert_eq!(seeds.len(), move_count, "moves of a block share no seed");
+}
@@ -0,0 +1,117 @@
+//! Integration tests for spectating a game of another chain after it started.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute};
+use game2048::{Direction, Game2048Parameters, Operation};
+use linera_sdk::{
+    base::ApplicationId,
+    serde_json::{json, Value},
+    test::ActiveChain,
+};
+
+/// Plays the first of `directions` that changes the board of game 5. The others are dropped
+/// without failing their block, and without counting as moves.
+async fn play(
+    chain: &ActiveChain,
+    application_id: ApplicationId<game2048::Game2048Abi>,
+    directions: &[Direction],
+) {
+    let query = "query { game(gameId: 5) { moveCount } }";
+    let before: Value = chain.graphql_query(application_id, query).await;
+    for direction in directions {
+        let operation = Operation::MakeMove {
+            game_id: 5,
+            direction: direction.clone(),
+            namespace: None,
+        };
+        assert!(execute(chain, application_id, operation).await);
+        let after: Value = chain.graphql_query(application_id, query).await;
+        if after != before {
+            return;
+        }
+    }
+    panic!("No direction changes the board");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn late_spectators_start_from_a_snapshot() {
+    let (validator, player, application_id) = deploy(Game2048Parameters::default()).await;
+    let spectator = validator.new_chain().await;
+    spectator.register_application(application_id).await;
+    let directions = [
+        Direction::Left,
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+    ];
+
+    let new_game = Operation::NewGame {
+        seed: 5,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    assert!(execute(&player, application_id, new_game).await);
+    play(&player, application_id, &directions).await;
+    play(&player, application_id, &directions).await;
+
+    let spectate = Operation::Spectate {
+        chain_id: player.id(),
+        game_id: 5,
+    };
+    assert!(execute(&spectator, application_id, spectate).await);
+    player.handle_received_messages().await;
+    spectator.handle_received_messages().await;
+
+    let game_query = "query { game(gameId: 5) { score isEnded moveCount } spectators(gameId: 5) }";
+    let spectated_query = "query { spectatedGames { chainId gameId moveIndex score isEnded } }";
+    let expected = |game: &Value| {
+        json!({
+            "spectatedGames": [{
+                "chainId": player.id(),
+                "gameId": 5,
+                "moveIndex": game["game"]["moveCount"],
+                "score": game["game"]["score"],
+                "isEnded": game["game"]["isEnded"],
+            }],
+        })
+    };
+
+    // The snapshot holds the two moves played before subscribing.
+    let game = player.graphql_query(application_id, game_query).await;
+    assert_eq!(game["game"]["moveCount"], json!(2));
+    assert_eq!(game["spectators"], json!([spectator.id()]));
+    let spectated = spectator
+        .graphql_query(application_id, spectated_query)
+        .await;
+    assert_eq!(spectated, expected(&game));
+
+    // Turns played since apply on top of it.
+    play(&player, application_id, &directions).await;
+    spectator.handle_received_messages().await;
+    let game = player.graphql_query(application_id, game_query).await;
+    assert_eq!(game["game"]["moveCount"], json!(3));
+    let spectated = spectator
+        .graphql_query(application_id, spectated_query)
+        .await;
+    assert_eq!(spectated, expected(&game));
+
+    // Spectators are dropped when the game ends, after a last snapshot.
+    let end_game = Operation::EndGame {
+        game_id: 5,
+        namespace: None,
+    };
+    assert!(execute(&player, application_id, end_game).await);
+    spectator.handle_received_messages().await;
+    let game = player.graphql_query(application_id, game_query).await;
+    assert_eq!(game["game"]["isEnded"], json!(true));
+    assert_eq!(game["spectators"], json!([]));
+    let spectated = spectator
+        .graphql_query(application_id, spectated_query)
+        .await;
+    assert_eq!(spectated, expected(&game));
+}
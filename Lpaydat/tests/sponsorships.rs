@@ -0,0 +1,111 @@
+//! Integration tests for the game fees of communities and the sponsorships prepaying them for
+//! a set of players.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute_at, fund};
+use game2048::{FeeSettings, Game2048Parameters, GameMode, Operation};
+use linera_sdk::{
+    base::{Amount, Owner, Timestamp},
+    serde_json::{json, Value},
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sponsorships_pay_the_fees_of_their_players() {
+    let (validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    // The creator of the application runs the community, sponsors its players and plays.
+    let owner = Owner::from(chain.public_key());
+    let recipient = Owner::from(validator.new_chain().await.public_key());
+    let club = Some("club".to_string());
+
+    let create_club = Operation::CreateCommunity {
+        community_id: "club".to_string(),
+        name: "Club".to_string(),
+        admins: vec![],
+        default_mode: GameMode::Classic,
+        fees: FeeSettings {
+            game_fee: Amount::from_tokens(1),
+            recipient: Some(recipient),
+        },
+    };
+    let join_club = Operation::JoinCommunity {
+        community_id: "club".to_string(),
+    };
+    let new_game = |seed, sandbox| Operation::NewGame {
+        seed,
+        namespace: club.clone(),
+        sandbox,
+        max_moves: None,
+        board_size: None,
+    };
+    let sponsor = Operation::SponsorGames {
+        community_id: "club".to_string(),
+        players: vec![owner, owner],
+        games_per_player: 2,
+        expires_at: Timestamp::from(100 * 1_000_000),
+    };
+    let withdraw = Operation::WithdrawSponsorship { sponsorship_id: 0 };
+
+    assert!(execute_at(&chain, application_id, 0, create_club).await);
+    assert!(execute_at(&chain, application_id, 0, join_club).await);
+    assert!(
+        !execute_at(&chain, application_id, 0, new_game(5, false)).await,
+        "the player cannot pay the fee"
+    );
+    assert!(
+        execute_at(&chain, application_id, 0, new_game(5, true)).await,
+        "sandbox games are free"
+    );
+    assert!(
+        !execute_at(&chain, application_id, 0, sponsor.clone()).await,
+        "the sponsor cannot prepay the fees"
+    );
+
+    // Duplicate players are sponsored once.
+    fund(&validator, &chain, owner, 2).await;
+    assert!(execute_at(&chain, application_id, 0, sponsor).await);
+    chain.handle_received_messages().await;
+
+    assert!(execute_at(&chain, application_id, 2, new_game(6, false)).await);
+    assert!(execute_at(&chain, application_id, 3, new_game(7, false)).await);
+    assert!(
+        !execute_at(&chain, application_id, 4, new_game(8, false)).await,
+        "the sponsored games are used up"
+    );
+
+    let query = format!(
+        "query {{ \
+         sponsorship(sponsorshipId: 0) {{ sponsor communityId balance credits {{ player games }} }} \
+         player(owner: \"{owner}\") {{ sponsorships }} }}"
+    );
+    let response: Value = chain.graphql_query(application_id, &query).await;
+    assert_eq!(
+        response,
+        json!({
+            "sponsorship": {
+                "sponsor": owner,
+                "communityId": "club",
+                "balance": "0.",
+                "credits": [{ "player": owner, "games": 0 }],
+            },
+            "player": { "sponsorships": [0] },
+        })
+    );
+
+    assert!(
+        !execute_at(&chain, application_id, 99, withdraw.clone()).await,
+        "the sponsorship has not expired"
+    );
+    assert!(execute_at(&chain, application_id, 100, withdraw.clone()).await);
+    assert!(
+        !execute_at(&chain, application_id, 100, withdraw).await,
+        "the sponsorship was withdrawn"
+    );
+    let response: Value = chain.graphql_query(application_id, &query).await;
+    assert_eq!(
+        response,
+        json!({ "sponsorship": null, "player": { "sponsorships": [] } })
+    );
+}
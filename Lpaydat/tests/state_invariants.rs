@@ -0,0 +1,338 @@
+//! Property tests driving random operation sequences through the test validator.
+//!
+//! Each sequence is generated from a fixed seed, so failures are reproducible. After every
+//! block, the application state is compared against a reference model built on the game
+//! library, and cross-module invariants are checked:
+//!
+//! - the score of a game never decreases until the game is created again,
+//! - every game index is listed in exactly one namespace and matches the model,
+//! - move counters, boards and end flags agree with the model,
+//! - community member counts agree with the joins and leaves that were accepted.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use game2048::{CommunityId, Direction, FeeSettings, Game, GameMode, Operation, OperationEnvelope};
+use linera_sdk::{
+    base::ChainId,
+    serde_json::Value,
+    test::{ActiveChain, TestValidator},
+};
+
+const COMMUNITY: &str = "fuzz";
+const SEQUENCES: u64 = 3;
+const BLOCKS_PER_SEQUENCE: usize = 60;
+
+/// A xorshift generator, so sequences only depend on their seed.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct ModelGame {
+    namespace: Option<CommunityId>,
+    sandbox: bool,
+    board: u64,
+    score: u64,
+    is_ended: bool,
+    move_count: u32,
+}
+
+impl ModelGame {
+    fn new(namespace: Option<CommunityId>, sandbox: bool, board: u64) -> Self {
+        ModelGame {
+            namespace,
+            sandbox,
+            board,
+            score: 0,
+            is_ended: false,
+            move_count: 0,
+        }
+    }
+}
+
+/// The expected application state.
+#[derive(Default)]
+struct Model {
+    games: BTreeMap<u16, ModelGame>,
+    /// Games created without a signer, which the signed blocks of the sequence cannot play.
+    unsigned_games: BTreeSet<u16>,
+    is_member: bool,
+    member_count: u32,
+}
+
+impl Model {
+    /// Returns whether the contract is expected to accept `operation`.
+    fn accepts(&self, operation: &Operation) -> bool {
+        match operation {
+            Operation::NewGame {
+                seed, namespace, ..
+            } => {
+                let in_namespace = self
+                    .games
+                    .get(seed)
+                    .map_or(true, |game| game.namespace == *namespace);
+                in_namespace && (namespace.is_none() || self.is_member)
+            }
+            Operation::MakeMove {
+                game_id, namespace, ..
+            }
+            | Operation::EndGame { game_id, namespace } => {
+                !self.unsigned_games.contains(game_id)
+                    && self
+                        .games
+                        .get(game_id)
+                        .is_some_and(|game| game.namespace == *namespace)
+            }
+            _ => true,
+        }
+    }
+
+    /// Applies an accepted `operation` executed in a block at `height` of `chain_id`.
+    fn apply(&mut self, operation: Operation, height: u64, chain_id: ChainId) {
+        match operation {
+            Operation::NewGame {
+                seed,
+                namespace,
+                sandbox,
+                ..
+            } => {
+                let game = ModelGame::new(namespace, sandbox, Game::new(seed).board);
+                self.games.insert(seed, game);
+            }
+            Operation::MakeMove {
+                game_id, direction, ..
+            } => {
+                let game = self.games.get_mut(&game_id).expect("Game exists");
+                if game.is_ended {
+                    return;
+                }
+                // Every accepted move spawns a tile, so the game's spawn index is its move count.
+                let mut rng = game2048::Rng::for_spawn(height, chain_id, game.move_count);
+                let new_board = Game::execute_with(game.board, direction, &mut rng);
+                if new_board != game.board {
+                    game.board = new_board;
+                    game.score = Game::score(new_board);
+                    game.is_ended = Game::is_ended(new_board);
+                    game.move_count += 1;
+                }
+            }
+            Operation::EndGame { game_id, .. } => {
+                self.games.get_mut(&game_id).expect("Game exists").is_ended = true;
+            }
+            Operation::JoinCommunity { .. } => {
+                if !self.is_member {
+                    self.is_member = true;
+                    self.member_count += 1;
+                }
+            }
+            Operation::LeaveCommunity { .. } => {
+                if self.is_member {
+                    self.is_member = false;
+                    self.member_count -= 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn random_operation(&self, rng: &mut Rng) -> Operation {
+        let namespace = |rng: &mut Rng| (rng.below(2) == 0).then(|| COMMUNITY.to_string());
+        let community_id = COMMUNITY.to_string();
+        let roll = rng.below(100);
+        if roll < 20 || self.games.is_empty() {
+            return Operation::NewGame {
+                seed: rng.below(6) as u16 + 1,
+                namespace: namespace(rng),
+                sandbox: rng.below(4) == 0,
+                max_moves: None,
+                board_size: None,
+            };
+        }
+        if roll < 25 {
+            return Operation::JoinCommunity { community_id };
+        }
+        if roll < 30 {
+            return Operation::LeaveCommunity { community_id };
+        }
+
+        let ids: Vec<u16> = self.games.keys().copied().collect();
+        let game_id = ids[rng.below(ids.len() as u64) as usize];
+        // Mostly address games from their own namespace, sometimes from the wrong one.
+        let namespace = if rng.below(10) == 0 {
+            namespace(rng)
+        } else {
+            self.games[&game_id].namespace.clone()
+        };
+        if roll < 35 {
+            return Operation::EndGame { game_id, namespace };
+        }
+        let direction = match rng.below(4) {
+            0 => Direction::Up,
+            1 => Direction::Down,
+            2 => Direction::Left,
+            _ => Direction::Right,
+        };
+        Operation::MakeMove {
+            game_id,
+            direction,
+            namespace,
+        }
+    }
+}
+
+/// Reads the games of `namespace` from the service, keyed by game id.
+async fn query_games(
+    chain: &ActiveChain,
+    application_id: linera_sdk::base::ApplicationId<game2048::Game2048Abi>,
+    namespace: Option<&str>,
+) -> BTreeMap<u16, ModelGame> {
+    let argument = namespace.map_or("null".to_string(), |namespace| format!("\"{namespace}\""));
+    let query = format!(
+        "query {{ games(namespace: {argument}) {{ gameId namespace sandbox board score isEnded moveCount }} }}"
+    );
+    let response = chain.graphql_query(application_id, query).await;
+
+    let mut games = BTreeMap::new();
+    for game in response["games"].as_array().expect("Games are a list") {
+        let game_id = game["gameId"].as_u64().unwrap() as u16;
+        let matrix: [[u16; 4]; 4] =
+            linera_sdk::serde_json::from_value(game["board"].clone()).expect("Board is a matrix");
+        let game = ModelGame {
+            namespace: game["namespace"].as_str().map(str::to_string),
+            sandbox: game["sandbox"].as_bool().unwrap(),
+            board: board_from_matrix(matrix),
+            score: game["score"].as_u64().unwrap(),
+            is_ended: game["isEnded"].as_bool().unwrap(),
+            move_count: game["moveCount"].as_u64().unwrap() as u32,
+        };
+        assert!(
+            games.insert(game_id, game).is_none(),
+            "Game {game_id} is listed twice"
+        );
+    }
+    games
+}
+
+/// Inverse of `Game::convert_to_matrix`.
+fn board_from_matrix(matrix: [[u16; 4]; 4]) -> u64 {
+    (0..16).fold(0, |board, i| {
+        board | u64::from(matrix[3 - i / 4][3 - i % 4]) << (i * 4)
+    })
+}
+
+async fn query_member_count(
+    chain: &ActiveChain,
+    application_id: linera_sdk::base::ApplicationId<game2048::Game2048Abi>,
+) -> u32 {
+    let query = format!("query {{ community(communityId: \"{COMMUNITY}\") {{ memberCount }} }}");
+    let response: Value = chain.graphql_query(application_id, query).await;
+    response["community"]["memberCount"].as_u64().unwrap() as u32
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn random_operation_sequences_preserve_invariants() {
+    let (validator, bytecode_id) = TestValidator::with_current_bytecode::<
+        game2048::Game2048Abi,
+        game2048::Game2048Parameters,
+        u16,
+    >()
+    .await;
+
+    for sequence in 0..SEQUENCES {
+        let mut chain = validator.new_chain().await;
+        let instantiation_game = 1_000 + sequence as u16;
+        let application_id = chain
+            .create_application(
+                bytecode_id,
+                game2048::Game2048Parameters::default(),
+                instantiation_game,
+                vec![],
+            )
+            .await;
+
+        let mut model = Model::default();
+        model
+            .games
+            .insert(instantiation_game, ModelGame::new(None, false, 0));
+        model.unsigned_games.insert(instantiation_game);
+
+        // The chain owner creates the community, which makes it a member.
+        chain
+            .add_block(|block| {
+                block.with_operation(
+                    application_id,
+                    OperationEnvelope::new(&Operation::CreateCommunity {
+                        community_id: COMMUNITY.to_string(),
+                        name: "Fuzz".to_string(),
+                        admins: vec![],
+                        default_mode: GameMode::Classic,
+                        fees: FeeSettings::default(),
+                    }),
+                );
+            })
+            .await;
+        model.is_member = true;
+        model.member_count = 1;
+
+        let mut rng = Rng(0x9E37_79B9_7F4A_7C15 ^ (sequence + 1));
+        for step in 0..BLOCKS_PER_SEQUENCE {
+            let operation = model.random_operation(&mut rng);
+            let description = format!("{operation:?}");
+            let expected = model.accepts(&operation);
+
+            let result = chain
+                .try_add_block(|block| {
+                    block.with_operation(application_id, OperationEnvelope::new(&operation));
+                })
+                .await;
+            assert_eq!(
+                result.is_ok(),
+                expected,
+                "Sequence {sequence}, step {step}: unexpected outcome for {description}"
+            );
+            if let Ok(certificate) = result {
+                let value = certificate.value();
+                model.apply(operation, value.height().0, value.chain_id());
+            }
+
+            let previous: BTreeMap<u16, ModelGame> = model.games.clone();
+            let mut games = query_games(&chain, application_id, None).await;
+            let community_games = query_games(&chain, application_id, Some(COMMUNITY)).await;
+            for (game_id, game) in community_games {
+                assert!(
+                    games.insert(game_id, game).is_none(),
+                    "Game {game_id} is listed in two namespaces"
+                );
+            }
+
+            assert_eq!(
+                games, model.games,
+                "Sequence {sequence}, step {step}: state diverged after {description}"
+            );
+            for (game_id, game) in &games {
+                if let Some(before) = previous.get(game_id) {
+                    if game.move_count >= before.move_count {
+                        assert!(game.score >= before.score, "Score of {game_id} decreased");
+                    }
+                }
+            }
+            assert_eq!(
+                query_member_count(&chain, application_id).await,
+                model.member_count
+            );
+        }
+    }
+}
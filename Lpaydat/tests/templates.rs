@@ -0,0 +1,188 @@
+//! Integration tests for game templates and their grouped results.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute_at_micros};
+use game2048::{Difficulty, Direction, Game2048Parameters, GameMode, GameTemplate, Operation};
+use linera_sdk::{
+    base::{Owner, Timestamp},
+    serde_json::{json, Value},
+};
+
+const SECOND: u64 = 1_000_000;
+
+fn workshop(board_size: u8) -> Operation {
+    Operation::CreateTemplate {
+        template: GameTemplate {
+            name: "Workshop".to_string(),
+            mode: GameMode::Blitz,
+            board_size,
+            difficulty: Difficulty::Easy,
+            time_limit_secs: Some(60),
+        },
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn template_games_share_settings_and_results() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let organizer = Owner::from(chain.public_key());
+    let new_game = |template_id, seed| Operation::NewTemplateGame { template_id, seed };
+    let make_move = |game_id| Operation::MakeMove {
+        game_id,
+        direction: Direction::Left,
+        namespace: None,
+    };
+
+    assert!(
+        !execute_at_micros(&chain, application_id, 0, workshop(7)).await,
+        "boards are at most 6x6"
+    );
+    assert!(execute_at_micros(&chain, application_id, 0, workshop(4)).await);
+    assert!(
+        !execute_at_micros(&chain, application_id, 0, new_game(1, 5)).await,
+        "games start from existing templates"
+    );
+    assert!(execute_at_micros(&chain, application_id, 0, new_game(0, 5)).await);
+    assert!(execute_at_micros(&chain, application_id, 0, new_game(0, 6)).await);
+    assert!(execute_at_micros(&chain, application_id, SECOND, make_move(5)).await);
+    // Moving once the time limit is over ends the game instead.
+    assert!(execute_at_micros(&chain, application_id, 61 * SECOND, make_move(6)).await);
+
+    let game: Value = chain
+        .graphql_query(
+            application_id,
+            "query { game(gameId: 6) { templateId mode isEnded moveCount } }",
+        )
+        .await;
+    assert_eq!(
+        game["game"],
+        json!({ "templateId": 0, "mode": "Blitz", "isEnded": true, "moveCount": 0 })
+    );
+
+    let results: Value = chain
+        .graphql_query(
+            application_id,
+            "query { templateResults(templateId: 0) { \
+             organizer template { name difficulty timeLimitSecs } \
+             finishedCount games { gameId score isEnded } } }",
+        )
+        .await;
+    let results = &results["templateResults"];
+    assert_eq!(results["organizer"], json!(organizer));
+    assert_eq!(
+        results["template"],
+        json!({ "name": "Workshop", "difficulty": "Easy", "timeLimitSecs": 60 })
+    );
+    assert_eq!(results["finishedCount"], json!(1));
+
+    let games = results["games"].as_array().unwrap();
+    let mut game_ids = games
+        .iter()
+        .map(|game| game["gameId"].as_u64().unwrap())
+        .collect::<Vec<_>>();
+    game_ids.sort();
+    assert_eq!(game_ids, [5, 6]);
+    let scores = games.iter().map(|game| game["score"].as_u64().unwrap());
+    let scores = scores.collect::<Vec<_>>();
+    assert!(scores.windows(2).all(|pair| pair[0] >= pair[1]));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn join_codes_restrict_templates_to_participants() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let participant = Owner::from(chain.public_key());
+    let mint = |capacity, expires_at: u64| Operation::MintJoinCode {
+        template_id: 0,
+        capacity,
+        expires_at: Timestamp::from(expires_at),
+    };
+    let join = |code: &str| Operation::JoinWithCode {
+        code: code.to_string(),
+    };
+    let new_game = |seed| Operation::NewTemplateGame {
+        template_id: 0,
+        seed,
+    };
+
+    assert!(execute_at_micros(&chain, application_id, 0, workshop(4)).await);
+    assert!(execute_at_micros(&chain, application_id, 0, new_game(5)).await);
+    assert!(
+        !execute_at_micros(&chain, application_id, SECOND, mint(0, 60 * SECOND)).await,
+        "codes admit at least one participant"
+    );
+    assert!(
+        !execute_at_micros(&chain, application_id, SECOND, mint(1, SECOND)).await,
+        "codes expire in the future"
+    );
+    assert!(execute_at_micros(&chain, application_id, SECOND, mint(1, 60 * SECOND)).await);
+    assert!(execute_at_micros(&chain, application_id, SECOND, mint(1, 2 * SECOND)).await);
+    assert!(
+        !execute_at_micros(&chain, application_id, SECOND, new_game(6)).await,
+        "minting a code makes the template invite-only"
+    );
+
+    let response: Value = chain
+        .graphql_query(
+            application_id,
+            "query { joinCodes(templateId: 0) { code capacity uses } }",
+        )
+        .await;
+    let join_codes = response["joinCodes"].as_array().unwrap();
+    assert_eq!(join_codes.len(), 2);
+    let codes = join_codes
+        .iter()
+        .map(|join_code| join_code["code"].as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_ne!(codes[0], codes[1]);
+
+    assert!(
+        !execute_at_micros(&chain, application_id, SECOND, join("NOTACODE")).await,
+        "codes must have been minted"
+    );
+    let mut joined = false;
+    for code in &codes {
+        let expired = !execute_at_micros(&chain, application_id, 3 * SECOND, join(code)).await;
+        if !expired {
+            assert!(
+                !joined,
+                "one of the codes expired after two seconds, the other one admits one participant"
+            );
+            joined = true;
+            // Joining again does not use up the code, which is full, and codes are not
+            // case-sensitive.
+            let lower_case = code.to_lowercase();
+            assert!(execute_at_micros(&chain, application_id, 3 * SECOND, join(&lower_case)).await);
+        }
+    }
+    assert!(joined);
+    assert!(execute_at_micros(&chain, application_id, 3 * SECOND, new_game(6)).await);
+
+    let response: Value = chain
+        .graphql_query(
+            application_id,
+            "query { joinCodes(templateId: 0) { uses } }",
+        )
+        .await;
+    let mut uses = response["joinCodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|join_code| join_code["uses"].as_u64().unwrap())
+        .collect::<Vec<_>>();
+    uses.sort();
+    assert_eq!(uses, [0, 1]);
+
+    let results: Value = chain
+        .graphql_query(
+            application_id,
+            "query { templateResults(templateId: 0) { inviteOnly participants games { gameId } } }",
+        )
+        .await;
+    let results = &results["templateResults"];
+    assert_eq!(results["inviteOnly"], json!(true));
+    assert_eq!(results["participants"], json!([participant]));
+    assert_eq!(results["games"].as_array().unwrap().len(), 2);
+}
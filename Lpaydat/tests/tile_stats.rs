@@ -0,0 +1,74 @@
+//! Integration tests for the tile and merge statistics of games.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute};
+use game2048::{BoardRepr, Direction, Game, Game2048Parameters, Operation};
+use linera_sdk::{
+    base::ApplicationId,
+    serde_json::{json, Value},
+    test::ActiveChain,
+};
+
+async fn board(chain: &ActiveChain, application_id: ApplicationId<game2048::Game2048Abi>) -> u64 {
+    let response: Value = chain
+        .graphql_query(application_id, "query { game(gameId: 3) { boardHex } }")
+        .await;
+    u64::from_str_radix(response["game"]["boardHex"].as_str().unwrap(), 16).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn moves_record_the_tiles_and_merges_they_reach() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let new_game = Operation::NewGame {
+        seed: 3,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    assert!(execute(&chain, application_id, new_game).await);
+    let query = "query { game(gameId: 3) { highestTile totalMerges largestMerge } }";
+    let response: Value = chain.graphql_query(application_id, query).await;
+    let mut highest_tile = Game::max_tile(board(&chain, application_id).await);
+    assert_eq!(
+        response["game"],
+        json!({ "highestTile": highest_tile, "totalMerges": 0, "largestMerge": 0 })
+    );
+
+    // The statistics follow the merges of every move played.
+    let mut total_merges = 0;
+    let mut largest_merge = 0;
+    for direction in [Direction::Left, Direction::Down]
+        .into_iter()
+        .cycle()
+        .take(20)
+    {
+        let previous_board = board(&chain, application_id).await;
+        if !Game::is_useful_move(previous_board, direction.clone()) {
+            continue;
+        }
+        let merges = previous_board.merges(direction.clone());
+        let play = Operation::MakeMove {
+            game_id: 3,
+            direction,
+            namespace: None,
+        };
+        assert!(execute(&chain, application_id, play).await);
+        total_merges += merges.len() as u64;
+        largest_merge = merges.into_iter().fold(largest_merge, u64::max);
+        highest_tile = highest_tile.max(Game::max_tile(board(&chain, application_id).await));
+    }
+    assert!(total_merges > 0);
+    let response: Value = chain.graphql_query(application_id, query).await;
+    assert_eq!(
+        response["game"],
+        json!({
+            "highestTile": highest_tile,
+            "totalMerges": total_merges,
+            "largestMerge": largest_merge,
+        })
+    );
+}
@@ -0,0 +1,269 @@
+//! Integration tests for tournaments, ranking the games their entrants play from the same
+//! seed.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute, execute_at};
+use game2048::{Direction, Game2048Parameters, Operation};
+use linera_sdk::{
+    base::{ApplicationId, KeyPair, Owner, Timestamp},
+    serde_json::{json, Value},
+    test::ActiveChain,
+};
+
+/// Plays game `game_id` until it ends, cycling through the directions, and returns its
+/// final score.
+async fn play_out(
+    chain: &ActiveChain,
+    application_id: ApplicationId<game2048::Game2048Abi>,
+    game_id: u16,
+) -> u64 {
+    let query = format!("query {{ game(gameId: {game_id}) {{ isEnded score }} }}");
+    for direction in [
+        Direction::Left,
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+    ]
+    .into_iter()
+    .cycle()
+    .take(16)
+    {
+        let game: Value = chain.graphql_query(application_id, &query).await;
+        if game["game"]["isEnded"] == true {
+            return game["game"]["score"].as_u64().unwrap();
+        }
+        let make_move = Operation::MakeMove {
+            game_id,
+            direction,
+            namespace: None,
+        };
+        assert!(execute(chain, application_id, make_move).await);
+    }
+    panic!("game {game_id} did not end");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn tournaments_rank_entrants_playing_the_same_seed() {
+    let (_validator, mut chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let organizer = Owner::from(chain.public_key());
+    let create = |move_limit| Operation::CreateTournament {
+        name: "Spring cup".to_string(),
+        seed: 9,
+        move_limit,
+    };
+    let join = Operation::JoinTournament { tournament_id: 0 };
+    let submit = Operation::SubmitResult { tournament_id: 0 };
+    let close = Operation::CloseTournament { tournament_id: 0 };
+
+    assert!(
+        !execute(&chain, application_id, create(0)).await,
+        "tournament games need at least one move"
+    );
+    assert!(execute(&chain, application_id, create(3)).await);
+
+    // The organizer enters first, taking game 9; the second entrant gets game 10.
+    assert!(execute(&chain, application_id, join.clone()).await);
+    assert!(
+        !execute(&chain, application_id, join.clone()).await,
+        "entrants enter once"
+    );
+    let organizer_key_pair = chain.key_pair().copy();
+    let entrant_key_pair = KeyPair::generate();
+    chain.set_key_pair(entrant_key_pair.copy());
+    let entrant = Owner::from(chain.public_key());
+    assert!(execute(&chain, application_id, join.clone()).await);
+    assert!(
+        !execute(&chain, application_id, submit.clone()).await,
+        "the game of the entrant is not over"
+    );
+
+    let query = "query { game(gameId: 10) { seed maxMoves undosLeft } }";
+    let game: Value = chain.graphql_query(application_id, query).await;
+    assert_eq!(
+        game,
+        json!({ "game": { "seed": 9, "maxMoves": 3, "undosLeft": 0 } })
+    );
+
+    let entrant_score = play_out(&chain, application_id, 10).await;
+    assert!(execute(&chain, application_id, submit.clone()).await);
+    assert!(
+        !execute(&chain, application_id, submit.clone()).await,
+        "results are submitted once"
+    );
+    assert!(
+        !execute(&chain, application_id, close.clone()).await,
+        "only the organizer can close the tournament"
+    );
+
+    chain.set_key_pair(organizer_key_pair);
+    let organizer_score = play_out(&chain, application_id, 9).await;
+    assert!(execute(&chain, application_id, submit.clone()).await);
+    assert!(execute(&chain, application_id, close).await);
+    chain.set_key_pair(entrant_key_pair);
+    assert!(
+        !execute(&chain, application_id, join).await,
+        "closed tournaments take no entrants"
+    );
+
+    let query = "query { tournament(tournamentId: 0) { \
+                 organizer name seed moveLimit isClosed entrantCount \
+                 standings { rank player gameId score profile { owner } } } \
+                 missing: tournament(tournamentId: 1) { name } }";
+    let response: Value = chain.graphql_query(application_id, query).await;
+    // The results were submitted at the same time, so equal scores are ordered by player.
+    let mut standings = vec![
+        (entrant, 10, entrant_score),
+        (organizer, 9, organizer_score),
+    ];
+    standings.sort_by_key(|(player, _, score)| (std::cmp::Reverse(*score), *player));
+    let mut ranks = vec![1, 2];
+    if entrant_score == organizer_score {
+        ranks[1] = 1;
+    }
+    let standings = standings
+        .into_iter()
+        .zip(ranks)
+        .map(|((player, game_id, score), rank)| {
+            json!({
+                "rank": rank,
+                "player": player,
+                "gameId": game_id,
+                "score": score,
+                "profile": { "owner": player },
+            })
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        response,
+        json!({
+            "tournament": {
+                "organizer": organizer,
+                "name": "Spring cup",
+                "seed": 9,
+                "moveLimit": 3,
+                "isClosed": true,
+                "entrantCount": 2,
+                "standings": standings,
+            },
+            "missing": null,
+        })
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn games_in_flight_end_with_their_tournament() {
+    let (_validator, mut chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let create = Operation::CreateTournament {
+        name: "Night cup".to_string(),
+        seed: 9,
+        move_limit: 50,
+    };
+    let set_end = |seconds: u64| Operation::SetTournamentEnd {
+        tournament_id: 0,
+        ends_at: Timestamp::from(seconds * 1_000_000),
+    };
+    let join = Operation::JoinTournament { tournament_id: 0 };
+    let submit = Operation::SubmitResult { tournament_id: 0 };
+    let sweep = Operation::EndTournamentGames { tournament_id: 0 };
+    let make_move = |game_id, direction| Operation::MakeMove {
+        game_id,
+        direction,
+        namespace: None,
+    };
+
+    assert!(execute_at(&chain, application_id, 1, create).await);
+    assert!(
+        !execute_at(&chain, application_id, 2, set_end(2)).await,
+        "tournaments end in the future"
+    );
+    assert!(execute_at(&chain, application_id, 2, set_end(100)).await);
+
+    // The organizer plays game 9, the mover game 10 and the idler game 11, which never
+    // receives another operation.
+    let organizer_key_pair = chain.key_pair().copy();
+    let mover_key_pair = KeyPair::generate();
+    let idler_key_pair = KeyPair::generate();
+    assert!(execute_at(&chain, application_id, 3, join.clone()).await);
+    chain.set_key_pair(mover_key_pair.copy());
+    assert!(
+        !execute_at(&chain, application_id, 3, set_end(200)).await,
+        "only the organizer sets the end of the tournament"
+    );
+    assert!(execute_at(&chain, application_id, 3, join.clone()).await);
+    assert!(execute_at(&chain, application_id, 4, make_move(10, Direction::Left)).await);
+    chain.set_key_pair(idler_key_pair.copy());
+    assert!(execute_at(&chain, application_id, 4, join.clone()).await);
+    chain.set_key_pair(organizer_key_pair.copy());
+    for direction in [Direction::Up, Direction::Left] {
+        assert!(execute_at(&chain, application_id, 5, make_move(9, direction)).await);
+    }
+
+    let query = "query { organizer: game(gameId: 9) { isEnded score moveCount } \
+                 mover: game(gameId: 10) { isEnded score moveCount } \
+                 idler: game(gameId: 11) { isEnded score moveCount } }";
+    let before: Value = chain.graphql_query(application_id, query).await;
+    for game in ["organizer", "mover", "idler"] {
+        assert_eq!(before[game]["isEnded"], json!(false));
+    }
+
+    // Once the tournament ended, nobody joins, and the next move of a game ends it instead.
+    chain.set_key_pair(KeyPair::generate());
+    assert!(
+        !execute_at(&chain, application_id, 100, join).await,
+        "ended tournaments take no entrants"
+    );
+    chain.set_key_pair(mover_key_pair);
+    assert!(execute_at(&chain, application_id, 101, make_move(10, Direction::Right)).await);
+    let after: Value = chain.graphql_query(application_id, query).await;
+    assert_eq!(after["mover"]["isEnded"], json!(true));
+    assert_eq!(after["mover"]["moveCount"], before["mover"]["moveCount"]);
+    assert_eq!(after["organizer"]["isEnded"], json!(false));
+    assert!(
+        !execute_at(&chain, application_id, 101, submit.clone()).await,
+        "the result of the mover was recorded when their game ended"
+    );
+
+    // Admins sweep the games nobody touched since.
+    assert!(
+        !execute_at(&chain, application_id, 102, sweep.clone()).await,
+        "only admins sweep tournaments"
+    );
+    chain.set_key_pair(organizer_key_pair);
+    assert!(execute_at(&chain, application_id, 102, sweep).await);
+    let after: Value = chain.graphql_query(application_id, query).await;
+    assert_eq!(after, {
+        let mut ended = before.clone();
+        for game in ["organizer", "mover", "idler"] {
+            ended[game]["isEnded"] = json!(true);
+        }
+        ended
+    });
+    assert!(!execute_at(&chain, application_id, 103, submit).await);
+    let close = Operation::CloseTournament { tournament_id: 0 };
+    assert!(execute_at(&chain, application_id, 103, close).await);
+
+    let query =
+        "query { tournament(tournamentId: 0) { status endsAt standings { gameId score } } }";
+    let response: Value = chain.graphql_query(application_id, query).await;
+    let tournament = &response["tournament"];
+    assert_eq!(tournament["status"], json!("Closed"));
+    assert!(tournament["endsAt"].is_string());
+    let mut scores = tournament["standings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|standing| (standing["gameId"].clone(), standing["score"].clone()))
+        .collect::<Vec<_>>();
+    scores.sort_by_key(|(game_id, _)| game_id.as_u64());
+    assert_eq!(
+        scores,
+        [
+            (json!(9), before["organizer"]["score"].clone()),
+            (json!(10), before["mover"]["score"].clone()),
+            (json!(11), json!(0)),
+        ]
+    );
+}
@@ -0,0 +1,81 @@
+//! Integration tests for the scripted tutorials and the achievement completing them unlocks.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute};
+use game2048::{Direction, Game2048Parameters, Operation, TUTORIALS};
+use linera_sdk::{
+    base::Owner,
+    serde_json::{json, Value},
+};
+
+/// A move that is not the one `expected`.
+fn other_than(expected: &Direction) -> Direction {
+    match expected {
+        Direction::Up => Direction::Down,
+        _ => Direction::Up,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn completing_every_tutorial_unlocks_the_achievement() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let owner = Owner::from(chain.public_key());
+    let play = |tutorial, direction| Operation::PlayTutorial {
+        tutorial,
+        direction,
+    };
+    let query = format!(
+        "query {{ player(owner: \"{owner}\") {{ tutorialSteps achievements }} \
+         metrics {{ failures {{ failure count }} }} }}"
+    );
+
+    let response: Value = chain
+        .graphql_query(
+            application_id,
+            "query { tutorials { tutorial name steps { expected } } }",
+        )
+        .await;
+    let tutorials = response["tutorials"].as_array().unwrap();
+    assert_eq!(tutorials.len(), TUTORIALS.len());
+    assert_eq!(tutorials[0]["name"], json!(TUTORIALS[0].name));
+
+    // A wrong move is counted and leaves the step to play.
+    let first = &TUTORIALS[0].steps[0].expected;
+    assert!(execute(&chain, application_id, play(0, other_than(first))).await);
+    assert!(execute(&chain, application_id, play(0, first.clone())).await);
+    let response: Value = chain.graphql_query(application_id, &query).await;
+    assert_eq!(response["player"]["tutorialSteps"], json!([1, 0, 0]));
+    assert_eq!(
+        response["metrics"]["failures"],
+        json!([{ "failure": "WrongTutorialMove", "count": 1 }])
+    );
+
+    // The achievement is only unlocked once the last tutorial is complete.
+    for (index, tutorial) in (0..).zip(&TUTORIALS) {
+        // The first step of the first tutorial was played above.
+        let done = if index == 0 { 1 } else { 0 };
+        for step in &tutorial.steps[done..] {
+            let response: Value = chain.graphql_query(application_id, &query).await;
+            assert_eq!(response["player"]["achievements"], json!([]));
+            assert!(execute(&chain, application_id, play(index, step.expected.clone())).await);
+        }
+        assert!(
+            !execute(&chain, application_id, play(index, Direction::Left)).await,
+            "completed tutorials cannot be played again"
+        );
+    }
+    let response: Value = chain.graphql_query(application_id, &query).await;
+    assert_eq!(response["player"]["tutorialSteps"], json!([2, 3, 3]));
+    assert_eq!(
+        response["player"]["achievements"],
+        json!(["TutorialComplete"])
+    );
+
+    assert!(
+        !execute(&chain, application_id, play(3, Direction::Left)).await,
+        "tutorial 3 does not exist"
+    );
+}
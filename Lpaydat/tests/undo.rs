@@ -0,0 +1,120 @@
+//! Integration tests for taking moves back.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{deploy, execute};
+use game2048::{Direction, Game, Game2048Parameters, Operation, Replay, UNDO_LIMIT};
+use linera_sdk::{
+    base::ApplicationId,
+    serde_json::{self, json, Value},
+    test::ActiveChain,
+};
+
+/// Returns the game's move count, undos left, and its latest turn.
+async fn position(
+    chain: &ActiveChain,
+    application_id: ApplicationId<game2048::Game2048Abi>,
+) -> Value {
+    let response: Value = chain
+        .graphql_query(
+            application_id,
+            "query { game(gameId: 5) { moveCount undosLeft } moves(gameId: 5) { board score } }",
+        )
+        .await;
+    let turns = response["moves"].as_array().unwrap();
+    json!({
+        "moveCount": response["game"]["moveCount"],
+        "undosLeft": response["game"]["undosLeft"],
+        "turn": turns[turns.len() - 1],
+    })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn undos_restore_the_previous_position() {
+    let (_validator, chain, application_id) = deploy(Game2048Parameters::default()).await;
+    let make_move = |direction| Operation::MakeMove {
+        game_id: 5,
+        direction,
+        namespace: None,
+    };
+    let undo = || Operation::Undo {
+        game_id: 5,
+        namespace: None,
+    };
+    let new_game = Operation::NewGame {
+        seed: 5,
+        namespace: None,
+        sandbox: false,
+        max_moves: None,
+        board_size: None,
+    };
+    assert!(execute(&chain, application_id, new_game).await);
+    let created = position(&chain, application_id).await;
+    assert_eq!(created["undosLeft"], json!(UNDO_LIMIT));
+
+    assert!(execute(&chain, application_id, make_move(Direction::Left)).await);
+    let first = position(&chain, application_id).await;
+    let board = first["turn"]["board"].as_u64().unwrap();
+    let direction = [Direction::Up, Direction::Right, Direction::Down]
+        .into_iter()
+        .find(|direction| Game::is_useful_move(board, direction.clone()))
+        .unwrap();
+    assert!(execute(&chain, application_id, make_move(direction)).await);
+
+    assert!(execute(&chain, application_id, undo()).await);
+    let mut expected = first.clone();
+    expected["undosLeft"] = json!(UNDO_LIMIT - 1);
+    assert_eq!(position(&chain, application_id).await, expected);
+    assert!(execute(&chain, application_id, undo()).await);
+    let mut expected = created.clone();
+    expected["undosLeft"] = json!(UNDO_LIMIT - 2);
+    assert_eq!(position(&chain, application_id).await, expected);
+    assert_eq!(
+        expected["turn"]["board"],
+        json!(Game::new(5).board),
+        "the game is back to its initial board"
+    );
+    assert!(
+        !execute(&chain, application_id, undo()).await,
+        "there is no move left to take back"
+    );
+
+    assert!(execute(&chain, application_id, make_move(Direction::Left)).await);
+    assert!(execute(&chain, application_id, undo()).await);
+    assert!(execute(&chain, application_id, make_move(Direction::Left)).await);
+    assert!(
+        !execute(&chain, application_id, undo()).await,
+        "every undo was used"
+    );
+
+    let response: Value = chain
+        .graphql_query(
+            application_id,
+            "query { history(gameId: 5) { moveIndex } moves(gameId: 5) { direction } \
+             replay(gameId: 5) }",
+        )
+        .await;
+    assert_eq!(
+        response["history"],
+        json!([
+            { "moveIndex": 1 },
+            { "moveIndex": 2 },
+            { "moveIndex": 1 },
+            { "moveIndex": 1 },
+        ])
+    );
+    let directions = response["moves"].as_array().unwrap();
+    let undos = directions[1..]
+        .iter()
+        .filter(|turn| turn["direction"].is_null())
+        .count();
+    assert_eq!((directions.len(), undos), (8, 3));
+
+    // Replays only keep the moves in effect.
+    let bytes: Vec<u8> = serde_json::from_value(response["replay"].clone()).unwrap();
+    let replay = Replay::decode(&bytes).unwrap();
+    assert_eq!(replay.moves.len(), 1);
+    assert_eq!(replay.verify(), Ok(()));
+}
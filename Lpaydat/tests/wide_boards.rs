@@ -0,0 +1,39 @@
+//! Equivalence of the wide board encoding with the nibble encoding `Game` plays with, on the
+//! boards both can hold.
+
+use game2048::{gen_range, Direction, Game, WideBoard};
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Left,
+    Direction::Down,
+    Direction::Right,
+];
+
+#[test]
+fn wide_boards_slide_like_nibble_boards() {
+    for seed in [0, 1, 7, 2048, u16::MAX] {
+        let mut board = Game::new(seed).board;
+        for height in 0..2_000_u32 {
+            if Game::is_ended(board) {
+                break;
+            }
+            let wide = WideBoard::from_nibbles(board);
+            assert_eq!(wide.to_nibbles(), Some(board));
+            assert_eq!(wide.max_tile(), Game::max_tile(board));
+            for direction in &DIRECTIONS {
+                let slid = wide.slide(direction.clone()).to_nibbles();
+                let moved = Game::move_board(board, direction.clone());
+                assert_eq!(slid, Some(moved), "seed {seed}, height {height}");
+            }
+
+            let move_seed = gen_range(&height.to_string(), 0, u16::MAX);
+            let direction = DIRECTIONS[usize::from(move_seed % 4)].clone();
+            board = Game {
+                board,
+                seed: move_seed,
+            }
+            .execute(direction);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+import { describe, expect, it } from "vitest";
+import { OptimisticGame, predictMove, type Board, type Direction } from "./optimistic";
+
+const board: Board = [
+  [1, 1, 2, 0],
+  [0, 3, 0, 3],
+  [2, 0, 0, 2],
+  [0, 0, 0, 1],
+];
+
+describe("predictMove", () => {
+  it("merges each pair of tiles once, towards the direction", () => {
+    expect(predictMove([[1, 1, 1, 1], [2, 2, 2, 0], [0, 0, 0, 0], [3, 0, 3, 0]], "Left")).toEqual([
+      [2, 2, 0, 0],
+      [3, 2, 0, 0],
+      [0, 0, 0, 0],
+      [4, 0, 0, 0],
+    ]);
+    expect(predictMove(board, "Right")).toEqual([
+      [0, 0, 2, 2],
+      [0, 0, 0, 4],
+      [0, 0, 0, 3],
+      [0, 0, 0, 1],
+    ]);
+  });
+
+  it("slides columns for vertical moves", () => {
+    expect(predictMove(board, "Up")).toEqual([
+      [1, 1, 2, 3],
+      [2, 3, 0, 2],
+      [0, 0, 0, 1],
+      [0, 0, 0, 0],
+    ]);
+    expect(predictMove(board, "Down")).toEqual([
+      [0, 0, 0, 0],
+      [0, 0, 0, 3],
+      [1, 1, 0, 2],
+      [2, 3, 2, 1],
+    ]);
+  });
+
+  it("caps tiles at the largest exponent a board cell holds", () => {
+    expect(predictMove([[15, 15, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0]], "Left")[0]).toEqual([
+      15, 0, 0, 0,
+    ]);
+  });
+});
+
+// `board` after `direction`, with a 1 spawned in its first empty cell, as the chain confirms it.
+const confirmed = (board: Board, direction: Direction): Board => {
+  const next = predictMove(board, direction).map((row) => [...row]);
+  const row = next.findIndex((tiles) => tiles.includes(0));
+  next[row][next[row].indexOf(0)] = 1;
+  return next;
+};
+
+describe("OptimisticGame", () => {
+  const start = () => {
+    const events: string[] = [];
+    const game = new OptimisticGame({
+      onConfirm: (moveCount) => events.push(`confirm ${moveCount}`),
+      onRollback: (moveCount) => events.push(`rollback ${moveCount}`),
+    });
+    game.reconcile(board, 0);
+    return { game, events };
+  };
+
+  it("shows moves before the chain confirms them", () => {
+    const { game, events } = start();
+    expect(game.play("Left")).toBe(true);
+    expect(game.current).toEqual(predictMove(board, "Left"));
+
+    const actual = confirmed(board, "Left");
+    game.reconcile(actual, 1);
+    expect(events).toEqual(["confirm 1"]);
+    expect(game.current).toEqual(actual);
+  });
+
+  it("ignores moves that change nothing", () => {
+    const { game } = start();
+    const full = [
+      [1, 2, 1, 2],
+      [2, 1, 2, 1],
+      [1, 2, 1, 2],
+      [2, 1, 2, 1],
+    ];
+    game.reconcile(full, 1);
+    expect(game.play("Left")).toBe(false);
+    expect(game.current).toEqual(full);
+  });
+
+  it("shows the chain's board when it differs from the prediction", () => {
+    const { game, events } = start();
+    game.play("Left");
+    const actual = predictMove(board, "Right");
+    game.reconcile(actual, 1);
+    expect(events).toEqual(["rollback 1"]);
+    expect(game.current).toEqual(actual);
+  });
+
+  it("predicts the moves in flight again on top of the confirmed board", () => {
+    const { game, events } = start();
+    game.play("Left");
+    game.play("Down");
+    const actual = confirmed(board, "Left");
+    game.reconcile(actual, 1);
+    expect(events).toEqual(["confirm 1"]);
+    expect(game.current).toEqual(predictMove(actual, "Down"));
+
+    // Both moves confirming at once settle without checking the tiles spawned in between.
+    const { game: batched, events: batchedEvents } = start();
+    batched.play("Left");
+    batched.play("Down");
+    const last = confirmed(actual, "Down");
+    batched.reconcile(last, 2);
+    expect(batchedEvents).toEqual([]);
+    expect(batched.current).toEqual(last);
+  });
+
+  it("ignores states older than the confirmed one", () => {
+    const { game } = start();
+    game.play("Left");
+    const actual = confirmed(board, "Left");
+    game.reconcile(actual, 1);
+    game.reconcile(board, 0);
+    expect(game.current).toEqual(actual);
+  });
+});
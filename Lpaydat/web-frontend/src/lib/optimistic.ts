@@ -0,0 +1,160 @@
+import { writable, type Readable } from "svelte/store";
+
+// Boards as returned by the service: a 4x4 matrix of tile exponents, `0` being empty.
+export type Board = number[][];
+
+export type Direction = "Up" | "Down" | "Left" | "Right";
+
+// Largest exponent a tile can reach, matching the 4-bit cells of the contract's board.
+const MAX_EXPONENT = 0xf;
+
+// Slides and merges a line towards its first cell, like `scripts/precompute_moves.rs`.
+const slideLine = (line: number[]): number[] => {
+  const tiles = line.filter((tile) => tile !== 0);
+  const result: number[] = [];
+  for (let i = 0; i < tiles.length; i++) {
+    if (i + 1 < tiles.length && tiles[i] === tiles[i + 1]) {
+      result.push(Math.min(tiles[i] + 1, MAX_EXPONENT));
+      i++;
+    } else {
+      result.push(tiles[i]);
+    }
+  }
+  while (result.length < line.length) result.push(0);
+  return result;
+};
+
+const transpose = (board: Board): Board => board[0].map((_, col) => board.map((row) => row[col]));
+
+/**
+ * Predicts the board after sliding `board` in `direction`, mirroring `Game::move_board`.
+ *
+ * The tile the contract spawns afterwards depends on the block the move lands in, so it is
+ * never part of the prediction.
+ *
+ * The service's `predictMove` query also predicts the spawn, but only for the first move of
+ * the next block, from the confirmed board, and a round trip later. Predicting locally shows
+ * moves at once and stacks the ones still in flight.
+ */
+export const predictMove = (board: Board, direction: Direction): Board => {
+  switch (direction) {
+    case "Left":
+      return board.map(slideLine);
+    case "Right":
+      return board.map((row) => slideLine([...row].reverse()).reverse());
+    case "Up":
+      return transpose(transpose(board).map(slideLine));
+    case "Down":
+      return transpose(transpose(board).map((col) => slideLine([...col].reverse()).reverse()));
+  }
+};
+
+const sameBoard = (a: Board, b: Board) => a.every((row, i) => row.every((tile, j) => tile === b[i][j]));
+
+/**
+ * Whether `actual` is `predicted` plus the single tile the contract spawns after a move.
+ */
+export const matchesPrediction = (predicted: Board, actual: Board): boolean => {
+  let spawned = 0;
+  for (let i = 0; i < predicted.length; i++) {
+    for (let j = 0; j < predicted[i].length; j++) {
+      if (predicted[i][j] === actual[i][j]) continue;
+      if (predicted[i][j] !== 0) return false;
+      spawned++;
+    }
+  }
+  return spawned === 1;
+};
+
+export interface OptimisticHooks {
+  // Called with the board shown while the move confirms.
+  onPredict?: (direction: Direction, predicted: Board) => void;
+  // Called when the chain confirmed the move as predicted.
+  onConfirm?: (moveCount: number, board: Board) => void;
+  // Called when the chain's result differs from the prediction, before showing it.
+  onRollback?: (moveCount: number, predicted: Board, actual: Board) => void;
+}
+
+interface PendingMove {
+  direction: Direction;
+  // The move count of the game once the chain executed this move.
+  moveCount: number;
+  predicted: Board;
+}
+
+/**
+ * Shows moves as soon as they are played, then reconciles them with the game state
+ * confirmed by the chain.
+ *
+ * Confirmed states are identified by their `moveCount`: every pending move up to it is
+ * settled, and the moves still in flight are predicted again on top of the confirmed board.
+ */
+export class OptimisticGame implements Readable<Board | undefined> {
+  private confirmed?: { board: Board; moveCount: number };
+  private pending: PendingMove[] = [];
+  private readonly board = writable<Board | undefined>(undefined);
+
+  constructor(private readonly hooks: OptimisticHooks = {}) {}
+
+  subscribe(run: (board: Board | undefined) => void) {
+    return this.board.subscribe(run);
+  }
+
+  // The board shown to the player, including the moves that are still confirming.
+  get current(): Board | undefined {
+    return this.pending[this.pending.length - 1]?.predicted ?? this.confirmed?.board;
+  }
+
+  /**
+   * Applies a move locally. Returns `false` if the move would not change the board, in
+   * which case the contract ignores it as well.
+   */
+  play(direction: Direction): boolean {
+    const board = this.current;
+    if (!board || !this.confirmed) return false;
+    const predicted = predictMove(board, direction);
+    if (sameBoard(board, predicted)) return false;
+
+    const moveCount = this.confirmed.moveCount + this.pending.length + 1;
+    this.pending.push({ direction, moveCount, predicted });
+    this.hooks.onPredict?.(direction, predicted);
+    this.board.set(predicted);
+    return true;
+  }
+
+  // Reconciles with the authoritative state of the game, as queried from the service.
+  reconcile(board: Board, moveCount: number) {
+    if (this.confirmed && moveCount < this.confirmed.moveCount) return;
+    // Only a move predicted on top of a confirmed board can be checked: when several moves
+    // confirm at once, the tiles spawned in between were never part of the predictions.
+    const settled = this.pending[0]?.moveCount === moveCount ? this.pending[0] : undefined;
+    this.confirmed = { board, moveCount };
+
+    if (settled) {
+      if (matchesPrediction(settled.predicted, board)) {
+        this.hooks.onConfirm?.(moveCount, board);
+      } else {
+        this.hooks.onRollback?.(moveCount, settled.predicted, board);
+      }
+    }
+
+    let current = board;
+    const inFlight = this.pending.filter((move) => move.moveCount > moveCount);
+    this.pending = [];
+    for (const { direction } of inFlight) {
+      // Moves that no longer change the board are ignored by the contract too.
+      const predicted = predictMove(current, direction);
+      if (sameBoard(current, predicted)) continue;
+      this.pending.push({ direction, moveCount: moveCount + this.pending.length + 1, predicted });
+      current = predicted;
+    }
+    this.board.set(current);
+  }
+
+  // Forgets every move in flight, e.g. when a new game starts.
+  reset() {
+    this.confirmed = undefined;
+    this.pending = [];
+    this.board.set(undefined);
+  }
+}
@@ -0,0 +1,650 @@
+use crate::views::TokenView;
+use linera_sdk::base::{Owner, PublicKey, Signature};
+use linera_sdk::Contract;
+use linera_token_creation::curve::{BondingCurve, CurveKind};
+use linera_token_creation::errors::{TokenError, ValidationError};
+use linera_token_creation::metadata::TokenMetadata;
+use linera_token_creation::policy::{PolicyChange, PolicyEvent};
+use linera_token_creation::pool::{Buyback, Pool, Quote, TradeKind};
+use linera_token_creation::portfolio::Holding;
+use linera_token_creation::rewards::Award;
+use linera_token_creation::types::{MetadataResponse, SignedRequest, TokenSummary};
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Checks that `signature` of `request` is by `public_key` and that its nonce is new,
+/// returning the account of the key.
+pub async fn authenticate(
+    public_key: PublicKey,
+    signature: Signature,
+    request: &SignedRequest,
+) -> Result<String, ApiError> {
+    signature
+        .check(request, public_key)
+        .map_err(|_| ApiError::Unauthenticated)?;
+    let account = Owner::from(public_key).to_string();
+    let mut view = TokenView::load().await;
+    if !view.use_nonce(&account, request.nonce) {
+        return Err(ApiError::Unauthenticated);
+    }
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving nonce".to_string()))?;
+    Ok(account)
+}
+
+/// Why a request failed, for endpoints to answer with the matching status.
+#[derive(Debug)]
+pub enum ApiError {
+    /// The request is not signed by the account it acts for, or replays an earlier one.
+    Unauthenticated,
+    /// The body of the request cannot be read.
+    Malformed(String),
+    Invalid(ValidationError),
+    Token(TokenError),
+    Storage(String),
+}
+
+pub async fn create_token(
+    name: &str,
+    symbol: &str,
+    total_supply: u128,
+    decimals: u8,
+    metadata_uri: Option<String>,
+    creator: &str,
+) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    view.create_token(name, symbol, total_supply, decimals, metadata_uri, creator)
+        .map_err(ApiError::Invalid)?;
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving token".to_string()))
+}
+
+pub async fn create_reward_token(
+    name: &str,
+    symbol: &str,
+    creator: &str,
+    clawback_admins: Vec<String>,
+) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    view.create_reward_token(name, symbol, creator, clawback_admins.into_iter().collect())
+        .map_err(ApiError::Invalid)?;
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving token".to_string()))
+}
+
+pub async fn deposit_native(owner: &str, amount: u128) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    view.wrapped
+        .deposit(owner, amount)
+        .map_err(ApiError::Token)?;
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving reserve".to_string()))
+}
+
+pub async fn withdraw_native(owner: &str, amount: u128) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    view.wrapped
+        .withdraw(owner, amount)
+        .map_err(ApiError::Token)?;
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving reserve".to_string()))
+}
+
+pub async fn create_pool(
+    token: &str,
+    provider: &str,
+    token_amount: u128,
+    native_amount: u128,
+) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    view.pools
+        .create(
+            token,
+            provider,
+            token_amount,
+            native_amount,
+            &mut view.tokens,
+            &mut view.wrapped,
+        )
+        .map_err(ApiError::Token)?;
+    view.index_holders(token, &[provider]);
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving pool".to_string()))
+}
+
+pub async fn pool(token: &str) -> Option<Pool> {
+    let view = TokenView::load().await;
+    view.pools.pools.get(token).cloned()
+}
+
+pub async fn quote(token: &str, kind: TradeKind, amount: u128) -> Result<Quote, ApiError> {
+    let view = TokenView::load().await;
+    let pool = view.pools.pool(token).map_err(ApiError::Token)?;
+    match kind {
+        TradeKind::Buy => pool.quote_buy(amount),
+        TradeKind::BuyExactTokens => pool.quote_buy_exact_tokens(amount),
+        TradeKind::Sell => pool.quote_sell(amount),
+        TradeKind::SellForExactNative => pool.quote_sell_for_exact_native(amount),
+    }
+    .map_err(ApiError::Token)
+}
+
+/// Executes a trade of `kind` fixing `amount`, bounded by `limit`: the least the trader
+/// receives for exact-input trades, the most they pay for exact-output ones. The referrer
+/// earns a share of the trade fee.
+#[allow(clippy::too_many_arguments)]
+pub async fn trade(
+    token: &str,
+    trader: &str,
+    referrer: Option<&str>,
+    kind: TradeKind,
+    amount: u128,
+    limit: u128,
+) -> Result<Quote, ApiError> {
+    let mut view = TokenView::load().await;
+    let (pools, tokens, wrapped) = (&mut view.pools, &mut view.tokens, &mut view.wrapped);
+    let quote = match kind {
+        TradeKind::Buy => pools.buy(token, trader, referrer, amount, limit, tokens, wrapped),
+        TradeKind::BuyExactTokens => {
+            pools.buy_exact_tokens(token, trader, referrer, amount, limit, tokens, wrapped)
+        }
+        TradeKind::Sell => pools.sell(token, trader, referrer, amount, limit, tokens, wrapped),
+        TradeKind::SellForExactNative => {
+            pools.sell_for_exact_native(token, trader, referrer, amount, limit, tokens, wrapped)
+        }
+    }
+    .map_err(ApiError::Token)?;
+    view.index_holders(token, &[trader]);
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving pool".to_string()))?;
+    Ok(quote)
+}
+
+pub async fn configure_buyback(
+    token: &str,
+    caller: &str,
+    share_bps: u32,
+    interval_secs: u64,
+) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    let token_state = view
+        .tokens
+        .get(token)
+        .ok_or(ApiError::Token(TokenError::NotFound))?;
+    view.pools
+        .configure_buyback(token_state, caller, share_bps, interval_secs)
+        .map_err(ApiError::Token)?;
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving pool".to_string()))
+}
+
+/// Executes the buyback of `token` if it is due. Anyone can.
+pub async fn poke(token: &str) -> Result<Buyback, ApiError> {
+    let mut view = TokenView::load().await;
+    let buyback = view
+        .pools
+        .poke(token, now(), &mut view.tokens)
+        .map_err(ApiError::Token)?;
+    view.buybacks.push(buyback.clone());
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving pool".to_string()))?;
+    Ok(buyback)
+}
+
+pub async fn buybacks(token: &str) -> Vec<Buyback> {
+    let view = TokenView::load().await;
+    view.buybacks
+        .iter()
+        .filter(|buyback| buyback.token == token)
+        .cloned()
+        .collect()
+}
+
+pub async fn referral_fees(referrer: &str) -> u128 {
+    let view = TokenView::load().await;
+    view.pools.referral_fees(referrer)
+}
+
+pub async fn claim_referral_fees(referrer: &str) -> Result<u128, ApiError> {
+    let mut view = TokenView::load().await;
+    let amount = view
+        .pools
+        .claim_referral_fees(referrer, &mut view.wrapped)
+        .map_err(ApiError::Token)?;
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving pool".to_string()))?;
+    Ok(amount)
+}
+
+pub async fn create_curve(
+    token: &str,
+    creator: &str,
+    kind: CurveKind,
+    base_price: u128,
+    slope: u128,
+    amount: u128,
+) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    view.curves
+        .create(
+            token,
+            creator,
+            kind,
+            base_price,
+            slope,
+            amount,
+            &mut view.tokens,
+        )
+        .map_err(ApiError::Token)?;
+    view.index_holders(token, &[creator]);
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving curve".to_string()))
+}
+
+pub async fn curve(token: &str) -> Option<BondingCurve> {
+    let view = TokenView::load().await;
+    view.curves.curves.get(token).cloned()
+}
+
+/// Buys `amount` tokens from the curve of `token` for at most `max_cost` wrapped native
+/// tokens, returning their cost.
+pub async fn buy_from_curve(
+    token: &str,
+    trader: &str,
+    amount: u128,
+    max_cost: u128,
+) -> Result<u128, ApiError> {
+    let mut view = TokenView::load().await;
+    let cost = view
+        .curves
+        .buy(
+            token,
+            trader,
+            amount,
+            max_cost,
+            &mut view.tokens,
+            &mut view.wrapped,
+        )
+        .map_err(ApiError::Token)?;
+    view.index_holders(token, &[trader]);
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving curve".to_string()))?;
+    Ok(cost)
+}
+
+/// Sells `amount` tokens back to the curve of `token` for at least `min_proceeds` wrapped
+/// native tokens, returning the proceeds.
+pub async fn sell_to_curve(
+    token: &str,
+    trader: &str,
+    amount: u128,
+    min_proceeds: u128,
+) -> Result<u128, ApiError> {
+    let mut view = TokenView::load().await;
+    let proceeds = view
+        .curves
+        .sell(
+            token,
+            trader,
+            amount,
+            min_proceeds,
+            &mut view.tokens,
+            &mut view.wrapped,
+        )
+        .map_err(ApiError::Token)?;
+    view.index_holders(token, &[trader]);
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving curve".to_string()))?;
+    Ok(proceeds)
+}
+
+pub async fn create_escrow(
+    token: &str,
+    depositor: &str,
+    beneficiary: &str,
+    arbiter: &str,
+    amount: u128,
+) -> Result<u64, ApiError> {
+    let mut view = TokenView::load().await;
+    let id = view
+        .escrows
+        .create(token, depositor, beneficiary, arbiter, amount)
+        .map_err(ApiError::Token)?;
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving escrow".to_string()))?;
+    Ok(id)
+}
+
+pub async fn fund_escrow(id: u64, caller: &str) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    view.escrows
+        .fund(id, caller, &mut view.tokens)
+        .map_err(ApiError::Token)?;
+    let escrow = view.escrows.escrows[&id].clone();
+    view.index_holders(&escrow.token, &[&escrow.depositor]);
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving escrow".to_string()))
+}
+
+pub async fn release_escrow(id: u64, caller: &str) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    view.escrows
+        .release(id, caller, &mut view.tokens)
+        .map_err(ApiError::Token)?;
+    let escrow = view.escrows.escrows[&id].clone();
+    view.index_holders(&escrow.token, &[&escrow.beneficiary]);
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving escrow".to_string()))
+}
+
+pub async fn refund_escrow(id: u64, caller: &str) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    view.escrows
+        .refund(id, caller, &mut view.tokens)
+        .map_err(ApiError::Token)?;
+    let escrow = view.escrows.escrows[&id].clone();
+    view.index_holders(&escrow.token, &[&escrow.depositor]);
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving escrow".to_string()))
+}
+
+pub async fn dispute_escrow(id: u64, caller: &str) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    view.escrows.dispute(id, caller).map_err(ApiError::Token)?;
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving escrow".to_string()))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+pub async fn create_stream(
+    token: &str,
+    sender: &str,
+    recipient: &str,
+    amount: u128,
+    start: u64,
+    end: u64,
+) -> Result<u64, ApiError> {
+    let mut view = TokenView::load().await;
+    let id = view
+        .streams
+        .create(
+            token,
+            sender,
+            recipient,
+            amount,
+            start,
+            end,
+            &mut view.tokens,
+        )
+        .map_err(ApiError::Token)?;
+    view.index_holders(token, &[sender]);
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving stream".to_string()))?;
+    Ok(id)
+}
+
+pub async fn withdraw_stream(id: u64, caller: &str) -> Result<u128, ApiError> {
+    let mut view = TokenView::load().await;
+    let amount = view
+        .streams
+        .withdraw(id, caller, now(), &mut view.tokens)
+        .map_err(ApiError::Token)?;
+    let stream = view.streams.streams[&id].clone();
+    view.index_holders(&stream.token, &[&stream.recipient]);
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving stream".to_string()))?;
+    Ok(amount)
+}
+
+pub async fn cancel_stream(id: u64, caller: &str) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    view.streams
+        .cancel(id, caller, now(), &mut view.tokens)
+        .map_err(ApiError::Token)?;
+    let stream = view.streams.streams[&id].clone();
+    view.index_holders(&stream.token, &[&stream.sender, &stream.recipient]);
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving stream".to_string()))
+}
+
+pub async fn transfer(token: &str, from: &str, to: &str, amount: u128) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    let token_state = view
+        .tokens
+        .get_mut(token)
+        .ok_or(ApiError::Token(TokenError::NotFound))?;
+    token_state
+        .transfer(from, to, amount)
+        .map_err(ApiError::Token)?;
+    view.index_holders(token, &[from, to]);
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving token".to_string()))
+}
+
+pub async fn mint(
+    token: &str,
+    caller: &str,
+    recipient: &str,
+    amount: u128,
+) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    let token_state = view
+        .tokens
+        .get_mut(token)
+        .ok_or(ApiError::Token(TokenError::NotFound))?;
+    token_state
+        .mint(caller, recipient, amount)
+        .map_err(ApiError::Token)?;
+    view.index_holders(token, &[recipient]);
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving token".to_string()))
+}
+
+pub async fn burn(token: &str, caller: &str, amount: u128) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    let token_state = view
+        .tokens
+        .get_mut(token)
+        .ok_or(ApiError::Token(TokenError::NotFound))?;
+    token_state.burn(caller, amount).map_err(ApiError::Token)?;
+    view.index_holders(token, &[caller]);
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving token".to_string()))
+}
+
+pub async fn balance(token: &str, owner: &str) -> Option<u128> {
+    let view = TokenView::load().await;
+    view.tokens.get(token).map(|token| token.balance(owner))
+}
+
+pub async fn portfolio(owner: &str) -> Vec<Holding> {
+    let view = TokenView::load().await;
+    view.portfolio(owner)
+}
+
+pub async fn award(
+    token: &str,
+    caller: &str,
+    recipient: &str,
+    amount: u128,
+    achievement: &str,
+) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    let token_state = view
+        .tokens
+        .get_mut(token)
+        .ok_or(ApiError::Token(TokenError::NotFound))?;
+    token_state
+        .award(caller, recipient, amount)
+        .map_err(ApiError::Token)?;
+    view.index_holders(token, &[recipient]);
+    view.awards.push(Award {
+        token: token.to_string(),
+        recipient: recipient.to_string(),
+        amount,
+        achievement: achievement.to_string(),
+        timestamp: now(),
+    });
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving token".to_string()))
+}
+
+pub async fn clawback(
+    token: &str,
+    caller: &str,
+    holder: &str,
+    amount: u128,
+) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    let token_state = view
+        .tokens
+        .get_mut(token)
+        .ok_or(ApiError::Token(TokenError::NotFound))?;
+    token_state
+        .clawback(caller, holder, amount)
+        .map_err(ApiError::Token)?;
+    view.index_holders(token, &[holder]);
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving token".to_string()))
+}
+
+pub async fn awards(token: &str) -> Vec<Award> {
+    let view = TokenView::load().await;
+    view.awards
+        .iter()
+        .filter(|award| award.token == token)
+        .cloned()
+        .collect()
+}
+
+pub async fn change_policy(
+    token: &str,
+    caller: &str,
+    change: PolicyChange,
+) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    let token_state = view
+        .tokens
+        .get_mut(token)
+        .ok_or(ApiError::Token(TokenError::NotFound))?;
+    if token_state.creator != caller {
+        return Err(ApiError::Token(TokenError::Unauthorized));
+    }
+    token_state.policy.apply(&change);
+    view.policy_events.push(PolicyEvent {
+        token: token.to_string(),
+        change,
+        timestamp: now(),
+    });
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving token".to_string()))
+}
+
+pub async fn policy_events(token: &str) -> Vec<PolicyEvent> {
+    let view = TokenView::load().await;
+    view.policy_events
+        .iter()
+        .filter(|event| event.token == token)
+        .cloned()
+        .collect()
+}
+
+pub async fn propose_metadata(
+    token: &str,
+    caller: &str,
+    metadata: TokenMetadata,
+) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    let token = view
+        .tokens
+        .get_mut(token)
+        .ok_or(ApiError::Token(TokenError::NotFound))?;
+    token
+        .propose_metadata(caller, metadata, now())
+        .map_err(ApiError::Token)?;
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving token".to_string()))
+}
+
+pub async fn cancel_metadata(token: &str, caller: &str) -> Result<(), ApiError> {
+    let mut view = TokenView::load().await;
+    let token = view
+        .tokens
+        .get_mut(token)
+        .ok_or(ApiError::Token(TokenError::NotFound))?;
+    token
+        .cancel_metadata(caller, now())
+        .map_err(ApiError::Token)?;
+    view.save()
+        .await
+        .map_err(|_| ApiError::Storage("Error saving token".to_string()))
+}
+
+pub async fn token_metadata(token: &str) -> Result<MetadataResponse, ApiError> {
+    let view = TokenView::load().await;
+    let token = view
+        .tokens
+        .get(token)
+        .ok_or(ApiError::Token(TokenError::NotFound))?;
+    let now = now();
+    // Reports a proposal whose timelock is over as the current metadata, even before a
+    // later operation applies it to the state.
+    let metadata_response = match &token.pending_metadata {
+        Some(proposal) if now >= proposal.effective_at => MetadataResponse {
+            metadata: proposal.metadata.clone(),
+            pending: None,
+        },
+        pending => MetadataResponse {
+            metadata: token.metadata.clone(),
+            pending: pending.clone(),
+        },
+    };
+    Ok(metadata_response)
+}
+
+/// The most tokens listed at once.
+pub const MAX_TOKEN_PAGE: usize = 100;
+
+pub async fn tokens(offset: usize, limit: Option<usize>) -> Vec<TokenSummary> {
+    let view = TokenView::load().await;
+    view.list_tokens(offset, limit.unwrap_or(MAX_TOKEN_PAGE).min(MAX_TOKEN_PAGE))
+}
+
+pub async fn token_by_symbol(symbol: &str) -> Option<TokenSummary> {
+    let view = TokenView::load().await;
+    view.get_token(symbol)
+}
@@ -1,10 +1,883 @@
-use linera_sdk::Contract;
-use crate::views::TokenView;
-use crate::types::Token;
-use serde::{Deserialize, Serialize};
-
-pub async fn create_token(name: &str, symbol: &str, total_supply: u32) -> Result<(), String> {
-    let mut view = TokenView::load().await;
-    view.create_token(name, symbol, total_supply);
-    view.save().await.map_err(|_| "Error saving token".to_string())
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+mod state;
+
+use linera_sdk::{
+    base::{Account, AccountOwner, WithContractAbi},
+    views::{RootView, View},
+    Contract, ContractRuntime,
+};
+use linera_token_creation::{
+    escrow::Escrow,
+    types::Token,
+    validation::{validate_name, validate_supply, validate_symbol},
+    Message, Operation, TokenAbi, TokenOrigin, TokenResponse,
+};
+
+use self::state::TokenState;
+
+pub struct TokenContract {
+    state: TokenState,
+    runtime: ContractRuntime<Self>,
+}
+
+linera_sdk::contract!(TokenContract);
+
+impl WithContractAbi for TokenContract {
+    type Abi = TokenAbi;
+}
+
+impl Contract for TokenContract {
+    type Message = Message;
+    type Parameters = ();
+    type InstantiationArgument = ();
+
+    async fn load(runtime: ContractRuntime<Self>) -> Self {
+        let state = TokenState::load(runtime.root_view_storage_context())
+            .await
+            .expect("Failed to load state");
+        TokenContract { state, runtime }
+    }
+
+    async fn instantiate(&mut self, _argument: ()) {}
+
+    async fn execute_operation(&mut self, operation: Operation) -> TokenResponse {
+        // Operations of other applications, which need not forward a signer.
+        let operation = match operation {
+            Operation::MintReward {
+                token,
+                recipient,
+                amount,
+            } => {
+                self.mint_reward(&token, recipient, amount).await;
+                return TokenResponse::Ok;
+            }
+            Operation::OpenEscrow { token, amount } => {
+                return TokenResponse::Escrow(self.open_escrow(&token, amount).await);
+            }
+            Operation::SettleEscrow {
+                escrow_id,
+                recipient,
+            } => {
+                self.settle_escrow(escrow_id, recipient).await;
+                return TokenResponse::Ok;
+            }
+            operation => operation,
+        };
+        let signer = self
+            .runtime
+            .authenticated_signer()
+            .expect("Token operations must be signed")
+            .to_string();
+        match operation {
+            Operation::CreateToken {
+                name,
+                symbol,
+                total_supply,
+                decimals,
+                metadata_uri,
+            } => {
+                validate_name(&name)
+                    .and_then(|()| validate_symbol(&symbol))
+                    .and_then(|()| validate_supply(total_supply))
+                    .unwrap_or_else(|err| panic!("Error creating token: {err:?}"));
+                assert!(
+                    self.state.token(&name).await.is_none(),
+                    "A token named {name} already exists"
+                );
+                let symbol_taken = self.state.symbols.contains_key(&symbol).await;
+                assert!(
+                    !symbol_taken.expect("Failed to load symbols"),
+                    "A token with symbol {symbol} already exists"
+                );
+                self.state
+                    .symbols
+                    .insert(&symbol, name.clone())
+                    .expect("Failed to store symbol");
+                let token = Token::new(
+                    &name,
+                    &symbol,
+                    total_supply,
+                    decimals,
+                    metadata_uri,
+                    &signer,
+                );
+                self.state.save_token(token, &[&signer]).await;
+            }
+            Operation::Transfer { token, to, amount } => {
+                let mut token = self.known_token(&token).await;
+                let to = to.to_string();
+                token
+                    .transfer(&signer, &to, amount)
+                    .unwrap_or_else(|err| panic!("Error transferring tokens: {err:?}"));
+                self.state.save_token(token, &[&signer, &to]).await;
+            }
+            Operation::Mint {
+                token,
+                recipient,
+                amount,
+            } => {
+                assert!(
+                    self.state.origin(&token).await.is_none(),
+                    "Token {token} can only be minted on the chain it was created on"
+                );
+                let mut token = self.known_token(&token).await;
+                let recipient = recipient.to_string();
+                token
+                    .mint(&signer, &recipient, amount)
+                    .unwrap_or_else(|err| panic!("Error minting tokens: {err:?}"));
+                self.state.save_token(token, &[&recipient]).await;
+            }
+            Operation::Burn { token, amount } => {
+                let mut token = self.known_token(&token).await;
+                token
+                    .burn(&signer, amount)
+                    .unwrap_or_else(|err| panic!("Error burning tokens: {err:?}"));
+                self.state.save_token(token, &[&signer]).await;
+            }
+            Operation::SetMinter {
+                token,
+                application_id,
+                authorized,
+            } => {
+                let known = self.known_token(&token).await;
+                assert!(
+                    signer == known.creator,
+                    "Only the creator of token {token} can authorize minters"
+                );
+                let mut minters = self.state.minters(&token).await;
+                if authorized {
+                    minters.insert(application_id);
+                } else {
+                    minters.remove(&application_id);
+                }
+                self.state
+                    .minters
+                    .insert(&token, minters)
+                    .expect("Failed to store minters");
+            }
+            Operation::MintReward { .. }
+            | Operation::OpenEscrow { .. }
+            | Operation::SettleEscrow { .. } => {
+                unreachable!("Operations of applications are executed above")
+            }
+            Operation::CrossChainTransfer {
+                symbol,
+                amount,
+                target_account,
+            } => {
+                let chain_id = self.runtime.chain_id();
+                assert!(
+                    target_account.chain_id != chain_id,
+                    "Tokens moving within a chain are transferred with Transfer"
+                );
+                assert!(
+                    target_account.owner.is_some(),
+                    "Cross-chain transfers credit an owner, not a chain"
+                );
+                assert!(amount > 0, "Cross-chain transfers must move tokens");
+                let name = self
+                    .state
+                    .symbols
+                    .get(&symbol)
+                    .await
+                    .expect("Failed to load symbols")
+                    .unwrap_or_else(|| panic!("Unknown token {symbol}"));
+                let mut token = self.known_token(&name).await;
+                token
+                    .debit(&signer, amount)
+                    .unwrap_or_else(|err| panic!("Error transferring tokens: {err:?}"));
+                self.state.send_off(&mut token, amount).await;
+                let origin = TokenOrigin {
+                    chain_id: self.state.origin(&name).await.unwrap_or(chain_id),
+                    name,
+                    decimals: token.decimals,
+                    metadata_uri: token.metadata_uri.clone(),
+                    creator: token.creator.clone(),
+                };
+                self.state.save_token(token, &[&signer]).await;
+                let message = Message::CrossChainTransfer {
+                    symbol,
+                    amount,
+                    target_account,
+                    origin,
+                };
+                // Tracked, so that tokens the target chain rejects bounce back to the signer.
+                self.runtime
+                    .prepare_message(message)
+                    .with_authentication()
+                    .with_tracking()
+                    .send_to(target_account.chain_id);
+            }
+        }
+        TokenResponse::Ok
+    }
+
+    async fn execute_message(&mut self, message: Message) {
+        let Message::CrossChainTransfer {
+            symbol,
+            amount,
+            target_account,
+            origin,
+        } = message;
+        let (mut token, recipient) = if self.runtime.message_is_bouncing() == Some(true) {
+            let sender = self
+                .runtime
+                .authenticated_signer()
+                .expect("Cross-chain transfers are signed");
+            (self.known_token(&origin.name).await, sender)
+        } else {
+            let recipient = target_account
+                .owner
+                .expect("Cross-chain transfers credit an owner");
+            (self.received_token(&symbol, &origin).await, recipient)
+        };
+        let recipient = recipient.to_string();
+        self.state.bring_in(&mut token, amount).await;
+        token
+            .credit(&recipient, amount)
+            .unwrap_or_else(|err| panic!("Error receiving tokens: {err:?}"));
+        self.state.save_token(token, &[&recipient]).await;
+    }
+
+    async fn store(mut self) {
+        self.state.save().await.expect("Failed to save state");
+    }
+}
+
+impl TokenContract {
+    /// Mints `amount` reward tokens `name` to `recipient`, for the application calling this
+    /// one, which the creator of the token must have authorized.
+    async fn mint_reward(&mut self, name: &str, recipient: Account, amount: u128) {
+        let caller = self
+            .runtime
+            .authenticated_caller_id()
+            .expect("Rewards are minted by applications");
+        assert!(
+            self.state.minters(name).await.contains(&caller),
+            "Application {caller:?} cannot mint token {name}"
+        );
+        assert!(
+            self.state.origin(name).await.is_none(),
+            "Token {name} can only be minted on the chain it was created on"
+        );
+        let owner = recipient
+            .owner
+            .expect("Rewards credit an owner, not a chain")
+            .to_string();
+        let mut token = self.known_token(name).await;
+        let creator = token.creator.clone();
+        token
+            .mint(&creator, &owner, amount)
+            .unwrap_or_else(|err| panic!("Error minting rewards: {err:?}"));
+        self.deliver(token, &owner, amount, recipient).await;
+    }
+
+    /// Opens an escrow of `amount` tokens `name` of the signer, which the application calling
+    /// this one settles, and returns its id.
+    async fn open_escrow(&mut self, name: &str, amount: u128) -> u64 {
+        let arbiter = self.calling_application("Escrows are opened by applications");
+        let depositor = self
+            .runtime
+            .authenticated_signer()
+            .expect("Escrows are funded by the signer")
+            .to_string();
+        let mut token = self.known_token(name).await;
+        // The application is also the beneficiary until it awards the tokens.
+        let mut escrow = Escrow::new(name, &depositor, &arbiter, &arbiter, amount)
+            .unwrap_or_else(|err| panic!("Error opening escrow: {err:?}"));
+        escrow
+            .fund(&depositor, &mut token)
+            .unwrap_or_else(|err| panic!("Error funding escrow: {err:?}"));
+        let id = *self.state.next_escrow_id.get();
+        self.state.next_escrow_id.set(id + 1);
+        self.state
+            .escrows
+            .insert(&id, escrow)
+            .expect("Failed to store escrow");
+        self.state.save_token(token, &[&depositor]).await;
+        id
+    }
+
+    /// Settles escrow `id` for the application that opened it: pays `recipient`, refunding
+    /// the depositor if it is their account on this chain, or burns the tokens if `None`.
+    async fn settle_escrow(&mut self, id: u64, recipient: Option<Account>) {
+        let caller = self.calling_application("Escrows are settled by applications");
+        let mut escrow = self.state.escrow(id).await;
+        let mut token = self.known_token(&escrow.token).await;
+        let Some(recipient) = recipient else {
+            escrow
+                .forfeit(&caller, &mut token)
+                .unwrap_or_else(|err| panic!("Error settling escrow: {err:?}"));
+            self.state
+                .escrows
+                .insert(&id, escrow)
+                .expect("Failed to store escrow");
+            self.state.save_token(token, &[]).await;
+            return;
+        };
+        let owner = recipient
+            .owner
+            .expect("Escrows pay an owner, not a chain")
+            .to_string();
+        let is_refund = recipient.chain_id == self.runtime.chain_id() && owner == escrow.depositor;
+        let settled = if is_refund {
+            escrow.refund(&caller, &mut token)
+        } else {
+            escrow.award(&caller, &owner, &mut token)
+        };
+        settled.unwrap_or_else(|err| panic!("Error settling escrow: {err:?}"));
+        let amount = escrow.amount;
+        self.state
+            .escrows
+            .insert(&id, escrow)
+            .expect("Failed to store escrow");
+        self.deliver(token, &owner, amount, recipient).await;
+    }
+
+    /// Returns the application calling this one, as escrows name their arbiter.
+    fn calling_application(&mut self, error: &str) -> String {
+        let caller = self.runtime.authenticated_caller_id().expect(error);
+        AccountOwner::Application(caller).to_string()
+    }
+
+    /// Stores `token`, whose `amount` was just credited to `owner`, sending that amount on to
+    /// `recipient` if it is on another chain. The transfer is untracked, as no signer's
+    /// balance could take back tokens the target chain rejects: they stay off this chain.
+    async fn deliver(&mut self, mut token: Token, owner: &str, amount: u128, recipient: Account) {
+        let chain_id = self.runtime.chain_id();
+        if recipient.chain_id != chain_id {
+            token
+                .debit(owner, amount)
+                .unwrap_or_else(|err| panic!("Error sending tokens: {err:?}"));
+            self.state.send_off(&mut token, amount).await;
+            let message = Message::CrossChainTransfer {
+                symbol: token.symbol.clone(),
+                amount,
+                target_account: recipient,
+                origin: TokenOrigin {
+                    chain_id: self.state.origin(&token.name).await.unwrap_or(chain_id),
+                    name: token.name.clone(),
+                    decimals: token.decimals,
+                    metadata_uri: token.metadata_uri.clone(),
+                    creator: token.creator.clone(),
+                },
+            };
+            self.runtime
+                .prepare_message(message)
+                .send_to(recipient.chain_id);
+        }
+        self.state.save_token(token, &[owner]).await;
+    }
+
+    /// Returns the token with ticker `symbol` arriving from another chain, recording it if
+    /// this chain never received it. Tokens clashing with a different local one are rejected,
+    /// bouncing the transfer.
+    async fn received_token(&mut self, symbol: &str, origin: &TokenOrigin) -> Token {
+        let name = &origin.name;
+        let chain_id = self.runtime.chain_id();
+        if let Some(token) = self.state.token(name).await {
+            let token_origin = self.state.origin(name).await.unwrap_or(chain_id);
+            assert!(
+                token.symbol == symbol && token_origin == origin.chain_id,
+                "Token {name} of chain {} is a different token here",
+                origin.chain_id
+            );
+            return token;
+        }
+        let symbol_taken = self.state.symbols.contains_key(symbol).await;
+        assert!(
+            !symbol_taken.expect("Failed to load symbols"),
+            "A different token with symbol {symbol} exists here"
+        );
+        self.state
+            .symbols
+            .insert(symbol, name.clone())
+            .expect("Failed to store symbol");
+        self.state
+            .origins
+            .insert(name, origin.chain_id)
+            .expect("Failed to store token origin");
+        let mut token = Token::new(
+            name,
+            symbol,
+            0,
+            origin.decimals,
+            origin.metadata_uri.clone(),
+            &origin.creator,
+        );
+        token.balances.clear();
+        token
+    }
+
+    async fn known_token(&self, name: &str) -> Token {
+        self.state
+            .token(name)
+            .await
+            .unwrap_or_else(|| panic!("Unknown token {name}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_sdk::{
+        base::{Account, ApplicationId, ChainId, Destination, MessageId, Owner, PublicKey},
+        util::BlockingWait,
+        Contract, ContractRuntime,
+    };
+    use linera_token_creation::{escrow::EscrowStatus, Message, Operation, TokenResponse};
+
+    use super::TokenContract;
+
+    fn owner(key: u8) -> Owner {
+        Owner::from(PublicKey::test_key(key))
+    }
+
+    fn account(chain: u32, key: u8) -> Account {
+        Account {
+            chain_id: ChainId::root(chain),
+            owner: Some(owner(key)),
+        }
+    }
+
+    fn contract_on(chain: u32) -> TokenContract {
+        let runtime = ContractRuntime::new().with_chain_id(ChainId::root(chain));
+        TokenContract::load(runtime).blocking_wait()
+    }
+
+    fn execute(contract: &mut TokenContract, signer: u8, operation: Operation) {
+        contract.runtime.set_authenticated_signer(owner(signer));
+        contract.execute_operation(operation).blocking_wait();
+    }
+
+    /// Sends `amount` COIN of `signer` to `target`, returning the message to deliver.
+    fn send(contract: &mut TokenContract, signer: u8, amount: u128, target: Account) -> Message {
+        let operation = Operation::CrossChainTransfer {
+            symbol: "COIN".to_string(),
+            amount,
+            target_account: target,
+        };
+        execute(contract, signer, operation);
+        let request = contract
+            .runtime
+            .created_send_message_requests()
+            .pop()
+            .expect("No message was sent");
+        assert_eq!(request.destination, Destination::from(target.chain_id));
+        assert!(request.authenticated && request.is_tracked);
+        request.message
+    }
+
+    /// Delivers `message`, signed by `signer`, back to its sender if it is `bouncing`.
+    fn deliver(contract: &mut TokenContract, signer: u8, message: Message, bouncing: bool) {
+        contract.runtime.set_authenticated_signer(owner(signer));
+        contract.runtime.set_message_is_bouncing(bouncing);
+        contract.execute_message(message).blocking_wait();
+    }
+
+    /// Chain 0 with alice (1) holding the whole 1 000 COIN it created.
+    fn origin_chain() -> TokenContract {
+        let mut contract = contract_on(0);
+        let operation = Operation::CreateToken {
+            name: "Coin".to_string(),
+            symbol: "COIN".to_string(),
+            total_supply: 1_000,
+            decimals: 0,
+            metadata_uri: None,
+        };
+        execute(&mut contract, 1, operation);
+        contract
+    }
+
+    fn balance(contract: &TokenContract, key: u8) -> u128 {
+        let token = contract.state.token("Coin").blocking_wait().unwrap();
+        token.balance(&owner(key).to_string())
+    }
+
+    fn supply(contract: &TokenContract) -> u128 {
+        let token = contract.state.token("Coin").blocking_wait().unwrap();
+        // Tokens escrowed for other chains or held by applications' escrows are in no
+        // balance here.
+        let escrowed = contract.state.escrowed("Coin").blocking_wait();
+        let mut held = 0;
+        contract
+            .state
+            .escrows
+            .for_each_index_value(|_, escrow| {
+                if matches!(escrow.status, EscrowStatus::Funded | EscrowStatus::Disputed) {
+                    held += escrow.amount;
+                }
+                Ok(())
+            })
+            .blocking_wait()
+            .expect("Failed to load escrows");
+        assert_eq!(
+            token.balances.values().sum::<u128>() + escrowed + held,
+            token.total_supply
+        );
+        token.total_supply
+    }
+
+    fn holdings(contract: &TokenContract, key: u8) -> Vec<String> {
+        let owner = owner(key).to_string();
+        let held = contract.state.holdings(&owner).blocking_wait();
+        held.into_iter().collect()
+    }
+
+    fn escrowed(contract: &TokenContract) -> u128 {
+        contract.state.escrowed("Coin").blocking_wait()
+    }
+
+    #[test]
+    fn tokens_leaving_their_chain_stay_escrowed_there() {
+        let mut origin = origin_chain();
+        let mut other = contract_on(1);
+
+        let message = send(&mut origin, 1, 300, account(1, 2));
+        assert_eq!((balance(&origin, 1), escrowed(&origin)), (700, 300));
+        deliver(&mut other, 1, message, false);
+        assert_eq!(balance(&other, 2), 300);
+        assert_eq!(supply(&other), 300);
+        assert_eq!(
+            other.state.origin("Coin").blocking_wait(),
+            Some(ChainId::root(0))
+        );
+
+        let message = send(&mut other, 2, 100, account(0, 1));
+        assert_eq!(supply(&other), 200);
+        deliver(&mut origin, 2, message, false);
+        assert_eq!((balance(&origin, 1), escrowed(&origin)), (800, 200));
+
+        // What the other chain minted is exactly what the origin chain escrows.
+        assert_eq!(supply(&origin), 1_000);
+        assert_eq!(escrowed(&origin), supply(&other));
+    }
+
+    #[test]
+    fn bounced_transfers_refund_the_sender() {
+        let mut origin = origin_chain();
+        let message = send(&mut origin, 1, 300, account(1, 2));
+        deliver(&mut origin, 1, message, true);
+        assert_eq!((balance(&origin, 1), escrowed(&origin)), (1_000, 0));
+
+        // Tokens bouncing back to a chain they were sent from are minted again there.
+        let mut other = contract_on(1);
+        let message = send(&mut origin, 1, 300, account(1, 2));
+        deliver(&mut other, 1, message, false);
+        let message = send(&mut other, 2, 100, account(2, 3));
+        assert_eq!(supply(&other), 200);
+        deliver(&mut other, 2, message, true);
+        assert_eq!((balance(&other, 2), supply(&other)), (300, 300));
+        assert_eq!(escrowed(&origin), supply(&other));
+    }
+
+    #[test]
+    #[should_panic(expected = "A different token with symbol COIN exists here")]
+    fn tokens_clashing_with_a_local_one_are_rejected() {
+        let mut origin = origin_chain();
+        let mut other = contract_on(1);
+        let operation = Operation::CreateToken {
+            name: "Other".to_string(),
+            symbol: "COIN".to_string(),
+            total_supply: 1,
+            decimals: 0,
+            metadata_uri: None,
+        };
+        execute(&mut other, 2, operation);
+        let message = send(&mut origin, 1, 300, account(1, 2));
+        deliver(&mut other, 1, message, false);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only 300 tokens Coin are held on other chains")]
+    fn origin_chains_release_no_more_than_they_escrow() {
+        let mut origin = origin_chain();
+        let Message::CrossChainTransfer {
+            symbol,
+            origin: token,
+            ..
+        } = send(&mut origin, 1, 300, account(1, 2));
+        let forged = Message::CrossChainTransfer {
+            symbol,
+            amount: 301,
+            target_account: account(0, 2),
+            origin: token,
+        };
+        deliver(&mut origin, 2, forged, false);
+    }
+
+    #[test]
+    fn operations_move_balances_and_holdings() {
+        let mut contract = origin_chain();
+        assert_eq!(holdings(&contract, 1), ["Coin"]);
+        let transfer = Operation::Transfer {
+            token: "Coin".to_string(),
+            to: owner(2),
+            amount: 1_000,
+        };
+        execute(&mut contract, 1, transfer);
+        assert_eq!((balance(&contract, 1), balance(&contract, 2)), (0, 1_000));
+        assert!(holdings(&contract, 1).is_empty());
+        assert_eq!(holdings(&contract, 2), ["Coin"]);
+
+        let mint = Operation::Mint {
+            token: "Coin".to_string(),
+            recipient: owner(1),
+            amount: 500,
+        };
+        execute(&mut contract, 1, mint);
+        let burn = Operation::Burn {
+            token: "Coin".to_string(),
+            amount: 200,
+        };
+        execute(&mut contract, 1, burn);
+        assert_eq!(balance(&contract, 1), 300);
+        assert_eq!(supply(&contract), 1_300);
+        assert_eq!(holdings(&contract, 1), ["Coin"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "A token named Coin already exists")]
+    fn names_are_unique() {
+        let mut contract = origin_chain();
+        let operation = Operation::CreateToken {
+            name: "Coin".to_string(),
+            symbol: "OTHER".to_string(),
+            total_supply: 1,
+            decimals: 0,
+            metadata_uri: None,
+        };
+        execute(&mut contract, 2, operation);
+    }
+
+    #[test]
+    #[should_panic(expected = "A token with symbol COIN already exists")]
+    fn symbols_are_unique() {
+        let mut contract = origin_chain();
+        let operation = Operation::CreateToken {
+            name: "Other".to_string(),
+            symbol: "COIN".to_string(),
+            total_supply: 1,
+            decimals: 0,
+            metadata_uri: None,
+        };
+        execute(&mut contract, 2, operation);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error minting tokens: Unauthorized")]
+    fn only_creators_mint() {
+        let mut contract = origin_chain();
+        let mint = Operation::Mint {
+            token: "Coin".to_string(),
+            recipient: owner(2),
+            amount: 1,
+        };
+        execute(&mut contract, 2, mint);
+    }
+
+    #[test]
+    #[should_panic(expected = "can only be minted on the chain it was created on")]
+    fn received_tokens_are_minted_only_on_their_chain() {
+        let mut origin = origin_chain();
+        let mut other = contract_on(1);
+        let message = send(&mut origin, 1, 300, account(1, 1));
+        deliver(&mut other, 1, message, false);
+        let mint = Operation::Mint {
+            token: "Coin".to_string(),
+            recipient: owner(1),
+            amount: 1,
+        };
+        execute(&mut other, 1, mint);
+    }
+
+    fn application(index: u32) -> ApplicationId {
+        ApplicationId {
+            creation: MessageId {
+                index,
+                ..MessageId::default()
+            },
+            ..ApplicationId::default()
+        }
+    }
+
+    fn set_minter(contract: &mut TokenContract, signer: u8, minter: u32, authorized: bool) {
+        let operation = Operation::SetMinter {
+            token: "Coin".to_string(),
+            application_id: application(minter),
+            authorized,
+        };
+        execute(contract, signer, operation);
+    }
+
+    /// Mints `amount` COIN to `recipient` on behalf of application `caller`, without a signer.
+    fn mint_reward_to(contract: &mut TokenContract, caller: u32, recipient: Account, amount: u128) {
+        contract.runtime.set_authenticated_signer(None);
+        contract
+            .runtime
+            .set_authenticated_caller_id(application(caller));
+        let operation = Operation::MintReward {
+            token: "Coin".to_string(),
+            recipient,
+            amount,
+        };
+        contract.execute_operation(operation).blocking_wait();
+    }
+
+    /// Mints `amount` COIN to bob (2) on chain 0 on behalf of application `caller`.
+    fn mint_reward(contract: &mut TokenContract, caller: u32, amount: u128) {
+        mint_reward_to(contract, caller, account(0, 2), amount);
+    }
+
+    #[test]
+    fn authorized_applications_mint_rewards() {
+        let mut contract = origin_chain();
+        set_minter(&mut contract, 1, 7, true);
+        mint_reward(&mut contract, 7, 50);
+        assert_eq!(balance(&contract, 2), 50);
+        assert_eq!(supply(&contract), 1_050);
+    }
+
+    #[test]
+    fn rewards_for_other_chains_are_escrowed_and_sent_there() {
+        let mut origin = origin_chain();
+        set_minter(&mut origin, 1, 7, true);
+        mint_reward_to(&mut origin, 7, account(1, 2), 50);
+        assert_eq!((balance(&origin, 2), escrowed(&origin)), (0, 50));
+        assert_eq!(supply(&origin), 1_050);
+        assert!(holdings(&origin, 2).is_empty());
+
+        let request = origin
+            .runtime
+            .created_send_message_requests()
+            .pop()
+            .expect("No message was sent");
+        assert_eq!(request.destination, Destination::from(ChainId::root(1)));
+        assert!(!request.authenticated && !request.is_tracked);
+        let mut other = contract_on(1);
+        deliver(&mut other, 1, request.message, false);
+        assert_eq!(balance(&other, 2), 50);
+        assert_eq!(escrowed(&origin), supply(&other));
+    }
+
+    #[test]
+    #[should_panic(expected = "Rewards credit an owner, not a chain")]
+    fn rewards_credit_owners() {
+        let mut contract = origin_chain();
+        set_minter(&mut contract, 1, 7, true);
+        let chain = Account::chain(ChainId::root(1));
+        mint_reward_to(&mut contract, 7, chain, 50);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot mint token Coin")]
+    fn revoked_applications_mint_nothing() {
+        let mut contract = origin_chain();
+        set_minter(&mut contract, 1, 7, true);
+        set_minter(&mut contract, 1, 7, false);
+        mint_reward(&mut contract, 7, 50);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot mint token Coin")]
+    fn other_applications_mint_nothing() {
+        let mut contract = origin_chain();
+        set_minter(&mut contract, 1, 7, true);
+        mint_reward(&mut contract, 8, 50);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the creator of token Coin can authorize minters")]
+    fn only_creators_authorize_minters() {
+        let mut contract = origin_chain();
+        set_minter(&mut contract, 2, 7, true);
+    }
+
+    /// Has application `caller` open an escrow of `amount` COIN of `signer`, returning its id.
+    fn open_escrow(contract: &mut TokenContract, caller: u32, signer: u8, amount: u128) -> u64 {
+        contract
+            .runtime
+            .set_authenticated_caller_id(application(caller));
+        contract.runtime.set_authenticated_signer(owner(signer));
+        let operation = Operation::OpenEscrow {
+            token: "Coin".to_string(),
+            amount,
+        };
+        match contract.execute_operation(operation).blocking_wait() {
+            TokenResponse::Escrow(id) => id,
+            response => panic!("Unexpected response {response:?}"),
+        }
+    }
+
+    /// Has application `caller` settle escrow `id` for `recipient`, without a signer.
+    fn settle_escrow(
+        contract: &mut TokenContract,
+        caller: u32,
+        id: u64,
+        recipient: Option<Account>,
+    ) {
+        contract.runtime.set_authenticated_signer(None);
+        contract
+            .runtime
+            .set_authenticated_caller_id(application(caller));
+        let operation = Operation::SettleEscrow {
+            escrow_id: id,
+            recipient,
+        };
+        contract.execute_operation(operation).blocking_wait();
+    }
+
+    #[test]
+    fn escrows_refund_award_or_burn_their_tokens() {
+        let mut contract = origin_chain();
+        let refunded = open_escrow(&mut contract, 7, 1, 100);
+        let awarded = open_escrow(&mut contract, 7, 1, 200);
+        let burned = open_escrow(&mut contract, 7, 1, 300);
+        assert_eq!((refunded, awarded, burned), (0, 1, 2));
+        assert_eq!(balance(&contract, 1), 400);
+        assert_eq!(supply(&contract), 1_000);
+
+        settle_escrow(&mut contract, 7, refunded, Some(account(0, 1)));
+        settle_escrow(&mut contract, 7, awarded, Some(account(0, 2)));
+        settle_escrow(&mut contract, 7, burned, None);
+        assert_eq!((balance(&contract, 1), balance(&contract, 2)), (500, 200));
+        assert_eq!(holdings(&contract, 2), ["Coin"]);
+        assert_eq!(supply(&contract), 700);
+    }
+
+    #[test]
+    fn escrows_awarded_on_other_chains_are_sent_there() {
+        let mut origin = origin_chain();
+        let id = open_escrow(&mut origin, 7, 1, 100);
+        // The depositor's account on another chain is paid as anyone else's.
+        settle_escrow(&mut origin, 7, id, Some(account(1, 1)));
+        assert_eq!((balance(&origin, 1), escrowed(&origin)), (900, 100));
+
+        let request = origin
+            .runtime
+            .created_send_message_requests()
+            .pop()
+            .expect("No message was sent");
+        assert!(!request.authenticated && !request.is_tracked);
+        let mut other = contract_on(1);
+        deliver(&mut other, 1, request.message, false);
+        assert_eq!(balance(&other, 1), 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error settling escrow: Unauthorized")]
+    fn only_the_opening_application_settles_an_escrow() {
+        let mut contract = origin_chain();
+        let id = open_escrow(&mut contract, 7, 1, 100);
+        settle_escrow(&mut contract, 8, id, Some(account(0, 2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Error settling escrow: InvalidState")]
+    fn escrows_are_settled_once() {
+        let mut contract = origin_chain();
+        let id = open_escrow(&mut contract, 7, 1, 100);
+        settle_escrow(&mut contract, 7, id, Some(account(0, 2)));
+        settle_escrow(&mut contract, 7, id, None);
+    }
 }
@@ -0,0 +1,412 @@
+use crate::errors::TokenError;
+use crate::types::Token;
+use crate::wrap::WrappedReserve;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The number of tokens sold over which the price of a curve rises by its slope.
+pub const CURVE_SCALE: u128 = 1_000_000;
+
+/// How the price of a bonding curve rises with the tokens it sold.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum CurveKind {
+    /// The price rises by `slope` for every `CURVE_SCALE` tokens sold.
+    Linear,
+    /// The price rises with the square of the tokens sold, by `slope` once `CURVE_SCALE` are.
+    Quadratic,
+}
+
+/// A launch of a token, selling the creator's tokens against wrapped native tokens at a
+/// price set by how many were sold. Buying a token costs the area under the price curve,
+/// and selling it back refunds that area, so the reserve always covers every refund. Both
+/// are differences of the area from no tokens sold, rounded down, so the reserve always holds
+/// that area up to the tokens sold.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BondingCurve {
+    pub kind: CurveKind,
+    /// The price of the first token, in wrapped native units per token unit.
+    pub base_price: u128,
+    pub slope: u128,
+    /// The tokens the curve holds, left for sale.
+    pub inventory: u128,
+    pub sold: u128,
+    /// The wrapped native tokens buyers paid in, less what sellers were refunded.
+    pub reserve: u128,
+}
+
+impl BondingCurve {
+    /// The price of the next token, rounded down.
+    pub fn price(&self) -> Result<u128, TokenError> {
+        let exponent = self.exponent();
+        let rise = scaled_rise(self.slope, self.sold, exponent, exponent, 1)?;
+        self.base_price
+            .checked_add(rise)
+            .ok_or(TokenError::Overflow)
+    }
+
+    /// Quotes buying `amount` tokens.
+    pub fn quote_buy(&self, amount: u128) -> Result<u128, TokenError> {
+        if amount == 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        if amount > self.inventory {
+            return Err(TokenError::InsufficientLiquidity);
+        }
+        let sold = self.sold.checked_add(amount).ok_or(TokenError::Overflow)?;
+        Ok(self.area(sold)? - self.area(self.sold)?)
+    }
+
+    /// Quotes selling `amount` tokens back.
+    pub fn quote_sell(&self, amount: u128) -> Result<u128, TokenError> {
+        if amount == 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        if amount > self.sold {
+            return Err(TokenError::InsufficientLiquidity);
+        }
+        Ok(self.area(self.sold)? - self.area(self.sold - amount)?)
+    }
+
+    fn exponent(&self) -> u32 {
+        match self.kind {
+            CurveKind::Linear => 1,
+            CurveKind::Quadratic => 2,
+        }
+    }
+
+    /// Returns the area under the price curve up to `sold` tokens sold, rounded down.
+    fn area(&self, sold: u128) -> Result<u128, TokenError> {
+        // The price integrates to `base_price * x + slope * x^(e + 1) / ((e + 1) * scale^e)`.
+        let exponent = self.exponent();
+        let rise = scaled_rise(
+            self.slope,
+            sold,
+            exponent + 1,
+            exponent,
+            u128::from(exponent + 1),
+        )?;
+        self.base_price
+            .checked_mul(sold)
+            .and_then(|flat| flat.checked_add(rise))
+            .ok_or(TokenError::Overflow)
+    }
+}
+
+/// Returns `slope * x^k / (divisor * CURVE_SCALE^m)` rounded down, for `m <= k <= 3`.
+///
+/// Powers are taken of `x / CURVE_SCALE` and `x % CURVE_SCALE` rather than of `x`, so that no
+/// intermediate value grows much past the result: `x^3` alone overflows from about a token
+/// with 18 decimals.
+fn scaled_rise(slope: u128, x: u128, k: u32, m: u32, divisor: u128) -> Result<u128, TokenError> {
+    if slope == 0 {
+        return Ok(0);
+    }
+    let (whole, remainder) = (x / CURVE_SCALE, x % CURVE_SCALE);
+    let denominator = power(CURVE_SCALE, m)?;
+    // Expands `(whole * scale + remainder)^k / scale^m` into its integer part and a fraction
+    // over `scale^m`.
+    let (mut integer, mut fraction) = (0u128, 0u128);
+    for j in 0..=k {
+        let term = binomial(k, j)
+            .checked_mul(power(whole, j)?)
+            .and_then(|term| term.checked_mul(remainder.pow(k - j)))
+            .ok_or(TokenError::Overflow)?;
+        if j >= m {
+            integer = term
+                .checked_mul(power(CURVE_SCALE, j - m)?)
+                .and_then(|term| integer.checked_add(term))
+                .ok_or(TokenError::Overflow)?;
+        } else {
+            let scale = power(CURVE_SCALE, m - j)?;
+            integer = integer
+                .checked_add(term / scale)
+                .ok_or(TokenError::Overflow)?;
+            fraction += term % scale * power(CURVE_SCALE, j)?;
+        }
+    }
+    integer = integer
+        .checked_add(fraction / denominator)
+        .ok_or(TokenError::Overflow)?;
+    fraction %= denominator;
+    // Multiplies by the slope in two parts, keeping `slope * fraction` within range.
+    let rise = slope
+        .checked_mul(integer)
+        .and_then(|rise| rise.checked_add((slope / denominator).checked_mul(fraction)?))
+        .and_then(|rise| rise.checked_add(slope % denominator * fraction / denominator))
+        .ok_or(TokenError::Overflow)?;
+    Ok(rise / divisor)
+}
+
+fn binomial(n: u32, k: u32) -> u128 {
+    (0..k).fold(1, |coefficient, i| {
+        coefficient * u128::from(n - i) / u128::from(i + 1)
+    })
+}
+
+fn power(base: u128, exponent: u32) -> Result<u128, TokenError> {
+    base.checked_pow(exponent).ok_or(TokenError::Overflow)
+}
+
+/// The bonding curves of the application, by token name.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct CurveBook {
+    pub curves: HashMap<String, BondingCurve>,
+}
+
+impl CurveBook {
+    /// Launches `token` on a bonding curve, selling `amount` of its creator's tokens.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create(
+        &mut self,
+        token: &str,
+        caller: &str,
+        kind: CurveKind,
+        base_price: u128,
+        slope: u128,
+        amount: u128,
+        tokens: &mut HashMap<String, Token>,
+    ) -> Result<(), TokenError> {
+        if self.curves.contains_key(token) {
+            return Err(TokenError::InvalidState);
+        }
+        if amount == 0 || (base_price == 0 && slope == 0) {
+            return Err(TokenError::InvalidAmount);
+        }
+        let launched = tokens.get_mut(token).ok_or(TokenError::NotFound)?;
+        if caller != launched.creator {
+            return Err(TokenError::Unauthorized);
+        }
+        launched.debit(caller, amount)?;
+        self.curves.insert(
+            token.to_string(),
+            BondingCurve {
+                kind,
+                base_price,
+                slope,
+                inventory: amount,
+                sold: 0,
+                reserve: 0,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn curve(&self, token: &str) -> Result<&BondingCurve, TokenError> {
+        self.curves.get(token).ok_or(TokenError::NotFound)
+    }
+
+    /// Buys `amount` tokens from the curve of `token`, failing if they cost more than
+    /// `max_cost` wrapped native tokens. Returns their cost.
+    pub fn buy(
+        &mut self,
+        token: &str,
+        trader: &str,
+        amount: u128,
+        max_cost: u128,
+        tokens: &mut HashMap<String, Token>,
+        wrapped: &mut WrappedReserve,
+    ) -> Result<u128, TokenError> {
+        let curve = self.curves.get_mut(token).ok_or(TokenError::NotFound)?;
+        let cost = curve.quote_buy(amount)?;
+        if cost > max_cost {
+            return Err(TokenError::SlippageExceeded);
+        }
+        let reserve = curve
+            .reserve
+            .checked_add(cost)
+            .ok_or(TokenError::Overflow)?;
+        wrapped.pool_deposit(trader, cost)?;
+        let bought = tokens.get_mut(token).ok_or(TokenError::NotFound)?;
+        bought.credit(trader, amount)?;
+        curve.reserve = reserve;
+        curve.inventory -= amount;
+        curve.sold += amount;
+        Ok(cost)
+    }
+
+    /// Sells `amount` tokens back to the curve of `token`, failing if that refunds fewer than
+    /// `min_proceeds` wrapped native tokens. Returns the refund.
+    pub fn sell(
+        &mut self,
+        token: &str,
+        trader: &str,
+        amount: u128,
+        min_proceeds: u128,
+        tokens: &mut HashMap<String, Token>,
+        wrapped: &mut WrappedReserve,
+    ) -> Result<u128, TokenError> {
+        let curve = self.curves.get_mut(token).ok_or(TokenError::NotFound)?;
+        let proceeds = curve.quote_sell(amount)?;
+        if proceeds < min_proceeds {
+            return Err(TokenError::SlippageExceeded);
+        }
+        let sold = tokens.get_mut(token).ok_or(TokenError::NotFound)?;
+        sold.debit(trader, amount)?;
+        wrapped.pool_payout(trader, proceeds)?;
+        curve.reserve -= proceeds;
+        curve.inventory += amount;
+        curve.sold -= amount;
+        Ok(proceeds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(kind: CurveKind, base_price: u128, slope: u128, sold: u128) -> BondingCurve {
+        BondingCurve {
+            kind,
+            base_price,
+            slope,
+            inventory: u128::MAX - sold,
+            sold,
+            reserve: 0,
+        }
+    }
+
+    /// Launches a curve selling the whole supply of a token, with two funded traders.
+    fn launch(kind: CurveKind) -> (CurveBook, HashMap<String, Token>, WrappedReserve) {
+        let supply = 1_000_000_000;
+        let mut tokens = HashMap::from([(
+            "Moon".to_string(),
+            Token::new("Moon", "MOON", supply, 0, None, "creator"),
+        )]);
+        let mut curves = CurveBook::default();
+        curves
+            .create("Moon", "creator", kind, 10, 3, supply, &mut tokens)
+            .unwrap();
+        let mut wrapped = WrappedReserve::default();
+        wrapped.deposit("alice", 1 << 100).unwrap();
+        wrapped.deposit("bob", 1 << 100).unwrap();
+        (curves, tokens, wrapped)
+    }
+
+    #[test]
+    fn rises_match_the_exact_formula() {
+        for kind in [CurveKind::Linear, CurveKind::Quadratic] {
+            for sold in [
+                0,
+                1,
+                999_999,
+                1_000_000,
+                1_234_567,
+                98_765_432_101,
+                999_999_999_999,
+            ] {
+                let curve = curve(kind, 7, 13, sold);
+                let e = curve.exponent();
+                let price = 7 + 13 * sold.pow(e) / CURVE_SCALE.pow(e);
+                let area = 7 * sold + 13 * sold.pow(e + 1) / (CURVE_SCALE.pow(e) * (e as u128 + 1));
+                assert_eq!(curve.price().unwrap(), price);
+                assert_eq!(curve.area(sold).unwrap(), area);
+            }
+        }
+    }
+
+    #[test]
+    fn amounts_with_18_decimals_do_not_overflow() {
+        // A thousandth of a token with 18 decimals: its cube alone exceeds `u128::MAX`.
+        let quadratic = curve(CurveKind::Quadratic, 0, 1, 10u128.pow(16));
+        assert_eq!(quadratic.price().unwrap(), 10u128.pow(20));
+        assert_eq!(quadratic.area(10u128.pow(16)).unwrap(), 10u128.pow(36) / 3);
+
+        // Ten thousand tokens: their square alone exceeds `u128::MAX`.
+        let linear = curve(CurveKind::Linear, 0, 1, 10u128.pow(22));
+        assert_eq!(linear.price().unwrap(), 10u128.pow(16));
+        assert_eq!(linear.area(10u128.pow(22)).unwrap(), 5 * 10u128.pow(37));
+        assert_eq!(
+            linear.quote_buy(10u128.pow(18)).unwrap(),
+            linear.area(10u128.pow(22) + 10u128.pow(18)).unwrap() - 5 * 10u128.pow(37)
+        );
+    }
+
+    #[test]
+    fn areas_too_large_for_u128_are_errors() {
+        let quadratic = curve(CurveKind::Quadratic, 0, 1, 10u128.pow(18));
+        assert!(matches!(
+            quadratic.area(10u128.pow(18)),
+            Err(TokenError::Overflow)
+        ));
+        assert!(matches!(quadratic.quote_buy(1), Err(TokenError::Overflow)));
+        let linear = curve(CurveKind::Linear, u128::MAX, 0, 1);
+        assert!(matches!(linear.price(), Ok(u128::MAX)));
+        assert!(matches!(linear.quote_buy(2), Err(TokenError::Overflow)));
+    }
+
+    #[test]
+    fn splitting_a_buy_does_not_change_its_cost() {
+        for kind in [CurveKind::Linear, CurveKind::Quadratic] {
+            let whole = curve(kind, 1, 7, 0).quote_buy(3_000_001).unwrap();
+            let mut parts = 0;
+            let mut split = curve(kind, 1, 7, 0);
+            for amount in [1, 999_999, 1_000_001, 1_000_000] {
+                parts += split.quote_buy(amount).unwrap();
+                split.sold += amount;
+            }
+            assert_eq!(parts, whole);
+        }
+    }
+
+    #[test]
+    fn the_reserve_covers_every_refund() {
+        for kind in [CurveKind::Linear, CurveKind::Quadratic] {
+            let (mut curves, mut tokens, mut wrapped) = launch(kind);
+            let trades = [
+                ("alice", true, 1_234_567),
+                ("bob", true, 1),
+                ("alice", false, 7),
+                ("bob", true, 3_333_333),
+                ("alice", false, 1_000_000),
+                ("bob", false, 2_000_001),
+                ("alice", true, 999_999),
+            ];
+            for (trader, buy, amount) in trades {
+                if buy {
+                    curves
+                        .buy("Moon", trader, amount, u128::MAX, &mut tokens, &mut wrapped)
+                        .unwrap();
+                } else {
+                    curves
+                        .sell("Moon", trader, amount, 0, &mut tokens, &mut wrapped)
+                        .unwrap();
+                }
+                let curve = curves.curve("Moon").unwrap();
+                assert_eq!(curve.reserve, curve.area(curve.sold).unwrap());
+                assert_eq!(curve.reserve, wrapped.pooled);
+            }
+
+            // Everyone can sell everything back, emptying the reserve exactly.
+            for trader in ["alice", "bob"] {
+                let held = tokens["Moon"].balance(trader);
+                curves
+                    .sell("Moon", trader, held, 0, &mut tokens, &mut wrapped)
+                    .unwrap();
+            }
+            let curve = curves.curve("Moon").unwrap();
+            assert_eq!((curve.sold, curve.reserve, wrapped.pooled), (0, 0, 0));
+            assert_eq!(wrapped.balance("alice") + wrapped.balance("bob"), 2 << 100);
+        }
+    }
+
+    #[test]
+    fn trades_respect_their_limits() {
+        let (mut curves, mut tokens, mut wrapped) = launch(CurveKind::Linear);
+        let cost = curves.curve("Moon").unwrap().quote_buy(500).unwrap();
+        let result = curves.buy("Moon", "alice", 500, cost - 1, &mut tokens, &mut wrapped);
+        assert!(matches!(result, Err(TokenError::SlippageExceeded)));
+        assert_eq!(
+            curves
+                .buy("Moon", "alice", 500, cost, &mut tokens, &mut wrapped)
+                .unwrap(),
+            cost
+        );
+        let result = curves.sell("Moon", "alice", 500, cost + 1, &mut tokens, &mut wrapped);
+        assert!(matches!(result, Err(TokenError::SlippageExceeded)));
+        let result = curves.sell("Moon", "bob", 1, 0, &mut tokens, &mut wrapped);
+        assert!(matches!(result, Err(TokenError::InsufficientBalance)));
+        let result = curves.create("Moon", "creator", CurveKind::Linear, 1, 1, 1, &mut tokens);
+        assert!(matches!(result, Err(TokenError::InvalidState)));
+    }
+}
@@ -1,4 +1,26 @@
 #[derive(Debug)]
 pub enum TokenError {
     BlockchainError,
+    InvalidAmount,
+    InsufficientBalance,
+    Overflow,
+    NotFound,
+    Unauthorized,
+    InvalidState,
+    TransfersPaused,
+    NotAllowlisted,
+    MaxWalletExceeded,
+    Soulbound,
+    InsufficientLiquidity,
+    SlippageExceeded,
+}
+
+/// Why a token cannot be created.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    DuplicateName,
+    DuplicateSymbol,
+    InvalidName,
+    InvalidSymbol,
+    ZeroSupply,
 }
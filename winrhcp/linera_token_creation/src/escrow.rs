@@ -0,0 +1,370 @@
+use crate::errors::TokenError;
+use crate::types::Token;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Where an escrow stands. Funds are only held while it is `Funded` or `Disputed`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscrowStatus {
+    Created,
+    Funded,
+    Disputed,
+    Released,
+    Refunded,
+    /// Burned by the arbiter.
+    Forfeited,
+}
+
+/// Tokens held on behalf of a depositor until they are released to the beneficiary or
+/// refunded, with an arbiter settling disputes.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Escrow {
+    pub token: String,
+    pub depositor: String,
+    pub beneficiary: String,
+    pub arbiter: String,
+    pub amount: u128,
+    pub status: EscrowStatus,
+}
+
+/// All the escrows of the application. Other features (wagers, tournament prizes) hold funds
+/// through it rather than moving balances themselves.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct EscrowBook {
+    pub escrows: HashMap<u64, Escrow>,
+    pub next_id: u64,
+}
+
+/// The transitions of a single escrow over the token it holds, for the on-chain escrows of
+/// other applications as for the book.
+impl Escrow {
+    /// Returns a new escrow. No tokens move until it is funded.
+    pub fn new(
+        token: &str,
+        depositor: &str,
+        beneficiary: &str,
+        arbiter: &str,
+        amount: u128,
+    ) -> Result<Self, TokenError> {
+        if amount == 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        Ok(Escrow {
+            token: token.to_string(),
+            depositor: depositor.to_string(),
+            beneficiary: beneficiary.to_string(),
+            arbiter: arbiter.to_string(),
+            amount,
+            status: EscrowStatus::Created,
+        })
+    }
+
+    /// Moves the escrowed amount out of the depositor's balance.
+    pub fn fund(&mut self, caller: &str, token: &mut Token) -> Result<(), TokenError> {
+        if caller != self.depositor {
+            return Err(TokenError::Unauthorized);
+        }
+        if self.status != EscrowStatus::Created {
+            return Err(TokenError::InvalidState);
+        }
+        token.debit(&self.depositor, self.amount)?;
+        self.status = EscrowStatus::Funded;
+        Ok(())
+    }
+
+    /// Pays the beneficiary. The depositor may release a funded escrow; once disputed, only
+    /// the arbiter can.
+    pub fn release(&mut self, caller: &str, token: &mut Token) -> Result<(), TokenError> {
+        let allowed = match self.status {
+            EscrowStatus::Funded => caller == self.depositor || caller == self.arbiter,
+            EscrowStatus::Disputed => caller == self.arbiter,
+            _ => return Err(TokenError::InvalidState),
+        };
+        if !allowed {
+            return Err(TokenError::Unauthorized);
+        }
+        token.credit(&self.beneficiary, self.amount)?;
+        self.status = EscrowStatus::Released;
+        Ok(())
+    }
+
+    /// Returns the funds to the depositor. The beneficiary may refund a funded escrow; once
+    /// disputed, only the arbiter can.
+    pub fn refund(&mut self, caller: &str, token: &mut Token) -> Result<(), TokenError> {
+        let allowed = match self.status {
+            EscrowStatus::Funded => caller == self.beneficiary || caller == self.arbiter,
+            EscrowStatus::Disputed => caller == self.arbiter,
+            _ => return Err(TokenError::InvalidState),
+        };
+        if !allowed {
+            return Err(TokenError::Unauthorized);
+        }
+        token.credit(&self.depositor, self.amount)?;
+        self.status = EscrowStatus::Refunded;
+        Ok(())
+    }
+
+    /// Freezes a funded escrow until the arbiter settles it.
+    pub fn dispute(&mut self, caller: &str) -> Result<(), TokenError> {
+        if caller != self.depositor && caller != self.beneficiary {
+            return Err(TokenError::Unauthorized);
+        }
+        if self.status != EscrowStatus::Funded {
+            return Err(TokenError::InvalidState);
+        }
+        self.status = EscrowStatus::Disputed;
+        Ok(())
+    }
+
+    /// Releases the funds to `beneficiary` instead, as the arbiter awards them.
+    pub fn award(
+        &mut self,
+        caller: &str,
+        beneficiary: &str,
+        token: &mut Token,
+    ) -> Result<(), TokenError> {
+        if caller != self.arbiter {
+            return Err(TokenError::Unauthorized);
+        }
+        if !matches!(self.status, EscrowStatus::Funded | EscrowStatus::Disputed) {
+            return Err(TokenError::InvalidState);
+        }
+        self.beneficiary = beneficiary.to_string();
+        self.release(caller, token)
+    }
+
+    /// Burns the funds, as the arbiter forfeits them to nobody.
+    pub fn forfeit(&mut self, caller: &str, token: &mut Token) -> Result<(), TokenError> {
+        if caller != self.arbiter {
+            return Err(TokenError::Unauthorized);
+        }
+        if !matches!(self.status, EscrowStatus::Funded | EscrowStatus::Disputed) {
+            return Err(TokenError::InvalidState);
+        }
+        token.total_supply -= self.amount;
+        self.status = EscrowStatus::Forfeited;
+        Ok(())
+    }
+}
+
+impl EscrowBook {
+    /// Records a new escrow and returns its id. No tokens move until it is funded.
+    pub fn create(
+        &mut self,
+        token: &str,
+        depositor: &str,
+        beneficiary: &str,
+        arbiter: &str,
+        amount: u128,
+    ) -> Result<u64, TokenError> {
+        let escrow = Escrow::new(token, depositor, beneficiary, arbiter, amount)?;
+        let id = self.next_id;
+        self.next_id += 1;
+        self.escrows.insert(id, escrow);
+        Ok(id)
+    }
+
+    /// Moves the escrowed amount out of the depositor's balance.
+    pub fn fund(
+        &mut self,
+        id: u64,
+        caller: &str,
+        tokens: &mut HashMap<String, Token>,
+    ) -> Result<(), TokenError> {
+        let (escrow, token) = self.escrow_and_token(id, tokens)?;
+        escrow.fund(caller, token)
+    }
+
+    /// Pays the beneficiary, see [`Escrow::release`].
+    pub fn release(
+        &mut self,
+        id: u64,
+        caller: &str,
+        tokens: &mut HashMap<String, Token>,
+    ) -> Result<(), TokenError> {
+        let (escrow, token) = self.escrow_and_token(id, tokens)?;
+        escrow.release(caller, token)
+    }
+
+    /// Returns the funds to the depositor, see [`Escrow::refund`].
+    pub fn refund(
+        &mut self,
+        id: u64,
+        caller: &str,
+        tokens: &mut HashMap<String, Token>,
+    ) -> Result<(), TokenError> {
+        let (escrow, token) = self.escrow_and_token(id, tokens)?;
+        escrow.refund(caller, token)
+    }
+
+    /// Freezes a funded escrow until the arbiter settles it.
+    pub fn dispute(&mut self, id: u64, caller: &str) -> Result<(), TokenError> {
+        let escrow = self.escrows.get_mut(&id).ok_or(TokenError::NotFound)?;
+        escrow.dispute(caller)
+    }
+
+    fn escrow_and_token<'a>(
+        &'a mut self,
+        id: u64,
+        tokens: &'a mut HashMap<String, Token>,
+    ) -> Result<(&'a mut Escrow, &'a mut Token), TokenError> {
+        let escrow = self.escrows.get_mut(&id).ok_or(TokenError::NotFound)?;
+        let token = tokens.get_mut(&escrow.token).ok_or(TokenError::NotFound)?;
+        Ok((escrow, token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::TokenMetadata;
+    use crate::policy::TransferPolicy;
+
+    fn tokens() -> HashMap<String, Token> {
+        let token = Token {
+            name: "Moon".to_string(),
+            symbol: "MOON".to_string(),
+            total_supply: 1_000,
+            decimals: 0,
+            metadata_uri: None,
+            balances: HashMap::from([("alice".to_string(), 1_000)]),
+            creator: "alice".to_string(),
+            policy: TransferPolicy::default(),
+            metadata: TokenMetadata::default(),
+            pending_metadata: None,
+            soulbound: None,
+        };
+        HashMap::from([("Moon".to_string(), token)])
+    }
+
+    /// The tokens in balances and in funded or disputed escrows.
+    fn accounted(book: &EscrowBook, tokens: &HashMap<String, Token>) -> u128 {
+        let held: u128 = book
+            .escrows
+            .values()
+            .filter(|escrow| matches!(escrow.status, EscrowStatus::Funded | EscrowStatus::Disputed))
+            .map(|escrow| escrow.amount)
+            .sum();
+        tokens["Moon"].balances.values().sum::<u128>() + held
+    }
+
+    #[test]
+    fn escrows_conserve_the_supply() {
+        let (mut book, mut tokens) = (EscrowBook::default(), tokens());
+        let released = book.create("Moon", "alice", "bob", "carol", 300).unwrap();
+        let refunded = book.create("Moon", "alice", "bob", "carol", 200).unwrap();
+        assert_eq!(tokens["Moon"].balance("alice"), 1_000);
+
+        book.fund(released, "alice", &mut tokens).unwrap();
+        book.fund(refunded, "alice", &mut tokens).unwrap();
+        assert_eq!(tokens["Moon"].balance("alice"), 500);
+        assert_eq!(accounted(&book, &tokens), 1_000);
+
+        book.release(released, "alice", &mut tokens).unwrap();
+        book.dispute(refunded, "bob").unwrap();
+        assert_eq!(accounted(&book, &tokens), 1_000);
+        book.refund(refunded, "carol", &mut tokens).unwrap();
+
+        assert_eq!(tokens["Moon"].balance("alice"), 700);
+        assert_eq!(tokens["Moon"].balance("bob"), 300);
+        assert_eq!(accounted(&book, &tokens), 1_000);
+        assert_eq!(book.escrows[&released].status, EscrowStatus::Released);
+        assert_eq!(book.escrows[&refunded].status, EscrowStatus::Refunded);
+    }
+
+    #[test]
+    fn only_the_parties_settle_an_escrow() {
+        let (mut book, mut tokens) = (EscrowBook::default(), tokens());
+        let id = book.create("Moon", "alice", "bob", "carol", 100).unwrap();
+        assert!(matches!(
+            book.release(id, "alice", &mut tokens),
+            Err(TokenError::InvalidState)
+        ));
+        assert!(matches!(
+            book.fund(id, "bob", &mut tokens),
+            Err(TokenError::Unauthorized)
+        ));
+        book.fund(id, "alice", &mut tokens).unwrap();
+        assert!(matches!(
+            book.fund(id, "alice", &mut tokens),
+            Err(TokenError::InvalidState)
+        ));
+
+        // The beneficiary cannot pay themselves, nor the depositor take the funds back.
+        assert!(matches!(
+            book.release(id, "bob", &mut tokens),
+            Err(TokenError::Unauthorized)
+        ));
+        assert!(matches!(
+            book.refund(id, "alice", &mut tokens),
+            Err(TokenError::Unauthorized)
+        ));
+        assert!(matches!(
+            book.dispute(id, "carol"),
+            Err(TokenError::Unauthorized)
+        ));
+
+        // Once disputed, only the arbiter decides.
+        book.dispute(id, "alice").unwrap();
+        assert!(matches!(
+            book.release(id, "alice", &mut tokens),
+            Err(TokenError::Unauthorized)
+        ));
+        assert!(matches!(
+            book.refund(id, "bob", &mut tokens),
+            Err(TokenError::Unauthorized)
+        ));
+        book.release(id, "carol", &mut tokens).unwrap();
+        assert!(matches!(
+            book.refund(id, "carol", &mut tokens),
+            Err(TokenError::InvalidState)
+        ));
+        assert_eq!(tokens["Moon"].balance("bob"), 100);
+    }
+
+    #[test]
+    fn arbiters_award_or_burn_the_funds() {
+        let mut token = tokens().remove("Moon").unwrap();
+        let mut awarded = Escrow::new("Moon", "alice", "carol", "carol", 300).unwrap();
+        let mut burned = Escrow::new("Moon", "alice", "carol", "carol", 200).unwrap();
+        awarded.fund("alice", &mut token).unwrap();
+        burned.fund("alice", &mut token).unwrap();
+        assert!(matches!(
+            awarded.award("alice", "alice", &mut token),
+            Err(TokenError::Unauthorized)
+        ));
+        assert!(matches!(
+            burned.forfeit("bob", &mut token),
+            Err(TokenError::Unauthorized)
+        ));
+
+        awarded.award("carol", "bob", &mut token).unwrap();
+        burned.forfeit("carol", &mut token).unwrap();
+        assert!(matches!(
+            burned.forfeit("carol", &mut token),
+            Err(TokenError::InvalidState)
+        ));
+        assert_eq!(token.balance("bob"), 300);
+        assert_eq!(token.balance("alice"), 500);
+        assert_eq!(token.total_supply, 800);
+        assert_eq!(burned.status, EscrowStatus::Forfeited);
+    }
+
+    #[test]
+    fn escrows_check_their_amounts_and_tokens() {
+        let (mut book, mut tokens) = (EscrowBook::default(), tokens());
+        assert!(matches!(
+            book.create("Moon", "alice", "bob", "carol", 0),
+            Err(TokenError::InvalidAmount)
+        ));
+        let id = book.create("Moon", "alice", "bob", "carol", 1_001).unwrap();
+        assert!(matches!(
+            book.fund(id, "alice", &mut tokens),
+            Err(TokenError::InsufficientBalance)
+        ));
+        assert!(matches!(
+            book.fund(id + 1, "alice", &mut tokens),
+            Err(TokenError::NotFound)
+        ));
+    }
+}
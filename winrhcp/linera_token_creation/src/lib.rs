@@ -0,0 +1,131 @@
+pub mod curve;
+pub mod errors;
+pub mod escrow;
+pub mod metadata;
+pub mod policy;
+pub mod pool;
+pub mod portfolio;
+pub mod rewards;
+pub mod stream;
+pub mod supply;
+pub mod types;
+pub mod validation;
+pub mod wrap;
+
+use async_graphql::{Request, Response};
+use linera_sdk::base::{Account, ApplicationId, ChainId, ContractAbi, Owner, ServiceAbi};
+use serde::{Deserialize, Serialize};
+
+/// The ABI of the token application, for other applications to call it with `Operation`.
+///
+/// Campaign applications mint reward tokens with `Operation::MintReward`, passed to
+/// `ContractRuntime::call_application` with the id of the token application typed with this
+/// ABI, on the chain the token was created on. The call must be authenticated, for the token
+/// application to know the calling application, which the creator of the token must first
+/// authorize with `SetMinter`.
+pub struct TokenAbi;
+
+impl ContractAbi for TokenAbi {
+    type Operation = Operation;
+    type Response = TokenResponse;
+}
+
+impl ServiceAbi for TokenAbi {
+    type Query = Request;
+    type QueryResponse = Response;
+}
+
+/// The operations of the on-chain token application. Accounts are the owners signing the
+/// blocks: tokens are created by, and moved out of the balance of, the signer.
+#[derive(Debug, Deserialize, Serialize)]
+pub enum Operation {
+    /// Creates a token, with its whole supply in the signer's balance.
+    CreateToken {
+        name: String,
+        symbol: String,
+        /// The supply in base units, as are all amounts.
+        total_supply: u128,
+        decimals: u8,
+        metadata_uri: Option<String>,
+    },
+    Transfer {
+        token: String,
+        to: Owner,
+        amount: u128,
+    },
+    /// Mints new tokens to `recipient`. Only the creator of the token can.
+    Mint {
+        token: String,
+        recipient: Owner,
+        amount: u128,
+    },
+    /// Burns tokens out of the creator's balance. Only the creator of the token can.
+    Burn { token: String, amount: u128 },
+    /// Moves tokens of the signer to an account on another chain. Tokens leaving the chain
+    /// they were created on are escrowed there until they come back; elsewhere they are
+    /// burned, and minted again on the chain they reach.
+    CrossChainTransfer {
+        symbol: String,
+        amount: u128,
+        target_account: Account,
+    },
+    /// Lets application `application_id` mint `token` with `MintReward`, or stops it when
+    /// `authorized` is false. Only the creator of the token can.
+    SetMinter {
+        token: String,
+        application_id: ApplicationId,
+        authorized: bool,
+    },
+    /// Mints reward tokens to `recipient` on behalf of the creator of `token`. Only the
+    /// applications the creator authorized with `SetMinter` can, by calling this application
+    /// on the chain the token was created on. Rewards for an account on another chain are
+    /// escrowed here and sent there.
+    MintReward {
+        token: String,
+        recipient: Account,
+        amount: u128,
+    },
+    /// Moves `amount` of `token` out of the signer's balance into a new escrow, which only
+    /// the application calling this one settles. Only applications can, forwarding the
+    /// signer. Answered with the id of the escrow.
+    OpenEscrow { token: String, amount: u128 },
+    /// Pays the tokens of escrow `escrow_id` to `recipient`, on this chain or another, or
+    /// burns them if `None`. Only the application that opened the escrow can.
+    SettleEscrow {
+        escrow_id: u64,
+        recipient: Option<Account>,
+    },
+}
+
+/// What the token application answers the applications calling it.
+#[derive(Debug, Deserialize, Serialize)]
+pub enum TokenResponse {
+    Ok,
+    /// The id of the escrow `OpenEscrow` opened.
+    Escrow(u64),
+}
+
+/// The messages the token application sends between chains.
+#[derive(Debug, Deserialize, Serialize)]
+pub enum Message {
+    /// Credits `amount` tokens with ticker `symbol`, which left the signer's balance on the
+    /// sending chain, to `target_account`. If the receiving chain rejects them, the message
+    /// bounces and the sender is refunded.
+    CrossChainTransfer {
+        symbol: String,
+        amount: u128,
+        target_account: Account,
+        /// The token, for chains receiving it for the first time to record it.
+        origin: TokenOrigin,
+    },
+}
+
+/// A token moving between chains, as created on its chain of origin.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TokenOrigin {
+    pub chain_id: ChainId,
+    pub name: String,
+    pub decimals: u8,
+    pub metadata_uri: Option<String>,
+    pub creator: String,
+}
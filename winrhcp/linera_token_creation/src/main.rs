@@ -1,22 +1,626 @@
-mod contract;
+#![cfg_attr(target_arch = "wasm32", no_main)]
+// The HTTP prototype serves from a host, not from the chain.
+#![cfg(not(target_arch = "wasm32"))]
+
+mod api;
 mod views;
-mod types;
-mod errors;
 
-use actix_web::{post, web, App, HttpServer, Responder, HttpResponse};
+use crate::api::{
+    authenticate, award, awards, balance, burn, buy_from_curve, buybacks, cancel_metadata,
+    cancel_stream, change_policy, claim_referral_fees, clawback, configure_buyback, create_curve,
+    create_escrow, create_pool, create_reward_token, create_stream, create_token, curve,
+    deposit_native, dispute_escrow, fund_escrow, mint, poke, policy_events, pool, portfolio,
+    propose_metadata, quote, referral_fees, refund_escrow, release_escrow, sell_to_curve,
+    token_by_symbol, token_metadata, tokens, trade, transfer, withdraw_native, withdraw_stream,
+    ApiError,
+};
+use actix_web::{get, post, web, App, HttpRequest, HttpResponse, HttpServer, Responder};
+use linera_sdk::base::{PublicKey, Signature};
+use linera_token_creation::errors::{TokenError, ValidationError};
+use linera_token_creation::types::{
+    AwardRequest, BurnRequest, BuybackRequest, ClawbackRequest, CreateCurveRequest,
+    CreateEscrowRequest, CreatePoolRequest, CreateStreamRequest, CurveTradeRequest,
+    MetadataProposalRequest, MintRequest, PolicyChangeRequest, QuoteRequest, RewardTokenRequest,
+    SignedRequest, TokenListRequest, TokenRequest, TokenTransferRequest, TradeRequest,
+    TransferRequest, WrapRequest,
+};
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
-use crate::contract::create_token;
-use crate::types::TokenRequest;
+use std::str::FromStr;
+
+/// Answers each failure with its status: `401 Unauthorized` for requests not signed by the
+/// account they act for, `400 Bad Request` for unreadable bodies, `404 Not Found` for unknown
+/// tokens, pools, escrows and streams, `403 Forbidden` for callers without the right, `409
+/// Conflict` for reused names and symbols and for operations the current balances or settings
+/// forbid, `422 Unprocessable Entity` for invalid tokens and amounts, and `500 Internal Server
+/// Error` when the state cannot be saved.
+fn error_response(err: ApiError) -> HttpResponse {
+    let message = format!("Error: {:?}", err);
+    match err {
+        ApiError::Unauthenticated => HttpResponse::Unauthorized().json(message),
+        ApiError::Malformed(err) => HttpResponse::BadRequest().json(format!("Error: {}", err)),
+        ApiError::Invalid(ValidationError::DuplicateName | ValidationError::DuplicateSymbol) => {
+            HttpResponse::Conflict().json(message)
+        }
+        ApiError::Invalid(_) => HttpResponse::UnprocessableEntity().json(message),
+        ApiError::Token(TokenError::NotFound) => HttpResponse::NotFound().json(message),
+        ApiError::Token(TokenError::Unauthorized) => HttpResponse::Forbidden().json(message),
+        ApiError::Token(TokenError::InvalidAmount | TokenError::Overflow) => {
+            HttpResponse::UnprocessableEntity().json(message)
+        }
+        ApiError::Token(
+            TokenError::InsufficientBalance
+            | TokenError::InsufficientLiquidity
+            | TokenError::SlippageExceeded
+            | TokenError::InvalidState
+            | TokenError::TransfersPaused
+            | TokenError::NotAllowlisted
+            | TokenError::MaxWalletExceeded
+            | TokenError::Soulbound,
+        ) => HttpResponse::Conflict().json(message),
+        ApiError::Token(TokenError::BlockchainError) => {
+            HttpResponse::InternalServerError().json(message)
+        }
+        ApiError::Storage(err) => {
+            HttpResponse::InternalServerError().json(format!("Error: {}", err))
+        }
+    }
+}
+
+/// Returns the value of the header `name` of `http`.
+fn header<'a>(http: &'a HttpRequest, name: &str) -> Result<&'a str, ApiError> {
+    http.headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .ok_or(ApiError::Unauthenticated)
+}
+
+/// Returns the account acting with `http`: the owner of the key in its `X-Public-Key`
+/// header, once its `X-Signature` header is checked to sign its path, its `X-Nonce` header
+/// and `body`.
+async fn authenticated_caller(http: &HttpRequest, body: &web::Bytes) -> Result<String, ApiError> {
+    let public_key = PublicKey::from_str(header(http, "X-Public-Key")?)
+        .map_err(|_| ApiError::Unauthenticated)?;
+    let nonce = header(http, "X-Nonce")?
+        .parse()
+        .map_err(|_| ApiError::Unauthenticated)?;
+    let signature: Signature = serde_json::from_value(header(http, "X-Signature")?.into())
+        .map_err(|_| ApiError::Unauthenticated)?;
+    let request = SignedRequest {
+        path: http.path().to_string(),
+        nonce,
+        body: body.to_vec(),
+    };
+    authenticate(public_key, signature, &request).await
+}
+
+/// Returns the account acting with `http`, with the JSON `body` it signed.
+async fn signed<T: DeserializeOwned>(
+    http: &HttpRequest,
+    body: &web::Bytes,
+) -> Result<(String, T), ApiError> {
+    let caller = authenticated_caller(http, body).await?;
+    let request =
+        serde_json::from_slice(body).map_err(|err| ApiError::Malformed(err.to_string()))?;
+    Ok((caller, request))
+}
 
 #[post("/create_token")]
-async fn create_token_endpoint(req: web::Json<TokenRequest>) -> impl Responder {
+async fn create_token_endpoint(http: HttpRequest, body: web::Bytes) -> impl Responder {
+    let (caller, req) = match signed::<TokenRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
     let token_name = &req.name;
     let token_symbol = &req.symbol;
     let total_supply = req.total_supply;
 
-    match create_token(token_name, token_symbol, total_supply).await {
+    match create_token(
+        token_name,
+        token_symbol,
+        total_supply,
+        req.decimals,
+        req.metadata_uri,
+        &caller,
+    )
+    .await
+    {
         Ok(_) => HttpResponse::Ok().json("Token created successfully"),
-        Err(err) => HttpResponse::BadRequest().json(format!("Error: {:?}", err)),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/rewards/create_token")]
+async fn create_reward_token_endpoint(http: HttpRequest, body: web::Bytes) -> impl Responder {
+    let (caller, req) = match signed::<RewardTokenRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
+    match create_reward_token(&req.name, &req.symbol, &caller, req.clawback_admins).await {
+        Ok(_) => HttpResponse::Ok().json("Reward token created successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/token/{name}/award")]
+async fn award_endpoint(
+    name: web::Path<String>,
+    http: HttpRequest,
+    body: web::Bytes,
+) -> impl Responder {
+    let (caller, req) = match signed::<AwardRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
+    match award(&name, &caller, &req.recipient, req.amount, &req.achievement).await {
+        Ok(_) => HttpResponse::Ok().json("Reward awarded successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/token/{name}/clawback")]
+async fn clawback_endpoint(
+    name: web::Path<String>,
+    http: HttpRequest,
+    body: web::Bytes,
+) -> impl Responder {
+    let (caller, req) = match signed::<ClawbackRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
+    match clawback(&name, &caller, &req.holder, req.amount).await {
+        Ok(_) => HttpResponse::Ok().json("Reward clawed back successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[get("/token/{name}/awards")]
+async fn awards_endpoint(name: web::Path<String>) -> impl Responder {
+    HttpResponse::Ok().json(awards(&name).await)
+}
+
+#[post("/wrap/deposit")]
+async fn deposit_endpoint(http: HttpRequest, body: web::Bytes) -> impl Responder {
+    let (caller, req) = match signed::<WrapRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
+    match deposit_native(&caller, req.amount).await {
+        Ok(_) => HttpResponse::Ok().json("Tokens wrapped successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/wrap/withdraw")]
+async fn withdraw_endpoint(http: HttpRequest, body: web::Bytes) -> impl Responder {
+    let (caller, req) = match signed::<WrapRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
+    match withdraw_native(&caller, req.amount).await {
+        Ok(_) => HttpResponse::Ok().json("Tokens unwrapped successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/pool/create")]
+async fn create_pool_endpoint(http: HttpRequest, body: web::Bytes) -> impl Responder {
+    let (caller, req) = match signed::<CreatePoolRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
+    match create_pool(&req.token, &caller, req.token_amount, req.native_amount).await {
+        Ok(_) => HttpResponse::Ok().json("Pool created successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[get("/pool/{token}")]
+async fn pool_endpoint(token: web::Path<String>) -> impl Responder {
+    match pool(&token).await {
+        Some(pool) => HttpResponse::Ok().json(pool),
+        None => HttpResponse::NotFound().json("Unknown pool"),
+    }
+}
+
+#[get("/pool/{token}/quote")]
+async fn quote_endpoint(token: web::Path<String>, req: web::Query<QuoteRequest>) -> impl Responder {
+    match quote(&token, req.kind, req.amount).await {
+        Ok(quote) => HttpResponse::Ok().json(quote),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/pool/{token}/trade")]
+async fn trade_endpoint(
+    token: web::Path<String>,
+    http: HttpRequest,
+    body: web::Bytes,
+) -> impl Responder {
+    let (caller, req) = match signed::<TradeRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
+    let referrer = req.referrer.as_deref();
+    match trade(&token, &caller, referrer, req.kind, req.amount, req.limit).await {
+        Ok(quote) => HttpResponse::Ok().json(quote),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/pool/{token}/buyback")]
+async fn configure_buyback_endpoint(
+    token: web::Path<String>,
+    http: HttpRequest,
+    body: web::Bytes,
+) -> impl Responder {
+    let (caller, req) = match signed::<BuybackRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
+    match configure_buyback(&token, &caller, req.share_bps, req.interval_secs).await {
+        Ok(_) => HttpResponse::Ok().json("Buybacks configured successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/pool/{token}/poke")]
+async fn poke_endpoint(token: web::Path<String>) -> impl Responder {
+    match poke(&token).await {
+        Ok(buyback) => HttpResponse::Ok().json(buyback),
+        Err(err) => error_response(err),
+    }
+}
+
+#[get("/pool/{token}/buybacks")]
+async fn buybacks_endpoint(token: web::Path<String>) -> impl Responder {
+    HttpResponse::Ok().json(buybacks(&token).await)
+}
+
+#[get("/referrals/{referrer}")]
+async fn referral_fees_endpoint(referrer: web::Path<String>) -> impl Responder {
+    HttpResponse::Ok().json(referral_fees(&referrer).await)
+}
+
+#[post("/referrals/claim")]
+async fn claim_referral_fees_endpoint(http: HttpRequest, body: web::Bytes) -> impl Responder {
+    let caller = match authenticated_caller(&http, &body).await {
+        Ok(caller) => caller,
+        Err(err) => return error_response(err),
+    };
+    match claim_referral_fees(&caller).await {
+        Ok(amount) => HttpResponse::Ok().json(amount),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/curve/create")]
+async fn create_curve_endpoint(http: HttpRequest, body: web::Bytes) -> impl Responder {
+    let (caller, req) = match signed::<CreateCurveRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
+    match create_curve(
+        &req.token,
+        &caller,
+        req.kind,
+        req.base_price,
+        req.slope,
+        req.amount,
+    )
+    .await
+    {
+        Ok(_) => HttpResponse::Ok().json("Curve created successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[get("/curve/{token}")]
+async fn curve_endpoint(token: web::Path<String>) -> impl Responder {
+    match curve(&token).await {
+        Some(curve) => HttpResponse::Ok().json(curve),
+        None => HttpResponse::NotFound().json("Unknown curve"),
+    }
+}
+
+#[post("/buy")]
+async fn buy_endpoint(http: HttpRequest, body: web::Bytes) -> impl Responder {
+    let (caller, req) = match signed::<CurveTradeRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
+    match buy_from_curve(&req.token, &caller, req.amount, req.limit).await {
+        Ok(cost) => HttpResponse::Ok().json(cost),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/sell")]
+async fn sell_endpoint(http: HttpRequest, body: web::Bytes) -> impl Responder {
+    let (caller, req) = match signed::<CurveTradeRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
+    match sell_to_curve(&req.token, &caller, req.amount, req.limit).await {
+        Ok(proceeds) => HttpResponse::Ok().json(proceeds),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/escrow/create")]
+async fn create_escrow_endpoint(http: HttpRequest, body: web::Bytes) -> impl Responder {
+    let (caller, req) = match signed::<CreateEscrowRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
+    match create_escrow(
+        &req.token,
+        &caller,
+        &req.beneficiary,
+        &req.arbiter,
+        req.amount,
+    )
+    .await
+    {
+        Ok(id) => HttpResponse::Ok().json(id),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/escrow/{id}/fund")]
+async fn fund_escrow_endpoint(
+    id: web::Path<u64>,
+    http: HttpRequest,
+    body: web::Bytes,
+) -> impl Responder {
+    let caller = match authenticated_caller(&http, &body).await {
+        Ok(caller) => caller,
+        Err(err) => return error_response(err),
+    };
+    match fund_escrow(id.into_inner(), &caller).await {
+        Ok(_) => HttpResponse::Ok().json("Escrow funded successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/escrow/{id}/release")]
+async fn release_escrow_endpoint(
+    id: web::Path<u64>,
+    http: HttpRequest,
+    body: web::Bytes,
+) -> impl Responder {
+    let caller = match authenticated_caller(&http, &body).await {
+        Ok(caller) => caller,
+        Err(err) => return error_response(err),
+    };
+    match release_escrow(id.into_inner(), &caller).await {
+        Ok(_) => HttpResponse::Ok().json("Escrow released successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/escrow/{id}/refund")]
+async fn refund_escrow_endpoint(
+    id: web::Path<u64>,
+    http: HttpRequest,
+    body: web::Bytes,
+) -> impl Responder {
+    let caller = match authenticated_caller(&http, &body).await {
+        Ok(caller) => caller,
+        Err(err) => return error_response(err),
+    };
+    match refund_escrow(id.into_inner(), &caller).await {
+        Ok(_) => HttpResponse::Ok().json("Escrow refunded successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/escrow/{id}/dispute")]
+async fn dispute_escrow_endpoint(
+    id: web::Path<u64>,
+    http: HttpRequest,
+    body: web::Bytes,
+) -> impl Responder {
+    let caller = match authenticated_caller(&http, &body).await {
+        Ok(caller) => caller,
+        Err(err) => return error_response(err),
+    };
+    match dispute_escrow(id.into_inner(), &caller).await {
+        Ok(_) => HttpResponse::Ok().json("Escrow disputed successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/stream/create")]
+async fn create_stream_endpoint(http: HttpRequest, body: web::Bytes) -> impl Responder {
+    let (caller, req) = match signed::<CreateStreamRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
+    match create_stream(
+        &req.token,
+        &caller,
+        &req.recipient,
+        req.amount,
+        req.start,
+        req.end,
+    )
+    .await
+    {
+        Ok(id) => HttpResponse::Ok().json(id),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/stream/{id}/withdraw")]
+async fn withdraw_stream_endpoint(
+    id: web::Path<u64>,
+    http: HttpRequest,
+    body: web::Bytes,
+) -> impl Responder {
+    let caller = match authenticated_caller(&http, &body).await {
+        Ok(caller) => caller,
+        Err(err) => return error_response(err),
+    };
+    match withdraw_stream(id.into_inner(), &caller).await {
+        Ok(amount) => HttpResponse::Ok().json(amount),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/stream/{id}/cancel")]
+async fn cancel_stream_endpoint(
+    id: web::Path<u64>,
+    http: HttpRequest,
+    body: web::Bytes,
+) -> impl Responder {
+    let caller = match authenticated_caller(&http, &body).await {
+        Ok(caller) => caller,
+        Err(err) => return error_response(err),
+    };
+    match cancel_stream(id.into_inner(), &caller).await {
+        Ok(_) => HttpResponse::Ok().json("Stream cancelled successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/transfer")]
+async fn token_transfer_endpoint(http: HttpRequest, body: web::Bytes) -> impl Responder {
+    let (caller, req) = match signed::<TokenTransferRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
+    match transfer(&req.token, &caller, &req.to, req.amount).await {
+        Ok(_) => HttpResponse::Ok().json("Tokens transferred successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/mint")]
+async fn mint_endpoint(http: HttpRequest, body: web::Bytes) -> impl Responder {
+    let (caller, req) = match signed::<MintRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
+    match mint(&req.token, &caller, &req.recipient, req.amount).await {
+        Ok(_) => HttpResponse::Ok().json("Tokens minted successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/burn")]
+async fn burn_endpoint(http: HttpRequest, body: web::Bytes) -> impl Responder {
+    let (caller, req) = match signed::<BurnRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
+    match burn(&req.token, &caller, req.amount).await {
+        Ok(_) => HttpResponse::Ok().json("Tokens burned successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/token/{name}/transfer")]
+async fn transfer_endpoint(
+    name: web::Path<String>,
+    http: HttpRequest,
+    body: web::Bytes,
+) -> impl Responder {
+    let (caller, req) = match signed::<TransferRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
+    match transfer(&name, &caller, &req.to, req.amount).await {
+        Ok(_) => HttpResponse::Ok().json("Tokens transferred successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/token/{name}/policy")]
+async fn change_policy_endpoint(
+    name: web::Path<String>,
+    http: HttpRequest,
+    body: web::Bytes,
+) -> impl Responder {
+    let (caller, req) = match signed::<PolicyChangeRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
+    match change_policy(&name, &caller, req.change).await {
+        Ok(_) => HttpResponse::Ok().json("Transfer policy changed successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[get("/token/{name}/policy/events")]
+async fn policy_events_endpoint(name: web::Path<String>) -> impl Responder {
+    HttpResponse::Ok().json(policy_events(&name).await)
+}
+
+#[post("/token/{name}/metadata/propose")]
+async fn propose_metadata_endpoint(
+    name: web::Path<String>,
+    http: HttpRequest,
+    body: web::Bytes,
+) -> impl Responder {
+    let (caller, req) = match signed::<MetadataProposalRequest>(&http, &body).await {
+        Ok(signed) => signed,
+        Err(err) => return error_response(err),
+    };
+    match propose_metadata(&name, &caller, req.metadata).await {
+        Ok(_) => HttpResponse::Ok().json("Metadata change proposed successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[post("/token/{name}/metadata/cancel")]
+async fn cancel_metadata_endpoint(
+    name: web::Path<String>,
+    http: HttpRequest,
+    body: web::Bytes,
+) -> impl Responder {
+    let caller = match authenticated_caller(&http, &body).await {
+        Ok(caller) => caller,
+        Err(err) => return error_response(err),
+    };
+    match cancel_metadata(&name, &caller).await {
+        Ok(_) => HttpResponse::Ok().json("Metadata proposal cancelled successfully"),
+        Err(err) => error_response(err),
+    }
+}
+
+#[get("/token/{name}/metadata")]
+async fn token_metadata_endpoint(name: web::Path<String>) -> impl Responder {
+    match token_metadata(&name).await {
+        Ok(metadata) => HttpResponse::Ok().json(metadata),
+        Err(err) => error_response(err),
+    }
+}
+
+#[get("/token/{name}/balance/{owner}")]
+async fn balance_endpoint(path: web::Path<(String, String)>) -> impl Responder {
+    let (name, owner) = path.into_inner();
+    match balance(&name, &owner).await {
+        Some(balance) => HttpResponse::Ok().json(balance),
+        None => HttpResponse::NotFound().json("Unknown token"),
+    }
+}
+
+#[get("/portfolio/{owner}")]
+async fn portfolio_endpoint(owner: web::Path<String>) -> impl Responder {
+    HttpResponse::Ok().json(portfolio(&owner).await)
+}
+
+#[get("/tokens")]
+async fn tokens_endpoint(req: web::Query<TokenListRequest>) -> impl Responder {
+    HttpResponse::Ok().json(tokens(req.offset, req.limit).await)
+}
+
+#[get("/tokens/{symbol}")]
+async fn token_endpoint(symbol: web::Path<String>) -> impl Responder {
+    match token_by_symbol(&symbol).await {
+        Some(token) => HttpResponse::Ok().json(token),
+        None => HttpResponse::NotFound().json("Unknown token"),
     }
 }
 
@@ -25,8 +629,48 @@ async fn main() -> std::io::Result<()> {
     HttpServer::new(|| {
         App::new()
             .service(create_token_endpoint)
+            .service(create_reward_token_endpoint)
+            .service(award_endpoint)
+            .service(clawback_endpoint)
+            .service(awards_endpoint)
+            .service(deposit_endpoint)
+            .service(withdraw_endpoint)
+            .service(create_pool_endpoint)
+            .service(pool_endpoint)
+            .service(quote_endpoint)
+            .service(trade_endpoint)
+            .service(configure_buyback_endpoint)
+            .service(poke_endpoint)
+            .service(buybacks_endpoint)
+            .service(referral_fees_endpoint)
+            .service(claim_referral_fees_endpoint)
+            .service(create_curve_endpoint)
+            .service(curve_endpoint)
+            .service(buy_endpoint)
+            .service(sell_endpoint)
+            .service(create_escrow_endpoint)
+            .service(fund_escrow_endpoint)
+            .service(release_escrow_endpoint)
+            .service(refund_escrow_endpoint)
+            .service(dispute_escrow_endpoint)
+            .service(create_stream_endpoint)
+            .service(withdraw_stream_endpoint)
+            .service(cancel_stream_endpoint)
+            .service(mint_endpoint)
+            .service(burn_endpoint)
+            .service(token_transfer_endpoint)
+            .service(transfer_endpoint)
+            .service(balance_endpoint)
+            .service(change_policy_endpoint)
+            .service(policy_events_endpoint)
+            .service(propose_metadata_endpoint)
+            .service(cancel_metadata_endpoint)
+            .service(token_metadata_endpoint)
+            .service(portfolio_endpoint)
+            .service(tokens_endpoint)
+            .service(token_endpoint)
     })
-    .bind("127.0.0.1:8080")?
+    .bind(std::env::var("TOKEN_APP_ADDR").unwrap_or_else(|_| "127.0.0.1:8080".to_string()))?
     .run()
     .await
 }
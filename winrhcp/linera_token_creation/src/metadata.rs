@@ -0,0 +1,176 @@
+use crate::errors::TokenError;
+use crate::types::Token;
+use serde::{Deserialize, Serialize};
+
+/// How long holders can see a metadata change before it takes effect: two days.
+pub const METADATA_TIMELOCK_SECS: u64 = 2 * 24 * 60 * 60;
+
+/// What a token shows about itself, besides its name and symbol.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct TokenMetadata {
+    pub description: String,
+    pub links: Vec<String>,
+    /// URL of the token's icon.
+    pub icon: Option<String>,
+}
+
+/// A metadata change the creator proposed, that applies once the timelock is over.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MetadataProposal {
+    pub metadata: TokenMetadata,
+    /// When the change was proposed, in seconds since the Unix epoch.
+    pub proposed_at: u64,
+    pub effective_at: u64,
+}
+
+impl Token {
+    /// Proposes new metadata, replacing any pending proposal and restarting the timelock.
+    pub fn propose_metadata(
+        &mut self,
+        caller: &str,
+        metadata: TokenMetadata,
+        now: u64,
+    ) -> Result<(), TokenError> {
+        if caller != self.creator {
+            return Err(TokenError::Unauthorized);
+        }
+        self.apply_metadata(now);
+        self.pending_metadata = Some(MetadataProposal {
+            metadata,
+            proposed_at: now,
+            effective_at: now + METADATA_TIMELOCK_SECS,
+        });
+        Ok(())
+    }
+
+    /// Withdraws the pending proposal, if its timelock is not over yet.
+    pub fn cancel_metadata(&mut self, caller: &str, now: u64) -> Result<(), TokenError> {
+        if caller != self.creator {
+            return Err(TokenError::Unauthorized);
+        }
+        self.apply_metadata(now);
+        self.pending_metadata
+            .take()
+            .map(|_| ())
+            .ok_or(TokenError::NotFound)
+    }
+
+    /// Makes the pending proposal the token's metadata if its timelock is over at `now`.
+    pub fn apply_metadata(&mut self, now: u64) {
+        let Some(proposal) = &self.pending_metadata else {
+            return;
+        };
+        if now >= proposal.effective_at {
+            let proposal = self.pending_metadata.take().unwrap();
+            self.metadata = proposal.metadata;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::TransferPolicy;
+    use std::collections::HashMap;
+
+    fn metadata(description: &str) -> TokenMetadata {
+        TokenMetadata {
+            description: description.to_string(),
+            ..TokenMetadata::default()
+        }
+    }
+
+    fn token() -> Token {
+        Token {
+            name: "Moon".to_string(),
+            symbol: "MOON".to_string(),
+            total_supply: 1_000,
+            decimals: 0,
+            metadata_uri: None,
+            balances: HashMap::from([("alice".to_string(), 1_000)]),
+            creator: "alice".to_string(),
+            policy: TransferPolicy::default(),
+            metadata: TokenMetadata::default(),
+            pending_metadata: None,
+            soulbound: None,
+        }
+    }
+
+    #[test]
+    fn proposals_apply_once_the_timelock_is_over() {
+        let mut token = token();
+        token
+            .propose_metadata("alice", metadata("to the moon"), 100)
+            .unwrap();
+        let proposal = token.pending_metadata.clone().unwrap();
+        assert_eq!(proposal.effective_at, 100 + METADATA_TIMELOCK_SECS);
+
+        token.apply_metadata(proposal.effective_at - 1);
+        assert_eq!(token.metadata, TokenMetadata::default());
+        token.apply_metadata(proposal.effective_at);
+        assert_eq!(token.metadata, metadata("to the moon"));
+        assert!(token.pending_metadata.is_none());
+    }
+
+    #[test]
+    fn new_proposals_restart_the_timelock() {
+        let mut token = token();
+        token
+            .propose_metadata("alice", metadata("first"), 0)
+            .unwrap();
+        token
+            .propose_metadata("alice", metadata("second"), 10)
+            .unwrap();
+        token.apply_metadata(METADATA_TIMELOCK_SECS);
+        assert_eq!(token.metadata, TokenMetadata::default());
+        token.apply_metadata(10 + METADATA_TIMELOCK_SECS);
+        assert_eq!(token.metadata, metadata("second"));
+
+        // A proposal made after the previous one matured keeps the matured one applied.
+        token
+            .propose_metadata("alice", metadata("third"), 0)
+            .unwrap();
+        token
+            .propose_metadata("alice", metadata("fourth"), METADATA_TIMELOCK_SECS)
+            .unwrap();
+        assert_eq!(token.metadata, metadata("third"));
+    }
+
+    #[test]
+    fn only_pending_proposals_can_be_cancelled() {
+        let mut token = token();
+        assert!(matches!(
+            token.cancel_metadata("alice", 0),
+            Err(TokenError::NotFound)
+        ));
+        token
+            .propose_metadata("alice", metadata("oops"), 0)
+            .unwrap();
+        assert!(matches!(
+            token.cancel_metadata("bob", 1),
+            Err(TokenError::Unauthorized)
+        ));
+        token.cancel_metadata("alice", 1).unwrap();
+        assert!(token.pending_metadata.is_none());
+
+        // Once the timelock is over the change is in effect and there is nothing to cancel.
+        token
+            .propose_metadata("alice", metadata("final"), 0)
+            .unwrap();
+        assert!(matches!(
+            token.cancel_metadata("alice", METADATA_TIMELOCK_SECS),
+            Err(TokenError::NotFound)
+        ));
+        assert_eq!(token.metadata, metadata("final"));
+    }
+
+    #[test]
+    fn only_the_creator_proposes() {
+        let mut token = token();
+        assert!(matches!(
+            token.propose_metadata("bob", metadata("rug"), 0),
+            Err(TokenError::Unauthorized)
+        ));
+        assert!(token.pending_metadata.is_none());
+    }
+}
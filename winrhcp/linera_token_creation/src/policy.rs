@@ -0,0 +1,243 @@
+use crate::errors::TokenError;
+use crate::types::Token;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Optional restrictions a creator puts on the transfers of their token, typically during a
+/// launch phase. The default policy restricts nothing.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct TransferPolicy {
+    /// Whether all balance movements are suspended.
+    pub paused: bool,
+    /// Accounts allowed to receive the token, if receiving is restricted.
+    pub allowlist: Option<HashSet<String>>,
+    /// The largest balance a single account may hold, if capped.
+    pub max_wallet: Option<u128>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum PolicyChange {
+    Paused(bool),
+    /// The allowlist was enabled, or disabled (`false`) which forgets its accounts.
+    AllowlistEnabled(bool),
+    Allowed(String),
+    Disallowed(String),
+    MaxWallet(Option<u128>),
+}
+
+/// A policy change, recorded so holders can follow what the creator changed and when.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PolicyEvent {
+    pub token: String,
+    pub change: PolicyChange,
+    /// When the change was made, in seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+impl TransferPolicy {
+    /// Applies `change` to the policy.
+    pub fn apply(&mut self, change: &PolicyChange) {
+        match change {
+            PolicyChange::Paused(paused) => self.paused = *paused,
+            PolicyChange::AllowlistEnabled(true) => {
+                self.allowlist.get_or_insert_with(HashSet::new);
+            }
+            PolicyChange::AllowlistEnabled(false) => self.allowlist = None,
+            PolicyChange::Allowed(owner) => {
+                self.allowlist
+                    .get_or_insert_with(HashSet::new)
+                    .insert(owner.clone());
+            }
+            PolicyChange::Disallowed(owner) => {
+                if let Some(allowlist) = &mut self.allowlist {
+                    allowlist.remove(owner);
+                }
+            }
+            PolicyChange::MaxWallet(max_wallet) => self.max_wallet = *max_wallet,
+        }
+    }
+
+    fn check_receive(&self, recipient: &str, new_balance: u128) -> Result<(), TokenError> {
+        if self.paused {
+            return Err(TokenError::TransfersPaused);
+        }
+        if let Some(allowlist) = &self.allowlist {
+            if !allowlist.contains(recipient) {
+                return Err(TokenError::NotAllowlisted);
+            }
+        }
+        if let Some(max_wallet) = self.max_wallet {
+            if new_balance > max_wallet {
+                return Err(TokenError::MaxWalletExceeded);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Every balance movement goes through these, so that the token's policy applies to plain
+/// transfers as well as to escrows and streams.
+impl Token {
+    /// Takes `amount` out of the balance of `owner`. Balances of soulbound tokens never
+    /// move, except through a clawback.
+    pub fn debit(&mut self, owner: &str, amount: u128) -> Result<(), TokenError> {
+        if self.soulbound.is_some() {
+            return Err(TokenError::Soulbound);
+        }
+        if self.policy.paused {
+            return Err(TokenError::TransfersPaused);
+        }
+        let balance = self
+            .balances
+            .get_mut(owner)
+            .ok_or(TokenError::InsufficientBalance)?;
+        if *balance < amount {
+            return Err(TokenError::InsufficientBalance);
+        }
+        *balance -= amount;
+        Ok(())
+    }
+
+    /// Adds `amount` to the balance of `owner`.
+    pub fn credit(&mut self, owner: &str, amount: u128) -> Result<(), TokenError> {
+        let balance = self.balances.get(owner).copied().unwrap_or(0);
+        let new_balance = balance.checked_add(amount).ok_or(TokenError::Overflow)?;
+        self.policy.check_receive(owner, new_balance)?;
+        self.balances.insert(owner.to_string(), new_balance);
+        Ok(())
+    }
+
+    pub fn balance(&self, owner: &str) -> u128 {
+        self.balances.get(owner).copied().unwrap_or(0)
+    }
+
+    /// Moves `amount` from `from` to `to`, or nothing if the policy refuses either side.
+    pub fn transfer(&mut self, from: &str, to: &str, amount: u128) -> Result<(), TokenError> {
+        if amount == 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        self.debit(from, amount)?;
+        if let Err(error) = self.credit(to, amount) {
+            // Puts the amount back, so that a refused transfer moves nothing.
+            *self.balances.entry(from.to_string()).or_default() += amount;
+            return Err(error);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::TokenMetadata;
+    use std::collections::HashMap;
+
+    fn token() -> Token {
+        Token {
+            name: "Moon".to_string(),
+            symbol: "MOON".to_string(),
+            total_supply: 1_000,
+            decimals: 0,
+            metadata_uri: None,
+            balances: HashMap::from([("alice".to_string(), 1_000)]),
+            creator: "alice".to_string(),
+            policy: TransferPolicy::default(),
+            metadata: TokenMetadata::default(),
+            pending_metadata: None,
+            soulbound: None,
+        }
+    }
+
+    #[test]
+    fn transfers_move_balances_within_the_supply() {
+        let mut token = token();
+        token.transfer("alice", "bob", 300).unwrap();
+        token.transfer("bob", "carol", 100).unwrap();
+        assert_eq!(
+            (
+                token.balance("alice"),
+                token.balance("bob"),
+                token.balance("carol")
+            ),
+            (700, 200, 100)
+        );
+        assert!(matches!(
+            token.transfer("bob", "carol", 201),
+            Err(TokenError::InsufficientBalance)
+        ));
+        assert!(matches!(
+            token.transfer("dave", "carol", 1),
+            Err(TokenError::InsufficientBalance)
+        ));
+        assert!(matches!(
+            token.transfer("bob", "carol", 0),
+            Err(TokenError::InvalidAmount)
+        ));
+        assert_eq!(token.balances.values().sum::<u128>(), token.total_supply);
+    }
+
+    #[test]
+    fn pausing_stops_every_movement() {
+        let mut token = token();
+        token.policy.apply(&PolicyChange::Paused(true));
+        assert!(matches!(
+            token.transfer("alice", "bob", 1),
+            Err(TokenError::TransfersPaused)
+        ));
+        assert!(matches!(
+            token.credit("bob", 1),
+            Err(TokenError::TransfersPaused)
+        ));
+        token.policy.apply(&PolicyChange::Paused(false));
+        token.transfer("alice", "bob", 1).unwrap();
+    }
+
+    #[test]
+    fn allowlists_restrict_recipients() {
+        let mut token = token();
+        token.policy.apply(&PolicyChange::AllowlistEnabled(true));
+        assert!(matches!(
+            token.transfer("alice", "bob", 1),
+            Err(TokenError::NotAllowlisted)
+        ));
+        token
+            .policy
+            .apply(&PolicyChange::Allowed("bob".to_string()));
+        token.transfer("alice", "bob", 1).unwrap();
+        token
+            .policy
+            .apply(&PolicyChange::Disallowed("bob".to_string()));
+        assert!(matches!(
+            token.transfer("alice", "bob", 1),
+            Err(TokenError::NotAllowlisted)
+        ));
+
+        // Disabling the allowlist forgets its accounts.
+        token
+            .policy
+            .apply(&PolicyChange::Allowed("carol".to_string()));
+        token.policy.apply(&PolicyChange::AllowlistEnabled(false));
+        token.transfer("alice", "bob", 1).unwrap();
+        token.policy.apply(&PolicyChange::AllowlistEnabled(true));
+        assert!(matches!(
+            token.transfer("alice", "carol", 1),
+            Err(TokenError::NotAllowlisted)
+        ));
+        assert_eq!((token.balance("alice"), token.balance("bob")), (998, 2));
+    }
+
+    #[test]
+    fn max_wallets_cap_what_accounts_receive() {
+        let mut token = token();
+        token.policy.apply(&PolicyChange::MaxWallet(Some(100)));
+        token.transfer("alice", "bob", 60).unwrap();
+        assert!(matches!(
+            token.transfer("alice", "bob", 41),
+            Err(TokenError::MaxWalletExceeded)
+        ));
+        token.transfer("alice", "bob", 40).unwrap();
+        token.policy.apply(&PolicyChange::MaxWallet(None));
+        token.transfer("alice", "bob", 1).unwrap();
+        assert_eq!((token.balance("alice"), token.balance("bob")), (899, 101));
+    }
+}
@@ -0,0 +1,789 @@
+use crate::errors::TokenError;
+use crate::types::Token;
+use crate::wrap::WrappedReserve;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The fee of every trade, in basis points of the wrapped native tokens traded.
+pub const TRADE_FEE_BPS: u32 = 100;
+/// The share of the fee paid to the referrer of a trade, in basis points of the fee.
+pub const REFERRAL_SHARE_BPS: u32 = 2_000;
+
+const BPS: u128 = 10_000;
+
+/// A constant-product pool trading a token against wrapped native tokens. Prices follow the
+/// reserves: every trade keeps their product from decreasing. The trade fees the pool keeps
+/// add to its native reserve, except for the share set aside for buybacks.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Pool {
+    pub token_reserve: u128,
+    pub native_reserve: u128,
+    #[serde(default)]
+    pub buyback: Option<BuybackSchedule>,
+    /// The fees set aside for the next buyback, out of the reserves.
+    #[serde(default)]
+    pub buyback_fees: u128,
+}
+
+/// A creator's standing order to buy their token back from its pool with a share of the
+/// trade fees, and burn it. Anyone can execute a due buyback.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BuybackSchedule {
+    /// The share of the fees the pool keeps set aside for buybacks, in basis points.
+    pub share_bps: u32,
+    /// How long buybacks wait after the previous one, in seconds.
+    pub interval_secs: u64,
+    /// When the last buyback was executed, in seconds since the Unix epoch.
+    pub last_executed: u64,
+}
+
+/// An executed buyback.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Buyback {
+    pub token: String,
+    pub native_spent: u128,
+    pub tokens_burned: u128,
+    /// When the buyback was executed, in seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+/// Both sides of a trade: the wrapped native tokens the trader pays or receives, fee
+/// included, and the tokens they receive or pay.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quote {
+    pub native_amount: u128,
+    pub token_amount: u128,
+    /// The part of the trade fee in `native_amount`.
+    pub fee: u128,
+}
+
+/// The trades a pool quotes, by the amount they fix.
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeKind {
+    /// Spends exactly the amount of wrapped native tokens.
+    Buy,
+    /// Receives exactly the amount of tokens.
+    BuyExactTokens,
+    /// Spends exactly the amount of tokens.
+    Sell,
+    /// Receives exactly the amount of wrapped native tokens.
+    SellForExactNative,
+}
+
+impl Pool {
+    /// Quotes buying tokens with exactly `native_in` wrapped tokens.
+    pub fn quote_buy(&self, native_in: u128) -> Result<Quote, TokenError> {
+        let fee = fee_of(native_in)?;
+        let token_out = amount_out(self.native_reserve, self.token_reserve, native_in - fee)?;
+        Ok(Quote {
+            native_amount: native_in,
+            token_amount: token_out,
+            fee,
+        })
+    }
+
+    /// Quotes buying exactly `token_out` tokens.
+    pub fn quote_buy_exact_tokens(&self, token_out: u128) -> Result<Quote, TokenError> {
+        let traded = amount_in(self.native_reserve, self.token_reserve, token_out)?;
+        let native_in = with_fee(traded)?;
+        let fee = native_in - traded;
+        Ok(Quote {
+            native_amount: native_in,
+            token_amount: token_out,
+            fee,
+        })
+    }
+
+    /// Quotes selling exactly `token_in` tokens.
+    pub fn quote_sell(&self, token_in: u128) -> Result<Quote, TokenError> {
+        let traded = amount_out(self.token_reserve, self.native_reserve, token_in)?;
+        let fee = fee_of(traded)?;
+        if fee == traded {
+            return Err(TokenError::InsufficientLiquidity);
+        }
+        Ok(Quote {
+            native_amount: traded - fee,
+            token_amount: token_in,
+            fee,
+        })
+    }
+
+    /// Quotes selling tokens for exactly `native_out` wrapped tokens.
+    pub fn quote_sell_for_exact_native(&self, native_out: u128) -> Result<Quote, TokenError> {
+        let traded = with_fee(native_out)?;
+        let token_in = amount_in(self.token_reserve, self.native_reserve, traded)?;
+        let fee = traded - native_out;
+        Ok(Quote {
+            native_amount: native_out,
+            token_amount: token_in,
+            fee,
+        })
+    }
+
+    /// Sets aside the buyback share of `fee`, which the pool keeps, and returns it.
+    fn set_aside(&mut self, fee: u128) -> Result<u128, TokenError> {
+        let Some(buyback) = &self.buyback else {
+            return Ok(0);
+        };
+        let share = share_of(fee, buyback.share_bps)?;
+        self.buyback_fees = self
+            .buyback_fees
+            .checked_add(share)
+            .ok_or(TokenError::Overflow)?;
+        Ok(share)
+    }
+}
+
+/// Returns the fee on trading `amount` wrapped tokens, rounded up.
+fn fee_of(amount: u128) -> Result<u128, TokenError> {
+    let fee = amount
+        .checked_mul(u128::from(TRADE_FEE_BPS))
+        .ok_or(TokenError::Overflow)?;
+    Ok(fee.div_ceil(BPS))
+}
+
+/// Returns the amount that leaves `amount` once its fee is taken, rounded up.
+fn with_fee(amount: u128) -> Result<u128, TokenError> {
+    let kept = BPS - u128::from(TRADE_FEE_BPS);
+    let gross = amount.checked_mul(BPS).ok_or(TokenError::Overflow)?;
+    Ok(gross.div_ceil(kept))
+}
+
+/// Returns `share_bps` basis points of `amount`, rounded down.
+fn share_of(amount: u128, share_bps: u32) -> Result<u128, TokenError> {
+    let share = amount
+        .checked_mul(u128::from(share_bps))
+        .ok_or(TokenError::Overflow)?;
+    Ok(share / BPS)
+}
+
+/// Returns what paying exactly `amount` into a pool gets out of it, rounded down.
+fn amount_out(reserve_in: u128, reserve_out: u128, amount: u128) -> Result<u128, TokenError> {
+    if amount == 0 {
+        return Err(TokenError::InvalidAmount);
+    }
+    let numerator = reserve_out
+        .checked_mul(amount)
+        .ok_or(TokenError::Overflow)?;
+    let denominator = reserve_in.checked_add(amount).ok_or(TokenError::Overflow)?;
+    let out = numerator / denominator;
+    if out == 0 {
+        return Err(TokenError::InsufficientLiquidity);
+    }
+    Ok(out)
+}
+
+/// Returns what must be paid into a pool to get exactly `amount` out of it, rounded up so
+/// that the pool never loses value.
+fn amount_in(reserve_in: u128, reserve_out: u128, amount: u128) -> Result<u128, TokenError> {
+    if amount == 0 {
+        return Err(TokenError::InvalidAmount);
+    }
+    if amount >= reserve_out {
+        return Err(TokenError::InsufficientLiquidity);
+    }
+    let numerator = reserve_in.checked_mul(amount).ok_or(TokenError::Overflow)?;
+    Ok(numerator.div_ceil(reserve_out - amount))
+}
+
+/// The pools of the application, by token name.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct PoolBook {
+    pub pools: HashMap<String, Pool>,
+    /// The wrapped native tokens referrers earned and did not claim yet. The pools hold them.
+    #[serde(default)]
+    pub referral_fees: HashMap<String, u128>,
+}
+
+impl PoolBook {
+    /// Opens a pool for `token`, seeded with the provider's tokens and wrapped native tokens.
+    /// The seeded liquidity stays in the pool.
+    pub fn create(
+        &mut self,
+        token: &str,
+        provider: &str,
+        token_amount: u128,
+        native_amount: u128,
+        tokens: &mut HashMap<String, Token>,
+        wrapped: &mut WrappedReserve,
+    ) -> Result<(), TokenError> {
+        if self.pools.contains_key(token) {
+            return Err(TokenError::InvalidState);
+        }
+        if token_amount == 0 || native_amount == 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        let pooled = tokens.get_mut(token).ok_or(TokenError::NotFound)?;
+        pooled.debit(provider, token_amount)?;
+        wrapped.pool_deposit(provider, native_amount)?;
+        self.pools.insert(
+            token.to_string(),
+            Pool {
+                token_reserve: token_amount,
+                native_reserve: native_amount,
+                buyback: None,
+                buyback_fees: 0,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn pool(&self, token: &str) -> Result<&Pool, TokenError> {
+        self.pools.get(token).ok_or(TokenError::NotFound)
+    }
+
+    /// Buys tokens with exactly `native_in` wrapped tokens, failing if that gets fewer than
+    /// `min_token_out`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn buy(
+        &mut self,
+        token: &str,
+        trader: &str,
+        referrer: Option<&str>,
+        native_in: u128,
+        min_token_out: u128,
+        tokens: &mut HashMap<String, Token>,
+        wrapped: &mut WrappedReserve,
+    ) -> Result<Quote, TokenError> {
+        let quote = self.pool(token)?.quote_buy(native_in)?;
+        if quote.token_amount < min_token_out {
+            return Err(TokenError::SlippageExceeded);
+        }
+        self.settle_buy(token, trader, referrer, quote, tokens, wrapped)
+    }
+
+    /// Buys exactly `token_out` tokens, failing if they cost more than `max_native_in` wrapped
+    /// tokens.
+    #[allow(clippy::too_many_arguments)]
+    pub fn buy_exact_tokens(
+        &mut self,
+        token: &str,
+        trader: &str,
+        referrer: Option<&str>,
+        token_out: u128,
+        max_native_in: u128,
+        tokens: &mut HashMap<String, Token>,
+        wrapped: &mut WrappedReserve,
+    ) -> Result<Quote, TokenError> {
+        let quote = self.pool(token)?.quote_buy_exact_tokens(token_out)?;
+        if quote.native_amount > max_native_in {
+            return Err(TokenError::SlippageExceeded);
+        }
+        self.settle_buy(token, trader, referrer, quote, tokens, wrapped)
+    }
+
+    /// Sells exactly `token_in` tokens, failing if that gets fewer than `min_native_out`
+    /// wrapped tokens.
+    #[allow(clippy::too_many_arguments)]
+    pub fn sell(
+        &mut self,
+        token: &str,
+        trader: &str,
+        referrer: Option<&str>,
+        token_in: u128,
+        min_native_out: u128,
+        tokens: &mut HashMap<String, Token>,
+        wrapped: &mut WrappedReserve,
+    ) -> Result<Quote, TokenError> {
+        let quote = self.pool(token)?.quote_sell(token_in)?;
+        if quote.native_amount < min_native_out {
+            return Err(TokenError::SlippageExceeded);
+        }
+        self.settle_sell(token, trader, referrer, quote, tokens, wrapped)
+    }
+
+    /// Sells tokens for exactly `native_out` wrapped tokens, failing if that takes more than
+    /// `max_token_in` tokens.
+    #[allow(clippy::too_many_arguments)]
+    pub fn sell_for_exact_native(
+        &mut self,
+        token: &str,
+        trader: &str,
+        referrer: Option<&str>,
+        native_out: u128,
+        max_token_in: u128,
+        tokens: &mut HashMap<String, Token>,
+        wrapped: &mut WrappedReserve,
+    ) -> Result<Quote, TokenError> {
+        let quote = self.pool(token)?.quote_sell_for_exact_native(native_out)?;
+        if quote.token_amount > max_token_in {
+            return Err(TokenError::SlippageExceeded);
+        }
+        self.settle_sell(token, trader, referrer, quote, tokens, wrapped)
+    }
+
+    /// Sets aside `share_bps` of the fees the pool of `token` keeps, for buybacks at most every
+    /// `interval_secs`. Only the creator of the token can.
+    pub fn configure_buyback(
+        &mut self,
+        token: &Token,
+        caller: &str,
+        share_bps: u32,
+        interval_secs: u64,
+    ) -> Result<(), TokenError> {
+        if caller != token.creator {
+            return Err(TokenError::Unauthorized);
+        }
+        if u128::from(share_bps) > BPS {
+            return Err(TokenError::InvalidAmount);
+        }
+        let pool = self
+            .pools
+            .get_mut(&token.name)
+            .ok_or(TokenError::NotFound)?;
+        let last_executed = pool
+            .buyback
+            .as_ref()
+            .map_or(0, |buyback| buyback.last_executed);
+        pool.buyback = Some(BuybackSchedule {
+            share_bps,
+            interval_secs,
+            last_executed,
+        });
+        Ok(())
+    }
+
+    /// Spends the fees set aside in the pool of `token` to buy it back, and burns what was
+    /// bought. Fails if the buyback is not due at `now`.
+    pub fn poke(
+        &mut self,
+        token: &str,
+        now: u64,
+        tokens: &mut HashMap<String, Token>,
+    ) -> Result<Buyback, TokenError> {
+        let pool = self.pools.get_mut(token).ok_or(TokenError::NotFound)?;
+        let schedule = pool.buyback.as_mut().ok_or(TokenError::InvalidState)?;
+        if now
+            < schedule
+                .last_executed
+                .saturating_add(schedule.interval_secs)
+        {
+            return Err(TokenError::InvalidState);
+        }
+        let native_spent = pool.buyback_fees;
+        let tokens_burned = amount_out(pool.native_reserve, pool.token_reserve, native_spent)?;
+        let native_reserve = pool
+            .native_reserve
+            .checked_add(native_spent)
+            .ok_or(TokenError::Overflow)?;
+        let burned = tokens.get_mut(token).ok_or(TokenError::NotFound)?;
+        burned.total_supply -= tokens_burned;
+        schedule.last_executed = now;
+        pool.native_reserve = native_reserve;
+        pool.token_reserve -= tokens_burned;
+        pool.buyback_fees = 0;
+        Ok(Buyback {
+            token: token.to_string(),
+            native_spent,
+            tokens_burned,
+            timestamp: now,
+        })
+    }
+
+    /// Returns the wrapped native tokens `referrer` earned so far.
+    pub fn referral_fees(&self, referrer: &str) -> u128 {
+        self.referral_fees.get(referrer).copied().unwrap_or(0)
+    }
+
+    /// Pays `referrer` the referral fees they earned, and returns how much.
+    pub fn claim_referral_fees(
+        &mut self,
+        referrer: &str,
+        wrapped: &mut WrappedReserve,
+    ) -> Result<u128, TokenError> {
+        let amount = self.referral_fees(referrer);
+        if amount == 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        wrapped.pool_payout(referrer, amount)?;
+        self.referral_fees.remove(referrer);
+        Ok(amount)
+    }
+
+    /// Credits `referrer` with their share of `fee`, and returns it. Traders cannot refer
+    /// themselves, which would just be a discount.
+    fn accrue_referral(
+        &mut self,
+        trader: &str,
+        referrer: Option<&str>,
+        fee: u128,
+    ) -> Result<u128, TokenError> {
+        let Some(referrer) = referrer else {
+            return Ok(0);
+        };
+        if referrer == trader {
+            return Err(TokenError::Unauthorized);
+        }
+        let share = share_of(fee, REFERRAL_SHARE_BPS)?;
+        if share > 0 {
+            let earned = self.referral_fees.entry(referrer.to_string()).or_insert(0);
+            *earned = earned.checked_add(share).ok_or(TokenError::Overflow)?;
+        }
+        Ok(share)
+    }
+
+    /// The trader pays the whole native amount into the pool. The referral and buyback shares
+    /// leave the reserve, the rest of the fee stays in it.
+    fn settle_buy(
+        &mut self,
+        token: &str,
+        trader: &str,
+        referrer: Option<&str>,
+        quote: Quote,
+        tokens: &mut HashMap<String, Token>,
+        wrapped: &mut WrappedReserve,
+    ) -> Result<Quote, TokenError> {
+        let referral = self.accrue_referral(trader, referrer, quote.fee)?;
+        let pool = self.pools.get_mut(token).ok_or(TokenError::NotFound)?;
+        let buyback = pool.set_aside(quote.fee - referral)?;
+        let native_reserve = pool
+            .native_reserve
+            .checked_add(quote.native_amount - referral - buyback)
+            .ok_or(TokenError::Overflow)?;
+        wrapped.pool_deposit(trader, quote.native_amount)?;
+        let traded = tokens.get_mut(token).ok_or(TokenError::NotFound)?;
+        traded.credit(trader, quote.token_amount)?;
+        pool.native_reserve = native_reserve;
+        pool.token_reserve -= quote.token_amount;
+        Ok(quote)
+    }
+
+    /// The pool pays out the native amount and the referral share, and sets aside the buyback
+    /// share, keeping the rest of the fee.
+    fn settle_sell(
+        &mut self,
+        token: &str,
+        trader: &str,
+        referrer: Option<&str>,
+        quote: Quote,
+        tokens: &mut HashMap<String, Token>,
+        wrapped: &mut WrappedReserve,
+    ) -> Result<Quote, TokenError> {
+        let referral = self.accrue_referral(trader, referrer, quote.fee)?;
+        let pool = self.pools.get_mut(token).ok_or(TokenError::NotFound)?;
+        let buyback = pool.set_aside(quote.fee - referral)?;
+        let token_reserve = pool
+            .token_reserve
+            .checked_add(quote.token_amount)
+            .ok_or(TokenError::Overflow)?;
+        let traded = tokens.get_mut(token).ok_or(TokenError::NotFound)?;
+        traded.debit(trader, quote.token_amount)?;
+        wrapped.pool_payout(trader, quote.native_amount)?;
+        pool.token_reserve = token_reserve;
+        pool.native_reserve -= quote.native_amount + referral + buyback;
+        Ok(quote)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::TokenMetadata;
+    use crate::policy::TransferPolicy;
+
+    struct Market {
+        book: PoolBook,
+        tokens: HashMap<String, Token>,
+        wrapped: WrappedReserve,
+    }
+
+    /// A pool of 100 000 tokens against 100 000 wrapped tokens, with traders holding both.
+    fn market() -> Market {
+        let mut token = Token {
+            name: "Coin".to_string(),
+            symbol: "COIN".to_string(),
+            total_supply: 1_000_000,
+            decimals: 0,
+            metadata_uri: None,
+            balances: HashMap::from([("creator".to_string(), 1_000_000)]),
+            creator: "creator".to_string(),
+            policy: TransferPolicy::default(),
+            metadata: TokenMetadata::default(),
+            pending_metadata: None,
+            soulbound: None,
+        };
+        token.transfer("creator", "alice", 100_000).unwrap();
+        let mut tokens = HashMap::from([("Coin".to_string(), token)]);
+        let mut wrapped = WrappedReserve::default();
+        for owner in ["creator", "alice", "bob"] {
+            wrapped.deposit(owner, 1_000_000).unwrap();
+        }
+        let mut book = PoolBook::default();
+        book.create(
+            "Coin",
+            "creator",
+            100_000,
+            100_000,
+            &mut tokens,
+            &mut wrapped,
+        )
+        .unwrap();
+        Market {
+            book,
+            tokens,
+            wrapped,
+        }
+    }
+
+    fn product(market: &Market) -> u128 {
+        let pool = market.book.pool("Coin").unwrap();
+        pool.token_reserve * pool.native_reserve
+    }
+
+    /// Every wrapped token the pools hold is in a reserve, and every token is in a balance or
+    /// a reserve.
+    fn assert_accounted(market: &Market) {
+        let pool = market.book.pool("Coin").unwrap();
+        let owed: u128 = market.book.referral_fees.values().sum();
+        assert_eq!(
+            market.wrapped.pooled,
+            pool.native_reserve + pool.buyback_fees + owed
+        );
+        let token = &market.tokens["Coin"];
+        assert_eq!(
+            token.balances.values().sum::<u128>() + pool.token_reserve,
+            token.total_supply
+        );
+    }
+
+    /// Trades `amount` with `limit` as the slippage bound, the way `kind` fixes it.
+    fn trade(
+        market: &mut Market,
+        kind: TradeKind,
+        trader: &str,
+        referrer: Option<&str>,
+        amount: u128,
+        limit: u128,
+    ) -> Result<Quote, TokenError> {
+        let Market {
+            book,
+            tokens,
+            wrapped,
+        } = market;
+        match kind {
+            TradeKind::Buy => book.buy("Coin", trader, referrer, amount, limit, tokens, wrapped),
+            TradeKind::BuyExactTokens => {
+                book.buy_exact_tokens("Coin", trader, referrer, amount, limit, tokens, wrapped)
+            }
+            TradeKind::Sell => book.sell("Coin", trader, referrer, amount, limit, tokens, wrapped),
+            TradeKind::SellForExactNative => {
+                book.sell_for_exact_native("Coin", trader, referrer, amount, limit, tokens, wrapped)
+            }
+        }
+    }
+
+    #[test]
+    fn trades_never_decrease_the_product() {
+        let mut market = market();
+        let trades = [
+            (TradeKind::Buy, "alice", 1_234, 0),
+            (TradeKind::Sell, "alice", 777, 0),
+            (TradeKind::BuyExactTokens, "bob", 3_001, u128::MAX),
+            (TradeKind::SellForExactNative, "alice", 2_999, u128::MAX),
+            (TradeKind::Buy, "bob", 101, 0),
+            (TradeKind::Sell, "bob", 1_000, 0),
+        ];
+        let mut last = product(&market);
+        for (kind, trader, amount, limit) in trades {
+            trade(&mut market, kind, trader, None, amount, limit).unwrap();
+            assert!(product(&market) >= last);
+            last = product(&market);
+            assert_accounted(&market);
+        }
+    }
+
+    #[test]
+    fn exact_outputs_cost_no_less_than_the_matching_inputs() {
+        let pool = market().book.pool("Coin").unwrap().clone();
+        for token_out in [1, 99, 1_000, 50_000, 90_000] {
+            let quote = pool.quote_buy_exact_tokens(token_out).unwrap();
+            assert_eq!(quote.token_amount, token_out);
+            assert!(pool.quote_buy(quote.native_amount).unwrap().token_amount >= token_out);
+        }
+        for native_out in [1, 99, 1_000, 50_000, 98_000] {
+            let quote = pool.quote_sell_for_exact_native(native_out).unwrap();
+            assert_eq!(quote.native_amount, native_out);
+            assert!(pool.quote_sell(quote.token_amount).unwrap().native_amount >= native_out);
+        }
+        assert!(matches!(
+            pool.quote_buy_exact_tokens(100_000),
+            Err(TokenError::InsufficientLiquidity)
+        ));
+        assert!(matches!(
+            pool.quote_buy_exact_tokens(0),
+            Err(TokenError::InvalidAmount)
+        ));
+    }
+
+    #[test]
+    fn slippage_limits_leave_the_market_alone() {
+        let mut market = market();
+        let quote = market
+            .book
+            .pool("Coin")
+            .unwrap()
+            .quote_buy_exact_tokens(500)
+            .unwrap();
+        let before = product(&market);
+        assert!(matches!(
+            trade(
+                &mut market,
+                TradeKind::BuyExactTokens,
+                "bob",
+                None,
+                500,
+                quote.native_amount - 1
+            ),
+            Err(TokenError::SlippageExceeded)
+        ));
+        let min_out = market
+            .book
+            .pool("Coin")
+            .unwrap()
+            .quote_buy(1_000)
+            .unwrap()
+            .token_amount;
+        assert!(matches!(
+            trade(&mut market, TradeKind::Buy, "bob", None, 1_000, min_out + 1),
+            Err(TokenError::SlippageExceeded)
+        ));
+        assert_eq!(product(&market), before);
+        assert_eq!(market.wrapped.balance("bob"), 1_000_000);
+
+        let paid = trade(
+            &mut market,
+            TradeKind::BuyExactTokens,
+            "bob",
+            None,
+            500,
+            quote.native_amount,
+        )
+        .unwrap();
+        assert_eq!(paid, quote);
+        assert_eq!(market.tokens["Coin"].balance("bob"), 500);
+        assert_eq!(
+            market.wrapped.balance("bob"),
+            1_000_000 - quote.native_amount
+        );
+    }
+
+    #[test]
+    fn fees_round_up_against_the_trader() {
+        assert_eq!(fee_of(1).unwrap(), 1);
+        assert_eq!(fee_of(100).unwrap(), 1);
+        assert_eq!(fee_of(101).unwrap(), 2);
+        assert_eq!(with_fee(99).unwrap(), 100);
+        assert_eq!(with_fee(100).unwrap(), 102);
+        assert!(matches!(fee_of(u128::MAX), Err(TokenError::Overflow)));
+
+        let pool = market().book.pool("Coin").unwrap().clone();
+        let buy = pool.quote_buy(10_000).unwrap();
+        assert_eq!(buy.fee, 100);
+        // 9 900 traded into 100 000 against 100 000 tokens.
+        assert_eq!(buy.token_amount, 100_000 * 9_900 / 109_900);
+        let sell = pool.quote_sell(10_000).unwrap();
+        let traded = 100_000 * 10_000 / 110_000;
+        assert_eq!(sell.fee, fee_of(traded).unwrap());
+        assert_eq!(sell.native_amount + sell.fee, traded);
+    }
+
+    #[test]
+    fn referrers_earn_their_share_of_the_fees() {
+        let mut market = market();
+        let buy = trade(&mut market, TradeKind::Buy, "alice", Some("bob"), 10_000, 0).unwrap();
+        let sell = trade(&mut market, TradeKind::Sell, "alice", Some("bob"), 5_000, 0).unwrap();
+        let earned = buy.fee / 5 + sell.fee / 5;
+        assert_eq!(market.book.referral_fees("bob"), earned);
+        assert_accounted(&market);
+
+        let before = market.wrapped.balance("bob");
+        let Market { book, wrapped, .. } = &mut market;
+        assert_eq!(book.claim_referral_fees("bob", wrapped).unwrap(), earned);
+        assert_eq!(market.wrapped.balance("bob"), before + earned);
+        assert_eq!(market.book.referral_fees("bob"), 0);
+        assert_accounted(&market);
+        let Market { book, wrapped, .. } = &mut market;
+        assert!(matches!(
+            book.claim_referral_fees("bob", wrapped),
+            Err(TokenError::InvalidAmount)
+        ));
+    }
+
+    #[test]
+    fn traders_cannot_refer_themselves() {
+        let mut market = market();
+        let before = product(&market);
+        assert!(matches!(
+            trade(
+                &mut market,
+                TradeKind::Buy,
+                "alice",
+                Some("alice"),
+                10_000,
+                0
+            ),
+            Err(TokenError::Unauthorized)
+        ));
+        assert_eq!(product(&market), before);
+        assert!(market.book.referral_fees.is_empty());
+    }
+
+    #[test]
+    fn buybacks_spend_the_fees_set_aside_and_burn() {
+        let mut market = market();
+        let coin = market.tokens["Coin"].clone();
+        market
+            .book
+            .configure_buyback(&coin, "creator", 5_000, 60)
+            .unwrap();
+        let buy = trade(&mut market, TradeKind::Buy, "alice", Some("bob"), 20_000, 0).unwrap();
+        let referral = buy.fee / 5;
+        let set_aside = (buy.fee - referral) / 2;
+        assert_eq!(market.book.pool("Coin").unwrap().buyback_fees, set_aside);
+        assert_accounted(&market);
+
+        let supply = market.tokens["Coin"].total_supply;
+        let before = product(&market);
+        let Market { book, tokens, .. } = &mut market;
+        assert!(matches!(
+            book.poke("Coin", 59, tokens),
+            Err(TokenError::InvalidState)
+        ));
+        let buyback = book.poke("Coin", 60, tokens).unwrap();
+        assert_eq!(buyback.native_spent, set_aside);
+        assert!(buyback.tokens_burned > 0);
+        assert_eq!(
+            market.tokens["Coin"].total_supply,
+            supply - buyback.tokens_burned
+        );
+        assert_eq!(market.book.pool("Coin").unwrap().buyback_fees, 0);
+        assert!(product(&market) >= before);
+        assert_accounted(&market);
+
+        let Market { book, tokens, .. } = &mut market;
+        assert!(matches!(
+            book.poke("Coin", 119, tokens),
+            Err(TokenError::InvalidState)
+        ));
+    }
+
+    #[test]
+    fn only_creators_schedule_buybacks() {
+        let mut market = market();
+        let coin = market.tokens["Coin"].clone();
+        assert!(matches!(
+            market.book.configure_buyback(&coin, "alice", 5_000, 60),
+            Err(TokenError::Unauthorized)
+        ));
+        assert!(matches!(
+            market.book.configure_buyback(&coin, "creator", 10_001, 60),
+            Err(TokenError::InvalidAmount)
+        ));
+        let Market { book, tokens, .. } = &mut market;
+        assert!(matches!(
+            book.poke("Coin", 1_000, tokens),
+            Err(TokenError::InvalidState)
+        ));
+    }
+}
@@ -0,0 +1,42 @@
+use crate::types::Token;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Which tokens each owner holds, kept up to date as balances move so that portfolios do not
+/// scan every token.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct HoldingsIndex {
+    pub holdings: HashMap<String, BTreeSet<String>>,
+}
+
+impl HoldingsIndex {
+    /// Records whether `owner` still holds `token`, after their balance changed.
+    pub fn update(&mut self, token: &Token, owner: &str) {
+        if token.balance(owner) > 0 {
+            self.holdings
+                .entry(owner.to_string())
+                .or_default()
+                .insert(token.name.clone());
+        } else if let Some(held) = self.holdings.get_mut(owner) {
+            held.remove(&token.name);
+            if held.is_empty() {
+                self.holdings.remove(owner);
+            }
+        }
+    }
+
+    /// Returns the names of the tokens `owner` holds, in order.
+    pub fn tokens_of(&self, owner: &str) -> impl Iterator<Item = &String> {
+        self.holdings.get(owner).into_iter().flatten()
+    }
+}
+
+/// A token in a portfolio.
+#[derive(Serialize, Debug)]
+pub struct Holding {
+    pub token: String,
+    pub symbol: String,
+    pub balance: u128,
+    /// The pool's current price, in wrapped native tokens per token, if the token has a pool.
+    pub price: Option<f64>,
+}
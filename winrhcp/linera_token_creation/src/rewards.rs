@@ -0,0 +1,164 @@
+use crate::errors::TokenError;
+use crate::types::Token;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// What makes a token a soulbound reward: its balances are minted for achievements and can
+/// never be transferred, escrowed or streamed, so campaign rewards cannot be traded or
+/// farmed through sales.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct Soulbound {
+    /// The only accounts allowed to move balances of the token: they can claw rewards back
+    /// from holders, burning them.
+    pub clawback_admins: HashSet<String>,
+}
+
+/// Reward tokens minted to a holder for an achievement.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Award {
+    pub token: String,
+    pub recipient: String,
+    pub amount: u128,
+    pub achievement: String,
+    /// When the reward was minted, in seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+impl Token {
+    /// Mints `amount` reward tokens to `recipient`. Only the creator awards rewards, and only
+    /// of soulbound tokens.
+    pub fn award(&mut self, caller: &str, recipient: &str, amount: u128) -> Result<(), TokenError> {
+        if caller != self.creator {
+            return Err(TokenError::Unauthorized);
+        }
+        if self.soulbound.is_none() {
+            return Err(TokenError::InvalidState);
+        }
+        if amount == 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        let total_supply = self
+            .total_supply
+            .checked_add(amount)
+            .ok_or(TokenError::Overflow)?;
+        self.credit(recipient, amount)?;
+        self.total_supply = total_supply;
+        Ok(())
+    }
+
+    /// Burns `amount` reward tokens of `holder`, bypassing the soulbound restriction. Only
+    /// the clawback admins of the token can.
+    pub fn clawback(&mut self, caller: &str, holder: &str, amount: u128) -> Result<(), TokenError> {
+        let soulbound = self.soulbound.as_ref().ok_or(TokenError::InvalidState)?;
+        if !soulbound.clawback_admins.contains(caller) {
+            return Err(TokenError::Unauthorized);
+        }
+        if amount == 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        let balance = self
+            .balances
+            .get_mut(holder)
+            .ok_or(TokenError::InsufficientBalance)?;
+        if *balance < amount {
+            return Err(TokenError::InsufficientBalance);
+        }
+        *balance -= amount;
+        self.total_supply -= amount;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::TokenMetadata;
+    use crate::policy::TransferPolicy;
+    use crate::stream::StreamBook;
+    use std::collections::HashMap;
+
+    fn badge(supply: u128, soulbound: Option<Soulbound>) -> Token {
+        Token {
+            name: "Badge".to_string(),
+            symbol: "BADGE".to_string(),
+            total_supply: supply,
+            decimals: 0,
+            metadata_uri: None,
+            balances: HashMap::from([("game".to_string(), supply)]),
+            creator: "game".to_string(),
+            policy: TransferPolicy::default(),
+            metadata: TokenMetadata::default(),
+            pending_metadata: None,
+            soulbound,
+        }
+    }
+
+    fn reward_token() -> Token {
+        let mut token = badge(0, None);
+        token.balances.clear();
+        token.soulbound = Some(Soulbound {
+            clawback_admins: HashSet::from(["moderator".to_string()]),
+        });
+        token
+    }
+
+    #[test]
+    fn awards_and_clawbacks_track_the_supply() {
+        let mut token = reward_token();
+        token.award("game", "alice", 30).unwrap();
+        token.award("game", "bob", 20).unwrap();
+        token.clawback("moderator", "alice", 10).unwrap();
+        assert_eq!((token.balance("alice"), token.balance("bob")), (20, 20));
+        assert_eq!(token.total_supply, 40);
+        assert_eq!(token.balances.values().sum::<u128>(), token.total_supply);
+    }
+
+    #[test]
+    fn rewards_never_move_between_holders() {
+        let mut token = reward_token();
+        token.award("game", "alice", 30).unwrap();
+        assert!(matches!(
+            token.transfer("alice", "bob", 1),
+            Err(TokenError::Soulbound)
+        ));
+        let mut tokens = HashMap::from([("Badge".to_string(), token)]);
+        assert!(matches!(
+            StreamBook::default().create("Badge", "alice", "bob", 1, 0, 1, &mut tokens),
+            Err(TokenError::Soulbound)
+        ));
+        assert_eq!(tokens["Badge"].balance("alice"), 30);
+    }
+
+    #[test]
+    fn only_the_right_accounts_award_and_claw_back() {
+        let mut token = reward_token();
+        assert!(matches!(
+            token.award("alice", "alice", 1),
+            Err(TokenError::Unauthorized)
+        ));
+        assert!(matches!(
+            token.award("game", "alice", 0),
+            Err(TokenError::InvalidAmount)
+        ));
+        token.award("game", "alice", 5).unwrap();
+        assert!(matches!(
+            token.clawback("game", "alice", 1),
+            Err(TokenError::Unauthorized)
+        ));
+        assert!(matches!(
+            token.clawback("moderator", "alice", 6),
+            Err(TokenError::InsufficientBalance)
+        ));
+
+        // Regular tokens are neither awarded nor clawed back.
+        let mut regular = badge(100, None);
+        assert!(matches!(
+            regular.award("game", "alice", 1),
+            Err(TokenError::InvalidState)
+        ));
+        assert!(matches!(
+            regular.clawback("moderator", "game", 1),
+            Err(TokenError::InvalidState)
+        ));
+    }
+}
@@ -0,0 +1,305 @@
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+mod state;
+
+use std::sync::Arc;
+
+use async_graphql::{
+    EmptySubscription, InputValueError, InputValueResult, Object, Request, Response, Scalar,
+    ScalarType, Schema, SimpleObject, Value,
+};
+use linera_sdk::{
+    base::{Account, ApplicationId, ChainId, Owner, WithServiceAbi},
+    bcs,
+    views::View,
+    Service, ServiceRuntime,
+};
+use linera_token_creation::{escrow::Escrow, types::Token, Operation, TokenAbi};
+
+use self::state::TokenState;
+
+pub struct TokenService {
+    state: Arc<TokenState>,
+}
+
+linera_sdk::service!(TokenService);
+
+impl WithServiceAbi for TokenService {
+    type Abi = TokenAbi;
+}
+
+impl Service for TokenService {
+    type Parameters = ();
+
+    async fn new(runtime: ServiceRuntime<Self>) -> Self {
+        let state = TokenState::load(runtime.root_view_storage_context())
+            .await
+            .expect("Failed to load state");
+        TokenService {
+            state: Arc::new(state),
+        }
+    }
+
+    async fn handle_query(&self, query: Request) -> Response {
+        let schema = Schema::build(
+            QueryRoot {
+                state: self.state.clone(),
+            },
+            MutationRoot,
+            EmptySubscription,
+        )
+        .finish();
+        schema.execute(query).await
+    }
+}
+
+struct QueryRoot {
+    state: Arc<TokenState>,
+}
+
+/// An amount of base units. GraphQL integers stop at 32 bits, so amounts are written out
+/// as decimal strings; small amounts may also be given as plain numbers.
+struct Units(u128);
+
+#[Scalar]
+impl ScalarType for Units {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match &value {
+            Value::String(units) => Ok(Units(units.parse()?)),
+            Value::Number(units) => units
+                .as_u64()
+                .map(|units| Units(units.into()))
+                .ok_or_else(|| InputValueError::expected_type(value.clone())),
+            _ => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.0.to_string())
+    }
+}
+
+#[derive(SimpleObject)]
+struct TokenInfo {
+    name: String,
+    symbol: String,
+    total_supply: Units,
+    decimals: u8,
+    metadata_uri: Option<String>,
+    creator: String,
+}
+
+impl From<Token> for TokenInfo {
+    fn from(token: Token) -> Self {
+        TokenInfo {
+            name: token.name,
+            symbol: token.symbol,
+            total_supply: Units(token.total_supply),
+            decimals: token.decimals,
+            metadata_uri: token.metadata_uri,
+            creator: token.creator,
+        }
+    }
+}
+
+/// A token in a portfolio.
+#[derive(SimpleObject)]
+struct HoldingInfo {
+    token: String,
+    symbol: String,
+    balance: Units,
+}
+
+/// An escrow an application opened.
+#[derive(SimpleObject)]
+struct EscrowInfo {
+    token: String,
+    depositor: String,
+    /// Who was paid, once it is released.
+    beneficiary: String,
+    arbiter: String,
+    amount: Units,
+    status: String,
+}
+
+impl From<Escrow> for EscrowInfo {
+    fn from(escrow: Escrow) -> Self {
+        EscrowInfo {
+            status: format!("{:?}", escrow.status),
+            token: escrow.token,
+            depositor: escrow.depositor,
+            beneficiary: escrow.beneficiary,
+            arbiter: escrow.arbiter,
+            amount: Units(escrow.amount),
+        }
+    }
+}
+
+#[Object]
+impl QueryRoot {
+    async fn tokens(&self) -> Vec<TokenInfo> {
+        let names = self
+            .state
+            .tokens
+            .indices()
+            .await
+            .expect("Failed to load tokens");
+        let mut tokens = Vec::new();
+        for name in names {
+            if let Some(token) = self.state.token(&name).await {
+                tokens.push(token.into());
+            }
+        }
+        tokens
+    }
+
+    async fn token(&self, name: String) -> Option<TokenInfo> {
+        self.state.token(&name).await.map(TokenInfo::from)
+    }
+
+    /// The balance of `owner` in `token`, `None` if there is no such token.
+    async fn balance(&self, token: String, owner: Owner) -> Option<Units> {
+        let token = self.state.token(&token).await?;
+        Some(Units(token.balance(&owner.to_string())))
+    }
+
+    /// The chain `token` was created on, if it was received from another chain.
+    async fn origin(&self, token: String) -> Option<ChainId> {
+        self.state.origin(&token).await
+    }
+
+    /// How many of the tokens `token`, created on this chain, are held on other chains.
+    async fn escrowed(&self, token: String) -> Units {
+        Units(self.state.escrowed(&token).await)
+    }
+
+    /// The applications allowed to mint reward tokens `token`.
+    async fn minters(&self, token: String) -> Vec<ApplicationId> {
+        self.state.minters(&token).await.into_iter().collect()
+    }
+
+    /// Escrow `id`, if an application opened it.
+    async fn escrow(&self, id: u64) -> Option<EscrowInfo> {
+        let escrow = self.state.escrows.get(&id).await;
+        escrow.expect("Failed to load escrow").map(EscrowInfo::from)
+    }
+
+    /// Every token `owner` holds, with its balance.
+    async fn portfolio(&self, owner: Owner) -> Vec<HoldingInfo> {
+        let owner = owner.to_string();
+        let mut holdings = Vec::new();
+        for name in self.state.holdings(&owner).await {
+            if let Some(token) = self.state.token(&name).await {
+                holdings.push(HoldingInfo {
+                    balance: Units(token.balance(&owner)),
+                    token: token.name,
+                    symbol: token.symbol,
+                });
+            }
+        }
+        holdings
+    }
+}
+
+struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    async fn create_token(
+        &self,
+        name: String,
+        symbol: String,
+        total_supply: Units,
+        #[graphql(default)] decimals: u8,
+        metadata_uri: Option<String>,
+    ) -> Vec<u8> {
+        let operation = Operation::CreateToken {
+            name,
+            symbol,
+            total_supply: total_supply.0,
+            decimals,
+            metadata_uri,
+        };
+        bcs::to_bytes(&operation).unwrap()
+    }
+
+    async fn transfer(&self, token: String, to: Owner, amount: Units) -> Vec<u8> {
+        let amount = amount.0;
+        bcs::to_bytes(&Operation::Transfer { token, to, amount }).unwrap()
+    }
+
+    async fn mint(&self, token: String, recipient: Owner, amount: Units) -> Vec<u8> {
+        let operation = Operation::Mint {
+            token,
+            recipient,
+            amount: amount.0,
+        };
+        bcs::to_bytes(&operation).unwrap()
+    }
+
+    async fn burn(&self, token: String, amount: Units) -> Vec<u8> {
+        let amount = amount.0;
+        bcs::to_bytes(&Operation::Burn { token, amount }).unwrap()
+    }
+
+    async fn set_minter(
+        &self,
+        token: String,
+        application_id: ApplicationId,
+        authorized: bool,
+    ) -> Vec<u8> {
+        let operation = Operation::SetMinter {
+            token,
+            application_id,
+            authorized,
+        };
+        bcs::to_bytes(&operation).unwrap()
+    }
+
+    async fn cross_chain_transfer(
+        &self,
+        symbol: String,
+        amount: Units,
+        target_account: Account,
+    ) -> Vec<u8> {
+        let operation = Operation::CrossChainTransfer {
+            symbol,
+            amount: amount.0,
+            target_account,
+        };
+        bcs::to_bytes(&operation).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::{ScalarType, Value};
+
+    use super::Units;
+
+    fn parsed(value: Value) -> Option<u128> {
+        Units::parse(value).ok().map(|units| units.0)
+    }
+
+    #[test]
+    fn units_keep_every_bit_of_u128_amounts() {
+        let value = Units(u128::MAX).to_value();
+        assert_eq!(value, Value::String(u128::MAX.to_string()));
+        assert_eq!(parsed(value), Some(u128::MAX));
+        assert_eq!(parsed(Value::from(5)), Some(5));
+    }
+
+    #[test]
+    fn units_are_whole_and_positive() {
+        for value in [
+            Value::from(-1),
+            Value::from(1.5),
+            Value::String("-1".to_string()),
+            Value::String("1.5".to_string()),
+            Value::String(format!("{}0", u128::MAX)),
+            Value::Boolean(true),
+        ] {
+            assert_eq!(parsed(value), None);
+        }
+    }
+}
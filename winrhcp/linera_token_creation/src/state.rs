@@ -0,0 +1,144 @@
+use linera_sdk::base::{ApplicationId, ChainId};
+use linera_sdk::views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext};
+use linera_token_creation::{escrow::Escrow, types::Token};
+use std::collections::BTreeSet;
+
+/// The on-chain state of the token application.
+#[derive(RootView)]
+#[view(context = "ViewStorageContext")]
+pub struct TokenState {
+    pub tokens: MapView<String, Token>,
+    /// The name of the token using each symbol, so that no two tokens share one.
+    pub symbols: MapView<String, String>,
+    /// The names of the tokens each owner holds, so that portfolios do not scan every token.
+    pub holdings: MapView<String, BTreeSet<String>>,
+    /// The chain each token received from another chain was created on. Tokens created on
+    /// this chain have no entry.
+    pub origins: MapView<String, ChainId>,
+    /// The tokens created on this chain that are held on other chains, by token name.
+    pub escrowed: MapView<String, u128>,
+    /// The applications each token's creator authorized to mint reward tokens.
+    pub minters: MapView<String, BTreeSet<ApplicationId>>,
+    /// The escrows applications opened, by id. Their tokens are in no balance until they are
+    /// settled.
+    pub escrows: MapView<u64, Escrow>,
+    pub next_escrow_id: RegisterView<u64>,
+}
+
+impl TokenState {
+    pub async fn token(&self, name: &str) -> Option<Token> {
+        self.tokens
+            .get(&name.to_string())
+            .await
+            .expect("Failed to load token")
+    }
+
+    /// Stores `token`, and records whether each of `owners`, whose balances changed, still
+    /// holds it.
+    pub async fn save_token(&mut self, token: Token, owners: &[&str]) {
+        for owner in owners {
+            let owner = owner.to_string();
+            let mut held = self
+                .holdings
+                .get(&owner)
+                .await
+                .expect("Failed to load holdings")
+                .unwrap_or_default();
+            if token.balance(&owner) > 0 {
+                held.insert(token.name.clone());
+            } else {
+                held.remove(&token.name);
+            }
+            if held.is_empty() {
+                self.holdings
+                    .remove(&owner)
+                    .expect("Failed to update holdings");
+            } else {
+                self.holdings
+                    .insert(&owner, held)
+                    .expect("Failed to update holdings");
+            }
+        }
+        self.tokens
+            .insert(&token.name.clone(), token)
+            .expect("Failed to store token");
+    }
+
+    /// Returns the chain `name` was created on, `None` for tokens created on this chain.
+    pub async fn origin(&self, name: &str) -> Option<ChainId> {
+        self.origins
+            .get(&name.to_string())
+            .await
+            .expect("Failed to load token origins")
+    }
+
+    /// Returns how many tokens `name`, created on this chain, are held on other chains.
+    pub async fn escrowed(&self, name: &str) -> u128 {
+        self.escrowed
+            .get(&name.to_string())
+            .await
+            .expect("Failed to load escrow")
+            .unwrap_or(0)
+    }
+
+    /// Returns the applications allowed to mint reward tokens `name`.
+    pub async fn minters(&self, name: &str) -> BTreeSet<ApplicationId> {
+        self.minters
+            .get(&name.to_string())
+            .await
+            .expect("Failed to load minters")
+            .unwrap_or_default()
+    }
+
+    /// Returns escrow `id`, which must exist.
+    pub async fn escrow(&self, id: u64) -> Escrow {
+        self.escrows
+            .get(&id)
+            .await
+            .expect("Failed to load escrow")
+            .unwrap_or_else(|| panic!("Unknown escrow {id}"))
+    }
+
+    /// Takes `amount` of `token`, already debited from its holder, off this chain: escrowed
+    /// if the token was created here, burned otherwise.
+    pub async fn send_off(&mut self, token: &mut Token, amount: u128) {
+        if self.origin(&token.name).await.is_some() {
+            token.total_supply -= amount;
+            return;
+        }
+        let escrowed = self.escrowed(&token.name).await + amount;
+        self.escrowed
+            .insert(&token.name, escrowed)
+            .expect("Failed to update escrow");
+    }
+
+    /// Brings `amount` of `token` onto this chain, before it is credited to its holder:
+    /// released from escrow if the token was created here, minted otherwise.
+    pub async fn bring_in(&mut self, token: &mut Token, amount: u128) {
+        if self.origin(&token.name).await.is_some() {
+            token.total_supply = token
+                .total_supply
+                .checked_add(amount)
+                .expect("Token supply overflow");
+            return;
+        }
+        let escrowed = self.escrowed(&token.name).await;
+        assert!(
+            escrowed >= amount,
+            "Only {escrowed} tokens {} are held on other chains",
+            token.name
+        );
+        self.escrowed
+            .insert(&token.name, escrowed - amount)
+            .expect("Failed to update escrow");
+    }
+
+    /// Returns the names of the tokens `owner` holds, in order.
+    pub async fn holdings(&self, owner: &str) -> BTreeSet<String> {
+        self.holdings
+            .get(&owner.to_string())
+            .await
+            .expect("Failed to load holdings")
+            .unwrap_or_default()
+    }
+}
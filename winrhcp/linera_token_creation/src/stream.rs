@@ -0,0 +1,275 @@
+use crate::errors::TokenError;
+use crate::types::Token;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// An amount the sender locked, that the recipient earns linearly between `start` and `end`
+/// (in seconds) and may withdraw at any time.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PaymentStream {
+    pub token: String,
+    pub sender: String,
+    pub recipient: String,
+    pub amount: u128,
+    pub start: u64,
+    pub end: u64,
+    /// How much the recipient withdrew so far.
+    pub withdrawn: u128,
+    pub cancelled: bool,
+}
+
+impl PaymentStream {
+    /// Returns how much of the amount the recipient earned by `now`.
+    pub fn vested(&self, now: u64) -> u128 {
+        if now <= self.start {
+            return 0;
+        }
+        if now >= self.end {
+            return self.amount;
+        }
+        let elapsed = u128::from(now - self.start);
+        let duration = u128::from(self.end - self.start);
+        // Rounds down, so the recipient never gets ahead of the schedule. Splitting the
+        // amount by the duration keeps the products within range.
+        self.amount / duration * elapsed + self.amount % duration * elapsed / duration
+    }
+
+    /// Returns how much the recipient can withdraw at `now`.
+    pub fn withdrawable(&self, now: u64) -> u128 {
+        self.vested(now) - self.withdrawn
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct StreamBook {
+    pub streams: HashMap<u64, PaymentStream>,
+    pub next_id: u64,
+}
+
+impl StreamBook {
+    /// Locks `amount` of the sender's balance in a new stream, and returns its id.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create(
+        &mut self,
+        token: &str,
+        sender: &str,
+        recipient: &str,
+        amount: u128,
+        start: u64,
+        end: u64,
+        tokens: &mut HashMap<String, Token>,
+    ) -> Result<u64, TokenError> {
+        if amount == 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        if end <= start {
+            return Err(TokenError::InvalidState);
+        }
+        let token_state = tokens.get_mut(token).ok_or(TokenError::NotFound)?;
+        token_state.debit(sender, amount)?;
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.streams.insert(
+            id,
+            PaymentStream {
+                token: token.to_string(),
+                sender: sender.to_string(),
+                recipient: recipient.to_string(),
+                amount,
+                start,
+                end,
+                withdrawn: 0,
+                cancelled: false,
+            },
+        );
+        Ok(id)
+    }
+
+    /// Pays the recipient everything earned by `now` and not withdrawn yet, and returns the
+    /// amount paid.
+    pub fn withdraw(
+        &mut self,
+        id: u64,
+        caller: &str,
+        now: u64,
+        tokens: &mut HashMap<String, Token>,
+    ) -> Result<u128, TokenError> {
+        let stream = self.streams.get_mut(&id).ok_or(TokenError::NotFound)?;
+        if caller != stream.recipient {
+            return Err(TokenError::Unauthorized);
+        }
+        let amount = stream.withdrawable(now);
+        let recipient = stream.recipient.clone();
+        credit(tokens, &stream.token, &recipient, amount)?;
+        stream.withdrawn += amount;
+        Ok(amount)
+    }
+
+    /// Stops the stream at `now`: the recipient is paid what they earned so far and the
+    /// sender gets the rest back.
+    pub fn cancel(
+        &mut self,
+        id: u64,
+        caller: &str,
+        now: u64,
+        tokens: &mut HashMap<String, Token>,
+    ) -> Result<(), TokenError> {
+        let stream = self.streams.get_mut(&id).ok_or(TokenError::NotFound)?;
+        if caller != stream.sender {
+            return Err(TokenError::Unauthorized);
+        }
+        if stream.cancelled {
+            return Err(TokenError::InvalidState);
+        }
+        let vested = stream.vested(now);
+        let recipient = stream.recipient.clone();
+        let sender = stream.sender.clone();
+        credit(tokens, &stream.token, &recipient, vested - stream.withdrawn)?;
+        credit(tokens, &stream.token, &sender, stream.amount - vested)?;
+        stream.withdrawn = vested;
+        // Nothing is left to earn: the amount now matches what the recipient got.
+        stream.amount = vested;
+        stream.end = now.max(stream.start);
+        stream.cancelled = true;
+        Ok(())
+    }
+}
+
+fn credit(
+    tokens: &mut HashMap<String, Token>,
+    token: &str,
+    owner: &str,
+    amount: u128,
+) -> Result<(), TokenError> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let token = tokens.get_mut(token).ok_or(TokenError::NotFound)?;
+    token.credit(owner, amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::TokenMetadata;
+    use crate::policy::TransferPolicy;
+
+    fn tokens(supply: u128) -> HashMap<String, Token> {
+        let token = Token {
+            name: "Moon".to_string(),
+            symbol: "MOON".to_string(),
+            total_supply: supply,
+            decimals: 0,
+            metadata_uri: None,
+            balances: HashMap::from([("alice".to_string(), supply)]),
+            creator: "alice".to_string(),
+            policy: TransferPolicy::default(),
+            metadata: TokenMetadata::default(),
+            pending_metadata: None,
+            soulbound: None,
+        };
+        HashMap::from([("Moon".to_string(), token)])
+    }
+
+    #[test]
+    fn vesting_is_linear_rounded_down_and_bounded() {
+        let stream = PaymentStream {
+            token: "Moon".to_string(),
+            sender: "alice".to_string(),
+            recipient: "bob".to_string(),
+            amount: 1_000,
+            start: 100,
+            end: 400,
+            withdrawn: 0,
+            cancelled: false,
+        };
+        assert_eq!(stream.vested(0), 0);
+        assert_eq!(stream.vested(100), 0);
+        assert_eq!(stream.vested(101), 3);
+        assert_eq!(stream.vested(250), 500);
+        assert_eq!(stream.vested(400), 1_000);
+        assert_eq!(stream.vested(u64::MAX), 1_000);
+        let mut last = 0;
+        for now in 100..=400 {
+            let vested = stream.vested(now);
+            assert!(vested >= last && vested * 300 <= 1_000 * u128::from(now - 100));
+            last = vested;
+        }
+
+        // Amounts near the top of the range do not overflow.
+        let large = PaymentStream {
+            amount: u128::MAX,
+            ..stream
+        };
+        assert_eq!(large.vested(250), u128::MAX / 2);
+    }
+
+    #[test]
+    fn withdrawals_pay_what_was_earned_once() {
+        let (mut book, mut tokens) = (StreamBook::default(), tokens(1_000));
+        let id = book
+            .create("Moon", "alice", "bob", 600, 0, 60, &mut tokens)
+            .unwrap();
+        assert_eq!(tokens["Moon"].balance("alice"), 400);
+        assert!(matches!(
+            book.withdraw(id, "alice", 30, &mut tokens),
+            Err(TokenError::Unauthorized)
+        ));
+
+        assert_eq!(book.withdraw(id, "bob", 10, &mut tokens).unwrap(), 100);
+        assert_eq!(book.withdraw(id, "bob", 10, &mut tokens).unwrap(), 0);
+        assert_eq!(book.withdraw(id, "bob", 45, &mut tokens).unwrap(), 350);
+        assert_eq!(book.withdraw(id, "bob", 1_000, &mut tokens).unwrap(), 150);
+        assert_eq!(tokens["Moon"].balance("bob"), 600);
+        assert_eq!(book.streams[&id].withdrawn, 600);
+    }
+
+    #[test]
+    fn cancelling_splits_the_amount_between_the_parties() {
+        for (withdraw_at, cancel_at) in [(None, 0), (Some(7), 7), (Some(3), 13), (None, 99)] {
+            let (mut book, mut tokens) = (StreamBook::default(), tokens(1_000));
+            let id = book
+                .create("Moon", "alice", "bob", 999, 5, 95, &mut tokens)
+                .unwrap();
+            if let Some(now) = withdraw_at {
+                book.withdraw(id, "bob", now, &mut tokens).unwrap();
+            }
+            assert!(matches!(
+                book.cancel(id, "bob", cancel_at, &mut tokens),
+                Err(TokenError::Unauthorized)
+            ));
+            book.cancel(id, "alice", cancel_at, &mut tokens).unwrap();
+
+            // What vested went to the recipient, and the rest back to the sender.
+            let vested = tokens["Moon"].balance("bob");
+            let refunded = tokens["Moon"].balance("alice") - 1;
+            assert_eq!(vested + refunded, 999);
+            let stream = &book.streams[&id];
+            assert_eq!((stream.amount, stream.withdrawn), (vested, vested));
+            assert_eq!(book.withdraw(id, "bob", 1_000, &mut tokens).unwrap(), 0);
+            assert!(matches!(
+                book.cancel(id, "alice", 1_000, &mut tokens),
+                Err(TokenError::InvalidState)
+            ));
+        }
+    }
+
+    #[test]
+    fn streams_need_an_amount_and_a_duration() {
+        let (mut book, mut tokens) = (StreamBook::default(), tokens(10));
+        assert!(matches!(
+            book.create("Moon", "alice", "bob", 0, 0, 1, &mut tokens),
+            Err(TokenError::InvalidAmount)
+        ));
+        assert!(matches!(
+            book.create("Moon", "alice", "bob", 1, 5, 5, &mut tokens),
+            Err(TokenError::InvalidState)
+        ));
+        assert!(matches!(
+            book.create("Moon", "alice", "bob", 11, 0, 1, &mut tokens),
+            Err(TokenError::InsufficientBalance)
+        ));
+        assert!(book.streams.is_empty());
+    }
+}
@@ -0,0 +1,114 @@
+use crate::errors::TokenError;
+use crate::types::Token;
+
+/// Supply changes of regular tokens. Soulbound reward tokens are minted by awards and burned
+/// by clawbacks instead.
+impl Token {
+    /// Mints `amount` new tokens to `recipient`. Only the creator can.
+    pub fn mint(&mut self, caller: &str, recipient: &str, amount: u128) -> Result<(), TokenError> {
+        if caller != self.creator {
+            return Err(TokenError::Unauthorized);
+        }
+        if self.soulbound.is_some() {
+            return Err(TokenError::Soulbound);
+        }
+        if amount == 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        let total_supply = self
+            .total_supply
+            .checked_add(amount)
+            .ok_or(TokenError::Overflow)?;
+        self.credit(recipient, amount)?;
+        self.total_supply = total_supply;
+        Ok(())
+    }
+
+    /// Burns `amount` tokens out of the creator's own balance. Only the creator can.
+    pub fn burn(&mut self, caller: &str, amount: u128) -> Result<(), TokenError> {
+        if caller != self.creator {
+            return Err(TokenError::Unauthorized);
+        }
+        if amount == 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        self.debit(caller, amount)?;
+        self.total_supply -= amount;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::TokenMetadata;
+    use crate::policy::TransferPolicy;
+    use std::collections::HashMap;
+
+    fn coin() -> Token {
+        Token {
+            name: "Coin".to_string(),
+            symbol: "COIN".to_string(),
+            total_supply: 1_000,
+            decimals: 0,
+            metadata_uri: None,
+            balances: HashMap::from([("creator".to_string(), 1_000)]),
+            creator: "creator".to_string(),
+            policy: TransferPolicy::default(),
+            metadata: TokenMetadata::default(),
+            pending_metadata: None,
+            soulbound: None,
+        }
+    }
+
+    fn assert_supply_matches(token: &Token) {
+        assert_eq!(token.balances.values().sum::<u128>(), token.total_supply);
+    }
+
+    #[test]
+    fn mints_and_burns_move_the_supply() {
+        let mut token = coin();
+        token.mint("creator", "alice", 500).unwrap();
+        token.mint("creator", "creator", 250).unwrap();
+        token.burn("creator", 1_100).unwrap();
+        assert_eq!(token.total_supply, 650);
+        assert_eq!(
+            (token.balance("creator"), token.balance("alice")),
+            (150, 500)
+        );
+        assert_supply_matches(&token);
+    }
+
+    #[test]
+    fn only_creators_change_the_supply() {
+        let mut token = coin();
+        token.transfer("creator", "alice", 100).unwrap();
+        assert!(matches!(
+            token.mint("alice", "alice", 1),
+            Err(TokenError::Unauthorized)
+        ));
+        assert!(matches!(
+            token.burn("alice", 1),
+            Err(TokenError::Unauthorized)
+        ));
+        assert!(matches!(
+            token.mint("creator", "alice", 0),
+            Err(TokenError::InvalidAmount)
+        ));
+        assert!(matches!(
+            token.burn("creator", 0),
+            Err(TokenError::InvalidAmount)
+        ));
+        // Creators burn only their own balance.
+        assert!(matches!(
+            token.burn("creator", 901),
+            Err(TokenError::InsufficientBalance)
+        ));
+        assert!(matches!(
+            token.mint("creator", "alice", u128::MAX),
+            Err(TokenError::Overflow)
+        ));
+        assert_eq!(token.total_supply, 1_000);
+        assert_supply_matches(&token);
+    }
+}
@@ -1,17 +1,349 @@
-use serde::{Serialize, Deserialize};
+use crate::curve::CurveKind;
+use crate::metadata::{MetadataProposal, TokenMetadata};
+use crate::policy::{PolicyChange, TransferPolicy};
+use crate::pool::TradeKind;
+use crate::rewards::Soulbound;
+use linera_sdk::base::BcsSignable;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Token {
     pub name: String,
     pub symbol: String,
-    pub total_supply: u32,
-    pub balances: HashMap<String, u32>,
+    /// The supply in base units, as are all amounts of the token.
+    pub total_supply: u128,
+    /// How many decimal places of base units make one token, for display.
+    #[serde(default)]
+    pub decimals: u8,
+    /// Where the token's off-chain metadata document lives.
+    #[serde(default)]
+    pub metadata_uri: Option<String>,
+    pub balances: HashMap<String, u128>,
+    /// The account that created the token, and manages its transfer policy.
+    pub creator: String,
+    pub policy: TransferPolicy,
+    pub metadata: TokenMetadata,
+    /// A metadata change holders can review before it takes effect.
+    pub pending_metadata: Option<MetadataProposal>,
+    /// Set for reward tokens, whose balances cannot be transferred.
+    pub soulbound: Option<Soulbound>,
+}
+
+/// What callers sign to act on their account over HTTP: the path of the endpoint, so that
+/// the signature is valid for no other, a nonce above every one they used before, so that it
+/// cannot be replayed, and the JSON body of the request.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SignedRequest {
+    pub path: String,
+    pub nonce: u64,
+    pub body: Vec<u8>,
+}
+
+impl BcsSignable for SignedRequest {}
+
+impl Token {
+    /// A token with its whole supply in the balance of its creator.
+    pub fn new(
+        name: &str,
+        symbol: &str,
+        total_supply: u128,
+        decimals: u8,
+        metadata_uri: Option<String>,
+        creator: &str,
+    ) -> Self {
+        Token {
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            total_supply,
+            decimals,
+            metadata_uri,
+            balances: HashMap::from([(creator.to_string(), total_supply)]),
+            creator: creator.to_string(),
+            policy: TransferPolicy::default(),
+            metadata: TokenMetadata::default(),
+            pending_metadata: None,
+            soulbound: None,
+        }
+    }
+
+    /// The number of accounts with a positive balance.
+    pub fn holders(&self) -> usize {
+        self.balances
+            .values()
+            .filter(|balance| **balance > 0)
+            .count()
+    }
 }
 
 #[derive(Deserialize)]
 pub struct TokenRequest {
     pub name: String,
     pub symbol: String,
-    pub total_supply: u32,
+    pub total_supply: u128,
+    #[serde(default)]
+    pub decimals: u8,
+    #[serde(default)]
+    pub metadata_uri: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RewardTokenRequest {
+    pub name: String,
+    pub symbol: String,
+    pub clawback_admins: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AwardRequest {
+    pub recipient: String,
+    pub amount: u128,
+    pub achievement: String,
+}
+
+#[derive(Deserialize)]
+pub struct ClawbackRequest {
+    pub holder: String,
+    pub amount: u128,
+}
+
+#[derive(Deserialize)]
+pub struct WrapRequest {
+    pub amount: u128,
+}
+
+#[derive(Deserialize)]
+pub struct CreatePoolRequest {
+    pub token: String,
+    pub token_amount: u128,
+    pub native_amount: u128,
+}
+
+#[derive(Deserialize)]
+pub struct CreateCurveRequest {
+    pub token: String,
+    pub kind: CurveKind,
+    pub base_price: u128,
+    pub slope: u128,
+    /// The creator's tokens put up for sale on the curve.
+    pub amount: u128,
+}
+
+#[derive(Deserialize)]
+pub struct CurveTradeRequest {
+    pub token: String,
+    pub amount: u128,
+    /// The most the trader accepts to pay when buying, or the least they accept to receive
+    /// when selling, in wrapped native tokens.
+    pub limit: u128,
+}
+
+#[derive(Deserialize)]
+pub struct TokenListRequest {
+    #[serde(default)]
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct QuoteRequest {
+    pub kind: TradeKind,
+    pub amount: u128,
+}
+
+#[derive(Deserialize)]
+pub struct TradeRequest {
+    pub kind: TradeKind,
+    pub amount: u128,
+    /// The least the trader accepts to receive for `buy` and `sell`, or the most they accept
+    /// to pay for `buy_exact_tokens` and `sell_for_exact_native`.
+    pub limit: u128,
+    /// Who referred the trader, earning a share of the trade fee.
+    #[serde(default)]
+    pub referrer: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct BuybackRequest {
+    /// The share of the fees kept by the pool set aside for buybacks, in basis points.
+    pub share_bps: u32,
+    pub interval_secs: u64,
+}
+
+#[derive(Deserialize)]
+pub struct CreateEscrowRequest {
+    pub token: String,
+    pub beneficiary: String,
+    pub arbiter: String,
+    pub amount: u128,
+}
+
+#[derive(Deserialize)]
+pub struct CreateStreamRequest {
+    pub token: String,
+    pub recipient: String,
+    pub amount: u128,
+    /// When the recipient starts earning, in seconds since the Unix epoch.
+    pub start: u64,
+    pub end: u64,
+}
+
+#[derive(Deserialize)]
+pub struct MintRequest {
+    pub token: String,
+    pub recipient: String,
+    pub amount: u128,
+}
+
+#[derive(Deserialize)]
+pub struct BurnRequest {
+    pub token: String,
+    pub amount: u128,
+}
+
+/// A transfer naming its token, for the top-level `/transfer` endpoint.
+#[derive(Deserialize)]
+pub struct TokenTransferRequest {
+    pub token: String,
+    pub to: String,
+    pub amount: u128,
+}
+
+#[derive(Deserialize)]
+pub struct TransferRequest {
+    pub to: String,
+    pub amount: u128,
+}
+
+#[derive(Deserialize)]
+pub struct PolicyChangeRequest {
+    pub change: PolicyChange,
+}
+
+#[derive(Deserialize)]
+pub struct MetadataProposalRequest {
+    pub metadata: TokenMetadata,
+}
+
+/// A token as listed, with its number of holders.
+#[derive(Serialize)]
+pub struct TokenSummary {
+    #[serde(flatten)]
+    pub token: Token,
+    pub holders: usize,
+}
+
+impl From<&Token> for TokenSummary {
+    fn from(token: &Token) -> Self {
+        TokenSummary {
+            token: token.clone(),
+            holders: token.holders(),
+        }
+    }
+}
+
+impl TokenSummary {
+    /// Lists at most `limit` of `tokens` in name order, skipping the first `offset`.
+    pub fn page<'a>(
+        tokens: impl IntoIterator<Item = &'a Token>,
+        offset: usize,
+        limit: usize,
+    ) -> Vec<TokenSummary> {
+        let mut tokens: Vec<&Token> = tokens.into_iter().collect();
+        tokens.sort_by(|a, b| a.name.cmp(&b.name));
+        tokens
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .map(TokenSummary::from)
+            .collect()
+    }
+
+    /// Returns the token of `tokens` with ticker `symbol`, the first by name if several
+    /// share it.
+    pub fn by_symbol<'a>(
+        tokens: impl IntoIterator<Item = &'a Token>,
+        symbol: &str,
+    ) -> Option<TokenSummary> {
+        tokens
+            .into_iter()
+            .filter(|token| token.symbol == symbol)
+            .min_by(|a, b| a.name.cmp(&b.name))
+            .map(TokenSummary::from)
+    }
+}
+
+/// A token's metadata as of now, with the change that is pending, if any.
+#[derive(Serialize)]
+pub struct MetadataResponse {
+    pub metadata: TokenMetadata,
+    pub pending: Option<MetadataProposal>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linera_sdk::base::{KeyPair, Signature};
+    use serde::de::IntoDeserializer;
+
+    fn key_pair(seed: u8) -> KeyPair {
+        // The hexadecimal secret key made of 32 `seed` bytes.
+        let secret = format!("{seed:02x}").repeat(32);
+        let deserializer: serde::de::value::StrDeserializer<serde::de::value::Error> =
+            secret.as_str().into_deserializer();
+        KeyPair::deserialize(deserializer).unwrap()
+    }
+
+    #[test]
+    fn signatures_cover_the_path_the_nonce_and_the_body() {
+        let request = |path: &str, nonce, body: &[u8]| SignedRequest {
+            path: path.to_string(),
+            nonce,
+            body: body.to_vec(),
+        };
+        let signer = key_pair(1);
+        let signed = request("/token/Coin/transfer", 7, br#"{"to":"bob","amount":5}"#);
+        let signature = Signature::new(&signed, &signer);
+        assert!(signature.check(&signed, signer.public()).is_ok());
+        assert!(signature.check(&signed, key_pair(2).public()).is_err());
+
+        let forgeries = [
+            request("/token/Coin/burn", 7, br#"{"to":"bob","amount":5}"#),
+            request("/token/Coin/transfer", 8, br#"{"to":"bob","amount":5}"#),
+            request("/token/Coin/transfer", 7, br#"{"to":"bob","amount":500}"#),
+        ];
+        for forgery in &forgeries {
+            assert!(signature.check(forgery, signer.public()).is_err());
+        }
+    }
+
+    #[test]
+    fn pages_list_every_token_once_in_name_order() {
+        let tokens: Vec<Token> = ["Delta", "Alpha", "Echo", "Charlie", "Bravo"]
+            .into_iter()
+            .map(|name| Token::new(name, &name.to_uppercase(), 1, 0, None, "alice"))
+            .collect();
+        let names = |page: Vec<TokenSummary>| -> Vec<String> {
+            page.into_iter().map(|summary| summary.token.name).collect()
+        };
+        let mut listed = Vec::new();
+        for offset in (0..tokens.len()).step_by(2) {
+            listed.extend(names(TokenSummary::page(&tokens, offset, 2)));
+        }
+        assert_eq!(listed, ["Alpha", "Bravo", "Charlie", "Delta", "Echo"]);
+        assert!(TokenSummary::page(&tokens, 5, 2).is_empty());
+        assert!(TokenSummary::page(&tokens, 0, 0).is_empty());
+    }
+
+    #[test]
+    fn symbols_find_the_first_token_by_name() {
+        let mut tokens = vec![
+            Token::new("Zeta", "COIN", 1, 0, None, "alice"),
+            Token::new("Beta", "COIN", 2, 0, None, "bob"),
+        ];
+        tokens[1].transfer("bob", "carol", 1).unwrap();
+        let found = TokenSummary::by_symbol(&tokens, "COIN").unwrap();
+        assert_eq!((found.token.name.as_str(), found.holders), ("Beta", 2));
+        assert!(TokenSummary::by_symbol(&tokens, "NONE").is_none());
+    }
 }
@@ -0,0 +1,87 @@
+use crate::errors::ValidationError;
+use crate::types::Token;
+
+pub const MIN_SYMBOL_LEN: usize = 3;
+pub const MAX_SYMBOL_LEN: usize = 8;
+pub const MAX_NAME_LEN: usize = 64;
+
+/// Checks that `name` is not blank and has at most `MAX_NAME_LEN` characters.
+pub fn validate_name(name: &str) -> Result<(), ValidationError> {
+    if name.trim().is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err(ValidationError::InvalidName);
+    }
+    Ok(())
+}
+
+/// Checks that `symbol` is `MIN_SYMBOL_LEN` to `MAX_SYMBOL_LEN` uppercase ASCII letters.
+pub fn validate_symbol(symbol: &str) -> Result<(), ValidationError> {
+    if !(MIN_SYMBOL_LEN..=MAX_SYMBOL_LEN).contains(&symbol.len())
+        || !symbol.bytes().all(|byte| byte.is_ascii_uppercase())
+    {
+        return Err(ValidationError::InvalidSymbol);
+    }
+    Ok(())
+}
+
+pub fn validate_supply(total_supply: u128) -> Result<(), ValidationError> {
+    if total_supply == 0 {
+        return Err(ValidationError::ZeroSupply);
+    }
+    Ok(())
+}
+
+/// Checks the name and symbol of a new token, which no token of `existing` may use already.
+pub fn validate_new_token<'a>(
+    name: &str,
+    symbol: &str,
+    existing: impl IntoIterator<Item = &'a Token>,
+) -> Result<(), ValidationError> {
+    validate_name(name)?;
+    validate_symbol(symbol)?;
+    for token in existing {
+        if token.name == name {
+            return Err(ValidationError::DuplicateName);
+        }
+        if token.symbol == symbol {
+            return Err(ValidationError::DuplicateSymbol);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_and_symbols_have_bounds() {
+        assert!(validate_name("Coin").is_ok());
+        assert!(validate_name(&"é".repeat(MAX_NAME_LEN)).is_ok());
+        assert_eq!(
+            validate_name(&"a".repeat(MAX_NAME_LEN + 1)),
+            Err(ValidationError::InvalidName)
+        );
+        assert_eq!(validate_name("   "), Err(ValidationError::InvalidName));
+
+        assert!(validate_symbol("ABC").is_ok());
+        assert!(validate_symbol("ABCDEFGH").is_ok());
+        for symbol in ["AB", "ABCDEFGHI", "Abc", "AB1", "ÉTÉ"] {
+            assert_eq!(validate_symbol(symbol), Err(ValidationError::InvalidSymbol));
+        }
+        assert_eq!(validate_supply(0), Err(ValidationError::ZeroSupply));
+    }
+
+    #[test]
+    fn new_tokens_take_unused_names_and_symbols() {
+        let existing = [Token::new("Coin", "COIN", 1, 0, None, "alice")];
+        assert!(validate_new_token("Other", "OTHER", &existing).is_ok());
+        assert_eq!(
+            validate_new_token("Coin", "OTHER", &existing),
+            Err(ValidationError::DuplicateName)
+        );
+        assert_eq!(
+            validate_new_token("Other", "COIN", &existing),
+            Err(ValidationError::DuplicateSymbol)
+        );
+    }
+}
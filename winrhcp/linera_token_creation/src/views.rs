@@ -1,20 +1,113 @@
 use linera_sdk::View;
-use crate::types::Token;
-use std::collections::HashMap;
+use linera_token_creation::curve::CurveBook;
+use linera_token_creation::errors::ValidationError;
+use linera_token_creation::escrow::EscrowBook;
+use linera_token_creation::policy::PolicyEvent;
+use linera_token_creation::pool::{Buyback, PoolBook};
+use linera_token_creation::portfolio::{Holding, HoldingsIndex};
+use linera_token_creation::rewards::{Award, Soulbound};
+use linera_token_creation::stream::StreamBook;
+use linera_token_creation::types::{Token, TokenSummary};
+use linera_token_creation::validation::{validate_new_token, validate_supply};
+use linera_token_creation::wrap::WrappedReserve;
+use std::collections::{HashMap, HashSet};
 
 #[derive(View)]
 pub struct TokenView {
     pub tokens: HashMap<String, Token>,
+    pub wrapped: WrappedReserve,
+    pub escrows: EscrowBook,
+    pub streams: StreamBook,
+    pub pools: PoolBook,
+    pub curves: CurveBook,
+    pub holdings: HoldingsIndex,
+    pub policy_events: Vec<PolicyEvent>,
+    pub awards: Vec<Award>,
+    /// The last nonce each account signed a request with.
+    pub nonces: HashMap<String, u64>,
+    pub buybacks: Vec<Buyback>,
 }
 
 impl TokenView {
-    pub fn create_token(&mut self, name: &str, symbol: &str, total_supply: u32) {
-        let token = Token {
-            name: name.to_string(),
-            symbol: symbol.to_string(),
-            total_supply,
-            balances: HashMap::new(),
-        };
+    pub fn create_token(
+        &mut self,
+        name: &str,
+        symbol: &str,
+        total_supply: u128,
+        decimals: u8,
+        metadata_uri: Option<String>,
+        creator: &str,
+    ) -> Result<(), ValidationError> {
+        validate_supply(total_supply)?;
+        validate_new_token(name, symbol, self.tokens.values())?;
+        let token = Token::new(name, symbol, total_supply, decimals, metadata_uri, creator);
+        self.tokens.insert(name.to_string(), token);
+        self.index_holders(name, &[creator]);
+        Ok(())
+    }
+
+    /// Creates a soulbound reward token, without supply until rewards are awarded.
+    pub fn create_reward_token(
+        &mut self,
+        name: &str,
+        symbol: &str,
+        creator: &str,
+        clawback_admins: HashSet<String>,
+    ) -> Result<(), ValidationError> {
+        validate_new_token(name, symbol, self.tokens.values())?;
+        let mut token = Token::new(name, symbol, 0, 0, None, creator);
+        token.balances.clear();
+        token.soulbound = Some(Soulbound { clawback_admins });
         self.tokens.insert(name.to_string(), token);
+        Ok(())
+    }
+
+    /// Records that `account` signed a request with `nonce`, unless it used that nonce or a
+    /// later one before, returning whether it did.
+    pub fn use_nonce(&mut self, account: &str, nonce: u64) -> bool {
+        let last = self.nonces.entry(account.to_string()).or_default();
+        if nonce <= *last {
+            return false;
+        }
+        *last = nonce;
+        true
+    }
+
+    /// Updates the holdings index after the balances of `owners` in `token` changed.
+    pub fn index_holders(&mut self, token: &str, owners: &[&str]) {
+        let Some(token) = self.tokens.get(token) else {
+            return;
+        };
+        for owner in owners {
+            self.holdings.update(token, owner);
+        }
+    }
+
+    /// Lists at most `limit` tokens in name order, skipping the first `offset`.
+    pub fn list_tokens(&self, offset: usize, limit: usize) -> Vec<TokenSummary> {
+        TokenSummary::page(self.tokens.values(), offset, limit)
+    }
+
+    /// Returns the token with ticker `symbol`, the first by name if several share it.
+    pub fn get_token(&self, symbol: &str) -> Option<TokenSummary> {
+        TokenSummary::by_symbol(self.tokens.values(), symbol)
+    }
+
+    /// Returns every token `owner` holds, with its balance and pool price.
+    pub fn portfolio(&self, owner: &str) -> Vec<Holding> {
+        self.holdings
+            .tokens_of(owner)
+            .filter_map(|name| self.tokens.get(name))
+            .map(|token| Holding {
+                token: token.name.clone(),
+                symbol: token.symbol.clone(),
+                balance: token.balance(owner),
+                price: self
+                    .pools
+                    .pools
+                    .get(&token.name)
+                    .map(|pool| pool.native_reserve as f64 / pool.token_reserve as f64),
+            })
+            .collect()
     }
 }
@@ -0,0 +1,133 @@
+use crate::errors::TokenError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Wrapped native tokens, credited for the deposits the prototype records. It moves no native
+/// tokens, so nothing backs them until wrapping moves on-chain.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct WrappedReserve {
+    pub balances: HashMap<String, u128>,
+    /// Wrapped tokens held by the pools and bonding curves rather than by an owner.
+    #[serde(default)]
+    pub pooled: u128,
+}
+
+impl WrappedReserve {
+    /// Mints `amount` wrapped tokens to `owner` for the native tokens they deposited.
+    pub fn deposit(&mut self, owner: &str, amount: u128) -> Result<(), TokenError> {
+        if amount == 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        self.total_supply()
+            .checked_add(amount)
+            .ok_or(TokenError::Overflow)?;
+        let balance = self.balances.entry(owner.to_string()).or_insert(0);
+        *balance += amount;
+        Ok(())
+    }
+
+    /// Burns `amount` wrapped tokens of `owner` for the native tokens they withdraw.
+    pub fn withdraw(&mut self, owner: &str, amount: u128) -> Result<(), TokenError> {
+        if amount == 0 {
+            return Err(TokenError::InvalidAmount);
+        }
+        let balance = self
+            .balances
+            .get_mut(owner)
+            .ok_or(TokenError::InsufficientBalance)?;
+        if *balance < amount {
+            return Err(TokenError::InsufficientBalance);
+        }
+        *balance -= amount;
+        if *balance == 0 {
+            self.balances.remove(owner);
+        }
+        Ok(())
+    }
+
+    /// Moves `amount` wrapped tokens of `owner` into the pools or bonding curves.
+    pub fn pool_deposit(&mut self, owner: &str, amount: u128) -> Result<(), TokenError> {
+        let pooled = self
+            .pooled
+            .checked_add(amount)
+            .ok_or(TokenError::Overflow)?;
+        let balance = self
+            .balances
+            .get_mut(owner)
+            .ok_or(TokenError::InsufficientBalance)?;
+        if *balance < amount {
+            return Err(TokenError::InsufficientBalance);
+        }
+        *balance -= amount;
+        if *balance == 0 {
+            self.balances.remove(owner);
+        }
+        self.pooled = pooled;
+        Ok(())
+    }
+
+    /// Pays `amount` wrapped tokens held by the pools or bonding curves to `owner`.
+    pub fn pool_payout(&mut self, owner: &str, amount: u128) -> Result<(), TokenError> {
+        if amount > self.pooled {
+            return Err(TokenError::InsufficientBalance);
+        }
+        let balance = self.balances.entry(owner.to_string()).or_insert(0);
+        *balance = balance.checked_add(amount).ok_or(TokenError::Overflow)?;
+        self.pooled -= amount;
+        Ok(())
+    }
+
+    pub fn balance(&self, owner: &str) -> u128 {
+        self.balances.get(owner).copied().unwrap_or(0)
+    }
+
+    pub fn total_supply(&self) -> u128 {
+        self.balances.values().sum::<u128>() + self.pooled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrapped_tokens_follow_deposits_and_withdrawals() {
+        let mut wrapped = WrappedReserve::default();
+        wrapped.deposit("alice", 100).unwrap();
+        wrapped.deposit("bob", 50).unwrap();
+        wrapped.withdraw("alice", 30).unwrap();
+        wrapped.withdraw("bob", 50).unwrap();
+
+        assert_eq!(wrapped.balance("alice"), 70);
+        assert_eq!(wrapped.balance("bob"), 0);
+        assert!(!wrapped.balances.contains_key("bob"));
+        assert_eq!(wrapped.total_supply(), 70);
+    }
+
+    #[test]
+    fn nothing_moves_beyond_a_balance() {
+        let mut wrapped = WrappedReserve::default();
+        wrapped.deposit("alice", 10).unwrap();
+        assert!(matches!(
+            wrapped.deposit("alice", 0),
+            Err(TokenError::InvalidAmount)
+        ));
+        assert!(matches!(
+            wrapped.withdraw("alice", 0),
+            Err(TokenError::InvalidAmount)
+        ));
+        assert!(matches!(
+            wrapped.withdraw("alice", 11),
+            Err(TokenError::InsufficientBalance)
+        ));
+        assert!(matches!(
+            wrapped.withdraw("bob", 1),
+            Err(TokenError::InsufficientBalance)
+        ));
+        assert!(matches!(
+            wrapped.deposit("alice", u128::MAX),
+            Err(TokenError::Overflow)
+        ));
+        assert_eq!((wrapped.total_supply(), wrapped.balance("alice")), (10, 10));
+    }
+}